@@ -2,12 +2,17 @@
 // Commandes exposées au frontend
 
 use super::types::*;
+use super::utils::ContentClassifier;
 use super::AWCSState;
-use tauri::{AppHandle, State, Emitter};
+use super::core::{ScreenCaptureManager, WindowWatcherConfig};
+use super::core::screen_capture::DisplayInfo;
+use super::core::global_shortcuts::persist_shortcut;
+use tauri::{AppHandle, State, Emitter, Manager};
 
-/// Récupère le contexte de la fenêtre active
+/// Récupère le contexte de la fenêtre active (cache réutilisé si rien n'a changé, sauf `force_refresh`)
 #[tauri::command]
 pub async fn awcs_get_current_context(
+    force_refresh: Option<bool>,
     awcs_state: State<'_, AWCSState>,
 ) -> Result<ContextEnvelope, String> {
     tracing::info!("Command: awcs_get_current_context - Quick context extraction");
@@ -17,8 +22,13 @@ pub async fn awcs_get_current_context(
     
     let manager_arc = awcs_state.manager();
     let mut manager = manager_arc.write().await;
-    manager.get_current_context().await
-        .map_err(|e| e.to_string())
+    let context = manager.get_current_context(force_refresh.unwrap_or(false)).await
+        .map_err(|e| e.to_string())?;
+
+    // Méthode retenue et scores des candidats (confidence.extractionMethod / methodScores)
+    tracing::info!("Context extracted with method '{}' ({} candidates scored)",
+                   context.confidence.extraction_method, context.confidence.method_scores.len());
+    Ok(context)
 }
 
 /// Traite une requête utilisateur avec le contexte
@@ -81,6 +91,29 @@ pub async fn awcs_setup_global_shortcut(
     Ok(())
 }
 
+/// Change le raccourci global AWCS et le persiste pour les prochains démarrages
+#[tauri::command]
+pub async fn set_awcs_shortcut(
+    accelerator: String,
+    awcs_state: State<'_, AWCSState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let accelerator = accelerator.trim().to_string();
+    tracing::debug!("Command: set_awcs_shortcut ({})", accelerator);
+    
+    let manager_arc = awcs_state.manager();
+    let mut manager = manager_arc.write().await;
+    manager.set_global_shortcut(&accelerator, app.clone()).await
+        .map_err(|e| e.to_string())?;
+    
+    let config_dir = app.path().app_config_dir()
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))?;
+    persist_shortcut(&config_dir, &accelerator)
+        .map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
 /// Récupère l'état d'activation AWCS
 #[tauri::command]
 pub async fn awcs_get_state(
@@ -110,6 +143,19 @@ pub async fn awcs_set_state(
     Ok(())
 }
 
+/// Remet à zéro les métriques AWCS de la session
+#[tauri::command]
+pub async fn awcs_reset_metrics(
+    awcs_state: State<'_, AWCSState>,
+) -> Result<(), String> {
+    tracing::debug!("Command: awcs_reset_metrics");
+    
+    let manager_arc = awcs_state.manager();
+    let mut manager = manager_arc.write().await;
+    manager.reset_metrics();
+    Ok(())
+}
+
 /// Nettoie les ressources AWCS
 #[tauri::command]
 pub async fn awcs_cleanup(
@@ -125,6 +171,9 @@ pub async fn awcs_cleanup(
             .map_err(|e| e.to_string())?;
     }
     
+    // Arrêter le suivi du focus s'il est actif
+    awcs_state.window_watcher().write().await.stop();
+    
     // Mettre à jour l'état
     awcs_state.set_activation_state(AWCSActivationState::Disabled).await;
     
@@ -238,11 +287,47 @@ pub async fn awcs_show_zone_selector(
             y: 100,
             width: 300,
             height: 200,
+            display_index: None,
         }),
         method: "zone_ocr".to_string(),
     })
 }
 
+/// Liste les écrans connectés pour mapper les coordonnées du sélecteur de zone
+#[tauri::command]
+pub async fn awcs_list_displays() -> Result<Vec<DisplayInfo>, String> {
+    tracing::debug!("Command: awcs_list_displays");
+    
+    let screen_capture = ScreenCaptureManager::new();
+    screen_capture.list_displays()
+        .map_err(|e| e.to_string())
+}
+
+/// Démarre le suivi du focus : émet `awcs-window-changed` (debounce ~500ms)
+#[tauri::command]
+pub async fn awcs_start_watching(
+    awcs_state: State<'_, AWCSState>,
+    app: AppHandle,
+) -> Result<bool, String> {
+    tracing::debug!("Command: awcs_start_watching");
+    
+    let watcher_arc = awcs_state.window_watcher();
+    let mut watcher = watcher_arc.write().await;
+    Ok(watcher.start(app, WindowWatcherConfig::default()))
+}
+
+/// Arrête le suivi du focus
+#[tauri::command]
+pub async fn awcs_stop_watching(
+    awcs_state: State<'_, AWCSState>,
+) -> Result<bool, String> {
+    tracing::debug!("Command: awcs_stop_watching");
+    
+    let watcher_arc = awcs_state.window_watcher();
+    let mut watcher = watcher_arc.write().await;
+    Ok(watcher.stop())
+}
+
 /// Émule l'événement de raccourci global (pour les tests)
 #[tauri::command]
 pub async fn awcs_trigger_shortcut(
@@ -277,7 +362,7 @@ pub async fn awcs_test_extraction(
     let manager_arc = awcs_state.manager();
     let mut manager = manager_arc.write().await;
     
-    match manager.get_current_context().await {
+    match manager.get_current_context(true).await {
         Ok(context) => {
             tracing::info!(
                 "Test extraction successful: {} characters extracted",
@@ -306,7 +391,20 @@ pub async fn awcs_get_context_ocr_direct(
     let mut manager = manager_arc.write().await;
     
     // Utiliser l'extraction OCR directe (méthode existante préservée)
-    manager.get_current_context().await
+    manager.get_current_context(true).await
+        .map_err(|e| e.to_string())
+}
+
+/// Extraction du texte sélectionné (accessibilité / copie synthétique), OCR si aucune sélection
+#[tauri::command]
+pub async fn awcs_get_context_selection(
+    awcs_state: State<'_, AWCSState>,
+) -> Result<ContextEnvelope, String> {
+    tracing::info!("Command: awcs_get_context_selection - Selected text extraction");
+    
+    let manager_arc = awcs_state.manager();
+    let mut manager = manager_arc.write().await;
+    manager.get_selection_context().await
         .map_err(|e| e.to_string())
 }
 
@@ -333,6 +431,7 @@ pub async fn awcs_get_context_focused_ocr(
         .map_err(|e| e.to_string())?;
     
     // 3. Construction de l'enveloppe de contexte
+    let content_kind = ContentClassifier::classify(&ocr_result.text);
     Ok(ContextEnvelope {
         source: window_info,
         document: None,
@@ -348,9 +447,12 @@ pub async fn awcs_get_context_focused_ocr(
             text_completeness: ocr_result.confidence,
             source_reliability: 0.9, // Méthode focalisée plus fiable
             extraction_method: "focused_ocr".to_string(),
+            degraded_steps: Vec::new(),
+            method_scores: Vec::new(),
         },
         timestamp: chrono::Utc::now(),
         security_flags: None,
+        content_kind,
     })
 }
 
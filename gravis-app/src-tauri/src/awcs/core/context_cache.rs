@@ -0,0 +1,138 @@
+// GRAVIS AWCS - Context Cache
+// Dernier contexte extrait, réutilisé tant que la fenêtre active n'a pas changé
+
+use crate::awcs::core::screen_capture::WindowBounds;
+use crate::awcs::types::*;
+use std::time::{Duration, Instant};
+
+/// Identité de l'état affiché : fenêtre (processus, application, titre) et sa géométrie.
+/// Signaux obtenus sans capture d'écran ; un défilement dans la même fenêtre reste servi
+/// par le cache jusqu'au TTL (ou `force_refresh`), d'où un cache désactivé par défaut
+/// (`AWCSConfig::context_cache_ttl_ms` = 0).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextCacheKey {
+    pub pid: u32,
+    pub app: String,
+    pub title: String,
+    /// `None` si la géométrie n'est pas disponible : seule la fenêtre est alors comparée
+    pub bounds: Option<WindowBounds>,
+}
+
+impl ContextCacheKey {
+    pub fn new(window: &WindowInfo, bounds: Option<WindowBounds>) -> Self {
+        Self {
+            pid: window.pid,
+            app: window.app.clone(),
+            title: window.title.clone(),
+            bounds,
+        }
+    }
+}
+
+/// Cache d'une entrée avec durée de validité
+#[derive(Debug)]
+pub struct ContextCache {
+    entry: Option<(ContextCacheKey, ContextEnvelope, Instant)>,
+    ttl: Duration,
+}
+
+impl ContextCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { entry: None, ttl }
+    }
+
+    /// Contexte en cache si la clé est identique et le TTL non expiré
+    pub fn get(&self, key: &ContextCacheKey) -> Option<ContextEnvelope> {
+        self.entry.as_ref()
+            .filter(|(cached_key, _, cached_at)| cached_key == key && cached_at.elapsed() < self.ttl)
+            .map(|(_, context, _)| context.clone())
+    }
+
+    pub fn store(&mut self, key: ContextCacheKey, context: ContextEnvelope) {
+        if !self.ttl.is_zero() {
+            self.entry = Some((key, context, Instant::now()));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entry = None;
+    }
+
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(title: &str) -> WindowInfo {
+        WindowInfo {
+            app: "Preview".to_string(),
+            title: title.to_string(),
+            pid: 42,
+            bundle_id: None,
+            window_class: None,
+        }
+    }
+
+    fn context(text: &str) -> ContextEnvelope {
+        ContextEnvelope {
+            source: window("rapport.pdf"),
+            document: None,
+            content: ContentData {
+                selection: None,
+                fulltext: Some(text.to_string()),
+                metadata: None,
+            },
+            confidence: ExtractionConfidence {
+                text_completeness: 1.0,
+                source_reliability: 1.0,
+                extraction_method: "ocr".to_string(),
+                degraded_steps: Vec::new(),
+                method_scores: Vec::new(),
+            },
+            timestamp: chrono::Utc::now(),
+            security_flags: None,
+            content_kind: ContentKind::default(),
+        }
+    }
+
+    #[test]
+    fn test_context_cache_keyed_by_window_and_bounds() {
+        let bounds = WindowBounds { x: 0, y: 25, width: 1280, height: 800 };
+        let key = ContextCacheKey::new(&window("rapport.pdf"), Some(bounds));
+        let mut cache = ContextCache::new(Duration::from_secs(60));
+        cache.store(key.clone(), context("contenu extrait"));
+
+        let cached = cache.get(&key).expect("Même fenêtre, même géométrie");
+        assert_eq!(cached.content.fulltext.as_deref(), Some("contenu extrait"));
+
+        let other_title = ContextCacheKey::new(&window("annexe.pdf"), Some(bounds));
+        assert!(cache.get(&other_title).is_none());
+
+        let resized = ContextCacheKey::new(&window("rapport.pdf"), Some(WindowBounds { width: 640, ..bounds }));
+        assert!(cache.get(&resized).is_none());
+
+        cache.clear();
+        assert!(cache.get(&key).is_none());
+    }
+
+    #[test]
+    fn test_context_cache_ttl() {
+        let key = ContextCacheKey::new(&window("rapport.pdf"), None);
+
+        // Désactivé par défaut : la clé ne détecte pas un défilement dans la même fenêtre
+        assert_eq!(AWCSConfig::default().context_cache_ttl_ms, 0);
+        let mut disabled = ContextCache::new(Duration::from_millis(AWCSConfig::default().context_cache_ttl_ms));
+        disabled.store(key.clone(), context("contenu"));
+        assert!(disabled.get(&key).is_none(), "TTL nul : rien n'est conservé");
+
+        let mut short = ContextCache::new(Duration::from_millis(1));
+        short.store(key.clone(), context("contenu"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(short.get(&key).is_none(), "Entrée expirée");
+    }
+}
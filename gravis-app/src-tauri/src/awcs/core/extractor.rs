@@ -8,10 +8,15 @@ use crate::awcs::extractors::{
     applescript_extractor::AppleScriptExtractor,
     accessibility_extractor::AccessibilityExtractor,
     ocr_extractor::OCRExtractor,
+    selection_extractor::SelectionExtractor,
 };
+use crate::awcs::utils::{ContentClassifier, TextQuality};
 use std::time::Duration;
 use tokio::time::timeout;
 
+/// Score à partir duquel l'OCR (dernier recours, lent) n'est pas tenté
+const OCR_SKIP_SCORE: f64 = 0.75;
+
 /// Extracteur de contexte avec stratégies multiples
 #[derive(Debug)]
 pub struct ContextExtractor {
@@ -20,22 +25,33 @@ pub struct ContextExtractor {
     applescript_extractor: AppleScriptExtractor,
     accessibility_extractor: AccessibilityExtractor,
     ocr_extractor: OCRExtractor,
-    extraction_timeout: Duration,
+    selection_extractor: SelectionExtractor,
+    step_timeouts: ExtractionTimeouts,
 }
 
 impl ContextExtractor {
     /// Crée un nouveau extracteur de contexte
     pub fn new() -> Self {
+        let step_timeouts = ExtractionTimeouts::default();
+        let mut applescript_extractor = AppleScriptExtractor::new();
+        applescript_extractor.set_timeout(step_timeouts.for_step(ExtractionStep::AppleScript));
+
         Self {
             window_detector: WindowDetector::new(),
             dom_extractor: DOMExtractor::new(),
-            applescript_extractor: AppleScriptExtractor::new(),
+            applescript_extractor,
             accessibility_extractor: AccessibilityExtractor::new(),
             ocr_extractor: OCRExtractor::new(),
-            extraction_timeout: Duration::from_millis(800),
+            selection_extractor: SelectionExtractor::new(),
+            step_timeouts,
         }
     }
     
+    /// Fenêtre active (sans extraction)
+    pub async fn current_window(&self) -> Result<WindowInfo, AWCSError> {
+        self.window_detector.get_current_window().await
+    }
+    
     /// Extrait le contexte de la fenêtre active avec fallbacks
     pub async fn extract_current_window_context(&mut self) -> Result<ContextEnvelope, AWCSError> {
         tracing::debug!("Starting context extraction with hierarchical fallbacks");
@@ -67,62 +83,57 @@ impl ContextExtractor {
             }
         }
         
-        // DOM extraction
-        tracing::info!("AWCS Phase 3 - Attempting extraction with method: DOM");
-        match timeout(self.extraction_timeout, self.try_dom_extraction(&window_info)).await {
-            Ok(Ok(context)) => {
-                if context.confidence.text_completeness > 0.5 {
-                    tracing::info!("Extraction successful with dom: {:.1}% completeness", context.confidence.text_completeness * 100.0);
-                    return Ok(context);
-                }
-                tracing::debug!("DOM extraction had low confidence, trying next method");
-            },
-            Ok(Err(e)) => tracing::debug!("DOM extraction failed: {}", e),
-            Err(_) => tracing::debug!("DOM extraction timed out"),
-        }
-        
-        // AppleScript extraction
-        tracing::info!("AWCS Phase 3 - Attempting extraction with method: AppleScript");
-        match timeout(self.extraction_timeout, self.try_applescript_extraction(&window_info)).await {
-            Ok(Ok(context)) => {
-                if context.confidence.text_completeness > 0.5 {
-                    tracing::info!("Extraction successful with applescript: {:.1}% completeness", context.confidence.text_completeness * 100.0);
-                    return Ok(context);
-                }
-                tracing::debug!("AppleScript extraction had low confidence, trying next method");
-            },
-            Ok(Err(e)) => tracing::info!("AppleScript extraction failed: {}", e),
-            Err(_) => tracing::warn!("AppleScript extraction timed out"),
-        }
-        
-        // Accessibility extraction
-        tracing::info!("AWCS Phase 3 - Attempting extraction with method: Accessibility");
-        match timeout(self.extraction_timeout, self.try_accessibility_extraction(&window_info)).await {
-            Ok(Ok(context)) => {
-                if context.confidence.text_completeness > 0.5 {
-                    tracing::info!("Extraction successful with accessibility: {:.1}% completeness", context.confidence.text_completeness * 100.0);
-                    return Ok(context);
-                }
-                tracing::debug!("Accessibility extraction had low confidence, trying next method");
-            },
-            Ok(Err(e)) => tracing::info!("Accessibility extraction failed: {}", e),
-            Err(_) => tracing::warn!("Accessibility extraction timed out"),
+        // Chaîne explicite DOM → AppleScript → Accessibility → OCR, chaque étape bornée par son timeout.
+        // Les extracteurs applicables produisent des candidats classés par score ; l'OCR (lent)
+        // n'est tenté que si aucun candidat n'est déjà satisfaisant.
+        let mut degraded_steps = Vec::new();
+        let mut candidates: Vec<(f64, ContextEnvelope)> = Vec::new();
+        for step in ExtractionStep::CHAIN {
+            let best_score = candidates.iter().map(|(score, _)| *score).fold(0.0, f64::max);
+            if step == ExtractionStep::Ocr && best_score >= OCR_SKIP_SCORE {
+                tracing::debug!("Skipping OCR: best candidate already scores {:.2}", best_score);
+                continue;
+            }
+
+            let step_timeout = self.step_timeouts.for_step(step);
+            tracing::info!("AWCS Phase 3 - Attempting extraction with method: {} (timeout {}ms)",
+                           step.as_str(), step_timeout.as_millis());
+
+            match timeout(step_timeout, self.try_step(step, &window_info)).await {
+                Ok(Ok(context)) => {
+                    let score = Self::score_candidate(&context);
+                    tracing::info!("{} extraction scored {:.2} ({:.1}% completeness)",
+                                   step.as_str(), score, context.confidence.text_completeness * 100.0);
+                    candidates.push((score, context));
+                },
+                Ok(Err(e)) => {
+                    tracing::info!("{} extraction failed: {}", step.as_str(), e);
+                    degraded_steps.push(format!("{}: {}", step.as_str(), e));
+                },
+                Err(_) => {
+                    // Le future abandonné tue le processus osascript (kill_on_drop)
+                    tracing::warn!("{} extraction timed out after {}ms", step.as_str(), step_timeout.as_millis());
+                    degraded_steps.push(format!("{}: timed out after {}ms", step.as_str(), step_timeout.as_millis()));
+                },
+            }
         }
         
-        // OCR extraction (last resort) - Timeout plus long pour l'OCR
-        tracing::info!("AWCS Phase 3 - Attempting extraction with method: OCR (last resort)");
-        let ocr_timeout = Duration::from_secs(10); // 10 secondes pour l'OCR
-        match timeout(ocr_timeout, self.try_ocr_extraction(&window_info)).await {
-            Ok(Ok(context)) => {
-                if context.confidence.text_completeness > 0.5 {
-                    tracing::info!("Extraction successful with ocr: {:.1}% completeness", context.confidence.text_completeness * 100.0);
-                    return Ok(context);
-                } else {
-                    tracing::debug!("OCR extraction had low confidence: {:.1}%", context.confidence.text_completeness * 100.0);
-                }
-            },
-            Ok(Err(e)) => tracing::error!("OCR extraction failed: {}", e),
-            Err(_) => tracing::error!("OCR extraction timed out after {} seconds", ocr_timeout.as_secs()),
+        // Meilleur candidat, avec le score des méthodes suivantes
+        candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let method_scores: Vec<MethodScore> = candidates.iter()
+            .map(|(score, context)| MethodScore {
+                method: context.confidence.extraction_method.clone(),
+                score: *score,
+            })
+            .collect();
+        if let Some((score, mut context)) = candidates.into_iter().next().filter(|(score, _)| *score > 0.0) {
+            tracing::info!("Extraction won by {} (score {:.2}), runner-ups: {:?}",
+                           context.confidence.extraction_method, score,
+                           method_scores.iter().skip(1).map(|m| format!("{}={:.2}", m.method, m.score)).collect::<Vec<_>>());
+            context.confidence.degraded_steps = degraded_steps;
+            context.confidence.method_scores = method_scores;
+            Self::classify_content(&mut context);
+            return Ok(context);
         }
         
         // Si tous les fallbacks échouent, retourner une enveloppe minimale
@@ -140,12 +151,34 @@ impl ContextExtractor {
                 text_completeness: 0.1,
                 source_reliability: 0.5,
                 extraction_method: "fallback".to_string(),
+                degraded_steps,
+                method_scores,
             },
             timestamp: chrono::Utc::now(),
             security_flags: None,
+            content_kind: ContentKind::default(),
         })
     }
     
+    /// Score d'un candidat : qualité du texte (longueur, bruit, cohérence) pondérée
+    /// par la fiabilité de la source
+    fn score_candidate(context: &ContextEnvelope) -> f64 {
+        let text = context.content.fulltext.as_deref()
+            .or(context.content.selection.as_deref())
+            .unwrap_or("");
+        0.8 * TextQuality::score(text) + 0.2 * context.confidence.source_reliability
+    }
+    
+    /// Exécute une étape de la chaîne d'extraction
+    async fn try_step(&mut self, step: ExtractionStep, window: &WindowInfo) -> Result<ContextEnvelope, AWCSError> {
+        match step {
+            ExtractionStep::Dom => self.try_dom_extraction(window).await,
+            ExtractionStep::AppleScript => self.try_applescript_extraction(window).await,
+            ExtractionStep::Accessibility => self.try_accessibility_extraction(window).await,
+            ExtractionStep::Ocr => self.try_ocr_extraction(window).await,
+        }
+    }
+    
     // === Méthodes d'extraction spécialisées ===
     
     /// Tentative d'extraction DOM (navigateurs)
@@ -178,9 +211,12 @@ impl ContextExtractor {
                 text_completeness: if content.word_count > 100 { 0.95 } else { 0.7 },
                 source_reliability: 0.9,
                 extraction_method: "dom".to_string(),
+                degraded_steps: Vec::new(),
+                method_scores: Vec::new(),
             },
             timestamp: chrono::Utc::now(),
             security_flags: None,
+            content_kind: ContentKind::default(),
         })
     }
     
@@ -211,9 +247,12 @@ impl ContextExtractor {
                 text_completeness: if content.word_count > 50 { 0.9 } else { 0.6 },
                 source_reliability: 0.95,
                 extraction_method: "applescript".to_string(),
+                degraded_steps: Vec::new(),
+                method_scores: Vec::new(),
             },
             timestamp: chrono::Utc::now(),
             security_flags: None,
+            content_kind: ContentKind::default(),
         })
     }
     
@@ -236,9 +275,12 @@ impl ContextExtractor {
                 text_completeness: if content.elements_count > 5 { 0.8 } else { 0.5 },
                 source_reliability: 0.7,
                 extraction_method: "accessibility".to_string(),
+                degraded_steps: Vec::new(),
+                method_scores: Vec::new(),
             },
             timestamp: chrono::Utc::now(),
             security_flags: None,
+            content_kind: ContentKind::default(),
         })
     }
     
@@ -261,9 +303,12 @@ impl ContextExtractor {
                 text_completeness: content.confidence,
                 source_reliability: 0.7,
                 extraction_method: "ocr".to_string(),
+                degraded_steps: Vec::new(),
+                method_scores: Vec::new(),
             },
             timestamp: chrono::Utc::now(),
             security_flags: None,
+            content_kind: ContentKind::default(),
         })
     }
     
@@ -285,9 +330,11 @@ impl ContextExtractor {
     /// Tente l'extraction via l'extension browser
     async fn try_extension_extraction(&self, window: &WindowInfo) -> Result<ContextEnvelope, AWCSError> {
         // Déclencher l'extraction via l'extension browser
+        let base_url = crate::ext_server::ext_server_base_url()
+            .ok_or_else(|| AWCSError::ExtractionFailed("Extension server not running".to_string()))?;
         let client = reqwest::Client::new();
         let response = client
-            .post("http://127.0.0.1:8766/api/extension/trigger")
+            .post(format!("{}/api/extension/trigger", base_url))
             .json(&serde_json::json!({
                 "action": "extract_current_tab",
                 "source": "awcs_shortcut"
@@ -314,24 +361,43 @@ impl ContextExtractor {
                     text_completeness: 0.8, // Haute confiance pour l'extension
                     source_reliability: 0.9,
                     extraction_method: "browser_extension".to_string(),
+                    degraded_steps: Vec::new(),
+                    method_scores: Vec::new(),
                 },
                 timestamp: chrono::Utc::now(),
                 security_flags: None,
+                content_kind: ContentKind::default(),
             })
         } else {
             Err(AWCSError::ExtractionFailed("Extension server returned error".to_string()))
         }
     }
     
+    /// Classe le contenu retenu (code / prose / tableau) ; la sélection prime sur le texte complet
+    fn classify_content(context: &mut ContextEnvelope) {
+        let text = context.content.selection.as_deref()
+            .or(context.content.fulltext.as_deref())
+            .unwrap_or_default();
+        context.content_kind = ContentClassifier::classify(text);
+        tracing::debug!("Content classified as {:?}", context.content_kind);
+    }
+    
     /// Vérifie si l'application est supportée par AppleScript
     fn is_applescript_supported(&self, app_name: &str) -> bool {
         let supported_apps = ["Microsoft Word", "Microsoft Excel", "Microsoft PowerPoint", "Pages", "Numbers", "Keynote"];
         supported_apps.iter().any(|&app| app_name.contains(app))
     }
     
-    /// Configure le timeout d'extraction
+    /// Configure le timeout des étapes DOM et Accessibility
     pub fn set_extraction_timeout(&mut self, timeout: Duration) {
-        self.extraction_timeout = timeout;
+        self.step_timeouts.dom_ms = timeout.as_millis() as u64;
+        self.step_timeouts.accessibility_ms = timeout.as_millis() as u64;
+    }
+    
+    /// Configure le timeout de chaque étape de la chaîne d'extraction
+    pub fn set_step_timeouts(&mut self, timeouts: ExtractionTimeouts) {
+        self.applescript_extractor.set_timeout(timeouts.for_step(ExtractionStep::AppleScript));
+        self.step_timeouts = timeouts;
     }
     
     /// Force l'extraction OCR directement (mode universel)
@@ -349,9 +415,61 @@ impl ContextExtractor {
         );
         
         // 2. Extraction OCR directe
+        self.ocr_direct_for_window(window_info).await
+    }
+    
+    /// Extrait le texte sélectionné dans la fenêtre active (sans capture ni OCR, confiance selon
+    /// la méthode de lecture) ; sans sélection, retombe sur l'OCR direct
+    pub async fn extract_from_selection(&mut self) -> Result<ContextEnvelope, AWCSError> {
+        let window_info = self.window_detector.get_current_window().await?;
+        
+        tracing::info!(
+            "AWCS - Selection extraction: app='{}', title='{}', pid={}",
+            window_info.app,
+            window_info.title,
+            window_info.pid
+        );
+        
+        let Some(selection) = self.selection_extractor.extract_selection(&window_info).await else {
+            tracing::info!("No text selected in {}, falling back to OCR", window_info.app);
+            let mut context = self.ocr_direct_for_window(window_info).await?;
+            context.confidence.degraded_steps.insert(0, "selection: no text selected".to_string());
+            return Ok(context);
+        };
+        
+        let method = format!("selection_{}", selection.method.as_str());
+        let reliability = selection.method.reliability();
+        let mut context = ContextEnvelope {
+            source: window_info,
+            document: None,
+            content: ContentData {
+                selection: Some(selection.text),
+                fulltext: None,
+                metadata: Some(serde_json::json!({
+                    "selection_method": selection.method,
+                })),
+            },
+            confidence: ExtractionConfidence {
+                text_completeness: reliability,
+                source_reliability: reliability,
+                extraction_method: method,
+                degraded_steps: Vec::new(),
+                method_scores: Vec::new(),
+            },
+            timestamp: chrono::Utc::now(),
+            security_flags: None,
+            content_kind: ContentKind::default(),
+        };
+        Self::classify_content(&mut context);
+        Ok(context)
+    }
+    
+    /// OCR direct d'une fenêtre déjà détectée, avec enveloppe minimale en cas d'échec
+    async fn ocr_direct_for_window(&mut self, window_info: WindowInfo) -> Result<ContextEnvelope, AWCSError> {
         match self.try_ocr_extraction(&window_info).await {
-            Ok(context) => {
+            Ok(mut context) => {
                 tracing::info!("Direct OCR extraction successful: {:.1}% confidence", context.confidence.text_completeness * 100.0);
+                Self::classify_content(&mut context);
                 Ok(context)
             },
             Err(e) => {
@@ -370,9 +488,12 @@ impl ContextExtractor {
                         text_completeness: 0.1,
                         source_reliability: 0.3,
                         extraction_method: "ocr_failed".to_string(),
+                        degraded_steps: Vec::new(),
+                        method_scores: Vec::new(),
                     },
                     timestamp: chrono::Utc::now(),
                     security_flags: None,
+                    content_kind: ContentKind::default(),
                 })
             }
         }
@@ -2,8 +2,63 @@
 // Phase 4: Système de raccourcis globaux pour activation AWCS
 
 use crate::awcs::types::*;
-use tauri_plugin_global_shortcut::GlobalShortcutExt;
-use tauri::Emitter;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut};
+
+/// Raccourci AWCS par défaut
+pub const DEFAULT_GLOBAL_SHORTCUT: &str = "Cmd+Shift+Control+L";
+
+/// Fichier de réglage du raccourci, dans le répertoire de configuration de l'app
+pub const SHORTCUT_SETTINGS_FILE: &str = "awcs_shortcut.json";
+
+/// Réglage persisté du raccourci global
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutSettings {
+    pub accelerator: String,
+}
+
+/// Valide un accelerator ("Cmd+Shift+L") : syntaxe du plugin et au moins un modificateur,
+/// une touche seule capturerait la saisie dans toutes les applications
+pub fn parse_accelerator(accelerator: &str) -> Result<Shortcut, AWCSError> {
+    let shortcut: Shortcut = accelerator.trim().parse()
+        .map_err(|e| AWCSError::InvalidInput(format!("Invalid shortcut '{}': {}", accelerator, e)))?;
+    
+    if shortcut.mods == Modifiers::empty() {
+        return Err(AWCSError::InvalidInput(format!(
+            "Shortcut '{}' needs at least one modifier (Cmd, Ctrl, Alt, Shift)", accelerator
+        )));
+    }
+    
+    Ok(shortcut)
+}
+
+fn settings_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(SHORTCUT_SETTINGS_FILE)
+}
+
+/// Lit le raccourci persisté (None si absent ou illisible)
+pub fn load_persisted_shortcut(config_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(settings_path(config_dir)).ok()?;
+    match serde_json::from_str::<ShortcutSettings>(&content) {
+        Ok(settings) => Some(settings.accelerator),
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable shortcut settings: {}", e);
+            None
+        }
+    }
+}
+
+/// Persiste le raccourci pour les prochains démarrages
+pub fn persist_shortcut(config_dir: &Path, accelerator: &str) -> Result<(), AWCSError> {
+    let settings = ShortcutSettings { accelerator: accelerator.to_string() };
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| AWCSError::InvalidInput(format!("Failed to serialize shortcut settings: {}", e)))?;
+    
+    std::fs::create_dir_all(config_dir)
+        .and_then(|_| std::fs::write(settings_path(config_dir), content))
+        .map_err(|e| AWCSError::TaskExecutionFailed(format!("Failed to save shortcut settings: {}", e)))
+}
 
 /// Gestionnaire des raccourcis globaux AWCS
 #[derive(Debug)]
@@ -22,20 +77,59 @@ impl GlobalShortcutManager {
     }
     
     /// Configure et enregistre un raccourci global
+    /// (les événements sont traités par le `with_handler` du plugin)
     pub async fn register_shortcut(
         &mut self, 
         shortcut: &str,
-        _app_handle: tauri::AppHandle,
+        app_handle: tauri::AppHandle,
     ) -> Result<(), AWCSError> {
-        tracing::info!("AWCS Phase 4: Shortcut is managed by plugin initialization: {}", shortcut);
+        let parsed = parse_accelerator(shortcut)?;
+        let shortcut_manager = app_handle.global_shortcut();
         
-        // Le raccourci est maintenant géré directement par le plugin lors de l'initialisation
-        // Pas besoin d'enregistrement manuel supplémentaire
-        self.shortcuts.push(shortcut.to_string());
+        if !shortcut_manager.is_registered(parsed) {
+            shortcut_manager.register(parsed).map_err(|e| conflict_error(shortcut, e))?;
+        }
+        
+        if !self.shortcuts.iter().any(|s| s == shortcut) {
+            self.shortcuts.push(shortcut.to_string());
+        }
         self.is_active = true;
         
         tracing::info!("AWCS Phase 4: Global shortcut setup completed: {}", shortcut);
-        tracing::info!("AWCS Phase 4: Shortcut events will be handled by with_handler");
+        Ok(())
+    }
+    
+    /// Remplace les raccourcis actifs par un nouveau : le nouveau est enregistré d'abord,
+    /// l'ancien reste actif si l'enregistrement échoue
+    pub async fn replace_shortcut(
+        &mut self,
+        shortcut: &str,
+        app_handle: tauri::AppHandle,
+    ) -> Result<(), AWCSError> {
+        let parsed = parse_accelerator(shortcut)?;
+        let shortcut_manager = app_handle.global_shortcut();
+        
+        let previous: Vec<String> = self.shortcuts.drain(..).collect();
+        let already_active = previous.iter()
+            .any(|s| parse_accelerator(s).map(|p| p.id() == parsed.id()).unwrap_or(false));
+        
+        if !already_active {
+            if let Err(e) = shortcut_manager.register(parsed) {
+                self.shortcuts = previous;
+                return Err(conflict_error(shortcut, e));
+            }
+        }
+        
+        for old in previous.iter().filter(|s| parse_accelerator(s).map(|p| p.id() != parsed.id()).unwrap_or(true)) {
+            if let Err(e) = shortcut_manager.unregister(old.as_str()) {
+                tracing::warn!("Failed to unregister shortcut {}: {}", old, e);
+            }
+        }
+        
+        self.shortcuts.push(shortcut.to_string());
+        self.is_active = true;
+        
+        tracing::info!("AWCS Phase 4: Global shortcut changed to {}", shortcut);
         Ok(())
     }
     
@@ -69,8 +163,16 @@ impl GlobalShortcutManager {
     }
 }
 
+/// Échec d'enregistrement : en pratique le raccourci est déjà pris par une autre application
+fn conflict_error(shortcut: &str, error: tauri_plugin_global_shortcut::Error) -> AWCSError {
+    AWCSError::InvalidInput(format!(
+        "Shortcut '{}' could not be registered, it is probably already used by another application: {}",
+        shortcut, error
+    ))
+}
+
 impl Default for GlobalShortcutManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
@@ -5,8 +5,10 @@ use super::extractor::ContextExtractor;
 use super::intention_analyzer::IntentionAnalyzer;
 use super::permissions::PermissionsManager;
 use super::global_shortcuts::GlobalShortcutManager; // Phase 4
+use super::context_cache::{ContextCache, ContextCacheKey};
+use super::screen_capture::ScreenCaptureManager;
 use crate::awcs::types::*;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::collections::HashMap;
 
 /// Gestionnaire principal AWCS
@@ -18,6 +20,8 @@ pub struct AWCSManager {
     shortcuts: GlobalShortcutManager, // Phase 4: Raccourcis globaux
     config: AWCSConfig,
     metrics: AWCSMetrics,
+    screen_capture: ScreenCaptureManager,
+    context_cache: ContextCache,
 }
 
 impl AWCSManager {
@@ -25,27 +29,33 @@ impl AWCSManager {
     pub fn new() -> Self {
         tracing::info!("Initializing AWCS Manager - Phase 2 (Incremental)");
         
+        let config = AWCSConfig::default();
         Self {
             extractor: ContextExtractor::new(),
             analyzer: IntentionAnalyzer::new(),
             permissions: PermissionsManager::new(),
             shortcuts: GlobalShortcutManager::new(), // Phase 4
-            config: AWCSConfig::default(),
+            context_cache: ContextCache::new(Duration::from_millis(config.context_cache_ttl_ms)),
+            config,
             metrics: AWCSMetrics::default(),
+            screen_capture: ScreenCaptureManager::new(),
         }
     }
     
     /// Configure AWCS avec des paramètres personnalisés
     pub fn with_config(mut self, config: AWCSConfig) -> Self {
+        self.extractor.set_step_timeouts(config.step_timeouts.clone());
+        self.context_cache.set_ttl(Duration::from_millis(config.context_cache_ttl_ms));
         self.config = config;
         self
     }
     
-    /// Extrait le contexte de la fenêtre active
-    pub async fn get_current_context(&mut self) -> Result<ContextEnvelope, AWCSError> {
+    /// Extrait le contexte de la fenêtre active ; avec `context_cache_ttl_ms` > 0 (désactivé par défaut),
+    /// le dernier contexte est réutilisé tant que la fenêtre (titre, position, taille) n'a pas changé, sauf `force_refresh`
+    pub async fn get_current_context(&mut self, force_refresh: bool) -> Result<ContextEnvelope, AWCSError> {
         let start_time = Instant::now();
         
-        tracing::debug!("Starting context extraction");
+        tracing::debug!("Starting context extraction (force_refresh={})", force_refresh);
         
         // 1. Vérification des permissions
         if !self.permissions.check_required_permissions().await? {
@@ -54,13 +64,34 @@ impl AWCSManager {
             ));
         }
         
-        // 2. Extraction du contexte
-        let context = self.extractor.extract_current_window_context().await?;
+        // 2. Cache : même fenêtre, même géométrie
+        let cache_key = self.context_cache_key().await;
+        if !force_refresh {
+            if let Some(cached) = cache_key.as_ref().and_then(|key| self.context_cache.get(key)) {
+                self.metrics.context_cache_hits += 1;
+                tracing::info!("Context served from cache: app={}, method={} ({}ms)",
+                               cached.source.app, cached.confidence.extraction_method,
+                               start_time.elapsed().as_millis());
+                return Ok(cached);
+            }
+        }
+        
+        // 3. Extraction du contexte
+        let context = match self.extractor.extract_current_window_context().await {
+            Ok(context) => context,
+            Err(e) => {
+                self.record_extraction_failure();
+                return Err(e);
+            }
+        };
         
-        // 3. Validation de sécurité
+        // 4. Validation de sécurité
         let validated_context = self.apply_security_filters(context).await?;
+        if let Some(key) = cache_key {
+            self.context_cache.store(key, validated_context.clone());
+        }
         
-        // 4. Mise à jour des métriques
+        // 5. Mise à jour des métriques
         let extraction_time = start_time.elapsed().as_millis() as f64;
         self.update_extraction_metrics(&validated_context, extraction_time);
         
@@ -74,6 +105,38 @@ impl AWCSManager {
         Ok(validated_context)
     }
     
+    /// Extrait le texte sélectionné dans la fenêtre active (OCR si aucune sélection)
+    pub async fn get_selection_context(&mut self) -> Result<ContextEnvelope, AWCSError> {
+        let start_time = Instant::now();
+        
+        if !self.permissions.check_required_permissions().await? {
+            return Err(AWCSError::PermissionsInsufficient(
+                "Required permissions not granted".to_string()
+            ));
+        }
+        
+        let context = match self.extractor.extract_from_selection().await {
+            Ok(context) => context,
+            Err(e) => {
+                self.record_extraction_failure();
+                return Err(e);
+            }
+        };
+        let validated_context = self.apply_security_filters(context).await?;
+        
+        let extraction_time = start_time.elapsed().as_millis() as f64;
+        self.update_extraction_metrics(&validated_context, extraction_time);
+        
+        tracing::info!(
+            "Selection context extracted: app={}, method={}, {}ms",
+            validated_context.source.app,
+            validated_context.confidence.extraction_method,
+            extraction_time
+        );
+        
+        Ok(validated_context)
+    }
+    
     /// Traite une requête utilisateur avec le contexte
     pub async fn handle_query(
         &mut self,
@@ -118,6 +181,13 @@ impl AWCSManager {
         Ok(())
     }
     
+    /// Change le raccourci global (validé, sans conflit) et met à jour la configuration
+    pub async fn set_global_shortcut(&mut self, accelerator: &str, app_handle: tauri::AppHandle) -> Result<(), AWCSError> {
+        self.shortcuts.replace_shortcut(accelerator, app_handle).await?;
+        self.config.global_shortcut = accelerator.to_string();
+        Ok(())
+    }
+    
     /// Nettoie les ressources AWCS
     pub async fn cleanup(&mut self, app_handle: tauri::AppHandle) -> Result<(), AWCSError> {
         tracing::info!("AWCS Phase 4: Cleaning up AWCS resources");
@@ -125,8 +195,9 @@ impl AWCSManager {
         // Désactiver les raccourcis globaux
         self.shortcuts.unregister_all(app_handle).await?;
         
-        // Réinitialiser les métriques
+        // Réinitialiser les métriques et le cache de contexte
         self.metrics = AWCSMetrics::default();
+        self.context_cache.clear();
         
         tracing::info!("AWCS Phase 4: Cleanup completed");
         Ok(())
@@ -137,6 +208,12 @@ impl AWCSManager {
         &self.metrics
     }
     
+    /// Remet à zéro les métriques de la session
+    pub fn reset_metrics(&mut self) {
+        self.metrics = AWCSMetrics::default();
+        tracing::info!("AWCS metrics reset");
+    }
+    
     /// Récupère la configuration actuelle
     pub fn get_config(&self) -> &AWCSConfig {
         &self.config
@@ -144,12 +221,29 @@ impl AWCSManager {
     
     /// Met à jour la configuration
     pub fn update_config(&mut self, config: AWCSConfig) {
+        self.extractor.set_step_timeouts(config.step_timeouts.clone());
+        self.context_cache.set_ttl(Duration::from_millis(config.context_cache_ttl_ms));
         self.config = config;
         tracing::info!("AWCS configuration updated");
     }
     
     // === Méthodes privées ===
     
+    /// Clé de cache de l'état courant : fenêtre active et sa géométrie, sans capture d'écran
+    /// (une capture par appel doublait le coût d'une extraction OCR) ; `None` si le cache est désactivé
+    async fn context_cache_key(&self) -> Option<ContextCacheKey> {
+        if self.config.context_cache_ttl_ms == 0 {
+            return None;
+        }
+        
+        let window = self.extractor.current_window().await.ok()?;
+        let bounds = self.screen_capture.active_window_bounds().await;
+        if bounds.is_none() {
+            tracing::debug!("Window bounds unavailable, caching by window only");
+        }
+        Some(ContextCacheKey::new(&window, bounds))
+    }
+    
     /// Applique les filtres de sécurité au contexte
     async fn apply_security_filters(
         &self,
@@ -159,6 +253,7 @@ impl AWCSManager {
         if self.config.blocked_apps.iter().any(|app| context.source.app.contains(app)) {
             // Mode sécurisé pour applications sensibles
             context.content.fulltext = None;
+            context.content.selection = None;
             context.security_flags = Some(SecurityFlags {
                 pii_redacted: true,
                 full_text_blocked: true,
@@ -255,10 +350,21 @@ impl AWCSManager {
         self.metrics.avg_extraction_time = 
             (self.metrics.avg_extraction_time * (total_extractions - 1.0) + extraction_time) / total_extractions;
         
-        // Distribution des méthodes
+        // Distribution des méthodes et temps moyen par méthode
+        let method = &context.confidence.extraction_method;
         *self.metrics.method_distribution
-            .entry(context.confidence.extraction_method.clone())
+            .entry(method.clone())
             .or_insert(0) += 1;
+        let method_metrics = self.metrics.method_metrics.entry(method.clone()).or_default();
+        method_metrics.count += 1;
+        method_metrics.total_time_ms += extraction_time;
+        method_metrics.avg_time_ms = method_metrics.total_time_ms / method_metrics.count as f64;
+        
+        // Distribution de la confiance OCR (complétude = confiance Tesseract)
+        if method.contains("ocr") {
+            let bucket = ((context.confidence.text_completeness * 5.0) as usize).min(4);
+            self.metrics.ocr_confidence_distribution[bucket] += 1;
+        }
         
         // Compatibilité par application
         let app_name = &context.source.app;
@@ -272,11 +378,23 @@ impl AWCSManager {
         
         // Taux de succès global
         if context.confidence.text_completeness > 0.5 {
-            let successes = (self.metrics.extraction_success_rate * (total_extractions - 1.0)) + 1.0;
-            self.metrics.extraction_success_rate = successes / total_extractions;
+            self.metrics.successful_extractions += 1;
         } else {
-            let successes = self.metrics.extraction_success_rate * (total_extractions - 1.0);
-            self.metrics.extraction_success_rate = successes / total_extractions;
+            self.metrics.failed_extractions += 1;
+        }
+        self.update_success_rate();
+    }
+    
+    /// Comptabilise une extraction en erreur
+    fn record_extraction_failure(&mut self) {
+        self.metrics.failed_extractions += 1;
+        self.update_success_rate();
+    }
+    
+    fn update_success_rate(&mut self) {
+        let attempts = self.metrics.successful_extractions + self.metrics.failed_extractions;
+        if attempts > 0 {
+            self.metrics.extraction_success_rate = self.metrics.successful_extractions as f64 / attempts as f64;
         }
     }
     
@@ -7,6 +7,8 @@ pub mod intention_analyzer;
 pub mod permissions;
 pub mod screen_capture; // Phase 3: Module de capture d'écran natif
 pub mod global_shortcuts; // Phase 4: Module de raccourcis globaux
+pub mod window_watcher; // Mode suivi du focus (awcs-window-changed)
+pub mod context_cache; // Réutilisation du dernier contexte (fenêtre/écran inchangés)
 
 // Re-exports
 pub use manager::AWCSManager;
@@ -14,4 +16,5 @@ pub use extractor::ContextExtractor;
 pub use intention_analyzer::IntentionAnalyzer;
 pub use permissions::PermissionsManager;
 pub use screen_capture::ScreenCaptureManager; // Phase 3: Capture d'écran native
-pub use global_shortcuts::GlobalShortcutManager; // Phase 4: Raccourcis globaux
\ No newline at end of file
+pub use global_shortcuts::GlobalShortcutManager; // Phase 4: Raccourcis globaux
+pub use window_watcher::{WindowWatcher, WindowWatcherConfig};
\ No newline at end of file
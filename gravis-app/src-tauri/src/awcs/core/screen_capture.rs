@@ -37,6 +37,37 @@ pub struct CaptureZone {
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    /// Écran de référence : si défini, (x, y) sont relatifs à l'origine de cet écran
+    pub display_index: Option<usize>,
+}
+
+/// Position et taille d'une fenêtre (coordonnées globales en points)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Informations sur un écran connecté (coordonnées globales en points)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    pub index: usize,
+    pub id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub is_primary: bool,
+}
+
+impl DisplayInfo {
+    /// Vérifie si un point global se trouve sur cet écran
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.width as i32
+            && y >= self.y && y < self.y + self.height as i32
+    }
 }
 
 impl ScreenCaptureManager {
@@ -60,14 +91,45 @@ impl ScreenCaptureManager {
         Self { platform, temp_dir }
     }
     
-    /// Capture l'écran entier
+    /// Liste les écrans connectés avec leurs bounds
+    pub fn list_displays(&self) -> Result<Vec<DisplayInfo>, AWCSError> {
+        match self.platform {
+            Platform::MacOS => self.list_macos_displays(),
+            Platform::Windows => Err(AWCSError::ScreenCaptureError("Windows display enumeration not implemented yet".to_string())),
+            Platform::Linux => Err(AWCSError::ScreenCaptureError("Linux display enumeration not implemented yet".to_string())),
+        }
+    }
+    
+    /// Retourne l'écran contenant le point global (x, y), ou l'écran principal
+    pub fn display_at(&self, x: i32, y: i32) -> Result<DisplayInfo, AWCSError> {
+        let displays = self.list_displays()?;
+        
+        displays.iter()
+            .find(|d| d.contains(x, y))
+            .or_else(|| displays.iter().find(|d| d.is_primary))
+            .or_else(|| displays.first())
+            .cloned()
+            .ok_or_else(|| AWCSError::ScreenCaptureError("No display available".to_string()))
+    }
+    
+    /// Capture l'écran entier contenant la fenêtre active (écran principal en fallback)
     pub async fn capture_full_screen(&self) -> Result<ScreenshotResult, AWCSError> {
-        tracing::debug!("Capturing full screen");
+        let display_index = match self.active_window_bounds().await {
+            Some(bounds) => self.display_at(bounds.x, bounds.y).map(|d| d.index).ok(),
+            None => None,
+        };
+        
+        self.capture_display(display_index).await
+    }
+    
+    /// Capture un écran spécifique (index dans `list_displays`), l'écran principal si `None`
+    pub async fn capture_display(&self, display_index: Option<usize>) -> Result<ScreenshotResult, AWCSError> {
+        tracing::debug!("Capturing full screen (display: {:?})", display_index);
         
         let start_time = Instant::now();
         
         let result = match self.platform {
-            Platform::MacOS => self.capture_macos_full_screen().await,
+            Platform::MacOS => self.capture_macos_full_screen(display_index).await,
             Platform::Windows => Err(AWCSError::ScreenCaptureError("Windows capture not implemented yet".to_string())),
             Platform::Linux => Err(AWCSError::ScreenCaptureError("Linux capture not implemented yet".to_string())),
         };
@@ -98,12 +160,25 @@ impl ScreenCaptureManager {
     
     /// Capture une zone spécifique de l'écran
     pub async fn capture_zone(&self, zone: &CaptureZone) -> Result<ScreenshotResult, AWCSError> {
-        tracing::debug!("Capturing zone: {}x{} at ({}, {})", zone.width, zone.height, zone.x, zone.y);
+        tracing::debug!("Capturing zone: {}x{} at ({}, {}) on display {:?}",
+                        zone.width, zone.height, zone.x, zone.y, zone.display_index);
         
         let start_time = Instant::now();
         
+        // Convertir les coordonnées relatives à l'écran en coordonnées globales
+        let mut global_zone = zone.clone();
+        if let Some(index) = zone.display_index {
+            let display = self.list_displays()?
+                .into_iter()
+                .find(|d| d.index == index)
+                .ok_or_else(|| AWCSError::InvalidInput(format!("Display {} not found", index)))?;
+            global_zone.x += display.x;
+            global_zone.y += display.y;
+            global_zone.display_index = None;
+        }
+        
         let result = match self.platform {
-            Platform::MacOS => self.capture_macos_zone(zone).await,
+            Platform::MacOS => self.capture_macos_zone(&global_zone).await,
             Platform::Windows => Err(AWCSError::ScreenCaptureError("Windows capture not implemented yet".to_string())),
             Platform::Linux => Err(AWCSError::ScreenCaptureError("Linux capture not implemented yet".to_string())),
         };
@@ -117,13 +192,88 @@ impl ScreenCaptureManager {
     // === Implémentations macOS ===
     
     #[cfg(target_os = "macos")]
-    async fn capture_macos_full_screen(&self) -> Result<ScreenshotResult, AWCSError> {
+    fn list_macos_displays(&self) -> Result<Vec<DisplayInfo>, AWCSError> {
+        use core_graphics::display::CGDisplay;
+        
+        let ids = CGDisplay::active_displays()
+            .map_err(|e| AWCSError::ScreenCaptureError(format!("Failed to list displays: {:?}", e)))?;
+        
+        Ok(ids.into_iter().enumerate().map(|(index, id)| {
+            let display = CGDisplay::new(id);
+            let bounds = display.bounds();
+            DisplayInfo {
+                index,
+                id,
+                x: bounds.origin.x as i32,
+                y: bounds.origin.y as i32,
+                width: bounds.size.width as u32,
+                height: bounds.size.height as u32,
+                is_primary: display.is_main(),
+            }
+        }).collect())
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    fn list_macos_displays(&self) -> Result<Vec<DisplayInfo>, AWCSError> {
+        Err(AWCSError::ScreenCaptureError("macOS capture not available on this platform".to_string()))
+    }
+    
+    /// Position et taille globales de la fenêtre au premier plan (via System Events, sans capture)
+    #[cfg(target_os = "macos")]
+    pub async fn active_window_bounds(&self) -> Option<WindowBounds> {
+        let script = r#"
+        tell application "System Events"
+            set frontApp to first application process whose frontmost is true
+            try
+                set {x, y} to position of front window of frontApp
+                set {w, h} to size of front window of frontApp
+                return (x as text) & "," & (y as text) & "," & (w as text) & "," & (h as text)
+            on error
+                return "error"
+            end try
+        end tell
+        "#;
+        
+        let output = tokio::process::Command::new("osascript")
+            .arg("-e")
+            .arg(script)
+            .output()
+            .await
+            .ok()?;
+        
+        let raw = String::from_utf8_lossy(&output.stdout);
+        let mut coords = raw.trim().split(',').map(|v| v.trim().parse::<i32>());
+        match (coords.next(), coords.next(), coords.next(), coords.next()) {
+            (Some(Ok(x)), Some(Ok(y)), Some(Ok(width)), Some(Ok(height))) => Some(WindowBounds {
+                x,
+                y,
+                width: width.max(0) as u32,
+                height: height.max(0) as u32,
+            }),
+            _ => None,
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    pub async fn active_window_bounds(&self) -> Option<WindowBounds> {
+        None
+    }
+    
+    #[cfg(target_os = "macos")]
+    async fn capture_macos_full_screen(&self, display_index: Option<usize>) -> Result<ScreenshotResult, AWCSError> {
         let temp_file = self.temp_dir.join(format!("full_screen_{}.png", std::process::id()));
         
-        let output = tokio::process::Command::new("screencapture")
-            .arg("-x") // Pas de son
+        let mut command = tokio::process::Command::new("screencapture");
+        command.arg("-x") // Pas de son
             .arg("-t")
-            .arg("png")
+            .arg("png");
+        
+        // screencapture numérote les écrans à partir de 1, dans l'ordre de CGGetActiveDisplayList
+        if let Some(index) = display_index {
+            command.arg("-D").arg((index + 1).to_string());
+        }
+        
+        let output = command
             .arg(&temp_file)
             .output()
             .await
@@ -137,7 +287,7 @@ impl ScreenCaptureManager {
     }
     
     #[cfg(not(target_os = "macos"))]
-    async fn capture_macos_full_screen(&self) -> Result<ScreenshotResult, AWCSError> {
+    async fn capture_macos_full_screen(&self, _display_index: Option<usize>) -> Result<ScreenshotResult, AWCSError> {
         Err(AWCSError::ScreenCaptureError("macOS capture not available on this platform".to_string()))
     }
     
@@ -0,0 +1,135 @@
+// GRAVIS AWCS - Window Watcher
+// Mode "suivre le focus" : émet awcs-window-changed quand la fenêtre active change
+
+use crate::awcs::extractors::WindowDetector;
+use crate::awcs::types::*;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::task::JoinHandle;
+
+/// Nom de l'événement émis vers le frontend
+pub const WINDOW_CHANGED_EVENT: &str = "awcs-window-changed";
+
+/// Configuration du watcher de focus
+#[derive(Debug, Clone)]
+pub struct WindowWatcherConfig {
+    pub poll_interval: Duration,
+    pub debounce: Duration, // Durée de stabilité requise avant émission
+}
+
+impl Default for WindowWatcherConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(150),
+            debounce: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Watcher de la fenêtre active (tâche tokio annulable)
+#[derive(Debug, Default)]
+pub struct WindowWatcher {
+    handle: Option<JoinHandle<()>>,
+}
+
+impl WindowWatcher {
+    pub fn new() -> Self {
+        Self { handle: None }
+    }
+
+    /// Vérifie si une tâche de surveillance tourne
+    pub fn is_running(&self) -> bool {
+        self.handle.as_ref().map(|h| !h.is_finished()).unwrap_or(false)
+    }
+
+    /// Démarre la surveillance (no-op si déjà active)
+    pub fn start(&mut self, app: AppHandle, config: WindowWatcherConfig) -> bool {
+        if self.is_running() {
+            tracing::debug!("AWCS watcher already running");
+            return false;
+        }
+
+        tracing::info!("AWCS: Starting window watcher (debounce {}ms)", config.debounce.as_millis());
+        self.handle = Some(tokio::spawn(watch_loop(app, config)));
+        true
+    }
+
+    /// Arrête la surveillance
+    pub fn stop(&mut self) -> bool {
+        match self.handle.take() {
+            Some(handle) => {
+                handle.abort();
+                tracing::info!("AWCS: Window watcher stopped");
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for WindowWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Boucle de polling : une fenêtre candidate n'est émise qu'après `debounce`
+/// de stabilité, ce qui fusionne les rafales d'alt-tab en un seul événement.
+async fn watch_loop(app: AppHandle, config: WindowWatcherConfig) {
+    let detector = WindowDetector::new();
+    let mut interval = tokio::time::interval(config.poll_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    let mut last_emitted: Option<String> = None;
+    let mut pending: Option<(String, Instant)> = None;
+
+    loop {
+        interval.tick().await;
+
+        let window = match detector.get_current_window().await {
+            Ok(window) => window,
+            Err(e) => {
+                tracing::debug!("AWCS watcher: window detection failed: {}", e);
+                continue;
+            }
+        };
+
+        if is_gravis_window(&window) {
+            // Retour sur GRAVIS : abandonner le candidat en cours sans émettre
+            pending = None;
+            continue;
+        }
+
+        let key = window_key(&window);
+        if last_emitted.as_deref() == Some(key.as_str()) {
+            pending = None;
+            continue;
+        }
+
+        match &pending {
+            Some((pending_key, since)) if *pending_key == key => {
+                if since.elapsed() >= config.debounce {
+                    if let Err(e) = app.emit(WINDOW_CHANGED_EVENT, &window) {
+                        tracing::warn!("AWCS watcher: failed to emit window change: {}", e);
+                    } else {
+                        tracing::debug!("AWCS watcher: focus changed to {} - {}", window.app, window.title);
+                    }
+                    last_emitted = Some(key);
+                    pending = None;
+                }
+            }
+            _ => pending = Some((key, Instant::now())),
+        }
+    }
+}
+
+/// Identifiant stable d'une fenêtre pour la détection de changement
+fn window_key(window: &WindowInfo) -> String {
+    format!("{}|{}|{}", window.pid, window.app, window.title)
+}
+
+/// Les fenêtres de GRAVIS ne doivent jamais déclencher d'événement
+fn is_gravis_window(window: &WindowInfo) -> bool {
+    window.pid == std::process::id()
+        || window.app.to_lowercase().contains("gravis")
+}
@@ -4,12 +4,17 @@
 use crate::awcs::types::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// Timeout par défaut d'une invocation osascript
+const DEFAULT_SCRIPT_TIMEOUT: Duration = Duration::from_secs(3);
 
 /// Extracteur AppleScript pour applications macOS
 #[derive(Debug)]
 pub struct AppleScriptExtractor {
     scripts: HashMap<String, String>,
     supported_apps: Vec<String>,
+    timeout: Duration,
 }
 
 /// Résultat d'extraction AppleScript
@@ -37,12 +42,18 @@ impl AppleScriptExtractor {
                 "TextEdit".to_string(),
                 "Preview".to_string(),
             ],
+            timeout: DEFAULT_SCRIPT_TIMEOUT,
         };
         
         extractor.initialize_scripts();
         extractor
     }
     
+    /// Configure le timeout d'une invocation osascript (processus tué à l'expiration)
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+    
     /// Vérifie si l'application est supportée
     pub fn is_supported_app(&self, app_name: &str) -> bool {
         self.supported_apps
@@ -66,12 +77,7 @@ impl AppleScriptExtractor {
         let script = self.get_script_for_app(&window.app)?;
         
         // Exécuter le script
-        let output = tokio::process::Command::new("osascript")
-            .arg("-e")
-            .arg(&script)
-            .output()
-            .await
-            .map_err(|e| AWCSError::ScriptFailed(format!("AppleScript execution failed: {}", e)))?;
+        let output = self.run_osascript(&script).await?;
         
         if !output.status.success() {
             let error_msg = String::from_utf8_lossy(&output.stderr);
@@ -84,6 +90,28 @@ impl AppleScriptExtractor {
         self.parse_script_result(&result, &window.app)
     }
     
+    /// Exécute osascript avec timeout : une app qui ne répond pas ne bloque pas l'extraction,
+    /// le processus est tué (kill_on_drop) quand le timeout expire
+    async fn run_osascript(&self, script: &str) -> Result<std::process::Output, AWCSError> {
+        let child = tokio::process::Command::new("osascript")
+            .arg("-e")
+            .arg(script)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| AWCSError::ScriptFailed(format!("AppleScript execution failed: {}", e)))?;
+        
+        match tokio::time::timeout(self.timeout, child.wait_with_output()).await {
+            Ok(output) => output
+                .map_err(|e| AWCSError::ScriptFailed(format!("AppleScript execution failed: {}", e))),
+            Err(_) => {
+                tracing::warn!("AppleScript timed out after {}ms, osascript killed", self.timeout.as_millis());
+                Err(AWCSError::ScriptFailed(format!("AppleScript timed out after {}ms", self.timeout.as_millis())))
+            }
+        }
+    }
+    
     // === Initialisation des scripts ===
     
     fn initialize_scripts(&mut self) {
@@ -288,12 +316,7 @@ impl AppleScriptExtractor {
     
     /// Vérifie si AppleScript est disponible
     pub async fn check_applescript_availability(&self) -> Result<bool, AWCSError> {
-        let output = tokio::process::Command::new("osascript")
-            .arg("-e")
-            .arg("return \"AppleScript available\"")
-            .output()
-            .await
-            .map_err(|e| AWCSError::ScriptFailed(format!("AppleScript check failed: {}", e)))?;
+        let output = self.run_osascript("return \"AppleScript available\"").await?;
         
         Ok(output.status.success())
     }
@@ -6,10 +6,12 @@ pub mod dom_extractor;
 pub mod applescript_extractor;
 pub mod accessibility_extractor;
 pub mod ocr_extractor;
+pub mod selection_extractor;
 
 // Re-exports
 pub use window_detector::WindowDetector;
 pub use dom_extractor::DOMExtractor;
 pub use applescript_extractor::AppleScriptExtractor;
 pub use accessibility_extractor::AccessibilityExtractor;
-pub use ocr_extractor::OCRExtractor;
\ No newline at end of file
+pub use ocr_extractor::OCRExtractor;
+pub use selection_extractor::SelectionExtractor;
\ No newline at end of file
@@ -4,11 +4,15 @@
 use crate::awcs::types::*;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
+use crate::rag::ocr::{strip_ocr_noise, OcrError, OcrNoiseConfig, TrOCRConfig, TrOCRProcessor};
+use crate::awcs::utils::ContentClassifier;
+use tokio::sync::OnceCell;
 
 /// Extracteur OCR pour fallback universel - Phase 2 Incrémental
 #[derive(Debug)]
 pub struct OCRExtractor {
-    // Phase 2: Améliorations à venir progressivement
+    /// Modèle TrOCR chargé au premier fallback puis réutilisé
+    transformer: OnceCell<TrOCRProcessor>,
 }
 
 /// Résultat d'extraction OCR
@@ -23,7 +27,7 @@ impl OCRExtractor {
     /// Crée un nouveau extracteur OCR
     pub fn new() -> Self {
         tracing::debug!("OCR extractor initialized - Phase 2 (Incremental)");
-        Self {}
+        Self { transformer: OnceCell::new() }
     }
     
     /// Extrait le texte depuis une fenêtre via OCR
@@ -264,6 +268,7 @@ impl OCRExtractor {
             y: coordinates.y,
             width: coordinates.width as u32,
             height: coordinates.height as u32,
+            display_index: coordinates.display_index,
         };
         
         let screen_capture = ScreenCaptureManager::new();
@@ -382,8 +387,20 @@ impl OCRExtractor {
             "dom_extractor.rs"
         ];
         
+        // Code : l'indentation et les lignes de symboles (`}`, `);`) font partie du contenu
+        let is_code = ContentClassifier::classify(text) == ContentKind::Code;
+        
+        // Bruit OCR générique (symboles isolés, fragments), partagé avec le RAG
+        let denoised = if is_code {
+            text.to_string()
+        } else {
+            let (denoised, noise_ratio) = strip_ocr_noise(text, &OcrNoiseConfig::default());
+            tracing::debug!("AWCS OCR noise ratio: {:.1}%", noise_ratio * 100.0);
+            denoised
+        };
+        
         let mut filtered_lines = Vec::new();
-        let lines: Vec<&str> = text.lines().collect();
+        let lines: Vec<&str> = denoised.lines().collect();
         
         for line in lines {
             let line_trimmed = line.trim();
@@ -398,15 +415,13 @@ impl OCRExtractor {
                 line_trimmed.contains(pattern)
             });
             
-            // Ignorer les lignes avec des caractères isolés ou de la navigation
-            let is_noise = line_trimmed.len() < 3 
-                || line_trimmed.chars().all(|c| "©€@&+>\\|".contains(c))
-                || line_trimmed.starts_with("ee0@")
+            // Ignorer les artefacts de navigation récurrents
+            let is_noise = line_trimmed.starts_with("ee0@")
                 || line_trimmed.starts_with("fe >")
                 || line_trimmed.starts_with("ES PP");
             
             if !contains_gravis_ui && !is_noise {
-                filtered_lines.push(line_trimmed);
+                filtered_lines.push(if is_code { line.trim_end() } else { line_trimmed });
             }
         }
         
@@ -420,7 +435,11 @@ impl OCRExtractor {
             .replace("po) V", "")
             .replace("Ca Y", "");
         
-        cleaned_text.trim().to_string()
+        if is_code {
+            cleaned_text.trim_end().to_string()
+        } else {
+            cleaned_text.trim().to_string()
+        }
     }
     
     /// NOUVELLE MÉTHODE : Extraction OCR avec capture de fenêtre focalisée (amélioration Phase 3)
@@ -459,11 +478,11 @@ impl OCRExtractor {
             Ok((x, y, width, height)) => {
                 tracing::info!("AWCS Phase 3: Got window bounds: x={}, y={}, w={}, h={}", x, y, width, height);
                 
-                // Capture d'écran complète puis crop
-                let full_screenshot = self.capture_full_screen_for_crop().await?;
+                // Capture de l'écran contenant la fenêtre puis crop
+                let (full_screenshot, (origin_x, origin_y)) = self.capture_full_screen_for_crop(x, y).await?;
                 
-                // Crop vers la région de la fenêtre active
-                let cropped_image = self.crop_image_to_bounds(full_screenshot, x, y, width, height)?;
+                // Crop vers la région de la fenêtre active (coordonnées relatives à l'écran)
+                let cropped_image = self.crop_image_to_bounds(full_screenshot, x - origin_x, y - origin_y, width, height)?;
                 
                 tracing::info!("AWCS Phase 3: Focused window capture successful: {}x{} pixels", width, height);
                 Ok(cropped_image)
@@ -536,14 +555,18 @@ impl OCRExtractor {
         Err(AWCSError::OCRFailed("Focused window bounds only available on macOS".to_string()))
     }
     
-    async fn capture_full_screen_for_crop(&self) -> Result<Vec<u8>, AWCSError> {
+    /// Capture l'écran contenant le point (x, y) et retourne son origine globale
+    async fn capture_full_screen_for_crop(&self, x: i32, y: i32) -> Result<(Vec<u8>, (i32, i32)), AWCSError> {
         use crate::awcs::core::ScreenCaptureManager;
         
         let screen_capture = ScreenCaptureManager::new();
-        let result = screen_capture.capture_full_screen().await
+        let display = screen_capture.display_at(x, y).ok();
+        
+        let result = screen_capture.capture_display(display.as_ref().map(|d| d.index)).await
             .map_err(|e| AWCSError::OCRFailed(format!("Full screen capture failed: {}", e)))?;
         
-        Ok(result.image_data)
+        let origin = display.map(|d| (d.x, d.y)).unwrap_or((0, 0));
+        Ok((result.image_data, origin))
     }
     
     fn crop_image_to_bounds(&self, image_data: Vec<u8>, x: i32, y: i32, width: i32, height: i32) -> Result<Vec<u8>, AWCSError> {
@@ -752,31 +775,26 @@ impl OCRExtractor {
         confidence.max(0.0).min(1.0)
     }
     
-    /// Essayer l'OCR via transformer (TrOCR/PaddleOCR) comme fallback
+    /// Essayer l'OCR via transformer (TrOCR ONNX) comme fallback ; tout échec est
+    /// rapporté en `OcrError::TransformerUnavailable`
     async fn try_transformer_ocr(&self, image_data: &[u8]) -> Result<OCRSimpleResult, AWCSError> {
-        tracing::info!("AWCS Phase 3: Attempting transformer OCR (experimental)");
-        
-        // Pour l'instant, on retourne une erreur car les transformers ne sont pas encore intégrés
-        // Dans une future version, on pourrait intégrer TrOCR via Candle ou onnxruntime
-        
-        // Simulation de ce que serait le résultat
-        // TODO: Intégrer TrOCR/PaddleOCR via Candle transformers
-        Err(AWCSError::OCRFailed(
-            "Transformer OCR not yet implemented. Available as fallback when Candle TrOCR integration is complete.".to_string()
-        ))
-        
-        /* Version future avec TrOCR:
+        tracing::info!("AWCS Phase 3: Attempting transformer OCR (TrOCR)");
         
-        use crate::rag::ocr::transformer::TrOCRProcessor;
+        // Échoue si la feature `trocr` ou le modèle est absent (nouvel essai au prochain appel)
+        let processor = self.transformer
+            .get_or_try_init(|| TrOCRProcessor::new(TrOCRConfig::default()))
+            .await?;
         
-        let processor = TrOCRProcessor::new().await?;
-        let result = processor.process_image(image_data).await?;
+        let result = processor.process_image(image_data).await
+            .map_err(|e| match e {
+                OcrError::TransformerUnavailable(_) => e,
+                other => OcrError::TransformerUnavailable(format!("Inference failed: {}", other)),
+            })?;
         
         Ok(OCRSimpleResult {
-            text: result.text,
+            text: self.filter_gravis_ui(&result.text),
             confidence: result.confidence,
         })
-        */
     }
 
     /// Teste l'extraction OCR
@@ -0,0 +1,268 @@
+// GRAVIS AWCS - Selection Extractor
+// Lecture directe du texte sélectionné (accessibilité, copie synthétique, sélection primaire X11)
+// Alternative rapide et exacte à l'OCR quand l'utilisateur a sélectionné du texte
+
+use crate::awcs::types::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Timeout par défaut d'une lecture de sélection
+const DEFAULT_SELECTION_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Extracteur de la sélection courante
+#[derive(Debug)]
+pub struct SelectionExtractor {
+    timeout: Duration,
+    /// Dernier texte lu dans PRIMARY : X11 conserve la sélection après désélection,
+    /// une lecture identique est considérée comme périmée
+    last_primary: Mutex<Option<String>>,
+}
+
+/// Source de la sélection lue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionMethod {
+    /// Attribut de sélection de l'élément focalisé (AXSelectedText / UIA TextPattern)
+    Accessibility,
+    /// Cmd/Ctrl+C simulé, presse-papiers restauré ensuite
+    SyntheticCopy,
+    /// Sélection PRIMARY (X11 / Wayland)
+    PrimarySelection,
+}
+
+impl SelectionMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SelectionMethod::Accessibility => "accessibility",
+            SelectionMethod::SyntheticCopy => "synthetic_copy",
+            SelectionMethod::PrimarySelection => "primary_selection",
+        }
+    }
+
+    /// Fiabilité de la lecture : PRIMARY peut provenir d'une autre fenêtre que la fenêtre active
+    pub fn reliability(&self) -> f64 {
+        match self {
+            SelectionMethod::Accessibility | SelectionMethod::SyntheticCopy => 1.0,
+            SelectionMethod::PrimarySelection => 0.6,
+        }
+    }
+}
+
+/// Texte sélectionné et méthode de lecture
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelectionResult {
+    pub text: String,
+    pub method: SelectionMethod,
+}
+
+impl SelectionExtractor {
+    /// Crée un nouveau extracteur de sélection
+    pub fn new() -> Self {
+        Self {
+            timeout: DEFAULT_SELECTION_TIMEOUT,
+            last_primary: Mutex::new(None),
+        }
+    }
+
+    /// Configure le timeout de chaque lecture (processus tué à l'expiration)
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Lit la sélection de la fenêtre active ; `None` si aucun texte n'est sélectionné
+    /// ou si aucune méthode n'est disponible. L'accessibilité est essayée d'abord, la copie
+    /// synthétique ensuite (elle suppose l'application cible au premier plan).
+    pub async fn extract_selection(&self, window: &WindowInfo) -> Option<SelectionResult> {
+        let attempts: Vec<(SelectionMethod, &str, Vec<String>)> = if cfg!(target_os = "macos") {
+            vec![
+                (SelectionMethod::Accessibility, "osascript", vec!["-e".to_string(), Self::macos_accessibility_script(window.pid)]),
+                (SelectionMethod::SyntheticCopy, "osascript", vec!["-e".to_string(), MACOS_SYNTHETIC_COPY_SCRIPT.to_string()]),
+            ]
+        } else if cfg!(target_os = "windows") {
+            vec![
+                (SelectionMethod::Accessibility, "powershell", vec!["-Command".to_string(), WINDOWS_ACCESSIBILITY_SCRIPT.to_string()]),
+                (SelectionMethod::SyntheticCopy, "powershell", vec!["-Command".to_string(), WINDOWS_SYNTHETIC_COPY_SCRIPT.to_string()]),
+            ]
+        } else {
+            vec![
+                (SelectionMethod::PrimarySelection, "xclip", vec!["-o".to_string(), "-selection".to_string(), "primary".to_string()]),
+                (SelectionMethod::PrimarySelection, "wl-paste", vec!["--primary".to_string(), "--no-newline".to_string()]),
+            ]
+        };
+
+        for (method, program, args) in attempts {
+            match self.run_command(program, &args).await {
+                Ok(text) if method == SelectionMethod::PrimarySelection && !self.is_fresh_primary(&text) => {
+                    tracing::debug!("PRIMARY selection unchanged since last read, treated as stale");
+                    return None;
+                },
+                Ok(text) if !text.trim().is_empty() => {
+                    tracing::info!("Selection read via {} for {}: {} characters",
+                                   method.as_str(), window.app, text.len());
+                    return Some(SelectionResult { text, method });
+                },
+                Ok(_) => tracing::debug!("No selection via {}", method.as_str()),
+                Err(e) => tracing::debug!("Selection read via {} failed: {}", method.as_str(), e),
+            }
+        }
+        None
+    }
+
+    /// Mémorise la lecture PRIMARY ; faux si elle est vide ou identique à la précédente
+    fn is_fresh_primary(&self, text: &str) -> bool {
+        if text.trim().is_empty() {
+            return true;
+        }
+        let mut last = self.last_primary.lock().unwrap_or_else(|e| e.into_inner());
+        if last.as_deref() == Some(text) {
+            return false;
+        }
+        *last = Some(text.to_string());
+        true
+    }
+
+    /// Exécute une commande avec timeout, sortie standard sans le saut de ligne final
+    async fn run_command(&self, program: &str, args: &[String]) -> Result<String, AWCSError> {
+        let child = tokio::process::Command::new(program)
+            .args(args)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| AWCSError::ScriptFailed(format!("{} execution failed: {}", program, e)))?;
+
+        let output = tokio::time::timeout(self.timeout, child.wait_with_output()).await
+            .map_err(|_| AWCSError::ScriptFailed(format!("{} timed out after {}ms", program, self.timeout.as_millis())))?
+            .map_err(|e| AWCSError::ScriptFailed(format!("{} execution failed: {}", program, e)))?;
+
+        if !output.status.success() {
+            return Err(AWCSError::ScriptFailed(
+                format!("{} error: {}", program, String::from_utf8_lossy(&output.stderr).trim())
+            ));
+        }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(text.trim_end_matches(&['\r', '\n'][..]).to_string())
+    }
+
+    /// Script AX : attribut AXSelectedText de l'élément focalisé du processus cible
+    fn macos_accessibility_script(pid: u32) -> String {
+        format!(r#"
+        try
+            tell application "System Events"
+                set targetProcess to first process whose unix id is {}
+                set focusedElement to value of attribute "AXFocusedUIElement" of targetProcess
+                set selectedText to value of attribute "AXSelectedText" of focusedElement
+                if selectedText is missing value then return ""
+                return selectedText
+            end tell
+        on error
+            return ""
+        end try
+        "#, pid)
+    }
+}
+
+/// Copie synthétique macOS : presse-papiers vidé, Cmd+C, lecture puis restauration.
+/// Refusée si le presse-papiers contient autre chose que du texte (image, fichiers, RTF) :
+/// AppleScript ne restaure qu'une représentation et le reste serait perdu.
+const MACOS_SYNTHETIC_COPY_SCRIPT: &str = r#"
+set textClasses to {string, Unicode text, «class utf8», «class ut16»}
+repeat with clipboardEntry in (clipboard info)
+    if textClasses does not contain (item 1 of clipboardEntry) then
+        error "clipboard holds non-text content" number 1
+    end if
+end repeat
+try
+    set previousClipboard to the clipboard as text
+on error
+    set previousClipboard to ""
+end try
+set the clipboard to ""
+tell application "System Events" to keystroke "c" using command down
+delay 0.15
+try
+    set copiedText to the clipboard as text
+on error
+    set copiedText to ""
+end try
+set the clipboard to previousClipboard
+return copiedText
+"#;
+
+/// UI Automation : plages sélectionnées du TextPattern de l'élément focalisé
+const WINDOWS_ACCESSIBILITY_SCRIPT: &str = r#"
+Add-Type -AssemblyName UIAutomationClient
+Add-Type -AssemblyName UIAutomationTypes
+try {
+    $focused = [System.Windows.Automation.AutomationElement]::FocusedElement
+    $pattern = $null
+    if ($focused.TryGetCurrentPattern([System.Windows.Automation.TextPattern]::Pattern, [ref]$pattern)) {
+        ($pattern.GetSelection() | ForEach-Object { $_.GetText(-1) }) -join "`n"
+    }
+} catch {
+    Write-Output ""
+}
+"#;
+
+/// Copie synthétique Windows : Ctrl+C envoyé à la fenêtre au premier plan, presse-papiers restauré.
+/// Refusée si le presse-papiers contient un format non textuel (seul le texte est restauré).
+const WINDOWS_SYNTHETIC_COPY_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Windows.Forms
+$data = [System.Windows.Forms.Clipboard]::GetDataObject()
+if ($data) {
+    $textFormats = @('Text', 'UnicodeText', 'OEMText', 'System.String', 'Locale')
+    $nonText = $data.GetFormats() | Where-Object { $textFormats -notcontains $_ }
+    if ($nonText) {
+        [Console]::Error.WriteLine("clipboard holds non-text content")
+        exit 1
+    }
+}
+$previous = Get-Clipboard -Raw
+[System.Windows.Forms.Clipboard]::Clear()
+[System.Windows.Forms.SendKeys]::SendWait("^c")
+Start-Sleep -Milliseconds 150
+$copied = Get-Clipboard -Raw
+if ($previous) { Set-Clipboard -Value $previous } else { [System.Windows.Forms.Clipboard]::Clear() }
+Write-Output $copied
+"#;
+
+impl Default for SelectionExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unchanged_primary_selection_is_stale() {
+        let extractor = SelectionExtractor::new();
+
+        assert!(extractor.is_fresh_primary("premier passage"));
+        assert!(!extractor.is_fresh_primary("premier passage"), "Lecture identique = sélection périmée");
+        assert!(extractor.is_fresh_primary("second passage"));
+        assert!(extractor.is_fresh_primary(""), "Une lecture vide ne remplace pas la précédente");
+        assert!(!extractor.is_fresh_primary("second passage"));
+    }
+
+    #[test]
+    fn test_primary_selection_is_less_reliable() {
+        assert_eq!(SelectionMethod::Accessibility.reliability(), 1.0);
+        assert_eq!(SelectionMethod::SyntheticCopy.reliability(), 1.0);
+        assert!(SelectionMethod::PrimarySelection.reliability() < 1.0);
+    }
+
+    #[test]
+    fn test_synthetic_copy_refuses_non_text_clipboard() {
+        // Vérification du contenu avant de vider le presse-papiers
+        let macos_guard = MACOS_SYNTHETIC_COPY_SCRIPT.find("clipboard info").unwrap();
+        assert!(macos_guard < MACOS_SYNTHETIC_COPY_SCRIPT.find("set the clipboard to \"\"").unwrap());
+
+        let windows_guard = WINDOWS_SYNTHETIC_COPY_SCRIPT.find("GetFormats()").unwrap();
+        assert!(windows_guard < WINDOWS_SYNTHETIC_COPY_SCRIPT.find("Clipboard]::Clear()").unwrap());
+    }
+}
@@ -31,6 +31,7 @@ use tokio::sync::RwLock;
 pub struct AWCSState {
     manager: Arc<RwLock<AWCSManager>>,
     activation_state: Arc<RwLock<AWCSActivationState>>,
+    window_watcher: Arc<RwLock<core::WindowWatcher>>,
 }
 
 impl AWCSState {
@@ -41,6 +42,7 @@ impl AWCSState {
         Self {
             manager: Arc::new(RwLock::new(AWCSManager::new())),
             activation_state: Arc::new(RwLock::new(AWCSActivationState::Disabled)),
+            window_watcher: Arc::new(RwLock::new(core::WindowWatcher::new())),
         }
     }
     
@@ -49,6 +51,11 @@ impl AWCSState {
         self.manager.clone()
     }
     
+    /// Accès au watcher de fenêtre active
+    pub fn window_watcher(&self) -> Arc<RwLock<core::WindowWatcher>> {
+        self.window_watcher.clone()
+    }
+    
     /// Accès à l'état d'activation
     pub fn activation_state(&self) -> Arc<RwLock<AWCSActivationState>> {
         self.activation_state.clone()
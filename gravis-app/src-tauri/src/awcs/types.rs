@@ -49,6 +49,17 @@ pub struct ContentData {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Nature du contenu extrait (heuristiques partagées avec le RAG)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Code,
+    #[default]
+    Prose,
+    Table,
+    Mixed,
+}
+
 /// Confidence de l'extraction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -56,6 +67,81 @@ pub struct ExtractionConfidence {
     pub text_completeness: f64,
     pub source_reliability: f64,
     pub extraction_method: String,
+    /// Étapes de la chaîne abandonnées avant la méthode retenue (ex: "applescript: timed out after 3000ms")
+    #[serde(default)]
+    pub degraded_steps: Vec<String>,
+    /// Scores des méthodes candidates, de la meilleure (retenue) aux suivantes
+    #[serde(default)]
+    pub method_scores: Vec<MethodScore>,
+}
+
+/// Score heuristique d'une méthode d'extraction candidate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodScore {
+    pub method: String,
+    pub score: f64,
+}
+
+/// Étapes de la chaîne d'extraction, dans l'ordre de fallback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractionStep {
+    Dom,
+    AppleScript,
+    Accessibility,
+    Ocr,
+}
+
+impl ExtractionStep {
+    /// Chaîne de fallback explicite : DOM → AppleScript → Accessibility → OCR
+    pub const CHAIN: [ExtractionStep; 4] = [
+        ExtractionStep::Dom,
+        ExtractionStep::AppleScript,
+        ExtractionStep::Accessibility,
+        ExtractionStep::Ocr,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExtractionStep::Dom => "dom",
+            ExtractionStep::AppleScript => "applescript",
+            ExtractionStep::Accessibility => "accessibility",
+            ExtractionStep::Ocr => "ocr",
+        }
+    }
+}
+
+/// Timeout de chaque étape de la chaîne d'extraction (millisecondes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionTimeouts {
+    pub dom_ms: u64,
+    pub applescript_ms: u64,
+    pub accessibility_ms: u64,
+    pub ocr_ms: u64,
+}
+
+impl Default for ExtractionTimeouts {
+    fn default() -> Self {
+        Self {
+            dom_ms: 800,
+            applescript_ms: 3000, // Une app qui ne répond pas bloque osascript
+            accessibility_ms: 800,
+            ocr_ms: 10_000,
+        }
+    }
+}
+
+impl ExtractionTimeouts {
+    pub fn for_step(&self, step: ExtractionStep) -> std::time::Duration {
+        let ms = match step {
+            ExtractionStep::Dom => self.dom_ms,
+            ExtractionStep::AppleScript => self.applescript_ms,
+            ExtractionStep::Accessibility => self.accessibility_ms,
+            ExtractionStep::Ocr => self.ocr_ms,
+        };
+        std::time::Duration::from_millis(ms)
+    }
 }
 
 /// Drapeaux de sécurité
@@ -77,6 +163,9 @@ pub struct ContextEnvelope {
     pub confidence: ExtractionConfidence,
     pub timestamp: DateTime<Utc>,
     pub security_flags: Option<SecurityFlags>,
+    /// Classification code / prose / tableau du texte extrait
+    #[serde(default)]
+    pub content_kind: ContentKind,
 }
 
 /// Type d'intention utilisateur
@@ -150,6 +239,8 @@ pub struct SelectionCoordinates {
     pub y: i32,
     pub width: i32,
     pub height: i32,
+    #[serde(default)]
+    pub display_index: Option<usize>, // Coordonnées relatives à cet écran si défini
 }
 
 /// Erreurs AWCS spécialisées
@@ -176,6 +267,9 @@ pub enum AWCSError {
     #[error("OCR processing failed: {0}")]
     OCRFailed(String),
     
+    #[error(transparent)]
+    Ocr(#[from] crate::rag::ocr::OcrError),
+    
     #[error("Screen capture failed: {0}")]
     ScreenCaptureError(String),
     
@@ -192,6 +286,14 @@ pub struct AWCSConfig {
     pub enabled: bool,
     pub global_shortcut: String,
     pub extraction_timeout: u64,
+    /// Timeouts par étape de la chaîne DOM → AppleScript → Accessibility → OCR
+    #[serde(default)]
+    pub step_timeouts: ExtractionTimeouts,
+    /// Durée de réutilisation du dernier contexte si la fenêtre (titre, géométrie) est inchangée.
+    /// 0 = désactivé (défaut) : la clé ne voit ni défilement ni édition dans la même fenêtre,
+    /// le cache est donc réservé aux appels rapprochés qui acceptent un contexte légèrement ancien.
+    #[serde(default = "default_context_cache_ttl_ms")]
+    pub context_cache_ttl_ms: u64,
     pub max_content_length: usize,
     pub pii_redaction_enabled: bool,
     pub allowed_apps: Vec<String>,
@@ -199,6 +301,10 @@ pub struct AWCSConfig {
     pub security_mode: SecurityMode,
 }
 
+fn default_context_cache_ttl_ms() -> u64 {
+    0
+}
+
 /// Mode de sécurité
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SecurityMode {
@@ -211,8 +317,10 @@ impl Default for AWCSConfig {
     fn default() -> Self {
         Self {
             enabled: false,
-            global_shortcut: "Cmd+Shift+Control+L".to_string(),
+            global_shortcut: crate::awcs::core::global_shortcuts::DEFAULT_GLOBAL_SHORTCUT.to_string(),
             extraction_timeout: 5000, // 5 secondes
+            step_timeouts: ExtractionTimeouts::default(),
+            context_cache_ttl_ms: default_context_cache_ttl_ms(),
             max_content_length: 100_000, // 100k caractères
             pii_redaction_enabled: true,
             allowed_apps: vec![
@@ -234,21 +342,49 @@ impl Default for AWCSConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AWCSMetrics {
     pub extractions_total: u64,
+    /// Contextes servis depuis le cache (fenêtre et écran inchangés)
+    #[serde(default)]
+    pub context_cache_hits: u64,
     pub extraction_success_rate: f64,
+    /// Extractions dont la complétude dépasse 50%
+    #[serde(default)]
+    pub successful_extractions: u64,
+    /// Extractions en erreur ou de complétude insuffisante
+    #[serde(default)]
+    pub failed_extractions: u64,
     pub avg_extraction_time: f64,
     pub method_distribution: HashMap<String, u64>,
+    /// Nombre d'extractions et temps moyen par méthode retenue (dom, applescript, accessibility, ocr...)
+    #[serde(default)]
+    pub method_metrics: HashMap<String, MethodMetrics>,
+    /// Confiance OCR par tranches de 20% : [0-20%, 20-40%, 40-60%, 60-80%, 80-100%]
+    #[serde(default)]
+    pub ocr_confidence_distribution: [u64; 5],
     pub app_compatibility: HashMap<String, f64>,
     pub intention_accuracy: f64,
     pub user_satisfaction: Option<f64>,
 }
 
+/// Compteurs d'une méthode d'extraction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MethodMetrics {
+    pub count: u64,
+    pub total_time_ms: f64,
+    pub avg_time_ms: f64,
+}
+
 impl Default for AWCSMetrics {
     fn default() -> Self {
         Self {
             extractions_total: 0,
+            context_cache_hits: 0,
             extraction_success_rate: 0.0,
+            successful_extractions: 0,
+            failed_extractions: 0,
             avg_extraction_time: 0.0,
             method_distribution: HashMap::new(),
+            method_metrics: HashMap::new(),
+            ocr_confidence_distribution: [0; 5],
             app_compatibility: HashMap::new(),
             intention_accuracy: 0.0,
             user_satisfaction: None,
@@ -2,6 +2,9 @@
 // Fonctions utilitaires pour AWCS
 
 use crate::awcs::types::*;
+use crate::rag::processing::document_classifier::TechnicalPatternMatcher;
+use crate::rag::processing::table_detector::find_table_regions;
+use once_cell::sync::Lazy;
 
 /// Validation des paramètres AWCS
 pub struct AWCSValidator;
@@ -45,6 +48,131 @@ impl SafeConverter {
     }
 }
 
+/// Qualité heuristique d'un texte extrait, pour départager les extracteurs
+pub struct TextQuality;
+
+impl TextQuality {
+    /// Nombre de mots à partir duquel la longueur ne rapporte plus
+    const FULL_LENGTH_WORDS: f64 = 150.0;
+
+    /// Score 0.0-1.0 : longueur (40%), part de caractères non parasites (30%),
+    /// part de tokens ressemblant à des mots (30%)
+    pub fn score(text: &str) -> f64 {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let total_chars = text.chars().filter(|c| !c.is_whitespace()).count();
+        if words.is_empty() || total_chars == 0 {
+            return 0.0;
+        }
+
+        let length_score = (words.len() as f64 / Self::FULL_LENGTH_WORDS).min(1.0);
+
+        let clean_chars = text.chars()
+            .filter(|c| !c.is_whitespace())
+            .filter(|c| c.is_alphanumeric() || ".,;:!?'\"()-€$%/".contains(*c))
+            .count();
+        let non_noise_ratio = clean_chars as f64 / total_chars as f64;
+
+        let coherent_words = words.iter().filter(|word| Self::is_word_like(word)).count();
+        let coherence_ratio = coherent_words as f64 / words.len() as f64;
+
+        0.4 * length_score + 0.3 * non_noise_ratio + 0.3 * coherence_ratio
+    }
+
+    /// Token alphabétique de longueur plausible contenant une voyelle (ou nombre)
+    fn is_word_like(token: &str) -> bool {
+        let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+        if word.is_empty() {
+            return false;
+        }
+        if word.chars().all(|c| c.is_ascii_digit()) {
+            return true;
+        }
+        let length = word.chars().count();
+        (1..=25).contains(&length)
+            && word.chars().all(|c| c.is_alphabetic() || c == '\'' || c == '-')
+            && word.to_lowercase().chars().any(|c| "aeiouyàâäéèêëîïôöùûü".contains(c))
+    }
+}
+
+/// Classification code / prose / tableau d'un texte extrait
+pub struct ContentClassifier;
+
+/// Motifs de code du RAG (`TechnicalPatternMatcher`)
+static CODE_PATTERNS: Lazy<TechnicalPatternMatcher> = Lazy::new(TechnicalPatternMatcher::new);
+
+impl ContentClassifier {
+    /// Part de lignes à partir de laquelle une nature domine le texte
+    const DOMINANT_RATIO: f64 = 0.5;
+    /// Part de lignes en dessous de laquelle une nature est négligeable
+    const MINOR_RATIO: f64 = 0.15;
+
+    /// Classe le texte d'après la part de lignes de code et la part de lignes
+    /// couvertes par un tableau (`find_table_regions`, comme le chunking RAG)
+    pub fn classify(text: &str) -> ContentKind {
+        let lines: Vec<(usize, &str)> = text.split('\n')
+            .scan(0, |offset, line| {
+                let start = *offset;
+                *offset += line.len() + 1;
+                Some((start, line))
+            })
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect();
+        if lines.is_empty() {
+            return ContentKind::Prose;
+        }
+
+        let table_regions = find_table_regions(text);
+        let mut code_lines = 0;
+        let mut table_lines = 0;
+        for (offset, line) in &lines {
+            if Self::is_code_line(line) {
+                code_lines += 1;
+            } else if table_regions.iter().any(|(start, end)| offset >= start && offset < end) {
+                table_lines += 1;
+            }
+        }
+
+        let code_ratio = code_lines as f64 / lines.len() as f64;
+        let table_ratio = table_lines as f64 / lines.len() as f64;
+        if code_ratio >= Self::DOMINANT_RATIO {
+            ContentKind::Code
+        } else if table_ratio >= Self::DOMINANT_RATIO {
+            ContentKind::Table
+        } else if code_ratio < Self::MINOR_RATIO && table_ratio < Self::MINOR_RATIO {
+            ContentKind::Prose
+        } else {
+            ContentKind::Mixed
+        }
+    }
+
+    /// Ligne de code : un indice de structure (indentation, accolade ou `;`/`:` final, commentaire)
+    /// ET un indice lexical (mot-clé en tête, opérateur, motif RAG). Un seul indice ne suffit pas :
+    /// une phrase finissant par ";" ou citant "class of" reste de la prose. Une ligne réduite
+    /// à des délimiteurs ("}", "});") est du code.
+    fn is_code_line(line: &str) -> bool {
+        const KEYWORDS: [&str; 18] = [
+            "fn ", "pub ", "let ", "const ", "def ", "class ", "function ", "import ", "return ",
+            "if ", "if(", "for ", "for(", "while ", "struct ", "impl ", "use ", "#include",
+        ];
+        const OPERATORS: [&str; 11] = [" => ", "()", "::", " == ", " != ", " = ", " += ", " -= ", "->", " && ", " || "];
+
+        let trimmed = line.trim();
+        if trimmed.chars().all(|c| "{}()[];,".contains(c)) {
+            return !trimmed.is_empty();
+        }
+
+        let structural = line.starts_with("    ")
+            || line.starts_with('\t')
+            || trimmed.ends_with(&['{', '}', ';', ':'][..])
+            || trimmed.starts_with("//")
+            || trimmed.starts_with("#include");
+        let lexical = KEYWORDS.iter().any(|keyword| trimmed.starts_with(keyword))
+            || OPERATORS.iter().any(|operator| trimmed.contains(operator))
+            || !CODE_PATTERNS.detect_code(trimmed).is_empty();
+        structural && lexical
+    }
+}
+
 /// Formatage et nettoyage de texte
 pub struct TextCleaner;
 
@@ -74,4 +202,41 @@ impl TextCleaner {
         
         cleaned
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_code() {
+        let rust = "use std::fmt;\n\npub struct Point {\n    x: i32,\n}\n\nimpl Point {\n    pub fn norm(&self) -> i32 {\n        self.x.abs()\n    }\n}";
+        assert_eq!(ContentClassifier::classify(rust), ContentKind::Code);
+
+        let python = "def total(items):\n    result = 0\n    for item in items:\n        result += item.price\n    return result";
+        assert_eq!(ContentClassifier::classify(python), ContentKind::Code);
+    }
+
+    #[test]
+    fn test_classify_prose_with_isolated_code_signals() {
+        // Point-virgule final ou "class of" : un seul indice par ligne, pas du code
+        let prose = "La fonction principale du service est de classer les demandes ;\n\
+                     cette class of service dépend du contrat signé en 2024.\n\
+                     pour chaque demande, le conseiller vérifie le dossier puis répond.\n\
+                     Le rapport final sera publié le mois prochain.";
+        assert_eq!(ContentClassifier::classify(prose), ContentKind::Prose);
+        assert!(!ContentClassifier::is_code_line("Voici la liste des participants :"));
+        assert!(ContentClassifier::is_code_line("});"));
+    }
+
+    #[test]
+    fn test_classify_mixed() {
+        let mixed = "Pour calculer le total, appelez la fonction ci-dessous.\n\
+                     Elle parcourt les lignes de la facture une par une.\n\
+                     Le résultat est arrondi au centime près.\n\
+                     Les remises sont appliquées avant les taxes.\n\
+                     let total = lines.iter().map(|line| line.amount).sum::<f64>();\n\
+                     return total;";
+        assert_eq!(ContentClassifier::classify(mixed), ContentKind::Mixed);
+    }
+}
@@ -0,0 +1,170 @@
+// GRAVIS OCR - CLI d'extraction
+// Utilise le pipeline réel de la bibliothèque (TesseractProcessor / LopdFProcessor) :
+// OCR par lots depuis des scripts et smoke-test CI du vrai chemin de code
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::json;
+
+use gravis_app_lib::rag::ocr::{
+    detect_file_format, hocr_document, FileFormat, LopdFPipelineConfig, LopdFProcessor,
+    OcrResult, TesseractConfig, TesseractProcessor,
+};
+
+#[derive(Parser)]
+#[command(name = "gravis-ocr", version, about = "Extraction OCR avec le pipeline GRAVIS")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Extraire le texte d'images ou de PDF
+    Extract {
+        /// Fichiers à traiter (PDF, PNG, JPEG, TIFF, BMP)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Langues Tesseract séparées par '+'
+        #[arg(long, default_value = "eng+fra")]
+        lang: String,
+
+        /// Format de sortie (json : une ligne JSON par fichier)
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Désactiver le preprocessing d'image (contraste, redressement, binarisation)
+        #[arg(long)]
+        no_preprocess: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Hocr,
+    Json,
+}
+
+/// Résultat d'extraction d'une page (image = une page)
+struct PageOutput {
+    page_number: usize,
+    text: String,
+    /// `None` : texte natif PDF, pas d'OCR
+    ocr: Option<OcrResult>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // Logs sur stderr : stdout reste exploitable par les scripts
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "warn".into()))
+        .with_writer(std::io::stderr)
+        .init();
+
+    let Command::Extract { files, lang, format, no_preprocess } = Cli::parse().command;
+
+    let mut tesseract_config = TesseractConfig {
+        languages: lang.split('+').map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect(),
+        ..TesseractConfig::default()
+    };
+    tesseract_config.preprocessing.enabled = !no_preprocess;
+
+    let mut failures = 0;
+    let mut empty_pages = 0;
+    for (index, file) in files.iter().enumerate() {
+        match extract_file(file, &tesseract_config).await {
+            Ok(pages) => {
+                print_pages(file, &pages, format, files.len() > 1, index == 0);
+                // Page sans aucun texte (ni natif ni OCR) : échec d'extraction, pas un succès silencieux
+                let empty = pages.iter().filter(|page| page.text.trim().is_empty()).count();
+                if empty > 0 {
+                    eprintln!("❌ {}: {}/{} pages without text", file.display(), empty, pages.len());
+                    empty_pages += empty;
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ {}: {:#}", file.display(), e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{}/{} files failed", failures, files.len());
+    }
+    if empty_pages > 0 {
+        eprintln!("{} pages without text", empty_pages);
+    }
+    if failures > 0 || empty_pages > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+async fn extract_file(path: &Path, tesseract_config: &TesseractConfig) -> anyhow::Result<Vec<PageOutput>> {
+    if !path.exists() {
+        anyhow::bail!("file not found");
+    }
+
+    match detect_file_format(path)? {
+        FileFormat::Pdf => {
+            let mut pipeline_config = LopdFPipelineConfig::default();
+            pipeline_config.tesseract_config.languages = tesseract_config.languages.clone();
+            pipeline_config.tesseract_config.preprocessing.enabled = tesseract_config.preprocessing.enabled;
+
+            let processor = LopdFProcessor::new(pipeline_config).await?;
+            let pages = processor.process_pdf(path).await?;
+            Ok(pages.into_iter().map(|page| {
+                // OCR vide : le texte natif fait foi et la page est rapportée comme native
+                let ocr = page.ocr_result.filter(|ocr| !ocr.text.trim().is_empty());
+                PageOutput {
+                    page_number: page.page_number,
+                    text: ocr.as_ref().map(|ocr| ocr.text.clone()).unwrap_or(page.native_text),
+                    ocr,
+                }
+            }).collect())
+        }
+        FileFormat::Png | FileFormat::Jpeg | FileFormat::Tiff | FileFormat::Bmp => {
+            let processor = TesseractProcessor::new(tesseract_config.clone()).await?;
+            let result = processor.process_image(path).await?;
+            Ok(vec![PageOutput { page_number: 1, text: result.text.clone(), ocr: Some(result) }])
+        }
+    }
+}
+
+fn print_pages(path: &Path, pages: &[PageOutput], format: OutputFormat, multiple_files: bool, first_file: bool) {
+    match format {
+        OutputFormat::Text => {
+            if multiple_files {
+                if !first_file {
+                    println!();
+                }
+                println!("==> {} <==", path.display());
+            }
+            let text: Vec<&str> = pages.iter().map(|page| page.text.trim_end()).collect();
+            println!("{}", text.join("\n\n"));
+        }
+        OutputFormat::Hocr => {
+            // Pages en texte natif : pas de mots localisés, ignorées en hOCR
+            let hocr_pages: Vec<String> = pages.iter()
+                .filter_map(|page| page.ocr.as_ref().map(|ocr| ocr.to_hocr_page(page.page_number)))
+                .collect();
+            print!("{}", hocr_document(&hocr_pages));
+        }
+        OutputFormat::Json => {
+            let pages: Vec<serde_json::Value> = pages.iter().map(|page| json!({
+                "page_number": page.page_number,
+                "text": page.text,
+                "method": if page.ocr.is_some() { "ocr" } else { "native" },
+                "confidence": page.ocr.as_ref().map(|ocr| ocr.confidence),
+                "words": page.ocr.as_ref().map(|ocr| &ocr.bounding_boxes),
+            })).collect();
+            println!("{}", json!({ "file": path.display().to_string(), "pages": pages }));
+        }
+    }
+}
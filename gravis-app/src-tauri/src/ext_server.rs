@@ -441,8 +441,235 @@ async fn handle_extension_trigger(
     }))
 }
 
-/// Démarrer le serveur extension HTTP local
-pub async fn start_extension_server(app: AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+/// Hôte et port d'écoute par défaut (boucle locale uniquement)
+const DEFAULT_EXT_SERVER_HOST: &str = "127.0.0.1";
+const DEFAULT_EXT_SERVER_PORT: u16 = 8766;
+/// Ports suivants essayés quand le port configuré est occupé (autre instance GRAVIS...)
+const DEFAULT_PORT_FALLBACK_ATTEMPTS: u16 = 10;
+/// Variable d'environnement contrôlant le démarrage automatique (`GRAVIS_EXT_SERVER=0` le désactive)
+pub const EXT_SERVER_ENV: &str = "GRAVIS_EXT_SERVER";
+/// Variables d'environnement de l'adresse d'écoute
+pub const EXT_SERVER_HOST_ENV: &str = "GRAVIS_EXT_SERVER_HOST";
+pub const EXT_SERVER_PORT_ENV: &str = "GRAVIS_EXT_SERVER_PORT";
+
+/// Adresse effectivement liée, lue par les appelants internes (déclenchement AWCS)
+static BOUND_ADDR: Mutex<Option<SocketAddr>> = Mutex::new(None);
+
+/// URL de base du serveur extension s'il écoute
+pub fn ext_server_base_url() -> Option<String> {
+    BOUND_ADDR.lock().ok()?.map(|addr| format!("http://{}", addr))
+}
+
+fn set_bound_addr(addr: Option<SocketAddr>) {
+    if let Ok(mut bound) = BOUND_ADDR.lock() {
+        *bound = addr;
+    }
+}
+
+/// Adresse d'écoute du serveur extension
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtServerConfig {
+    pub host: String,
+    pub port: u16,
+    /// Ports suivants essayés si `port` est occupé (0 : échec immédiat)
+    pub port_fallback_attempts: u16,
+}
+
+impl Default for ExtServerConfig {
+    fn default() -> Self {
+        Self {
+            host: DEFAULT_EXT_SERVER_HOST.to_string(),
+            port: DEFAULT_EXT_SERVER_PORT,
+            port_fallback_attempts: DEFAULT_PORT_FALLBACK_ATTEMPTS,
+        }
+    }
+}
+
+impl ExtServerConfig {
+    /// Configuration par défaut surchargée par `GRAVIS_EXT_SERVER_HOST` / `GRAVIS_EXT_SERVER_PORT`
+    pub fn from_env() -> Self {
+        Self::from_values(
+            std::env::var(EXT_SERVER_HOST_ENV).ok().as_deref(),
+            std::env::var(EXT_SERVER_PORT_ENV).ok().as_deref(),
+        )
+    }
+
+    fn from_values(host: Option<&str>, port: Option<&str>) -> Self {
+        let mut config = Self::default();
+        if let Some(host) = host.map(str::trim).filter(|host| !host.is_empty()) {
+            config.host = host.to_string();
+        }
+        if let Some(port) = port {
+            match port.trim().parse::<u16>() {
+                Ok(port) if port > 0 => config.port = port,
+                _ => tracing::warn!("Invalid {}={:?}, using port {}", EXT_SERVER_PORT_ENV, port, config.port),
+            }
+        }
+        config
+    }
+
+    /// Refuser une adresse hors boucle locale, sauf si elle est celle de `GRAVIS_EXT_SERVER_HOST`
+    /// (l'exposition réseau reste un choix explicite de l'utilisateur, pas du frontend)
+    fn ensure_host_allowed(&self, opted_in_host: Option<&str>) -> Result<(), String> {
+        let host = self.host.trim();
+        let loopback = host.eq_ignore_ascii_case("localhost")
+            || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback());
+        if loopback || opted_in_host.map(str::trim) == Some(host) {
+            return Ok(());
+        }
+        Err(format!(
+            "Refusing to expose the extension server on {}: only loopback addresses are allowed unless {}={} is set",
+            host, EXT_SERVER_HOST_ENV, host
+        ))
+    }
+}
+/// Délai d'arrêt gracieux avant abandon de la tâche serveur
+const EXT_SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Vrai sauf si `GRAVIS_EXT_SERVER` vaut 0/false/off/no
+pub fn ext_server_enabled_by_env() -> bool {
+    is_enabled_value(std::env::var(EXT_SERVER_ENV).ok().as_deref())
+}
+
+fn is_enabled_value(value: Option<&str>) -> bool {
+    !matches!(
+        value.map(|v| v.trim().to_ascii_lowercase()).as_deref(),
+        Some("0" | "false" | "off" | "no")
+    )
+}
+
+/// Lier le premier port libre à partir de `config.port` ; les autres erreurs sont remontées telles quelles
+async fn bind_with_fallback(config: &ExtServerConfig) -> Result<tokio::net::TcpListener, String> {
+    let last_port = config.port.saturating_add(config.port_fallback_attempts);
+    for port in config.port..=last_port {
+        match tokio::net::TcpListener::bind((config.host.as_str(), port)).await {
+            Ok(listener) => {
+                if port != config.port {
+                    tracing::warn!("⚠️ Port {} already in use, extension server bound to port {}", config.port, port);
+                }
+                return Ok(listener);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                tracing::debug!("Extension server port {}:{} in use", config.host, port);
+            }
+            Err(e) => return Err(format!("Failed to bind extension server on {}:{}: {}", config.host, port, e)),
+        }
+    }
+
+    Err(if last_port == config.port {
+        format!("Port {} on {} is already in use (another GRAVIS instance?)", config.port, config.host)
+    } else {
+        format!("Ports {}-{} on {} are all in use (another GRAVIS instance?)", config.port, last_port, config.host)
+    })
+}
+
+/// Serveur en cours d'exécution
+struct RunningServer {
+    addr: SocketAddr,
+    started_at_ms: u64,
+    shutdown: tokio::sync::oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+/// État du serveur extension, piloté par `ext_server_start`/`ext_server_stop`
+pub struct ExtServerState {
+    running: tokio::sync::Mutex<Option<RunningServer>>,
+    config: tokio::sync::Mutex<ExtServerConfig>,
+    /// Dernier échec de démarrage (port occupé, adresse invalide...)
+    last_error: tokio::sync::Mutex<Option<String>>,
+}
+
+impl Default for ExtServerState {
+    fn default() -> Self {
+        Self::new(ExtServerConfig::from_env())
+    }
+}
+
+/// Statut rapporté au frontend
+#[derive(Debug, Serialize)]
+pub struct ExtServerStatus {
+    pub listening: bool,
+    pub address: Option<String>,
+    pub port: Option<u16>,
+    pub started_at_ms: Option<u64>,
+    /// Démarrage automatique autorisé par `GRAVIS_EXT_SERVER`
+    pub enabled_at_startup: bool,
+    /// Adresse demandée (le port effectif peut différer si elle était occupée)
+    pub configured: ExtServerConfig,
+    pub last_error: Option<String>,
+}
+
+impl ExtServerState {
+    pub fn new(config: ExtServerConfig) -> Self {
+        Self {
+            running: tokio::sync::Mutex::new(None),
+            config: tokio::sync::Mutex::new(config),
+            last_error: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Démarrer le serveur (sans effet s'il écoute déjà). Une nouvelle `config` remplace la précédente.
+    pub async fn start(&self, app: AppHandle, config: Option<ExtServerConfig>) -> Result<ExtServerStatus, String> {
+        if let Some(config) = config {
+            config.ensure_host_allowed(std::env::var(EXT_SERVER_HOST_ENV).ok().as_deref())?;
+            *self.config.lock().await = config;
+        }
+        let config = self.config.lock().await.clone();
+
+        let mut running = self.running.lock().await;
+        if running.as_ref().is_some_and(|server| !server.task.is_finished()) {
+            tracing::info!("🌐 Extension server already listening");
+        } else {
+            match start_extension_server(app, &config).await {
+                Ok(server) => {
+                    set_bound_addr(Some(server.addr));
+                    *running = Some(server);
+                    *self.last_error.lock().await = None;
+                }
+                Err(e) => {
+                    *self.last_error.lock().await = Some(e.clone());
+                    return Err(e);
+                }
+            }
+        }
+        drop(running);
+        Ok(self.status().await)
+    }
+
+    /// Arrêter le serveur : plus aucune connexion acceptée, requêtes en cours terminées
+    pub async fn stop(&self) -> ExtServerStatus {
+        if let Some(server) = self.running.lock().await.take() {
+            let _ = server.shutdown.send(());
+            let mut task = server.task;
+            if tokio::time::timeout(EXT_SERVER_SHUTDOWN_TIMEOUT, &mut task).await.is_err() {
+                tracing::warn!("Extension server did not stop gracefully, aborting");
+                task.abort();
+            }
+            tracing::info!("🛑 Extension server on http://{} stopped", server.addr);
+            set_bound_addr(None);
+        }
+        self.status().await
+    }
+
+    pub async fn status(&self) -> ExtServerStatus {
+        let running = self.running.lock().await;
+        // Une tâche terminée (erreur du serveur) n'écoute plus
+        let server = running.as_ref().filter(|server| !server.task.is_finished());
+        ExtServerStatus {
+            listening: server.is_some(),
+            address: server.map(|server| server.addr.to_string()),
+            port: server.map(|server| server.addr.port()),
+            started_at_ms: server.map(|server| server.started_at_ms),
+            enabled_at_startup: ext_server_enabled_by_env(),
+            configured: self.config.lock().await.clone(),
+            last_error: self.last_error.lock().await.clone(),
+        }
+    }
+}
+
+/// Démarrer le serveur extension HTTP local (port lié avant le retour : une erreur de bind est remontée)
+async fn start_extension_server(app: AppHandle, config: &ExtServerConfig) -> Result<RunningServer, String> {
     let security = Arc::new(ExtensionSecurity::new());
     
     let router = Router::new()
@@ -466,27 +693,56 @@ pub async fn start_extension_server(app: AppHandle) -> Result<(), Box<dyn std::e
         }))
         .layer(create_cors_layer());
 
-    let addr: SocketAddr = "127.0.0.1:8766".parse()?;
-    tracing::info!("🌐 Extension API server starting on http://127.0.0.1:8766");
-    
-    tokio::spawn(async move {
-        let listener = match tokio::net::TcpListener::bind(&addr).await {
-            Ok(listener) => listener,
-            Err(e) => {
-                tracing::error!("Failed to bind extension server: {}", e);
-                return;
-            }
-        };
-        
+    let listener = bind_with_fallback(config).await?;
+    let addr = listener.local_addr().map_err(|e| format!("Extension server address unavailable: {}", e))?;
+    if !addr.ip().is_loopback() {
+        tracing::warn!("⚠️ Extension server exposed beyond localhost on {}", addr);
+    }
+    tracing::info!("🌐 Extension API server listening on http://{}", addr);
+
+    let (shutdown, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let task = tokio::spawn(async move {
         if let Err(e) = axum::serve(
             listener,
             router.into_make_service_with_connect_info::<SocketAddr>()
-        ).await {
+        )
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.await;
+        })
+        .await {
             tracing::error!("Extension server error: {}", e);
         }
     });
 
-    Ok(())
+    let started_at_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+
+    Ok(RunningServer { addr, started_at_ms, shutdown, task })
+}
+
+/// Statut du serveur extension : adresse, port et écoute effective
+#[tauri::command]
+pub async fn ext_server_status(state: tauri::State<'_, ExtServerState>) -> Result<ExtServerStatus, String> {
+    Ok(state.status().await)
+}
+
+/// Démarrer le serveur extension (même si `GRAVIS_EXT_SERVER=0` a empêché le démarrage automatique),
+/// éventuellement sur une autre adresse (boucle locale uniquement, sauf hôte de `GRAVIS_EXT_SERVER_HOST`)
+#[tauri::command]
+pub async fn ext_server_start(
+    config: Option<ExtServerConfig>,
+    app: AppHandle,
+    state: tauri::State<'_, ExtServerState>,
+) -> Result<ExtServerStatus, String> {
+    state.start(app, config).await
+}
+
+/// Arrêter le serveur extension
+#[tauri::command]
+pub async fn ext_server_stop(state: tauri::State<'_, ExtServerState>) -> Result<ExtServerStatus, String> {
+    Ok(state.stop().await)
 }
 
 /// Handler pour recevoir le contenu de l'extension (utilise body brut pour HMAC)
@@ -698,6 +954,62 @@ mod tests {
         assert!(!security.get_token().is_empty());
     }
 
+    #[test]
+    fn test_ext_server_env_gate() {
+        assert!(is_enabled_value(None));
+        assert!(is_enabled_value(Some("1")));
+        assert!(!is_enabled_value(Some("0")));
+        assert!(!is_enabled_value(Some(" False ")));
+        assert!(!is_enabled_value(Some("off")));
+    }
+
+    #[test]
+    fn test_ext_server_config_from_env_values() {
+        assert_eq!(ExtServerConfig::from_values(None, None), ExtServerConfig::default());
+
+        let config = ExtServerConfig::from_values(Some(" 0.0.0.0 "), Some("9000"));
+        assert_eq!((config.host.as_str(), config.port), ("0.0.0.0", 9000));
+
+        // Port invalide : valeur par défaut conservée
+        assert_eq!(ExtServerConfig::from_values(None, Some("http")).port, DEFAULT_EXT_SERVER_PORT);
+        assert_eq!(ExtServerConfig::from_values(None, Some("0")).port, DEFAULT_EXT_SERVER_PORT);
+    }
+
+    #[tokio::test]
+    async fn test_bind_falls_back_to_next_port() {
+        // Port éphémère laissant de la place pour les 5 ports de repli
+        let taken = loop {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            if listener.local_addr().unwrap().port() <= u16::MAX - 5 {
+                break listener;
+            }
+        };
+        let port = taken.local_addr().unwrap().port();
+
+        let strict = ExtServerConfig { host: "127.0.0.1".to_string(), port, port_fallback_attempts: 0 };
+        let error = bind_with_fallback(&strict).await.unwrap_err();
+        assert!(error.contains("already in use"), "{}", error);
+
+        let lenient = ExtServerConfig { port_fallback_attempts: 5, ..strict };
+        let listener = bind_with_fallback(&lenient).await.expect("One of the next ports should be free");
+        let bound_port = listener.local_addr().unwrap().port();
+        assert!(bound_port > port && bound_port <= port + 5, "port {} not in {}..={}", bound_port, port + 1, port + 5);
+    }
+
+    #[test]
+    fn test_non_loopback_host_requires_env_opt_in() {
+        let config = |host: &str| ExtServerConfig { host: host.to_string(), ..ExtServerConfig::default() };
+
+        assert!(config("127.0.0.1").ensure_host_allowed(None).is_ok());
+        assert!(config("::1").ensure_host_allowed(None).is_ok());
+        assert!(config("localhost").ensure_host_allowed(None).is_ok());
+
+        let error = config("0.0.0.0").ensure_host_allowed(None).unwrap_err();
+        assert!(error.contains(EXT_SERVER_HOST_ENV), "{}", error);
+        assert!(config("192.168.1.10").ensure_host_allowed(Some("0.0.0.0")).is_err());
+        assert!(config("0.0.0.0").ensure_host_allowed(Some(" 0.0.0.0 ")).is_ok());
+    }
+
     #[test]
     fn test_rate_limiting() {
         let security = ExtensionSecurity::new();
@@ -13,6 +13,8 @@ mod window_commands;
 mod menu;
 // System tray / Menu bar icon
 mod tray;
+// Logging (spans de traçabilité, niveau modifiable à chaud)
+mod logging;
 
 use rag::{DocumentGroup, OcrState, RagState, DirectChatState};
 use std::path::Path;
@@ -23,20 +25,29 @@ use rag::ocr::commands::{
     ocr_get_version, ocr_get_cache_stats, ocr_clear_cache, ocr_get_config
 };
 use rag::commands::{
-    add_document_intelligent, search_with_metadata, get_document_metadata, list_rag_documents, delete_rag_document, query_rag_with_context
+    add_document_intelligent, search_with_metadata, get_document_metadata, list_rag_documents, delete_rag_document, query_rag_with_context,
+    get_financial_kpis, reset_group_collection, get_extraction_diagnostics, preview_ingestion,
+    system_health, list_embedding_models, set_embedding_model, get_collection_info, warm_up, reclassify_group,
+    get_priority_weights, set_priority_weights, export_group, import_group, analyze_chunking,
+    get_group_embedding_stats, browse_chunks, rename_group, migrate_group_collection, export_embeddings
 };
 use rag::direct_chat_commands::{
     process_dropped_document, chat_with_dropped_document, chat_with_llm_context, get_direct_chat_session,
     cleanup_direct_chat_session, get_direct_chat_stats, list_direct_chat_sessions, cleanup_expired_sessions,
-    get_temp_pdf_url, get_pdf_for_session
+    get_temp_pdf_url, get_pdf_for_session, get_direct_chat_config, set_direct_chat_config, add_document_to_session,
+    get_session_history, clear_session_history, record_session_turn,
+    export_direct_chat_session, explain_answer, search_everything
 };
 use awcs::AWCSState;
 use awcs::commands::{
     awcs_get_current_context, awcs_handle_query, awcs_check_permissions, awcs_request_permissions,
-    awcs_setup_global_shortcut, awcs_get_state, awcs_set_state, awcs_cleanup, awcs_get_metrics,
+    awcs_setup_global_shortcut, awcs_get_state, awcs_set_state, awcs_cleanup, awcs_get_metrics, awcs_reset_metrics,
     awcs_get_config, awcs_update_config, awcs_open_system_preferences, awcs_show_zone_selector,
-    awcs_trigger_shortcut, awcs_test_extraction, awcs_get_context_ocr_direct, awcs_get_context_focused_ocr
+    awcs_trigger_shortcut, awcs_test_extraction, awcs_get_context_ocr_direct, awcs_get_context_focused_ocr, awcs_get_context_selection,
+    awcs_list_displays, awcs_start_watching, awcs_stop_watching, set_awcs_shortcut
 };
+use ext_server::{ext_server_status, ext_server_start, ext_server_stop};
+use logging::set_log_level;
 use window_commands::{open_rag_storage_window, open_settings_window, open_model_selector_window, open_conversations_window, emit_model_changed, emit_parameters_changed, broadcast_to_window, get_active_windows, close_specific_window, open_ocr_viewer_window, close_ocr_viewer_window, update_ocr_viewer_highlights};
 
 
@@ -80,6 +91,7 @@ async fn rag_create_group(name: String, state: State<'_, RagState>) -> Result<Do
         metadata_config: crate::rag::MetadataConfig::default(),
         documents: Vec::new(),
         qdrant_collection: format!("collection_{}", group_id),
+        collection_config: crate::rag::CollectionConfig::for_dimension(state.embedder().await.dimension()),
         created_at: now,
         updated_at: now,
     };
@@ -88,6 +100,9 @@ async fn rag_create_group(name: String, state: State<'_, RagState>) -> Result<Do
     {
         let mut groups = state.groups.write().await;
         groups.insert(group_id.clone(), group.clone());
+        if let Err(e) = state.group_registry.save(&groups) {
+            tracing::warn!("⚠️ Group {} not persisted, it will be lost on restart: {}", group_id, e);
+        }
     }
     
     tracing::info!("✅ Created and persisted group '{}' with ID: {}", name, group_id);
@@ -243,32 +258,114 @@ async fn delete_document(filename: String) -> Result<String, String> {
     }
 }
 
+/// Taille maximale par défaut d'un document téléversé
+const DEFAULT_UPLOAD_MAX_SIZE_MB: u64 = 100;
+
+/// Extensions acceptées par upload_document (formats pris en charge par l'extraction)
+const UPLOAD_ALLOWED_EXTENSIONS: &[&str] = &["pdf", "png", "jpg", "jpeg", "tiff", "bmp", "txt", "md"];
+
+/// Valider le nom cible et la taille d'un document avant copie
+fn validate_upload(target_name: &str, size_bytes: u64, max_size_mb: u64) -> Result<(), rag::CommandError> {
+    if target_name.trim().is_empty()
+        || target_name.contains(['/', '\\'])
+        || target_name == "."
+        || target_name == ".."
+    {
+        return Err(rag::CommandError::invalid_input(format!("Invalid file name '{}': path separators are not allowed", target_name)));
+    }
+
+    let extension = std::path::Path::new(target_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+    if !UPLOAD_ALLOWED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(rag::CommandError::invalid_input(format!(
+            "Unsupported file type '{}' for '{}' (allowed: {})",
+            extension, target_name, UPLOAD_ALLOWED_EXTENSIONS.join(", ")
+        )));
+    }
+
+    let max_bytes = max_size_mb.saturating_mul(1024 * 1024);
+    if size_bytes > max_bytes {
+        return Err(rag::CommandError::invalid_input(format!(
+            "File too large: {:.1} MB, limit {} MB",
+            size_bytes as f64 / (1024.0 * 1024.0), max_size_mb
+        )));
+    }
+
+    Ok(())
+}
+
+/// Copier un document dans le dossier exemple, après validation du type et de la taille
+/// (`max_size_mb` : `DEFAULT_UPLOAD_MAX_SIZE_MB` par défaut).
+///
+/// Un fichier identique (empreinte blake3) déjà ingéré est refusé, sauf `force`.
 #[tauri::command]
-async fn upload_document(file_path: String, target_name: String) -> Result<String, String> {
+async fn upload_document(
+    file_path: String,
+    target_name: String,
+    max_size_mb: Option<u64>,
+    force: Option<bool>,
+    state: State<'_, RagState>,
+) -> Result<String, rag::CommandError> {
     use std::fs;
     use std::path::Path;
     use std::env;
     
+    let source_path = Path::new(&file_path);
+    let metadata = fs::metadata(source_path)
+        .map_err(|_| rag::CommandError::not_found(format!("Source file '{}' not found", file_path)))?;
+    if !metadata.is_file() {
+        return Err(rag::CommandError::invalid_input(format!("Source '{}' is not a file", file_path)));
+    }
+    
+    let max_size_mb = max_size_mb.unwrap_or(DEFAULT_UPLOAD_MAX_SIZE_MB);
+    if let Err(e) = validate_upload(&target_name, metadata.len(), max_size_mb) {
+        tracing::warn!("Upload rejected for '{}': {}", target_name, e.message());
+        return Err(e);
+    }
+    
+    if !force.unwrap_or(false) {
+        // Détection de doublons au mieux : une empreinte illisible ou Qdrant indisponible ne bloque pas l'upload
+        let duplicate = match rag::commands::file_checksum(source_path.to_path_buf()).await {
+            Ok(checksum) => rag::commands::find_duplicate_document(&state, &checksum, None).await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Duplicate lookup failed for '{}', uploading anyway: {}", target_name, e.message());
+                    None
+                }),
+            Err(e) => {
+                tracing::warn!("Failed to hash '{}', uploading without duplicate check: {}", file_path, e);
+                None
+            }
+        };
+        if let Some(duplicate) = duplicate {
+            tracing::warn!("Upload of '{}' skipped: duplicate of document {}", target_name, duplicate.document_id);
+            return Err(rag::CommandError::already_exists(format!(
+                "Duplicate of document {} ({}) in group {}",
+                duplicate.document_id,
+                duplicate.source_file.as_deref().unwrap_or("unknown file"),
+                duplicate.group_id
+            )));
+        }
+    }
+    
     // Obtenir le répertoire courant et naviguer vers le dossier exemple
-    let current_dir = env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+    let current_dir = env::current_dir()
+        .map_err(|e| rag::CommandError::internal(format!("Failed to get current directory: {}", e)))?;
     let docs_path = current_dir.parent()
-        .ok_or("Failed to get parent directory")?
+        .ok_or_else(|| rag::CommandError::internal("Failed to get parent directory"))?
         .join("exemple");
     let docs_dir = docs_path.as_path();
     
     // Créer le dossier exemple s'il n'existe pas
     if !docs_dir.exists() {
         fs::create_dir_all(docs_dir)
-            .map_err(|e| format!("Failed to create documents directory: {}", e))?;
+            .map_err(|e| rag::CommandError::internal(format!("Failed to create documents directory: {}", e)))?;
     }
     
-    let source_path = Path::new(&file_path);
     let target_path = docs_dir.join(&target_name);
     
-    if !source_path.exists() {
-        return Err(format!("Source file '{}' not found", file_path));
-    }
-    
     match fs::copy(source_path, &target_path) {
         Ok(_) => {
             tracing::info!("Successfully uploaded file: {} -> {}", file_path, target_name);
@@ -276,16 +373,83 @@ async fn upload_document(file_path: String, target_name: String) -> Result<Strin
         },
         Err(e) => {
             tracing::error!("Failed to upload file '{}': {}", target_name, e);
-            Err(format!("Failed to upload file '{}': {}", target_name, e))
+            Err(rag::CommandError::internal(format!("Failed to upload file '{}': {}", target_name, e)))
+        }
+    }
+}
+
+/// Lance un visualiseur externe ; `false` si le programme est absent ou ne démarre pas
+fn spawn_viewer(program: &str, args: &[String]) -> bool {
+    match std::process::Command::new(program).args(args).spawn() {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::debug!("Viewer {} unavailable: {}", program, e);
+            false
+        }
+    }
+}
+
+/// Ouvre un PDF directement à la page demandée (1-based) si le visualiseur du système le permet.
+/// Retourne `false` quand la page ne peut pas être ciblée : l'appelant ouvre alors le fichier simplement.
+#[allow(unused_variables)]
+fn open_pdf_at_page(file_path: &std::path::Path, page: u32) -> bool {
+    let path = file_path.to_string_lossy().to_string();
+
+    #[cfg(target_os = "macos")]
+    {
+        // Preview n'expose pas la navigation en AppleScript : ouverture puis "Aller à la page" (⌥⌘G)
+        // via System Events (nécessite l'autorisation d'accessibilité)
+        let script = format!(
+            r#"tell application "Preview"
+    open POSIX file "{}"
+    activate
+end tell
+delay 0.5
+tell application "System Events" to tell process "Preview"
+    keystroke "g" using {{option down, command down}}
+    delay 0.2
+    keystroke "{}"
+    keystroke return
+end tell"#,
+            path.replace('\\', "\\\\").replace('"', "\\\""),
+            page
+        );
+        match std::process::Command::new("osascript").arg("-e").arg(&script).output() {
+            Ok(output) if output.status.success() => return true,
+            Ok(output) => {
+                tracing::warn!("⚠️ Preview page navigation failed: {}", String::from_utf8_lossy(&output.stderr));
+                return false;
+            }
+            Err(e) => {
+                tracing::warn!("⚠️ osascript unavailable: {}", e);
+                return false;
+            }
         }
     }
+
+    #[cfg(target_os = "windows")]
+    {
+        return spawn_viewer("SumatraPDF", &["-page".to_string(), page.to_string(), path]);
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let viewers: [(&str, Vec<String>); 4] = [
+            ("evince", vec![format!("--page-index={}", page), path.clone()]),
+            ("okular", vec!["-p".to_string(), page.to_string(), path.clone()]),
+            ("zathura", vec![format!("--page={}", page), path.clone()]),
+            ("qpdfview", vec![format!("{}#{}", path, page)]),
+        ];
+        return viewers.iter().any(|(program, args)| spawn_viewer(program, args));
+    }
+
+    #[allow(unreachable_code)]
+    false
 }
 
 #[tauri::command]
-async fn open_document_viewer(filename: String) -> Result<String, String> {
+async fn open_document_viewer(filename: String, page: Option<u32>) -> Result<String, String> {
     use std::env;
-    use std::path::Path;
-    use tauri::AppHandle;
     
     // Obtenir le chemin du document
     let current_dir = env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
@@ -297,50 +461,49 @@ async fn open_document_viewer(filename: String) -> Result<String, String> {
     if !file_path.exists() {
         return Err(format!("File '{}' not found", filename));
     }
+
+    // PDF + page demandée : tenter d'ouvrir directement à la page, sinon ouverture simple
+    let is_pdf = file_path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+    if let Some(page) = page.filter(|page| *page > 0) {
+        // osascript/visualiseur lancés hors du runtime async (la navigation Preview attend l'ouverture)
+        let pdf_path = file_path.clone();
+        let opened_at_page = is_pdf && tokio::task::spawn_blocking(move || open_pdf_at_page(&pdf_path, page))
+            .await
+            .unwrap_or(false);
+        if opened_at_page {
+            tracing::info!("📖 Document '{}' opened at page {}", filename, page);
+            return Ok(format!("Document '{}' opened at page {}", filename, page));
+        }
+        tracing::info!("📖 Page {} cannot be targeted for '{}', opening document", page, filename);
+    }
     
     // Ouvrir le fichier avec l'application par défaut du système
     #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        let result = Command::new("open")
-            .arg(&file_path)
-            .spawn();
-        
-        match result {
-            Ok(_) => Ok(format!("Document '{}' opened successfully", filename)),
-            Err(e) => Err(format!("Failed to open document '{}': {}", filename, e))
-        }
-    }
+    let opened = spawn_viewer("open", &[file_path.to_string_lossy().to_string()]);
     
     #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-        let result = Command::new("cmd")
-            .args(&["/C", "start", "", &file_path.to_string_lossy()])
-            .spawn();
-        
-        match result {
-            Ok(_) => Ok(format!("Document '{}' opened successfully", filename)),
-            Err(e) => Err(format!("Failed to open document '{}': {}", filename, e))
-        }
-    }
+    let opened = spawn_viewer("cmd", &["/C".to_string(), "start".to_string(), String::new(), file_path.to_string_lossy().to_string()]);
     
     #[cfg(target_os = "linux")]
-    {
-        use std::process::Command;
-        let result = Command::new("xdg-open")
-            .arg(&file_path)
-            .spawn();
-        
-        match result {
-            Ok(_) => Ok(format!("Document '{}' opened successfully", filename)),
-            Err(e) => Err(format!("Failed to open document '{}': {}", filename, e))
-        }
+    let opened = spawn_viewer("xdg-open", &[file_path.to_string_lossy().to_string()]);
+
+    if opened {
+        Ok(format!("Document '{}' opened successfully", filename))
+    } else {
+        Err(format!("Failed to open document '{}'", filename))
     }
 }
 
 #[tauri::command]
-async fn extract_document_content(filename: String) -> Result<serde_json::Value, String> {
+async fn extract_document_content(
+    filename: String,
+    languages: Option<Vec<String>>,
+    pdf_engine: Option<rag::ocr::PdfEngine>,
+    min_native_tokens: Option<usize>,
+    app: tauri::AppHandle,
+) -> Result<serde_json::Value, rag::CommandError> {
     use std::env;
     use std::path::Path;
     
@@ -349,49 +512,35 @@ async fn extract_document_content(filename: String) -> Result<serde_json::Value,
     // Obtenir le chemin du document
     let current_dir = env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
     let docs_path = current_dir.parent()
-        .ok_or("Failed to get parent directory")?
+        .ok_or_else(|| rag::CommandError::internal("Failed to get parent directory"))?
         .join("exemple");
     let file_path = docs_path.join(&filename);
     
     if !file_path.exists() {
-        return Err(format!("File '{}' not found", filename));
+        return Err(rag::CommandError::not_found(format!("File '{}' not found", filename)));
     }
+
+    // Langues OCR : explicites (validées contre les packs installés) ou eng+fra par défaut
+    let languages = crate::rag::ocr::resolve_ocr_languages(languages).await
+        .map_err(|e| rag::CommandError::from(e).with_context("Missing Tesseract language packs"))?;
+    tracing::info!("🌐 OCR languages: {}", languages.join("+"));
     
     let start_time = std::time::Instant::now();
     
     // Déterminer le type de fichier et extraire le contenu
+    let pdf_engine = pdf_engine.unwrap_or_default();
+    let min_native_tokens = min_native_tokens.unwrap_or(DEFAULT_EXTRACTION_MIN_NATIVE_TOKENS);
     let extraction_result = if filename.to_lowercase().ends_with(".pdf") {
-        // Stratégie hybride : essayer extractous d'abord, puis fallback vers lopdf
-        #[cfg(feature = "extractous")]
-        {
-            tracing::info!("🚀 Using extractous for PDF extraction (feature enabled)");
-            match extract_pdf_content_extractous(&file_path).await {
-                Ok(result) if !result.text.trim().is_empty() => {
-                    tracing::info!("✅ Extractous extraction successful");
-                    Ok(result)
-                },
-                Ok(_) => {
-                    tracing::info!("⚠️ Extractous returned empty text, trying lopdf fallback...");
-                    extract_pdf_content_lopdf(&file_path).await
-                },
-                Err(e) => {
-                    tracing::warn!("⚠️ Extractous failed: {}, trying lopdf fallback...", e);
-                    extract_pdf_content_lopdf(&file_path).await
-                }
-            }
-        }
-        #[cfg(not(feature = "extractous"))]
-        {
-            tracing::info!("📄 Using lopdf for PDF extraction (extractous feature disabled)");
-            extract_pdf_content_lopdf(&file_path).await
-        }
+        // Progression page par page relayée au frontend
+        let progress = rag::commands::ocr_progress_emitter(app, filename.clone());
+        extract_pdf_content(&file_path, &languages, pdf_engine, min_native_tokens, Some(progress)).await
     } else if filename.to_lowercase().ends_with(".png") 
            || filename.to_lowercase().ends_with(".jpg") 
            || filename.to_lowercase().ends_with(".jpeg") {
         // Extraction OCR pour les images
-        extract_image_content(&file_path).await
+        extract_image_content(&file_path, &languages).await
     } else {
-        return Err(format!("Unsupported file type for '{}'", filename));
+        return Err(rag::CommandError::invalid_input(format!("Unsupported file type for '{}'", filename)));
     };
     
     let processing_time = start_time.elapsed();
@@ -406,6 +555,11 @@ async fn extract_document_content(filename: String) -> Result<serde_json::Value,
                 "processing_time_ms": processing_time.as_millis(),
                 "pages": content.pages,
                 "metadata": content.metadata,
+                "page_quality": content.page_quality,
+                "worst_page": rag::ocr::worst_page(&content.page_quality),
+                "languages": languages,
+                "pdf_engine": pdf_engine.as_str(),
+                "min_native_tokens": min_native_tokens,
                 "extracted_at": chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
             });
             
@@ -414,11 +568,82 @@ async fn extract_document_content(filename: String) -> Result<serde_json::Value,
         },
         Err(e) => {
             tracing::error!("❌ Extraction failed for '{}': {}", filename, e);
-            Err(format!("Extraction failed: {}", e))
+            Err(rag::CommandError::internal(format!("Extraction failed: {}", e)))
         }
     }
 }
 
+/// Exécuter les moteurs disponibles sur un même fichier et comparer leurs sorties
+#[tauri::command]
+async fn compare_extractions(
+    filename: String,
+    languages: Option<Vec<String>>,
+    min_native_tokens: Option<usize>,
+) -> Result<rag::ocr::ExtractionComparison, rag::CommandError> {
+    use rag::ocr::{EngineExtraction, ExtractionComparison};
+
+    let current_dir = std::env::current_dir().map_err(|e| format!("Failed to get current directory: {}", e))?;
+    let file_path = current_dir.parent()
+        .ok_or_else(|| rag::CommandError::internal("Failed to get parent directory"))?
+        .join("exemple")
+        .join(&filename);
+    if !file_path.exists() {
+        return Err(rag::CommandError::not_found(format!("File '{}' not found", filename)));
+    }
+
+    let languages = crate::rag::ocr::resolve_ocr_languages(languages).await
+        .map_err(|e| rag::CommandError::from(e).with_context("Missing Tesseract language packs"))?;
+
+    let lowercase = filename.to_lowercase();
+    let is_pdf = lowercase.ends_with(".pdf");
+    let is_image = [".png", ".jpg", ".jpeg"].iter().any(|ext| lowercase.ends_with(ext));
+    if !is_pdf && !is_image {
+        return Err(rag::CommandError::invalid_input(format!("Unsupported file type for '{}'", filename)));
+    }
+
+    tracing::info!("⚖️ Comparing extraction engines for '{}'", filename);
+
+    // Chaque moteur est exécuté séquentiellement pour que les temps restent comparables
+    let timed = |engine: &str, result: Result<ExtractionResult, String>, started: std::time::Instant| {
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(content) => {
+                let run = EngineExtraction::success(engine, &content.text, content.confidence, content.pages, elapsed_ms);
+                (run, Some(content.text))
+            }
+            Err(e) => (EngineExtraction::failure(engine, e, elapsed_ms), None),
+        }
+    };
+
+    let mut runs = Vec::new();
+    if is_pdf {
+        let started = std::time::Instant::now();
+        let min_native_tokens = min_native_tokens.unwrap_or(DEFAULT_EXTRACTION_MIN_NATIVE_TOKENS);
+        let result = extract_pdf_content_lopdf(&file_path, &languages, min_native_tokens, None).await;
+        runs.push(timed("lopdf", result, started));
+
+        #[cfg(feature = "extractous")]
+        {
+            let started = std::time::Instant::now();
+            let result = extract_pdf_content_extractous(&file_path, &languages).await;
+            runs.push(timed("extractous", result, started));
+        }
+        #[cfg(not(feature = "extractous"))]
+        runs.push((EngineExtraction::unavailable("extractous", "Build compiled without the `extractous` feature"), None));
+
+        // Le processeur Tesseract n'OCRise que des images : pas de rendu de pages PDF
+        runs.push((EngineExtraction::unavailable("tesseract", "Tesseract needs rasterized pages; PDF rendering is not supported"), None));
+    } else {
+        let started = std::time::Instant::now();
+        let result = extract_image_content(&file_path, &languages).await;
+        runs.push(timed("tesseract", result, started));
+    }
+
+    let comparison = ExtractionComparison::from_runs(&filename, runs);
+    tracing::info!("✅ Extraction comparison for '{}': recommended engine {:?}", filename, comparison.recommended_engine);
+    Ok(comparison)
+}
+
 #[tauri::command]
 async fn get_document_extraction(filename: String) -> Result<serde_json::Value, String> {
     // Pour l'instant, on retourne un placeholder
@@ -441,20 +666,77 @@ struct ExtractionResult {
     method: String,
     pages: u32,
     metadata: serde_json::Value,
+    /// Qualité page par page (vide si le moteur ne la fournit pas)
+    page_quality: Vec<rag::ocr::PageQuality>,
+}
+
+// Extraction PDF selon le moteur demandé
+async fn extract_pdf_content(
+    file_path: &Path,
+    languages: &[String],
+    engine: rag::ocr::PdfEngine,
+    min_native_tokens: usize,
+    progress: Option<rag::ocr::OcrProgressCallback>,
+) -> Result<ExtractionResult, String> {
+    use rag::ocr::PdfEngine;
+
+    match engine {
+        PdfEngine::Lopdf => {
+            tracing::info!("📄 Using lopdf for PDF extraction (requested)");
+            extract_pdf_content_lopdf(file_path, languages, min_native_tokens, progress).await
+        }
+        PdfEngine::Extractous => {
+            #[cfg(feature = "extractous")]
+            {
+                tracing::info!("🚀 Using extractous for PDF extraction (requested)");
+                extract_pdf_content_extractous(file_path, languages).await
+            }
+            #[cfg(not(feature = "extractous"))]
+            {
+                Err("PDF engine 'extractous' requested but this build was compiled without the `extractous` feature; use 'lopdf' or 'auto'".to_string())
+            }
+        }
+        // Stratégie hybride : essayer extractous d'abord, puis fallback vers lopdf
+        PdfEngine::Auto => {
+            #[cfg(feature = "extractous")]
+            {
+                tracing::info!("🚀 Using extractous for PDF extraction (feature enabled)");
+                match extract_pdf_content_extractous(file_path, languages).await {
+                    Ok(result) if !result.text.trim().is_empty() => {
+                        tracing::info!("✅ Extractous extraction successful");
+                        Ok(result)
+                    },
+                    Ok(_) => {
+                        tracing::info!("⚠️ Extractous returned empty text, trying lopdf fallback...");
+                        extract_pdf_content_lopdf(file_path, languages, min_native_tokens, progress).await
+                    },
+                    Err(e) => {
+                        tracing::warn!("⚠️ Extractous failed: {}, trying lopdf fallback...", e);
+                        extract_pdf_content_lopdf(file_path, languages, min_native_tokens, progress).await
+                    }
+                }
+            }
+            #[cfg(not(feature = "extractous"))]
+            {
+                tracing::info!("📄 Using lopdf for PDF extraction (extractous feature disabled)");
+                extract_pdf_content_lopdf(file_path, languages, min_native_tokens, progress).await
+            }
+        }
+    }
 }
 
-// Extraction PDF avec lopdf
 // Nouvelle fonction d'extraction PDF avec extractous (2025)
 #[cfg(feature = "extractous")]
-async fn extract_pdf_content_extractous(file_path: &Path) -> Result<ExtractionResult, String> {
+async fn extract_pdf_content_extractous(file_path: &Path, languages: &[String]) -> Result<ExtractionResult, String> {
     use extractous::{Extractor, TesseractOcrConfig, PdfParserConfig, PdfOcrStrategy};
     
     tracing::info!("🚀 Extracting PDF with extractous (OCR): {:?}", file_path);
     
+    let ocr_languages = languages.join("+");
     let extractor = Extractor::new()
         .set_ocr_config(
             TesseractOcrConfig::new()
-                .set_language("eng+fra") // Support anglais et français
+                .set_language(&ocr_languages)
         )
         .set_pdf_config(
             PdfParserConfig::new()
@@ -477,8 +759,9 @@ async fn extract_pdf_content_extractous(file_path: &Path) -> Result<ExtractionRe
                     "pages": page_count,
                     "source": "extractous",
                     "ocr_strategy": "AUTO",
-                    "languages": "eng+fra"
-                })
+                    "languages": ocr_languages
+                }),
+                page_quality: Vec::new(),
             })
         },
         Err(e) => {
@@ -489,20 +772,33 @@ async fn extract_pdf_content_extractous(file_path: &Path) -> Result<ExtractionRe
 }
 
 // Fonction d'extraction PDF originale (backup)
-async fn extract_pdf_content_lopdf(file_path: &Path) -> Result<ExtractionResult, String> {
+// Seuil de tokens natifs sous lequel une page est traitée comme scannée (OCR)
+const DEFAULT_EXTRACTION_MIN_NATIVE_TOKENS: usize = 10;
+
+// Extraction PDF avec lopdf
+async fn extract_pdf_content_lopdf(
+    file_path: &Path,
+    languages: &[String],
+    min_native_tokens: usize,
+    progress: Option<rag::ocr::OcrProgressCallback>,
+) -> Result<ExtractionResult, String> {
     use crate::rag::ocr::pdf_lopdf::{LopdFProcessor, LopdFPipelineConfig};
     
     tracing::info!("📄 Extracting PDF content from: {:?}", file_path);
     
     let mut config = LopdFPipelineConfig::default();
-    // Forcer l'OCR même avec peu de texte natif pour les PDFs scannés
-    config.min_native_tokens = 10;
-    let processor = LopdFProcessor::new(config).await
+    // Page considérée comme scannée (OCR) sous ce nombre de tokens natifs
+    config.min_native_tokens = min_native_tokens;
+    config.tesseract_config.languages = languages.to_vec();
+    let mut processor = LopdFProcessor::new(config).await
         .map_err(|e| format!("Failed to initialize PDF processor: {}", e))?;
+    if let Some(progress) = progress {
+        processor = processor.with_progress_callback(progress);
+    }
     
     match processor.process_pdf(file_path).await {
         Ok(pages) => {
-            let combined_text = pages.iter()
+            let page_texts: Vec<String> = pages.iter()
                 .map(|page| {
                     // Prioriser le texte natif, mais utiliser OCR si vide
                     if !page.native_text.trim().is_empty() {
@@ -513,28 +809,31 @@ async fn extract_pdf_content_lopdf(file_path: &Path) -> Result<ExtractionResult,
                         String::new()
                     }
                 })
+                .collect();
+
+            // En-têtes/pieds de page répétés sur la majorité des pages
+            let stripped = crate::rag::processing::strip_repeated_headers_footers(
+                &page_texts,
+                &crate::rag::processing::HeaderFooterConfig::default(),
+            );
+            if stripped.lines_stripped > 0 {
+                tracing::info!("🧹 Stripped {} header/footer lines from {} pages", stripped.lines_stripped, page_texts.len());
+            }
+
+            let combined_text = stripped.pages.into_iter()
                 .filter(|text| !text.trim().is_empty())
                 .collect::<Vec<_>>()
                 .join("\n\n");
             
-            let avg_confidence = if pages.is_empty() { 0.5 } else {
-                let mut total_confidence = 0.0;
-                let mut count = 0;
-                
-                for page in &pages {
-                    if !page.native_text.trim().is_empty() {
-                        // Texte natif = haute confiance
-                        total_confidence += 0.95;
-                        count += 1;
-                    } else if let Some(ocr_result) = &page.ocr_result {
-                        // Utiliser la confiance OCR
-                        total_confidence += ocr_result.confidence;
-                        count += 1;
-                    }
-                }
-                
-                if count > 0 { total_confidence / count as f32 } else { 0.5 }
-            };
+            // Qualité par page (texte natif = haute confiance, sinon confiance OCR)
+            let page_quality: Vec<rag::ocr::PageQuality> = pages.iter()
+                .map(rag::ocr::PageQuality::from_page)
+                .collect();
+            let avg_confidence = rag::ocr::average_page_confidence(&page_quality);
+            if let Some(worst) = rag::ocr::worst_page(&page_quality).filter(|_| page_quality.len() > 1) {
+                tracing::info!("📉 Worst page: {} (confidence {:.2}, native ratio {:.2}, {} chars)",
+                               worst.page_number, worst.confidence, worst.native_text_ratio, worst.char_count);
+            }
             
             Ok(ExtractionResult {
                 text: combined_text,
@@ -544,7 +843,8 @@ async fn extract_pdf_content_lopdf(file_path: &Path) -> Result<ExtractionResult,
                 metadata: serde_json::json!({
                     "pages": pages.len(),
                     "source": "lopdf"
-                })
+                }),
+                page_quality,
             })
         },
         Err(e) => Err(format!("PDF extraction failed: {}", e))
@@ -552,12 +852,15 @@ async fn extract_pdf_content_lopdf(file_path: &Path) -> Result<ExtractionResult,
 }
 
 // Extraction OCR pour les images
-async fn extract_image_content(file_path: &Path) -> Result<ExtractionResult, String> {
+async fn extract_image_content(file_path: &Path, languages: &[String]) -> Result<ExtractionResult, String> {
     use crate::rag::ocr::{TesseractProcessor, TesseractConfig};
     
     tracing::info!("🖼️ Extracting image content from: {:?}", file_path);
     
-    let config = TesseractConfig::default();
+    let config = TesseractConfig {
+        languages: languages.to_vec(),
+        ..TesseractConfig::default()
+    };
     let processor = TesseractProcessor::new(config).await
         .map_err(|e| format!("Failed to initialize OCR: {}", e))?;
     
@@ -572,20 +875,42 @@ async fn extract_image_content(file_path: &Path) -> Result<ExtractionResult, Str
                     "language": result.language,
                     "processing_time": result.processing_time,
                     "engine": result.engine_used
-                })
+                }),
+                page_quality: Vec::new(),
             })
         },
         Err(e) => Err(format!("OCR extraction failed: {}", e))
     }
 }
 
+/// Enregistrer le raccourci global AWCS persisté (celui par défaut si absent, invalide ou déjà pris)
+async fn register_awcs_shortcut(app: &tauri::AppHandle) {
+    use tauri::Manager;
+    use awcs::core::global_shortcuts::{load_persisted_shortcut, DEFAULT_GLOBAL_SHORTCUT};
+
+    let persisted = app.path().app_config_dir().ok()
+        .and_then(|dir| load_persisted_shortcut(&dir));
+    let awcs_state = app.state::<AWCSState>();
+    let manager_arc = awcs_state.manager();
+    let mut manager = manager_arc.write().await;
+
+    if let Some(shortcut) = persisted {
+        match manager.set_global_shortcut(&shortcut, app.clone()).await {
+            Ok(()) => return,
+            Err(e) => tracing::warn!("⚠️ Persisted shortcut {} unavailable, using default: {}", shortcut, e),
+        }
+    }
+
+    if let Err(e) = manager.set_global_shortcut(DEFAULT_GLOBAL_SHORTCUT, app.clone()).await {
+        tracing::error!("Failed to register global shortcut {}: {}", DEFAULT_GLOBAL_SHORTCUT, e);
+    }
+}
+
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialiser le logging pour le debugging RAG + OCR (Phase 2)
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .init();
+    // Initialiser le logging pour le debugging RAG + OCR (Phase 2), niveau modifiable via `set_log_level`
+    logging::init_logging();
 
     tracing::info!("GRAVIS starting with RAG Module Phase 2 + OCR Integration + AWCS Phase 1 + Extension Server");
 
@@ -599,7 +924,7 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     })?;
     
     // Créer l'état Chat Direct Phase 2
-    let direct_chat_state = DirectChatState::new(rag_state.embedder.clone()).await.map_err(|e| {
+    let direct_chat_state = DirectChatState::new(rag_state.embedder.clone(), rag_state.unified_cache.clone()).await.map_err(|e| {
         tracing::error!("Failed to initialize DirectChatState: {}", e);
         e
     })?;
@@ -611,9 +936,8 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(
+            // Raccourci enregistré après le build (réglage persisté, cf. register_awcs_shortcut)
             tauri_plugin_global_shortcut::Builder::new()
-                .with_shortcuts(["Cmd+Shift+Control+L"])
-                .unwrap()
                 .with_handler(|app, shortcut, event| {
                     use tauri_plugin_global_shortcut::ShortcutState;
                     use tauri::Emitter;
@@ -632,7 +956,8 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
         .manage(ocr_state)
         .manage(rag_state)
         .manage(direct_chat_state)
-        .manage(awcs_state);
+        .manage(awcs_state)
+        .manage(ext_server::ExtServerState::default());
 
     // Configurer le menu natif macOS et le system tray
     #[cfg(target_os = "macos")]
@@ -685,6 +1010,7 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
             upload_document,
             open_document_viewer,
             extract_document_content,
+            compare_extractions,
             get_document_extraction,
             // OCR Commands Phase 2
             ocr_initialize,
@@ -701,6 +1027,26 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
             list_rag_documents,
             delete_rag_document,
             query_rag_with_context,
+            get_financial_kpis,
+            reset_group_collection,
+            rename_group,
+            migrate_group_collection,
+            get_extraction_diagnostics,
+            preview_ingestion,
+            system_health,
+            list_embedding_models,
+            set_embedding_model,
+            get_collection_info,
+            warm_up,
+            reclassify_group,
+            get_priority_weights,
+            set_priority_weights,
+            export_group,
+            import_group,
+            analyze_chunking,
+            get_group_embedding_stats,
+            export_embeddings,
+            browse_chunks,
             // AWCS Commands Phase 1 - Core
             awcs_get_current_context,
             awcs_handle_query,
@@ -711,6 +1057,7 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
             awcs_set_state,
             awcs_cleanup,
             awcs_get_metrics,
+            awcs_reset_metrics,
             awcs_get_config,
             awcs_update_config,
             awcs_open_system_preferences,
@@ -719,6 +1066,11 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
             awcs_test_extraction,
             awcs_get_context_ocr_direct,
             awcs_get_context_focused_ocr,
+            awcs_get_context_selection,
+            awcs_list_displays,
+            awcs_start_watching,
+            awcs_stop_watching,
+            set_awcs_shortcut,
             // Phase 2: Chat Direct Commands
             process_dropped_document,
             chat_with_dropped_document,
@@ -729,15 +1081,60 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
             list_direct_chat_sessions,
             cleanup_expired_sessions,
             get_temp_pdf_url,
-            get_pdf_for_session
+            get_pdf_for_session,
+            get_direct_chat_config,
+            set_direct_chat_config,
+            add_document_to_session,
+            get_session_history,
+            clear_session_history,
+            record_session_turn,
+            export_direct_chat_session,
+            explain_answer,
+            search_everything,
+            ext_server_status,
+            ext_server_start,
+            ext_server_stop,
+            set_log_level
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
 
-    // Démarrer le serveur extension après que l'app soit prête
-    let app_handle_clone = app_handle.handle().clone();
-    if let Err(e) = ext_server::start_extension_server(app_handle_clone).await {
-        tracing::error!("Failed to start extension server: {}", e);
+    // Langues OCR par défaut manquantes : avertir sans bloquer le démarrage
+    let ocr_check_handle = app_handle.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        use tauri::Emitter;
+
+        if let Some(warning) = rag::ocr::check_default_languages().await {
+            tracing::warn!("⚠️ {}", warning.message);
+            if let Err(e) = ocr_check_handle.emit(rag::ocr::OCR_LANGUAGES_MISSING_EVENT, &warning) {
+                tracing::error!("Failed to emit OCR languages event: {}", e);
+            }
+        }
+    });
+
+    // Préchauffer l'embedder et Tesseract en tâche de fond (GRAVIS_SKIP_WARM_UP=1 pour désactiver)
+    if std::env::var("GRAVIS_SKIP_WARM_UP").map(|v| v != "1" && v != "true").unwrap_or(true) {
+        let warm_up_handle = app_handle.handle().clone();
+        tauri::async_runtime::spawn(async move {
+            use tauri::Manager;
+
+            let state = warm_up_handle.state::<RagState>();
+            rag::commands::run_warm_up(&state).await;
+        });
+    }
+
+    register_awcs_shortcut(app_handle.handle()).await;
+
+    // Démarrer le serveur extension après que l'app soit prête (GRAVIS_EXT_SERVER=0 pour désactiver)
+    if ext_server::ext_server_enabled_by_env() {
+        use tauri::Manager;
+
+        let ext_server_state = app_handle.state::<ext_server::ExtServerState>();
+        if let Err(e) = ext_server_state.start(app_handle.handle().clone(), None).await {
+            tracing::error!("Failed to start extension server: {}", e);
+        }
+    } else {
+        tracing::info!("🔌 Extension server disabled ({}=0)", ext_server::EXT_SERVER_ENV);
     }
 
     app_handle.run(|_app_handle, event| match event {
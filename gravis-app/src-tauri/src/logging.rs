@@ -0,0 +1,85 @@
+// GRAVIS - Initialisation du logging et niveau modifiable à chaud
+// Les opérations clés (ingestion, chunking, embedding, upsert, recherche) ouvrent des spans
+// portant document_id/group_id/session_id : chaque ligne de log est préfixée par leur contexte.
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
+
+/// Poignée de rechargement du filtre, disponible après `init_logging`
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Niveau de log global
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            other => Err(format!("Unknown log level '{}' (expected error, warn, info, debug or trace)", other)),
+        }
+    }
+}
+
+/// Installer le subscriber global : `RUST_LOG` si défini, INFO sinon
+pub fn init_logging() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(LogLevel::Info.as_str()));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    if tracing_subscriber::registry().with(filter).with(fmt::layer()).try_init().is_ok() {
+        let _ = FILTER_HANDLE.set(handle);
+    }
+}
+
+/// Changer le niveau de log global sans redémarrage
+#[tauri::command]
+pub fn set_log_level(level: String) -> Result<LogLevel, String> {
+    let level = LogLevel::from_str(&level)?;
+    let handle = FILTER_HANDLE.get().ok_or("Logging not initialized")?;
+    handle
+        .reload(EnvFilter::new(level.as_str()))
+        .map_err(|e| format!("Failed to change log level: {}", e))?;
+
+    tracing::info!("🔧 Log level set to {}", level.as_str());
+    Ok(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_level_parsing() {
+        assert_eq!(LogLevel::from_str("DEBUG"), Ok(LogLevel::Debug));
+        assert_eq!(LogLevel::from_str(" warning "), Ok(LogLevel::Warn));
+        assert!(LogLevel::from_str("verbose").is_err());
+        assert_eq!(LogLevel::from_str(LogLevel::Trace.as_str()), Ok(LogLevel::Trace));
+    }
+}
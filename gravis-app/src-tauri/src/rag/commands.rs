@@ -10,13 +10,26 @@ use std::time::SystemTime;
 use tauri::State;
 use tokio::sync::RwLock;
 use anyhow::Result;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 use crate::rag::{
     DocumentGroup, DocumentCategory, BusinessMetadata, SourceType, ExtractionMethod,
     DocumentProcessor, IngestionEngine, DocumentClassifier, BusinessMetadataEnricher,
     UnifiedCache, QdrantRestClient, CustomE5Embedder, CustomE5Config, QdrantRestConfig,
-    OcrCache, CacheConfig, TesseractConfig, GroupDocument, RagError
+    OcrCache, CacheConfig, TesseractConfig, GroupDocument, RagError, ChunkSource, EnrichedChunk,
+    FinancialKPI, ExtractionQualityMetrics, EXTRACTION_QUALITY_FIELD, SourceBoundingBox, CommandError,
+    EmbeddingModelInfo, EMBEDDING_MODELS, EnrichedMetadata, Priority, PriorityWeights, GroupExportBundle
+};
+use crate::rag::core::direct_chat::OCRBlock;
+use crate::rag::core::embedding_export::{existing_export_files, validate_export_path, ChunkEmbedding, EmbeddingFileWriter};
+use crate::rag::core::llm_synthesis::LlmSynthesisConfig;
+use crate::rag::processing::document_summary::{summarize_document, DocumentSummary, SummaryMethod, DOCUMENT_SUMMARY_FIELD};
+use crate::rag::processing::numeric_facts::{extract_numeric_facts, facts_match_query, NumericFact, NUMERIC_FACTS_FIELD};
+use crate::rag::search::{
+    detect_answer_intent, compute_bm25_score, compute_hybrid_score, AnswerIntent,
+    detect_language, dominant_language, translate_query,
+    QueryCache, QueryCacheHit, QUERY_CACHE_TTL, SEMANTIC_CACHE_THRESHOLD,
+    EmbeddingSpaceStats, SampledEmbedding, DEFAULT_EMBEDDING_SAMPLE_SIZE,
 };
 
 /// État unifié RAG Phase 3 avec OCR et Classification
@@ -25,10 +38,19 @@ pub struct RagState {
     pub ingestion_engine: Arc<IngestionEngine>,
     pub document_classifier: Arc<DocumentClassifier>,
     pub business_enricher: Arc<BusinessMetadataEnricher>,
-    pub embedder: Arc<CustomE5Embedder>,
+    /// Embedder actif, remplaçable à chaud par `set_embedding_model`
+    pub embedder: Arc<RwLock<Arc<CustomE5Embedder>>>,
     pub qdrant_client: Arc<QdrantRestClient>,
     pub unified_cache: Arc<UnifiedCache>,
     pub groups: Arc<RwLock<HashMap<String, DocumentGroup>>>,
+    /// Nom et collection des groupes, persistés entre deux démarrages
+    pub group_registry: Arc<crate::rag::GroupRegistry>,
+    /// Rapport du dernier préchauffage (None tant qu'il n'est pas terminé)
+    pub warm_up: Arc<RwLock<Option<WarmUpReport>>>,
+    /// Multiplicateurs de score par priorité de document (`set_priority_weights`)
+    pub priority_weights: Arc<RwLock<PriorityWeights>>,
+    /// Réponses de `search_with_metadata` récentes, invalidées par groupe à chaque ajout/suppression
+    pub query_cache: Arc<RwLock<QueryCache<SearchResponseWithMetadata>>>,
 }
 
 impl RagState {
@@ -55,14 +77,8 @@ impl RagState {
         let document_classifier = Arc::new(DocumentClassifier::new());
         let business_enricher = Arc::new(BusinessMetadataEnricher::new());
 
-        // Document processor avec OCR
-        let ocr_processor = crate::rag::ocr::TesseractProcessor::new(TesseractConfig::default())
-            .await
-            .map_err(|e| RagError::InvalidConfig(format!("OCR init failed: {}", e)))?;
-        
-        let document_processor = DocumentProcessor::new(ocr_processor, embedder.clone())
-            .await
-            .map_err(|e| RagError::InvalidConfig(format!("DocumentProcessor init failed: {}", e)))?;
+        // Document processor : Tesseract initialisé au premier document nécessitant l'OCR
+        let document_processor = DocumentProcessor::with_lazy_ocr(TesseractConfig::default(), embedder.clone());
 
         let ingestion_engine = Arc::new(
             IngestionEngine::new(document_processor)
@@ -73,23 +89,38 @@ impl RagState {
         let default_group = crate::rag::DocumentGroup::new_with_id(
             "default_group".to_string(),
             "Default Group".to_string()
-        );
+        ).with_collection_config(crate::rag::CollectionConfig::for_dimension(embedder.dimension()));
         info!("📁 Created default RAG group: {} -> collection: {}",
               default_group.id, default_group.qdrant_collection);
         groups.insert("default_group".to_string(), default_group);
 
+        // Renommages, migrations de collection et groupes créés lors des sessions précédentes
+        let group_registry = crate::rag::GroupRegistry::from_env();
+        group_registry.restore(&mut groups);
+
         Ok(Self {
             ingestion_engine,
             document_classifier,
             business_enricher,
-            embedder: embedder.clone(),
+            embedder: Arc::new(RwLock::new(embedder)),
             qdrant_client,
             unified_cache,
             groups: Arc::new(RwLock::new(groups)),
+            group_registry: Arc::new(group_registry),
+            warm_up: Arc::new(RwLock::new(None)),
+            priority_weights: Arc::new(RwLock::new(PriorityWeights::default())),
+            query_cache: Arc::new(RwLock::new(QueryCache::new(QUERY_CACHE_TTL))),
         })
     }
 }
 
+impl RagState {
+    /// Embedder actif (le verrou n'est tenu que le temps du clone)
+    pub async fn embedder(&self) -> Arc<CustomE5Embedder> {
+        self.embedder.read().await.clone()
+    }
+}
+
 /// Réponse d'ingestion de document avec métadonnées enrichies
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DocumentIngestionResponse {
@@ -102,6 +133,37 @@ pub struct DocumentIngestionResponse {
     pub business_metadata: Option<BusinessMetadata>,
     pub cache_stats: CacheStats,
     pub confidence_score: f32,
+    pub embeddings_from_cache: usize,
+    pub embeddings_computed: usize,
+    /// Métriques de l'extraction PDF native (absentes pour les autres sources)
+    pub extraction_quality: Option<ExtractionQualityMetrics>,
+    /// Fichier identique déjà ingéré dans le groupe : rien n'a été ré-ingéré (`force` pour passer outre)
+    #[serde(default)]
+    pub duplicate_of: Option<DuplicateDocument>,
+    /// Chunks non indexés faute d'embedding (après une seconde tentative) : document partiellement indexé
+    #[serde(default)]
+    pub chunks_failed_to_embed: Vec<crate::rag::core::ChunkEmbeddingFailure>,
+    /// Chunks plus longs que la fenêtre du modèle d'embedding (fin non représentée)
+    #[serde(default)]
+    pub chunks_truncated: usize,
+    /// Chunks OCR sous `min_chunk_confidence` : conservés pour l'affichage, absents de l'index vectoriel
+    #[serde(default)]
+    pub chunks_dropped_low_confidence: usize,
+    /// Résumé du document (extractif par défaut, LLM si `summary_llm` fourni)
+    #[serde(default)]
+    pub summary: Option<DocumentSummary>,
+}
+
+/// Clé du payload Qdrant et de `EnrichedMetadata.custom_fields` portant l'empreinte blake3 du fichier
+pub const FILE_CHECKSUM_FIELD: &str = "file_checksum";
+
+/// Document déjà ingéré dont le fichier a la même empreinte
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DuplicateDocument {
+    pub document_id: String,
+    pub group_id: String,
+    pub source_file: Option<String>,
+    pub file_checksum: String,
 }
 
 /// Statistiques de cache
@@ -121,6 +183,14 @@ pub struct SearchResponseWithMetadata {
     pub total_results: usize,
     pub search_time_ms: u64,
     pub query_embedding_time_ms: u64,
+    /// Intention détectée, qui a fixé limit/min_score/poids BM25 par défaut
+    pub intent: AnswerIntent,
+    /// Requête additionnelle dans la langue du corpus (`cross_lingual`), si générée
+    #[serde(default)]
+    pub translated_query: Option<String>,
+    /// Réponse réutilisée depuis le cache de requêtes (exact ou sémantique)
+    #[serde(default)]
+    pub cache_hit: Option<QueryCacheHit>,
 }
 
 /// Résultat de recherche individuel avec métadonnées
@@ -129,14 +199,34 @@ pub struct SearchResultWithMetadata {
     pub chunk_id: String,
     pub content: String,
     pub score: f32,
+    /// Similarité cosinus brute renvoyée par Qdrant, avant pondérations hybrides/priorité
+    #[serde(default)]
+    pub cosine_score: f32,
     pub document_id: String,
     pub document_category: DocumentCategory,
     pub source_type: SourceType,
     pub extraction_method: ExtractionMethod,
+    /// Offsets (caractères) du chunk dans le document, `None` si inconnus (points indexés avant ce champ)
+    #[serde(default)]
+    pub char_range: Option<(usize, usize)>,
     pub business_metadata: Option<BusinessMetadata>,
     pub ocr_confidence: Option<f32>,
     pub chunk_metadata: ChunkMetadataSlim,
     pub source_file: Option<String>,
+    pub chunk_source: ChunkSource,
+    pub figure_id: Option<String>,
+    pub fiscal_year: Option<i32>,
+    /// Position du chunk dans la source (page + coordonnées PDF) pour le click-to-locate
+    pub bbox: Option<SourceBoundingBox>,
+    /// Chunks fusionnés dans ce résultat (`merge_overlapping`), vide sinon
+    #[serde(default)]
+    pub merged_chunk_ids: Vec<String>,
+    /// Priorité du document source (Normal pour les points indexés avant ce champ)
+    #[serde(default)]
+    pub document_priority: Priority,
+    /// Valeurs chiffrées du chunk extraites à l'ingestion (vide pour les points indexés avant ce champ)
+    #[serde(default)]
+    pub numeric_facts: Vec<NumericFact>,
 }
 
 /// Métadonnées de chunk simplifiées pour l'API
@@ -161,77 +251,204 @@ pub struct AdvancedSearchParams {
     pub min_ocr_confidence: Option<f32>,
     pub include_business_metadata: bool,
     pub fiscal_year_filter: Option<i32>,
+    /// Fusionner les chunks chevauchants/adjacents d'un même document
+    #[serde(default)]
+    pub merge_overlapping: bool,
+    /// Longueur maximale (caractères) d'un résultat fusionné, `MAX_MERGED_CONTENT_CHARS` par défaut
+    #[serde(default)]
+    pub max_merged_chars: Option<usize>,
+    /// Tags utilisateur requis (tous)
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Priorités de document acceptées
+    #[serde(default)]
+    pub priorities: Option<Vec<Priority>>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Requête dans une autre langue que le corpus : chercher aussi avec sa traduction lexicale
+    #[serde(default)]
+    pub cross_lingual: bool,
+    /// Réutiliser la réponse en cache d'une requête sémantiquement très proche (`SEMANTIC_CACHE_THRESHOLD`)
+    #[serde(default)]
+    pub semantic_cache: bool,
+    /// Chunks contenant une valeur chiffrée dans l'intervalle (et l'unité) demandés
+    #[serde(default)]
+    pub numeric_filter: Option<NumericFactFilter>,
 }
 
+/// Filtre sur les faits numériques indexés (`numeric_facts` du payload)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NumericFactFilter {
+    /// "€", "$", "%", "x" ou "year" ; toute unité si absent
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub gte: Option<f64>,
+    #[serde(default)]
+    pub lte: Option<f64>,
+}
+
+/// Métadonnées utilisateur fournies à l'ingestion (tags, priorité, auteur...)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DocumentUserMetadata {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub priority: Option<Priority>,
+    pub author: Option<String>,
+    pub project: Option<String>,
+    pub description: Option<String>,
+}
+
+impl DocumentUserMetadata {
+    /// Les tags s'ajoutent aux tags automatiques, les autres champs les remplacent s'ils sont fournis
+    pub fn apply_to(&self, metadata: &mut EnrichedMetadata) {
+        for tag in &self.tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !metadata.tags.iter().any(|existing| existing == tag) {
+                metadata.tags.push(tag.to_string());
+            }
+        }
+        if let Some(priority) = self.priority {
+            metadata.priority = priority;
+        }
+        if let Some(ref author) = self.author {
+            metadata.author = Some(author.clone());
+        }
+        if let Some(ref project) = self.project {
+            metadata.project = Some(project.clone());
+        }
+        if let Some(ref description) = self.description {
+            metadata.description = Some(description.clone());
+        }
+    }
+}
+
+/// Longueur maximale par défaut d'un résultat issu de la fusion de chunks
+const MAX_MERGED_CONTENT_CHARS: usize = 4000;
+
+/// Chevauchement textuel minimal (caractères) retiré lors de la concaténation de deux chunks
+const MIN_TEXT_OVERLAP_CHARS: usize = 20;
+
+/// Multiplicateur de score d'un résultat contenant une valeur chiffrée de la requête
+const NUMERIC_FACT_BOOST: f32 = 1.2;
+
 // === Commandes Tauri Phase 3 ===
 
+/// Résoudre le chemin d'un document comme dans extract_document_content
+fn resolve_document_path(file_path: &str) -> Result<PathBuf, String> {
+    if let Some(filename) = file_path.strip_prefix("exemple/") {
+        // Chemin relatif depuis le frontend - résoudre vers le dossier exemple
+        let current_dir = env::current_dir()
+            .map_err(|e| format!("Failed to get current directory: {}", e))?;
+        let docs_path = current_dir.parent()
+            .ok_or("Failed to get parent directory")?
+            .join("exemple");
+        Ok(docs_path.join(filename))
+    } else {
+        // Chemin absolu ou autre - utiliser tel quel
+        Ok(PathBuf::from(file_path))
+    }
+}
+
 /// Ajouter un document avec classification automatique et extraction intelligente
 #[tauri::command]
+#[tracing::instrument(name = "ingest", skip_all, fields(group_id = %group_id, document_id = tracing::field::Empty, file = %file_path))]
 pub async fn add_document_intelligent(
     file_path: String,
     group_id: String,
     extracted_text: Option<String>, // Texte pré-extrait par OCR (si disponible)
+    force: Option<bool>,            // Ré-ingérer même si un fichier identique est déjà dans le groupe
+    user_metadata: Option<DocumentUserMetadata>, // Tags, priorité, auteur, projet, description
+    summary_llm: Option<LlmSynthesisConfig>,     // Résumé abstractif via LLM (opt-in, extractif sinon)
+    min_chunk_confidence: Option<f32>,           // Confiance OCR minimale pour indexer un chunk (défaut : config du groupe)
+    app: tauri::AppHandle,
     state: State<'_, RagState>,
-) -> Result<DocumentIngestionResponse, String> {
+) -> Result<DocumentIngestionResponse, CommandError> {
     let start_time = std::time::Instant::now();
     info!("Adding document intelligently: {} to group {}", file_path, group_id);
 
-    // Résoudre le chemin du fichier comme dans extract_document_content
-    let path = if file_path.starts_with("exemple/") {
-        // Chemin relatif depuis le frontend - résoudre vers le dossier exemple
-        let current_dir = env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?;
-        let docs_path = current_dir.parent()
-            .ok_or("Failed to get parent directory")?
-            .join("exemple");
-        let filename = file_path.strip_prefix("exemple/").unwrap_or(&file_path);
-        docs_path.join(filename)
-    } else {
-        // Chemin absolu ou autre - utiliser tel quel
-        PathBuf::from(file_path.clone())
-    };
+    if let Some(threshold) = min_chunk_confidence.filter(|threshold| !(0.0..=1.0).contains(threshold)) {
+        return Err(CommandError::invalid_input(format!(
+            "min_chunk_confidence must be between 0.0 and 1.0, got {}", threshold
+        )));
+    }
+
+    let path = resolve_document_path(&file_path)?;
     
     info!("📂 Resolved file path: {:?}", path);
     
     if !path.exists() {
-        return Err(format!("File not found: {:?}", path));
+        return Err(CommandError::not_found(format!("File not found: {:?}", path)));
     }
     
     // Vérifier que le groupe existe
     let groups = state.groups.read().await;
     let group = groups.get(&group_id)
-        .ok_or_else(|| format!("Group not found: {}", group_id))?;
+        .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
     let chunk_config = group.chunk_config.clone();
     drop(groups);
+    let min_chunk_confidence = min_chunk_confidence.or(chunk_config.min_chunk_confidence);
+
+    // Doublon : même contenu binaire déjà ingéré dans ce groupe (sous n'importe quel nom)
+    let checksum = file_checksum(path.clone())
+        .await
+        .map_err(|e| CommandError::from(RagError::Io(e)).with_context("Failed to hash document"))?;
+    if !force.unwrap_or(false) {
+        if let Some(duplicate) = find_duplicate_document(&state, &checksum, Some(&group_id)).await? {
+            warn!("♻️ {} is a duplicate of document {} (checksum {}), skipping ingestion",
+                  file_path, duplicate.document_id, &checksum[..12]);
+            return Ok(DocumentIngestionResponse {
+                document_id: duplicate.document_id.clone(),
+                document_category: DocumentCategory::Mixed,
+                chunks_created: 0,
+                extraction_method: ExtractionMethod::DirectRead,
+                source_type: SourceType::NativeText,
+                processing_time_ms: start_time.elapsed().as_millis() as u64,
+                business_metadata: None,
+                cache_stats: get_cache_statistics(&state).await,
+                confidence_score: 0.0,
+                embeddings_from_cache: 0,
+                embeddings_computed: 0,
+                extraction_quality: None,
+                duplicate_of: Some(duplicate),
+                chunks_failed_to_embed: Vec::new(),
+                chunks_truncated: 0,
+                chunks_dropped_low_confidence: 0,
+                summary: None,
+            });
+        }
+    }
 
     // Si du texte pré-extrait est fourni, l'utiliser directement
     let document = if let Some(preextracted_text) = extracted_text {
         info!("📄 Using pre-extracted text ({} chars)", preextracted_text.len());
 
+        let meaningful_chars = crate::rag::processing::meaningful_char_count(&preextracted_text);
+        if meaningful_chars < crate::rag::processing::DEFAULT_MIN_MEANINGFUL_CHARS {
+            return Err(RagError::EmptyExtraction {
+                path: path.display().to_string(),
+                meaningful_chars,
+            }.into());
+        }
+
         // Créer un document directement depuis le texte pré-extrait
-        use crate::rag::{GroupDocument, DocumentType, EnrichedChunk, ChunkType, ChunkMetadata, Priority, SourceType, ExtractionMethod, EnrichedMetadata};
+        use crate::rag::{GroupDocument, DocumentType, ChunkType, ChunkMetadata, Priority, SourceType, ExtractionMethod, EnrichedMetadata};
         use std::collections::HashMap;
 
         // Chunking intelligent du texte pré-extrait avec la configuration spécifiée
         use crate::rag::processing::smart_chunker::{SmartChunker, SmartChunkConfig};
 
-        // Convertir chunk_size (caractères) en tokens (approximativement 4 chars = 1 token)
-        let target_tokens = chunk_config.chunk_size / 4;
-        let overlap_percent = (chunk_config.overlap as f32 / chunk_config.chunk_size as f32) * 100.0;
-
-        let smart_config = SmartChunkConfig {
-            target_tokens,
-            overlap_percent,
-            min_tokens: target_tokens / 2,
-            max_tokens: target_tokens + 100,
-            chars_per_token: 4.0,
-            overlap_target_ratio: None,
-            mmr_lambda: 0.5,
-            max_context_docs: 10,
-        };
+        use crate::rag::processing::ast_chunker::CodeLanguage;
+
+        // chunk_size/overlap du groupe sont en tokens (cf. ChunkConfig)
+        let smart_config = SmartChunkConfig::from_chunk_config(&chunk_config);
 
         let mut chunker = SmartChunker::new(smart_config)
-            .map_err(|e| format!("Failed to create chunker: {}", e))?;
+            .map_err(|e| format!("Failed to create chunker: {}", e))?
+            .with_strategy(chunk_config.strategy)
+            .with_code_language(CodeLanguage::from_path(&path));
 
         let extraction_method = ExtractionMethod::TesseractOcr {
             confidence: 0.85,
@@ -242,12 +459,27 @@ pub async fn add_document_intelligent(
             .chunk_document(&preextracted_text, SourceType::OcrExtracted, &extraction_method, &group_id)
             .map_err(|e| format!("Failed to chunk text: {}", e))?;
 
-        info!("📊 Smart chunking created {} chunks (avg: {:.0} chars, detected {} sections)",
-              smart_result.chunks.len(), smart_result.avg_chunk_size, smart_result.sections_detected.len());
-
-        let chunks = smart_result.chunks;
+        info!("📊 Smart chunking created {} chunks (avg: {:.0} chars, detected {} sections, strategy {:?})",
+              smart_result.chunks.len(), smart_result.avg_chunk_size, smart_result.sections_detected.len(),
+              smart_result.strategy_used);
+
+        let mut chunks = smart_result.chunks;
+
+        // Vision-Aware RAG: légendes et zones de figures, comme process_document pour les PDF
+        let is_pdf = path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+        if is_pdf {
+            let figure_chunks = crate::rag::processing::build_figure_chunks_from_text(&preextracted_text, &group_id).await;
+            if !figure_chunks.is_empty() {
+                info!("🖼️ Added {} figure chunks from pre-extracted text", figure_chunks.len());
+                chunks.extend(figure_chunks);
+            }
+        }
 
-        let document_id = format!("doc_{}", uuid::Uuid::new_v4().simple());
+        // Identifiants stables : une ré-ingestion du même fichier et du même texte réécrit les mêmes points
+        let document_id = crate::rag::processing::deterministic_document_id(&checksum, &preextracted_text, &group_id);
+        crate::rag::processing::assign_deterministic_chunk_ids(&mut chunks, &document_id, &group_id);
         let now = SystemTime::now();
 
         GroupDocument {
@@ -273,40 +505,81 @@ pub async fn add_document_intelligent(
             },
             group_id: group_id.clone(),
             ocr_blocks: Vec::new(),  // No OCR blocks for pre-extracted text
+            business_metadata: None,
         }
     } else {
         // Processing intelligent avec classification automatique
         info!("📄 Extracting text from document...");
+        let progress = ocr_progress_emitter(app, file_path.clone());
         let doc_result = state.ingestion_engine
-            .ingest_document(&path, &group_id, &chunk_config)
+            .ingest_document_with_progress(&path, &group_id, &chunk_config, Some(&progress))
             .await
-            .map_err(|e| format!("Document processing failed: {}", e))?;
+            .map_err(|e| CommandError::from(e).with_context("Document processing failed"))?;
         doc_result.document
     };
 
+    tracing::Span::current().record("document_id", document.id.as_str());
+
     // === GÉNÉRATION DES EMBEDDINGS ===
     info!("🧮 Generating embeddings for {} chunks", document.chunks.len());
     let mut document_with_embeddings = document.clone();
-    let mut embedded_count = 0;
-
-    for chunk in &mut document_with_embeddings.chunks {
-        // Ignorer les chunks vides ou d'erreur
-        if !chunk.content.trim().is_empty()
-            && !chunk.content.starts_with("EXTRACTION FAILED") {
-            // Utiliser encode_document pour les documents (préfixe "passage:")
-            match state.embedder.encode_document(&chunk.content).await {
-                Ok(embedding) => {
-                    chunk.embedding = Some(embedding);
-                    embedded_count += 1;
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to embed chunk {}: {}", chunk.id, e);
-                }
+    document_with_embeddings.metadata.custom_fields.insert(FILE_CHECKSUM_FIELD.to_string(), checksum.clone());
+    if let Some(ref user_metadata) = user_metadata {
+        user_metadata.apply_to(&mut document_with_embeddings.metadata);
+        info!("🏷️ User metadata applied: tags {:?}, priority {:?}",
+              document_with_embeddings.metadata.tags, document_with_embeddings.metadata.priority);
+    }
+
+    // === RÉSUMÉ DU DOCUMENT ===
+    let summary = document_summary(&state.unified_cache, &document_with_embeddings.content, summary_llm.as_ref()).await;
+    if let Some(ref summary) = summary {
+        document_with_embeddings.metadata.custom_fields.insert(DOCUMENT_SUMMARY_FIELD.to_string(), summary.text.clone());
+    }
+
+    // Garde-fou : au-delà de la fenêtre du modèle, la fin d'un chunk serait ignorée à l'embedding
+    let embedder = state.embedder().await;
+    let (guarded_chunks, chunks_truncated) = crate::rag::processing::enforce_chunk_token_limit(
+        std::mem::take(&mut document_with_embeddings.chunks),
+        embedder.max_input_tokens(),
+        chunk_config.oversized_chunks,
+        |text| embedder.count_tokens(text),
+    );
+    document_with_embeddings.chunks = guarded_chunks;
+    if !chunks_truncated.is_empty() {
+        warn!("✂️ {} chunks exceed the embedding limit of {} tokens and will be truncated: {:?}",
+              chunks_truncated.len(), embedder.max_input_tokens(), chunks_truncated);
+    }
+
+    // Chunks OCR peu fiables : texte conservé pour l'affichage, mais ni embeddés ni indexés
+    let low_confidence_chunks = match min_chunk_confidence {
+        Some(threshold) => {
+            let (indexable, dropped) = crate::rag::processing::split_low_confidence_chunks(
+                std::mem::take(&mut document_with_embeddings.chunks),
+                threshold,
+            );
+            document_with_embeddings.chunks = indexable;
+            if !dropped.is_empty() {
+                info!("🧹 {} OCR chunks below confidence {:.2} excluded from the vector index", dropped.len(), threshold);
             }
+            dropped
         }
-    }
+        None => Vec::new(),
+    };
 
-    info!("✅ Generated {} embeddings", embedded_count);
+    // Cache par hash de chunk : une ré-ingestion ou un chunk dupliqué ne recalcule rien
+    let embedding_stats = state.unified_cache
+        .embed_chunks(&embedder, &mut document_with_embeddings.chunks)
+        .await;
+    let embedded_count = embedding_stats.total();
+    let chunks_dropped_low_confidence = low_confidence_chunks.len();
+    document_with_embeddings.chunks.extend(low_confidence_chunks);
+
+    info!("✅ Generated {} embeddings ({} from cache, {} computed)",
+          embedded_count, embedding_stats.embeddings_from_cache, embedding_stats.embeddings_computed);
+    if !embedding_stats.failed_chunks.is_empty() {
+        warn!("⚠️ Document partially indexed: {}/{} chunks failed to embed",
+              embedding_stats.failed_chunks.len(), document_with_embeddings.chunks.len());
+    }
 
     // === CLASSIFICATION AVANT INJECTION ===
     // Classification automatique du contenu
@@ -319,28 +592,81 @@ pub async fn add_document_intelligent(
     // === INJECTION DANS QDRANT ===
     if embedded_count > 0 {
         let groups_read = state.groups.read().await;
-        let collection_name = groups_read.get(&group_id)
-            .map(|g| g.qdrant_collection.clone())
-            .ok_or_else(|| format!("Group not found: {}", group_id))?;
+        let (collection_name, collection_config) = groups_read.get(&group_id)
+            .map(|g| (g.qdrant_collection.clone(), g.collection_config))
+            .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
         drop(groups_read);
 
         info!("💾 Upserting {} chunks to Qdrant: {}", embedded_count, collection_name);
 
-        // Créer la collection si elle n'existe pas (384D pour E5-small-v2)
-        let _ = state.qdrant_client.create_collection(&collection_name, 384, "Cosine").await;
+        // Exercice fiscal du document, filtrable à la recherche (fiscal_year_filter)
+        let fiscal_year = state.business_enricher.detect_fiscal_year(&document_with_embeddings.content);
+        if let Some(year) = fiscal_year {
+            info!("📅 Fiscal year detected: {}", year);
+        }
 
-        // Convertir en points Qdrant
+        // Créer la collection si elle n'existe pas (384D pour E5-small-v2)
+        let _ = state.qdrant_client.create_collection(
+            &collection_name,
+            collection_config.dimension as u64,
+            collection_config.distance.as_qdrant_str(),
+        ).await;
+
+        // Une collection existante peut avoir été créée pour un autre modèle : refuser plutôt qu'un 400 Qdrant
+        let existing_dimension = state.qdrant_client
+            .collection_info(&collection_name)
+            .await
+            .ok()
+            .and_then(|info| info["result"]["config"]["params"]["vectors"]["size"].as_u64())
+            .map(|size| size as usize);
+        validate_embedding_dimensions(
+            &document_with_embeddings.chunks,
+            existing_dimension.unwrap_or(collection_config.dimension),
+            &collection_name,
+        ).map_err(CommandError::invalid_input)?;
+
+        // Offsets réels des chunks dans le document (explicabilité), absents si introuvables
+        let chunk_ranges = crate::rag::locate_chunks(&document_with_embeddings.content, &document_with_embeddings.chunks);
+        let located_blocks = locate_ocr_blocks(&document_with_embeddings.content, &document_with_embeddings.ocr_blocks);
+
+        // Convertir en points Qdrant (jamais de chunk sans contenu : vecteur inutile qui pollue la recherche)
         let points: Vec<crate::rag::RestPoint> = document_with_embeddings.chunks
             .iter()
             .enumerate()
+            .filter(|(_, chunk)| !chunk.content.trim().is_empty())
             .filter_map(|(idx, chunk)| {
                 chunk.embedding.as_ref().map(|emb| {
+                    // Fin de la page précédente : embeddée avec le chunk, mais ni stockée ni indexée deux fois
+                    let own_content = crate::rag::strip_page_overlap(chunk);
                     let mut payload = HashMap::new();
-                    payload.insert("content".to_string(), serde_json::json!(chunk.content));
+                    payload.insert("content".to_string(), serde_json::json!(own_content));
                     payload.insert("document_id".to_string(), serde_json::json!(document_with_embeddings.id));
                     payload.insert("group_id".to_string(), serde_json::json!(group_id));
                     payload.insert("confidence".to_string(), serde_json::json!(chunk.metadata.confidence));
                     payload.insert("chunk_id".to_string(), serde_json::json!(chunk.id.clone()));
+                    payload.insert(FILE_CHECKSUM_FIELD.to_string(), serde_json::json!(checksum));
+                    if let Some(ref summary) = summary {
+                        payload.insert(DOCUMENT_SUMMARY_FIELD.to_string(), serde_json::json!(summary.text));
+                    }
+                    payload.insert("start_line".to_string(), serde_json::json!(chunk.start_line));
+                    payload.insert("end_line".to_string(), serde_json::json!(chunk.end_line));
+                    payload.insert("source_type".to_string(), serde_json::json!(chunk.metadata.source_type));
+                    payload.insert("extraction_method".to_string(), serde_json::json!(chunk.metadata.extraction_method));
+                    if let Some((char_start, char_end)) = chunk_ranges[idx] {
+                        payload.insert("char_start".to_string(), serde_json::json!(char_start));
+                        payload.insert("char_end".to_string(), serde_json::json!(char_end));
+                    }
+                    insert_chunk_source_fields(&mut payload, chunk);
+                    if let Some(bbox) = chunk_ranges[idx].and_then(|range| chunk_bounding_box(range, &located_blocks)) {
+                        payload.insert("bbox".to_string(), serde_json::json!(bbox));
+                    }
+                    if let Some(year) = fiscal_year {
+                        payload.insert("fiscal_year".to_string(), serde_json::json!(year));
+                    }
+                    let numeric_facts = extract_numeric_facts(own_content);
+                    if !numeric_facts.is_empty() {
+                        payload.insert(NUMERIC_FACTS_FIELD.to_string(), serde_json::json!(numeric_facts));
+                    }
 
                     // Ajouter le nom du fichier source pour l'affichage dans l'interface
                     if let Some(filename) = document_with_embeddings.file_path.file_name() {
@@ -356,8 +682,11 @@ pub async fn add_document_intelligent(
                     if let Some(ref author) = document_with_embeddings.metadata.author {
                         payload.insert("document_author".to_string(), serde_json::json!(author));
                     }
+                    if let Some(ref project) = document_with_embeddings.metadata.project {
+                        payload.insert("document_project".to_string(), serde_json::json!(project));
+                    }
                     payload.insert("document_tags".to_string(), serde_json::json!(document_with_embeddings.metadata.tags));
-                    payload.insert("document_priority".to_string(), serde_json::json!(format!("{:?}", document_with_embeddings.metadata.priority)));
+                    payload.insert("document_priority".to_string(), serde_json::json!(document_with_embeddings.metadata.priority));
                     payload.insert("document_category".to_string(), serde_json::json!(format!("{:?}", document_category)));
 
                     // Générer UUID reproductible à partir du chunk.id en utilisant blake3
@@ -377,10 +706,18 @@ pub async fn add_document_intelligent(
             })
             .collect();
 
+        let point_ids: Vec<serde_json::Value> = points.iter().map(|point| point.id.clone()).collect();
         state.qdrant_client
             .upsert_points(&collection_name, points)
             .await
-            .map_err(|e| format!("Qdrant upsert failed: {}", e))?;
+            .map_err(|e| CommandError::service_unavailable(format!("Qdrant upsert failed: {}", e)))?;
+
+        // Ré-ingestion : seuls les points de l'ingestion précédente absents du nouveau découpage sont
+        // retirés, après l'upsert (un échec ne laisse jamais le document sans points)
+        state.qdrant_client
+            .delete_points_by_filter(&collection_name, &stale_points_filter(&document_with_embeddings.id, &point_ids))
+            .await
+            .map_err(|e| CommandError::service_unavailable(format!("Qdrant stale points cleanup failed: {}", e)))?;
 
         info!("✅ Successfully stored {} chunks in Qdrant", embedded_count);
     }
@@ -403,12 +740,19 @@ pub async fn add_document_intelligent(
     // Confiance globale basée sur extraction + classification
     let confidence_score = calculate_global_confidence(&document_with_embeddings, &business_metadata);
 
+    let extraction_quality = document_with_embeddings.metadata.custom_fields
+        .get(EXTRACTION_QUALITY_FIELD)
+        .and_then(|json| serde_json::from_str::<ExtractionQualityMetrics>(json).ok());
+
+    document_with_embeddings.business_metadata = business_metadata.clone();
+
     // Mettre à jour le groupe avec le nouveau document (avec embeddings)
     let mut groups = state.groups.write().await;
     if let Some(group) = groups.get_mut(&group_id) {
-        group.documents.push(document_with_embeddings.clone());
+        upsert_group_document(&mut group.documents, document_with_embeddings.clone());
         group.updated_at = SystemTime::now();
     }
+    state.query_cache.write().await.invalidate_group(&group_id);
 
     info!("Document processed successfully: {} chunks, category: {:?}, confidence: {:.3}",
           document_with_embeddings.chunks.len(), document_category, confidence_score);
@@ -426,68 +770,281 @@ pub async fn add_document_intelligent(
         } else {
             ExtractionMethod::DirectRead
         },
-        source_type: match &document_with_embeddings.document_type {
-            crate::rag::DocumentType::PDF { extraction_strategy, .. } => {
-                match extraction_strategy {
-                    crate::rag::PdfStrategy::NativeOnly => SourceType::NativeText,
-                    crate::rag::PdfStrategy::OcrOnly => SourceType::OcrExtracted,
-                    crate::rag::PdfStrategy::HybridIntelligent => SourceType::HybridPdfNative,
-                }
-            },
-            crate::rag::DocumentType::Image { .. } => SourceType::OcrExtracted,
-            _ => SourceType::NativeText,
-        },
+        source_type: document_source_type(&document_with_embeddings),
         processing_time_ms: processing_time,
         business_metadata,
         cache_stats,
         confidence_score,
+        embeddings_from_cache: embedding_stats.embeddings_from_cache,
+        embeddings_computed: embedding_stats.embeddings_computed,
+        extraction_quality,
+        duplicate_of: None,
+        chunks_failed_to_embed: embedding_stats.failed_chunks,
+        chunks_truncated: chunks_truncated.len(),
+        chunks_dropped_low_confidence,
+        summary,
+    })
+}
+
+/// Résumé du document, calculé une seule fois par contenu et méthode (cache unifié)
+async fn document_summary(
+    cache: &UnifiedCache,
+    content: &str,
+    llm: Option<&LlmSynthesisConfig>,
+) -> Option<DocumentSummary> {
+    let method = llm.map_or(SummaryMethod::Extractive, |config| SummaryMethod::Llm(config.model.clone()));
+    let method_key = match &method {
+        SummaryMethod::Extractive => "extractive".to_string(),
+        SummaryMethod::Llm(model) => format!("llm:{}", model),
+    };
+    let cache_key = UnifiedCache::summary_cache_key(&method_key, content);
+    if let Some(text) = cache.get_cached_summary(&cache_key) {
+        debug!("📝 Document summary from cache ({})", method_key);
+        return Some(DocumentSummary { text, method });
+    }
+
+    let summary = summarize_document(content, llm).await?;
+    info!("📝 Document summary generated ({} chars, {:?})", summary.text.chars().count(), summary.method);
+    // Un repli extractif après échec LLM n'est pas mémorisé sous la clé LLM : nouvel essai à la prochaine ingestion
+    if summary.method == method {
+        cache.cache_summary(&cache_key, summary.text.clone());
+    }
+    Some(summary)
+}
+
+/// Relayer la progression OCR d'un document au frontend (`OCR_PROGRESS_EVENT`, avec le nom du fichier)
+pub fn ocr_progress_emitter(app: tauri::AppHandle, filename: String) -> crate::rag::ocr::OcrProgressCallback {
+    Arc::new(move |progress| {
+        use tauri::Emitter;
+
+        let mut payload = serde_json::json!(progress);
+        payload["filename"] = serde_json::json!(filename);
+        if let Err(e) = app.emit(crate::rag::ocr::OCR_PROGRESS_EVENT, payload) {
+            warn!("Failed to emit OCR progress: {}", e);
+        }
+    })
+}
+
+/// Filtre Qdrant des points d'un document qui ne font plus partie de sa dernière ingestion
+fn stale_points_filter(document_id: &str, current_point_ids: &[serde_json::Value]) -> serde_json::Value {
+    serde_json::json!({
+        "must": [{ "key": "document_id", "match": { "value": document_id } }],
+        "must_not": [{ "has_id": current_point_ids }]
+    })
+}
+
+/// Remplacer l'entrée d'un document ré-ingéré (même ID) plutôt que d'en ajouter un doublon
+fn upsert_group_document(documents: &mut Vec<GroupDocument>, document: GroupDocument) {
+    match documents.iter_mut().find(|existing| existing.id == document.id) {
+        Some(existing) => *existing = document,
+        None => documents.push(document),
+    }
+}
+
+/// Empreinte blake3 du contenu binaire d'un fichier (lecture en flux, hors runtime async)
+pub async fn file_checksum(path: PathBuf) -> std::io::Result<String> {
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)?;
+        let mut hasher = blake3::Hasher::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(hasher.finalize().to_hex().to_string())
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+/// Chercher un document déjà ingéré avec cette empreinte, dans un groupe ou dans tous.
+///
+/// Les documents en RAM sont consultés d'abord, puis les collections Qdrant
+/// (documents ingérés lors d'une session précédente).
+pub async fn find_duplicate_document(
+    state: &RagState,
+    checksum: &str,
+    group_id: Option<&str>,
+) -> Result<Option<DuplicateDocument>, CommandError> {
+    let in_scope = |group: &DocumentGroup| match group_id {
+        Some(id) => group.id == id,
+        None => true,
+    };
+
+    let collections: Vec<(String, String)> = {
+        let groups = state.groups.read().await;
+        if let Some(duplicate) = find_duplicate_in_groups(&groups, checksum, group_id) {
+            return Ok(Some(duplicate));
+        }
+        groups.values()
+            .filter(|g| in_scope(g))
+            .map(|g| (g.id.clone(), g.qdrant_collection.clone()))
+            .collect()
+    };
+
+    let request = duplicate_lookup_request(checksum);
+    for (group_id, collection_name) in collections {
+        let data = state.qdrant_client
+            .scroll_points(&collection_name, &request)
+            .await
+            .map_err(|e| CommandError::service_unavailable(format!("Qdrant duplicate lookup failed: {:#}", e)))?;
+
+        let point = data.as_ref()
+            .and_then(|d| d["result"]["points"].as_array())
+            .and_then(|points| points.first());
+        if let Some(point) = point {
+            return Ok(Some(DuplicateDocument {
+                document_id: point["payload"]["document_id"].as_str().unwrap_or("unknown").to_string(),
+                group_id,
+                source_file: point["payload"]["source_file"].as_str().map(|s| s.to_string()),
+                file_checksum: checksum.to_string(),
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Document en RAM portant cette empreinte, dans le groupe demandé ou dans tous
+fn find_duplicate_in_groups(
+    groups: &HashMap<String, DocumentGroup>,
+    checksum: &str,
+    group_id: Option<&str>,
+) -> Option<DuplicateDocument> {
+    groups.values()
+        .filter(|group| group_id.is_none() || group_id == Some(group.id.as_str()))
+        .find_map(|group| {
+            group.documents.iter()
+                .find(|doc| doc.metadata.custom_fields.get(FILE_CHECKSUM_FIELD).map(String::as_str) == Some(checksum))
+                .map(|doc| DuplicateDocument {
+                    document_id: doc.id.clone(),
+                    group_id: group.id.clone(),
+                    source_file: doc.file_path.file_name().map(|name| name.to_string_lossy().to_string()),
+                    file_checksum: checksum.to_string(),
+                })
+        })
+}
+
+/// Scroll Qdrant d'un point portant l'empreinte (payload minimal, sans vecteur)
+fn duplicate_lookup_request(checksum: &str) -> serde_json::Value {
+    serde_json::json!({
+        "limit": 1,
+        "with_payload": { "include": ["document_id", "source_file"] },
+        "with_vector": false,
+        "filter": { "must": [{ "key": FILE_CHECKSUM_FIELD, "match": { "value": checksum } }] }
     })
 }
 
 /// Recherche avancée avec filtres de métadonnées
 #[tauri::command]
+#[tracing::instrument(name = "search", skip_all, fields(group_id = %params.group_id))]
 pub async fn search_with_metadata(
     params: AdvancedSearchParams,
     state: State<'_, RagState>,
-) -> Result<SearchResponseWithMetadata, String> {
+) -> Result<SearchResponseWithMetadata, CommandError> {
     let start_time = std::time::Instant::now();
     info!("Advanced search with metadata: '{}' in group {}", params.query, params.group_id);
 
+    // Intention de la requête → paramètres de retrieval par défaut (les paramètres explicites priment)
+    let intent = detect_answer_intent(&params.query);
+    let profile = intent.retrieval_profile();
+    let min_score = params.min_score.unwrap_or(profile.min_score);
+    info!("🎯 Query intent: {:?} (limit {}, min_score {:.2}, bm25 {:.1})",
+          intent, params.limit.unwrap_or(profile.limit), min_score, profile.bm25_weight);
+
+    // Même requête (normalisée) avec les mêmes paramètres : réponse en cache, sans embedding
+    let fingerprint = search_params_fingerprint(&params);
+    let exact_hit = state.query_cache.write().await.get_exact(&params.group_id, &params.query, &fingerprint);
+    if let Some((response, hit)) = exact_hit {
+        info!("⚡ Query cache hit (exact) for '{}', {}ms old", params.query, hit.age_ms);
+        return Ok(SearchResponseWithMetadata {
+            search_time_ms: start_time.elapsed().as_millis() as u64,
+            query_embedding_time_ms: 0,
+            cache_hit: Some(hit),
+            ..response
+        });
+    }
+
     // Générer embedding de la requête
     let embedding_start = std::time::Instant::now();
-    let query_embedding = state.embedder
+    let query_embedding = state.embedder().await
         .encode(&params.query)
         .await
         .map_err(|e| format!("Query embedding failed: {}", e))?;
     let query_embedding_time = embedding_start.elapsed().as_millis() as u64;
 
+    if params.semantic_cache {
+        let semantic_hit = state.query_cache.write().await
+            .get_semantic(&params.group_id, &query_embedding, &fingerprint, SEMANTIC_CACHE_THRESHOLD);
+        if let Some((response, hit)) = semantic_hit {
+            info!("⚡ Query cache hit (semantic {:.3}) for '{}' ~ '{}'", hit.similarity, params.query, hit.cached_query);
+            return Ok(SearchResponseWithMetadata {
+                search_time_ms: start_time.elapsed().as_millis() as u64,
+                query_embedding_time_ms: query_embedding_time,
+                cache_hit: Some(hit),
+                ..response
+            });
+        }
+    }
+
     // Récupérer le nom de la collection
     let groups = state.groups.read().await;
-    let collection_name = if let Some(group) = groups.get(&params.group_id) {
-        group.qdrant_collection.clone()
+    let Some(group) = groups.get(&params.group_id) else {
+        return Err(CommandError::not_found(format!("Group not found: {}", params.group_id)));
+    };
+    let collection_name = group.qdrant_collection.clone();
+
+    // Requête cross-lingue : traduction vers la langue dominante du corpus si elle diffère
+    let translated_query = if params.cross_lingual {
+        let corpus_language = dominant_language(group.documents.iter().map(|doc| doc.content.as_str()));
+        match (detect_language(&params.query), corpus_language) {
+            (Some(query_language), Some(corpus_language)) if query_language != corpus_language => {
+                translate_query(&params.query, query_language, corpus_language)
+            }
+            _ => None,
+        }
     } else {
-        return Err(format!("Group not found: {}", params.group_id));
+        None
     };
     drop(groups);
 
     // Rechercher dans Qdrant avec l'embedding de la requête
-    let limit = params.limit.unwrap_or(10);
-    let search_url = format!("http://localhost:6333/collections/{}/points/search", collection_name);
-    let client = reqwest::Client::new();
-
-    let search_response = client
-        .post(&search_url)
-        .json(&serde_json::json!({
-            "vector": query_embedding,
-            "limit": limit,
-            "with_payload": true
-        }))
-        .send()
+    let limit = params.limit.unwrap_or(profile.limit);
+    let mut search_request = serde_json::json!({
+        "vector": query_embedding,
+        "limit": limit,
+        "with_payload": true
+    });
+    // Filtres durs (exercice, tags, priorité, auteur, projet, catégorie, type de source) : les points sans le champ sont exclus
+    if let Some(filter) = build_search_filter(&params) {
+        search_request["filter"] = filter;
+    }
+
+    let search_data = state.qdrant_client
+        .search_raw(&collection_name, &search_request)
         .await
-        .map_err(|e| format!("Qdrant search request failed: {}", e))?;
+        .map_err(|e| CommandError::service_unavailable(format!("Qdrant search failed: {:#}", e)))?;
+
+    // Seconde recherche avec la requête traduite, fusionnée avec la première (meilleur score par point)
+    let search_data = match (search_data, &translated_query) {
+        (Some(mut search_data), Some(translated)) => {
+            info!("🌐 Cross-lingual search: '{}' -> '{}'", params.query, translated);
+            match state.embedder().await.encode(translated).await {
+                Ok(translated_embedding) => {
+                    search_request["vector"] = serde_json::json!(translated_embedding);
+                    match state.qdrant_client.search_raw(&collection_name, &search_request).await {
+                        Ok(Some(translated_data)) => {
+                            search_data["result"] = merge_qdrant_hits(&search_data["result"], &translated_data["result"], limit);
+                        }
+                        Ok(None) => {}
+                        Err(e) => warn!("⚠️ Translated query search failed: {:#}", e),
+                    }
+                }
+                Err(e) => warn!("⚠️ Translated query embedding failed: {}", e),
+            }
+            Some(search_data)
+        }
+        (search_data, _) => search_data,
+    };
 
     // Si la collection n'existe pas (404), retourner des résultats vides
-    if search_response.status() == 404 {
+    let Some(search_data) = search_data else {
         info!("📭 Collection {} does not exist yet (no documents)", collection_name);
         let search_time = start_time.elapsed().as_millis() as u64;
         return Ok(SearchResponseWithMetadata {
@@ -495,15 +1052,11 @@ pub async fn search_with_metadata(
             total_results: 0,
             search_time_ms: search_time,
             query_embedding_time_ms: query_embedding_time,
+            intent,
+            translated_query,
+            cache_hit: None,
         });
-    }
-
-    if !search_response.status().is_success() {
-        return Err(format!("Qdrant search returned error: {}", search_response.status()));
-    }
-
-    let search_data: serde_json::Value = search_response.json().await
-        .map_err(|e| format!("Failed to parse Qdrant search response: {}", e))?;
+    };
 
     let search_results = search_data["result"].as_array()
         .ok_or_else(|| "Invalid Qdrant search response format".to_string())?;
@@ -515,10 +1068,8 @@ pub async fn search_with_metadata(
         let score = qdrant_result["score"].as_f64().unwrap_or(0.0) as f32;
 
         // Filtrer par score minimum
-        if let Some(min_score) = params.min_score {
-            if score < min_score {
-                continue;
-            }
+        if score < min_score {
+            continue;
         }
 
         let payload = match qdrant_result["payload"].as_object() {
@@ -545,26 +1096,43 @@ pub async fn search_with_metadata(
             .and_then(|v| v.as_f64())
             .unwrap_or(0.85) as f32;
 
-        // Classification du contenu si demandé
-        let document_category = if params.include_business_metadata || params.document_categories.is_some() {
-            state.document_classifier
-                .classify(&content)
-                .unwrap_or(DocumentCategory::Mixed)
-        } else {
-            DocumentCategory::Mixed
+        // Lignes source (0 pour les points indexés avant ce champ)
+        let line = |key: &str| payload.get(key)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize;
+        let (start_line, end_line) = (line("start_line"), line("end_line"));
+
+        // Catégorie du document (payload, déjà filtrée par Qdrant), sinon classification du contenu si demandée
+        let document_category = payload.get("document_category")
+            .and_then(|v| serde_json::from_value::<DocumentCategory>(v.clone()).ok())
+            .unwrap_or_else(|| if params.include_business_metadata {
+                state.document_classifier
+                    .classify(&content)
+                    .unwrap_or(DocumentCategory::Mixed)
+            } else {
+                DocumentCategory::Mixed
+            });
+
+        // Type de source (OCR par défaut pour les points indexés avant ce champ)
+        let source_type = payload.get("source_type")
+            .and_then(|v| serde_json::from_value::<SourceType>(v.clone()).ok())
+            .unwrap_or(SourceType::OcrExtracted);
+        let extraction_method = payload.get("extraction_method")
+            .and_then(|v| serde_json::from_value::<ExtractionMethod>(v.clone()).ok())
+            .unwrap_or_else(|| legacy_extraction_method(&source_type, confidence));
+        let char_range = match (payload.get("char_start").and_then(|v| v.as_u64()), payload.get("char_end").and_then(|v| v.as_u64())) {
+            (Some(start), Some(end)) => Some((start as usize, end as usize)),
+            _ => None,
         };
 
-        // Filtrer par catégorie si spécifié
-        if let Some(ref categories) = params.document_categories {
-            if !categories.contains(&document_category) {
-                continue;
-            }
-        }
+        let fiscal_year = payload.get("fiscal_year")
+            .and_then(|v| v.as_i64())
+            .map(|year| year as i32);
 
         // Enrichir avec métadonnées Business si demandé
         let business_metadata = if params.include_business_metadata && matches!(document_category, DocumentCategory::Business) {
             state.business_enricher
-                .enrich_business_content(&content, params.fiscal_year_filter, None)
+                .enrich_business_content(&content, fiscal_year, None)
                 .ok()
         } else {
             None
@@ -575,42 +1143,170 @@ pub async fn search_with_metadata(
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        let (chunk_source, figure_id) = chunk_source_from_payload(payload);
+        let bbox = payload.get("bbox")
+            .and_then(|v| serde_json::from_value::<SourceBoundingBox>(v.clone()).ok());
+        let document_priority = payload.get("document_priority")
+            .and_then(|v| serde_json::from_value::<Priority>(v.clone()).ok())
+            .unwrap_or_default();
+        let numeric_facts = payload.get(NUMERIC_FACTS_FIELD)
+            .and_then(|v| serde_json::from_value::<Vec<NumericFact>>(v.clone()).ok())
+            .unwrap_or_default();
+
         let search_result = SearchResultWithMetadata {
             chunk_id,
             content,
             score,
+            cosine_score: score,
             document_id,
             document_category,
-            source_type: SourceType::OcrExtracted, // Par défaut (stocké dans Qdrant)
-            extraction_method: ExtractionMethod::TesseractOcr {
-                confidence: confidence,
-                language: "fra+eng".to_string(),
-            },
+            source_type,
+            extraction_method,
+            char_range,
             business_metadata,
             ocr_confidence: Some(confidence),
             chunk_metadata: ChunkMetadataSlim {
                 tags: vec!["rag".to_string()],
                 language: "auto".to_string(),
                 confidence,
-                start_line: 0,
-                end_line: 0,
+                start_line,
+                end_line,
             },
             source_file,
+            chunk_source,
+            figure_id,
+            fiscal_year,
+            bbox,
+            merged_chunk_ids: Vec::new(),
+            document_priority,
+            numeric_facts,
         };
 
         results.push(search_result);
     }
 
+    // Le score lexical porte sur les deux formulations (termes du corpus présents dans la traduction)
+    let lexical_query = match &translated_query {
+        Some(translated) => format!("{} {}", params.query, translated),
+        None => params.query.clone(),
+    };
+    apply_hybrid_weights(&mut results, &lexical_query, profile.bm25_weight);
+    // Les embeddings distinguent mal "16x" de "10x" : la valeur exacte demandée est remontée
+    apply_numeric_fact_boost(&mut results, &extract_numeric_facts(&params.query));
+    let priority_weights = *state.priority_weights.read().await;
+    apply_priority_weights(&mut results, &priority_weights);
+
+    if params.merge_overlapping {
+        let before = results.len();
+        results = merge_overlapping_results(results, params.max_merged_chars.unwrap_or(MAX_MERGED_CONTENT_CHARS));
+        if results.len() < before {
+            info!("🧩 Merged overlapping chunks: {} -> {} results", before, results.len());
+        }
+    }
+
     let search_time = start_time.elapsed().as_millis() as u64;
 
     info!("Search completed: {} results in {}ms", results.len(), search_time);
 
-    Ok(SearchResponseWithMetadata {
+    let response = SearchResponseWithMetadata {
         total_results: results.len(),
         results,
         search_time_ms: search_time,
         query_embedding_time_ms: query_embedding_time,
-    })
+        intent,
+        translated_query,
+        cache_hit: None,
+    };
+    state.query_cache.write().await
+        .insert(&params.group_id, &params.query, &fingerprint, query_embedding, response.clone());
+    Ok(response)
+}
+
+/// Méthode d'extraction des points indexés avant le champ `extraction_method`, déduite du type de source
+/// (langue OCR inconnue)
+fn legacy_extraction_method(source_type: &SourceType, confidence: f32) -> ExtractionMethod {
+    match source_type {
+        SourceType::NativeText => ExtractionMethod::DirectRead,
+        SourceType::HybridPdfNative => ExtractionMethod::PdfNative,
+        SourceType::HybridPdfOcr => ExtractionMethod::PdfOcrFallback,
+        SourceType::OcrExtracted => ExtractionMethod::TesseractOcr { confidence, language: "unknown".to_string() },
+    }
+}
+
+/// Empreinte des paramètres de recherche hors requête : deux réponses ne sont interchangeables
+/// que si filtres, limites et options sont identiques
+fn search_params_fingerprint(params: &AdvancedSearchParams) -> String {
+    let mut value = serde_json::to_value(params).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("query");
+        fields.remove("semantic_cache");
+    }
+    value.to_string()
+}
+
+/// Fusionner deux listes de hits Qdrant : un point présent dans les deux garde son meilleur score
+fn merge_qdrant_hits(primary: &serde_json::Value, secondary: &serde_json::Value, limit: usize) -> serde_json::Value {
+    let mut merged: Vec<serde_json::Value> = primary.as_array().cloned().unwrap_or_default();
+    for hit in secondary.as_array().into_iter().flatten() {
+        let score = hit["score"].as_f64().unwrap_or(0.0);
+        match merged.iter_mut().find(|existing| existing["id"] == hit["id"]) {
+            Some(existing) => {
+                if score > existing["score"].as_f64().unwrap_or(0.0) {
+                    *existing = hit.clone();
+                }
+            }
+            None => merged.push(hit.clone()),
+        }
+    }
+    merged.sort_by(|a, b| b["score"].as_f64().unwrap_or(0.0).total_cmp(&a["score"].as_f64().unwrap_or(0.0)));
+    merged.truncate(limit);
+    serde_json::Value::Array(merged)
+}
+
+/// Multiplicateurs de score par priorité de document actuellement appliqués
+#[tauri::command]
+pub async fn get_priority_weights(state: State<'_, RagState>) -> Result<PriorityWeights, CommandError> {
+    Ok(*state.priority_weights.read().await)
+}
+
+/// Configurer les multiplicateurs de score par priorité (appliqués après la recherche Qdrant)
+#[tauri::command]
+pub async fn set_priority_weights(
+    weights: PriorityWeights,
+    state: State<'_, RagState>,
+) -> Result<PriorityWeights, CommandError> {
+    weights.validate().map_err(CommandError::invalid_input)?;
+    *state.priority_weights.write().await = weights;
+    state.query_cache.write().await.clear();
+    info!("⚖️ Priority weights updated: low {:.2}, normal {:.2}, high {:.2}", weights.low, weights.normal, weights.high);
+    Ok(weights)
+}
+
+/// Chunker un texte d'essai sans l'indexer : chunks, sections, taille moyenne et recouvrement,
+/// pour ajuster `SmartChunkConfig` avant de l'appliquer à un groupe
+#[tauri::command]
+pub async fn analyze_chunking(
+    text: String,
+    config: Option<crate::rag::processing::smart_chunker::SmartChunkConfig>,
+) -> Result<crate::rag::processing::smart_chunker::SmartChunkResult, CommandError> {
+    use crate::rag::processing::smart_chunker::SmartChunker;
+
+    if text.trim().is_empty() {
+        return Err(CommandError::invalid_input("Text to analyze is empty"));
+    }
+    let config = config.unwrap_or_default();
+    config.validate().map_err(CommandError::invalid_input)?;
+
+    let mut chunker = SmartChunker::new(config)
+        .map_err(|e| CommandError::internal(format!("Failed to create chunker: {}", e)))?;
+    let result = chunker
+        .chunk_document(&text, SourceType::NativeText, &ExtractionMethod::DirectRead, "chunking_preview")
+        .map_err(|e| CommandError::internal(format!("Failed to chunk text: {}", e)))?;
+
+    info!("🔬 Chunking analysis: {} chunks (avg {:.0} chars), {} sections, {}/{} overlapping pairs",
+          result.chunks.len(), result.avg_chunk_size, result.sections_detected.len(),
+          result.overlap.overlapping_pairs, result.overlap.pairs);
+    Ok(result)
 }
 
 /// Obtenir les métadonnées enrichies d'un document
@@ -619,24 +1315,26 @@ pub async fn get_document_metadata(
     document_id: String,
     group_id: String,
     state: State<'_, RagState>,
-) -> Result<DocumentMetadataResponse, String> {
+) -> Result<DocumentMetadataResponse, CommandError> {
     info!("Getting metadata for document {} in group {}", document_id, group_id);
 
     let groups = state.groups.read().await;
     let group = groups.get(&group_id)
-        .ok_or_else(|| format!("Group not found: {}", group_id))?;
+        .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
 
     let document = group.documents
         .iter()
         .find(|doc| doc.id == document_id)
-        .ok_or_else(|| format!("Document not found: {}", document_id))?;
+        .ok_or_else(|| CommandError::not_found(format!("Document not found: {}", document_id)))?;
 
     // Classification et enrichissement
     let document_category = state.document_classifier
         .classify(&document.content)
         .map_err(|e| format!("Classification failed: {}", e))?;
 
-    let business_metadata = if matches!(document_category, DocumentCategory::Business) {
+    let business_metadata = if document.business_metadata.is_some() {
+        document.business_metadata.clone()
+    } else if matches!(document_category, DocumentCategory::Business) {
         Some(
             state.business_enricher
                 .enrich_business_content(&document.content, None, None)
@@ -654,6 +1352,7 @@ pub async fn get_document_metadata(
         language: document.language.clone(),
         business_metadata,
         processing_metadata: document.metadata.clone(),
+        extraction_breakdown: compute_extraction_breakdown(document),
     })
 }
 
@@ -667,15 +1366,178 @@ pub struct DocumentMetadataResponse {
     pub language: String,
     pub business_metadata: Option<BusinessMetadata>,
     pub processing_metadata: crate::rag::EnrichedMetadata,
+    pub extraction_breakdown: ExtractionBreakdown,
+}
+
+/// Répartition OCR vs natif des chunks d'un document
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExtractionBreakdown {
+    pub chunks_by_source_type: HashMap<String, usize>,
+    pub native_text_ratio: f32,                // Part des chunks issus de texte natif
+    pub mean_ocr_confidence: Option<f32>,      // None si aucun chunk OCR
+    pub dominant_extraction_method: Option<ExtractionMethod>,
+}
+
+/// Agréger `source_type` et `extraction_method` sur tous les chunks du document
+fn compute_extraction_breakdown(document: &GroupDocument) -> ExtractionBreakdown {
+    let mut chunks_by_source_type: HashMap<String, usize> = HashMap::new();
+    let mut method_counts: Vec<(ExtractionMethod, usize)> = Vec::new();
+    let mut native_chunks = 0usize;
+    let mut ocr_confidences = Vec::new();
+
+    for chunk in &document.chunks {
+        let source_type = &chunk.metadata.source_type;
+        *chunks_by_source_type.entry(format!("{:?}", source_type)).or_insert(0) += 1;
+
+        if matches!(source_type, SourceType::NativeText | SourceType::HybridPdfNative) {
+            native_chunks += 1;
+        }
+
+        if let ExtractionMethod::TesseractOcr { confidence, .. } = &chunk.metadata.extraction_method {
+            ocr_confidences.push(*confidence);
+        }
+
+        // Regrouper par variante (la confiance OCR varie d'un chunk à l'autre)
+        let method = &chunk.metadata.extraction_method;
+        match method_counts.iter_mut()
+            .find(|(m, _)| std::mem::discriminant(m) == std::mem::discriminant(method)) {
+            Some((_, count)) => *count += 1,
+            None => method_counts.push((method.clone(), 1)),
+        }
+    }
+
+    let native_text_ratio = if document.chunks.is_empty() {
+        0.0
+    } else {
+        native_chunks as f32 / document.chunks.len() as f32
+    };
+
+    let mean_ocr_confidence = if ocr_confidences.is_empty() {
+        None
+    } else {
+        Some(ocr_confidences.iter().sum::<f32>() / ocr_confidences.len() as f32)
+    };
+
+    let dominant_extraction_method = method_counts.into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(method, _)| method);
+
+    ExtractionBreakdown {
+        chunks_by_source_type,
+        native_text_ratio,
+        mean_ocr_confidence,
+        dominant_extraction_method,
+    }
 }
 
-/// Lister les documents stockés dans une collection Qdrant
+/// Taille d'une page de scroll Qdrant
+const SCROLL_PAGE_SIZE: usize = 1000;
+
+/// Points récupérés par scroll et offset de reprise (None si la collection est épuisée)
+struct ScrolledPoints {
+    points: Vec<serde_json::Value>,
+    next_page_offset: Option<serde_json::Value>,
+}
+
+/// Scroller page par page depuis `start_offset` jusqu'à épuisement, `max_points` atteint,
+/// ou après une seule page si `single_page`. None si la collection n'existe pas.
+async fn scroll_pages<F, Fut>(
+    mut fetch_page: F,
+    start_offset: Option<serde_json::Value>,
+    max_points: Option<usize>,
+    single_page: bool,
+) -> Result<Option<ScrolledPoints>, CommandError>
+where
+    F: FnMut(Option<serde_json::Value>, usize) -> Fut,
+    Fut: std::future::Future<Output = Result<Option<serde_json::Value>, CommandError>>,
+{
+    let mut points = Vec::new();
+    let mut offset = start_offset;
+
+    loop {
+        let page_size = max_points
+            .map(|max| max.saturating_sub(points.len()).min(SCROLL_PAGE_SIZE))
+            .unwrap_or(SCROLL_PAGE_SIZE);
+        if page_size == 0 {
+            break;
+        }
+
+        let Some(data) = fetch_page(offset.clone(), page_size).await? else {
+            return Ok(None);
+        };
+
+        let page = data["result"]["points"].as_array()
+            .ok_or_else(|| CommandError::internal("Invalid Qdrant response format"))?;
+        points.extend(page.iter().cloned());
+
+        offset = match &data["result"]["next_page_offset"] {
+            serde_json::Value::Null => None,
+            next => Some(next.clone()),
+        };
+        if offset.is_none() || single_page {
+            break;
+        }
+    }
+
+    Ok(Some(ScrolledPoints { points, next_page_offset: offset }))
+}
+
+/// Scroller une collection Qdrant (voir `scroll_pages`).
+/// `projection` : champs `with_payload`/`with_vector` de la requête (vecteurs exclus par défaut)
+async fn scroll_collection(
+    qdrant_client: &Arc<QdrantRestClient>,
+    collection_name: &str,
+    filter: Option<&serde_json::Value>,
+    projection: serde_json::Value,
+    start_offset: Option<serde_json::Value>,
+    max_points: Option<usize>,
+    single_page: bool,
+) -> Result<Option<ScrolledPoints>, CommandError> {
+    scroll_pages(
+        |page_offset, page_size| {
+            let mut request = serde_json::json!({
+                "limit": page_size,
+                "with_vector": false
+            });
+            if let Some(fields) = projection.as_object() {
+                for (key, value) in fields {
+                    request[key.as_str()] = value.clone();
+                }
+            }
+            if let Some(filter) = filter {
+                request["filter"] = filter.clone();
+            }
+            if let Some(page_offset) = page_offset {
+                request["offset"] = page_offset;
+            }
+            let qdrant_client = qdrant_client.clone();
+            let collection_name = collection_name.to_string();
+            async move {
+                qdrant_client
+                    .scroll_points(&collection_name, &request)
+                    .await
+                    .map_err(|e| CommandError::service_unavailable(format!("Qdrant scroll failed: {:#}", e)))
+            }
+        },
+        start_offset,
+        max_points,
+        single_page,
+    ).await
+}
+
+/// Lister les documents stockés dans une collection Qdrant.
+/// Toute la collection est parcourue (ou `max_points` points) ; `sample_only` se limite à une page.
 #[tauri::command]
 pub async fn list_rag_documents(
     group_id: String,
+    category: Option<String>,
+    tags: Option<Vec<String>>,
+    offset: Option<serde_json::Value>,
+    max_points: Option<usize>,
+    sample_only: Option<bool>,
     state: State<'_, RagState>,
-) -> Result<Vec<RagDocumentInfo>, String> {
-    info!("📋 Listing RAG documents from group: {}", group_id);
+) -> Result<RagDocumentListResponse, CommandError> {
+    info!("📋 Listing RAG documents from group: {} (category: {:?}, tags: {:?})", group_id, category, tags);
 
     // Récupérer le nom de la collection
     let groups = state.groups.read().await;
@@ -692,39 +1554,56 @@ pub async fn list_rag_documents(
 
     info!("🔍 Querying Qdrant collection: {}", collection_name);
 
-    // Récupérer tous les points de la collection via scroll
-    let mut document_map: std::collections::HashMap<String, RagDocumentInfo> = std::collections::HashMap::new();
-
-    // Utiliser l'API REST Qdrant pour scroller tous les points
-    let url = format!("http://localhost:6333/collections/{}/points/scroll", collection_name);
-    let client = reqwest::Client::new();
+    // Utiliser l'API REST Qdrant pour scroller les points
+    let filter = build_document_filter(category.as_deref(), tags.as_deref());
+    let sample_only = sample_only.unwrap_or(false);
 
-    let response = client
-        .post(&url)
-        .json(&serde_json::json!({
-            "limit": 1000,
-            "with_payload": true,
-            "with_vector": false
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Qdrant request failed: {}", e))?;
+    let scrolled = scroll_collection(
+        &state.qdrant_client,
+        &collection_name,
+        filter.as_ref(),
+        serde_json::json!({ "with_payload": true }),
+        offset.clone(),
+        max_points,
+        sample_only,
+    ).await?;
 
     // Si la collection n'existe pas encore (404), retourner une liste vide
-    if response.status() == 404 {
+    let Some(ScrolledPoints { points, next_page_offset }) = scrolled else {
         info!("📭 Collection {} does not exist yet (no documents injected)", collection_name);
-        return Ok(Vec::new());
-    }
+        return Ok(RagDocumentListResponse {
+            documents: Vec::new(),
+            next_page_offset: None,
+            total_documents: 0,
+        });
+    };
 
-    if !response.status().is_success() {
-        return Err(format!("Qdrant returned error: {}", response.status()));
-    }
+    let documents = group_points_by_document(&points, &group_id);
 
-    let data: serde_json::Value = response.json().await
-        .map_err(|e| format!("Failed to parse Qdrant response: {}", e))?;
+    // Compter les documents distincts sur l'ensemble des pages (pas seulement celles parcourues)
+    let total_documents = if next_page_offset.is_none() && offset.is_none() {
+        documents.len()
+    } else {
+        count_distinct_documents(&state.qdrant_client, &collection_name, filter.as_ref()).await?
+    };
 
-    let points = data["result"]["points"].as_array()
-        .ok_or_else(|| "Invalid Qdrant response format".to_string())?;
+    info!("📊 Returning {} of {} documents with {} total chunks from collection {} ({} points scanned)",
+          documents.len(),
+          total_documents,
+          documents.iter().map(|d| d.chunks_count).sum::<usize>(),
+          collection_name,
+          points.len());
+
+    Ok(RagDocumentListResponse {
+        documents,
+        next_page_offset,
+        total_documents,
+    })
+}
+
+/// Regrouper les points scrollés par document_id (chunks, figures, confiance moyenne, extrait)
+fn group_points_by_document(points: &[serde_json::Value], group_id: &str) -> Vec<RagDocumentInfo> {
+    let mut document_map: HashMap<String, RagDocumentInfo> = HashMap::new();
 
     // Regrouper par document_id
     for point in points {
@@ -737,10 +1616,11 @@ pub async fn list_rag_documents(
             let entry = document_map.entry(doc_id.clone()).or_insert_with(|| {
                 RagDocumentInfo {
                     document_id: doc_id.clone(),
-                    group_id: group_id.clone(),
+                    group_id: group_id.to_string(),
                     chunks_count: 0,
                     confidence: 0.0,
                     sample_content: String::new(),
+                    summary: payload.get(DOCUMENT_SUMMARY_FIELD).and_then(|v| v.as_str()).map(|s| s.to_string()),
                     source_file: payload.get("source_file").and_then(|v| v.as_str()).map(|s| s.to_string()),
                     document_title: payload.get("document_title").and_then(|v| v.as_str()).map(|s| s.to_string()),
                     document_author: payload.get("document_author").and_then(|v| v.as_str()).map(|s| s.to_string()),
@@ -749,147 +1629,1953 @@ pub async fn list_rag_documents(
                         .and_then(|v| v.as_array())
                         .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
                         .unwrap_or_default(),
+                    figure_chunks_count: 0,
+                    figure_ids: Vec::new(),
                 }
             });
 
             entry.chunks_count += 1;
 
-            // Récupérer un échantillon de contenu
+            let (chunk_source, figure_id) = chunk_source_from_payload(payload);
+            let is_figure_chunk = matches!(chunk_source, ChunkSource::FigureCaption | ChunkSource::FigureRegionText);
+            if is_figure_chunk {
+                entry.figure_chunks_count += 1;
+            }
+            if let Some(figure_id) = figure_id {
+                if !entry.figure_ids.contains(&figure_id) {
+                    entry.figure_ids.push(figure_id);
+                }
+            }
+
+            // Récupérer un échantillon de contenu : le résumé du document, sinon du texte courant (pas une légende)
             if entry.sample_content.is_empty() {
-                if let Some(content) = payload.get("content").and_then(|v| v.as_str()) {
-                    entry.sample_content = content.chars().take(200).collect();
+                if let Some(ref summary) = entry.summary {
+                    entry.sample_content = summary.clone();
+                } else if !is_figure_chunk {
+                    if let Some(content) = payload.get("content").and_then(|v| v.as_str()) {
+                        entry.sample_content = content.chars().take(200).collect();
+                    }
+                }
+            }
+
+            // Moyenne de confiance
+            if let Some(conf) = payload.get("confidence").and_then(|v| v.as_f64()) {
+                entry.confidence = (entry.confidence * (entry.chunks_count - 1) as f32 + conf as f32)
+                    / entry.chunks_count as f32;
+            }
+        }
+    }
+
+    document_map.into_values().collect()
+}
+
+/// Construire le filtre Qdrant pour la catégorie et les tags (tous requis)
+fn build_document_filter(category: Option<&str>, tags: Option<&[String]>) -> Option<serde_json::Value> {
+    let mut must = Vec::new();
+
+    if let Some(category) = category {
+        must.push(serde_json::json!({
+            "key": "document_category",
+            "match": { "value": category }
+        }));
+    }
+
+    for tag in tags.unwrap_or_default() {
+        must.push(serde_json::json!({
+            "key": "document_tags",
+            "match": { "value": tag }
+        }));
+    }
+
+    if must.is_empty() {
+        None
+    } else {
+        Some(serde_json::json!({ "must": must }))
+    }
+}
+
+/// Nombre de chunks par page de `browse_chunks` par défaut / au maximum
+const BROWSE_DEFAULT_LIMIT: usize = 50;
+const BROWSE_MAX_LIMIT: usize = 500;
+
+/// Parcourir les chunks indexés d'un groupe par filtre de payload, sans requête vectorielle
+/// (inspecteur de documents : ce qui a réellement été indexé). Pagination par l'offset Qdrant.
+#[tauri::command]
+pub async fn browse_chunks(
+    group_id: String,
+    filter: Option<ChunkBrowseFilter>,
+    offset: Option<serde_json::Value>,
+    limit: Option<usize>,
+    state: State<'_, RagState>,
+) -> Result<ChunkBrowseResponse, CommandError> {
+    let filter = filter.unwrap_or_default();
+    let limit = limit.unwrap_or(BROWSE_DEFAULT_LIMIT).clamp(1, BROWSE_MAX_LIMIT);
+    info!("🔎 Browsing chunks of group {} (filter: {:?}, limit: {})", group_id, filter, limit);
+
+    let collection_name = state.groups.read().await
+        .get(&group_id)
+        .map(|group| group.qdrant_collection.clone())
+        .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
+
+    let qdrant_filter = build_chunk_filter(&filter);
+    let scrolled = scroll_collection(
+        &state.qdrant_client,
+        &collection_name,
+        qdrant_filter.as_ref(),
+        serde_json::json!({ "with_payload": true }),
+        offset,
+        Some(limit),
+        true,
+    ).await?;
+
+    let Some(ScrolledPoints { points, next_page_offset }) = scrolled else {
+        info!("📭 Collection {} does not exist yet (no documents injected)", collection_name);
+        return Ok(ChunkBrowseResponse { chunks: Vec::new(), next_page_offset: None });
+    };
+
+    // Ordre de lecture dans la page : document puis position dans le document
+    let mut chunks: Vec<BrowsedChunk> = points.iter().filter_map(browsed_chunk_from_point).collect();
+    chunks.sort_by(|a, b| {
+        a.document_id.cmp(&b.document_id)
+            .then(a.start_line.cmp(&b.start_line))
+            .then(a.chunk_id.cmp(&b.chunk_id))
+    });
+
+    info!("📊 Returning {} chunks from collection {}", chunks.len(), collection_name);
+    Ok(ChunkBrowseResponse { chunks, next_page_offset })
+}
+
+/// Filtre Qdrant de `browse_chunks` : catégorie et tags (voir `build_document_filter`), document, fichier, origine
+fn build_chunk_filter(filter: &ChunkBrowseFilter) -> Option<serde_json::Value> {
+    let mut must = build_document_filter(filter.category.as_deref(), filter.tags.as_deref())
+        .and_then(|filter| filter["must"].as_array().cloned())
+        .unwrap_or_default();
+
+    let exact_fields = [
+        ("document_id", filter.document_id.as_ref().map(|v| serde_json::json!(v))),
+        ("source_file", filter.source_file.as_ref().map(|v| serde_json::json!(v))),
+        ("chunk_source", filter.chunk_source.as_ref().map(|v| serde_json::json!(v))),
+    ];
+    for (key, value) in exact_fields {
+        if let Some(value) = value {
+            must.push(serde_json::json!({ "key": key, "match": { "value": value } }));
+        }
+    }
+
+    if must.is_empty() {
+        None
+    } else {
+        Some(serde_json::json!({ "must": must }))
+    }
+}
+
+/// Chunk d'un point Qdrant ; le reste du payload est conservé dans `metadata`
+fn browsed_chunk_from_point(point: &serde_json::Value) -> Option<BrowsedChunk> {
+    let payload = point["payload"].as_object()?;
+    let (chunk_source, figure_id) = chunk_source_from_payload(payload);
+    let text = |key: &str| payload.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let line = |key: &str| payload.get(key).and_then(|v| v.as_u64()).map(|n| n as usize);
+
+    let metadata = payload.iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "content" | "chunk_id" | "document_id" | "start_line" | "end_line"))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    Some(BrowsedChunk {
+        point_id: point["id"].clone(),
+        chunk_id: text("chunk_id").unwrap_or_default(),
+        document_id: text("document_id").unwrap_or_else(|| "unknown".to_string()),
+        content: text("content").unwrap_or_default(),
+        start_line: line("start_line"),
+        end_line: line("end_line"),
+        chunk_source,
+        figure_id,
+        source_file: text("source_file"),
+        metadata,
+    })
+}
+
+/// Re-scorer les résultats denses avec BM25 (normalisé sur le lot) puis re-trier
+fn apply_hybrid_weights(results: &mut [SearchResultWithMetadata], query: &str, bm25_weight: f32) {
+    if bm25_weight <= 0.0 || results.is_empty() {
+        return;
+    }
+
+    let query_terms: Vec<&str> = query.split_whitespace().filter(|term| term.chars().count() > 2).collect();
+    let bm25_scores: Vec<f32> = results.iter()
+        .map(|result| compute_bm25_score(&query_terms, &result.content))
+        .collect();
+    let max_bm25 = bm25_scores.iter().cloned().fold(0.0_f32, f32::max);
+    if max_bm25 <= 0.0 {
+        return;
+    }
+
+    for (result, bm25) in results.iter_mut().zip(bm25_scores) {
+        result.score = compute_hybrid_score(bm25 / max_bm25, result.score, bm25_weight);
+    }
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+}
+
+/// Fusionner les résultats d'un même document dont les lignes se chevauchent ou se suivent.
+///
+/// Le contenu est concaténé sans la partie dupliquée par l'overlap d'ingestion, le score retenu
+/// est le maximum. Une fusion qui dépasserait `max_chars` n'est pas faite ; les résultats sans
+/// lignes connues (points anciens) sont laissés tels quels. Résultat trié par score décroissant.
+fn merge_overlapping_results(results: Vec<SearchResultWithMetadata>, max_chars: usize) -> Vec<SearchResultWithMetadata> {
+    let (mut ranged, mut merged): (Vec<_>, Vec<_>) = results.into_iter()
+        .partition(|r| r.chunk_metadata.end_line > 0);
+
+    ranged.sort_by(|a, b| a.document_id.cmp(&b.document_id)
+        .then(a.chunk_metadata.start_line.cmp(&b.chunk_metadata.start_line)));
+
+    let mut current: Option<SearchResultWithMetadata> = None;
+    for next in ranged {
+        current = match current.take() {
+            Some(mut base) if base.document_id == next.document_id
+                && next.chunk_metadata.start_line <= base.chunk_metadata.end_line + 1 =>
+            {
+                let overlap = text_overlap_len(&base.content, &next.content);
+                let addition = &next.content[overlap..];
+                if base.content.chars().count() + addition.chars().count() + 1 > max_chars {
+                    merged.push(base);
+                    Some(next)
+                } else {
+                    if overlap == 0 {
+                        base.content.push('\n');
+                    }
+                    base.content.push_str(addition);
+                    if base.merged_chunk_ids.is_empty() {
+                        base.merged_chunk_ids.push(base.chunk_id.clone());
+                    }
+                    base.merged_chunk_ids.push(next.chunk_id.clone());
+                    for fact in next.numeric_facts {
+                        if !base.numeric_facts.iter().any(|existing| existing.matches(&fact)) {
+                            base.numeric_facts.push(fact);
+                        }
+                    }
+                    base.chunk_metadata.end_line = base.chunk_metadata.end_line.max(next.chunk_metadata.end_line);
+                    base.cosine_score = base.cosine_score.max(next.cosine_score);
+                    base.char_range = match (base.char_range, next.char_range) {
+                        (Some(a), Some(b)) => Some((a.0.min(b.0), a.1.max(b.1))),
+                        _ => None,
+                    };
+                    if next.score > base.score {
+                        base.score = next.score;
+                        base.chunk_id = next.chunk_id;
+                    }
+                    Some(base)
+                }
+            }
+            Some(base) => {
+                merged.push(base);
+                Some(next)
+            }
+            None => Some(next),
+        };
+    }
+    merged.extend(current);
+
+    merged.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    merged
+}
+
+/// Longueur (octets) du plus long suffixe de `previous` qui commence `next`, si ≥ `MIN_TEXT_OVERLAP_CHARS`
+fn text_overlap_len(previous: &str, next: &str) -> usize {
+    next.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(next.len()))
+        .rev()
+        .find(|&end| end <= previous.len()
+            && next[..end].chars().count() >= MIN_TEXT_OVERLAP_CHARS
+            && previous.ends_with(&next[..end]))
+        .unwrap_or(0)
+}
+
+/// Vérifier que tous les embeddings ont la dimension de la collection cible
+fn validate_embedding_dimensions(
+    chunks: &[EnrichedChunk],
+    expected: usize,
+    collection_name: &str,
+) -> Result<(), String> {
+    let mismatch = chunks.iter()
+        .filter_map(|chunk| chunk.embedding.as_ref().map(|emb| (chunk, emb.len())))
+        .find(|(_, len)| *len != expected);
+
+    match mismatch {
+        Some((chunk, len)) => Err(format!(
+            "Embedding dimension mismatch for chunk {}: model produced {}D but collection {} expects {}D (reset the group collection after changing the embedding model)",
+            chunk.id, len, collection_name, expected
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Filtre Qdrant sur l'exercice fiscal stocké à l'ingestion
+fn build_fiscal_year_filter(fiscal_year: Option<i32>) -> Option<serde_json::Value> {
+    fiscal_year.map(|year| serde_json::json!({
+        "must": [{ "key": "fiscal_year", "match": { "value": year } }]
+    }))
+}
+
+/// Condition Qdrant imbriquée : au moins un fait numérique du chunk satisfait unité et intervalle
+fn build_numeric_fact_condition(filter: &NumericFactFilter) -> Option<serde_json::Value> {
+    let mut must = Vec::new();
+    if let Some(ref unit) = filter.unit {
+        must.push(serde_json::json!({ "key": "unit", "match": { "value": unit } }));
+    }
+    if filter.gte.is_some() || filter.lte.is_some() {
+        let mut range = serde_json::Map::new();
+        if let Some(gte) = filter.gte {
+            range.insert("gte".to_string(), serde_json::json!(gte));
+        }
+        if let Some(lte) = filter.lte {
+            range.insert("lte".to_string(), serde_json::json!(lte));
+        }
+        must.push(serde_json::json!({ "key": "value", "range": range }));
+    }
+    if must.is_empty() {
+        return None;
+    }
+    Some(serde_json::json!({
+        "nested": { "key": NUMERIC_FACTS_FIELD, "filter": { "must": must } }
+    }))
+}
+
+/// Filtre Qdrant de la recherche avancée : exercice fiscal, tags (tous requis), priorités, auteur, projet, valeurs chiffrées
+fn build_search_filter(params: &AdvancedSearchParams) -> Option<serde_json::Value> {
+    let mut must: Vec<serde_json::Value> = [
+        build_fiscal_year_filter(params.fiscal_year_filter),
+        build_document_filter(None, params.tags.as_deref()),
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(|filter| filter["must"].as_array().cloned().unwrap_or_default())
+    .collect();
+
+    if let Some(ref priorities) = params.priorities {
+        if !priorities.is_empty() {
+            must.push(serde_json::json!({
+                "key": "document_priority",
+                "match": { "any": priorities }
+            }));
+        }
+    }
+    for (key, value) in [("document_author", &params.author), ("document_project", &params.project)] {
+        if let Some(value) = value {
+            must.push(serde_json::json!({ "key": key, "match": { "value": value } }));
+        }
+    }
+    if let Some(condition) = params.numeric_filter.as_ref().and_then(build_numeric_fact_condition) {
+        must.push(condition);
+    }
+    // Filtres appliqués par Qdrant avant le top-k : un post-filtre viderait des pages entières
+    if let Some(ref categories) = params.document_categories {
+        let categories: Vec<String> = categories.iter().map(|category| format!("{:?}", category)).collect();
+        must.push(serde_json::json!({ "key": "document_category", "match": { "any": categories } }));
+    }
+    if let Some(ref source_types) = params.source_types {
+        let mut condition = serde_json::json!({ "key": "source_type", "match": { "any": source_types } });
+        // Points indexés avant le champ `source_type` : considérés OCR
+        if source_types.contains(&SourceType::OcrExtracted) {
+            condition = serde_json::json!({ "should": [condition, { "is_empty": { "key": "source_type" } }] });
+        }
+        must.push(condition);
+    }
+
+    if must.is_empty() {
+        None
+    } else {
+        Some(serde_json::json!({ "must": must }))
+    }
+}
+
+/// Remonter les résultats contenant une valeur chiffrée de la requête (même unité, même valeur) puis re-trier
+fn apply_numeric_fact_boost(results: &mut [SearchResultWithMetadata], query_facts: &[NumericFact]) {
+    if query_facts.is_empty() {
+        return;
+    }
+    for result in results.iter_mut() {
+        if facts_match_query(&result.numeric_facts, query_facts) {
+            let boosted = result.score * NUMERIC_FACT_BOOST;
+            debug!("🔢 Numeric match on {}: {:.4} -> {:.4}", result.chunk_id, result.score, boosted);
+            result.score = boosted;
+        }
+    }
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+}
+
+/// Pondérer les scores par la priorité du document puis re-trier
+fn apply_priority_weights(results: &mut [SearchResultWithMetadata], weights: &PriorityWeights) {
+    for result in results.iter_mut() {
+        let weight = weights.weight(result.document_priority);
+        if weight == 1.0 {
+            continue;
+        }
+        let boosted = result.score * weight;
+        debug!("⚖️ Priority {:?} x{:.2} on {}: {:.4} -> {:.4}",
+               result.document_priority, weight, result.chunk_id, result.score, boosted);
+        result.score = boosted;
+    }
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+}
+
+/// Origine du chunk (corps, légende, zone de figure...) stockée dans le payload Qdrant
+fn insert_chunk_source_fields(payload: &mut HashMap<String, serde_json::Value>, chunk: &EnrichedChunk) {
+    payload.insert("chunk_source".to_string(), serde_json::json!(chunk.chunk_source));
+    if let Some(ref figure_id) = chunk.figure_id {
+        payload.insert("figure_id".to_string(), serde_json::json!(figure_id));
+    }
+}
+
+/// Relire l'origine du chunk ; les points indexés avant son ajout sont du texte courant
+fn chunk_source_from_payload(payload: &serde_json::Map<String, serde_json::Value>) -> (ChunkSource, Option<String>) {
+    let chunk_source = payload.get("chunk_source")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or(ChunkSource::BodyText);
+    let figure_id = payload.get("figure_id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    (chunk_source, figure_id)
+}
+
+/// Blocs OCR localisés dans le texte du document (plage en caractères), dans l'ordre du texte ;
+/// les blocs introuvables tels quels sont ignorés
+fn locate_ocr_blocks<'a>(content: &str, blocks: &'a [OCRBlock]) -> Vec<((usize, usize), &'a OCRBlock)> {
+    crate::rag::locate_texts(content, blocks.iter().map(|block| block.content.as_str()))
+        .into_iter()
+        .zip(blocks)
+        .filter_map(|(range, block)| range.map(|range| (range, block)))
+        .collect()
+}
+
+/// BBox des blocs OCR dont la plage chevauche celle du chunk (offsets de `locate_chunks`, hors
+/// overlap de page) : union des blocs de la première page concernée.
+/// `None` sans bloc correspondant (texte pré-extrait, fichiers texte...).
+fn chunk_bounding_box(
+    (chunk_start, chunk_end): (usize, usize),
+    located_blocks: &[((usize, usize), &OCRBlock)],
+) -> Option<SourceBoundingBox> {
+    let matching: Vec<&OCRBlock> = located_blocks
+        .iter()
+        .filter(|((block_start, block_end), _)| *block_start < chunk_end && chunk_start < *block_end)
+        .map(|(_, block)| *block)
+        .collect();
+
+    let page = matching.first()?.page_number;
+    let (mut x0, mut y0, mut x1, mut y1) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for block in matching.iter().filter(|block| block.page_number == page) {
+        let bbox = &block.bounding_box;
+        x0 = x0.min(bbox.x);
+        y0 = y0.min(bbox.y);
+        x1 = x1.max(bbox.x + bbox.width);
+        y1 = y1.max(bbox.y + bbox.height);
+    }
+
+    Some(SourceBoundingBox::pdf_points(
+        page as usize,
+        x0 as f32,
+        y0 as f32,
+        (x1 - x0) as f32,
+        (y1 - y0) as f32,
+    ))
+}
+
+/// Compter les document_id distincts en scrollant uniquement ce champ du payload
+async fn count_distinct_documents(
+    qdrant_client: &QdrantRestClient,
+    collection_name: &str,
+    filter: Option<&serde_json::Value>,
+) -> Result<usize, CommandError> {
+    let mut document_ids = std::collections::HashSet::new();
+    let mut offset: Option<serde_json::Value> = None;
+
+    loop {
+        let mut request = serde_json::json!({
+            "limit": 1000,
+            "with_payload": { "include": ["document_id"] },
+            "with_vector": false
+        });
+        if let Some(filter) = filter {
+            request["filter"] = filter.clone();
+        }
+        if let Some(ref offset) = offset {
+            request["offset"] = offset.clone();
+        }
+
+        let data = qdrant_client
+            .scroll_points(collection_name, &request)
+            .await
+            .map_err(|e| CommandError::service_unavailable(format!("Qdrant count request failed: {:#}", e)))?
+            .unwrap_or_default();
+
+        if let Some(points) = data["result"]["points"].as_array() {
+            document_ids.extend(points.iter().filter_map(|p| {
+                p["payload"]["document_id"].as_str().map(|s| s.to_string())
+            }));
+        }
+
+        match &data["result"]["next_page_offset"] {
+            serde_json::Value::Null => break,
+            next => offset = Some(next.clone()),
+        }
+    }
+
+    Ok(document_ids.len())
+}
+
+/// Page de documents RAG avec pagination Qdrant
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RagDocumentListResponse {
+    pub documents: Vec<RagDocumentInfo>,
+    pub next_page_offset: Option<serde_json::Value>, // À renvoyer comme `offset` pour la page suivante
+    pub total_documents: usize,                      // Documents distincts correspondant aux filtres
+}
+
+/// Filtre de payload de `browse_chunks` (tous les critères fournis sont requis)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ChunkBrowseFilter {
+    pub document_id: Option<String>,
+    pub source_file: Option<String>,
+    pub category: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub chunk_source: Option<ChunkSource>,
+}
+
+/// Page de chunks parcourus par payload
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChunkBrowseResponse {
+    pub chunks: Vec<BrowsedChunk>,
+    pub next_page_offset: Option<serde_json::Value>, // À renvoyer comme `offset` pour la page suivante
+}
+
+/// Chunk tel qu'indexé dans Qdrant
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BrowsedChunk {
+    pub point_id: serde_json::Value,
+    pub chunk_id: String,
+    pub document_id: String,
+    pub content: String,
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+    pub chunk_source: ChunkSource,
+    pub figure_id: Option<String>,
+    pub source_file: Option<String>,
+    /// Autres champs du payload (catégorie, tags, bbox, checksum, résumé...)
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Information simplifiée sur un document RAG
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RagDocumentInfo {
+    pub document_id: String,
+    pub group_id: String,
+    pub chunks_count: usize,
+    pub confidence: f32,
+    /// Résumé du document, repris dans `sample_content` (documents ingérés avant les résumés : extrait brut)
+    pub sample_content: String,
+    pub summary: Option<String>,
+    pub source_file: Option<String>,
+    pub document_title: Option<String>,
+    pub document_author: Option<String>,
+    pub document_category: Option<String>,
+    pub document_tags: Vec<String>,
+    /// Chunks issus de figures (légendes + zones) et figures référencées
+    pub figure_chunks_count: usize,
+    pub figure_ids: Vec<String>,
+}
+
+/// Supprimer un document RAG et tous ses chunks de Qdrant
+#[tauri::command]
+pub async fn delete_rag_document(
+    document_id: String,
+    group_id: String,
+    state: State<'_, RagState>,
+) -> Result<DeleteRagDocumentResponse, CommandError> {
+    info!("🗑️ Deleting RAG document {} from group {}", document_id, group_id);
+
+    // Récupérer le nom de la collection
+    let groups = state.groups.read().await;
+    let collection_name = if let Some(group) = groups.get(&group_id) {
+        group.qdrant_collection.clone()
+    } else {
+        format!("collection_{}", group_id)
+    };
+    drop(groups);
+
+    // 1. Récupérer tous les points du document via scroll avec filtre
+    let data = state.qdrant_client
+        .scroll_points(&collection_name, &serde_json::json!({
+            "limit": 1000,
+            "with_payload": true,
+            "with_vector": false,
+            "filter": {
+                "must": [{
+                    "key": "document_id",
+                    "match": {
+                        "value": document_id
+                    }
+                }]
+            }
+        }))
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Qdrant scroll failed: {:#}", e)))?
+        .ok_or_else(|| CommandError::not_found(format!("Collection {} not found", collection_name)))?;
+
+    let points = data["result"]["points"].as_array()
+        .ok_or_else(|| "Invalid Qdrant scroll response format".to_string())?;
+
+    // 2. Extraire les IDs des points à supprimer
+    let point_ids: Vec<String> = points
+        .iter()
+        .filter_map(|point| {
+            point["id"].as_str().map(|s| s.to_string())
+        })
+        .collect();
+
+    if point_ids.is_empty() {
+        return Err(CommandError::not_found(format!("Document {} not found in collection", document_id)));
+    }
+
+    let chunks_count = point_ids.len();
+    info!("📊 Found {} chunks to delete for document {}", chunks_count, document_id);
+
+    // 3. Supprimer les points via l'API Qdrant
+    state.qdrant_client
+        .delete_points(&collection_name, &point_ids)
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Qdrant delete failed: {:#}", e)))?;
+
+    info!("✅ Successfully deleted {} chunks for document {}", chunks_count, document_id);
+    state.query_cache.write().await.invalidate_group(&group_id);
+
+    // 4. Supprimer aussi du state en RAM (si présent)
+    let mut groups = state.groups.write().await;
+    if let Some(group) = groups.get_mut(&group_id) {
+        group.documents.retain(|doc| doc.id != document_id);
+        group.updated_at = SystemTime::now();
+        info!("🔄 Also removed document from RAM state");
+    }
+
+    Ok(DeleteRagDocumentResponse {
+        document_id,
+        chunks_deleted: chunks_count,
+        success: true,
+    })
+}
+
+/// Vider entièrement la collection Qdrant d'un groupe (ex: après changement de modèle d'embedding)
+///
+/// La collection est supprimée puis recréée vide ; `confirm` doit valoir `true`.
+#[tauri::command]
+pub async fn reset_group_collection(
+    group_id: String,
+    confirm: bool,
+    state: State<'_, RagState>,
+) -> Result<ResetGroupCollectionResponse, CommandError> {
+    if !confirm {
+        return Err(CommandError::invalid_input(format!("Reset of group {} not confirmed (confirm must be true)", group_id)));
+    }
+
+    warn!("🧨 Resetting Qdrant collection of group {}", group_id);
+
+    let (collection_name, collection_config) = {
+        let groups = state.groups.read().await;
+        groups.get(&group_id)
+            .map(|g| (g.qdrant_collection.clone(), g.collection_config))
+            .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?
+    };
+
+    // La collection est recréée à la dimension du modèle actif
+    let collection_config = crate::rag::CollectionConfig {
+        dimension: state.embedder().await.dimension(),
+        ..collection_config
+    };
+
+    // Nombre de points avant suppression (0 si la collection n'existe pas encore)
+    let points_removed = state.qdrant_client
+        .collection_info(&collection_name)
+        .await
+        .ok()
+        .and_then(|info| info["result"]["points_count"].as_u64())
+        .unwrap_or(0) as usize;
+
+    state.qdrant_client
+        .delete_collection(&collection_name)
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Failed to delete collection {}: {}", collection_name, e)))?;
+
+    state.qdrant_client
+        .create_collection(
+            &collection_name,
+            collection_config.dimension as u64,
+            collection_config.distance.as_qdrant_str(),
+        )
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Failed to recreate collection {}: {}", collection_name, e)))?;
+
+    state.query_cache.write().await.invalidate_group(&group_id);
+    let mut groups = state.groups.write().await;
+    let documents_cleared = match groups.get_mut(&group_id) {
+        Some(group) => {
+            let count = group.documents.len();
+            group.documents.clear();
+            group.collection_config = collection_config;
+            group.updated_at = SystemTime::now();
+            count
+        }
+        None => 0,
+    };
+
+    info!("✅ Collection {} reset: {} points removed, {} documents cleared from RAM",
+          collection_name, points_removed, documents_cleared);
+
+    Ok(ResetGroupCollectionResponse {
+        group_id,
+        collection_name,
+        points_removed,
+        documents_cleared,
+    })
+}
+
+/// Réponse de réinitialisation de la collection d'un groupe
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResetGroupCollectionResponse {
+    pub group_id: String,
+    pub collection_name: String,
+    pub points_removed: usize,
+    pub documents_cleared: usize,
+}
+
+/// Longueur maximale du nom affiché d'un groupe
+const MAX_GROUP_NAME_CHARS: usize = 120;
+
+/// Réponse de renommage d'un groupe
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RenameGroupResponse {
+    pub group_id: String,
+    pub previous_name: String,
+    pub name: String,
+}
+
+/// Renommer un groupe : seul le nom affiché change, l'identifiant et la collection Qdrant restent stables
+/// (voir `migrate_group_collection` pour changer de collection)
+#[tauri::command]
+pub async fn rename_group(
+    group_id: String,
+    new_name: String,
+    state: State<'_, RagState>,
+) -> Result<RenameGroupResponse, CommandError> {
+    let name = new_name.trim();
+    if name.is_empty() {
+        return Err(CommandError::invalid_input("Group name cannot be empty"));
+    }
+    if name.chars().count() > MAX_GROUP_NAME_CHARS {
+        return Err(CommandError::invalid_input(format!("Group name exceeds {} characters", MAX_GROUP_NAME_CHARS)));
+    }
+
+    let mut groups = state.groups.write().await;
+    let group = groups.get_mut(&group_id)
+        .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
+    let previous_name = std::mem::replace(&mut group.name, name.to_string());
+    let previous_updated_at = std::mem::replace(&mut group.updated_at, SystemTime::now());
+
+    if let Err(e) = state.group_registry.save(&groups) {
+        if let Some(group) = groups.get_mut(&group_id) {
+            group.name = previous_name;
+            group.updated_at = previous_updated_at;
+        }
+        return Err(CommandError::from(e).with_context("Failed to persist group rename"));
+    }
+
+    info!("✏️ Group {} renamed: '{}' -> '{}'", group_id, previous_name, name);
+    Ok(RenameGroupResponse { group_id, previous_name, name: name.to_string() })
+}
+
+/// Réponse de migration de la collection d'un groupe
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MigrateGroupCollectionResponse {
+    pub group_id: String,
+    pub previous_collection: String,
+    pub collection_name: String,
+    pub points_copied: usize,
+    /// `false` : l'ancienne collection n'a pas pu être supprimée (à nettoyer manuellement)
+    pub previous_collection_deleted: bool,
+    pub processing_time_ms: u64,
+}
+
+/// Nom de collection accepté par Qdrant (lettres, chiffres, `_` et `-`)
+fn validate_collection_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 255 {
+        return Err(format!("Invalid collection name '{}': expected 1-255 characters", name));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("Invalid collection name '{}': only letters, digits, '_' and '-' are allowed", name));
+    }
+    Ok(())
+}
+
+/// Copier les points d'un groupe vers une nouvelle collection Qdrant, y basculer le groupe
+/// (correspondance persistée) puis supprimer l'ancienne. En cas d'échec de la copie ou de
+/// l'enregistrement, la nouvelle collection est supprimée et le groupe reste sur l'ancienne. Les ingestions concurrentes pendant la copie ne sont pas migrées.
+#[tauri::command]
+pub async fn migrate_group_collection(
+    group_id: String,
+    new_collection: String,
+    state: State<'_, RagState>,
+) -> Result<MigrateGroupCollectionResponse, CommandError> {
+    let start_time = std::time::Instant::now();
+    validate_collection_name(&new_collection).map_err(CommandError::invalid_input)?;
+
+    let (previous_collection, collection_config) = {
+        let groups = state.groups.read().await;
+        groups.get(&group_id)
+            .map(|g| (g.qdrant_collection.clone(), g.collection_config))
+            .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?
+    };
+    if previous_collection == new_collection {
+        return Err(CommandError::invalid_input(format!("Group {} already uses collection {}", group_id, new_collection)));
+    }
+    let target_exists = state.qdrant_client
+        .find_collection_info(&new_collection)
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Qdrant collection lookup failed: {:#}", e)))?
+        .is_some();
+    if target_exists {
+        return Err(CommandError::already_exists(format!("Collection already exists: {}", new_collection)));
+    }
+
+    info!("🚚 Migrating group {} from collection {} to {}", group_id, previous_collection, new_collection);
+
+    // Collection source absente (aucun document ingéré) : rien à copier
+    let points = scroll_collection(
+        &state.qdrant_client,
+        &previous_collection,
+        None,
+        serde_json::json!({ "with_payload": true, "with_vector": true }),
+        None,
+        None,
+        false,
+    ).await?
+        .map(|scrolled| scrolled.points)
+        .unwrap_or_default()
+        .into_iter()
+        .map(serde_json::from_value::<crate::rag::RestPoint>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| CommandError::internal(format!("Unexpected Qdrant point format: {}", e)))?;
+    let points_copied = points.len();
+
+    state.qdrant_client
+        .create_collection(
+            &new_collection,
+            collection_config.dimension as u64,
+            collection_config.distance.as_qdrant_str(),
+        )
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Failed to create collection {}: {:#}", new_collection, e)))?;
+
+    // Identifiants de points conservés : les références existantes restent valides
+    if !points.is_empty() {
+        if let Err(e) = state.qdrant_client.upsert_points(&new_collection, points).await {
+            if let Err(cleanup) = state.qdrant_client.delete_collection(&new_collection).await {
+                warn!("⚠️ Failed to drop partial collection {}: {:#}", new_collection, cleanup);
+            }
+            return Err(CommandError::service_unavailable(format!(
+                "Copy to {} failed, group {} left on {}: {:#}", new_collection, group_id, previous_collection, e
+            )));
+        }
+    }
+
+    // Nouvelle correspondance enregistrée avant toute suppression : un redémarrage ne doit
+    // jamais ramener le groupe sur une collection effacée
+    let persisted = {
+        let mut groups = state.groups.write().await;
+        let group = groups.get_mut(&group_id)
+            .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
+        let previous_updated_at = std::mem::replace(&mut group.updated_at, SystemTime::now());
+        group.qdrant_collection = new_collection.clone();
+
+        let persisted = state.group_registry.save(&groups);
+        if persisted.is_err() {
+            if let Some(group) = groups.get_mut(&group_id) {
+                group.qdrant_collection = previous_collection.clone();
+                group.updated_at = previous_updated_at;
+            }
+        }
+        persisted
+    };
+    if let Err(e) = persisted {
+        if let Err(cleanup) = state.qdrant_client.delete_collection(&new_collection).await {
+            warn!("⚠️ Failed to drop copied collection {}: {:#}", new_collection, cleanup);
+        }
+        return Err(CommandError::from(e).with_context(&format!(
+            "Failed to persist migration, group {} left on {}", group_id, previous_collection
+        )));
+    }
+    state.query_cache.write().await.invalidate_group(&group_id);
+
+    let previous_collection_deleted = match state.qdrant_client.delete_collection(&previous_collection).await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("⚠️ Group {} migrated but collection {} could not be deleted: {:#}", group_id, previous_collection, e);
+            false
+        }
+    };
+
+    info!("✅ Group {} migrated to {} ({} points copied)", group_id, new_collection, points_copied);
+    Ok(MigrateGroupCollectionResponse {
+        group_id,
+        previous_collection,
+        collection_name: new_collection,
+        points_copied,
+        previous_collection_deleted,
+        processing_time_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+/// Changement de catégorie d'un document lors d'une reclassification
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CategoryChange {
+    pub document_id: String,
+    pub source_file: Option<String>,
+    pub previous_category: Option<String>,
+    pub new_category: String,
+}
+
+/// Réponse de reclassification des documents d'un groupe
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReclassifyGroupResponse {
+    pub group_id: String,
+    pub documents_scanned: usize,
+    pub documents_changed: usize,
+    pub changes: Vec<CategoryChange>,
+    pub processing_time_ms: u64,
+}
+
+/// Contenu d'un document reconstitué à partir de ses chunks Qdrant
+#[derive(Debug, Clone, PartialEq)]
+struct StoredDocumentContent {
+    document_id: String,
+    source_file: Option<String>,
+    category: Option<String>,
+    content: String,
+}
+
+/// Reconstituer le contenu de chaque document (chunks dans l'ordre des lignes source)
+fn collect_document_contents(points: &[serde_json::Value]) -> Vec<StoredDocumentContent> {
+    let mut chunks_by_document: HashMap<String, (StoredDocumentContent, Vec<(u64, String)>)> = HashMap::new();
+
+    for point in points {
+        let payload = &point["payload"];
+        let Some(document_id) = payload["document_id"].as_str() else {
+            continue;
+        };
+
+        let (_, chunks) = chunks_by_document.entry(document_id.to_string()).or_insert_with(|| (
+            StoredDocumentContent {
+                document_id: document_id.to_string(),
+                source_file: payload["source_file"].as_str().map(|s| s.to_string()),
+                category: payload["document_category"].as_str().map(|s| s.to_string()),
+                content: String::new(),
+            },
+            Vec::new(),
+        ));
+        if let Some(content) = payload["content"].as_str() {
+            chunks.push((payload["start_line"].as_u64().unwrap_or(0), content.to_string()));
+        }
+    }
+
+    let mut documents: Vec<StoredDocumentContent> = chunks_by_document.into_values()
+        .map(|(mut document, mut chunks)| {
+            chunks.sort_by_key(|(start_line, _)| *start_line);
+            document.content = chunks.into_iter().map(|(_, content)| content).collect::<Vec<_>>().join("\n");
+            document
+        })
+        .collect();
+    documents.sort_by(|a, b| a.document_id.cmp(&b.document_id));
+    documents
+}
+
+/// Reclasser les documents d'un groupe avec le classifieur courant, sans ré-ingestion :
+/// seul le champ `document_category` du payload Qdrant est mis à jour
+#[tauri::command]
+pub async fn reclassify_group(
+    group_id: String,
+    state: State<'_, RagState>,
+) -> Result<ReclassifyGroupResponse, CommandError> {
+    let start_time = std::time::Instant::now();
+
+    let collection_name = state.groups.read().await
+        .get(&group_id)
+        .map(|group| group.qdrant_collection.clone())
+        .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
+
+    info!("🏷️ Reclassifying documents of group {} (collection {})", group_id, collection_name);
+
+    let points = scroll_collection(
+        &state.qdrant_client,
+        &collection_name,
+        None,
+        serde_json::json!({ "with_payload": { "include": ["document_id", "source_file", "document_category", "content", "start_line"] } }),
+        None,
+        None,
+        false,
+    ).await?
+        .map(|scrolled| scrolled.points)
+        .unwrap_or_default();
+
+    let documents = collect_document_contents(&points);
+    let mut changes = Vec::new();
+
+    for document in &documents {
+        let category = state.document_classifier
+            .classify(&document.content)
+            .map_err(|e| CommandError::internal(format!("Classification failed for {}: {}", document.document_id, e)))?;
+        let new_category = format!("{:?}", category);
+
+        if document.category.as_deref() == Some(new_category.as_str()) {
+            continue;
+        }
+
+        let filter = serde_json::json!({
+            "must": [{ "key": "document_id", "match": { "value": document.document_id } }]
+        });
+        state.qdrant_client
+            .set_payload(&collection_name, &serde_json::json!({ "document_category": new_category }), &filter)
+            .await
+            .map_err(|e| CommandError::service_unavailable(format!("Qdrant payload update failed: {:#}", e)))?;
+
+        info!("🏷️ {} : {:?} → {}", document.document_id, document.category, new_category);
+        changes.push(CategoryChange {
+            document_id: document.document_id.clone(),
+            source_file: document.source_file.clone(),
+            previous_category: document.category.clone(),
+            new_category,
+        });
+    }
+
+    // Les réponses en cache ont été filtrées et enrichies avec les anciennes catégories
+    if !changes.is_empty() {
+        state.query_cache.write().await.invalidate_group(&group_id);
+    }
+
+    info!("✅ Reclassified group {}: {}/{} documents changed", group_id, changes.len(), documents.len());
+
+    Ok(ReclassifyGroupResponse {
+        group_id,
+        documents_scanned: documents.len(),
+        documents_changed: changes.len(),
+        changes,
+        processing_time_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+/// Exporter l'index complet d'un groupe (configuration + points avec vecteurs et payload)
+/// dans un bundle JSON versionné, réimportable via `import_group`
+#[tauri::command]
+pub async fn export_group(
+    group_id: String,
+    state: State<'_, RagState>,
+) -> Result<Vec<u8>, CommandError> {
+    let group = state.groups.read().await
+        .get(&group_id)
+        .cloned()
+        .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
+
+    info!("📦 Exporting group {} (collection {})", group_id, group.qdrant_collection);
+
+    let points = scroll_collection(
+        &state.qdrant_client,
+        &group.qdrant_collection,
+        None,
+        serde_json::json!({ "with_payload": true, "with_vector": true }),
+        None,
+        None,
+        false,
+    ).await?
+        .map(|scrolled| scrolled.points)
+        .unwrap_or_default()
+        .into_iter()
+        .map(serde_json::from_value::<crate::rag::RestPoint>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| CommandError::internal(format!("Unexpected Qdrant point format: {}", e)))?;
+
+    let embedding_model = state.embedder().await.model_key();
+    let bytes = GroupExportBundle::new(&group, embedding_model, points)
+        .to_bytes()
+        .map_err(CommandError::internal)?;
+
+    info!("✅ Group {} exported: {} documents, {} bytes", group_id, group.documents.len(), bytes.len());
+    Ok(bytes)
+}
+
+/// Statistiques de l'espace d'embedding d'un groupe (`get_group_embedding_stats`)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GroupEmbeddingStats {
+    pub group_id: String,
+    pub collection_name: String,
+    pub stats: EmbeddingSpaceStats,
+    pub processing_time_ms: u64,
+}
+
+/// Diagnostic de la distribution des vecteurs d'un groupe sur un échantillon scrollé :
+/// similarité moyenne entre paires, clusters serrés, chunks les plus centraux et isolés
+#[tauri::command]
+pub async fn get_group_embedding_stats(
+    group_id: String,
+    sample_size: Option<usize>,
+    state: State<'_, RagState>,
+) -> Result<GroupEmbeddingStats, CommandError> {
+    let start_time = std::time::Instant::now();
+    let sample_size = sample_size.unwrap_or(DEFAULT_EMBEDDING_SAMPLE_SIZE);
+    if sample_size < 2 {
+        return Err(CommandError::invalid_input("sample_size must be at least 2"));
+    }
+
+    let collection_name = state.groups.read().await
+        .get(&group_id)
+        .map(|group| group.qdrant_collection.clone())
+        .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
+
+    info!("📐 Computing embedding stats for group {} (sample of {} points)", group_id, sample_size);
+
+    let points = scroll_collection(
+        &state.qdrant_client,
+        &collection_name,
+        None,
+        serde_json::json!({ "with_payload": { "include": ["chunk_id", "document_id", "content"] }, "with_vector": true }),
+        None,
+        Some(sample_size),
+        false,
+    ).await?
+        .map(|scrolled| scrolled.points)
+        .unwrap_or_default();
+
+    let samples: Vec<SampledEmbedding> = points.iter()
+        .filter_map(|point| {
+            let vector = serde_json::from_value::<Vec<f32>>(point["vector"].clone()).ok()?;
+            let payload = &point["payload"];
+            Some(SampledEmbedding {
+                chunk_id: payload["chunk_id"].as_str()
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| point["id"].to_string()),
+                document_id: payload["document_id"].as_str().map(|id| id.to_string()),
+                content: payload["content"].as_str().unwrap_or_default().to_string(),
+                vector,
+            })
+        })
+        .take(sample_size)
+        .collect();
+
+    let stats = EmbeddingSpaceStats::compute(&samples);
+    info!("📐 Group {}: {} points, mean similarity {:.3} (σ {:.3}), {} tight clusters",
+          group_id, stats.sampled_points, stats.mean_pairwise_similarity,
+          stats.pairwise_similarity_std, stats.tight_clusters);
+
+    Ok(GroupEmbeddingStats {
+        group_id,
+        collection_name,
+        stats,
+        processing_time_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+/// Résultat de `export_embeddings`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbeddingExportResponse {
+    pub group_id: String,
+    pub collection_name: String,
+    pub embedding_model: String,
+    pub dimension: Option<usize>,
+    pub chunks_exported: usize,
+    /// Embeddings renvoyés directement (vide quand ils sont écrits dans `output_path`)
+    pub embeddings: Vec<ChunkEmbedding>,
+    pub output_path: Option<String>,
+    /// CSV des identifiants accompagnant un export `.npy` (ligne i ↔ ligne i de la matrice)
+    pub metadata_path: Option<String>,
+    pub processing_time_ms: u64,
+}
+
+/// Exporter les embeddings d'un groupe (chunk_id, document_id, aperçu, vecteur) pour l'analyse hors ligne.
+/// Avec `output_path` (chemin absolu .csv ou .npy), chaque page de scroll est écrite sur disque dès sa réception
+/// au lieu d'être renvoyée : la mémoire reste bornée à une page quelle que soit la taille de la collection.
+/// Les fichiers existants (y compris le CSV compagnon d'un `.npy`) ne sont remplacés qu'avec `overwrite`,
+/// et seulement une fois l'export terminé.
+#[tauri::command]
+pub async fn export_embeddings(
+    group_id: String,
+    output_path: Option<String>,
+    limit: Option<usize>,
+    overwrite: Option<bool>,
+    state: State<'_, RagState>,
+) -> Result<EmbeddingExportResponse, CommandError> {
+    let start_time = std::time::Instant::now();
+    let collection_name = state.groups.read().await
+        .get(&group_id)
+        .map(|group| group.qdrant_collection.clone())
+        .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
+
+    let overwrite = overwrite.unwrap_or(false);
+    let mut writer = match output_path.as_ref().map(PathBuf::from) {
+        Some(path) => {
+            validate_export_path(&path).map_err(CommandError::invalid_input)?;
+            let existing = existing_export_files(&path);
+            if !overwrite && !existing.is_empty() {
+                return Err(CommandError::already_exists(format!(
+                    "Embedding export would overwrite {:?} (set overwrite to replace)", existing
+                )));
+            }
+            Some(run_export_io(move || EmbeddingFileWriter::create(&path, overwrite)).await?)
+        }
+        None => None,
+    };
+    let metadata_path = writer.as_ref()
+        .and_then(|writer| writer.metadata_path())
+        .map(|path| path.display().to_string());
+
+    info!("📤 Exporting embeddings of group {} (collection {}) to {}",
+          group_id, collection_name, output_path.as_deref().unwrap_or("response"));
+
+    let projection = serde_json::json!({
+        "with_payload": { "include": ["chunk_id", "document_id", "content"] },
+        "with_vector": true
+    });
+    let mut embeddings = Vec::new();
+    let mut chunks_exported = 0;
+    let mut offset = None;
+    loop {
+        let remaining = limit.map(|limit| limit.saturating_sub(chunks_exported));
+        if remaining == Some(0) {
+            break;
+        }
+        let Some(page) = scroll_collection(
+            &state.qdrant_client,
+            &collection_name,
+            None,
+            projection.clone(),
+            offset,
+            remaining,
+            true,
+        ).await? else {
+            break;
+        };
+
+        let batch: Vec<ChunkEmbedding> = page.points.iter().filter_map(ChunkEmbedding::from_point).collect();
+        chunks_exported += batch.len();
+        match writer.take() {
+            Some(mut file_writer) => {
+                writer = Some(run_export_io(move || file_writer.write_batch(&batch).map(|_| file_writer)).await?);
+            }
+            None => embeddings.extend(batch),
+        }
+
+        offset = page.next_page_offset;
+        if offset.is_none() {
+            break;
+        }
+    }
+
+    let dimension = match writer {
+        Some(writer) => {
+            let dimension = writer.dimension();
+            run_export_io(move || writer.finish()).await?;
+            dimension
+        }
+        None => embeddings.first().map(|embedding| embedding.vector.len()),
+    };
+
+    info!("✅ Exported {} embeddings ({}D) from group {}",
+          chunks_exported, dimension.unwrap_or(0), group_id);
+
+    Ok(EmbeddingExportResponse {
+        group_id,
+        collection_name,
+        embedding_model: state.embedder().await.model_key(),
+        dimension,
+        chunks_exported,
+        embeddings,
+        output_path,
+        metadata_path,
+        processing_time_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+/// Écriture d'export sur disque hors runtime async (fichiers volumineux, disques lents)
+async fn run_export_io<T, F>(io: F) -> Result<T, CommandError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(io)
+        .await
+        .map_err(|e| CommandError::internal(format!("Embedding export task failed: {}", e)))?
+        .map_err(CommandError::internal)
+}
+
+/// Résultat de `import_group`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportGroupResponse {
+    pub group_id: String,
+    pub collection_name: String,
+    pub documents_imported: usize,
+    pub points_imported: usize,
+    pub processing_time_ms: u64,
+}
+
+/// Recréer un groupe et sa collection Qdrant depuis un bundle `export_group`.
+/// Refuse un groupe déjà chargé et toute dimension différente de l'embedder actif.
+#[tauri::command]
+pub async fn import_group(
+    bundle: Vec<u8>,
+    state: State<'_, RagState>,
+) -> Result<ImportGroupResponse, CommandError> {
+    let start_time = std::time::Instant::now();
+    let bundle = GroupExportBundle::from_bytes(&bundle).map_err(CommandError::invalid_input)?;
+    bundle.validate_dimension(state.embedder().await.dimension()).map_err(CommandError::invalid_input)?;
+
+    let GroupExportBundle { group, points, embedding_model, .. } = bundle;
+    if state.groups.read().await.contains_key(&group.id) {
+        return Err(CommandError::already_exists(format!("Group already exists: {}", group.id)));
+    }
+
+    let collection_name = group.qdrant_collection.clone();
+    let collection_config = group.collection_config;
+    info!("📥 Importing group {} ({} points, model {}) into {}", group.id, points.len(), embedding_model, collection_name);
+
+    // Une collection restante (données Qdrant conservées) n'est réutilisée qu'à dimension égale
+    let existing_dimension = state.qdrant_client
+        .find_collection_info(&collection_name)
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Qdrant collection lookup failed: {:#}", e)))?
+        .and_then(|info| info["result"]["config"]["params"]["vectors"]["size"].as_u64())
+        .map(|size| size as usize);
+    match existing_dimension {
+        Some(dimension) if dimension != collection_config.dimension => {
+            return Err(CommandError::invalid_input(format!(
+                "Embedding dimension mismatch: collection {} already exists with {}D vectors, bundle has {}D",
+                collection_name, dimension, collection_config.dimension
+            )));
+        }
+        Some(_) => {}
+        None => {
+            state.qdrant_client
+                .create_collection(
+                    &collection_name,
+                    collection_config.dimension as u64,
+                    collection_config.distance.as_qdrant_str(),
+                )
+                .await
+                .map_err(|e| CommandError::service_unavailable(format!("Failed to create collection {}: {:#}", collection_name, e)))?;
+        }
+    }
+
+    // Identifiants de points conservés : un import répété écrase au lieu de dupliquer
+    let points_imported = points.len();
+    if !points.is_empty() {
+        state.qdrant_client
+            .upsert_points(&collection_name, points)
+            .await
+            .map_err(|e| CommandError::service_unavailable(format!("Qdrant upsert failed: {:#}", e)))?;
+    }
+
+    let response = ImportGroupResponse {
+        group_id: group.id.clone(),
+        collection_name,
+        documents_imported: group.documents.len(),
+        points_imported,
+        processing_time_ms: start_time.elapsed().as_millis() as u64,
+    };
+    state.query_cache.write().await.invalidate_group(&group.id);
+    {
+        let mut groups = state.groups.write().await;
+        groups.insert(group.id.clone(), group);
+        if let Err(e) = state.group_registry.save(&groups) {
+            warn!("⚠️ Imported group {} not persisted, it will be lost on restart: {}", response.group_id, e);
+        }
+    }
+
+    info!("✅ Group {} imported: {} documents, {} points", response.group_id, response.documents_imported, points_imported);
+    Ok(response)
+}
+
+/// État de la collection Qdrant d'un groupe
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CollectionInfo {
+    pub group_id: String,
+    pub collection_name: String,
+    /// `false` : collection pas encore créée (aucun document ingéré)
+    pub exists: bool,
+    pub points_count: usize,
+    pub indexed_vectors_count: usize,
+    pub vectors_config: Option<VectorsConfigInfo>,
+    /// Statut Qdrant (green/yellow/red)
+    pub status: Option<String>,
+    pub optimizer_status: Option<String>,
+    /// Optimisation terminée (statut green, optimiseur ok)
+    pub indexed: bool,
+    /// Configuration attendue par le groupe (dimension du modèle, distance)
+    pub expected_config: crate::rag::CollectionConfig,
+}
+
+/// Configuration des vecteurs d'une collection Qdrant
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VectorsConfigInfo {
+    pub size: usize,
+    pub distance: String,
+}
+
+/// Informations de la collection Qdrant d'un groupe (nombre de vecteurs, dimension, distance, indexation)
+#[tauri::command]
+pub async fn get_collection_info(
+    group_id: String,
+    state: State<'_, RagState>,
+) -> Result<CollectionInfo, CommandError> {
+    let (collection_name, collection_config) = {
+        let groups = state.groups.read().await;
+        groups.get(&group_id)
+            .map(|g| (g.qdrant_collection.clone(), g.collection_config))
+            .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?
+    };
+
+    let info = state.qdrant_client
+        .find_collection_info(&collection_name)
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Qdrant collection info failed: {:#}", e)))?;
+
+    let collection_info = parse_collection_info(group_id, collection_name, collection_config, info.as_ref());
+    info!("📦 Collection {}: exists={}, {} points, indexed={}",
+          collection_info.collection_name, collection_info.exists,
+          collection_info.points_count, collection_info.indexed);
+
+    Ok(collection_info)
+}
+
+/// Construire `CollectionInfo` depuis la réponse `/collections/{name}` (`None` : collection absente)
+fn parse_collection_info(
+    group_id: String,
+    collection_name: String,
+    expected_config: crate::rag::CollectionConfig,
+    info: Option<&serde_json::Value>,
+) -> CollectionInfo {
+    let result = info.map(|info| &info["result"]);
+    let count = |key: &str| result
+        .and_then(|r| r[key].as_u64())
+        .unwrap_or(0) as usize;
+
+    let vectors = result.map(|r| &r["config"]["params"]["vectors"]);
+    let vectors_config = vectors
+        .and_then(|v| Some(VectorsConfigInfo {
+            size: v["size"].as_u64()? as usize,
+            distance: v["distance"].as_str().unwrap_or("unknown").to_string(),
+        }));
+
+    let status = result.and_then(|r| r["status"].as_str()).map(|s| s.to_string());
+    // "ok" ou {"error": "..."}
+    let optimizer_status = result.and_then(|r| match &r["optimizer_status"] {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    });
+    let indexed = status.as_deref() == Some("green") && optimizer_status.as_deref() == Some("ok");
+
+    CollectionInfo {
+        group_id,
+        collection_name,
+        exists: info.is_some(),
+        points_count: count("points_count"),
+        indexed_vectors_count: count("indexed_vectors_count"),
+        vectors_config,
+        status,
+        optimizer_status,
+        indexed,
+        expected_config,
+    }
+}
+
+/// Modèles d'embedding disponibles et modèle actif
+#[derive(Serialize, Debug, Clone)]
+pub struct EmbeddingModelsResponse {
+    pub active_model: String,
+    pub models: Vec<EmbeddingModelInfo>,
+}
+
+/// Résultat d'un changement de modèle d'embedding
+#[derive(Serialize, Debug, Clone)]
+pub struct EmbeddingModelSwitch {
+    pub previous_model: String,
+    pub model: EmbeddingModelInfo,
+    /// Groupes dont la collection a une autre dimension : à réinitialiser (`reset_group_collection`) puis réindexer
+    pub groups_requiring_reindex: Vec<String>,
+    pub warning: Option<String>,
+}
+
+/// Lister les modèles d'embedding sélectionnables
+#[tauri::command]
+pub async fn list_embedding_models(state: State<'_, RagState>) -> Result<EmbeddingModelsResponse, CommandError> {
+    Ok(EmbeddingModelsResponse {
+        active_model: state.embedder().await.model_key(),
+        models: EMBEDDING_MODELS.to_vec(),
+    })
+}
+
+/// Charger un autre modèle d'embedding et l'activer pour l'ingestion et la recherche
+///
+/// Les collections construites avec une autre dimension ne sont pas modifiées : elles sont
+/// signalées dans `groups_requiring_reindex`.
+#[tauri::command]
+pub async fn set_embedding_model(
+    name: String,
+    state: State<'_, RagState>,
+) -> Result<EmbeddingModelSwitch, CommandError> {
+    let model = EMBEDDING_MODELS.iter()
+        .find(|model| model.name == name || model.model_id == name)
+        .cloned()
+        .ok_or_else(|| CommandError::invalid_input(format!(
+            "Unknown embedding model: {} (available: {})",
+            name,
+            EMBEDDING_MODELS.iter().map(|m| m.name).collect::<Vec<_>>().join(", ")
+        )))?;
+    let config = CustomE5Config::for_model(model.name)
+        .ok_or_else(|| CommandError::internal(format!("No config for embedding model {}", model.name)))?;
+
+    info!("🔄 Switching embedding model to {} ({}D)", model.model_id, model.dimension);
+
+    // Chargement hors verrou : recherche et ingestion continuent sur l'ancien modèle
+    let embedder = CustomE5Embedder::new(config)
+        .await
+        .map_err(|e| CommandError::service_unavailable(format!("Failed to load embedding model {}: {}", model.model_id, e)))?;
+
+    let previous_model = {
+        let mut active = state.embedder.write().await;
+        let previous = active.model_key();
+        *active = Arc::new(embedder);
+        previous
+    };
+    // Embeddings de requête en cache calculés avec l'ancien modèle
+    state.query_cache.write().await.clear();
+
+    let groups_requiring_reindex = {
+        let groups = state.groups.read().await;
+        groups_with_other_dimension(&groups, model.dimension)
+    };
+    let warning = if groups_requiring_reindex.is_empty() {
+        None
+    } else {
+        let message = format!(
+            "{} group(s) were indexed with a different embedding dimension and must be reset and re-indexed before use with {}D: {}",
+            groups_requiring_reindex.len(), model.dimension, groups_requiring_reindex.join(", ")
+        );
+        warn!("⚠️ {}", message);
+        Some(message)
+    };
+
+    info!("✅ Embedding model switched: {} -> {}", previous_model, model.model_id);
+
+    Ok(EmbeddingModelSwitch {
+        previous_model,
+        model,
+        groups_requiring_reindex,
+        warning,
+    })
+}
+
+/// Identifiants (triés) des groupes dont la collection n'a pas la dimension donnée
+fn groups_with_other_dimension(groups: &HashMap<String, DocumentGroup>, dimension: usize) -> Vec<String> {
+    let mut ids: Vec<String> = groups.values()
+        .filter(|group| group.collection_config.dimension != dimension)
+        .map(|group| group.id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Métriques de qualité de l'extraction native d'un PDF, sans ingestion
+///
+/// `filename` est résolu dans le dossier exemple, sauf chemin absolu.
+#[tauri::command]
+pub async fn get_extraction_diagnostics(filename: String) -> Result<ExtractionQualityMetrics, CommandError> {
+    let path = if filename.starts_with("exemple/") || std::path::Path::new(&filename).is_absolute() {
+        resolve_document_path(&filename)?
+    } else {
+        resolve_document_path(&format!("exemple/{}", filename))?
+    };
+
+    if !path.exists() {
+        return Err(CommandError::not_found(format!("File not found: {:?}", path)));
+    }
+    let is_pdf = path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+    if !is_pdf {
+        return Err(CommandError::invalid_input(format!("Extraction diagnostics are only available for PDF files: {}", filename)));
+    }
+
+    let metrics = crate::rag::processing::diagnose_pdf_extraction(&path)
+        .await
+        .map_err(|e| format!("Extraction diagnostics failed: {}", e))?;
+
+    debug!("🩺 Extraction diagnostics for {}: native ratio {:.2}, printable {:.2}, {:.0} tokens/page",
+           filename, metrics.detected_native_ratio, metrics.printable_ratio, metrics.tokens_per_page);
+
+    Ok(metrics)
+}
+
+/// Longueur (en caractères) de l'aperçu de chaque chunk
+const CHUNK_PREVIEW_CHARS: usize = 120;
+
+/// Aperçu d'ingestion : extraction + chunking, sans embeddings ni écriture Qdrant
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IngestionPreview {
+    pub file_path: String,
+    pub group_id: String,
+    pub document_category: DocumentCategory,
+    pub source_type: SourceType,
+    pub chunk_count: usize,
+    pub estimated_total_tokens: usize,
+    pub chunks: Vec<ChunkPreview>,
+    pub processing_time_ms: u64,
+    pub extraction_quality: Option<ExtractionQualityMetrics>,
+}
+
+/// Aperçu d'un chunk tel qu'il serait indexé
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChunkPreview {
+    pub chunk_id: String,
+    pub preview: String,
+    pub source_type: SourceType,
+    pub chunk_source: ChunkSource,
+    pub estimated_tokens: usize,
+}
+
+/// Prévisualiser l'ingestion d'un document (dry run) pour ajuster le ChunkConfig du groupe
+///
+/// Même extraction et chunking que `add_document_intelligent`, mais ni embeddings,
+/// ni upsert Qdrant, ni ajout au groupe.
+#[tauri::command]
+pub async fn preview_ingestion(
+    file_path: String,
+    group_id: String,
+    state: State<'_, RagState>,
+) -> Result<IngestionPreview, CommandError> {
+    let start_time = std::time::Instant::now();
+    info!("🔎 Previewing ingestion of {} for group {}", file_path, group_id);
+
+    let path = resolve_document_path(&file_path)?;
+    if !path.exists() {
+        return Err(CommandError::not_found(format!("File not found: {:?}", path)));
+    }
+
+    let chunk_config = {
+        let groups = state.groups.read().await;
+        groups.get(&group_id)
+            .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?
+            .chunk_config
+            .clone()
+    };
+
+    let document = state.ingestion_engine
+        .ingest_document(&path, &group_id, &chunk_config)
+        .await
+        .map_err(|e| CommandError::from(e).with_context("Document processing failed"))?
+        .document;
+
+    let document_category = state.document_classifier
+        .classify(&document.content)
+        .map_err(|e| format!("Classification failed: {}", e))?;
+
+    let mut preview = build_ingestion_preview(&document, document_category);
+    preview.file_path = file_path;
+    preview.processing_time_ms = start_time.elapsed().as_millis() as u64;
+
+    info!("🔎 Preview: {} chunks, ~{} tokens, category {:?}",
+          preview.chunk_count, preview.estimated_total_tokens, preview.document_category);
+
+    Ok(preview)
+}
+
+/// Construire l'aperçu d'un document déjà extrait et découpé
+fn build_ingestion_preview(document: &GroupDocument, document_category: DocumentCategory) -> IngestionPreview {
+    let chunks: Vec<ChunkPreview> = document.chunks
+        .iter()
+        .map(|chunk| ChunkPreview {
+            chunk_id: chunk.id.clone(),
+            preview: chunk.content.chars().take(CHUNK_PREVIEW_CHARS).collect(),
+            source_type: chunk.metadata.source_type.clone(),
+            chunk_source: chunk.chunk_source.clone(),
+            estimated_tokens: crate::rag::estimate_tokens(&chunk.content),
+        })
+        .collect();
+
+    IngestionPreview {
+        file_path: document.file_path.display().to_string(),
+        group_id: document.group_id.clone(),
+        document_category,
+        source_type: document_source_type(document),
+        chunk_count: chunks.len(),
+        estimated_total_tokens: chunks.iter().map(|chunk| chunk.estimated_tokens).sum(),
+        chunks,
+        processing_time_ms: 0,
+        extraction_quality: document.metadata.custom_fields
+            .get(EXTRACTION_QUALITY_FIELD)
+            .and_then(|json| serde_json::from_str::<ExtractionQualityMetrics>(json).ok()),
+    }
+}
+
+/// Type de source global d'un document, d'après sa stratégie d'extraction
+fn document_source_type(document: &GroupDocument) -> SourceType {
+    match &document.document_type {
+        crate::rag::DocumentType::PDF { extraction_strategy, .. } => {
+            match extraction_strategy {
+                crate::rag::PdfStrategy::NativeOnly => SourceType::NativeText,
+                crate::rag::PdfStrategy::OcrOnly => SourceType::OcrExtracted,
+                crate::rag::PdfStrategy::HybridIntelligent => SourceType::HybridPdfNative,
+            }
+        },
+        crate::rag::DocumentType::Image { .. } => SourceType::OcrExtracted,
+        _ => SourceType::NativeText,
+    }
+}
+
+/// Délai maximal de chaque vérification de `system_health`
+const HEALTH_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// État d'un composant de la stack
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    Degraded,
+    Down,
+}
+
+/// Résultat de la vérification d'un composant
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComponentHealth {
+    pub component: String,
+    pub status: HealthStatus,
+    pub message: String,
+    pub latency_ms: u64,
+}
+
+/// Diagnostic de démarrage : Tesseract, langues OCR, Qdrant et embedder
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HealthReport {
+    /// Pire état parmi les composants
+    pub status: HealthStatus,
+    pub components: Vec<ComponentHealth>,
+    pub total_time_ms: u64,
+}
+
+/// Vérifier que la stack RAG est opérationnelle (vérifications en parallèle, délai court)
+#[tauri::command]
+pub async fn system_health(state: State<'_, RagState>) -> Result<HealthReport, CommandError> {
+    let start_time = std::time::Instant::now();
+
+    let tesseract = timed_health_check("tesseract", HEALTH_CHECK_TIMEOUT, async {
+        tesseract_health(crate::rag::ocr::get_tesseract_version().await)
+    });
+
+    let languages = timed_health_check("ocr_languages", HEALTH_CHECK_TIMEOUT, async {
+        match crate::rag::ocr::get_available_languages().await {
+            Ok(available) => {
+                let missing = crate::rag::ocr::missing_languages(&TesseractConfig::default().languages, &available);
+                if missing.is_empty() {
+                    (HealthStatus::Ok, format!("Installed: {}", available.join(", ")))
+                } else {
+                    (HealthStatus::Degraded, format!("Missing default languages: {} (TESSDATA_PREFIX: {})",
+                                                     missing.join(", "), crate::rag::ocr::tessdata_prefix()))
                 }
             }
+            // Déjà signalé par le composant `tesseract`
+            Err(crate::rag::ocr::OcrError::NotInstalled(_)) => {
+                (HealthStatus::Degraded, "Skipped: Tesseract is not installed".to_string())
+            }
+            Err(e) => (HealthStatus::Down, e.to_string()),
+        }
+    });
 
-            // Moyenne de confiance
-            if let Some(conf) = payload.get("confidence").and_then(|v| v.as_f64()) {
-                entry.confidence = (entry.confidence * (entry.chunks_count - 1) as f32 + conf as f32)
-                    / entry.chunks_count as f32;
+    let qdrant = timed_health_check("qdrant", HEALTH_CHECK_TIMEOUT, async {
+        match state.qdrant_client.health_check().await {
+            Ok(true) => (HealthStatus::Ok, "Reachable".to_string()),
+            Ok(false) => (HealthStatus::Down, "Unreachable".to_string()),
+            Err(e) => (HealthStatus::Down, e.to_string()),
+        }
+    });
+
+    let embedder = timed_health_check("embedder", HEALTH_CHECK_TIMEOUT, async {
+        let embedder = state.embedder().await;
+        match embedder.encode("ping").await {
+            Ok(embedding) if embedding.len() == embedder.dimension() => {
+                (HealthStatus::Ok, format!("{} ({} dims)", embedder.model_key(), embedding.len()))
             }
+            Ok(embedding) => (
+                HealthStatus::Degraded,
+                format!("Unexpected dimension {} (expected {})", embedding.len(), embedder.dimension()),
+            ),
+            Err(e) => (HealthStatus::Down, e.to_string()),
         }
-    }
+    });
 
-    let documents: Vec<RagDocumentInfo> = document_map.into_values().collect();
+    let (tesseract, languages, qdrant, embedder) = tokio::join!(tesseract, languages, qdrant, embedder);
+    let warm_up = warm_up_health(state.warm_up.read().await.as_ref());
+    let components = vec![tesseract, languages, qdrant, embedder, warm_up];
+    let status = overall_health_status(&components);
 
-    info!("📊 Returning {} documents with {} total chunks from collection {}",
-          documents.len(),
-          documents.iter().map(|d| d.chunks_count).sum::<usize>(),
-          collection_name);
+    info!("🩺 System health: {:?} ({})", status,
+          components.iter().map(|c| format!("{}={:?}", c.component, c.status)).collect::<Vec<_>>().join(", "));
 
-    Ok(documents)
+    Ok(HealthReport {
+        status,
+        components,
+        total_time_ms: start_time.elapsed().as_millis() as u64,
+    })
 }
 
-/// Information simplifiée sur un document RAG
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct RagDocumentInfo {
-    pub document_id: String,
-    pub group_id: String,
-    pub chunks_count: usize,
-    pub confidence: f32,
-    pub sample_content: String,
-    pub source_file: Option<String>,
-    pub document_title: Option<String>,
-    pub document_author: Option<String>,
-    pub document_category: Option<String>,
-    pub document_tags: Vec<String>,
+/// État de Tesseract : absent, seul l'OCR est indisponible (le texte natif reste indexé),
+/// d'où `Degraded` avec les instructions d'installation plutôt que `Down`
+fn tesseract_health(version: crate::rag::ocr::Result<String>) -> (HealthStatus, String) {
+    match version {
+        Ok(version) => (HealthStatus::Ok, version),
+        Err(crate::rag::ocr::OcrError::NotInstalled(instructions)) => (
+            HealthStatus::Degraded,
+            format!("Not installed: OCR is disabled for images and scanned PDFs. {}", instructions),
+        ),
+        Err(e) => (HealthStatus::Down, e.to_string()),
+    }
 }
 
-/// Supprimer un document RAG et tous ses chunks de Qdrant
-#[tauri::command]
-pub async fn delete_rag_document(
-    document_id: String,
-    group_id: String,
-    state: State<'_, RagState>,
-) -> Result<DeleteRagDocumentResponse, String> {
-    info!("🗑️ Deleting RAG document {} from group {}", document_id, group_id);
-
-    // Récupérer le nom de la collection
-    let groups = state.groups.read().await;
-    let collection_name = if let Some(group) = groups.get(&group_id) {
-        group.qdrant_collection.clone()
-    } else {
-        format!("collection_{}", group_id)
-    };
-    drop(groups);
-
-    // 1. Récupérer tous les points du document via scroll avec filtre
-    let url = format!("http://localhost:6333/collections/{}/points/scroll", collection_name);
-    let client = reqwest::Client::new();
-
-    let response = client
-        .post(&url)
-        .json(&serde_json::json!({
-            "limit": 1000,
-            "with_payload": true,
-            "with_vector": false,
-            "filter": {
-                "must": [{
-                    "key": "document_id",
-                    "match": {
-                        "value": document_id
-                    }
-                }]
-            }
-        }))
-        .send()
+/// Exécuter une vérification bornée par `timeout` ; un dépassement vaut `Down`
+async fn timed_health_check<F>(component: &str, timeout: std::time::Duration, check: F) -> ComponentHealth
+where
+    F: std::future::Future<Output = (HealthStatus, String)>,
+{
+    let start_time = std::time::Instant::now();
+    let (status, message) = tokio::time::timeout(timeout, check)
         .await
-        .map_err(|e| format!("Qdrant scroll request failed: {}", e))?;
+        .unwrap_or_else(|_| (HealthStatus::Down, format!("Timed out after {}ms", timeout.as_millis())));
 
-    if !response.status().is_success() {
-        return Err(format!("Qdrant scroll returned error: {}", response.status()));
+    if status != HealthStatus::Ok {
+        warn!("🩺 {} is {:?}: {}", component, status, message);
     }
 
-    let data: serde_json::Value = response.json().await
-        .map_err(|e| format!("Failed to parse Qdrant scroll response: {}", e))?;
+    ComponentHealth {
+        component: component.to_string(),
+        status,
+        message,
+        latency_ms: start_time.elapsed().as_millis() as u64,
+    }
+}
 
-    let points = data["result"]["points"].as_array()
-        .ok_or_else(|| "Invalid Qdrant scroll response format".to_string())?;
+/// État global : le pire des composants (Ok si aucun composant)
+fn overall_health_status(components: &[ComponentHealth]) -> HealthStatus {
+    components.iter().map(|c| c.status).max().unwrap_or(HealthStatus::Ok)
+}
 
-    // 2. Extraire les IDs des points à supprimer
-    let point_ids: Vec<String> = points
-        .iter()
-        .filter_map(|point| {
-            point["id"].as_str().map(|s| s.to_string())
-        })
-        .collect();
+/// Préchauffage d'un sous-système
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WarmUpComponent {
+    pub component: String,
+    pub ready: bool,
+    pub duration_ms: u64,
+    pub message: String,
+}
 
-    if point_ids.is_empty() {
-        return Err(format!("Document {} not found in collection", document_id));
+/// Rapport de préchauffage : modèles chargés avant la première requête utilisateur
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WarmUpReport {
+    pub components: Vec<WarmUpComponent>,
+    pub total_time_ms: u64,
+}
+
+impl WarmUpReport {
+    pub fn is_ready(&self) -> bool {
+        self.components.iter().all(|c| c.ready)
     }
+}
 
-    let chunks_count = point_ids.len();
-    info!("📊 Found {} chunks to delete for document {}", chunks_count, document_id);
+/// Charger l'embedder et Tesseract avec une requête jetable, puis mémoriser le rapport dans l'état
+pub async fn run_warm_up(state: &RagState) -> WarmUpReport {
+    let start_time = std::time::Instant::now();
+    info!("🔥 Warming up embedder and Tesseract");
+
+    let embedder = timed_warm_up("embedder", async {
+        let embedder = state.embedder().await;
+        embedder.encode_document("warm-up").await.map_err(|e| e.to_string())?;
+        embedder.encode("warm-up").await.map_err(|e| e.to_string())?;
+        Ok(embedder.model_key())
+    });
+
+    // Processeur Tesseract paresseux de l'ingestion : l'instance préchauffée est celle qui servira
+    let tesseract = timed_warm_up("tesseract", async {
+        state.ingestion_engine
+            .document_processor()
+            .warm_up_ocr()
+            .await
+            .map(|_| "OCR engine loaded".to_string())
+            .map_err(|e| e.to_string())
+    });
+
+    let (embedder, tesseract) = tokio::join!(embedder, tesseract);
+    let report = WarmUpReport {
+        components: vec![embedder, tesseract],
+        total_time_ms: start_time.elapsed().as_millis() as u64,
+    };
 
-    // 3. Supprimer les points via l'API Qdrant
-    let delete_url = format!("http://localhost:6333/collections/{}/points/delete", collection_name);
-    let delete_response = client
-        .post(&delete_url)
-        .json(&serde_json::json!({
-            "points": point_ids
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Qdrant delete request failed: {}", e))?;
+    info!("🔥 Warm-up finished in {}ms ({})", report.total_time_ms,
+          report.components.iter().map(|c| format!("{}={}ms", c.component, c.duration_ms)).collect::<Vec<_>>().join(", "));
+
+    *state.warm_up.write().await = Some(report.clone());
+    report
+}
+
+/// Exécuter une étape de préchauffage en mesurant sa durée
+async fn timed_warm_up<F>(component: &str, step: F) -> WarmUpComponent
+where
+    F: std::future::Future<Output = std::result::Result<String, String>>,
+{
+    let start_time = std::time::Instant::now();
+    let (ready, message) = match step.await {
+        Ok(message) => (true, message),
+        Err(e) => {
+            warn!("🔥 {} warm-up failed: {}", component, e);
+            (false, e)
+        }
+    };
 
-    if !delete_response.status().is_success() {
-        return Err(format!("Qdrant delete returned error: {}", delete_response.status()));
+    WarmUpComponent {
+        component: component.to_string(),
+        ready,
+        duration_ms: start_time.elapsed().as_millis() as u64,
+        message,
     }
+}
 
-    info!("✅ Successfully deleted {} chunks for document {}", chunks_count, document_id);
+/// État du préchauffage pour `system_health`
+fn warm_up_health(report: Option<&WarmUpReport>) -> ComponentHealth {
+    let (status, message, latency_ms) = match report {
+        None => (HealthStatus::Degraded, "Warming up".to_string(), 0),
+        Some(report) if report.is_ready() => (HealthStatus::Ok, "Models ready".to_string(), report.total_time_ms),
+        Some(report) => {
+            let failed: Vec<String> = report.components.iter()
+                .filter(|c| !c.ready)
+                .map(|c| format!("{}: {}", c.component, c.message))
+                .collect();
+            (HealthStatus::Degraded, format!("Warm-up failed ({})", failed.join("; ")), report.total_time_ms)
+        }
+    };
 
-    // 4. Supprimer aussi du state en RAM (si présent)
-    let mut groups = state.groups.write().await;
-    if let Some(group) = groups.get_mut(&group_id) {
-        group.documents.retain(|doc| doc.id != document_id);
-        group.updated_at = SystemTime::now();
-        info!("🔄 Also removed document from RAM state");
+    ComponentHealth {
+        component: "warm_up".to_string(),
+        status,
+        message,
+        latency_ms,
     }
+}
 
-    Ok(DeleteRagDocumentResponse {
-        document_id,
-        chunks_deleted: chunks_count,
-        success: true,
-    })
+/// Précharger l'embedder et Tesseract (aussi lancé en tâche de fond au démarrage)
+#[tauri::command]
+pub async fn warm_up(state: State<'_, RagState>) -> Result<WarmUpReport, CommandError> {
+    Ok(run_warm_up(&state).await)
 }
 
 /// Réponse de suppression d'un document RAG
@@ -908,6 +3594,7 @@ pub struct RagContextResponse {
     pub total_chunks: usize,
     pub query: String,
     pub search_time_ms: u64,
+    pub intent: AnswerIntent,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -918,6 +3605,57 @@ pub struct SourceInfo {
     pub score: f32,
     pub source_file: Option<String>,
     pub document_category: Option<String>,
+    pub chunk_source: ChunkSource,
+    pub figure_id: Option<String>,
+}
+
+/// KPIs financiers extraits d'un document (enrichissement mis en cache sur le document)
+#[tauri::command]
+pub async fn get_financial_kpis(
+    document_id: String,
+    group_id: String,
+    state: State<'_, RagState>,
+) -> Result<Vec<FinancialKPI>, CommandError> {
+    info!("💹 Getting financial KPIs for document {} in group {}", document_id, group_id);
+    financial_kpis_for_document(&state.groups, &state.business_enricher, &group_id, &document_id).await
+}
+
+/// KPIs d'un document : métadonnées déjà calculées, sinon extraction sur une copie du contenu
+/// sans verrou sur les groupes (l'enrichissement est long), puis mémorisation sur le document
+async fn financial_kpis_for_document(
+    groups: &RwLock<HashMap<String, DocumentGroup>>,
+    enricher: &BusinessMetadataEnricher,
+    group_id: &str,
+    document_id: &str,
+) -> Result<Vec<FinancialKPI>, CommandError> {
+    let content = {
+        let groups = groups.read().await;
+        let group = groups.get(group_id)
+            .ok_or_else(|| CommandError::not_found(format!("Group not found: {}", group_id)))?;
+        let document = group.documents.iter()
+            .find(|doc| doc.id == document_id)
+            .ok_or_else(|| CommandError::not_found(format!("Document not found: {}", document_id)))?;
+        if let Some(ref business) = document.business_metadata {
+            return Ok(business.financial_kpis.clone());
+        }
+        document.content.clone()
+    };
+
+    let business = enricher
+        .enrich_business_content(&content, None, None)
+        .map_err(|e| format!("Business enrichment failed: {}", e))?;
+    let kpis = business.financial_kpis.clone();
+
+    // Document retiré, ré-ingéré ou déjà enrichi entre-temps : son état courant prime
+    let mut groups = groups.write().await;
+    let document = groups.get_mut(group_id)
+        .and_then(|group| group.documents.iter_mut().find(|doc| doc.id == document_id));
+    if let Some(document) = document.filter(|doc| doc.business_metadata.is_none() && doc.content == content) {
+        document.business_metadata = Some(business);
+    }
+
+    info!("✅ Extracted {} financial KPIs", kpis.len());
+    Ok(kpis)
 }
 
 /// Interroger le RAG et formater le contexte pour le LLM
@@ -926,22 +3664,38 @@ pub async fn query_rag_with_context(
     query: String,
     group_id: String,
     limit: Option<usize>,
+    categories: Option<Vec<DocumentCategory>>,
+    source_types: Option<Vec<SourceType>>,
+    min_score: Option<f32>,
     state: State<'_, RagState>,
-) -> Result<RagContextResponse, String> {
+) -> Result<RagContextResponse, CommandError> {
     let start_time = std::time::Instant::now();
     info!("🤖 RAG query for LLM: '{}' in group {}", query, group_id);
+    if categories.is_some() || source_types.is_some() {
+        info!("🔎 Scoped to categories {:?}, source types {:?}", categories, source_types);
+    }
 
-    // 1. Recherche dans le RAG
+    // 1. Recherche dans le RAG (sans filtre ni seuil explicite : seuil fixé par l'intention de la requête)
     let search_params = AdvancedSearchParams {
         query: query.clone(),
         group_id: group_id.clone(),
         limit,
-        min_score: Some(0.5), // Filtrer les résultats peu pertinents
-        document_categories: None,
-        source_types: None,
+        min_score,
+        document_categories: categories,
+        source_types,
         min_ocr_confidence: None,
         include_business_metadata: true,
         fiscal_year_filter: None,
+        // Contexte LLM sans passages répétés par l'overlap d'ingestion
+        merge_overlapping: true,
+        max_merged_chars: None,
+        tags: None,
+        priorities: None,
+        author: None,
+        project: None,
+        cross_lingual: false,
+        semantic_cache: false,
+        numeric_filter: None,
     };
 
     let search_response = search_with_metadata(search_params, state.clone()).await?;
@@ -962,6 +3716,9 @@ pub async fn query_rag_with_context(
             formatted_context.push_str(&format!("Fichier: {}\n", source_file));
         }
 
+        let source_label = result.chunk_source.context_label(result.figure_id.as_deref());
+        formatted_context.push_str(&format!("Type: {}\n", source_label));
+
         // Format enum as string for display
         formatted_context.push_str(&format!("Catégorie: {:?}\n", result.document_category));
 
@@ -981,6 +3738,8 @@ pub async fn query_rag_with_context(
             score: result.score,
             source_file: result.source_file.clone(),
             document_category: Some(format!("{:?}", result.document_category)),
+            chunk_source: result.chunk_source.clone(),
+            figure_id: result.figure_id.clone(),
         });
     }
 
@@ -1018,19 +3777,22 @@ pub async fn query_rag_with_context(
         total_chunks: search_response.results.len(),
         query,
         search_time_ms: search_time,
+        intent: search_response.intent,
     })
 }
 
 // === Fonctions utilitaires ===
 
-async fn get_cache_statistics(_state: &RagState) -> CacheStats {
-    // TODO: Implémenter vraies statistiques depuis UnifiedCache
+async fn get_cache_statistics(state: &RagState) -> CacheStats {
+    let global = state.unified_cache.get_global_stats();
+    let total_hits = (global.ocr_cache_hits + global.embedding_cache_hits + global.document_cache_hits) as u64;
+
     CacheStats {
-        ocr_cache_hits: 0,
-        embedding_cache_hits: 0,
-        document_cache_hits: 0,
-        total_hits: 0,
-        hit_ratio: 0.0,
+        ocr_cache_hits: global.ocr_cache_hits as u64,
+        embedding_cache_hits: global.embedding_cache_hits as u64,
+        document_cache_hits: global.document_cache_hits as u64,
+        total_hits,
+        hit_ratio: global.hit_ratio(),
     }
 }
 
@@ -1073,25 +3835,10 @@ fn calculate_global_confidence(document: &GroupDocument, business_metadata: &Opt
     }
 }
 
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
-    }
-
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-    if norm_a == 0.0 || norm_b == 0.0 {
-        0.0
-    } else {
-        dot_product / (norm_a * norm_b)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rag::test_fixtures::{test_chunk, test_search_result};
 
     #[tokio::test]
     async fn test_rag_pipeline_with_preextracted_text() {
@@ -1156,4 +3903,632 @@ Pour toute réclamation, veuillez contacter votre centre des impôts.
 
         info!("✅ Test détection chunk d'urgence: SUCCÈS");
     }
+
+    fn figure_region_chunk() -> EnrichedChunk {
+        let mut chunk = test_chunk("fig_region_Figure_2_p1", "[FIGURE REGION - Figure 2 - Page 2]");
+        chunk.metadata.tags = vec!["figure".to_string()];
+        chunk.chunk_source = ChunkSource::FigureRegionText;
+        chunk.figure_id = Some("Figure 2".to_string());
+        chunk
+    }
+
+    #[test]
+    fn test_chunk_source_payload_roundtrip() {
+        let chunk = figure_region_chunk();
+
+        let mut payload = HashMap::new();
+        insert_chunk_source_fields(&mut payload, &chunk);
+        let stored: serde_json::Map<String, serde_json::Value> = payload.into_iter().collect();
+
+        assert_eq!(
+            chunk_source_from_payload(&stored),
+            (ChunkSource::FigureRegionText, Some("Figure 2".to_string()))
+        );
+        assert_eq!(chunk_source_from_payload(&serde_json::Map::new()), (ChunkSource::BodyText, None));
+    }
+
+    #[test]
+    fn test_validate_embedding_dimensions() {
+        let mut chunk = figure_region_chunk();
+        chunk.embedding = Some(vec![0.0; 384]);
+
+        assert!(validate_embedding_dimensions(std::slice::from_ref(&chunk), 384, "collection_g").is_ok());
+        let err = validate_embedding_dimensions(&[chunk], 768, "collection_g").unwrap_err();
+        assert!(err.contains("384D") && err.contains("768D"));
+    }
+
+    fn ranged_result(document_id: &str, chunk_id: &str, lines: (usize, usize), content: &str, score: f32) -> SearchResultWithMetadata {
+        let mut result = test_search_result(chunk_id, content, score);
+        result.document_id = document_id.to_string();
+        result.chunk_metadata.start_line = lines.0;
+        result.chunk_metadata.end_line = lines.1;
+        result
+    }
+
+    #[test]
+    fn test_merge_overlapping_results() {
+        let shared = "le chiffre d'affaires progresse de 12% sur l'exercice";
+        let results = vec![
+            ranged_result("doc_a", "a1", (1, 10), &format!("Introduction du rapport annuel, {}", shared), 0.6),
+            ranged_result("doc_a", "a2", (8, 20), &format!("{} grâce à l'export.", shared), 0.9),
+            ranged_result("doc_a", "a3", (40, 50), "Section sans rapport avec les précédentes.", 0.5),
+            ranged_result("doc_b", "b1", (11, 20), "Autre document, lignes voisines.", 0.7),
+            ranged_result("doc_c", "legacy", (0, 0), "Point indexé sans lignes.", 0.4),
+        ];
+
+        let merged = merge_overlapping_results(results.clone(), MAX_MERGED_CONTENT_CHARS);
+        assert_eq!(merged.len(), 4);
+        let first = &merged[0];
+        assert_eq!(first.score, 0.9);
+        assert_eq!(first.chunk_id, "a2");
+        assert_eq!(first.merged_chunk_ids, vec!["a1".to_string(), "a2".to_string()]);
+        assert_eq!(first.content, format!("Introduction du rapport annuel, {} grâce à l'export.", shared));
+        assert_eq!(first.content.matches(shared).count(), 1);
+        assert_eq!((first.chunk_metadata.start_line, first.chunk_metadata.end_line), (1, 20));
+
+        // Plafond : pas de fusion au-delà de max_chars
+        let capped = merge_overlapping_results(results, 60);
+        assert_eq!(capped.len(), 5);
+        assert!(capped.iter().all(|r| r.merged_chunk_ids.is_empty()));
+    }
+
+    #[test]
+    fn test_parse_collection_info() {
+        let config = crate::rag::CollectionConfig::for_dimension(384);
+
+        let missing = parse_collection_info("g".to_string(), "collection_g".to_string(), config, None);
+        assert!(!missing.exists && !missing.indexed);
+        assert_eq!(missing.points_count, 0);
+        assert!(missing.vectors_config.is_none());
+
+        let response = serde_json::json!({
+            "result": {
+                "status": "green",
+                "optimizer_status": "ok",
+                "points_count": 42,
+                "indexed_vectors_count": 0,
+                "config": { "params": { "vectors": { "size": 384, "distance": "Cosine" } } }
+            }
+        });
+        let info = parse_collection_info("g".to_string(), "collection_g".to_string(), config, Some(&response));
+        assert!(info.exists && info.indexed);
+        assert_eq!(info.points_count, 42);
+        assert_eq!(info.vectors_config, Some(VectorsConfigInfo { size: 384, distance: "Cosine".to_string() }));
+
+        let failing = serde_json::json!({ "result": { "status": "red", "optimizer_status": { "error": "disk full" } } });
+        let info = parse_collection_info("g".to_string(), "collection_g".to_string(), config, Some(&failing));
+        assert!(!info.indexed);
+        assert!(info.optimizer_status.unwrap().contains("disk full"));
+    }
+
+    #[test]
+    fn test_embedding_model_switch_reindex_detection() {
+        let config = CustomE5Config::for_model("e5-base-v2").unwrap();
+        assert_eq!(config.model_id, "intfloat/e5-base-v2");
+        assert_eq!(config.embedding_dimension, 768);
+        assert!(CustomE5Config::for_model("unknown").is_none());
+
+        let mut groups = HashMap::new();
+        for (id, dimension) in [("g_small", 384), ("g_base", 768)] {
+            let group = DocumentGroup::new_with_id(id.to_string(), id.to_string())
+                .with_collection_config(crate::rag::CollectionConfig::for_dimension(dimension));
+            groups.insert(id.to_string(), group);
+        }
+
+        assert_eq!(groups_with_other_dimension(&groups, 768), vec!["g_small".to_string()]);
+        assert_eq!(groups_with_other_dimension(&groups, 1024).len(), 2);
+    }
+
+    #[test]
+    fn test_build_fiscal_year_filter() {
+        assert!(build_fiscal_year_filter(None).is_none());
+
+        let filter = build_fiscal_year_filter(Some(2023)).unwrap();
+        assert_eq!(filter["must"][0]["key"], "fiscal_year");
+        assert_eq!(filter["must"][0]["match"]["value"], 2023);
+    }
+
+    #[test]
+    fn test_numeric_fact_filter_and_boost() {
+        let params: AdvancedSearchParams = serde_json::from_value(serde_json::json!({
+            "query": "précision à 16x", "group_id": "g", "limit": null, "min_score": null,
+            "document_categories": null, "source_types": null, "min_ocr_confidence": null,
+            "include_business_metadata": false, "fiscal_year_filter": null,
+            "numeric_filter": { "unit": "%", "gte": 95.0 }
+        })).unwrap();
+        let condition = &build_search_filter(&params).unwrap()["must"][0]["nested"];
+        assert_eq!(condition["key"], NUMERIC_FACTS_FIELD);
+        assert_eq!(condition["filter"]["must"][0]["match"]["value"], "%");
+        assert_eq!(condition["filter"]["must"][1]["range"]["gte"], 95.0);
+        assert!(condition["filter"]["must"][1]["range"].get("lte").is_none());
+        assert!(build_numeric_fact_condition(&NumericFactFilter::default()).is_none());
+
+        let mut results = vec![
+            ranged_result("doc_a", "a", (1, 2), "97.3% precision at 10x compression", 0.80),
+            ranged_result("doc_b", "b", (1, 2), "97.3% precision at 16x compression", 0.75),
+        ];
+        for result in results.iter_mut() {
+            result.numeric_facts = extract_numeric_facts(&result.content);
+        }
+        apply_numeric_fact_boost(&mut results, &extract_numeric_facts(&params.query));
+        assert_eq!(results[0].chunk_id, "b");
+        assert!((results[0].score - 0.9).abs() < 1e-4);
+        assert!((results[1].score - 0.80).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_build_document_filter() {
+        assert!(build_document_filter(None, None).is_none());
+        assert!(build_document_filter(None, Some(&[])).is_none());
+
+        let tags = vec!["finance".to_string(), "2024".to_string()];
+        let filter = build_document_filter(Some("Business"), Some(&tags)).unwrap();
+        let must = filter["must"].as_array().unwrap();
+
+        assert_eq!(must.len(), 3);
+        assert_eq!(must[0]["key"], "document_category");
+        assert_eq!(must[0]["match"]["value"], "Business");
+        assert_eq!(must[2]["match"]["value"], "2024");
+    }
+
+    #[test]
+    fn test_browse_chunks_filter_and_point_mapping() {
+        assert!(build_chunk_filter(&ChunkBrowseFilter::default()).is_none());
+
+        let filter = build_chunk_filter(&ChunkBrowseFilter {
+            document_id: Some("doc-1".to_string()),
+            tags: Some(vec!["finance".to_string()]),
+            chunk_source: Some(ChunkSource::Table),
+            ..ChunkBrowseFilter::default()
+        }).unwrap();
+        let must = filter["must"].as_array().unwrap();
+        assert_eq!(must.len(), 3);
+        assert_eq!(must[0]["key"], "document_tags");
+        assert_eq!(must[1]["match"]["value"], "doc-1");
+        assert_eq!(must[2]["match"]["value"], serde_json::json!(ChunkSource::Table));
+
+        let point = serde_json::json!({
+            "id": "8d9c",
+            "payload": {
+                "content": "Chiffre d'affaires 2024",
+                "chunk_id": "chunk_3",
+                "document_id": "doc-1",
+                "start_line": 12,
+                "end_line": 18,
+                "chunk_source": ChunkSource::Table,
+                "document_tags": ["finance"]
+            }
+        });
+        let chunk = browsed_chunk_from_point(&point).unwrap();
+        assert_eq!(chunk.chunk_id, "chunk_3");
+        assert_eq!((chunk.start_line, chunk.end_line), (Some(12), Some(18)));
+        assert_eq!(chunk.chunk_source, ChunkSource::Table);
+        assert!(chunk.metadata.contains_key("document_tags"));
+        assert!(!chunk.metadata.contains_key("content"));
+        assert!(browsed_chunk_from_point(&serde_json::json!({ "id": 1 })).is_none());
+    }
+
+    #[test]
+    fn test_merge_qdrant_hits_keeps_best_score() {
+        let original = serde_json::json!([{"id": "a", "score": 0.62}, {"id": "b", "score": 0.55}]);
+        let translated = serde_json::json!([{"id": "b", "score": 0.81}, {"id": "c", "score": 0.70}]);
+
+        let merged = merge_qdrant_hits(&original, &translated, 10);
+        let ids: Vec<&str> = merged.as_array().unwrap().iter().map(|hit| hit["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec!["b", "c", "a"]);
+        assert_eq!(merged[0]["score"], 0.81);
+
+        assert_eq!(merge_qdrant_hits(&original, &translated, 2).as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_category_and_source_filters_pushed_to_qdrant() {
+        let params: AdvancedSearchParams = serde_json::from_value(serde_json::json!({
+            "query": "budget", "group_id": "g", "limit": 5, "min_score": null,
+            "document_categories": ["Business", "Legal"], "source_types": ["NativeText"],
+            "min_ocr_confidence": null, "include_business_metadata": false, "fiscal_year_filter": null
+        })).unwrap();
+        let must = build_search_filter(&params).unwrap()["must"].as_array().unwrap().clone();
+        assert_eq!(must.len(), 2);
+        assert_eq!(must[0], serde_json::json!({ "key": "document_category", "match": { "any": ["Business", "Legal"] } }));
+        assert_eq!(must[1], serde_json::json!({ "key": "source_type", "match": { "any": ["NativeText"] } }));
+
+        // OCR demandé : les points sans `source_type` (anciens index) restent éligibles
+        let ocr_params = AdvancedSearchParams {
+            document_categories: None,
+            source_types: Some(vec![SourceType::OcrExtracted]),
+            ..params
+        };
+        let must = build_search_filter(&ocr_params).unwrap()["must"].as_array().unwrap().clone();
+        assert_eq!(must.len(), 1);
+        assert_eq!(must[0]["should"][1]["is_empty"]["key"], "source_type");
+    }
+
+    #[test]
+    fn test_user_metadata_filter_and_priority_weights() {
+        let mut metadata = EnrichedMetadata {
+            tags: vec!["auto-imported".to_string()],
+            priority: Priority::Normal,
+            description: None,
+            author: None,
+            project: None,
+            custom_fields: HashMap::new(),
+        };
+        DocumentUserMetadata {
+            tags: vec!["confidential".to_string(), "auto-imported".to_string()],
+            priority: Some(Priority::High),
+            project: Some("Audit 2024".to_string()),
+            ..Default::default()
+        }.apply_to(&mut metadata);
+        assert_eq!(metadata.tags, vec!["auto-imported", "confidential"]);
+        assert_eq!(metadata.priority, Priority::High);
+        assert_eq!(metadata.project.as_deref(), Some("Audit 2024"));
+
+        let params: AdvancedSearchParams = serde_json::from_value(serde_json::json!({
+            "query": "budget", "group_id": "g", "limit": null, "min_score": null,
+            "document_categories": null, "source_types": null, "min_ocr_confidence": null,
+            "include_business_metadata": false, "fiscal_year_filter": 2024,
+            "tags": ["confidential"], "priorities": ["High"], "project": "Audit 2024"
+        })).unwrap();
+        let must = build_search_filter(&params).unwrap()["must"].as_array().unwrap().clone();
+        assert_eq!(must.len(), 4);
+        assert_eq!(must[1]["key"], "document_tags");
+        assert_eq!(must[2]["match"]["any"][0], "High");
+        assert_eq!(must[3]["key"], "document_project");
+
+        let mut results = vec![
+            ranged_result("doc_a", "a", (1, 2), "normal", 0.80),
+            ranged_result("doc_b", "b", (1, 2), "important", 0.75),
+        ];
+        results[1].document_priority = Priority::High;
+        apply_priority_weights(&mut results, &PriorityWeights::default());
+        assert_eq!(results[0].chunk_id, "b");
+        assert!((results[0].score - 0.8625).abs() < 1e-4);
+
+        // Multiplicateurs neutres : classement cosinus inchangé
+        let neutral = PriorityWeights { low: 1.0, normal: 1.0, high: 1.0 };
+        results[0].score = 0.75;
+        apply_priority_weights(&mut results, &neutral);
+        assert_eq!(results[0].chunk_id, "a");
+        assert!(PriorityWeights { low: 0.0, ..neutral }.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_timeout_and_overall_status() {
+        let timeout = std::time::Duration::from_millis(20);
+        let slow = timed_health_check("slow", timeout, async {
+            tokio::time::sleep(timeout * 10).await;
+            (HealthStatus::Ok, "late".to_string())
+        });
+        let degraded = timed_health_check("degraded", timeout, async {
+            (HealthStatus::Degraded, "partial".to_string())
+        });
+
+        let (slow, degraded) = tokio::join!(slow, degraded);
+        assert_eq!(slow.status, HealthStatus::Down);
+        assert_eq!(degraded.status, HealthStatus::Degraded);
+
+        assert_eq!(overall_health_status(&[degraded.clone()]), HealthStatus::Degraded);
+        assert_eq!(overall_health_status(&[degraded, slow]), HealthStatus::Down);
+        assert_eq!(overall_health_status(&[]), HealthStatus::Ok);
+    }
+
+    #[tokio::test]
+    async fn test_scroll_pages_consumes_large_collections() {
+        // 2500 points répartis sur 3 documents, servis par pages comme Qdrant
+        let all_points: Vec<serde_json::Value> = (0..2500)
+            .map(|i| serde_json::json!({
+                "id": i,
+                "payload": { "document_id": format!("doc_{}", i % 3), "confidence": 0.9, "content": "texte" }
+            }))
+            .collect();
+        let fetch = |offset: Option<serde_json::Value>, page_size: usize| {
+            let start = offset.and_then(|o| o.as_u64()).unwrap_or(0) as usize;
+            let end = (start + page_size).min(all_points.len());
+            let next = if end < all_points.len() { serde_json::json!(end) } else { serde_json::Value::Null };
+            let page = serde_json::json!({ "result": { "points": all_points[start..end], "next_page_offset": next } });
+            async move { Ok(Some(page)) }
+        };
+
+        let full = scroll_pages(fetch, None, None, false).await.unwrap().unwrap();
+        assert_eq!(full.points.len(), 2500);
+        assert!(full.next_page_offset.is_none());
+
+        let documents = group_points_by_document(&full.points, "g1");
+        assert_eq!(documents.len(), 3);
+        assert_eq!(documents.iter().map(|d| d.chunks_count).sum::<usize>(), 2500);
+
+        let sample = scroll_pages(fetch, None, None, true).await.unwrap().unwrap();
+        assert_eq!(sample.points.len(), SCROLL_PAGE_SIZE);
+        assert_eq!(sample.next_page_offset, Some(serde_json::json!(1000)));
+
+        let capped = scroll_pages(fetch, None, Some(1500), false).await.unwrap().unwrap();
+        assert_eq!(capped.points.len(), 1500);
+        assert_eq!(capped.next_page_offset, Some(serde_json::json!(1500)));
+    }
+
+    #[test]
+    fn test_collect_document_contents_orders_chunks() {
+        let point = |document_id: &str, start_line: u64, content: &str| serde_json::json!({
+            "payload": {
+                "document_id": document_id,
+                "document_category": "Mixed",
+                "source_file": format!("{}.pdf", document_id),
+                "start_line": start_line,
+                "content": content
+            }
+        });
+        let points = vec![
+            point("doc_b", 1, "Article 1"),
+            point("doc_a", 12, "second"),
+            point("doc_a", 1, "premier"),
+            serde_json::json!({ "payload": { "content": "sans document" } }),
+        ];
+
+        let documents = collect_document_contents(&points);
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].document_id, "doc_a");
+        assert_eq!(documents[0].content, "premier\nsecond");
+        assert_eq!(documents[0].category.as_deref(), Some("Mixed"));
+        assert_eq!(documents[1].source_file.as_deref(), Some("doc_b.pdf"));
+    }
+
+    #[test]
+    fn test_tesseract_not_installed_is_degraded() {
+        use crate::rag::ocr::{tesseract_install_instructions, OcrError};
+
+        let (status, message) = tesseract_health(Err(OcrError::NotInstalled(tesseract_install_instructions().to_string())));
+        assert_eq!(status, HealthStatus::Degraded);
+        assert!(message.contains(tesseract_install_instructions()), "{}", message);
+
+        assert_eq!(tesseract_health(Ok("tesseract 5.3.4".to_string())).0, HealthStatus::Ok);
+        assert_eq!(tesseract_health(Err(OcrError::Timeout)).0, HealthStatus::Down);
+    }
+
+    #[test]
+    fn test_warm_up_health() {
+        let component = |name: &str, ready: bool| WarmUpComponent {
+            component: name.to_string(),
+            ready,
+            duration_ms: 10,
+            message: if ready { "ok".to_string() } else { "no tessdata".to_string() },
+        };
+
+        assert_eq!(warm_up_health(None).status, HealthStatus::Degraded);
+
+        let ready = WarmUpReport { components: vec![component("embedder", true), component("tesseract", true)], total_time_ms: 20 };
+        let health = warm_up_health(Some(&ready));
+        assert_eq!(health.status, HealthStatus::Ok);
+        assert_eq!(health.message, "Models ready");
+
+        let failed = WarmUpReport { components: vec![component("embedder", true), component("tesseract", false)], total_time_ms: 20 };
+        let health = warm_up_health(Some(&failed));
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert!(health.message.contains("tesseract: no tessdata"));
+    }
+
+    #[test]
+    fn test_chunk_bounding_box_from_ocr_blocks() {
+        use crate::rag::core::direct_chat::{BlockType, BoundingBox};
+
+        let block = |page_number: u32, content: &str, x: f64, y: f64, width: f64, height: f64| OCRBlock {
+            page_number,
+            block_type: BlockType::Text,
+            content: content.to_string(),
+            bounding_box: BoundingBox { x, y, width, height },
+            confidence: 1.0,
+            spans: vec![],
+        };
+        let blocks = vec![
+            block(1, "Sommaire", 0.0, 0.0, 50.0, 10.0),
+            block(2, "Chiffre d'affaires 2023 : 12 M€.", 10.0, 20.0, 100.0, 10.0),
+            block(2, "Résultat net : 2 M€", 10.0, 40.0, 80.0, 10.0),
+            block(3, "Résultat net : 2 M€", 0.0, 0.0, 50.0, 10.0),
+        ];
+        let content = "Sommaire\n\nChiffre d'affaires 2023 : 12 M€.\nRésultat net : 2 M€\n\nRésultat net : 2 M€";
+        let located_blocks = locate_ocr_blocks(content, &blocks);
+
+        // Blocs au texte identique : chacun à sa propre plage
+        let block_starts: Vec<usize> = located_blocks.iter().map(|((start, _), _)| *start).collect();
+        assert_eq!(block_starts.len(), 4);
+        assert!(block_starts.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // Fin de la page 1 reprise en overlap : ignorée, seuls les blocs de la page 2 sont retenus
+        // (le doublon de la page 3 est hors de la plage du chunk)
+        let mut chunk = test_chunk("c", "Sommaire\nChiffre d'affaires 2023 : 12 M€.\nRésultat net : 2 M€");
+        chunk.metadata.page_overlap_len = "Sommaire".len();
+        let chunk_range = crate::rag::locate_chunks(content, std::slice::from_ref(&chunk))[0].unwrap();
+
+        let bbox = chunk_bounding_box(chunk_range, &located_blocks).unwrap();
+        assert_eq!(bbox.page, Some(2));
+        assert_eq!((bbox.x, bbox.y, bbox.width, bbox.height), (10.0, 20.0, 100.0, 30.0));
+
+        // Plage sans bloc (texte entre deux blocs)
+        assert!(chunk_bounding_box((8, 10), &located_blocks).is_none());
+    }
+
+    #[test]
+    fn test_compute_extraction_breakdown() {
+        use crate::rag::DocumentType;
+
+        let make_chunk = |source_type: SourceType, extraction_method: ExtractionMethod| {
+            let mut chunk = test_chunk(&uuid::Uuid::new_v4().to_string(), "chunk");
+            chunk.metadata.source_type = source_type;
+            chunk.metadata.extraction_method = extraction_method;
+            chunk
+        };
+        let ocr = |confidence| ExtractionMethod::TesseractOcr { confidence, language: "fra".to_string() };
+
+        let document = GroupDocument {
+            id: "doc".to_string(),
+            file_path: PathBuf::from("doc.pdf"),
+            language: "fr".to_string(),
+            content: String::new(),
+            chunks: vec![
+                make_chunk(SourceType::NativeText, ExtractionMethod::PdfNative),
+                make_chunk(SourceType::OcrExtracted, ocr(0.6)),
+                make_chunk(SourceType::OcrExtracted, ocr(0.8)),
+            ],
+            metadata: EnrichedMetadata {
+                tags: vec![],
+                priority: Priority::Normal,
+                description: None,
+                author: None,
+                project: None,
+                custom_fields: HashMap::new(),
+            },
+            last_modified: SystemTime::now(),
+            document_type: DocumentType::PlainText,
+            group_id: "g".to_string(),
+            ocr_blocks: Vec::new(),
+            business_metadata: None,
+        };
+
+        let breakdown = compute_extraction_breakdown(&document);
+        assert_eq!(breakdown.chunks_by_source_type.get("OcrExtracted"), Some(&2));
+        assert_eq!(breakdown.chunks_by_source_type.get("NativeText"), Some(&1));
+        assert!((breakdown.native_text_ratio - 1.0 / 3.0).abs() < 1e-6);
+        assert!((breakdown.mean_ocr_confidence.unwrap() - 0.7).abs() < 1e-6);
+        assert!(matches!(
+            breakdown.dominant_extraction_method,
+            Some(ExtractionMethod::TesseractOcr { .. })
+        ));
+    }
+
+    #[test]
+    fn test_ingestion_preview() {
+        let content = "Facture n°2024-017 ".repeat(10);
+        let mut document = test_group_document("scan", &content);
+        document.document_type = crate::rag::DocumentType::PDF {
+            extraction_strategy: crate::rag::PdfStrategy::OcrOnly,
+            native_text_ratio: 0.0,
+            ocr_pages: vec![1],
+            total_pages: 1,
+        };
+        let quality = ExtractionQualityMetrics::compute("", 0, 1);
+        document.metadata.custom_fields.insert(
+            EXTRACTION_QUALITY_FIELD.to_string(),
+            serde_json::to_string(&quality).unwrap(),
+        );
+
+        let preview = build_ingestion_preview(&document, DocumentCategory::Business);
+        assert_eq!(preview.file_path, "scan.txt");
+        assert_eq!(preview.group_id, "g");
+        assert_eq!(preview.chunk_count, 1);
+        assert_eq!(preview.chunks[0].preview.chars().count(), CHUNK_PREVIEW_CHARS);
+        assert!(content.starts_with(&preview.chunks[0].preview));
+        assert_eq!(preview.estimated_total_tokens, crate::rag::estimate_tokens(&content));
+        assert_eq!(preview.source_type, SourceType::OcrExtracted);
+        assert_eq!(preview.extraction_quality, Some(quality));
+
+        // Métriques absentes ou illisibles : pas de qualité exposée
+        document.metadata.custom_fields.insert(EXTRACTION_QUALITY_FIELD.to_string(), "{".to_string());
+        assert!(build_ingestion_preview(&document, DocumentCategory::Business).extraction_quality.is_none());
+    }
+
+    #[test]
+    fn test_validate_collection_name() {
+        assert!(validate_collection_name("collection_group_1a2b").is_ok());
+        assert!(validate_collection_name("contrats-2024").is_ok());
+        assert!(validate_collection_name("").is_err());
+        assert!(validate_collection_name("mes contrats").is_err());
+        assert!(validate_collection_name("../collections").is_err());
+    }
+
+    fn test_group_document(id: &str, content: &str) -> GroupDocument {
+        GroupDocument {
+            id: id.to_string(),
+            file_path: PathBuf::from(format!("{}.txt", id)),
+            language: "fr".to_string(),
+            content: content.to_string(),
+            chunks: vec![test_chunk(&format!("{}_chunk", id), content)],
+            metadata: EnrichedMetadata {
+                tags: vec![],
+                priority: Priority::Normal,
+                description: None,
+                author: None,
+                project: None,
+                custom_fields: HashMap::new(),
+            },
+            last_modified: SystemTime::now(),
+            document_type: crate::rag::DocumentType::PlainText,
+            group_id: "g".to_string(),
+            ocr_blocks: Vec::new(),
+            business_metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_reingest_replaces_group_entry_and_keeps_current_points() {
+        let mut documents = vec![test_group_document("doc_1", "v1")];
+        upsert_group_document(&mut documents, test_group_document("doc_1", "v2"));
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[0].content, "v2");
+
+        upsert_group_document(&mut documents, test_group_document("doc_2", "autre"));
+        assert_eq!(documents.len(), 2);
+
+        let filter = stale_points_filter("doc_1", &[serde_json::json!("p1"), serde_json::json!("p2")]);
+        assert_eq!(filter["must"][0]["match"]["value"], "doc_1");
+        assert_eq!(filter["must_not"][0]["has_id"], serde_json::json!(["p1", "p2"]));
+    }
+
+    #[test]
+    fn test_find_duplicate_in_groups_scope() {
+        let mut document = test_group_document("doc_1", "contrat");
+        document.metadata.custom_fields.insert(FILE_CHECKSUM_FIELD.to_string(), "abc123".to_string());
+        let mut group_a = DocumentGroup::new_with_id("group_a".to_string(), "A".to_string());
+        group_a.documents.push(document);
+        let group_b = DocumentGroup::new_with_id("group_b".to_string(), "B".to_string());
+        let groups: HashMap<String, DocumentGroup> = [group_a, group_b].into_iter()
+            .map(|group| (group.id.clone(), group))
+            .collect();
+
+        let duplicate = find_duplicate_in_groups(&groups, "abc123", None).unwrap();
+        assert_eq!((duplicate.document_id.as_str(), duplicate.group_id.as_str()), ("doc_1", "group_a"));
+        assert_eq!(duplicate.source_file.as_deref(), Some("doc_1.txt"));
+        assert!(find_duplicate_in_groups(&groups, "abc123", Some("group_a")).is_some());
+        assert!(find_duplicate_in_groups(&groups, "abc123", Some("group_b")).is_none());
+        assert!(find_duplicate_in_groups(&groups, "autre", None).is_none());
+
+        let request = duplicate_lookup_request("abc123");
+        assert_eq!(request["filter"]["must"][0]["key"], FILE_CHECKSUM_FIELD);
+        assert_eq!(request["filter"]["must"][0]["match"]["value"], "abc123");
+        assert_eq!(request["with_vector"], false);
+    }
+
+    #[tokio::test]
+    async fn test_financial_kpis_cached_on_document() {
+        let mut group = DocumentGroup::new_with_id("g".to_string(), "Finance".to_string());
+        group.documents.push(test_group_document(
+            "doc_1",
+            "Annual Report FY 2023. Revenue increased to $2.1 billion, with EBITDA of $450 million and Net Income of $120 million.",
+        ));
+        let groups = RwLock::new(HashMap::from([(group.id.clone(), group)]));
+        let enricher = BusinessMetadataEnricher::new();
+
+        let kpis = financial_kpis_for_document(&groups, &enricher, "g", "doc_1").await.unwrap();
+        let cached = groups.read().await["g"].documents[0].business_metadata.clone()
+            .expect("Enrichissement mémorisé sur le document");
+        assert_eq!(cached.financial_kpis.len(), kpis.len());
+        assert_eq!(financial_kpis_for_document(&groups, &enricher, "g", "doc_1").await.unwrap().len(), kpis.len());
+
+        assert!(financial_kpis_for_document(&groups, &enricher, "g", "absent").await.is_err());
+        assert!(financial_kpis_for_document(&groups, &enricher, "absent", "doc_1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_checksum_follows_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let [original, renamed, edited] = ["original.pdf", "copie.pdf", "modifie.pdf"].map(|name| dir.path().join(name));
+        std::fs::write(&original, b"%PDF-1.7 contenu").unwrap();
+        std::fs::write(&renamed, b"%PDF-1.7 contenu").unwrap();
+        std::fs::write(&edited, b"%PDF-1.7 contenu modifie").unwrap();
+
+        let checksum = file_checksum(original.clone()).await.unwrap();
+        assert_eq!(checksum, blake3::hash(b"%PDF-1.7 contenu").to_hex().to_string());
+        assert_eq!(checksum, file_checksum(renamed).await.unwrap());
+        assert_ne!(checksum, file_checksum(edited).await.unwrap());
+        assert!(file_checksum(dir.path().join("absent.pdf")).await.is_err());
+    }
 }
\ No newline at end of file
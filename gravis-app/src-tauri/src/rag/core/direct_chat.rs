@@ -28,6 +28,104 @@ pub struct DirectChatSession {
     pub embeddings: Vec<f32>,
     pub created_at: SystemTime,
     pub is_temporary: bool,
+
+    /// Documents agrégés dans la session (le premier est `document_path`)
+    #[serde(default)]
+    pub documents: Vec<SessionDocument>,
+
+    /// Historique glissant des échanges (questions de suivi)
+    #[serde(default)]
+    pub history: Vec<ConversationTurn>,
+
+    /// `Processing` tant que les embeddings sont calculés en arrière-plan
+    #[serde(default)]
+    pub status: SessionStatus,
+}
+
+/// État d'une session : les embeddings d'un document dragué sont calculés après la réponse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Processing,
+    #[default]
+    Ready,
+}
+
+/// Nombre maximal de tours conservés par session
+pub const MAX_HISTORY_TURNS: usize = 10;
+
+/// Tour de conversation (question + réponse + sources citées)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationTurn {
+    pub query: String,
+    pub response: String,
+    pub timestamp: SystemTime,
+    #[serde(default)]
+    pub sources: Vec<SourceSummary>,
+}
+
+/// Résumé d'une source contributrice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceSummary {
+    pub chunk_id: String,
+    pub content_preview: String,
+    pub score: f32,
+    pub confidence: f64,
+    pub span_count: usize,
+    pub source_document: Option<String>, // Document d'origine (sessions multi-documents)
+    #[serde(default)]
+    pub page: Option<usize>, // Page issue des spans réels du chunk
+    #[serde(default)]
+    pub figure_id: Option<String>,
+}
+
+impl SourceSummary {
+    /// Citation inline de la source : "[Figure 2]", "[p.3]", ou None sans page connue
+    pub fn citation(&self) -> Option<String> {
+        match (&self.figure_id, self.page) {
+            (Some(figure_id), _) => Some(format!("[{}]", figure_id)),
+            (None, Some(page)) => Some(format!("[p.{}]", page)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl ConversationTurn {
+    /// Estimation grossière du nombre de tokens (~4 caractères par token)
+    pub fn estimated_tokens(&self) -> usize {
+        (self.query.chars().count() + self.response.chars().count()).div_ceil(4)
+    }
+}
+
+/// Document rattaché à une session multi-documents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionDocument {
+    pub document_id: String,
+    pub document_name: String,
+    pub document_path: PathBuf,
+    pub chunk_ids: Vec<String>,
+    pub added_at: SystemTime,
+}
+
+impl SessionDocument {
+    pub fn new(document_path: PathBuf, chunks: &[EnrichedChunk]) -> Self {
+        Self {
+            document_id: Uuid::new_v4().to_string(),
+            document_name: document_display_name(&document_path),
+            document_path,
+            chunk_ids: chunks.iter().map(|chunk| chunk.id.clone()).collect(),
+            added_at: SystemTime::now(),
+        }
+    }
+}
+
+/// Nom affichable d'un document (sans le préfixe des fichiers temporaires)
+fn document_display_name(document_path: &std::path::Path) -> String {
+    let name = document_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("unknown");
+    name.strip_prefix("gravis_temp_").unwrap_or(name).to_string()
 }
 
 /// Réponse de chat direct avec spans contributeurs
@@ -289,6 +387,7 @@ impl DirectChatSession {
             .and_then(|name| name.to_str())
             .unwrap_or("unknown")
             .to_string();
+        let documents = vec![SessionDocument::new(document_path.clone(), &chunks)];
 
         Self {
             session_id: Uuid::new_v4().to_string(),
@@ -302,6 +401,9 @@ impl DirectChatSession {
             embeddings: vec![],
             created_at: SystemTime::now(),
             is_temporary: true,
+            documents,
+            history: Vec::new(),
+            status: SessionStatus::Ready,
         }
     }
 
@@ -359,6 +461,70 @@ impl DirectChatSession {
         }
     }
 
+    /// Refuser la recherche tant que les embeddings sont calculés en arrière-plan :
+    /// les chunks encore sans embedding seraient ignorés silencieusement.
+    pub fn ensure_ready(&self) -> DirectChatResult<()> {
+        match self.status {
+            SessionStatus::Ready => Ok(()),
+            SessionStatus::Processing => Err(DirectChatError::SessionProcessing(self.session_id.clone())),
+        }
+    }
+
+    /// Ajouter un document à la session: ses chunks rejoignent l'espace de recherche commun
+    pub fn add_document(
+        &mut self,
+        document_path: PathBuf,
+        chunks: Vec<EnrichedChunk>,
+        search_content: OCRContent,
+    ) -> SessionDocument {
+        let document = SessionDocument::new(document_path, &chunks);
+
+        self.chunks.extend(chunks);
+        self.search_content.pages.extend(search_content.pages);
+        self.documents.push(document.clone());
+
+        document
+    }
+
+    /// Enregistrer un tour de conversation (fenêtre glissante de MAX_HISTORY_TURNS)
+    pub fn push_turn(&mut self, query: String, response: String, sources: Vec<SourceSummary>) {
+        self.history.push(ConversationTurn {
+            query,
+            response,
+            timestamp: SystemTime::now(),
+            sources,
+        });
+        if self.history.len() > MAX_HISTORY_TURNS {
+            let overflow = self.history.len() - MAX_HISTORY_TURNS;
+            self.history.drain(..overflow);
+        }
+    }
+
+    /// Tours les plus récents tenant dans `max_tokens` (ordre chronologique)
+    pub fn recent_history(&self, max_tokens: usize) -> &[ConversationTurn] {
+        let mut budget = max_tokens;
+        let mut start = self.history.len();
+
+        for turn in self.history.iter().rev() {
+            let cost = turn.estimated_tokens();
+            if cost > budget {
+                break;
+            }
+            budget -= cost;
+            start -= 1;
+        }
+
+        &self.history[start..]
+    }
+
+    /// Nom du document source d'un chunk
+    pub fn document_name_for_chunk(&self, chunk_id: &str) -> Option<&str> {
+        self.documents
+            .iter()
+            .find(|doc| doc.chunk_ids.iter().any(|id| id == chunk_id))
+            .map(|doc| doc.document_name.as_str())
+    }
+
     /// Obtenir nombre de chunks avec embeddings
     pub fn embedded_chunks_count(&self) -> usize {
         self.chunks
@@ -409,6 +575,9 @@ pub enum DirectChatError {
     
     #[error("Session expired: {0}")]
     SessionExpired(String),
+
+    #[error("Session still processing embeddings: {0}")]
+    SessionProcessing(String),
     
     #[error("Document processing failed: {0}")]
     ProcessingFailed(String),
@@ -421,6 +590,9 @@ pub enum DirectChatError {
     
     #[error("Embedding generation failed: {0}")]
     EmbeddingFailed(String),
+
+    #[error("LLM synthesis failed: {0}")]
+    LlmSynthesisFailed(String),
 }
 
 pub type DirectChatResult<T> = Result<T, DirectChatError>;
@@ -462,6 +634,7 @@ mod tests {
                     confidence: 0.9,
                     language: "fra".to_string(),
                 },
+                page_overlap_len: 0,
             },
             group_id: "temp_group".to_string(),
             source_spans: None,
@@ -496,6 +669,72 @@ mod tests {
         assert!(!session.is_expired(10800)); // TTL de 3 heures
     }
 
+    #[test]
+    fn test_processing_session_refuses_search() {
+        let mut session = DirectChatSession::new_legacy(
+            PathBuf::from("/test.pdf"),
+            DocumentType::PlainText,
+            vec![],
+            OCRContent::empty(),
+        );
+        session.status = SessionStatus::Processing;
+        assert!(matches!(session.ensure_ready(), Err(DirectChatError::SessionProcessing(_))));
+
+        session.status = SessionStatus::Ready;
+        assert!(session.ensure_ready().is_ok());
+    }
+
+    #[test]
+    fn test_add_document_to_session() {
+        let chunk = |id: &str| {
+            let mut chunk = crate::rag::test_fixtures::test_chunk(id, &format!("content of {}", id));
+            chunk.hash = id.to_string();
+            chunk.group_id = "direct_chat_temp".to_string();
+            chunk
+        };
+
+        let mut session = DirectChatSession::new_legacy(
+            PathBuf::from("/tmp/gravis_temp_devis.pdf"),
+            DocumentType::PlainText,
+            vec![chunk("a1")],
+            OCRContent::empty(),
+        );
+        let added = session.add_document(
+            PathBuf::from("/tmp/gravis_temp_facture.pdf"),
+            vec![chunk("b1"), chunk("b2")],
+            OCRContent::empty(),
+        );
+
+        assert_eq!(session.documents.len(), 2);
+        assert_eq!(session.chunks.len(), 3);
+        assert_eq!(added.document_name, "facture.pdf");
+        assert_eq!(session.document_name_for_chunk("a1"), Some("devis.pdf"));
+        assert_eq!(session.document_name_for_chunk("b2"), Some("facture.pdf"));
+        assert_eq!(session.document_name_for_chunk("zz"), None);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut session = DirectChatSession::new_legacy(
+            PathBuf::from("/test.pdf"),
+            DocumentType::PlainText,
+            vec![],
+            OCRContent::empty(),
+        );
+
+        for i in 0..(MAX_HISTORY_TURNS + 3) {
+            session.push_turn(format!("question {}", i), "x".repeat(40), vec![]);
+        }
+        assert_eq!(session.history.len(), MAX_HISTORY_TURNS);
+        assert_eq!(session.history[0].query, "question 3");
+
+        // ~13 tokens par tour: un budget de 30 ne garde que les 2 derniers
+        let recent = session.recent_history(30);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[1].query, format!("question {}", MAX_HISTORY_TURNS + 2));
+        assert!(session.recent_history(0).is_empty());
+    }
+
     #[test]
     fn test_structured_data_creation() {
         let payslip_data = PayslipData {
@@ -8,64 +8,63 @@ use tracing::{info, warn, debug};
 use uuid::Uuid;
 
 use super::direct_chat::{
-    DirectChatSession, DirectChatError, DirectChatResult, SelectionContext
+    DirectChatSession, DirectChatError, DirectChatResult, SelectionContext,
+    OCRContent, SessionDocument, SessionStatus, ConversationTurn, SourceSummary,
 };
 use crate::rag::{
     EnrichedChunk, CustomE5Embedder, EnhancedBM25Encoder, ScoringEngine,
-    QueryKindDetector, NumericalReranker, QueryKind,
+    QueryKindDetector, NumericalReranker, QueryKind, SourceSpan, SourceSpanManager,
+    strip_page_overlap,
 };
+use crate::rag::search::vector_ops::cosine_similarity;
+
+/// Similarité cosinus minimale par défaut d'un chunk transmis à la génération de réponse
+/// (même ordre de grandeur que les profils de recherche de groupe, cf. `RetrievalProfile`)
+pub const DEFAULT_SESSION_MIN_SCORE: f32 = 0.5;
 
 /// Gestionnaire de sessions temporaires
 #[derive(Clone)]
 pub struct DirectChatManager {
     sessions: Arc<RwLock<HashMap<String, DirectChatSession>>>,
-    pub embedder: Arc<CustomE5Embedder>, // Public pour accès direct pendant traitement
+    span_managers: Arc<RwLock<HashMap<String, SourceSpanManager>>>, // Spans réels (bbox) par session
+    embedder: Arc<RwLock<Arc<CustomE5Embedder>>>, // Partagé avec RagState (`set_embedding_model`)
     ttl_seconds: u64, // Time-to-live par défaut
 }
 
 impl DirectChatManager {
     /// Créer nouveau gestionnaire
-    pub fn new(embedder: Arc<CustomE5Embedder>) -> Self {
+    pub fn new(embedder: Arc<RwLock<Arc<CustomE5Embedder>>>) -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            span_managers: Arc::new(RwLock::new(HashMap::new())),
             embedder,
             ttl_seconds: 3600, // 1 heure par défaut
         }
     }
 
     /// Créer nouveau gestionnaire avec TTL personnalisé
-    pub fn with_ttl(embedder: Arc<CustomE5Embedder>, ttl_seconds: u64) -> Self {
+    pub fn with_ttl(embedder: Arc<RwLock<Arc<CustomE5Embedder>>>, ttl_seconds: u64) -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            span_managers: Arc::new(RwLock::new(HashMap::new())),
             embedder,
             ttl_seconds,
         }
     }
 
+    /// Embedder actif (suit les changements de modèle)
+    pub async fn embedder(&self) -> Arc<CustomE5Embedder> {
+        self.embedder.read().await.clone()
+    }
+
     /// Stocker une session temporaire
     pub async fn store_session(&self, mut session: DirectChatSession) -> DirectChatResult<()> {
-        // Générer embeddings pour les chunks si pas déjà fait
-        if session.embedded_chunks_count() == 0 {
+        // Générer embeddings pour les chunks si pas déjà fait (sauf session finalisée en arrière-plan)
+        if session.status == SessionStatus::Ready && session.embedded_chunks_count() == 0 {
             debug!("Generating embeddings for {} chunks in session {}", 
                    session.chunks.len(), session.session_id);
             
-            let mut embedded_count = 0;
-            for chunk in &mut session.chunks {
-                if !chunk.content.trim().is_empty() 
-                    && !chunk.content.starts_with("EXTRACTION FAILED") {
-                    
-                    match self.embedder.encode_document(&chunk.content).await {
-                        Ok(embedding) => {
-                            chunk.embedding = Some(embedding);
-                            embedded_count += 1;
-                        }
-                        Err(e) => {
-                            warn!("Failed to embed chunk {} in session {}: {}", 
-                                  chunk.id, session.session_id, e);
-                        }
-                    }
-                }
-            }
+            let embedded_count = self.embed_missing_chunks(&mut session.chunks, &session.session_id).await;
             
             info!("Generated {} embeddings for session {}", 
                   embedded_count, session.session_id);
@@ -81,6 +80,184 @@ impl DirectChatManager {
         Ok(())
     }
 
+    /// Encode par paquets les chunks sans embedding (hors chunks vides ou en échec d'extraction).
+    /// Retourne le nombre d'embeddings générés ; un échec n'affecte que son chunk.
+    async fn embed_missing_chunks(&self, chunks: &mut [EnrichedChunk], session_id: &str) -> usize {
+        let pending: Vec<usize> = chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| {
+                chunk.embedding.is_none()
+                    && !chunk.content.trim().is_empty()
+                    && !chunk.content.starts_with("EXTRACTION FAILED")
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let texts: Vec<String> = pending.iter().map(|&index| chunks[index].content.clone()).collect();
+        let embeddings = self.embedder().await.encode_documents_batch(&texts).await;
+
+        let mut embedded_count = 0;
+        for (index, result) in pending.into_iter().zip(embeddings) {
+            match result {
+                Ok(embedding) => {
+                    chunks[index].embedding = Some(embedding);
+                    embedded_count += 1;
+                }
+                Err(e) => warn!("Failed to embed chunk {} for session {}: {}", chunks[index].id, session_id, e),
+            }
+        }
+
+        embedded_count
+    }
+
+    /// Reporter dans la session les embeddings calculés en arrière-plan (chunk_id -> embedding).
+    /// Retourne le nombre de chunks mis à jour.
+    pub async fn apply_chunk_embeddings(
+        &self,
+        session_id: &str,
+        embeddings: HashMap<String, Vec<f32>>,
+    ) -> DirectChatResult<usize> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| DirectChatError::SessionNotFound(session_id.to_string()))?;
+
+        let mut updated = 0;
+        for chunk in session.chunks.iter_mut() {
+            if let Some(embedding) = embeddings.get(&chunk.id) {
+                chunk.embedding = Some(embedding.clone());
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+
+    /// Changer l'état de la session (fin du traitement des embeddings)
+    pub async fn set_session_status(&self, session_id: &str, status: SessionStatus) -> DirectChatResult<()> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| DirectChatError::SessionNotFound(session_id.to_string()))?;
+
+        session.status = status;
+        debug!("Session {} status: {:?}", session_id, status);
+        Ok(())
+    }
+
+    /// Associer les source spans extraits à l'ingestion à une session
+    /// (fusionnés avec ceux des documents déjà présents)
+    pub async fn store_spans(&self, session_id: &str, span_manager: SourceSpanManager) {
+        debug!("Storing {} source spans for session {}",
+               span_manager.get_stats().total_spans, session_id);
+
+        let mut span_managers = self.span_managers.write().await;
+        match span_managers.get_mut(session_id) {
+            Some(existing) => existing.merge(span_manager),
+            None => {
+                span_managers.insert(session_id.to_string(), span_manager);
+            }
+        }
+    }
+
+    /// Ajouter un document déjà traité à une session existante.
+    /// Ses chunks sont fusionnés et classés avec les autres par `search_in_session`.
+    pub async fn add_document_to_session(
+        &self,
+        session_id: &str,
+        document_path: std::path::PathBuf,
+        mut chunks: Vec<EnrichedChunk>,
+        search_content: OCRContent,
+    ) -> DirectChatResult<SessionDocument> {
+        // Vérifie l'existence/expiration avant de calculer des embeddings
+        self.get_session(session_id).await?;
+
+        self.embed_missing_chunks(&mut chunks, session_id).await;
+
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| DirectChatError::SessionNotFound(session_id.to_string()))?;
+
+        let document = session.add_document(document_path, chunks, search_content);
+        info!("Added document '{}' to session {} ({} chunks, {} documents total)",
+              document.document_name, session_id, document.chunk_ids.len(), session.documents.len());
+
+        Ok(document)
+    }
+
+    /// Résoudre les span IDs des chunks en SourceSpan réels (chunk_id -> spans)
+    /// Les chunks sans span résolu sont absents du résultat
+    pub async fn resolve_chunk_spans(
+        &self,
+        session_id: &str,
+        scored_chunks: &[ScoredChunk],
+    ) -> HashMap<String, Vec<SourceSpan>> {
+        let span_managers = self.span_managers.read().await;
+        let Some(span_manager) = span_managers.get(session_id) else {
+            return HashMap::new();
+        };
+
+        scored_chunks
+            .iter()
+            .filter_map(|sc| {
+                let spans: Vec<SourceSpan> = sc.chunk.source_spans.as_ref()?
+                    .iter()
+                    .filter_map(|span_id| span_manager.get_span(span_id).cloned())
+                    .collect();
+                (!spans.is_empty()).then(|| (sc.chunk.id.clone(), spans))
+            })
+            .collect()
+    }
+
+    /// Enregistrer un échange question/réponse dans l'historique de la session
+    pub async fn record_turn(
+        &self,
+        session_id: &str,
+        query: String,
+        response: String,
+        sources: Vec<SourceSummary>,
+    ) -> DirectChatResult<()> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| DirectChatError::SessionNotFound(session_id.to_string()))?;
+
+        session.push_turn(query, response, sources);
+        debug!("Recorded turn {} for session {}", session.history.len(), session_id);
+        Ok(())
+    }
+
+    /// Historique complet de la session
+    pub async fn get_history(&self, session_id: &str) -> DirectChatResult<Vec<ConversationTurn>> {
+        let sessions = self.sessions.read().await;
+        sessions
+            .get(session_id)
+            .map(|session| session.history.clone())
+            .ok_or_else(|| DirectChatError::SessionNotFound(session_id.to_string()))
+    }
+
+    /// Tours récents tenant dans le budget de tokens (pour le contexte LLM)
+    pub async fn recent_history(&self, session_id: &str, max_tokens: usize) -> DirectChatResult<Vec<ConversationTurn>> {
+        let sessions = self.sessions.read().await;
+        sessions
+            .get(session_id)
+            .map(|session| session.recent_history(max_tokens).to_vec())
+            .ok_or_else(|| DirectChatError::SessionNotFound(session_id.to_string()))
+    }
+
+    /// Vider l'historique de la session
+    pub async fn clear_history(&self, session_id: &str) -> DirectChatResult<()> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| DirectChatError::SessionNotFound(session_id.to_string()))?;
+
+        session.history.clear();
+        info!("Cleared conversation history for session {}", session_id);
+        Ok(())
+    }
+
     /// Récupérer une session
     pub async fn get_session(&self, session_id: &str) -> DirectChatResult<DirectChatSession> {
         let sessions = self.sessions.read().await;
@@ -102,6 +279,7 @@ impl DirectChatManager {
 
     /// Supprimer une session
     pub async fn remove_session(&self, session_id: &str) -> DirectChatResult<()> {
+        self.span_managers.write().await.remove(session_id);
         let mut sessions = self.sessions.write().await;
         
         match sessions.remove(session_id) {
@@ -125,9 +303,11 @@ impl DirectChatManager {
             .map(|(id, _)| id.clone())
             .collect();
 
-        // Supprimer sessions expirées
+        // Supprimer sessions expirées (et leurs spans)
+        let mut span_managers = self.span_managers.write().await;
         for id in &expired_ids {
             sessions.remove(id);
+            span_managers.remove(id);
         }
 
         let cleaned_count = expired_ids.len();
@@ -138,18 +318,22 @@ impl DirectChatManager {
         cleaned_count
     }
 
-    /// Recherche hybride avec normalisation et poids adaptatifs par intent
+    /// Recherche hybride avec normalisation et poids adaptatifs par intent.
+    /// `min_score` écarte les chunks dont la similarité cosinus brute est inférieure (aucun filtre si `None`).
     pub async fn search_in_session(
         &self,
         session_id: &str,
         query: &str,
         selection: Option<SelectionContext>,
         limit: Option<usize>,
+        min_score: Option<f32>,
     ) -> DirectChatResult<Vec<ScoredChunk>> {
         let session = self.get_session(session_id).await?;
+        session.ensure_ready()?;
 
         // Générer embedding de la requête
-        let query_embedding = self.embedder
+        let query_embedding = self.embedder()
+            .await
             .encode(&query)
             .await
             .map_err(|e| DirectChatError::EmbeddingFailed(e.to_string()))?;
@@ -169,10 +353,10 @@ impl DirectChatManager {
         // 1. Initialiser BM25 encoder
         let mut bm25_encoder = EnhancedBM25Encoder::new();
 
-        // Préparer documents pour BM25
+        // Préparer documents pour BM25 (sans l'overlap de page : déjà compté dans la page précédente)
         let bm25_docs: Vec<(String, String)> = chunks_to_search
             .iter()
-            .map(|chunk| (chunk.id.clone(), chunk.content.clone()))
+            .map(|chunk| (chunk.id.clone(), strip_page_overlap(chunk).to_string()))
             .collect();
 
         bm25_encoder.index_documents(&bm25_docs);
@@ -190,38 +374,40 @@ impl DirectChatManager {
 
         info!("🎯 Query: '{}' | Intent: {:?} | Kind: {:?}", query, query_intent, query_kind);
 
-        // 4. Calculer tous les scores bruts
+        // 4. Score dense (embeddings sémantiques) et seuil de pertinence sur le cosinus brut :
+        // mieux vaut aucun contexte qu'un contexte à peine pertinent
+        let (candidates, dropped) = dense_candidates(&query_embedding, chunks_to_search, min_score);
+        if dropped > 0 {
+            debug!("🧹 {} chunks below min_score {:.2} discarded ({} kept)",
+                   dropped, min_score.unwrap_or_default(), candidates.len());
+        }
+
+        // 5. Calculer les autres scores bruts
         let mut dense_scores = Vec::new();
         let mut sparse_scores = Vec::new();
         let mut keyword_boosts = Vec::new();
 
-        for chunk in &chunks_to_search {
-            // Score dense (embeddings sémantiques)
-            let dense_score = if let Some(ref chunk_embedding) = chunk.embedding {
-                cosine_similarity(&query_embedding, chunk_embedding)
-            } else {
-                0.0
-            };
-
+        for (chunk, dense_score) in &candidates {
             // Score sparse (BM25 lexical)
             let sparse_score = bm25_encoder.score(query, &chunk.id);
 
             // Keyword boost de base
-            let base_boost = bm25_encoder.keyword_boost(query, &chunk.content);
+            let lexical_content = strip_page_overlap(chunk);
+            let base_boost = bm25_encoder.keyword_boost(query, lexical_content);
 
             // Boost additionnel pour termes techniques détectés dynamiquement via IDF
             let keyword_boost = scoring_engine.apply_dynamic_technical_boost(
                 query,
-                &chunk.content,
+                lexical_content,
                 base_boost
             );
 
-            dense_scores.push(dense_score);
+            dense_scores.push(*dense_score);
             sparse_scores.push(sparse_score);
             keyword_boosts.push(keyword_boost);
         }
 
-        // 5. Calculer scores hybrides normalisés avec poids adaptatifs
+        // 6. Calculer scores hybrides normalisés avec poids adaptatifs
         let hybrid_scores = scoring_engine.compute_hybrid_scores(
             &dense_scores,
             &sparse_scores,
@@ -229,18 +415,20 @@ impl DirectChatManager {
             &query_intent
         );
 
-        // 6. Créer les scored chunks
-        let mut scored_chunks: Vec<ScoredChunk> = chunks_to_search
+        // 7. Créer les scored chunks
+        let mut scored_chunks: Vec<ScoredChunk> = candidates
             .into_iter()
             .enumerate()
-            .map(|(i, chunk)| {
+            .map(|(i, (chunk, _))| {
                 debug!("🎯 Chunk {}: dense={:.3}, sparse={:.3}, boost={:.3}, hybrid={:.3}",
                        &chunk.id[..12.min(chunk.id.len())],
                        dense_scores[i], sparse_scores[i], keyword_boosts[i], hybrid_scores[i]);
 
                 ScoredChunk {
+                    source_document: session.document_name_for_chunk(&chunk.id).map(str::to_string),
                     chunk,
                     score: hybrid_scores[i],
+                    dense_score: dense_scores[i],
                 }
             })
             .collect();
@@ -471,6 +659,9 @@ impl DirectChatManager {
 pub struct ScoredChunk {
     pub chunk: EnrichedChunk,
     pub score: f32,
+    /// Similarité cosinus brute (comparable aux scores Qdrant des groupes)
+    pub dense_score: f32,
+    pub source_document: Option<String>, // Nom du document d'origine (sessions multi-documents)
 }
 
 /// Statistiques du gestionnaire de sessions
@@ -496,21 +687,27 @@ pub struct SessionInfo {
 
 // === Fonctions utilitaires ===
 
-/// Calcul similarité cosinus entre deux vecteurs
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
-    }
-
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-    if norm_a == 0.0 || norm_b == 0.0 {
-        0.0
-    } else {
-        dot_product / (norm_a * norm_b)
-    }
+/// Similarité cosinus brute de chaque chunk avec la requête, sans les chunks sous `min_score` ;
+/// renvoie aussi le nombre de chunks écartés. Le seuil s'applique avant la normalisation min-max,
+/// qui donnerait au meilleur chunk un score élevé même pour une requête étrangère au document.
+fn dense_candidates(
+    query_embedding: &[f32],
+    chunks: Vec<EnrichedChunk>,
+    min_score: Option<f32>,
+) -> (Vec<(EnrichedChunk, f32)>, usize) {
+    let total = chunks.len();
+    let candidates: Vec<(EnrichedChunk, f32)> = chunks
+        .into_iter()
+        .map(|chunk| {
+            let dense_score = chunk.embedding.as_ref()
+                .map(|embedding| cosine_similarity(query_embedding, embedding))
+                .unwrap_or(0.0);
+            (chunk, dense_score)
+        })
+        .filter(|(_, dense_score)| !matches!(min_score, Some(min_score) if *dense_score < min_score))
+        .collect();
+    let dropped = total - candidates.len();
+    (candidates, dropped)
 }
 
 /// Similarité textuelle simple (Jaccard sur mots)
@@ -538,10 +735,8 @@ fn text_similarity(text1: &str, text2: &str) -> f32 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::rag::{
-        DocumentType, ChunkType, ChunkMetadata, Priority, SourceType, 
-        ExtractionMethod, CustomE5Config
-    };
+    use crate::rag::{DocumentType, CustomE5Config};
+    use crate::rag::test_fixtures::test_chunk;
     use crate::rag::core::direct_chat::OCRContent;
 
     #[tokio::test]
@@ -553,7 +748,7 @@ mod tests {
                 .expect("Failed to create embedder")
         );
 
-        let manager = DirectChatManager::with_ttl(embedder, 60); // 1 minute TTL
+        let manager = DirectChatManager::with_ttl(Arc::new(RwLock::new(embedder)), 60); // 1 minute TTL
 
         // Créer session test
         let session = DirectChatSession::new(
@@ -597,6 +792,35 @@ mod tests {
         assert!(different < 0.3);
     }
 
+    #[test]
+    fn test_dense_candidates_min_score() {
+        let embedded = |id: &str, embedding: Vec<f32>| {
+            let mut chunk = test_chunk(id, &format!("contenu {}", id));
+            chunk.embedding = Some(embedding);
+            chunk
+        };
+        let chunks = vec![
+            embedded("proche", vec![1.0, 0.1, 0.0]),
+            embedded("voisin", vec![0.7, 0.7, 0.0]),
+            embedded("loin", vec![0.0, 1.0, 0.0]),
+            test_chunk("sans_embedding", "contenu"),
+        ];
+
+        let (kept, dropped) = dense_candidates(&[1.0, 0.0, 0.0], chunks.clone(), Some(DEFAULT_SESSION_MIN_SCORE));
+        assert_eq!(dropped, 2);
+        assert_eq!(kept.iter().map(|(c, _)| c.id.as_str()).collect::<Vec<_>>(), vec!["proche", "voisin"]);
+
+        // Requête sans rapport avec le document : aucun chunk, même le "meilleur"
+        let (kept, dropped) = dense_candidates(&[0.0, 0.0, 1.0], chunks.clone(), Some(DEFAULT_SESSION_MIN_SCORE));
+        assert!(kept.is_empty());
+        assert_eq!(dropped, 4);
+
+        // Sans seuil : tous les candidats, cosinus brut conservé
+        let (kept, dropped) = dense_candidates(&[0.0, 0.0, 1.0], chunks, None);
+        assert_eq!((kept.len(), dropped), (4, 0));
+        assert!(kept.iter().all(|(_, score)| *score == 0.0));
+    }
+
     #[test]
     fn test_cosine_similarity() {
         let vec1 = vec![1.0, 0.0, 1.0];
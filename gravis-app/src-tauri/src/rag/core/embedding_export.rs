@@ -0,0 +1,338 @@
+// GRAVIS RAG - Export des embeddings d'un groupe pour l'analyse hors ligne
+// Vecteurs par chunk (clustering, visualisation) écrits page par page : CSV autonome, ou
+// matrice `.npy` (float32, une ligne par chunk) accompagnée d'un CSV des identifiants.
+// Les fichiers sont écrits sous un nom temporaire et ne remplacent les cibles qu'une fois l'export terminé.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Longueur de l'aperçu de contenu exporté
+const PREVIEW_CHARS: usize = 120;
+/// Taille fixe de l'en-tête `.npy` (préfixe + dictionnaire), réécrit avec la forme finale
+const NPY_HEADER_LEN: usize = 128;
+
+/// Embedding d'un chunk tel que stocké dans Qdrant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEmbedding {
+    pub chunk_id: String,
+    pub document_id: Option<String>,
+    pub content_preview: String,
+    pub vector: Vec<f32>,
+}
+
+impl ChunkEmbedding {
+    /// Point Qdrant scrollé avec `with_vector` (None sans vecteur exploitable)
+    pub fn from_point(point: &serde_json::Value) -> Option<Self> {
+        let vector = serde_json::from_value::<Vec<f32>>(point["vector"].clone()).ok()?;
+        let payload = &point["payload"];
+        Some(Self {
+            chunk_id: payload["chunk_id"].as_str()
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| point["id"].to_string()),
+            document_id: payload["document_id"].as_str().map(|id| id.to_string()),
+            content_preview: payload["content"].as_str().unwrap_or_default().chars().take(PREVIEW_CHARS).collect(),
+            vector,
+        })
+    }
+}
+
+/// Format du fichier d'export, déduit de l'extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingExportFormat {
+    Csv,
+    Npy,
+}
+
+impl EmbeddingExportFormat {
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_ascii_lowercase()).as_deref() {
+            Some("csv") => Ok(Self::Csv),
+            Some("npy") => Ok(Self::Npy),
+            _ => Err(format!("Unsupported embedding export file {:?} (expected .csv or .npy)", path)),
+        }
+    }
+}
+
+/// Vérifier une cible d'export : chemin absolu sans `..`, extension `.csv`/`.npy`, dossier parent existant
+pub fn validate_export_path(path: &Path) -> Result<EmbeddingExportFormat, String> {
+    let format = EmbeddingExportFormat::from_path(path)?;
+    if !path.is_absolute() || path.components().any(|component| component == Component::ParentDir) {
+        return Err(format!("Embedding export path must be absolute without '..': {:?}", path));
+    }
+    if path.is_dir() {
+        return Err(format!("Embedding export path is a directory: {:?}", path));
+    }
+    if !path.parent().is_some_and(Path::is_dir) {
+        return Err(format!("Embedding export directory does not exist: {:?}", path));
+    }
+    Ok(format)
+}
+
+/// Fichiers qu'un export vers `path` remplacerait (la cible, et le CSV compagnon d'un `.npy`)
+pub fn existing_export_files(path: &Path) -> Vec<PathBuf> {
+    let mut targets = vec![path.to_path_buf()];
+    if EmbeddingExportFormat::from_path(path) == Ok(EmbeddingExportFormat::Npy) {
+        targets.push(path.with_extension("csv"));
+    }
+    targets.into_iter().filter(|target| target.exists()).collect()
+}
+
+/// Fichier écrit sous un nom temporaire, renommé vers sa cible par `commit` ; supprimé sinon
+struct PendingFile {
+    temp_path: PathBuf,
+    target_path: PathBuf,
+    committed: bool,
+}
+
+impl PendingFile {
+    fn create(target_path: &Path) -> Result<(Self, BufWriter<File>), String> {
+        let mut temp_name = target_path.as_os_str().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = PathBuf::from(temp_name);
+        let file = File::create(&temp_path).map_err(|e| format!("Failed to create {:?}: {}", temp_path, e))?;
+        Ok((Self { temp_path, target_path: target_path.to_path_buf(), committed: false }, BufWriter::new(file)))
+    }
+
+    fn commit(mut self) -> Result<(), String> {
+        std::fs::rename(&self.temp_path, &self.target_path)
+            .map_err(|e| format!("Failed to move export to {:?}: {}", self.target_path, e))?;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl Drop for PendingFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Écriture incrémentale d'un export : les lots sont écrits dès leur réception
+pub struct EmbeddingFileWriter {
+    format: EmbeddingExportFormat,
+    vectors: BufWriter<File>,
+    /// CSV des identifiants (ligne i ↔ ligne i de la matrice), `.npy` uniquement
+    metadata: Option<BufWriter<File>>,
+    metadata_path: Option<PathBuf>,
+    dimension: Option<usize>,
+    rows: usize,
+    /// Déclarés après les tampons : fichiers fermés avant la suppression d'un export abandonné
+    pending: Vec<PendingFile>,
+}
+
+impl EmbeddingFileWriter {
+    /// Préparer un export vers `path` ; les fichiers existants (cible ou CSV compagnon) ne sont
+    /// remplacés qu'avec `overwrite`
+    pub fn create(path: &Path, overwrite: bool) -> Result<Self, String> {
+        let format = validate_export_path(path)?;
+        let existing = existing_export_files(path);
+        if !overwrite && !existing.is_empty() {
+            return Err(format!("Embedding export would overwrite existing files: {:?}", existing));
+        }
+
+        let (vectors_file, mut vectors) = PendingFile::create(path)?;
+        let mut pending = vec![vectors_file];
+        let (metadata, metadata_path) = match format {
+            EmbeddingExportFormat::Csv => (None, None),
+            EmbeddingExportFormat::Npy => {
+                // Forme inconnue avant la fin du scroll : en-tête provisoire de taille fixe
+                vectors.write_all(&npy_header(0, 0)).map_err(write_error)?;
+                let metadata_path = path.with_extension("csv");
+                let (metadata_file, mut metadata) = PendingFile::create(&metadata_path)?;
+                pending.push(metadata_file);
+                metadata.write_all(b"row,chunk_id,document_id,content_preview\n").map_err(write_error)?;
+                (Some(metadata), Some(metadata_path))
+            }
+        };
+
+        Ok(Self { format, vectors, metadata, metadata_path, dimension: None, rows: 0, pending })
+    }
+
+    /// Fichier compagnon des identifiants (export `.npy`)
+    pub fn metadata_path(&self) -> Option<&Path> {
+        self.metadata_path.as_deref()
+    }
+
+    pub fn dimension(&self) -> Option<usize> {
+        self.dimension
+    }
+
+    /// Ajouter un lot ; tous les vecteurs doivent avoir la dimension du premier
+    pub fn write_batch(&mut self, embeddings: &[ChunkEmbedding]) -> Result<(), String> {
+        for embedding in embeddings {
+            let dimension = *self.dimension.get_or_insert(embedding.vector.len());
+            if embedding.vector.len() != dimension {
+                return Err(format!(
+                    "Embedding dimension mismatch: chunk {} has {} dimensions, export has {}",
+                    embedding.chunk_id, embedding.vector.len(), dimension
+                ));
+            }
+
+            match (self.format, self.metadata.as_mut()) {
+                (EmbeddingExportFormat::Npy, Some(metadata)) => {
+                    for value in &embedding.vector {
+                        self.vectors.write_all(&value.to_le_bytes()).map_err(write_error)?;
+                    }
+                    writeln!(metadata, "{},{}", self.rows, csv_identity(embedding)).map_err(write_error)?;
+                }
+                _ => {
+                    if self.rows == 0 {
+                        let columns: Vec<String> = (0..dimension).map(|i| format!("v{}", i)).collect();
+                        writeln!(self.vectors, "chunk_id,document_id,content_preview,{}", columns.join(","))
+                            .map_err(write_error)?;
+                    }
+                    let values: Vec<String> = embedding.vector.iter().map(|value| value.to_string()).collect();
+                    writeln!(self.vectors, "{},{}", csv_identity(embedding), values.join(",")).map_err(write_error)?;
+                }
+            }
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// Vider les tampons (et fixer la forme de la matrice `.npy`), puis renommer les fichiers
+    /// temporaires vers leurs cibles ; renvoie le nombre de lignes
+    pub fn finish(mut self) -> Result<usize, String> {
+        if self.format == EmbeddingExportFormat::Npy {
+            self.vectors.seek(SeekFrom::Start(0)).map_err(write_error)?;
+            self.vectors.write_all(&npy_header(self.rows, self.dimension.unwrap_or(0))).map_err(write_error)?;
+        }
+        self.vectors.flush().map_err(write_error)?;
+        if let Some(metadata) = self.metadata.as_mut() {
+            metadata.flush().map_err(write_error)?;
+        }
+
+        let Self { vectors, metadata, rows, pending, .. } = self;
+        drop(vectors);
+        drop(metadata);
+        for file in pending {
+            file.commit()?;
+        }
+        Ok(rows)
+    }
+}
+
+/// En-tête NPY 1.0 float32 little-endian, complété par des espaces jusqu'à `NPY_HEADER_LEN`
+fn npy_header(rows: usize, dimension: usize) -> Vec<u8> {
+    let dictionary = format!("{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}", rows, dimension);
+    let padding = NPY_HEADER_LEN - 10 - dictionary.len() - 1;
+
+    let mut header = b"\x93NUMPY\x01\x00".to_vec();
+    header.extend_from_slice(&((NPY_HEADER_LEN - 10) as u16).to_le_bytes());
+    header.extend_from_slice(dictionary.as_bytes());
+    header.resize(header.len() + padding, b' ');
+    header.push(b'\n');
+    header
+}
+
+/// Colonnes `chunk_id,document_id,content_preview` échappées
+fn csv_identity(embedding: &ChunkEmbedding) -> String {
+    [
+        embedding.chunk_id.as_str(),
+        embedding.document_id.as_deref().unwrap_or_default(),
+        embedding.content_preview.as_str(),
+    ]
+    .iter()
+    .map(|field| csv_field(field))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_error(error: std::io::Error) -> String {
+    format!("Embedding export write failed: {}", error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn embedding(chunk_id: &str, vector: Vec<f32>) -> ChunkEmbedding {
+        ChunkEmbedding {
+            chunk_id: chunk_id.to_string(),
+            document_id: Some("doc_1".to_string()),
+            content_preview: "Chiffre d'affaires, \"net\"".to_string(),
+            vector,
+        }
+    }
+
+    #[test]
+    fn test_embedding_export_files() {
+        let point = json!({ "id": 7, "vector": [0.5, 1.0], "payload": { "document_id": "doc_1", "content": "abc" } });
+        let parsed = ChunkEmbedding::from_point(&point).unwrap();
+        assert_eq!((parsed.chunk_id.as_str(), parsed.vector.len()), ("7", 2));
+        assert!(ChunkEmbedding::from_point(&json!({ "id": 8, "payload": {} })).is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(EmbeddingFileWriter::create(&dir.path().join("out.json"), false).is_err());
+
+        // CSV : en-tête + une ligne par chunk, champs texte échappés
+        let csv_path = dir.path().join("out.csv");
+        let mut writer = EmbeddingFileWriter::create(&csv_path, false).unwrap();
+        writer.write_batch(&[embedding("c1", vec![0.5, -1.0])]).unwrap();
+        assert!(writer.write_batch(&[embedding("c2", vec![0.5])]).is_err());
+        assert!(!csv_path.exists(), "Cible écrite seulement à la fin de l'export");
+        assert_eq!(writer.finish().unwrap(), 1);
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv, "chunk_id,document_id,content_preview,v0,v1\nc1,doc_1,\"Chiffre d'affaires, \"\"net\"\"\",0.5,-1\n");
+
+        // NPY : forme réécrite à la fin, données float32 après l'en-tête
+        let npy_path = dir.path().join("matrix.npy");
+        let mut writer = EmbeddingFileWriter::create(&npy_path, false).unwrap();
+        writer.write_batch(&[embedding("c1", vec![1.0, 2.0, 3.0])]).unwrap();
+        writer.write_batch(&[embedding("c2", vec![4.0, 5.0, 6.0])]).unwrap();
+        let metadata_path = writer.metadata_path().unwrap().to_path_buf();
+        assert_eq!(writer.finish().unwrap(), 2);
+
+        let bytes = std::fs::read(&npy_path).unwrap();
+        assert_eq!(bytes.len(), NPY_HEADER_LEN + 2 * 3 * 4);
+        assert!(String::from_utf8_lossy(&bytes[..NPY_HEADER_LEN]).contains("'shape': (2, 3)"));
+        assert_eq!(bytes[NPY_HEADER_LEN - 1], b'\n');
+        assert_eq!(f32::from_le_bytes(bytes[NPY_HEADER_LEN + 12..NPY_HEADER_LEN + 16].try_into().unwrap()), 4.0);
+        let metadata = std::fs::read_to_string(metadata_path).unwrap();
+        assert!(metadata.lines().nth(2).unwrap().starts_with("1,c2,doc_1,"));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 3, "Aucun fichier temporaire restant");
+    }
+
+    #[test]
+    fn test_embedding_export_target_safety() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(validate_export_path(Path::new("out.csv")).is_err());
+        assert!(validate_export_path(&dir.path().join("../out.csv")).is_err());
+        assert!(validate_export_path(&dir.path().join("absent/out.csv")).is_err());
+        assert_eq!(validate_export_path(&dir.path().join("out.npy")), Ok(EmbeddingExportFormat::Npy));
+
+        // Le CSV compagnon d'un `.npy` existe déjà : refus sans `overwrite`
+        let csv_path = dir.path().join("out.csv");
+        std::fs::write(&csv_path, "données à garder").unwrap();
+        let npy_path = dir.path().join("out.npy");
+        assert_eq!(existing_export_files(&npy_path), vec![csv_path.clone()]);
+        assert!(EmbeddingFileWriter::create(&npy_path, false).is_err());
+        assert_eq!(std::fs::read_to_string(&csv_path).unwrap(), "données à garder");
+
+        // Export abandonné : cibles intactes, fichiers temporaires supprimés
+        let mut writer = EmbeddingFileWriter::create(&npy_path, true).unwrap();
+        writer.write_batch(&[embedding("c1", vec![1.0])]).unwrap();
+        drop(writer);
+        assert_eq!(std::fs::read_to_string(&csv_path).unwrap(), "données à garder");
+        assert!(!npy_path.exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let writer = EmbeddingFileWriter::create(&npy_path, true).unwrap();
+        assert_eq!(writer.finish().unwrap(), 0);
+        assert!(std::fs::read_to_string(&csv_path).unwrap().starts_with("row,chunk_id"));
+    }
+}
@@ -0,0 +1,121 @@
+// GRAVIS RAG - Export/import de l'index d'un groupe
+// Bundle JSON versionné : configuration du groupe + points Qdrant (contenu, vecteurs, payload)
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use super::qdrant_rest::RestPoint;
+use crate::rag::DocumentGroup;
+
+/// Version du format de bundle (incrémentée à chaque changement incompatible)
+pub const GROUP_EXPORT_VERSION: u32 = 1;
+
+/// Index complet d'un groupe, réimportable sans re-OCR ni ré-embedding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupExportBundle {
+    pub format_version: u32,
+    pub exported_at: String,
+    /// Modèle d'embedding ayant produit les vecteurs (`CustomE5Embedder::model_key`)
+    pub embedding_model: String,
+    /// Groupe sans les embeddings des chunks (les vecteurs sont dans `points`)
+    pub group: DocumentGroup,
+    pub points: Vec<RestPoint>,
+}
+
+impl GroupExportBundle {
+    pub fn new(group: &DocumentGroup, embedding_model: String, points: Vec<RestPoint>) -> Self {
+        let mut group = group.clone();
+        for document in &mut group.documents {
+            for chunk in &mut document.chunks {
+                chunk.embedding = None;
+            }
+        }
+
+        Self {
+            format_version: GROUP_EXPORT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            embedding_model,
+            group,
+            points,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(self).map_err(|e| format!("Group export serialization failed: {}", e))
+    }
+
+    /// Relire un bundle ; refuse les versions de format inconnues
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let bundle: Self = serde_json::from_slice(bytes)
+            .map_err(|e| format!("Invalid group export bundle: {}", e))?;
+        if bundle.format_version != GROUP_EXPORT_VERSION {
+            return Err(format!(
+                "Unsupported group export version {} (expected {})",
+                bundle.format_version, GROUP_EXPORT_VERSION
+            ));
+        }
+        Ok(bundle)
+    }
+
+    /// Les vecteurs doivent avoir la dimension de la collection et de l'embedder actif
+    pub fn validate_dimension(&self, embedder_dimension: usize) -> Result<(), String> {
+        let dimension = self.group.collection_config.dimension;
+        if dimension != embedder_dimension {
+            return Err(format!(
+                "Embedding dimension mismatch: bundle {} was built with {} ({}D) but the active model produces {}D vectors; switch embedding model before importing",
+                self.group.id, self.embedding_model, dimension, embedder_dimension
+            ));
+        }
+
+        if let Some(point) = self.points.iter().find(|point| point.vector.len() != dimension) {
+            return Err(format!(
+                "Corrupted bundle: point {} has {} dimensions, collection expects {}",
+                point.id, point.vector.len(), dimension
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rag::{ChunkConfig, CollectionConfig, MetadataConfig};
+    use serde_json::json;
+    use std::time::SystemTime;
+
+    fn group(dimension: usize) -> DocumentGroup {
+        DocumentGroup {
+            id: "group_kb".to_string(),
+            name: "KB".to_string(),
+            active: true,
+            chunk_config: ChunkConfig::default(),
+            metadata_config: MetadataConfig::default(),
+            documents: Vec::new(),
+            qdrant_collection: "collection_group_kb".to_string(),
+            collection_config: CollectionConfig::for_dimension(dimension),
+            created_at: SystemTime::now(),
+            updated_at: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_bundle_roundtrip_and_dimension_checks() {
+        let points = vec![RestPoint { id: json!("p1"), vector: vec![0.1; 4], payload: None }];
+        let bytes = GroupExportBundle::new(&group(4), "e5-small-v2".to_string(), points).to_bytes().unwrap();
+
+        let bundle = GroupExportBundle::from_bytes(&bytes).unwrap();
+        assert_eq!(bundle.group.id, "group_kb");
+        assert_eq!(bundle.points.len(), 1);
+        assert!(bundle.validate_dimension(4).is_ok());
+        assert!(bundle.validate_dimension(384).unwrap_err().contains("dimension mismatch"));
+
+        let mut corrupted = bundle.clone();
+        corrupted.points[0].vector.pop();
+        assert!(corrupted.validate_dimension(4).unwrap_err().contains("p1"));
+
+        let mut future = serde_json::from_slice::<serde_json::Value>(&bytes).unwrap();
+        future["format_version"] = json!(GROUP_EXPORT_VERSION + 1);
+        assert!(GroupExportBundle::from_bytes(&serde_json::to_vec(&future).unwrap()).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+// GRAVIS RAG - Registre persistant des groupes
+// Nom et collection Qdrant de chaque groupe, rechargés au démarrage (les chunks restent dans Qdrant)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+use crate::rag::{CollectionConfig, DocumentGroup, RagError, RagResult};
+
+/// Variable d'environnement surchargeant l'emplacement du registre
+pub const GROUPS_FILE_ENV: &str = "GRAVIS_GROUPS_FILE";
+
+/// Identité persistée d'un groupe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupRecord {
+    pub id: String,
+    pub name: String,
+    pub qdrant_collection: String,
+    #[serde(default)]
+    pub collection_config: CollectionConfig,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+impl GroupRecord {
+    fn from_group(group: &DocumentGroup) -> Self {
+        Self {
+            id: group.id.clone(),
+            name: group.name.clone(),
+            qdrant_collection: group.qdrant_collection.clone(),
+            collection_config: group.collection_config,
+            created_at: group.created_at,
+            updated_at: group.updated_at,
+        }
+    }
+
+    /// Reporter le nom et la collection enregistrés sur un groupe existant ou recréé
+    fn apply_to(&self, group: &mut DocumentGroup) {
+        group.name = self.name.clone();
+        group.qdrant_collection = self.qdrant_collection.clone();
+        group.collection_config = self.collection_config;
+        group.created_at = self.created_at;
+        group.updated_at = self.updated_at;
+    }
+}
+
+/// Fichier JSON des groupes (`~/.gravis/groups.json` par défaut)
+#[derive(Debug, Clone)]
+pub struct GroupRegistry {
+    path: PathBuf,
+}
+
+impl GroupRegistry {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Emplacement de `GRAVIS_GROUPS_FILE`, sinon `~/.gravis/groups.json`
+    pub fn from_env() -> Self {
+        let path = std::env::var(GROUPS_FILE_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                std::env::var("HOME")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| std::env::temp_dir())
+                    .join(".gravis/groups.json")
+            });
+        Self::new(path)
+    }
+
+    /// Groupes enregistrés ; registre absent ou illisible = aucun
+    pub fn load(&self) -> Vec<GroupRecord> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+            Err(e) => {
+                warn!("⚠️ Group registry {} unreadable: {}", self.path.display(), e);
+                return Vec::new();
+            }
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("⚠️ Ignoring invalid group registry {}: {}", self.path.display(), e);
+            Vec::new()
+        })
+    }
+
+    /// Appliquer le registre aux groupes créés au démarrage et recréer les autres
+    pub fn restore(&self, groups: &mut HashMap<String, DocumentGroup>) {
+        let records = self.load();
+        for record in &records {
+            let group = groups
+                .entry(record.id.clone())
+                .or_insert_with(|| DocumentGroup::new_with_id(record.id.clone(), record.name.clone()));
+            record.apply_to(group);
+        }
+        if !records.is_empty() {
+            info!("📁 Restored {} groups from {}", records.len(), self.path.display());
+        }
+    }
+
+    /// Écrire l'état des groupes (fichier temporaire puis renommage : jamais de registre tronqué)
+    pub fn save(&self, groups: &HashMap<String, DocumentGroup>) -> RagResult<()> {
+        let mut records: Vec<GroupRecord> = groups.values().map(GroupRecord::from_group).collect();
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+        let content = serde_json::to_string_pretty(&records)?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, &self.path).map_err(|e| {
+            let _ = std::fs::remove_file(&temp_path);
+            RagError::Io(e)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_restores_name_and_collection() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = GroupRegistry::new(dir.path().join("gravis/groups.json"));
+        assert!(registry.load().is_empty(), "Registre absent : aucun groupe");
+
+        let mut saved = HashMap::new();
+        let mut default_group = DocumentGroup::new_with_id("default_group".to_string(), "Default Group".to_string());
+        default_group.name = "Contrats".to_string();
+        default_group.qdrant_collection = "contrats_v2".to_string();
+        let other = DocumentGroup::new_with_id("group_a".to_string(), "Factures".to_string());
+        saved.insert(default_group.id.clone(), default_group);
+        saved.insert(other.id.clone(), other);
+        registry.save(&saved).unwrap();
+
+        // Redémarrage : seul le groupe par défaut est recréé avec sa collection dérivée de l'id
+        let mut groups = HashMap::new();
+        groups.insert(
+            "default_group".to_string(),
+            DocumentGroup::new_with_id("default_group".to_string(), "Default Group".to_string()),
+        );
+        registry.restore(&mut groups);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["default_group"].name, "Contrats");
+        assert_eq!(groups["default_group"].qdrant_collection, "contrats_v2");
+        assert_eq!(groups["group_a"].name, "Factures");
+        assert_eq!(groups["group_a"].qdrant_collection, "collection_group_a");
+    }
+
+    #[test]
+    fn test_invalid_registry_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("groups.json");
+        std::fs::write(&path, "{ not json").unwrap();
+
+        assert!(GroupRegistry::new(path).load().is_empty());
+    }
+}
@@ -10,7 +10,7 @@ use crate::rag::{
     ChunkConfig, RagResult, RagError
 };
 use crate::rag::processing::DocumentProcessor;
-use crate::rag::ocr::{PreprocessConfig, FileFormat, detect_file_format};
+use crate::rag::ocr::{PreprocessConfig, FileFormat, OcrProgressCallback, detect_file_format};
 
 /// Moteur d'ingestion intelligent avec détection automatique
 pub struct IngestionEngine {
@@ -27,12 +27,28 @@ impl IngestionEngine {
         }
     }
 
+    /// Processeur de documents partagé par toutes les stratégies d'ingestion
+    pub fn document_processor(&self) -> &DocumentProcessor {
+        &self.document_processor
+    }
+
     /// Point d'entrée principal: ingestion intelligente d'un document
     pub async fn ingest_document(
         &self,
         file_path: &Path,
         group_id: &str,
         chunk_config: &ChunkConfig,
+    ) -> RagResult<IngestionResult> {
+        self.ingest_document_with_progress(file_path, group_id, chunk_config, None).await
+    }
+
+    /// `ingest_document` avec progression OCR optionnelle, relayée au `DocumentProcessor`
+    pub async fn ingest_document_with_progress(
+        &self,
+        file_path: &Path,
+        group_id: &str,
+        chunk_config: &ChunkConfig,
+        progress: Option<&OcrProgressCallback>,
     ) -> RagResult<IngestionResult> {
         let start_time = Instant::now();
         info!("Starting intelligent ingestion for: {:?}", file_path);
@@ -44,13 +60,13 @@ impl IngestionEngine {
         // 2. Traitement avec stratégie optimisée
         let document = match &strategy {
             IngestionStrategy::OptimizedPdf(pdf_strategy) => {
-                self.process_pdf_with_strategy(file_path, group_id, chunk_config, *pdf_strategy).await?
+                self.process_pdf_with_strategy(file_path, group_id, chunk_config, *pdf_strategy, progress).await?
             }
             IngestionStrategy::OptimizedImage(preprocess_config) => {
-                self.process_image_with_preprocessing(file_path, group_id, chunk_config, preprocess_config.clone()).await?
+                self.process_image_with_preprocessing(file_path, group_id, chunk_config, preprocess_config.clone(), progress).await?
             }
             IngestionStrategy::DirectText => {
-                self.document_processor.process_document(file_path, group_id, chunk_config).await?
+                self.document_processor.process_document_with_progress(file_path, group_id, chunk_config, progress).await?
             }
         };
 
@@ -79,6 +95,7 @@ impl IngestionEngine {
         group_id: &str,
         chunk_config: &ChunkConfig,
         strategy: PdfStrategy,
+        progress: Option<&OcrProgressCallback>,
     ) -> RagResult<GroupDocument> {
         debug!("Processing PDF with strategy: {:?}", strategy);
 
@@ -87,17 +104,17 @@ impl IngestionEngine {
                 // Force l'utilisation de l'extraction native uniquement
                 // TODO: Implémenter extraction native directe
                 warn!("Native PDF extraction not fully implemented, using standard processor");
-                self.document_processor.process_document(file_path, group_id, chunk_config).await
+                self.document_processor.process_document_with_progress(file_path, group_id, chunk_config, progress).await
             }
             PdfStrategy::OcrOnly => {
                 // Force l'utilisation de l'OCR uniquement
                 debug!("Forcing OCR-only processing for PDF");
-                self.document_processor.process_document(file_path, group_id, chunk_config).await
+                self.document_processor.process_document_with_progress(file_path, group_id, chunk_config, progress).await
             }
             PdfStrategy::HybridIntelligent => {
                 // Pipeline hybride avec logique intelligente
                 debug!("Using hybrid intelligent processing for PDF");
-                self.process_pdf_hybrid_intelligent(file_path, group_id, chunk_config).await
+                self.process_pdf_hybrid_intelligent(file_path, group_id, chunk_config, progress).await
             }
         }
     }
@@ -108,6 +125,7 @@ impl IngestionEngine {
         file_path: &Path,
         group_id: &str,
         chunk_config: &ChunkConfig,
+        progress: Option<&OcrProgressCallback>,
     ) -> RagResult<GroupDocument> {
         // TODO Phase 2: Implémentation complète du pipeline hybride
         // 1. Tentative extraction native rapide
@@ -116,7 +134,7 @@ impl IngestionEngine {
         // 4. Fusion intelligente texte natif + OCR
         
         warn!("Hybrid intelligent processing not fully implemented, using standard processor");
-        self.document_processor.process_document(file_path, group_id, chunk_config).await
+        self.document_processor.process_document_with_progress(file_path, group_id, chunk_config, progress).await
     }
 
     /// Traitement image avec préprocessing optimisé
@@ -126,12 +144,13 @@ impl IngestionEngine {
         group_id: &str,
         chunk_config: &ChunkConfig,
         preprocess_config: PreprocessConfig,
+        progress: Option<&OcrProgressCallback>,
     ) -> RagResult<GroupDocument> {
         debug!("Processing image with preprocessing: {:?}", preprocess_config);
         
         // TODO Phase 2: Appliquer préprocessing avant OCR
         // Pour l'instant, utilise le processeur standard
-        self.document_processor.process_document(file_path, group_id, chunk_config).await
+        self.document_processor.process_document_with_progress(file_path, group_id, chunk_config, progress).await
     }
 
     /// Traitement par lot avec parallélisation
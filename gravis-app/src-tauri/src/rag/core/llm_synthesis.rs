@@ -0,0 +1,382 @@
+// Phase 2: Synthèse LLM pour Chat Direct
+// Backend optionnel (API compatible OpenAI ou Ollama local) avec streaming des tokens
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::direct_chat::{ConversationTurn, DirectChatError, DirectChatResult};
+
+/// Prompt système commun aux deux backends
+const SYSTEM_PROMPT: &str = "Tu es un assistant qui répond aux questions sur un document. \
+Réponds uniquement à partir du contexte fourni, dans la langue de la question. \
+Cite les sources sous la forme [Source N]. Si le contexte ne permet pas de répondre, dis-le. \
+Utilise les échanges précédents pour interpréter les questions de suivi (pronoms, \"le second\", etc.).";
+
+/// Type de backend LLM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmBackend {
+    /// `POST {base_url}/chat/completions` (OpenAI, LM Studio, vLLM, llama.cpp server...)
+    OpenAiCompatible,
+    /// `POST {base_url}/api/chat` (Ollama local)
+    Ollama,
+}
+
+/// Configuration du backend de synthèse
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LlmSynthesisConfig {
+    pub backend: LlmBackend,
+    pub base_url: String,
+    pub model: String,
+    /// Jamais renvoyée au frontend ni écrite dans les logs
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl std::fmt::Debug for LlmSynthesisConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmSynthesisConfig")
+            .field("backend", &self.backend)
+            .field("base_url", &self.base_url)
+            .field("model", &self.model)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("temperature", &self.temperature)
+            .field("timeout_secs", &self.timeout_secs)
+            .finish()
+    }
+}
+
+fn default_temperature() -> f32 {
+    0.2
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+impl LlmSynthesisConfig {
+    /// Ollama local avec le modèle donné (`http://localhost:11434`)
+    pub fn ollama(model: impl Into<String>) -> Self {
+        Self {
+            backend: LlmBackend::Ollama,
+            base_url: "http://localhost:11434".to_string(),
+            model: model.into(),
+            api_key: None,
+            temperature: default_temperature(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        let base = self.base_url.trim_end_matches('/');
+        match self.backend {
+            LlmBackend::OpenAiCompatible => format!("{}/chat/completions", base),
+            LlmBackend::Ollama => format!("{}/api/chat", base),
+        }
+    }
+}
+
+/// Client de synthèse: contexte formaté + query → réponse rédigée
+pub struct LlmSynthesizer {
+    client: reqwest::Client,
+    config: LlmSynthesisConfig,
+}
+
+impl LlmSynthesizer {
+    pub fn new(config: LlmSynthesisConfig) -> DirectChatResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| DirectChatError::LlmSynthesisFailed(format!("HTTP client init failed: {}", e)))?;
+
+        Ok(Self { client, config })
+    }
+
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// Synthétiser une réponse en streaming; `on_token` est appelé pour chaque fragment reçu.
+    /// `history` (déjà borné par l'appelant) est rejoué avant la question courante.
+    pub async fn synthesize<F>(
+        &self,
+        query: &str,
+        formatted_context: &str,
+        history: &[ConversationTurn],
+        mut on_token: F,
+    ) -> DirectChatResult<String>
+    where
+        F: FnMut(&str),
+    {
+        let start_time = std::time::Instant::now();
+        let body = self.build_request_body(query, formatted_context, history);
+
+        let mut request = self.client.post(self.config.endpoint()).json(&body);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| DirectChatError::LlmSynthesisFailed(format!("Request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(DirectChatError::LlmSynthesisFailed(
+                format!("LLM backend returned {}: {}", status, error_text)
+            ));
+        }
+
+        // Les deux backends envoient une ligne par fragment (SSE pour OpenAI, NDJSON pour Ollama)
+        let mut stream = response.bytes_stream();
+        let mut buffer = LineBuffer::default();
+        let mut answer = String::new();
+        let mut done = false;
+
+        'stream: while let Some(bytes) = stream.next().await {
+            let bytes = bytes
+                .map_err(|e| DirectChatError::LlmSynthesisFailed(format!("Stream interrupted: {}", e)))?;
+
+            for line in buffer.push(&bytes) {
+                match parse_stream_line(self.config.backend, line.trim()) {
+                    StreamEvent::Token(token) => {
+                        on_token(&token);
+                        answer.push_str(&token);
+                    }
+                    StreamEvent::Done => {
+                        done = true;
+                        break 'stream;
+                    }
+                    StreamEvent::Skip => {}
+                }
+            }
+        }
+
+        // Dernière ligne sans retour à la ligne final
+        if !done {
+            if let Some(line) = buffer.finish() {
+                if let StreamEvent::Token(token) = parse_stream_line(self.config.backend, line.trim()) {
+                    on_token(&token);
+                    answer.push_str(&token);
+                }
+            }
+        }
+
+        if answer.trim().is_empty() {
+            return Err(DirectChatError::LlmSynthesisFailed("LLM returned an empty answer".to_string()));
+        }
+
+        info!("✅ LLM synthesis ({}) completed: {} chars in {}ms",
+              self.config.model, answer.len(), start_time.elapsed().as_millis());
+
+        Ok(answer)
+    }
+
+    fn build_request_body(
+        &self,
+        query: &str,
+        formatted_context: &str,
+        history: &[ConversationTurn],
+    ) -> serde_json::Value {
+        let mut messages = vec![serde_json::json!({ "role": "system", "content": SYSTEM_PROMPT })];
+        for turn in history {
+            messages.push(serde_json::json!({ "role": "user", "content": turn.query }));
+            messages.push(serde_json::json!({ "role": "assistant", "content": turn.response }));
+        }
+        messages.push(serde_json::json!({
+            "role": "user",
+            "content": format!("Contexte:\n{}\n\nQuestion: {}", formatted_context, query)
+        }));
+
+        match self.config.backend {
+            LlmBackend::OpenAiCompatible => serde_json::json!({
+                "model": self.config.model,
+                "messages": messages,
+                "temperature": self.config.temperature,
+                "stream": true,
+            }),
+            LlmBackend::Ollama => serde_json::json!({
+                "model": self.config.model,
+                "messages": messages,
+                "options": { "temperature": self.config.temperature },
+                "stream": true,
+            }),
+        }
+    }
+}
+
+/// Octets du flux en attente d'une ligne complète : un caractère UTF-8 coupé entre deux
+/// fragments réseau n'est décodé qu'une fois la ligne entière reçue
+#[derive(Debug, Default)]
+struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    /// Ajouter un fragment et retourner les lignes désormais complètes
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(bytes);
+        match self.pending.iter().rposition(|&byte| byte == b'\n') {
+            Some(last_newline) => {
+                let complete: Vec<u8> = self.pending.drain(..=last_newline).collect();
+                String::from_utf8_lossy(&complete).lines().map(str::to_string).collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Reste du flux après le dernier retour à la ligne
+    fn finish(self) -> Option<String> {
+        (!self.pending.is_empty()).then(|| String::from_utf8_lossy(&self.pending).into_owned())
+    }
+}
+
+/// Fragment décodé d'une ligne du flux
+#[derive(Debug, PartialEq)]
+enum StreamEvent {
+    Token(String),
+    Done,
+    Skip,
+}
+
+fn parse_stream_line(backend: LlmBackend, line: &str) -> StreamEvent {
+    if line.is_empty() {
+        return StreamEvent::Skip;
+    }
+
+    let payload = match backend {
+        LlmBackend::OpenAiCompatible => match line.strip_prefix("data:") {
+            Some(data) if data.trim() == "[DONE]" => return StreamEvent::Done,
+            Some(data) => data.trim(),
+            None => return StreamEvent::Skip, // Commentaires SSE, champs event:/id:
+        },
+        LlmBackend::Ollama => line,
+    };
+
+    let value: serde_json::Value = match serde_json::from_str(payload) {
+        Ok(value) => value,
+        Err(e) => {
+            warn!("Unparseable LLM stream line: {}", e);
+            return StreamEvent::Skip;
+        }
+    };
+
+    let token = match backend {
+        LlmBackend::OpenAiCompatible => value["choices"][0]["delta"]["content"].as_str(),
+        LlmBackend::Ollama => value["message"]["content"].as_str(),
+    };
+
+    match token {
+        Some(token) if !token.is_empty() => StreamEvent::Token(token.to_string()),
+        _ if backend == LlmBackend::Ollama && value["done"].as_bool() == Some(true) => {
+            debug!("Ollama stream finished");
+            StreamEvent::Done
+        }
+        _ => StreamEvent::Skip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_never_exposed() {
+        let config = LlmSynthesisConfig {
+            api_key: Some("sk-secret".to_string()),
+            ..LlmSynthesisConfig::ollama("llama3")
+        };
+
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("sk-secret") && debug.contains("<redacted>"), "{}", debug);
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json.get("api_key").is_none(), "{}", json);
+
+        // La clé reste acceptée en entrée
+        let parsed: LlmSynthesisConfig = serde_json::from_value(serde_json::json!({
+            "backend": "ollama", "base_url": "http://localhost:11434", "model": "llama3", "api_key": "sk-new"
+        })).unwrap();
+        assert_eq!(parsed.api_key.as_deref(), Some("sk-new"));
+    }
+
+    #[test]
+    fn test_parse_openai_stream_lines() {
+        let backend = LlmBackend::OpenAiCompatible;
+        assert_eq!(
+            parse_stream_line(backend, r#"data: {"choices":[{"delta":{"content":"Bon"}}]}"#),
+            StreamEvent::Token("Bon".to_string())
+        );
+        assert_eq!(parse_stream_line(backend, r#"data: {"choices":[{"delta":{}}]}"#), StreamEvent::Skip);
+        assert_eq!(parse_stream_line(backend, ": keep-alive"), StreamEvent::Skip);
+        assert_eq!(parse_stream_line(backend, "data: [DONE]"), StreamEvent::Done);
+    }
+
+    #[test]
+    fn test_line_buffer_keeps_split_utf8_characters() {
+        let stream = "data: {\"choices\":[{\"delta\":{\"content\":\"Été\"}}]}\ndata: [DONE]\n".as_bytes();
+        // Coupure au milieu du "É" (2 octets) puis au milieu de la seconde ligne
+        let split = stream.iter().position(|&byte| byte == 0xC3).unwrap() + 1;
+        let mut buffer = LineBuffer::default();
+
+        assert!(buffer.push(&stream[..split]).is_empty());
+        let lines = buffer.push(&stream[split..stream.len() - 3]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(
+            parse_stream_line(LlmBackend::OpenAiCompatible, &lines[0]),
+            StreamEvent::Token("Été".to_string())
+        );
+        assert_eq!(buffer.push(&stream[stream.len() - 3..]), vec!["data: [DONE]".to_string()]);
+        assert_eq!(buffer.finish(), None);
+    }
+
+    #[test]
+    fn test_parse_ollama_stream_lines() {
+        let backend = LlmBackend::Ollama;
+        assert_eq!(
+            parse_stream_line(backend, r#"{"message":{"role":"assistant","content":"jour"},"done":false}"#),
+            StreamEvent::Token("jour".to_string())
+        );
+        assert_eq!(
+            parse_stream_line(backend, r#"{"message":{"role":"assistant","content":""},"done":true}"#),
+            StreamEvent::Done
+        );
+    }
+
+    #[test]
+    fn test_history_is_replayed_before_question() {
+        let synthesizer = LlmSynthesizer::new(LlmSynthesisConfig::ollama("llama3.1")).unwrap();
+        let history = vec![ConversationTurn {
+            query: "Quels sont les deux modes ?".to_string(),
+            response: "Tiny et Gundam.".to_string(),
+            timestamp: std::time::SystemTime::now(),
+            sources: vec![],
+        }];
+
+        let body = synthesizer.build_request_body("Et le second ?", "[Source 1] ...", &history);
+        let messages = body["messages"].as_array().unwrap();
+
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[1]["role"], "user");
+        assert_eq!(messages[2]["content"], "Tiny et Gundam.");
+        assert!(messages[3]["content"].as_str().unwrap().ends_with("Question: Et le second ?"));
+    }
+
+    #[test]
+    fn test_endpoint_building() {
+        let mut config = LlmSynthesisConfig::ollama("llama3.1");
+        assert_eq!(config.endpoint(), "http://localhost:11434/api/chat");
+
+        config.backend = LlmBackend::OpenAiCompatible;
+        config.base_url = "https://api.openai.com/v1/".to_string();
+        assert_eq!(config.endpoint(), "https://api.openai.com/v1/chat/completions");
+    }
+}
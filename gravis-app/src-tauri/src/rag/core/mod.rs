@@ -4,10 +4,15 @@ pub mod ingestion_engine;
 pub mod unified_cache;
 pub mod qdrant_rest;
 pub mod source_spans;
+pub mod group_export;
+pub mod group_registry;
+pub mod embedding_export;
 
 // Phase 2: Chat Direct modules
 pub mod direct_chat;
 pub mod direct_chat_manager;
+pub mod llm_synthesis;
+pub mod session_export;
 
 #[cfg(test)]
 mod source_spans_integration_test;
@@ -17,6 +22,9 @@ pub use ingestion_engine::*;
 pub use unified_cache::*;
 pub use qdrant_rest::*;
 pub use source_spans::*;
+pub use group_export::*;
+pub use group_registry::*;
+pub use embedding_export::*;
 
 // Phase 2: Chat Direct exports
 pub use direct_chat::*;
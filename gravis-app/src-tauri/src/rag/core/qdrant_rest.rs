@@ -2,17 +2,24 @@
 // Solution alternative utilisant l'API REST de Qdrant
 
 use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
 
 /// Configuration pour le client REST Qdrant
 #[derive(Debug, Clone)]
 pub struct QdrantRestConfig {
     pub url: String,
     pub timeout_secs: u64,
+    /// Nombre maximal de tentatives par requête (erreurs de connexion et 5xx uniquement)
+    pub max_attempts: u32,
+    /// Délai avant la 2e tentative, doublé à chaque nouvel essai
+    pub retry_base_delay_ms: u64,
+    /// Points envoyés par requête d'upsert (les gros documents sont découpés en lots)
+    pub upsert_batch_size: usize,
 }
 
 impl Default for QdrantRestConfig {
@@ -20,10 +27,28 @@ impl Default for QdrantRestConfig {
         Self {
             url: "http://localhost:6333".to_string(),
             timeout_secs: 30,
+            max_attempts: 4,
+            retry_base_delay_ms: 250,
+            upsert_batch_size: 256,
         }
     }
 }
 
+/// Délai avant la tentative `attempt` (1 = première relance) : base * 2^(attempt-1)
+fn retry_delay(base_delay_ms: u64, attempt: u32) -> Duration {
+    Duration::from_millis(base_delay_ms.saturating_mul(1u64 << (attempt.saturating_sub(1)).min(16)))
+}
+
+/// Seules les erreurs serveur sont transitoires ; un 4xx (404, 409...) est une réponse définitive
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+}
+
+/// Erreurs réseau transitoires (Qdrant en cours de démarrage, coupure, timeout)
+fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout() || error.is_request()
+}
+
 /// Point pour l'API REST Qdrant
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RestPoint {
@@ -53,6 +78,9 @@ pub struct RestSearchResult {
 pub struct QdrantRestClient {
     client: Client,
     base_url: String,
+    max_attempts: u32,
+    retry_base_delay_ms: u64,
+    upsert_batch_size: usize,
 }
 
 impl QdrantRestClient {
@@ -66,9 +94,106 @@ impl QdrantRestClient {
         Ok(Self {
             client,
             base_url: config.url,
+            max_attempts: config.max_attempts.max(1),
+            retry_base_delay_ms: config.retry_base_delay_ms,
+            upsert_batch_size: config.upsert_batch_size.max(1),
         })
     }
 
+    /// Envoie une requête avec relances à backoff exponentiel borné.
+    ///
+    /// `build` reconstruit la requête à chaque tentative. Seules les erreurs de connexion/timeout
+    /// et les réponses 5xx sont relancées ; la dernière réponse 5xx est retournée telle quelle.
+    async fn send_with_retry<F>(&self, operation: &str, build: F) -> Result<Response>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let mut attempt = 1;
+        loop {
+            match build(&self.client).send().await {
+                Ok(response) if is_retryable_status(response.status()) && attempt < self.max_attempts => {
+                    warn!("⚠️ Qdrant {} returned {} (attempt {}/{}), retrying",
+                          operation, response.status(), attempt, self.max_attempts);
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if is_retryable_error(&e) && attempt < self.max_attempts => {
+                    warn!("⚠️ Qdrant {} failed (attempt {}/{}): {}, retrying",
+                          operation, attempt, self.max_attempts, e);
+                }
+                Err(e) => return Err(anyhow::Error::new(e).context(format!("Failed to send {} request", operation))),
+            }
+
+            tokio::time::sleep(retry_delay(self.retry_base_delay_ms, attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// POST JSON sur un endpoint de collection ; `None` si la collection n'existe pas (404)
+    async fn post_collection_json(&self, collection_name: &str, endpoint: &str, operation: &str, body: &Value) -> Result<Option<Value>> {
+        let url = format!("{}/collections/{}/{}", self.base_url, collection_name, endpoint);
+
+        let response = self
+            .send_with_retry(operation, |client| client.post(&url).json(body))
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if response.status().is_success() {
+            let data: Value = response
+                .json()
+                .await
+                .with_context(|| format!("Failed to parse {} response", operation))?;
+            Ok(Some(data))
+        } else {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            Err(anyhow::anyhow!(
+                "Failed to {}: {} - {}",
+                operation,
+                status,
+                text
+            ))
+        }
+    }
+
+    /// Recherche avec requête Qdrant brute (filtres, paramètres) ; `None` si la collection n'existe pas
+    pub async fn search_raw(&self, collection_name: &str, request: &Value) -> Result<Option<Value>> {
+        self.post_collection_json(collection_name, "points/search", "search", request).await
+    }
+
+    /// Page de points via l'API scroll ; `None` si la collection n'existe pas
+    pub async fn scroll_points(&self, collection_name: &str, request: &Value) -> Result<Option<Value>> {
+        self.post_collection_json(collection_name, "points/scroll", "scroll", request).await
+    }
+
+    /// Fusionner des champs dans le payload des points correspondant au filtre (sans toucher aux vecteurs)
+    pub async fn set_payload(&self, collection_name: &str, payload: &Value, filter: &Value) -> Result<()> {
+        let body = json!({ "payload": payload, "filter": filter });
+        self.post_collection_json(collection_name, "points/payload", "set payload", &body)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| anyhow::anyhow!("Failed to set payload: collection {} not found", collection_name))
+    }
+
+    /// Supprimer des points par identifiant
+    pub async fn delete_points(&self, collection_name: &str, point_ids: &[String]) -> Result<()> {
+        let body = json!({ "points": point_ids });
+        self.post_collection_json(collection_name, "points/delete", "delete points", &body)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| anyhow::anyhow!("Failed to delete points: collection {} not found", collection_name))
+    }
+
+    /// Supprimer les points correspondant au filtre (collection absente : rien à supprimer)
+    pub async fn delete_points_by_filter(&self, collection_name: &str, filter: &Value) -> Result<()> {
+        let body = json!({ "filter": filter });
+        self.post_collection_json(collection_name, "points/delete", "delete points", &body)
+            .await
+            .map(|_| ())
+    }
+
     /// Supprimer une collection pour garantir l'isolation des benchmarks
     pub async fn delete_collection(&self, collection_name: &str) -> Result<()> {
         let url = format!("{}/collections/{}", self.base_url, collection_name);
@@ -76,11 +201,8 @@ impl QdrantRestClient {
         info!("🗑️ Deleting collection for clean benchmark: {}", collection_name);
         
         let response = self
-            .client
-            .delete(&url)
-            .send()
-            .await
-            .context("Failed to send delete collection request")?;
+            .send_with_retry("delete collection", |client| client.delete(&url))
+            .await?;
 
         if response.status().is_success() || response.status() == 404 {
             info!("✅ Collection deleted (or didn't exist): {}", collection_name);
@@ -126,12 +248,8 @@ impl QdrantRestClient {
         info!("🔄 Creating collection via REST: {}", collection_name);
         
         let response = self
-            .client
-            .put(&url)
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to send create collection request")?;
+            .send_with_retry("create collection", |client| client.put(&url).json(&payload))
+            .await?;
 
         if response.status().is_success() {
             info!("✅ Collection created successfully: {}", collection_name);
@@ -184,12 +302,8 @@ impl QdrantRestClient {
         info!("🔄 Updating collection config: {} (threshold: {:?})", collection_name, indexing_threshold);
         
         let response = self
-            .client
-            .patch(&url)
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to update collection config")?;
+            .send_with_retry("update collection config", |client| client.patch(&url).json(&payload))
+            .await?;
 
         if response.status().is_success() {
             info!("✅ Collection config updated: {}", collection_name);
@@ -250,36 +364,49 @@ impl QdrantRestClient {
     }
 
     /// Upserter des points
+    ///
+    /// Les points sont envoyés par lots de `upsert_batch_size`. En cas d'échec, l'erreur indique
+    /// le lot fautif et le nombre de points déjà enregistrés (les lots précédents restent en base).
+    #[tracing::instrument(name = "upsert", skip_all, fields(collection = %collection_name, points = points.len()))]
     pub async fn upsert_points(
         &self,
         collection_name: &str,
         points: Vec<RestPoint>,
     ) -> Result<()> {
         let url = format!("{}/collections/{}/points", self.base_url, collection_name);
-        
-        let payload = json!({
-            "points": points
-        });
+        let total_points = points.len();
+        let total_batches = total_points.div_ceil(self.upsert_batch_size);
+        let mut committed = 0;
 
-        let response = self
-            .client
-            .put(&url)
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to send upsert request")?;
+        for (index, batch) in points.chunks(self.upsert_batch_size).enumerate() {
+            let payload = json!({
+                "points": batch
+            });
 
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            Err(anyhow::anyhow!(
-                "Failed to upsert points: {} - {}",
-                status,
-                text
-            ))
+            let batch_error = |reason: String| anyhow::anyhow!(
+                "Failed to upsert batch {}/{} into {} ({} of {} points already committed): {}",
+                index + 1, total_batches, collection_name, committed, total_points, reason
+            );
+
+            let response = self
+                .send_with_retry("upsert", |client| client.put(&url).json(&payload))
+                .await
+                .map_err(|e| batch_error(format!("{:#}", e)))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                return Err(batch_error(format!("{} - {}", status, text)));
+            }
+
+            committed += batch.len();
+            if total_batches > 1 {
+                info!("📤 Upsert {}: batch {}/{} ({}/{} points)",
+                      collection_name, index + 1, total_batches, committed, total_points);
+            }
         }
+
+        Ok(())
     }
 
     /// Chercher des points similaires
@@ -307,12 +434,8 @@ impl QdrantRestClient {
         }
 
         let response = self
-            .client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to send search request")?;
+            .send_with_retry("search", |client| client.post(&url).json(&payload))
+            .await?;
 
         if response.status().is_success() {
             let search_response: RestSearchResponse = response
@@ -331,7 +454,7 @@ impl QdrantRestClient {
         }
     }
 
-    /// Vérifier le statut du serveur
+    /// Vérifier le statut du serveur (une seule tentative : un diagnostic ne doit pas attendre)
     pub async fn health_check(&self) -> Result<bool> {
         let url = format!("{}/", self.base_url);
         
@@ -343,21 +466,29 @@ impl QdrantRestClient {
 
     /// Obtenir les informations de la collection
     pub async fn collection_info(&self, collection_name: &str) -> Result<Value> {
+        self.find_collection_info(collection_name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Failed to get collection info: 404 Not Found - collection {} does not exist", collection_name))
+    }
+
+    /// Informations de la collection ; `None` si elle n'a pas encore été créée (404)
+    pub async fn find_collection_info(&self, collection_name: &str) -> Result<Option<Value>> {
         let url = format!("{}/collections/{}", self.base_url, collection_name);
         
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to get collection info")?;
+            .send_with_retry("collection info", |client| client.get(&url))
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
 
         if response.status().is_success() {
             let info: Value = response
                 .json()
                 .await
                 .context("Failed to parse collection info")?;
-            Ok(info)
+            Ok(Some(info))
         } else {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
@@ -375,6 +506,45 @@ impl QdrantRestClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_retry_policy() {
+        assert_eq!(retry_delay(250, 1), Duration::from_millis(250));
+        assert_eq!(retry_delay(250, 2), Duration::from_millis(500));
+        assert_eq!(retry_delay(250, 4), Duration::from_millis(2000));
+
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::CONFLICT));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+    }
+
+    #[tokio::test]
+    async fn test_connection_errors_are_retried_then_reported() {
+        // Port fermé : chaque tentative échoue à la connexion
+        let client = QdrantRestClient::new(QdrantRestConfig {
+            url: "http://127.0.0.1:9".to_string(),
+            timeout_secs: 1,
+            max_attempts: 3,
+            retry_base_delay_ms: 1,
+            upsert_batch_size: 2,
+        }).unwrap();
+
+        let start = std::time::Instant::now();
+        let err = client.collection_info("missing").await.unwrap_err();
+        assert!(format!("{:#}", err).contains("collection info"));
+        assert!(start.elapsed() >= retry_delay(1, 1) + retry_delay(1, 2));
+
+        // 5 points en lots de 2 : l'échec est rapporté sur le premier lot, rien n'est enregistré
+        let points = (0..5)
+            .map(|i| RestPoint { id: json!(i), vector: vec![0.0; 4], payload: None })
+            .collect();
+        let err = client.upsert_points("missing", points).await.unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("batch 1/3"));
+        assert!(message.contains("0 of 5 points already committed"));
+    }
+
     #[tokio::test]
     async fn test_rest_client_health() {
         let config = QdrantRestConfig::default();
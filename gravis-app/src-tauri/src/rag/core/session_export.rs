@@ -0,0 +1,184 @@
+// Phase 2: Export d'une session de Chat Direct
+// Bundle Markdown (citations en notes de bas de page) ou JSON
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::direct_chat::{ConversationTurn, DirectChatSession, SourceSummary};
+
+/// Format d'export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Tour exporté (horodatage lisible)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedTurn {
+    pub query: String,
+    pub response: String,
+    pub asked_at: String,
+    pub sources: Vec<SourceSummary>,
+}
+
+/// Contenu complet d'une session exportée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExportBundle {
+    pub session_id: String,
+    pub document_names: Vec<String>,
+    pub exported_at: String,
+    pub turns: Vec<ExportedTurn>,
+}
+
+impl SessionExportBundle {
+    pub fn from_session(session: &DirectChatSession) -> Self {
+        let mut document_names: Vec<String> = session
+            .documents
+            .iter()
+            .map(|doc| doc.document_name.clone())
+            .collect();
+        if document_names.is_empty() {
+            document_names.push(session.document_name.clone());
+        }
+
+        Self {
+            session_id: session.session_id.clone(),
+            document_names,
+            exported_at: Utc::now().to_rfc3339(),
+            turns: session.history.iter().map(export_turn).collect(),
+        }
+    }
+
+    /// Nom de fichier suggéré pour l'export
+    pub fn file_name(&self, format: ExportFormat) -> String {
+        let stem = self
+            .document_names
+            .first()
+            .map(|name| name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name))
+            .unwrap_or("session");
+        format!("{}_chat.{}", stem, format.extension())
+    }
+
+    pub fn render(&self, format: ExportFormat) -> Result<String, serde_json::Error> {
+        match format {
+            ExportFormat::Json => serde_json::to_string_pretty(self),
+            ExportFormat::Markdown => Ok(self.to_markdown()),
+        }
+    }
+
+    /// Chaque réponse est suivie de ses sources sous forme de notes `[^tour-source]`
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Conversation — {}\n\n", self.document_names.join(", "));
+        out.push_str(&format!("_Session `{}` exportée le {}_\n\n", self.session_id, self.exported_at));
+
+        if self.turns.is_empty() {
+            out.push_str("_Aucun échange dans cette session._\n");
+            return out;
+        }
+
+        for (turn_index, turn) in self.turns.iter().enumerate() {
+            let turn_number = turn_index + 1;
+            out.push_str(&format!("## Question {}\n\n{}\n\n", turn_number, turn.query.trim()));
+
+            let markers: Vec<String> = (1..=turn.sources.len())
+                .map(|source_number| format!("[^{}-{}]", turn_number, source_number))
+                .collect();
+            out.push_str(&format!("**Réponse** :\n\n{}", turn.response.trim()));
+            if !markers.is_empty() {
+                out.push(' ');
+                out.push_str(&markers.join(""));
+            }
+            out.push_str("\n\n");
+
+            for (marker, source) in markers.iter().zip(&turn.sources) {
+                out.push_str(&format!(
+                    "{}: **{}** — score {:.2} — {}\n",
+                    marker,
+                    source.source_document.as_deref().unwrap_or(&self.document_names[0]),
+                    source.score,
+                    footnote_text(&source.content_preview),
+                ));
+            }
+            if !markers.is_empty() {
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+}
+
+fn export_turn(turn: &ConversationTurn) -> ExportedTurn {
+    ExportedTurn {
+        query: turn.query.clone(),
+        response: turn.response.clone(),
+        asked_at: DateTime::<Utc>::from(turn.timestamp).to_rfc3339(),
+        sources: turn.sources.clone(),
+    }
+}
+
+/// Une note de bas de page doit tenir sur une ligne
+fn footnote_text(preview: &str) -> String {
+    preview.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(chunk_id: &str, score: f32, document: Option<&str>) -> SourceSummary {
+        SourceSummary {
+            chunk_id: chunk_id.to_string(),
+            content_preview: "Le budget total\nest de 1,2 M€".to_string(),
+            score,
+            confidence: 0.9,
+            span_count: 1,
+            source_document: document.map(str::to_string),
+            page: None,
+            figure_id: None,
+        }
+    }
+
+    fn bundle() -> SessionExportBundle {
+        SessionExportBundle {
+            session_id: "session_1".to_string(),
+            document_names: vec!["rapport.pdf".to_string()],
+            exported_at: "2026-01-01T00:00:00+00:00".to_string(),
+            turns: vec![ExportedTurn {
+                query: "Quel est le budget ?".to_string(),
+                response: "1,2 M€.".to_string(),
+                asked_at: "2026-01-01T00:00:00+00:00".to_string(),
+                sources: vec![source("c1", 0.823, None), source("c2", 0.5, Some("annexe.pdf"))],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_markdown_renders_citations_as_footnotes() {
+        let markdown = bundle().to_markdown();
+
+        assert!(markdown.contains("1,2 M€. [^1-1][^1-2]"));
+        assert!(markdown.contains("[^1-1]: **rapport.pdf** — score 0.82 — Le budget total est de 1,2 M€"));
+        assert!(markdown.contains("[^1-2]: **annexe.pdf** — score 0.50"));
+    }
+
+    #[test]
+    fn test_json_export_roundtrip() {
+        let content = bundle().render(ExportFormat::Json).unwrap();
+        let parsed: SessionExportBundle = serde_json::from_str(&content).unwrap();
+
+        assert_eq!(parsed.turns[0].sources.len(), 2);
+        assert_eq!(bundle().file_name(ExportFormat::Json), "rapport_chat.json");
+    }
+}
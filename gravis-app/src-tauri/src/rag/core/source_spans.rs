@@ -97,6 +97,9 @@ pub struct SourceSpanManager {
     /// Index par char offsets pour recherche rapide
     char_offset_index: HashMap<String, Vec<(usize, usize, String)>>, // (start, end, span_id)
     
+    /// Index span_id -> document_id pour résolution des références de chunks
+    span_document_index: HashMap<String, String>,
+    
     /// Statistiques d'utilisation
     stats: SpanStats,
 }
@@ -117,6 +120,7 @@ impl SourceSpanManager {
         Self {
             spans_by_document: HashMap::new(),
             char_offset_index: HashMap::new(),
+            span_document_index: HashMap::new(),
             stats: SpanStats::default(),
         }
     }
@@ -128,6 +132,7 @@ impl SourceSpanManager {
         
         // Mise à jour de l'index
         self.update_char_offset_index(&span);
+        self.span_document_index.insert(span.span_id.clone(), span.document_id.clone());
         
         // Ajout au cache
         let document_spans = self.spans_by_document
@@ -146,6 +151,14 @@ impl SourceSpanManager {
         self.spans_by_document.get(document_id)
     }
     
+    /// Résoudre un span par son ID (références `EnrichedChunk.source_spans`)
+    pub fn get_span(&self, span_id: &str) -> Option<&SourceSpan> {
+        let document_id = self.span_document_index.get(span_id)?;
+        self.spans_by_document.get(document_id)?
+            .iter()
+            .find(|span| span.span_id == span_id)
+    }
+    
     /// Trouver le span contenant une position donnée
     pub fn find_span_at_position(&self, document_id: &str, char_offset: usize) -> Option<&SourceSpan> {
         let spans = self.spans_by_document.get(document_id)?;
@@ -181,6 +194,20 @@ impl SourceSpanManager {
             }).cloned().collect())
             .unwrap_or_default();
             
+        self.build_report(chunk_content, document_id, related_spans)
+    }
+    
+    /// Générer un rapport d'explainability à partir des span IDs référencés par un chunk
+    /// (IDs inconnus ignorés)
+    pub fn generate_report_for_spans(&self, chunk_content: &str, document_id: &str, span_ids: &[String]) -> ExplainabilityReport {
+        let spans: Vec<SourceSpan> = span_ids.iter()
+            .filter_map(|span_id| self.get_span(span_id).cloned())
+            .collect();
+        
+        self.build_report(chunk_content, document_id, spans)
+    }
+    
+    fn build_report(&self, chunk_content: &str, document_id: &str, related_spans: Vec<SourceSpan>) -> ExplainabilityReport {
         ExplainabilityReport {
             chunk_content: chunk_content.to_string(),
             document_id: document_id.to_string(),
@@ -280,6 +307,13 @@ impl SourceSpanManager {
         (covered_chars as f32 / chunk_content.len() as f32) * 100.0
     }
     
+    /// Fusionner les spans d'un autre gestionnaire (spans invalides ignorés)
+    pub fn merge(&mut self, other: SourceSpanManager) {
+        for span in other.spans_by_document.into_values().flatten() {
+            let _ = self.add_span(span);
+        }
+    }
+    
     /// Obtenir les statistiques
     pub fn get_stats(&self) -> &SpanStats {
         &self.stats
@@ -289,6 +323,7 @@ impl SourceSpanManager {
     pub fn remove_document_spans(&mut self, document_id: &str) {
         self.spans_by_document.remove(document_id);
         self.char_offset_index.remove(document_id);
+        self.span_document_index.retain(|_, doc_id| doc_id != document_id);
         self.update_stats();
     }
 }
@@ -304,6 +339,34 @@ pub struct ExplainabilityReport {
     pub generated_at: std::time::SystemTime,
 }
 
+impl ExplainabilityReport {
+    /// Trace lisible : une ligne par span (page, offsets, méthode, confidence, extrait)
+    pub fn trace(&self) -> String {
+        let mut lines = vec![format!(
+            "Document {} — {} span(s), confidence {:.2}, couverture {:.0}%",
+            self.document_id,
+            self.contributing_spans.len(),
+            self.confidence_score,
+            self.coverage_percentage,
+        )];
+        
+        for span in &self.contributing_spans {
+            let page = span.bbox.as_ref()
+                .and_then(|bbox| bbox.page)
+                .map(|page| format!("p.{} ", page))
+                .unwrap_or_default();
+            let preview: String = span.original_content.chars().take(80).collect();
+            lines.push(format!(
+                "  • {}chars {}-{} (lignes {}-{}) via {:?}, confidence {:.2} : « {} »",
+                page, span.char_start, span.char_end, span.line_start, span.line_end,
+                span.extraction_metadata.method, span.extraction_metadata.confidence, preview.trim(),
+            ));
+        }
+        
+        lines.join("\n")
+    }
+}
+
 /// Erreurs de source spans
 #[derive(Debug, thiserror::Error)]
 pub enum SourceSpanError {
@@ -378,9 +441,9 @@ impl SourceSpan {
         self
     }
     
-    /// Calculer les lignes à partir du contenu
+    /// Calculer les lignes à partir du contenu (`char_start` en caractères, pas en octets)
     pub fn calculate_lines(&mut self, full_content: &str) {
-        let lines_before = full_content[..self.char_start].matches('\n').count();
+        let lines_before = full_content.chars().take(self.char_start).filter(|&c| c == '\n').count();
         let lines_in_span = self.original_content.matches('\n').count();
         
         self.line_start = lines_before + 1; // 1-indexed
@@ -420,6 +483,33 @@ impl BoundingBox {
     }
 }
 
+/// Offsets (en caractères) de chaque chunk dans le texte du document.
+/// Recherche séquentielle : les chunks se chevauchent mais démarrent dans l'ordre du texte.
+/// `None` si le chunk n'apparaît pas tel quel (texte retouché après découpage) : inconnu plutôt qu'inventé.
+pub fn locate_chunks(content: &str, chunks: &[crate::rag::EnrichedChunk]) -> Vec<Option<(usize, usize)>> {
+    locate_texts(content, chunks.iter().map(crate::rag::strip_page_overlap))
+}
+
+/// Offsets (en caractères) de fragments du document pris dans l'ordre du texte (chunks, blocs OCR...)
+pub fn locate_texts<'a>(content: &str, texts: impl IntoIterator<Item = &'a str>) -> Vec<Option<(usize, usize)>> {
+    let mut cursor = 0;
+    // (octet, caractère) : conversion incrémentale, les positions trouvées sont croissantes
+    let mut converted = (0, 0);
+
+    texts.into_iter().map(|text| {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let start = cursor + content.get(cursor..)?.find(text)?;
+        cursor = start + text.chars().next().map_or(1, char::len_utf8);
+
+        converted.1 += content[converted.0..start].chars().count();
+        converted.0 = start;
+        Some((converted.1, converted.1 + text.chars().count()))
+    }).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -495,4 +585,58 @@ mod tests {
         assert_eq!(report.contributing_spans.len(), 1);
         assert!(report.confidence_score > 0.0);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_report_for_referenced_spans() {
+        let mut manager = SourceSpanManager::new();
+        
+        let span = SourceSpan::new(
+            "doc1".to_string(),
+            PathBuf::from("/test/doc1.pdf"),
+            100,
+            118,
+            "Total net: 1250 €".to_string(),
+            ExtractionMethod::PdfNative,
+        ).with_bbox(BoundingBox::pdf_points(2, 50.0, 400.0, 200.0, 12.0));
+        let span_id = span.span_id.clone();
+        manager.add_span(span).unwrap();
+        
+        // Le contenu du chunk diffère du span : seule la référence par ID compte
+        let report = manager.generate_report_for_spans(
+            "Salaire — Total net: 1250 €",
+            "doc1",
+            &[span_id, "unknown_span".to_string()],
+        );
+        
+        assert_eq!(report.contributing_spans.len(), 1);
+        assert_eq!(report.confidence_score, 1.0);
+        
+        let trace = report.trace();
+        assert!(trace.contains("p.2 chars 100-118"));
+        assert!(trace.contains("PdfNative"));
+    }
+
+    #[test]
+    fn test_locate_chunks_in_char_offsets() {
+        use crate::rag::test_fixtures::test_chunk;
+
+        let content = "Préambule é.\nLe salarié perçoit 42 000 €.\nLe salarié perçoit une prime.";
+        let chunks = vec![
+            test_chunk("a", "Préambule é.\nLe salarié"),
+            // Chevauchement avec le chunk précédent
+            test_chunk("b", "Le salarié perçoit 42 000 €."),
+            test_chunk("c", "Le salarié perçoit"),
+            test_chunk("d", "absent du document"),
+        ];
+
+        let ranges = locate_chunks(content, &chunks);
+        let chars: Vec<char> = content.chars().collect();
+        let slice = |(start, end): (usize, usize)| chars[start..end].iter().collect::<String>();
+        assert_eq!(ranges[0], Some((0, 23)));
+        assert_eq!(slice(ranges[1].unwrap()), "Le salarié perçoit 42 000 €.");
+        // Aussi présent au début de b : trouvé après lui, pas sur la première occurrence
+        assert_eq!(slice(ranges[2].unwrap()), "Le salarié perçoit");
+        assert!(ranges[2].unwrap().0 > ranges[1].unwrap().0);
+        assert_eq!(ranges[3], None);
+    }
+}
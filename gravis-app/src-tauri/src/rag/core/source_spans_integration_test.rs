@@ -50,6 +50,11 @@ mod integration_tests {
         assert!(span_outside.is_none(), "❌ Span trouvé en dehors de la plage");
         println!("✅ Recherche hors plage fonctionne");
         
+        // Résolution par ID (références des chunks)
+        assert_eq!(manager.get_span(&span.span_id).map(|s| s.char_end), Some(50));
+        assert!(manager.get_span("span_inconnu").is_none());
+        println!("✅ Résolution par span_id fonctionne");
+        
         // Test des statistiques
         let stats = manager.get_stats();
         assert_eq!(stats.total_spans, 1);
@@ -149,6 +154,7 @@ mod integration_tests {
                 ocr_metadata: None,
                 source_type: SourceType::NativeText,
                 extraction_method: ExtractionMethod::DirectRead,
+                page_overlap_len: 0,
             },
             group_id: "test_group".to_string(),
             source_spans: Some(span_ids.clone()),
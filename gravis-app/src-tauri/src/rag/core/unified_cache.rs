@@ -3,14 +3,15 @@
 
 use anyhow::Result;
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use blake3::Hasher;
 
 use crate::rag::{
-    GroupDocument, ChunkConfig, RagResult, RagError
+    GroupDocument, ChunkConfig, RagResult, RagError, EnrichedChunk, CustomE5Embedder
 };
 use super::ingestion_engine::CacheStats;
 use crate::rag::ocr::OcrCache;
@@ -27,6 +28,9 @@ pub struct UnifiedCache {
     // Cache documents (par hash de fichier + config)
     document_cache: Arc<DashMap<String, CachedDocument>>,
     
+    // Cache résumés de documents (par hash de contenu + méthode)
+    summary_cache: Arc<DashMap<String, String>>,
+    
     // Statistiques temps réel
     stats: Arc<DashMap<String, CacheStats>>,
 }
@@ -48,6 +52,7 @@ impl UnifiedCache {
             ocr_cache,
             embedding_cache: Arc::new(DashMap::new()),
             document_cache: Arc::new(DashMap::new()),
+            summary_cache: Arc::new(DashMap::new()),
             stats: Arc::new(DashMap::new()),
         }
     }
@@ -129,6 +134,109 @@ impl UnifiedCache {
         self.embedding_cache.get(chunk_hash).map(|entry| entry.clone())
     }
 
+    /// Clé de cache d'un résumé : méthode (`extractive`, `llm:<modèle>`) + hash du texte
+    pub fn summary_cache_key(method_key: &str, text: &str) -> String {
+        format!("{}:{}", method_key, blake3::hash(text.as_bytes()).to_hex())
+    }
+
+    /// Cache le résumé d'un document
+    pub fn cache_summary(&self, key: &str, summary: String) {
+        debug!("Caching document summary: {}", key);
+        self.summary_cache.insert(key.to_string(), summary);
+    }
+
+    /// Récupère un résumé depuis le cache
+    pub fn get_cached_summary(&self, key: &str) -> Option<String> {
+        self.summary_cache.get(key).map(|entry| entry.clone())
+    }
+
+    /// Clé de cache d'un embedding : modèle + hash du chunk (hash du contenu si absent)
+    pub fn embedding_cache_key(model_key: &str, chunk: &EnrichedChunk) -> String {
+        if chunk.hash.is_empty() {
+            format!("{}:{}", model_key, blake3::hash(chunk.content.as_bytes()).to_hex())
+        } else {
+            format!("{}:{}", model_key, chunk.hash)
+        }
+    }
+
+    /// Calcule les embeddings manquants d'un lot de chunks en réutilisant le cache.
+    /// Les chunks vides ou d'échec d'extraction sont ignorés. Un chunk en échec est ré-encodé
+    /// seul une fois après le lot ; s'il échoue encore, il est rapporté dans `failed_chunks`.
+    #[tracing::instrument(name = "embed", skip_all, fields(chunks = chunks.len()))]
+    pub async fn embed_chunks(
+        &self,
+        embedder: &CustomE5Embedder,
+        chunks: &mut [EnrichedChunk],
+    ) -> EmbeddingBatchStats {
+        let model_key = embedder.model_key();
+        let mut batch = EmbeddingBatchStats::default();
+
+        // Chunks à encoder (index, clé de cache), les autres sont servis par le cache
+        let mut pending: Vec<(usize, String)> = Vec::new();
+        for (index, chunk) in chunks.iter_mut().enumerate().filter(|(_, chunk)| chunk.embedding.is_none()) {
+            if chunk.content.trim().is_empty() || chunk.content.starts_with("EXTRACTION FAILED") {
+                continue;
+            }
+
+            let key = Self::embedding_cache_key(&model_key, chunk);
+            if let Some(cached) = self.get_cached_embedding(&key) {
+                chunk.embedding = Some(cached);
+                batch.embeddings_from_cache += 1;
+            } else {
+                pending.push((index, key));
+            }
+        }
+
+        // Préfixe "passage:" pour l'indexation, encodage par paquets
+        let texts: Vec<String> = pending.iter().map(|(index, _)| chunks[*index].content.clone()).collect();
+        let embeddings = embedder.encode_documents_batch(&texts).await;
+        let mut retries: Vec<(usize, String)> = Vec::new();
+        for ((index, key), result) in pending.into_iter().zip(embeddings) {
+            let chunk = &mut chunks[index];
+            match result {
+                Ok(embedding) => {
+                    self.cache_embedding(&key, embedding.clone());
+                    chunk.embedding = Some(embedding);
+                    batch.embeddings_computed += 1;
+                }
+                Err(e) => {
+                    warn!("Failed to embed chunk {}, will retry: {}", chunk.id, e);
+                    retries.push((index, key));
+                }
+            }
+        }
+
+        // Seconde tentative, chunk par chunk, après le lot
+        for (index, key) in retries {
+            let chunk = &mut chunks[index];
+            match embedder.encode_document(&chunk.content).await {
+                Ok(embedding) => {
+                    self.cache_embedding(&key, embedding.clone());
+                    chunk.embedding = Some(embedding);
+                    batch.embeddings_computed += 1;
+                    info!("Chunk {} embedded on retry", chunk.id);
+                }
+                Err(e) => {
+                    warn!("❌ Chunk {} not embedded after retry, it will not be indexed: {}", chunk.id, e);
+                    batch.failed_chunks.push(ChunkEmbeddingFailure {
+                        chunk_id: chunk.id.clone(),
+                        reason: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        self.update_global_stats(&CacheStats {
+            embedding_cache_hits: batch.embeddings_from_cache as u32,
+            total_cache_requests: batch.total() as u32,
+            ..CacheStats::default()
+        });
+        debug!("Embeddings: {} from cache, {} computed, {} failed",
+               batch.embeddings_from_cache, batch.embeddings_computed, batch.failed_chunks.len());
+
+        batch
+    }
+
     /// Génère la clé de cache pour un document
     fn generate_document_cache_key(&self, file_path: &Path, config: &ChunkConfig) -> RagResult<String> {
         let path_str = file_path.to_string_lossy();
@@ -300,6 +408,29 @@ impl UnifiedCache {
     }
 }
 
+/// Bilan d'un calcul d'embeddings par lot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingBatchStats {
+    pub embeddings_from_cache: usize,
+    pub embeddings_computed: usize,
+    /// Chunks sans embedding après la seconde tentative (absents de l'index)
+    #[serde(default)]
+    pub failed_chunks: Vec<ChunkEmbeddingFailure>,
+}
+
+/// Chunk dont l'embedding a échoué deux fois
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkEmbeddingFailure {
+    pub chunk_id: String,
+    pub reason: String,
+}
+
+impl EmbeddingBatchStats {
+    pub fn total(&self) -> usize {
+        self.embeddings_from_cache + self.embeddings_computed
+    }
+}
+
 /// Résultat du nettoyage de cache
 #[derive(Debug)]
 pub struct CacheCleanupResult {
@@ -357,6 +488,7 @@ mod tests {
             chunk_size: 256,
             overlap: 32,
             strategy: crate::rag::ChunkStrategy::Heuristic,
+            ..ChunkConfig::default()
         };
         
         let key3 = unified_cache.generate_document_cache_key(temp_file.path(), &different_config).unwrap();
@@ -383,4 +515,18 @@ mod tests {
         let cached = unified_cache.get_cached_embedding(chunk_hash).unwrap();
         assert_eq!(cached, embedding);
     }
+
+    #[test]
+    fn test_embedding_cache_key_includes_model() {
+        let mut chunk = crate::rag::test_fixtures::test_chunk("chunk_1", "Chiffre d'affaires 2023");
+        chunk.hash = "abc123".to_string();
+
+        let small = UnifiedCache::embedding_cache_key("intfloat/e5-small-v2@main/384d", &chunk);
+        let base = UnifiedCache::embedding_cache_key("intfloat/e5-base-v2@main/768d", &chunk);
+        assert_ne!(small, base);
+        assert!(small.ends_with(":abc123"));
+
+        chunk.hash.clear();
+        assert_ne!(UnifiedCache::embedding_cache_key("m", &chunk), "m:");
+    }
 }
\ No newline at end of file
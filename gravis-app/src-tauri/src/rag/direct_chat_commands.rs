@@ -3,40 +3,120 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tauri::State;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::RwLock;
 use tracing::{info, warn, error, debug};
 
 use crate::rag::{
-    DocumentProcessor, TesseractProcessor, TesseractConfig, CustomE5Embedder,
-    DocumentType, ChunkConfig, RagError
+    DocumentProcessor, TesseractConfig, CustomE5Embedder,
+    DocumentType, ChunkConfig, RagError, UnifiedCache
 };
+use crate::rag::processing::table_detector::{detect_table_content, structure_table_content};
+use crate::rag::processing::document_summary::{condense_text, extract_key_sentences};
 use crate::rag::core::source_spans::{SourceSpan, ExtractionMetadata};
 use crate::rag::core::direct_chat::{
     DirectChatSession, DirectChatResponse, SelectionContext, OCRContent, OCRPage, 
-    OCRBlock, BlockType, BoundingBox, LayoutAnalysis, DirectChatError
+    OCRBlock, BlockType, BoundingBox, LayoutAnalysis, DirectChatError, SessionStatus
 };
-use crate::rag::core::direct_chat_manager::{DirectChatManager, ScoredChunk, SessionStats, SessionInfo};
+use crate::rag::core::direct_chat_manager::{DirectChatManager, ScoredChunk, SessionStats, SessionInfo, DEFAULT_SESSION_MIN_SCORE};
+use crate::rag::core::llm_synthesis::{LlmSynthesisConfig, LlmSynthesizer};
+use crate::rag::core::direct_chat::ConversationTurn;
+pub use crate::rag::core::direct_chat::SourceSummary;
+use crate::rag::core::session_export::{ExportFormat, SessionExportBundle};
+
+/// Événement de streaming des tokens de synthèse LLM
+pub const DIRECT_CHAT_TOKEN_EVENT: &str = "direct-chat-token";
+
+/// Événement de progression des embeddings d'un document dragué
+pub const DIRECT_CHAT_EMBEDDING_PROGRESS_EVENT: &str = "direct-chat-embedding-progress";
 
 /// État pour chat direct (ajouté au RagState principal)
 #[derive(Clone)]
 pub struct DirectChatState {
     pub manager: DirectChatManager,
     pub document_processor: DocumentProcessor,
+    pub config: Arc<RwLock<DirectChatConfig>>,
+    /// Cache d'embeddings partagé avec le RAG persistant (clé: modèle + hash de chunk)
+    pub embedding_cache: Arc<UnifiedCache>,
+}
+
+/// Configuration runtime du pipeline de retrieval pour `chat_with_llm_context`
+/// (modifiable sans recompilation via `set_direct_chat_config`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectChatConfig {
+    pub enable_query_reranking: bool,
+    pub enable_numerical_reranking: bool,
+    pub enable_section_prior: bool,
+    pub candidate_pool_size: usize, // Chunks récupérés avant reranking
+    pub final_top_k: usize,         // Chunks envoyés au LLM
+    pub llm: Option<LlmSynthesisConfig>, // None = réponse heuristique (generate_contextual_response)
+    pub max_history_tokens: usize,       // Budget de l'historique injecté dans le contexte
+    pub llm_chunk_max_chars: usize,      // Longueur max d'un chunk dans le contexte LLM (coupe en fin de phrase)
+    pub embedding_batch_size: usize,     // Chunks par lot d'embedding d'un document dragué
+    pub embedding_concurrency: usize,    // Lots encodés simultanément en arrière-plan
+    pub min_score: f32,                  // Similarité cosinus minimale d'un chunk transmis à la réponse
+}
+
+impl Default for DirectChatConfig {
+    fn default() -> Self {
+        Self {
+            enable_query_reranking: false,
+            enable_numerical_reranking: true,
+            enable_section_prior: true,
+            candidate_pool_size: 20,
+            final_top_k: 10,
+            llm: None,
+            max_history_tokens: 1500,
+            llm_chunk_max_chars: 500,
+            embedding_batch_size: 16,
+            embedding_concurrency: 2,
+            min_score: DEFAULT_SESSION_MIN_SCORE,
+        }
+    }
+}
+
+impl DirectChatConfig {
+    /// Valider la cohérence des tailles
+    pub fn validate(&self) -> Result<(), String> {
+        if self.final_top_k == 0 {
+            return Err("final_top_k must be at least 1".to_string());
+        }
+        if self.candidate_pool_size < self.final_top_k {
+            return Err(format!(
+                "candidate_pool_size ({}) must be >= final_top_k ({})",
+                self.candidate_pool_size, self.final_top_k
+            ));
+        }
+        if self.llm_chunk_max_chars == 0 {
+            return Err("llm_chunk_max_chars must be at least 1".to_string());
+        }
+        if self.embedding_batch_size == 0 || self.embedding_concurrency == 0 {
+            return Err("embedding_batch_size and embedding_concurrency must be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_score) {
+            return Err(format!("min_score must be between 0.0 and 1.0, got {}", self.min_score));
+        }
+        if let Some(llm) = &self.llm {
+            if llm.base_url.trim().is_empty() || llm.model.trim().is_empty() {
+                return Err("LLM backend requires a base_url and a model".to_string());
+            }
+        }
+        Ok(())
+    }
 }
 
 impl DirectChatState {
-    pub async fn new(embedder: std::sync::Arc<CustomE5Embedder>) -> Result<Self, RagError> {
+    /// `embedder` est le verrou de `RagState` : un changement de modèle vaut aussi pour le chat direct
+    pub async fn new(
+        embedder: Arc<RwLock<Arc<CustomE5Embedder>>>,
+        embedding_cache: Arc<UnifiedCache>,
+    ) -> Result<Self, RagError> {
         info!("Initializing DirectChatState for Phase 2 MVP");
 
-        // Créer processeur OCR pour mode direct
-        let ocr_processor = TesseractProcessor::new(TesseractConfig::default())
-            .await
-            .map_err(|e| RagError::InvalidConfig(format!("OCR processor init failed: {}", e)))?;
-
-        // Créer processeur de documents
-        let document_processor = DocumentProcessor::new(ocr_processor, embedder.clone())
-            .await
-            .map_err(|e| RagError::InvalidConfig(format!("DocumentProcessor init failed: {}", e)))?;
+        // Créer processeur de documents (OCR initialisé au premier document qui en a besoin)
+        let document_processor = DocumentProcessor::with_lazy_ocr(TesseractConfig::default(), embedder.read().await.clone());
 
         // Créer gestionnaire avec TTL de 2 heures pour MVP
         let manager = DirectChatManager::with_ttl(embedder, 7200);
@@ -44,6 +124,8 @@ impl DirectChatState {
         Ok(Self {
             manager,
             document_processor,
+            config: Arc::new(RwLock::new(DirectChatConfig::default())),
+            embedding_cache,
         })
     }
 }
@@ -56,6 +138,8 @@ pub struct ProcessDocumentResponse {
     pub chunks_created: usize,
     pub embedded_chunks: usize,
     pub confidence_score: f64,
+    pub embeddings_from_cache: usize,
+    pub embeddings_computed: usize,
 }
 
 /// Paramètres de chat avec sélection optionnelle
@@ -65,6 +149,9 @@ pub struct ChatRequest {
     pub query: String,
     pub selection: Option<SelectionContext>,
     pub limit: Option<usize>,
+    /// Similarité cosinus minimale des chunks retenus (défaut : `DirectChatConfig::min_score`)
+    #[serde(default)]
+    pub min_score: Option<f32>,
 }
 
 /// Réponse pour URL de PDF temporaire
@@ -84,16 +171,15 @@ pub struct ChatResponse {
     pub search_time_ms: u64,
     pub chunks_used: usize,
     pub sources_summary: Vec<SourceSummary>,
+    pub generation_method: String, // "llm:<model>" ou "heuristic"
 }
 
-/// Résumé d'une source contributrice
+/// Fragment de réponse émis pendant la synthèse LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SourceSummary {
-    pub chunk_id: String,
-    pub content_preview: String,
-    pub score: f32,
-    pub confidence: f64,
-    pub span_count: usize,
+pub struct LlmTokenEvent {
+    pub session_id: String,
+    pub token: String,
+    pub done: bool,
 }
 
 // === Sprint 1 Niveau 1: LLM Response Generation ===
@@ -124,18 +210,256 @@ pub struct LlmChunkInfo {
 
 // === Commandes Tauri Phase 2 ===
 
-/// Traiter un document dragué et créer session temporaire - VERSION CANONIQUE
+/// Progression des embeddings d'une session (`DIRECT_CHAT_EMBEDDING_PROGRESS_EVENT`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingProgress {
+    pub session_id: String,
+    pub embedded_chunks: usize,
+    pub total_chunks: usize,
+    pub embeddings_from_cache: usize,
+    pub embeddings_computed: usize,
+    /// `true` sur le dernier événement : la session est passée à `Ready`
+    pub done: bool,
+}
+
+/// Traiter un document dragué et créer session temporaire - VERSION CANONIQUE.
+/// La session est retournée dès l'extraction terminée, en état `Processing` :
+/// les embeddings sont calculés en arrière-plan (progression via `DIRECT_CHAT_EMBEDDING_PROGRESS_EVENT`).
 #[tauri::command]
+#[tracing::instrument(name = "ingest", skip_all, fields(session_id = tracing::field::Empty, file = %file_path))]
 pub async fn process_dropped_document(
     file_path: String,
     file_data: Vec<u8>,
     mime_type: String,
+    app: AppHandle,
     state: State<'_, DirectChatState>,
 ) -> Result<ProcessDocumentResponse, String> {
     let start_time = std::time::Instant::now();
     info!("🚀 Phase 2: Processing dropped document: {} ({} bytes, {})", 
           file_path, file_data.len(), mime_type);
 
+    // 1-5. Fichier temporaire, traitement et spans (embeddings en arrière-plan)
+    let progress = crate::rag::commands::ocr_progress_emitter(app.clone(), file_path.clone());
+    let ingested = ingest_dropped_file(&state, &file_path, file_data, Some(&progress)).await?;
+    let temp_path = ingested.temp_path.clone();
+
+    let mut session = DirectChatSession::new_legacy(
+        temp_path.clone(),
+        ingested.document_type,
+        ingested.chunks,
+        ingested.ocr_content,
+    );
+    session.status = SessionStatus::Processing;
+
+    let session_id = session.session_id.clone();
+    tracing::Span::current().record("session_id", session_id.as_str());
+    let chunks_created = session.chunks.len();
+    let pending_chunks = session.chunks.clone();
+    
+    // 6. Stockage immédiat de la session (recherche refusée tant qu'elle n'est pas `Ready`)
+    state.manager.store_spans(&session_id, ingested.span_manager).await;
+    state.manager.store_session(session.clone()).await
+        .map_err(|e| format!("Failed to store session: {}", e))?;
+
+    // 7. Embeddings par lots, concurrence bornée, sans bloquer la commande
+    let (batch_size, concurrency) = {
+        let config = state.config.read().await;
+        (config.embedding_batch_size, config.embedding_concurrency)
+    };
+    tokio::spawn(embed_session_in_background(
+        app,
+        state.inner().clone(),
+        session_id.clone(),
+        pending_chunks,
+        batch_size,
+        concurrency,
+    ));
+
+    let confidence_score = calculate_session_confidence(&session);
+    let processing_time = start_time.elapsed().as_millis() as u64;
+
+    info!("✅ Created direct chat session {} with {} chunks in {}ms (embeddings in progress)",
+          session_id, chunks_created, processing_time);
+
+    // 8. NE PAS supprimer le fichier temporaire - conservé pour affichage PDF
+    // Le fichier sera nettoyé lors du cleanup de la session via cleanup_direct_chat_session
+    info!("📌 Keeping temp file for PDF display: {:?}", temp_path);
+
+    // Compteurs d'embeddings à 0 : les valeurs finales arrivent avec l'événement `done`
+    Ok(ProcessDocumentResponse {
+        session,
+        processing_time_ms: processing_time,
+        chunks_created,
+        embedded_chunks: 0,
+        confidence_score,
+        embeddings_from_cache: 0,
+        embeddings_computed: 0,
+    })
+}
+
+/// Encoder les chunks d'une session par lots de `batch_size`, au plus `concurrency` lots à la fois,
+/// puis passer la session à `Ready`. Chaque lot terminé est reporté dans la session et notifié au frontend.
+#[tracing::instrument(name = "embed", skip_all, fields(session_id = %session_id, chunks = chunks.len()))]
+async fn embed_session_in_background(
+    app: AppHandle,
+    state: DirectChatState,
+    session_id: String,
+    chunks: Vec<crate::rag::EnrichedChunk>,
+    batch_size: usize,
+    concurrency: usize,
+) {
+    use tokio::sync::Semaphore;
+
+    let start_time = std::time::Instant::now();
+    let total_chunks = chunks.len();
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut handles = Vec::new();
+    // Un seul modèle pour toute la session, même si l'embedder change pendant l'encodage
+    let embedder = state.manager.embedder().await;
+
+    for batch in chunks.chunks(batch_size.max(1)) {
+        let sem = Arc::clone(&semaphore);
+        let state = state.clone();
+        let embedder = Arc::clone(&embedder);
+        let mut batch = batch.to_vec();
+
+        handles.push(tokio::spawn(async move {
+            let _permit = sem.acquire().await.unwrap();
+            // Encodage CPU-bound hors des workers async : les commandes IPC restent réactives
+            tokio::task::spawn_blocking(move || {
+                let stats = tokio::runtime::Handle::current()
+                    .block_on(state.embedding_cache.embed_chunks(&embedder, &mut batch));
+                (batch, stats)
+            })
+            .await
+        }));
+    }
+
+    let mut progress = EmbeddingProgress {
+        session_id: session_id.clone(),
+        embedded_chunks: 0,
+        total_chunks,
+        embeddings_from_cache: 0,
+        embeddings_computed: 0,
+        done: false,
+    };
+
+    for handle in handles {
+        let (batch, stats) = match handle.await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) | Err(e) => {
+                error!("Embedding batch task failed for session {}: {}", session_id, e);
+                continue;
+            }
+        };
+
+        let embeddings: std::collections::HashMap<String, Vec<f32>> = batch.into_iter()
+            .filter_map(|chunk| Some((chunk.id, chunk.embedding?)))
+            .collect();
+        match state.manager.apply_chunk_embeddings(&session_id, embeddings).await {
+            Ok(updated) => progress.embedded_chunks += updated,
+            Err(e) => {
+                // Session nettoyée ou expirée pendant le traitement : inutile de continuer
+                warn!("⚠️ Stopping embeddings for session {}: {}", session_id, e);
+                return;
+            }
+        }
+        progress.embeddings_from_cache += stats.embeddings_from_cache;
+        progress.embeddings_computed += stats.embeddings_computed;
+
+        debug!("🔄 Session {} embeddings: {}/{}", session_id, progress.embedded_chunks, total_chunks);
+        if let Err(e) = app.emit(DIRECT_CHAT_EMBEDDING_PROGRESS_EVENT, &progress) {
+            warn!("Failed to emit embedding progress: {}", e);
+        }
+    }
+
+    if let Err(e) = state.manager.set_session_status(&session_id, SessionStatus::Ready).await {
+        warn!("⚠️ Could not finalize session {}: {}", session_id, e);
+        return;
+    }
+
+    progress.done = true;
+    info!("✅ Session {} ready: {}/{} chunks embedded ({} from cache) in {}ms",
+          session_id, progress.embedded_chunks, total_chunks,
+          progress.embeddings_from_cache, start_time.elapsed().as_millis());
+    if let Err(e) = app.emit(DIRECT_CHAT_EMBEDDING_PROGRESS_EVENT, &progress) {
+        warn!("Failed to emit embedding progress: {}", e);
+    }
+}
+
+/// Ajouter un document dragué à une session existante (questions multi-documents)
+#[tauri::command]
+#[tracing::instrument(name = "ingest", skip_all, fields(session_id = %session_id, file = %file_path))]
+pub async fn add_document_to_session(
+    session_id: String,
+    file_path: String,
+    file_data: Vec<u8>,
+    mime_type: String,
+    state: State<'_, DirectChatState>,
+) -> Result<ProcessDocumentResponse, String> {
+    let start_time = std::time::Instant::now();
+    info!("➕ Adding document {} ({} bytes, {}) to session {}",
+          file_path, file_data.len(), mime_type, session_id);
+
+    // Échouer tôt si la session n'existe plus (évite un traitement inutile)
+    state.manager.get_session(&session_id).await
+        .map_err(|e| format!("Session not available: {}", e))?;
+
+    let mut ingested = ingest_dropped_file(&state, &file_path, file_data, None).await?;
+    let chunks_created = ingested.chunks.len();
+
+    // Embeddings via le cache par hash de chunk
+    let embedding_stats = state.embedding_cache
+        .embed_chunks(&state.manager.embedder().await, &mut ingested.chunks)
+        .await;
+    info!("✅ Generated {} embeddings for added document ({} from cache)",
+          embedding_stats.total(), embedding_stats.embeddings_from_cache);
+
+    state.manager
+        .add_document_to_session(&session_id, ingested.temp_path, ingested.chunks, ingested.ocr_content)
+        .await
+        .map_err(|e| format!("Failed to add document to session: {}", e))?;
+    state.manager.store_spans(&session_id, ingested.span_manager).await;
+
+    let updated_session = state.manager.get_session(&session_id).await
+        .map_err(|e| format!("Failed to retrieve updated session: {}", e))?;
+
+    let embedded_chunks = updated_session.embedded_chunks_count();
+    let confidence_score = calculate_session_confidence(&updated_session);
+    let processing_time = start_time.elapsed().as_millis() as u64;
+
+    info!("✅ Session {} now has {} documents, {} chunks ({} embedded) in {}ms",
+          session_id, updated_session.documents.len(), updated_session.chunks.len(),
+          embedded_chunks, processing_time);
+
+    Ok(ProcessDocumentResponse {
+        session: updated_session,
+        processing_time_ms: processing_time,
+        chunks_created,
+        embedded_chunks,
+        confidence_score,
+        embeddings_from_cache: embedding_stats.embeddings_from_cache,
+        embeddings_computed: embedding_stats.embeddings_computed,
+    })
+}
+
+/// Document dragué traité, prêt à rejoindre une session
+struct IngestedDocument {
+    temp_path: PathBuf,
+    document_type: DocumentType,
+    chunks: Vec<crate::rag::EnrichedChunk>,
+    ocr_content: OCRContent,
+    span_manager: crate::rag::SourceSpanManager,
+}
+
+/// Pipeline commun aux documents dragués: fichier temporaire, extraction, spans.
+/// Les embeddings sont calculés par l'appelant (arrière-plan ou synchrone).
+async fn ingest_dropped_file(
+    state: &DirectChatState,
+    file_path: &str,
+    file_data: Vec<u8>,
+    progress: Option<&crate::rag::ocr::OcrProgressCallback>,
+) -> Result<IngestedDocument, String> {
     // 1. Créer un fichier temporaire avec les données
     let temp_dir = std::env::temp_dir();
     let temp_file_name = format!("gravis_temp_{}", file_path);
@@ -152,101 +476,73 @@ pub async fn process_dropped_document(
     let temp_group_id = "direct_chat_temp";
     
     let document = state.document_processor
-        .process_document(&temp_path, temp_group_id, &chunk_config)
+        .process_document_with_progress(&temp_path, temp_group_id, &chunk_config, progress)
         .await
         .map_err(|e| format!("Document processing failed: {}", e))?;
 
     // 3. Création du contenu OCR à partir du document traité
-    let ocr_content = create_ocr_content_from_document(&document)?;
+    let mut ocr_content = create_ocr_content_from_document(&document)?;
     
     // 4. Détermination du type de document (pour l'instant générique)
     let document_type = determine_document_type(&document);
 
-    // 5. Création de la session temporaire avec chunks enrichis
-    let mut enriched_chunks = document.chunks.clone();
-    
-    // 6. Génération des embeddings PENDANT le traitement (PR #4 Fix)
-    info!("🔄 Generating embeddings for {} chunks during processing", enriched_chunks.len());
-    let mut embedded_count = 0;
-    
-    for chunk in &mut enriched_chunks {
-        if !chunk.content.trim().is_empty() 
-            && !chunk.content.starts_with("EXTRACTION FAILED") {
-            
-            match state.manager.embedder.encode_document(&chunk.content).await {
-                Ok(embedding) => {
-                    chunk.embedding = Some(embedding);
-                    embedded_count += 1;
-                }
-                Err(e) => {
-                    warn!("Failed to embed chunk {} during processing: {}", chunk.id, e);
-                }
-            }
-        }
+    // 5. Garde-fou de la fenêtre du modèle (comme l'ingestion RAG), avant l'attribution des spans
+    let embedder = state.manager.embedder().await;
+    let (mut enriched_chunks, chunks_truncated) = crate::rag::processing::enforce_chunk_token_limit(
+        document.chunks.clone(),
+        embedder.max_input_tokens(),
+        chunk_config.oversized_chunks,
+        |text| embedder.count_tokens(text),
+    );
+    if !chunks_truncated.is_empty() {
+        warn!("✂️ {} chunks exceed the embedding limit and will be truncated: {:?}", chunks_truncated.len(), chunks_truncated);
     }
-    
-    info!("✅ Generated {} embeddings during processing", embedded_count);
 
-    let session = DirectChatSession::new_legacy(
-        temp_path.clone(),
+    // 6. Chunks enrichis + source spans réels (bbox natives) référencés par ID
+    let span_manager = build_session_spans(&document, &mut ocr_content, &mut enriched_chunks);
+
+    Ok(IngestedDocument {
+        temp_path,
         document_type,
-        enriched_chunks,
+        chunks: enriched_chunks,
         ocr_content,
-    );
-
-    let session_id = session.session_id.clone();
-    let chunks_created = session.chunks.len();
-    
-    // 7. Stockage direct (embeddings déjà générés)
-    state.manager.store_session(session.clone()).await
-        .map_err(|e| format!("Failed to store session: {}", e))?;
-
-    // 8. Récupérer la session mise à jour
-    let updated_session = state.manager.get_session(&session_id).await
-        .map_err(|e| format!("Failed to retrieve updated session: {}", e))?;
-
-    let embedded_chunks = updated_session.embedded_chunks_count();
-    let confidence_score = calculate_session_confidence(&updated_session);
-    let processing_time = start_time.elapsed().as_millis() as u64;
-
-    info!("✅ Created direct chat session {} with {} chunks ({} embedded) in {}ms",
-          session_id, chunks_created, embedded_chunks, processing_time);
-
-    // 9. NE PAS supprimer le fichier temporaire - conservé pour affichage PDF
-    // Le fichier sera nettoyé lors du cleanup de la session via cleanup_direct_chat_session
-    info!("📌 Keeping temp file for PDF display: {:?}", temp_path);
-
-    Ok(ProcessDocumentResponse {
-        session: updated_session,
-        processing_time_ms: processing_time,
-        chunks_created,
-        embedded_chunks,
-        confidence_score,
+        span_manager,
     })
 }
 
 /// Chatter avec un document via session temporaire
 #[tauri::command]
+#[tracing::instrument(name = "search", skip_all, fields(session_id = %request.session_id))]
 pub async fn chat_with_dropped_document(
     request: ChatRequest,
+    app: AppHandle,
     state: State<'_, DirectChatState>,
 ) -> Result<ChatResponse, String> {
     let start_time = std::time::Instant::now();
     info!("💬 Chat request for session {}: '{}'", request.session_id, request.query);
 
+    // 0. Historique borné: résolution des questions de suivi ("et le second ?")
+    let config = state.config.read().await.clone();
+    let history = state.manager
+        .recent_history(&request.session_id, config.max_history_tokens)
+        .await
+        .unwrap_or_default();
+    let search_query = contextualize_query(&request.query, &history);
+
     // 1. Recherche sémantique dans la session
     let scored_chunks = state.manager
         .search_in_session(
             &request.session_id,
-            &request.query,
-            request.selection,
+            &search_query,
+            request.selection.clone(),
             request.limit,
+            Some(request.min_score.unwrap_or(config.min_score)),
         )
         .await
         .map_err(|e| format!("Search failed: {}", e))?;
 
     if scored_chunks.is_empty() {
-        warn!("No relevant chunks found for query: {}", request.query);
+        warn!("No chunk above min_score for query: {}", request.query);
         return Ok(ChatResponse {
             response: "Je n'ai pas trouvé d'informations pertinentes pour répondre à votre question dans ce document.".to_string(),
             contributing_spans: vec![],
@@ -255,20 +551,41 @@ pub async fn chat_with_dropped_document(
             search_time_ms: start_time.elapsed().as_millis() as u64,
             chunks_used: 0,
             sources_summary: vec![],
+            generation_method: "heuristic".to_string(),
         });
     }
 
-    // 2. Génération de la réponse contextuelle
-    let response = generate_contextual_response(&scored_chunks, &request.query)?;
-    
-    // 3. Extraction des spans contributeurs
-    let contributing_spans = extract_contributing_spans(&scored_chunks);
+    // 2. Spans contributeurs et résumé des sources (pages citées par la réponse heuristique)
+    let resolved_spans = state.manager
+        .resolve_chunk_spans(&request.session_id, &scored_chunks)
+        .await;
+    let contributing_spans = extract_contributing_spans(&scored_chunks, &resolved_spans);
+    let sources_summary = create_sources_summary(&scored_chunks, &resolved_spans);
+
+    // 3. Génération de la réponse: synthèse LLM si configurée, heuristique sinon
+    let llm_response = match config.llm {
+        Some(llm_config) => synthesize_with_llm(&app, &request, &scored_chunks, &history, llm_config, config.llm_chunk_max_chars).await,
+        None => None,
+    };
+
+    let (response, generation_method) = match llm_response {
+        Some(result) => result,
+        None => (
+            generate_contextual_response(&scored_chunks, &request.query, &sources_summary)?,
+            "heuristic".to_string(),
+        ),
+    };
     
     // 4. Calcul de la confidence globale
     let confidence_score = calculate_response_confidence(&scored_chunks);
-    
-    // 5. Création du résumé des sources
-    let sources_summary = create_sources_summary(&scored_chunks);
+
+    // 6. Mémoriser l'échange pour les questions suivantes
+    if let Err(e) = state.manager
+        .record_turn(&request.session_id, request.query.clone(), response.clone(), sources_summary.clone())
+        .await
+    {
+        warn!("Failed to record conversation turn: {}", e);
+    }
 
     let search_time = start_time.elapsed().as_millis() as u64;
 
@@ -283,11 +600,57 @@ pub async fn chat_with_dropped_document(
         search_time_ms: search_time,
         chunks_used: scored_chunks.len(),
         sources_summary,
+        generation_method,
     })
 }
 
+/// Synthèse LLM avec streaming des tokens vers le frontend.
+/// Retourne None en cas d'échec pour basculer sur la réponse heuristique.
+async fn synthesize_with_llm(
+    app: &AppHandle,
+    request: &ChatRequest,
+    scored_chunks: &[ScoredChunk],
+    history: &[ConversationTurn],
+    llm_config: LlmSynthesisConfig,
+    chunk_max_chars: usize,
+) -> Option<(String, String)> {
+    let synthesizer = match LlmSynthesizer::new(llm_config) {
+        Ok(synthesizer) => synthesizer,
+        Err(e) => {
+            warn!("LLM synthesizer unavailable, using heuristic response: {}", e);
+            return None;
+        }
+    };
+
+    let (formatted_context, _, _) = build_llm_context(scored_chunks, chunk_max_chars);
+    let emit_token = |token: &str, done: bool| {
+        let event = LlmTokenEvent {
+            session_id: request.session_id.clone(),
+            token: token.to_string(),
+            done,
+        };
+        if let Err(e) = app.emit(DIRECT_CHAT_TOKEN_EVENT, event) {
+            debug!("Failed to emit LLM token: {}", e);
+        }
+    };
+
+    let result = synthesizer
+        .synthesize(&request.query, &formatted_context, history, |token| emit_token(token, false))
+        .await;
+    emit_token("", true);
+
+    match result {
+        Ok(answer) => Some((answer, format!("llm:{}", synthesizer.model()))),
+        Err(e) => {
+            warn!("LLM synthesis failed, falling back to heuristic response: {}", e);
+            None
+        }
+    }
+}
+
 /// Sprint 1 Niveau 1: Chat avec contexte formaté pour LLM synthesis
 #[tauri::command]
+#[tracing::instrument(name = "search", skip_all, fields(session_id = %request.session_id))]
 pub async fn chat_with_llm_context(
     request: ChatRequest,
     state: State<'_, DirectChatState>,
@@ -296,14 +659,22 @@ pub async fn chat_with_llm_context(
     info!("🤖 LLM Context Chat - session: {}, query: '{}'",
           request.session_id, request.query);
 
+    let config = state.config.read().await.clone();
+    let history = state.manager
+        .recent_history(&request.session_id, config.max_history_tokens)
+        .await
+        .unwrap_or_default();
+    let search_query = contextualize_query(&request.query, &history);
+
     // 1. Recherche RAG classique (réutilise le pipeline existant)
-    // Fetch top-20 pour avoir un pool élargi, puis reranking + filtres
+    // Pool élargi (candidate_pool_size) avant reranking + filtres
     let scored_chunks = state.manager
         .search_in_session(
             &request.session_id,
-            &request.query,
+            &search_query,
             request.selection,
-            Some(20),  // Pool de 20 chunks avant reranking (élargi pour mieux capturer objectifs stratégiques)
+            Some(config.candidate_pool_size),
+            Some(request.min_score.unwrap_or(config.min_score)),
         )
         .await
         .map_err(|e| format!("Search failed: {}", e))?;
@@ -323,13 +694,22 @@ pub async fn chat_with_llm_context(
     // ========== MODE SIMPLE vs COMPLEXE ==========
     // AUDIT 22 NOV 2024: Test A/B pour valider utilité des composants
     //
-    // MODE SIMPLE (baseline): RAG vanilla → top-10 → LLM
-    // MODE COMPLEXE: RAG → query-aware rerank → filtres 3-pass → top-7 → LLM
+    // MODE SIMPLE (baseline): RAG vanilla → top-k → LLM
+    // MODE COMPLEXE: RAG → query-aware rerank → top-k → LLM
     //
-    // Configuration: Set ENABLE_QUERY_RERANKING=false pour mode SIMPLE
-    const ENABLE_QUERY_RERANKING: bool = false;  // ⚠️ DÉSACTIVÉ pour test baseline
+    // Configuration: DirectChatConfig.enable_query_reranking (set_direct_chat_config)
+    let top_k = config.final_top_k;
+
+    // ========== NUMERICAL RERANKING ==========
+    // Queries avec chiffres/unités ("16x compression", "35000€"): booster les chunks
+    // contenant la valeur sur tout le pool de candidats, avant la coupe au top-k
+    let scored_chunks = if config.enable_numerical_reranking {
+        apply_numerical_boost(&request.query, scored_chunks)
+    } else {
+        scored_chunks
+    };
 
-    let mut scored_chunks = if ENABLE_QUERY_RERANKING {
+    let scored_chunks = if config.enable_query_reranking {
         // MODE COMPLEXE: Query-aware reranking (Sprint 1 Niveau 1.5)
         use crate::rag::search::QueryAwareReranker;
         let reranker = QueryAwareReranker::default();
@@ -355,82 +735,598 @@ pub async fn chat_with_llm_context(
                 sc.score = new_score;
                 sc
             })
-            .take(10)
+            .take(top_k)
             .collect();
 
-        debug!("🔄 MODE COMPLEXE: Query-aware reranking 20 → 10, top: {:.3}",
+        debug!("🔄 MODE COMPLEXE: Query-aware reranking {} → {}, top: {:.3}",
+               config.candidate_pool_size, result.len(),
                result.first().map(|sc| sc.score).unwrap_or(0.0));
         result
     } else {
-        // MODE SIMPLE: Prendre top-10 directement, pas de reranking
-        let result: Vec<ScoredChunk> = scored_chunks.into_iter().take(10).collect();
-        debug!("✅ MODE SIMPLE: Top-10 direct (no reranking), top: {:.3}",
-               result.first().map(|sc| sc.score).unwrap_or(0.0));
+        // MODE SIMPLE: Prendre top-k directement, pas de reranking
+        let result: Vec<ScoredChunk> = scored_chunks.into_iter().take(top_k).collect();
+        debug!("✅ MODE SIMPLE: Top-{} direct (no reranking), top: {:.3}",
+               top_k, result.first().map(|sc| sc.score).unwrap_or(0.0));
         result
     };
 
     // ========== SECTION PRIOR + CONTAMINATION FILTER ==========
     // AUDIT 22 NOV: Section prior simple (~50 lignes) remplace filtres 3-pass (~300 lignes)
-    use crate::rag::search::SectionPriorReranker;
+    let filtered_chunks: Vec<ScoredChunk> = if config.enable_section_prior {
+        use crate::rag::search::SectionPriorReranker;
 
-    let items: Vec<(ScoredChunk, f32)> = scored_chunks
-        .into_iter()
-        .map(|sc| (sc.clone(), sc.score))
-        .collect();
+        let items: Vec<(ScoredChunk, f32)> = scored_chunks
+            .into_iter()
+            .map(|sc| (sc.clone(), sc.score))
+            .collect();
+
+        let reranked = SectionPriorReranker::rerank_and_filter(
+            items,
+            |sc: &ScoredChunk| sc.chunk.content.as_str(),
+            |sc: &ScoredChunk| {
+                use crate::rag::ChunkSource;
+                match sc.chunk.chunk_source {
+                    ChunkSource::FigureCaption => "Figure Caption",
+                    ChunkSource::Table => "Table",
+                    _ => "Document Text",
+                }
+            },
+        );
+
+        reranked
+            .into_iter()
+            .map(|(mut sc, new_score)| {
+                sc.score = new_score;
+                sc
+            })
+            .take(top_k)
+            .collect()
+    } else {
+        scored_chunks.into_iter().take(top_k).collect()
+    };
+
+    debug!("✅ Section Prior ({}): {} chunks (top-{}), top: {:.3}",
+           if config.enable_section_prior { "on" } else { "off" },
+           filtered_chunks.len(), top_k,
+           filtered_chunks.first().map(|sc| sc.score).unwrap_or(0.0));
+
+    if filtered_chunks.is_empty() {
+        warn!("All chunks filtered out by section prior");
+        return Ok(LlmContextResponse {
+            session_id: request.session_id,
+            formatted_context: String::new(),
+            chunks: vec![],
+            query: request.query,
+            search_time_ms: start_time.elapsed().as_millis() as u64,
+            has_ocr_data: false,
+        });
+    }
+
+    // 3. Construction du contexte formaté pour le LLM (historique en tête pour les suivis)
+    let (chunks_context, chunk_infos, has_ocr) = build_llm_context(&filtered_chunks, config.llm_chunk_max_chars);
+    let formatted_context = format!("{}{}", format_history_for_context(&history), chunks_context);
+
+    let search_time = start_time.elapsed().as_millis() as u64;
+
+    info!("✅ Built LLM context from {} chunks in {}ms (OCR: {})",
+          chunk_infos.len(), search_time, has_ocr);
+
+    Ok(LlmContextResponse {
+        session_id: request.session_id,
+        formatted_context,
+        chunks: chunk_infos,
+        query: request.query,
+        search_time_ms: search_time,
+        has_ocr_data: has_ocr,
+    })
+}
+
+/// Obtenir la configuration du pipeline de retrieval
+#[tauri::command]
+pub async fn get_direct_chat_config(
+    state: State<'_, DirectChatState>,
+) -> Result<DirectChatConfig, String> {
+    Ok(state.config.read().await.clone())
+}
+
+/// Mettre à jour la configuration du pipeline de retrieval (A/B tests sans rebuild)
+#[tauri::command]
+pub async fn set_direct_chat_config(
+    mut config: DirectChatConfig,
+    state: State<'_, DirectChatState>,
+) -> Result<DirectChatConfig, String> {
+    config.validate()?;
+
+    let mut current = state.config.write().await;
+    // La clé n'est jamais renvoyée par `get_direct_chat_config` : sans nouvelle clé, la précédente est conservée
+    if let (Some(llm), Some(previous)) = (config.llm.as_mut(), current.llm.as_ref()) {
+        if llm.api_key.is_none() {
+            llm.api_key = previous.api_key.clone();
+        }
+    }
+    info!("⚙️ Direct chat config updated: {:?}", config);
+
+    *current = config.clone();
+    Ok(config)
+}
+
+/// Historique de conversation d'une session
+#[tauri::command]
+pub async fn get_session_history(
+    session_id: String,
+    state: State<'_, DirectChatState>,
+) -> Result<Vec<ConversationTurn>, String> {
+    state.manager
+        .get_history(&session_id)
+        .await
+        .map_err(|e| format!("History retrieval failed: {}", e))
+}
+
+/// Vider l'historique de conversation d'une session
+#[tauri::command]
+pub async fn clear_session_history(
+    session_id: String,
+    state: State<'_, DirectChatState>,
+) -> Result<(), String> {
+    state.manager
+        .clear_history(&session_id)
+        .await
+        .map_err(|e| format!("History cleanup failed: {}", e))
+}
+
+/// Enregistrer la réponse générée côté frontend à partir de `chat_with_llm_context`
+#[tauri::command]
+pub async fn record_session_turn(
+    session_id: String,
+    query: String,
+    response: String,
+    sources: Option<Vec<SourceSummary>>,
+    state: State<'_, DirectChatState>,
+) -> Result<(), String> {
+    state.manager
+        .record_turn(&session_id, query, response, sources.unwrap_or_default())
+        .await
+        .map_err(|e| format!("Failed to record turn: {}", e))
+}
+
+/// Session exportée (contenu prêt à être enregistré par le frontend)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSession {
+    pub format: ExportFormat,
+    pub file_name: String,
+    pub content: String,
+}
+
+/// Exporter l'historique d'une session (questions, réponses, sources citées) en Markdown ou JSON
+#[tauri::command]
+pub async fn export_direct_chat_session(
+    session_id: String,
+    format: ExportFormat,
+    state: State<'_, DirectChatState>,
+) -> Result<ExportedSession, String> {
+    info!("📤 Exporting session {} as {:?}", session_id, format);
+
+    let session = state.manager
+        .get_session(&session_id)
+        .await
+        .map_err(|e| format!("Session not found: {}", e))?;
+
+    let bundle = SessionExportBundle::from_session(&session);
+    let content = bundle
+        .render(format)
+        .map_err(|e| format!("Export serialization failed: {}", e))?;
+
+    Ok(ExportedSession {
+        format,
+        file_name: bundle.file_name(format),
+        content,
+    })
+}
+
+/// Demande d'explication : recherche dans une session de chat direct, sinon dans un groupe RAG
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainAnswerRequest {
+    pub query: String,
+    pub session_id: Option<String>,
+    pub group_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Métadonnée de span indiquant la provenance de ses offsets
+const OFFSETS_METADATA_KEY: &str = "offsets";
+
+/// Offsets inconnus : `char_start`/`char_end` du span ne sont pas significatifs
+const OFFSETS_UNKNOWN: &str = "unknown";
+
+/// Zone à surligner dans le viewer pour un span
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpanHighlight {
+    pub span_id: String,
+    pub page: Option<usize>,
+    pub bbox: crate::rag::SourceBoundingBox,
+    /// Offsets (caractères) dans le document, `None` si inconnus
+    pub char_start: Option<usize>,
+    pub char_end: Option<usize>,
+}
+
+/// Chunk ayant contribué à la réponse, ramené à ses spans source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkExplanation {
+    pub chunk_id: String,
+    pub source_document: Option<String>,
+    pub score: f32,
+    pub extraction_method: crate::rag::ExtractionMethod,
+    pub confidence: f32,
+    pub report: crate::rag::ExplainabilityReport,
+    pub highlights: Vec<SpanHighlight>,
+}
+
+/// Explication d'une réponse : chunks contributeurs et trace lisible
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerExplanation {
+    pub query: String,
+    pub chunks: Vec<ChunkExplanation>,
+    pub trace: String,
+    pub processing_time_ms: u64,
+}
 
-    let reranked = SectionPriorReranker::rerank_and_filter(
-        items,
-        |sc: &ScoredChunk| sc.chunk.content.as_str(),
-        |sc: &ScoredChunk| {
-            use crate::rag::ChunkSource;
-            match sc.chunk.chunk_source {
-                ChunkSource::FigureCaption => "Figure Caption",
-                ChunkSource::Table => "Table",
-                _ => "Document Text",
+/// Expliquer pourquoi une réponse cite ses sources : chaque chunk contributeur est ramené
+/// à ses spans (offsets, méthode d'extraction, confidence, bbox pour le viewer)
+#[tauri::command]
+pub async fn explain_answer(
+    request: ExplainAnswerRequest,
+    state: State<'_, DirectChatState>,
+    rag_state: State<'_, crate::rag::RagState>,
+) -> Result<AnswerExplanation, String> {
+    let start_time = std::time::Instant::now();
+
+    let chunks = match (&request.session_id, &request.group_id) {
+        (Some(session_id), _) => {
+            info!("🔍 Explaining answer for session {}: '{}'", session_id, request.query);
+            explain_session_chunks(&state, session_id, &request.query, request.limit).await?
+        }
+        (None, Some(group_id)) => {
+            info!("🔍 Explaining answer for group {}: '{}'", group_id, request.query);
+            explain_group_chunks(rag_state, group_id, &request.query, request.limit).await?
+        }
+        (None, None) => return Err("Either session_id or group_id is required".to_string()),
+    };
+
+    let trace = chunks.iter()
+        .enumerate()
+        .map(|(idx, chunk)| format!("[Source {}] chunk {} (score {:.2})\n{}",
+                                    idx + 1, chunk.chunk_id, chunk.score, chunk.report.trace()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    info!("✅ Explained {} contributing chunks ({} spans)",
+          chunks.len(), chunks.iter().map(|c| c.report.contributing_spans.len()).sum::<usize>());
+
+    Ok(AnswerExplanation {
+        query: request.query,
+        chunks,
+        trace,
+        processing_time_ms: start_time.elapsed().as_millis() as u64,
+    })
+}
+
+/// Chunks d'une session : spans réels référencés à l'ingestion
+async fn explain_session_chunks(
+    state: &DirectChatState,
+    session_id: &str,
+    query: &str,
+    limit: Option<usize>,
+) -> Result<Vec<ChunkExplanation>, String> {
+    let scored_chunks = state.manager
+        .search_in_session(session_id, query, None, limit, None)
+        .await
+        .map_err(|e| format!("Search failed: {}", e))?;
+    let resolved_spans = state.manager
+        .resolve_chunk_spans(session_id, &scored_chunks)
+        .await;
+
+    Ok(scored_chunks.iter().map(|scored| {
+        let spans = resolved_spans.get(&scored.chunk.id).cloned().unwrap_or_default();
+        let document_id = spans.first()
+            .map(|span| span.document_id.clone())
+            .or_else(|| scored.source_document.clone())
+            .unwrap_or_else(|| session_id.to_string());
+
+        let mut span_manager = crate::rag::SourceSpanManager::new();
+        let span_ids: Vec<String> = spans.into_iter()
+            .filter_map(|span| {
+                let span_id = span.span_id.clone();
+                span_manager.add_span(span).ok().map(|_| span_id)
+            })
+            .collect();
+
+        chunk_explanation(
+            scored.chunk.id.clone(),
+            scored.source_document.clone(),
+            scored.score,
+            scored.chunk.metadata.extraction_method.clone(),
+            scored.chunk.metadata.confidence,
+            span_manager.generate_report_for_spans(&scored.chunk.content, &document_id, &span_ids),
+        )
+    }).collect())
+}
+
+/// Résultats d'un groupe RAG : un span par chunk (offsets, méthode d'extraction, lignes et bbox du payload).
+/// Offsets absents du payload (points indexés avant ce champ) : span marqué `offsets: unknown`, sans surlignage de texte.
+async fn explain_group_chunks(
+    rag_state: State<'_, crate::rag::RagState>,
+    group_id: &str,
+    query: &str,
+    limit: Option<usize>,
+) -> Result<Vec<ChunkExplanation>, String> {
+    let params = crate::rag::commands::AdvancedSearchParams {
+        query: query.to_string(),
+        group_id: group_id.to_string(),
+        limit,
+        min_score: None,
+        document_categories: None,
+        source_types: None,
+        min_ocr_confidence: None,
+        include_business_metadata: false,
+        fiscal_year_filter: None,
+        merge_overlapping: false,
+        max_merged_chars: None,
+        tags: None,
+        priorities: None,
+        author: None,
+        project: None,
+        cross_lingual: false,
+        semantic_cache: false,
+        numeric_filter: None,
+    };
+    let search_response = crate::rag::commands::search_with_metadata(params, rag_state)
+        .await
+        .map_err(|e| e.message().to_string())?;
+
+    Ok(search_response.results.into_iter().map(|result| {
+        let confidence = result.ocr_confidence.unwrap_or(result.chunk_metadata.confidence);
+        let mut span_manager = crate::rag::SourceSpanManager::new();
+        let mut span_ids = Vec::new();
+
+        if !result.content.is_empty() {
+            let (char_start, char_end) = result.char_range.unwrap_or_default();
+            let mut span = SourceSpan::new(
+                result.document_id.clone(),
+                PathBuf::from(result.source_file.clone().unwrap_or_default()),
+                char_start,
+                char_end,
+                result.content.clone(),
+                result.extraction_method.clone(),
+            )
+            .with_metadata(OFFSETS_METADATA_KEY.to_string(), serde_json::json!(if result.char_range.is_some() { "document" } else { OFFSETS_UNKNOWN }));
+            span.span_id = format!("{}_{}", span.span_id, result.chunk_id);
+            span.line_start = result.chunk_metadata.start_line;
+            span.line_end = result.chunk_metadata.end_line;
+            span.bbox = result.bbox.clone();
+            span.extraction_metadata.confidence = confidence;
+
+            let span_id = span.span_id.clone();
+            if span_manager.add_span(span).is_ok() {
+                span_ids.push(span_id);
             }
-        },
-    );
+        }
+
+        chunk_explanation(
+            result.chunk_id.clone(),
+            result.source_file.clone(),
+            result.score,
+            result.extraction_method.clone(),
+            confidence,
+            span_manager.generate_report_for_spans(&result.content, &result.document_id, &span_ids),
+        )
+    }).collect())
+}
+
+fn chunk_explanation(
+    chunk_id: String,
+    source_document: Option<String>,
+    score: f32,
+    extraction_method: crate::rag::ExtractionMethod,
+    confidence: f32,
+    report: crate::rag::ExplainabilityReport,
+) -> ChunkExplanation {
+    let highlights = report.contributing_spans.iter()
+        .filter_map(|span| span.bbox.as_ref().map(|bbox| {
+            let offsets_known = span.extraction_metadata.method_specific.get(OFFSETS_METADATA_KEY)
+                != Some(&serde_json::json!(OFFSETS_UNKNOWN));
+            SpanHighlight {
+                span_id: span.span_id.clone(),
+                page: bbox.page,
+                bbox: bbox.clone(),
+                char_start: offsets_known.then_some(span.char_start),
+                char_end: offsets_known.then_some(span.char_end),
+            }
+        }))
+        .collect();
+
+    ChunkExplanation {
+        chunk_id,
+        source_document,
+        score,
+        extraction_method,
+        confidence,
+        report,
+        highlights,
+    }
+}
+
+/// Nombre de résultats par défaut de `search_everything`
+const DEFAULT_UNIFIED_SEARCH_LIMIT: usize = 10;
+
+/// Poids des résultats de la base persistante : le document déposé reste le focus,
+/// la base de connaissances sert de contexte complémentaire
+const GROUP_RESULT_WEIGHT: f32 = 0.9;
+
+/// Résultat de recherche unifiée, tagué par origine (`session:<id>` ou `group:<id>`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedSearchResult {
+    pub origin: String,
+    pub chunk_id: String,
+    pub document_id: Option<String>,
+    pub source_file: Option<String>,
+    pub content: String,
+    /// Score de fusion : cosinus brut pondéré par origine, comparable entre origines
+    pub score: f32,
+    /// Score de classement de l'origine (hybride session ou cosinus/BM25/priorité du groupe)
+    pub origin_score: f32,
+    /// Similarité cosinus brute entre la requête et le chunk (même embedder pour toutes les origines)
+    pub cosine_score: f32,
+}
 
-    let filtered_chunks: Vec<ScoredChunk> = reranked
-        .into_iter()
-        .map(|(mut sc, new_score)| {
-            sc.score = new_score;
-            sc
-        })
-        .take(10)  // TEST A/B: Temporarily back to top-10 to debug "16x compressor" recall issue
-        .collect();
+/// Réponse de `search_everything`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedSearchResponse {
+    pub query: String,
+    pub results: Vec<UnifiedSearchResult>,
+    pub sessions_searched: Vec<String>,
+    pub groups_searched: Vec<String>,
+    /// Doublons de contenu retirés entre origines
+    pub duplicates_removed: usize,
+    pub search_time_ms: u64,
+}
 
-    debug!("✅ Section Prior: {} chunks (top-10 TEST), top: {:.3}",
-           filtered_chunks.len(), filtered_chunks.first().map(|sc| sc.score).unwrap_or(0.0));
+/// Rechercher à la fois dans les sessions DirectChat actives et dans les groupes RAG persistants.
+/// `include_sessions`/`include_groups` : `None` → toutes les sessions non expirées / tous les groupes,
+/// liste vide → origine ignorée. Une origine en erreur est ignorée (warn) sans faire échouer la recherche.
+#[tauri::command]
+pub async fn search_everything(
+    query: String,
+    include_sessions: Option<Vec<String>>,
+    include_groups: Option<Vec<String>>,
+    limit: Option<usize>,
+    state: State<'_, DirectChatState>,
+    rag_state: State<'_, crate::rag::RagState>,
+) -> Result<UnifiedSearchResponse, String> {
+    let start_time = std::time::Instant::now();
+    let limit = limit.unwrap_or(DEFAULT_UNIFIED_SEARCH_LIMIT);
 
-    if filtered_chunks.is_empty() {
-        warn!("All chunks filtered out by section prior");
-        return Ok(LlmContextResponse {
-            session_id: request.session_id,
-            formatted_context: String::new(),
-            chunks: vec![],
-            query: request.query,
-            search_time_ms: start_time.elapsed().as_millis() as u64,
-            has_ocr_data: false,
-        });
+    let sessions_searched = match include_sessions {
+        Some(session_ids) => session_ids,
+        None => state.manager.list_sessions().await
+            .into_iter()
+            .filter(|session| !session.is_expired)
+            .map(|session| session.session_id)
+            .collect(),
+    };
+    let groups_searched = match include_groups {
+        Some(group_ids) => group_ids,
+        None => rag_state.groups.read().await.keys().cloned().collect(),
+    };
+    info!("🔎 Unified search '{}': {} sessions, {} groups", query, sessions_searched.len(), groups_searched.len());
+
+    let mut candidates = Vec::new();
+
+    for session_id in &sessions_searched {
+        match state.manager.search_in_session(session_id, &query, None, Some(limit), None).await {
+            Ok(scored_chunks) => {
+                let results = scored_chunks.into_iter().map(|scored| UnifiedSearchResult {
+                    origin: format!("session:{}", session_id),
+                    chunk_id: scored.chunk.id,
+                    document_id: None,
+                    source_file: scored.source_document,
+                    content: scored.chunk.content,
+                    score: scored.score,
+                    origin_score: scored.score,
+                    cosine_score: scored.dense_score,
+                }).collect();
+                candidates.push((results, 1.0));
+            }
+            Err(e) => warn!("⚠️ Unified search skipped session {}: {}", session_id, e),
+        }
     }
 
-    // 3. Construction du contexte formaté pour le LLM
-    let (formatted_context, chunk_infos, has_ocr) = build_llm_context(&filtered_chunks);
+    for group_id in &groups_searched {
+        let params = crate::rag::commands::AdvancedSearchParams {
+            query: query.clone(),
+            group_id: group_id.clone(),
+            limit: Some(limit),
+            min_score: None,
+            document_categories: None,
+            source_types: None,
+            min_ocr_confidence: None,
+            include_business_metadata: false,
+            fiscal_year_filter: None,
+            merge_overlapping: false,
+            max_merged_chars: None,
+            tags: None,
+            priorities: None,
+            author: None,
+            project: None,
+            cross_lingual: false,
+            semantic_cache: false,
+            numeric_filter: None,
+        };
+        match crate::rag::commands::search_with_metadata(params, rag_state.clone()).await {
+            Ok(response) => {
+                let results = response.results.into_iter().map(|result| UnifiedSearchResult {
+                    origin: format!("group:{}", group_id),
+                    chunk_id: result.chunk_id,
+                    document_id: Some(result.document_id),
+                    source_file: result.source_file,
+                    content: result.content,
+                    score: result.score,
+                    origin_score: result.score,
+                    cosine_score: result.cosine_score,
+                }).collect();
+                candidates.push((results, GROUP_RESULT_WEIGHT));
+            }
+            Err(e) => warn!("⚠️ Unified search skipped group {}: {}", group_id, e.message()),
+        }
+    }
 
-    let search_time = start_time.elapsed().as_millis() as u64;
+    let (results, duplicates_removed) = merge_unified_results(candidates, limit);
+    let search_time_ms = start_time.elapsed().as_millis() as u64;
+    info!("✅ Unified search: {} results ({} duplicates removed) in {}ms", results.len(), duplicates_removed, search_time_ms);
+
+    Ok(UnifiedSearchResponse {
+        query,
+        results,
+        sessions_searched,
+        groups_searched,
+        duplicates_removed,
+        search_time_ms,
+    })
+}
 
-    info!("✅ Built LLM context from {} chunks in {}ms (OCR: {})",
-          chunk_infos.len(), search_time, has_ocr);
+/// Fusionner les résultats de plusieurs origines sur le cosinus brut pondéré : les scores hybrides
+/// de chaque origine ne sont pas sur la même échelle, et une normalisation par origine hisserait
+/// le meilleur résultat d'une origine peu pertinente au même rang que celui d'une origine pertinente.
+/// Doublons (hash du contenu normalisé) réduits au meilleur score.
+/// Retourne les `limit` meilleurs résultats et le nombre de doublons retirés.
+fn merge_unified_results(candidates: Vec<(Vec<UnifiedSearchResult>, f32)>, limit: usize) -> (Vec<UnifiedSearchResult>, usize) {
+    let mut by_hash: std::collections::HashMap<String, UnifiedSearchResult> = std::collections::HashMap::new();
+    let mut duplicates_removed = 0;
+
+    for (results, weight) in candidates {
+        for mut result in results {
+            result.score = result.cosine_score * weight;
+
+            let normalized = result.content.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+            let content_hash = blake3::hash(normalized.as_bytes()).to_hex().to_string();
+            match by_hash.get(&content_hash) {
+                Some(existing) => {
+                    duplicates_removed += 1;
+                    if result.score > existing.score {
+                        by_hash.insert(content_hash, result);
+                    }
+                }
+                None => {
+                    by_hash.insert(content_hash, result);
+                }
+            }
+        }
+    }
 
-    Ok(LlmContextResponse {
-        session_id: request.session_id,
-        formatted_context,
-        chunks: chunk_infos,
-        query: request.query,
-        search_time_ms: search_time,
-        has_ocr_data: has_ocr,
-    })
+    let mut merged: Vec<UnifiedSearchResult> = by_hash.into_values().collect();
+    merged.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+        .then_with(|| a.origin.cmp(&b.origin)));
+    merged.truncate(limit);
+    (merged, duplicates_removed)
 }
 
 /// Obtenir informations sur session temporaire
@@ -455,17 +1351,22 @@ pub async fn cleanup_direct_chat_session(
 ) -> Result<(), String> {
     info!("🗑️ Cleaning up direct chat session: {}", session_id);
 
-    // 1. Récupérer la session avant suppression pour obtenir le path du fichier
+    // 1. Récupérer la session avant suppression pour obtenir les paths des fichiers
     if let Ok(session) = state.manager.get_session(&session_id).await {
-        let temp_path = &session.document_path;
+        let mut temp_paths: Vec<&PathBuf> = session.documents.iter().map(|doc| &doc.document_path).collect();
+        if !temp_paths.contains(&&session.document_path) {
+            temp_paths.push(&session.document_path);
+        }
 
-        // 2. Supprimer le fichier temporaire si c'est un PDF
-        if temp_path.exists() && temp_path.to_string_lossy().contains("gravis_temp_") {
-            info!("🗑️ Removing temporary file: {:?}", temp_path);
-            if let Err(e) = std::fs::remove_file(temp_path) {
-                warn!("Failed to remove temp file {:?}: {}", temp_path, e);
-            } else {
-                info!("✅ Temporary file removed successfully");
+        // 2. Supprimer les fichiers temporaires de tous les documents de la session
+        for temp_path in temp_paths {
+            if temp_path.exists() && temp_path.to_string_lossy().contains("gravis_temp_") {
+                info!("🗑️ Removing temporary file: {:?}", temp_path);
+                if let Err(e) = std::fs::remove_file(temp_path) {
+                    warn!("Failed to remove temp file {:?}: {}", temp_path, e);
+                } else {
+                    info!("✅ Temporary file removed successfully");
+                }
             }
         }
     }
@@ -650,56 +1551,6 @@ fn detect_figure_content(lines: &[&str], start_idx: usize) -> Option<usize> {
     }
 }
 
-/// Détecter contenu tabulaire avec colonnes alignées
-fn detect_table_content(lines: &[&str], start_idx: usize) -> Option<usize> {
-    let mut lines_consumed = 0;
-    let mut consistent_columns = 0;
-    let mut total_potential_rows = 0;
-
-    for i in start_idx..std::cmp::min(start_idx + 8, lines.len()) {
-        let line = lines[i].trim();
-        
-        if line.is_empty() {
-            lines_consumed += 1;
-            continue;
-        }
-
-        // Détecter séparateurs tabulaires
-        let separators = ["\t", "  ", " | ", "|"];
-        let mut max_columns = 0;
-        
-        for sep in &separators {
-            let columns = line.split(sep).filter(|s| !s.trim().is_empty()).count();
-            max_columns = max_columns.max(columns);
-        }
-
-        if max_columns >= 2 {
-            if consistent_columns == 0 {
-                consistent_columns = max_columns;
-            }
-            
-            // Vérifier cohérence des colonnes
-            if max_columns >= consistent_columns / 2 {
-                total_potential_rows += 1;
-            }
-        }
-
-        lines_consumed += 1;
-
-        // Stop si trop de lignes sans structure tabulaire
-        if total_potential_rows == 0 && lines_consumed > 3 {
-            break;
-        }
-    }
-
-    // Au moins 2 lignes avec structure cohérente = table
-    if total_potential_rows >= 2 {
-        Some(lines_consumed)
-    } else {
-        None
-    }
-}
-
 /// Détecter table des matières ou listes numérotées
 fn detect_toc_or_list(lines: &[&str], start_idx: usize) -> Option<usize> {
     let mut lines_consumed = 0;
@@ -850,62 +1701,6 @@ fn structure_figure_content(lines: &[&str]) -> String {
     structured
 }
 
-/// Structurer le contenu tabulaire
-fn structure_table_content(lines: &[&str]) -> String {
-    let mut structured = String::new();
-    structured.push_str("📋 **Table Data**\n\n");
-
-    let mut is_first_row = true;
-
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() { continue; }
-
-        // Essayer différents séparateurs
-        let separators = ["\t", "  ", " | ", "|"];
-        let mut best_columns = Vec::new();
-        let mut max_columns = 0;
-
-        for sep in &separators {
-            let columns: Vec<&str> = line.split(sep)
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty())
-                .collect();
-            
-            if columns.len() > max_columns {
-                max_columns = columns.len();
-                best_columns = columns;
-            }
-        }
-
-        if best_columns.len() >= 2 {
-            // Format en table markdown
-            if is_first_row {
-                structured.push_str("| ");
-                structured.push_str(&best_columns.join(" | "));
-                structured.push_str(" |\n");
-                
-                // Ligne de séparation
-                structured.push_str("|");
-                for _ in 0..best_columns.len() {
-                    structured.push_str("---|");
-                }
-                structured.push('\n');
-                is_first_row = false;
-            } else {
-                structured.push_str("| ");
-                structured.push_str(&best_columns.join(" | "));
-                structured.push_str(" |\n");
-            }
-        } else {
-            // Ligne simple si pas assez de colonnes
-            structured.push_str(&format!("• {}\n", line));
-        }
-    }
-
-    structured
-}
-
 /// Structurer les listes et tables des matières
 fn structure_list_content(lines: &[&str]) -> String {
     let mut structured = String::new();
@@ -1494,10 +2289,12 @@ fn determine_document_type(document: &crate::rag::GroupDocument) -> DocumentType
 }
 
 /// Générer réponse contextuelle à partir des chunks pertinents - VERSION AMÉLIORÉE PR #4
-/// Synthétise l'information au lieu de lister les chunks bruts
+/// Synthétise l'information au lieu de lister les chunks bruts, avec citations inline ("[p.3]", "[Figure 2]")
+/// et la liste des sources citées en fin de réponse
 fn generate_contextual_response(
     scored_chunks: &[ScoredChunk],
     query: &str,
+    sources: &[SourceSummary],
 ) -> Result<String, String> {
     if scored_chunks.is_empty() {
         return Ok("Aucune information pertinente trouvée.".to_string());
@@ -1525,6 +2322,7 @@ fn generate_contextual_response(
 
     // Construire une réponse synthétisée
     let mut response = String::new();
+    let mut citations = ResponseCitations::new(sources);
 
     if is_summary_request {
         // Pour les demandes de résumé : synthèse narrative
@@ -1542,13 +2340,18 @@ fn generate_contextual_response(
 
         if !key_points.is_empty() {
             for (idx, point) in key_points.iter().enumerate() {
-                response.push_str(&format!("{}. {}\n\n", idx + 1, point.trim()));
+                // Citer le chunk dont provient la phrase
+                let citation = top_chunks.iter()
+                    .find(|sc| sc.chunk.content.contains(point.trim()))
+                    .map(|sc| citations.cite(&sc.chunk.id))
+                    .unwrap_or_default();
+                response.push_str(&format!("{}. {}{}\n\n", idx + 1, point.trim(), citation));
             }
         } else {
             // Fallback : utiliser les chunks directement mais de manière condensée
-            for (idx, chunk) in top_chunks.iter().take(3).enumerate() {
+            for chunk in top_chunks.iter().take(3) {
                 let preview = condense_text(&chunk.chunk.content, 200);
-                response.push_str(&format!("• {}\n\n", preview));
+                response.push_str(&format!("• {}{}\n\n", preview, citations.cite(&chunk.chunk.id)));
             }
         }
 
@@ -1559,7 +2362,7 @@ fn generate_contextual_response(
         // Prendre le chunk le plus pertinent comme réponse principale
         if let Some(best_chunk) = top_chunks.first() {
             let main_content = condense_text(&best_chunk.chunk.content, 400);
-            response.push_str(&format!("{}\n\n", main_content));
+            response.push_str(&format!("{}{}\n\n", main_content, citations.cite(&best_chunk.chunk.id)));
         }
 
         // Ajouter contexte additionnel si pertinent
@@ -1567,7 +2370,7 @@ fn generate_contextual_response(
             response.push_str("**Informations complémentaires :**\n\n");
             for chunk in top_chunks.iter().skip(1).take(2) {
                 let additional = condense_text(&chunk.chunk.content, 200);
-                response.push_str(&format!("• {}\n\n", additional));
+                response.push_str(&format!("• {}{}\n\n", additional, citations.cite(&chunk.chunk.id)));
             }
         }
 
@@ -1577,7 +2380,7 @@ fn generate_contextual_response(
 
         for (idx, chunk) in top_chunks.iter().enumerate() {
             let item = condense_text(&chunk.chunk.content, 250);
-            response.push_str(&format!("{}. {}\n\n", idx + 1, item));
+            response.push_str(&format!("{}. {}{}\n\n", idx + 1, item, citations.cite(&chunk.chunk.id)));
         }
 
     } else {
@@ -1587,7 +2390,7 @@ fn generate_contextual_response(
         // Chunk principal
         if let Some(best_chunk) = top_chunks.first() {
             let main_answer = condense_text(&best_chunk.chunk.content, 350);
-            response.push_str(&format!("{}\n\n", main_answer));
+            response.push_str(&format!("{}{}\n\n", main_answer, citations.cite(&best_chunk.chunk.id)));
         }
 
         // Informations additionnelles si score > 0.5
@@ -1601,7 +2404,7 @@ fn generate_contextual_response(
             response.push_str("**Détails supplémentaires :**\n\n");
             for chunk in highly_relevant {
                 let detail = condense_text(&chunk.chunk.content, 200);
-                response.push_str(&format!("• {}\n\n", detail));
+                response.push_str(&format!("• {}{}\n\n", detail, citations.cite(&chunk.chunk.id)));
             }
         }
     }
@@ -1616,59 +2419,202 @@ fn generate_contextual_response(
         "modérée"
     };
 
+    response.push_str(&citations.render_sources());
     response.push_str(&format!("\n*Réponse générée à partir de {} sections du document (confiance: {})*",
                               top_chunks.len(), confidence_level));
 
     Ok(response)
 }
 
-/// Extraire les phrases clés d'un texte (phrases complètes)
-fn extract_key_sentences(text: &str, max_sentences: usize) -> Vec<String> {
-    let sentences: Vec<String> = text
-        .split(|c| c == '.' || c == '!' || c == '?')
-        .filter(|s| !s.trim().is_empty() && s.len() > 30) // Filtrer phrases trop courtes
-        .take(max_sentences)
-        .map(|s| s.trim().to_string())
-        .collect();
-
-    sentences
+/// Sources citées par une réponse heuristique, dans l'ordre de première citation
+struct ResponseCitations<'a> {
+    sources: &'a [SourceSummary],
+    cited: Vec<&'a SourceSummary>,
 }
 
-/// Condenser un texte à une longueur maximale en préservant les phrases complètes
-fn condense_text(text: &str, max_chars: usize) -> String {
-    let trimmed = text.trim();
+impl<'a> ResponseCitations<'a> {
+    fn new(sources: &'a [SourceSummary]) -> Self {
+        Self { sources, cited: Vec::new() }
+    }
+
+    /// Citation inline (" [p.3]") du chunk, vide si sa page est inconnue
+    fn cite(&mut self, chunk_id: &str) -> String {
+        let Some(source) = self.sources.iter().find(|s| s.chunk_id == chunk_id) else {
+            return String::new();
+        };
+        if !self.cited.iter().any(|cited| cited.chunk_id == chunk_id) {
+            self.cited.push(source);
+        }
+        source.citation().map(|citation| format!(" {}", citation)).unwrap_or_default()
+    }
+
+    /// Liste des sources citées (vide si aucune)
+    fn render_sources(&self) -> String {
+        if self.cited.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from("**Sources :**\n");
+        for source in &self.cited {
+            let document = source.source_document.as_deref().unwrap_or("document");
+            match source.citation() {
+                Some(citation) => out.push_str(&format!("- {} {} — pertinence {:.0}%\n", citation, document, source.score * 100.0)),
+                None => out.push_str(&format!("- {} — pertinence {:.0}%\n", document, source.score * 100.0)),
+            }
+        }
+        out
+    }
+}
 
-    if trimmed.len() <= max_chars {
-        return trimmed.to_string();
+/// Construire les source spans d'une session à partir des blocs OCR natifs (bbox réelles)
+/// et référencer leurs IDs dans les chunks dont la plage de texte chevauche celle du bloc.
+/// Offsets des spans en caractères, comme ceux des chunks (`locate_chunks`).
+/// Les blocs synthétiques (positions estimées) ne produisent aucun span.
+fn build_session_spans(
+    document: &crate::rag::GroupDocument,
+    ocr_content: &mut OCRContent,
+    chunks: &mut [crate::rag::EnrichedChunk],
+) -> crate::rag::SourceSpanManager {
+    use crate::rag::{SourceSpanManager, SourceBoundingBox};
+
+    let mut span_manager = SourceSpanManager::new();
+    if !document.metadata.custom_fields.contains_key("ocr_blocks") {
+        debug!("No native OCR blocks, chunks will use synthetic spans");
+        return span_manager;
     }
 
-    // Trouver la dernière phrase complète avant max_chars
-    let truncated = &trimmed[..max_chars];
+    // Chunks localisés, dans l'ordre du texte : (index du chunk, plage en caractères)
+    let located_chunks: Vec<(usize, (usize, usize))> = crate::rag::locate_chunks(&document.content, chunks)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, range)| range.map(|range| (index, range)))
+        .collect();
+
+    let mut cursor = 0;
+    let mut position = TextPosition::new(&document.content);
+    for page in &mut ocr_content.pages {
+        for (block_idx, block) in page.blocks.iter_mut().enumerate() {
+            let text = block.content.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            // Offset (octets) dans le texte du document : recherche séquentielle, puis globale
+            let found = document.content.get(cursor..)
+                .and_then(|rest| rest.find(text).map(|pos| cursor + pos))
+                .or_else(|| document.content.find(text));
+            let (char_start, line_start) = match found {
+                Some(start) => {
+                    cursor = start + text.len();
+                    position.advance_to(start)
+                }
+                None => (0, 0),
+            };
+            let offset_resolved = found.is_some();
+            let char_end = char_start + text.chars().count();
+
+            let mut span = SourceSpan::new(
+                document.id.clone(),
+                document.file_path.clone(),
+                char_start,
+                char_end,
+                text.to_string(),
+                crate::rag::ExtractionMethod::PdfNative,
+            )
+            .with_bbox(SourceBoundingBox::pdf_points(
+                block.page_number as usize,
+                block.bounding_box.x as f32,
+                block.bounding_box.y as f32,
+                block.bounding_box.width as f32,
+                block.bounding_box.height as f32,
+            ))
+            .with_metadata("block_type".to_string(), serde_json::json!(format!("{:?}", block.block_type)))
+            .with_metadata("offset_resolved".to_string(), serde_json::Value::Bool(offset_resolved));
+            span.span_id = format!("{}_p{}_b{}", span.span_id, block.page_number, block_idx);
+            span.extraction_metadata.confidence = block.confidence as f32;
+            if offset_resolved {
+                span.line_start = line_start;
+                span.line_end = line_start + text.matches('\n').count();
+            }
+
+            let span_id = span.span_id.clone();
+            if let Err(e) = span_manager.add_span(span) {
+                debug!("Skipping invalid span for block {}: {}", block_idx, e);
+                continue;
+            }
+            block.spans.push(span_id.clone());
 
-    // Chercher le dernier point/virgule/espace pour couper proprement
-    if let Some(last_period) = truncated.rfind('.') {
-        if last_period > max_chars / 2 { // Garder au moins 50% du texte
-            return format!("{}.", &trimmed[..last_period]);
+            // Référencer le span dans les chunks dont la plage chevauche celle du bloc
+            if offset_resolved {
+                for &(chunk_index, _) in overlapping_chunks(&located_chunks, (char_start, char_end)) {
+                    chunks[chunk_index].source_spans.get_or_insert_with(Vec::new).push(span_id.clone());
+                }
+            }
         }
     }
 
-    // Sinon, chercher le dernier espace
-    if let Some(last_space) = truncated.rfind(' ') {
-        return format!("{}...", &trimmed[..last_space]);
+    let stats = span_manager.get_stats();
+    info!("📍 Built {} source spans ({} with bbox) for {} chunks",
+          stats.total_spans, stats.spans_with_bbox,
+          chunks.iter().filter(|c| c.source_spans.is_some()).count());
+
+    span_manager
+}
+
+/// Chunks localisés dont la plage chevauche `range` (recherche dichotomique : chunks
+/// séquentiels, débuts et fins croissants)
+fn overlapping_chunks(
+    located_chunks: &[(usize, (usize, usize))],
+    (start, end): (usize, usize),
+) -> &[(usize, (usize, usize))] {
+    let first = located_chunks.partition_point(|(_, (_, chunk_end))| *chunk_end <= start);
+    let last = located_chunks.partition_point(|(_, (chunk_start, _))| *chunk_start < end);
+    &located_chunks[first..last.max(first)]
+}
+
+/// Conversion incrémentale d'un offset en octets vers (caractère, ligne 1-indexée)
+struct TextPosition<'a> {
+    text: &'a str,
+    byte: usize,
+    char: usize,
+    line: usize,
+}
+
+impl<'a> TextPosition<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, byte: 0, char: 0, line: 1 }
     }
 
-    // Fallback: couper directement
-    format!("{}...", truncated)
+    /// Positions croissantes en temps linéaire ; un retour en arrière repart du début
+    fn advance_to(&mut self, byte: usize) -> (usize, usize) {
+        if byte < self.byte {
+            *self = Self::new(self.text);
+        }
+        let skipped = &self.text[self.byte..byte];
+        self.char += skipped.chars().count();
+        self.line += skipped.matches('\n').count();
+        self.byte = byte;
+        (self.char, self.line)
+    }
 }
 
 /// Extraire spans contributeurs des chunks scorés - VERSION AMÉLIORÉE PR #4
-/// Génère des SourceSpan avec bbox synthétiques pour le surlignage visuel
-fn extract_contributing_spans(scored_chunks: &[ScoredChunk]) -> Vec<SourceSpan> {
+/// Utilise les SourceSpan réels résolus depuis `EnrichedChunk.source_spans` (chunk_id -> spans),
+/// et ne génère des bbox synthétiques que pour les chunks sans span réel
+fn extract_contributing_spans(
+    scored_chunks: &[ScoredChunk],
+    resolved_spans: &std::collections::HashMap<String, Vec<SourceSpan>>,
+) -> Vec<SourceSpan> {
     let mut all_spans = Vec::new();
 
     for (chunk_idx, scored_chunk) in scored_chunks.iter().enumerate() {
-        // Note: source_spans dans EnrichedChunk contient des IDs (Vec<String>), pas des SourceSpan
-        // Pour le moment, on génère toujours des spans synthétiques avec bbox pour visualisation
+        // Spans réels (bbox + page extraites à l'ingestion)
+        if let Some(real_spans) = resolved_spans.get(&scored_chunk.chunk.id) {
+            all_spans.extend(real_spans.iter().cloned());
+            continue;
+        }
+
+        // Fallback: span synthétique avec bbox estimée pour visualisation
 
         // 1. Hash du contenu pour traçabilité
         let content_hash = blake3::hash(scored_chunk.chunk.content.as_bytes()).to_hex().to_string();
@@ -1750,7 +2696,10 @@ fn calculate_response_confidence(scored_chunks: &[ScoredChunk]) -> f64 {
 }
 
 /// Créer résumé des sources contributives - VERSION AMÉLIORÉE PR #4
-fn create_sources_summary(scored_chunks: &[ScoredChunk]) -> Vec<SourceSummary> {
+fn create_sources_summary(
+    scored_chunks: &[ScoredChunk],
+    resolved_spans: &std::collections::HashMap<String, Vec<SourceSpan>>,
+) -> Vec<SourceSummary> {
     scored_chunks
         .iter()
         .take(5) // Top 5 sources
@@ -1773,14 +2722,124 @@ fn create_sources_summary(scored_chunks: &[ScoredChunk]) -> Vec<SourceSummary> {
                 score: sc.score,
                 confidence: sc.chunk.metadata.confidence as f64,
                 span_count,
+                source_document: sc.source_document.clone(),
+                // Première page couverte par les spans réels du chunk
+                page: resolved_spans.get(&sc.chunk.id)
+                    .and_then(|spans| spans.iter().filter_map(|span| span.bbox.as_ref()?.page).min()),
+                figure_id: sc.chunk.figure_id.clone(),
             }
         })
         .collect()
 }
 
+/// Débuts (mots entiers) de questions de suivi qui dépendent du tour précédent ("et le second ?")
+const FOLLOW_UP_OPENERS: &[&str] = &[
+    "et", "aussi", "sinon", "celui", "celle", "ceux", "celles", "le second", "la seconde", "le premier",
+    "la première", "le dernier", "la dernière",
+    "and", "also", "what about", "how about", "the second", "the first", "the last",
+];
+
+/// Pronoms qui constituent à eux seuls une question de suivi ("ça ?", "them?")
+const FOLLOW_UP_PRONOUNS: &[&str] = &[
+    "il", "elle", "ils", "elles", "lui", "eux", "ça", "cela", "it", "they", "them", "this", "that",
+];
+
+/// Enrichir une question de suivi avec la question précédente pour la recherche
+/// ("et le second ?" → "quels sont les deux modes ? et le second ?")
+fn contextualize_query(query: &str, history: &[ConversationTurn]) -> String {
+    let Some(last_turn) = history.last() else {
+        return query.to_string();
+    };
+
+    // Ponctuation → espaces pour comparer des mots entiers
+    let words: String = query
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let normalized = words.split_whitespace().collect::<Vec<_>>().join(" ");
+    let padded = format!("{} ", normalized);
+
+    // Anaphore en tête de question ou pronom seul : un mot comme "et" ou "it" au milieu
+    // d'une question autonome ne la rattache pas au tour précédent
+    let is_follow_up = FOLLOW_UP_OPENERS.iter().any(|opener| padded.starts_with(&format!("{} ", opener)))
+        || FOLLOW_UP_PRONOUNS.contains(&normalized.as_str());
+
+    if is_follow_up {
+        debug!("🔗 Follow-up detected, search query expanded with previous turn");
+        format!("{} {}", last_turn.query, query)
+    } else {
+        query.to_string()
+    }
+}
+
+/// Section d'historique préfixée au contexte LLM (vide si aucun échange)
+fn format_history_for_context(history: &[ConversationTurn]) -> String {
+    if history.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("## Échanges précédents\n\n");
+    for turn in history {
+        section.push_str(&format!("Utilisateur: {}\nAssistant: {}\n\n", turn.query, turn.response));
+    }
+    section.push_str("---\n\n");
+    section
+}
+
+/// Boost appliqué aux chunks qui satisfont la contrainte numérique de la query
+const NUMERICAL_MATCH_BOOST: f32 = 1.5;
+
+/// Reranking numérique: booste les chunks contenant les valeurs de la query
+/// (no-op si la query ne contient pas de chiffre avec unité)
+fn apply_numerical_boost(query: &str, scored_chunks: Vec<ScoredChunk>) -> Vec<ScoredChunk> {
+    use crate::rag::search::{NumericalReranker, QueryKind, QueryKindDetector};
+
+    let query_kind = QueryKindDetector::new().detect_query_kind(query);
+    if !matches!(query_kind, QueryKind::DigitAtomic | QueryKind::DigitCombined) {
+        return scored_chunks;
+    }
+
+    let chunks_for_rerank: Vec<(String, f32)> = scored_chunks
+        .iter()
+        .map(|sc| (sc.chunk.id.clone(), sc.score))
+        .collect();
+    let chunk_contents: std::collections::HashMap<String, String> = scored_chunks
+        .iter()
+        .map(|sc| (sc.chunk.id.clone(), sc.chunk.content.clone()))
+        .collect();
+
+    let reranker = NumericalReranker::new();
+    let matches: std::collections::HashMap<String, bool> = match query_kind {
+        QueryKind::DigitAtomic => reranker.rerank_digit_atomic(query, chunks_for_rerank, &chunk_contents),
+        _ => reranker.rerank_digit_combined(query, chunks_for_rerank, &chunk_contents),
+    }
+    .into_iter()
+    .map(|(id, _, has_match)| (id, has_match))
+    .collect();
+
+    let mut boosted: Vec<ScoredChunk> = scored_chunks
+        .into_iter()
+        .map(|mut sc| {
+            if matches.get(&sc.chunk.id).copied().unwrap_or(false) {
+                sc.score *= NUMERICAL_MATCH_BOOST;
+            }
+            sc
+        })
+        .collect();
+    boosted.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    debug!("🔢 Numerical boost ({:?}): {} chunks matched, top: {:.3}",
+           query_kind,
+           matches.values().filter(|m| **m).count(),
+           boosted.first().map(|sc| sc.score).unwrap_or(0.0));
+
+    boosted
+}
+
 /// Sprint 1 Niveau 1: Construire contexte formaté pour LLM synthesis
 /// Retourne: (formatted_context, chunk_infos, has_ocr_data)
-fn build_llm_context(scored_chunks: &[ScoredChunk]) -> (String, Vec<LlmChunkInfo>, bool) {
+fn build_llm_context(scored_chunks: &[ScoredChunk], chunk_max_chars: usize) -> (String, Vec<LlmChunkInfo>, bool) {
     use crate::rag::ChunkSource;
 
     let mut context_parts = Vec::new();
@@ -1791,26 +2850,13 @@ fn build_llm_context(scored_chunks: &[ScoredChunk]) -> (String, Vec<LlmChunkInfo
         let chunk = &scored_chunk.chunk;
 
         // Déterminer le label de source
-        let source_label = match chunk.chunk_source {
-            ChunkSource::FigureCaption => {
-                format!("Figure Caption - {}",
-                    chunk.figure_id.as_deref().unwrap_or("Unknown"))
-            },
-            ChunkSource::FigureRegionText => {
-                has_ocr_data = true;
-                format!("Figure OCR - {}",
-                    chunk.figure_id.as_deref().unwrap_or("Unknown"))
-            },
-            ChunkSource::Table => "Table".to_string(),
-            ChunkSource::BodyText => "Document Text".to_string(),
-            _ => "Content".to_string(),
-        };
+        if chunk.chunk_source == ChunkSource::FigureRegionText {
+            has_ocr_data = true;
+        }
+        let source_label = chunk.chunk_source.context_label(chunk.figure_id.as_deref());
 
-        // SIMPLIFICATION 23 Nov: Tronquer à 500 chars au lieu de 800 pour réduire latence LLM
-        let truncated_content: String = chunk.content
-            .chars()
-            .take(500)
-            .collect();
+        // Tronqué pour réduire la latence LLM, en fin de phrase ou de mot (`llm_chunk_max_chars`)
+        let truncated_content = condense_text(&chunk.content, chunk_max_chars);
 
         // Formater pour le contexte LLM
         let context_block = format!(
@@ -1867,12 +2913,16 @@ fn calculate_session_confidence(session: &DirectChatSession) -> f64 {
     avg_chunk_confidence * 0.4 + ocr_factor * 0.3 + embedding_factor * 0.3
 }
 
-/// Convertir un fichier temporaire en URL accessible pour affichage PDF
+/// Convertir un fichier temporaire en URL `asset:` chargeable par la webview
+/// (équivalent Rust de `convertFileSrc`; le scope est défini dans tauri.conf.json)
 #[tauri::command]
 pub async fn get_temp_pdf_url(
+    app: AppHandle,
     file_path: String,
 ) -> Result<TempPdfUrlResponse, String> {
-    info!("🔗 Converting temp file to accessible URL: {}", file_path);
+    use tauri::Manager;
+
+    info!("🔗 Converting temp file to asset URL: {}", file_path);
     
     let path = std::path::Path::new(&file_path);
     
@@ -1880,10 +2930,18 @@ pub async fn get_temp_pdf_url(
     if !path.exists() {
         return Err(format!("File not found: {}", file_path));
     }
+
+    // Le protocole asset refuse tout fichier hors scope: échouer explicitement
+    if !app.asset_protocol_scope().is_allowed(path) {
+        warn!("Asset protocol scope denies {:?}", path);
+        return Err(format!(
+            "File is outside the allowed asset scope (expected a gravis_temp_* file in {}): {}",
+            std::env::temp_dir().display(),
+            file_path
+        ));
+    }
     
-    // Pour l'instant, utiliser convertFileSrc concept de Tauri
-    // Note: Il faudra peut-être ajuster selon la config Tauri
-    let pdf_url = format!("file://{}", path.to_string_lossy());
+    let pdf_url = convert_file_src(path);
     
     info!("✅ Generated PDF URL: {} → {}", file_path, pdf_url);
     
@@ -1893,6 +2951,29 @@ pub async fn get_temp_pdf_url(
     })
 }
 
+/// URL du protocole `asset` pour un chemin local (même format que `convertFileSrc`)
+fn convert_file_src(path: &std::path::Path) -> String {
+    let encoded = encode_uri_component(&path.to_string_lossy());
+    if cfg!(windows) {
+        format!("http://asset.localhost/{}", encoded)
+    } else {
+        format!("asset://localhost/{}", encoded)
+    }
+}
+
+/// Encodage identique à `encodeURIComponent` côté JS
+fn encode_uri_component(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9'
+            | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1908,16 +2989,29 @@ mod tests {
         assert!(path.to_string_lossy().ends_with("test.pdf"));
     }
 
+    #[test]
+    fn test_convert_file_src_encodes_path() {
+        assert_eq!(encode_uri_component("/tmp/gravis_temp_a b.pdf"), "%2Ftmp%2Fgravis_temp_a%20b.pdf");
+        assert_eq!(encode_uri_component("rapport-été.pdf"), "rapport-%C3%A9t%C3%A9.pdf");
+
+        let url = convert_file_src(std::path::Path::new("/tmp/doc.pdf"));
+        assert!(url.ends_with("localhost/%2Ftmp%2Fdoc.pdf"));
+    }
+
     #[test]
     fn test_confidence_calculation() {
         let scored_chunks = vec![
             ScoredChunk {
                 chunk: create_test_chunk("chunk1", 0.9),
                 score: 0.8,
+                dense_score: 0.8,
+                source_document: None,
             },
             ScoredChunk {
                 chunk: create_test_chunk("chunk2", 0.7),
                 score: 0.6,
+                dense_score: 0.6,
+                source_document: None,
             },
         ];
 
@@ -1946,9 +3040,200 @@ mod tests {
                 ocr_metadata: None,
                 source_type: SourceType::NativeText,
                 extraction_method: ExtractionMethod::DirectRead,
+                page_overlap_len: 0,
             },
             group_id: "test_group".to_string(),
             source_spans: None,
+            chunk_source: crate::rag::ChunkSource::BodyText,
+            figure_id: None,
+        }
+    }
+
+    #[test]
+    fn test_direct_chat_config_validation() {
+        assert!(DirectChatConfig::default().validate().is_ok());
+
+        let zero_top_k = DirectChatConfig { final_top_k: 0, ..DirectChatConfig::default() };
+        assert!(zero_top_k.validate().is_err());
+
+        let small_pool = DirectChatConfig { candidate_pool_size: 5, ..DirectChatConfig::default() };
+        assert!(small_pool.validate().is_err());
+
+        // Champs absents: valeurs par défaut
+        let partial: DirectChatConfig = serde_json::from_str(r#"{"final_top_k": 7}"#).unwrap();
+        assert_eq!(partial.final_top_k, 7);
+        assert_eq!(partial.candidate_pool_size, 20);
+        assert_eq!(partial.llm_chunk_max_chars, 500);
+        assert_eq!(partial.embedding_concurrency, 2);
+
+        let no_concurrency = DirectChatConfig { embedding_concurrency: 0, ..DirectChatConfig::default() };
+        assert!(no_concurrency.validate().is_err());
+    }
+
+    #[test]
+    fn test_contextual_response_cites_pages_and_figures() {
+        let mut body = create_test_chunk("body", 0.9);
+        body.content = "Le chiffre d'affaires atteint 4,2 M€ en 2024.".to_string();
+        let mut figure = create_test_chunk("figure", 0.9);
+        figure.content = "Répartition du chiffre d'affaires par région.".to_string();
+        figure.figure_id = Some("Figure 2".to_string());
+
+        let scored_chunks = vec![
+            ScoredChunk { chunk: body, score: 0.9, dense_score: 0.9, source_document: Some("rapport.pdf".to_string()) },
+            ScoredChunk { chunk: figure, score: 0.8, dense_score: 0.8, source_document: Some("rapport.pdf".to_string()) },
+        ];
+        let mut sources = create_sources_summary(&scored_chunks, &std::collections::HashMap::new());
+        sources[0].page = Some(3);
+
+        let response = generate_contextual_response(&scored_chunks, "Quel est le chiffre d'affaires ?", &sources).unwrap();
+        assert!(response.contains("4,2 M€ en 2024. [p.3]"));
+        assert!(response.contains("par région. [Figure 2]"));
+        assert!(response.contains("**Sources :**\n- [p.3] rapport.pdf — pertinence 90%\n- [Figure 2] rapport.pdf"));
+    }
+
+    #[test]
+    fn test_condense_text_stops_at_sentence_or_word() {
+        let text = "Le salaire net s'élève à 2 150 €. Il est versé le 28 du mois ! La prime annuelle dépend des résultats.";
+
+        assert_eq!(condense_text(text, 200), text);
+        assert_eq!(condense_text(text, 70), "Le salaire net s'élève à 2 150 €. Il est versé le 28 du mois !");
+        assert_eq!(condense_text(text, 20), "Le salaire net...");
+    }
+
+    #[test]
+    fn test_contributing_spans_prefer_real_spans() {
+        use crate::rag::{SourceBoundingBox, ExtractionMethod};
+
+        let scored_chunks = vec![
+            ScoredChunk { chunk: create_test_chunk("with_span", 0.9), score: 0.8, dense_score: 0.8, source_document: None },
+            ScoredChunk { chunk: create_test_chunk("without_span", 0.9), score: 0.6, dense_score: 0.6, source_document: None },
+        ];
+
+        let real_span = SourceSpan::new(
+            "doc".to_string(),
+            PathBuf::from("doc.pdf"),
+            0,
+            12,
+            "test content".to_string(),
+            ExtractionMethod::PdfNative,
+        ).with_bbox(SourceBoundingBox::pdf_points(3, 72.0, 400.0, 450.0, 40.0));
+
+        let mut resolved = std::collections::HashMap::new();
+        resolved.insert("with_span".to_string(), vec![real_span.clone()]);
+
+        let spans = extract_contributing_spans(&scored_chunks, &resolved);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0], real_span);
+        assert_eq!(spans[0].bbox.as_ref().and_then(|b| b.page), Some(3));
+        assert!(spans[1].span_id.starts_with("synthetic_chunk_"));
+    }
+
+    #[test]
+    fn test_contextualize_follow_up_query() {
+        let history = vec![ConversationTurn {
+            query: "Quels sont les deux modes de compression ?".to_string(),
+            response: "Tiny et Gundam.".to_string(),
+            timestamp: std::time::SystemTime::now(),
+            sources: vec![],
+        }];
+
+        assert_eq!(
+            contextualize_query("Et le second ?", &history),
+            "Quels sont les deux modes de compression ? Et le second ?"
+        );
+        assert_eq!(
+            contextualize_query("What about the last one?", &history),
+            "Quels sont les deux modes de compression ? What about the last one?"
+        );
+        assert_ne!(contextualize_query("ça ?", &history), "ça ?");
+
+        // Questions autonomes : courtes, ou avec "et"/"il"/"it" au milieu
+        for standalone in [
+            "Quelle est la précision de décodage du modèle DeepEncoder ?",
+            "Résumé du document ?",
+            "Quelle est la marge brute et le résultat net ?",
+            "Combien de pages contient-il ?",
+            "What does it say about revenue and margins?",
+            "Etats financiers 2023",
+        ] {
+            assert_eq!(contextualize_query(standalone, &history), standalone);
         }
+        assert_eq!(contextualize_query("Et le second ?", &[]), "Et le second ?");
+    }
+
+    #[test]
+    fn test_numerical_boost_promotes_matching_chunk() {
+        let mut matching = create_test_chunk("table", 0.9);
+        matching.content = "Table 2: 97.3% precision at 16x compression".to_string();
+
+        let scored_chunks = vec![
+            ScoredChunk { chunk: create_test_chunk("intro", 0.9), score: 0.8, dense_score: 0.8, source_document: None },
+            ScoredChunk { chunk: matching, score: 0.6, dense_score: 0.6, source_document: None },
+        ];
+
+        let boosted = apply_numerical_boost("précision à 16x compression ?", scored_chunks.clone());
+        assert_eq!(boosted[0].chunk.id, "table");
+
+        // Query sans chiffre: ordre inchangé
+        let unchanged = apply_numerical_boost("DeepEncoder c'est quoi ?", scored_chunks);
+        assert_eq!(unchanged[0].chunk.id, "intro");
+    }
+
+    #[test]
+    fn test_spans_linked_to_chunks_by_offsets() {
+        // Chunks [0..40), [30..80), [80..120) : le chevauchement 30..40 appartient aux deux premiers
+        let located = vec![(0, (0, 40)), (1, (30, 80)), (2, (80, 120))];
+        let linked = |range| overlapping_chunks(&located, range).iter().map(|(index, _)| *index).collect::<Vec<_>>();
+
+        assert_eq!(linked((5, 12)), vec![0]);
+        assert_eq!(linked((32, 38)), vec![0, 1]);
+        // Bloc court ("Total") en fin de document : un seul chunk, pas tous ceux qui contiennent le mot
+        assert_eq!(linked((110, 115)), vec![2]);
+        assert!(linked((130, 140)).is_empty());
+
+        let text = "é\nLe total\nTotal";
+        let mut position = TextPosition::new(text);
+        let byte = text.rfind("Total").unwrap();
+        assert_eq!(position.advance_to(byte), (11, 3));
+        assert_eq!(position.advance_to(text.find("Le").unwrap()), (2, 2));
+    }
+
+    #[test]
+    fn test_merge_unified_results_on_raw_cosine() {
+        let result = |origin: &str, chunk_id: &str, content: &str, origin_score: f32, cosine: f32| UnifiedSearchResult {
+            origin: origin.to_string(),
+            chunk_id: chunk_id.to_string(),
+            document_id: None,
+            source_file: None,
+            content: content.to_string(),
+            score: origin_score,
+            origin_score,
+            cosine_score: cosine,
+        };
+
+        let session = vec![
+            result("session:s1", "s_a", "Le préavis est de trois mois.", 0.95, 0.86),
+            result("session:s1", "s_b", "Clause de non-concurrence", 0.40, 0.61),
+        ];
+        // Origine peu pertinente : son meilleur résultat ne doit pas être ramené au niveau des autres
+        let group = vec![
+            result("group:kb", "g_a", "le préavis est  de trois mois.", 0.92, 0.84),
+            result("group:kb", "g_b", "Convention collective Syntec", 0.46, 0.74),
+        ];
+        let weak = vec![result("group:misc", "w_a", "Recette de la tarte aux pommes", 0.90, 0.35)];
+
+        let (merged, duplicates) = merge_unified_results(
+            vec![(session, 1.0), (group, GROUP_RESULT_WEIGHT), (weak, GROUP_RESULT_WEIGHT)],
+            3,
+        );
+
+        assert_eq!(duplicates, 1);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].chunk_id, "s_a");
+        assert!((merged[0].score - 0.86).abs() < 1e-6);
+        assert!(merged.iter().all(|r| r.chunk_id != "g_a"));
+        assert_eq!(merged[1].chunk_id, "g_b");
+        assert_eq!(merged[2].chunk_id, "s_b");
+        assert!(merged.iter().all(|r| r.chunk_id != "w_a"));
     }
 }
\ No newline at end of file
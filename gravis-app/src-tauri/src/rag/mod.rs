@@ -21,6 +21,9 @@ pub mod ocr;
 pub mod commands;
 // Phase 2: Chat Direct commands
 pub mod direct_chat_commands;
+// Shared test fixtures
+#[cfg(test)]
+pub(crate) mod test_fixtures;
 
 // Phase 4 exports - Production ready
 pub use search::{
@@ -30,7 +33,8 @@ pub use search::{
     QueryKindDetector, NumericalReranker, QueryKind, NumericalConstraint,
 };
 pub use core::{
-    QdrantRestClient, QdrantRestConfig, RestPoint, RestSearchResponse
+    QdrantRestClient, QdrantRestConfig, RestPoint, RestSearchResponse,
+    GroupExportBundle, GROUP_EXPORT_VERSION
 };
 // Phase 2 OCR exports - Command-based implementation
 pub use ocr::{
@@ -51,7 +55,7 @@ pub use core::{
     UnifiedCache, CachedDocument, CacheCleanupResult, CacheMetrics,
     // Phase 4A: Source Spans & Explainability
     SourceSpan, SourceSpanManager, CoordinateSystem, 
-    ExtractionMetadata, ExplainabilityReport, SourceSpanError, SpanStats
+    ExtractionMetadata, ExplainabilityReport, SourceSpanError, SpanStats, locate_chunks, locate_texts
 };
 
 // Alias pour éviter conflits avec BoundingBox de direct_chat
@@ -60,8 +64,10 @@ pub use crate::rag::core::source_spans::BoundingBox as SourceBoundingBox;
 // Processing exports
 pub use processing::{
     DocumentProcessor, DocumentClassifier, DocumentCategory, BusinessSignals,
-    SmartChunker, SmartChunkConfig, SmartChunkResult, ChunkSection,
+    SmartChunker, SmartChunkConfig, SmartChunkResult, ChunkSection, CodeLanguage,
     BusinessMetadata, BusinessSection, FinancialKPI, BusinessMetadataEnricher,
+    ExtractionQualityMetrics, EXTRACTION_QUALITY_FIELD,
+    PageOverlapConfig, PAGE_OVERLAP_TAG, apply_page_overlap, strip_page_overlap,
     // Phase 4A: Span-Aware Chunking
     SpanAwareChunker, SpanAwareChunkConfig, SpanAwareChunkResult, 
     ChunkingStats, SpanChunkError
@@ -86,7 +92,7 @@ pub use commands::{
 
 // Phase 2: Chat Direct exports
 pub use direct_chat_commands::{
-    DirectChatState, ProcessDocumentResponse, ChatRequest, ChatResponse, SourceSummary
+    DirectChatState, DirectChatConfig, ProcessDocumentResponse, ChatRequest, ChatResponse, SourceSummary
 };
 
 // === Core Data Structures (Phase 1) ===
@@ -101,16 +107,100 @@ pub struct DocumentGroup {
     pub metadata_config: MetadataConfig,
     pub documents: Vec<GroupDocument>,
     pub qdrant_collection: String,
+    /// Paramètres de la collection Qdrant (doivent correspondre à l'embedder)
+    #[serde(default)]
+    pub collection_config: CollectionConfig,
     pub created_at: SystemTime,
     pub updated_at: SystemTime,
 }
 
-/// Configuration de chunking par groupe
+/// Métrique de distance des vecteurs Qdrant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum VectorDistance {
+    #[default]
+    Cosine,
+    Dot,
+    Euclid,
+}
+
+impl VectorDistance {
+    /// Nom attendu par l'API Qdrant
+    pub fn as_qdrant_str(&self) -> &'static str {
+        match self {
+            VectorDistance::Cosine => "Cosine",
+            VectorDistance::Dot => "Dot",
+            VectorDistance::Euclid => "Euclid",
+        }
+    }
+}
+
+/// Configuration de la collection Qdrant d'un groupe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionConfig {
+    pub dimension: usize,
+    pub distance: VectorDistance,
+}
+
+impl CollectionConfig {
+    /// Configuration alignée sur la dimension de l'embedder chargé
+    pub fn for_dimension(dimension: usize) -> Self {
+        Self { dimension, distance: VectorDistance::default() }
+    }
+}
+
+impl Default for CollectionConfig {
+    /// E5-small-v2 (384D, Cosine) : groupes créés avant ce champ
+    fn default() -> Self {
+        Self::for_dimension(384)
+    }
+}
+
+/// Ratio approximatif caractères → tokens, commun à tous les chunkers
+pub const CHARS_PER_TOKEN: f32 = 4.0;
+
+/// Estimation du nombre de tokens d'un texte (`chars / CHARS_PER_TOKEN`, arrondi supérieur)
+pub fn estimate_tokens(text: &str) -> usize {
+    (text.chars().count() as f32 / CHARS_PER_TOKEN).ceil() as usize
+}
+
+/// Configuration de chunking par groupe.
+///
+/// `chunk_size` et `overlap` sont exprimés en **tokens** pour tous les chunkers
+/// (SmartChunker, chunking OCR/natif, split de secours), convertis en caractères via
+/// [`CHARS_PER_TOKEN`]. Migration: les anciens chemins interprétaient `chunk_size` en
+/// caractères (texte pré-extrait, chunks ~4x plus petits) ou en mots (OCR/natif, chunks
+/// ~30% plus grands); les groupes existants gardent leur valeur mais doivent être
+/// réindexés pour obtenir des tailles homogènes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChunkConfig {
     pub chunk_size: usize,    // 256-1024 tokens
     pub overlap: usize,       // 32-128 tokens  
     pub strategy: ChunkStrategy,
+    /// Traitement des chunks dépassant la limite de tokens du modèle d'embedding
+    #[serde(default)]
+    pub oversized_chunks: OversizedChunkPolicy,
+    /// Confiance OCR minimale (0.0 - 1.0) pour indexer un chunk ; `None` : tout indexer
+    #[serde(default)]
+    pub min_chunk_confidence: Option<f32>,
+    /// Détection des en-têtes/pieds de page répétés sur les pages PDF
+    #[serde(default)]
+    pub header_footer: crate::rag::processing::HeaderFooterConfig,
+    /// Tokens natifs par page sous lesquels un PDF est traité comme scanné (OCR) ;
+    /// `None` : décision uniquement sur la qualité d'extraction
+    #[serde(default)]
+    pub min_native_tokens: Option<usize>,
+}
+
+impl ChunkConfig {
+    /// Taille cible d'un chunk en caractères
+    pub fn target_chars(&self) -> usize {
+        (self.chunk_size as f32 * CHARS_PER_TOKEN) as usize
+    }
+
+    /// Overlap en caractères
+    pub fn overlap_chars(&self) -> usize {
+        (self.overlap as f32 * CHARS_PER_TOKEN) as usize
+    }
 }
 
 impl Default for ChunkConfig {
@@ -119,18 +209,33 @@ impl Default for ChunkConfig {
             chunk_size: 384,   // Optimisé pour E5-small-v2 (256-512 tokens idéal)
             overlap: 48,       // 12.5% d'overlap pour continuité sans redondance excessive
             strategy: ChunkStrategy::AstFirst,
+            oversized_chunks: OversizedChunkPolicy::default(),
+            min_chunk_confidence: None,
+            header_footer: crate::rag::processing::HeaderFooterConfig::default(),
+            min_native_tokens: None,
         }
     }
 }
 
 /// Stratégie de chunking
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ChunkStrategy {
     AstFirst,      // Tree-sitter → fallback heuristique
     Heuristic,     // Fenêtres glissantes uniquement
     Hybrid,        // Mix AST + heuristique optimisé
 }
 
+/// Chunk plus long que la fenêtre du modèle d'embedding (sa fin serait ignorée)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OversizedChunkPolicy {
+    /// Découper en morceaux tenant dans la fenêtre du modèle
+    #[default]
+    Split,
+    /// Conserver tel quel, journaliser et compter dans `chunks_truncated`
+    Flag,
+}
+
 /// Configuration des métadonnées par défaut
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetadataConfig {
@@ -150,13 +255,52 @@ impl Default for MetadataConfig {
 }
 
 /// Priorité pour le scoring
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Priority {
     Low = 1,
+    #[default]
     Normal = 2, 
     High = 3,
 }
 
+/// Multiplicateurs appliqués au score de recherche selon la priorité du document
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriorityWeights {
+    pub low: f32,
+    pub normal: f32,
+    pub high: f32,
+}
+
+impl Default for PriorityWeights {
+    fn default() -> Self {
+        Self {
+            low: 0.9,
+            normal: 1.0,
+            high: 1.15,
+        }
+    }
+}
+
+impl PriorityWeights {
+    pub fn weight(&self, priority: Priority) -> f32 {
+        match priority {
+            Priority::Low => self.low,
+            Priority::Normal => self.normal,
+            Priority::High => self.high,
+        }
+    }
+
+    /// Multiplicateurs finis et strictement positifs
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, weight) in [("low", self.low), ("normal", self.normal), ("high", self.high)] {
+            if !weight.is_finite() || weight <= 0.0 {
+                return Err(format!("Priority weight '{}' must be > 0 (got {})", name, weight));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Document dans un groupe
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupDocument {
@@ -171,6 +315,9 @@ pub struct GroupDocument {
     pub group_id: String,
     #[serde(default)]
     pub ocr_blocks: Vec<crate::rag::core::direct_chat::OCRBlock>,  // Figure blocks from PDF extraction
+    /// Enrichissement Business (KPIs, exercice...) calculé à la demande puis conservé
+    #[serde(default)]
+    pub business_metadata: Option<BusinessMetadata>,
 }
 
 /// Type de document avec stratégies intelligentes - Phase 1 OCR
@@ -204,6 +351,7 @@ pub enum PdfStrategy {
 pub struct EnrichedChunk {
     pub id: String,
     pub content: String,
+    /// Lignes couvertes dans le texte source (1-based, bornes incluses)
     pub start_line: usize,
     pub end_line: usize,
     pub chunk_type: ChunkType,
@@ -243,6 +391,19 @@ pub enum ChunkSource {
     SectionHeader,
 }
 
+impl ChunkSource {
+    /// Libellé de la source pour le contexte LLM (chat direct et RAG persistant)
+    pub fn context_label(&self, figure_id: Option<&str>) -> String {
+        match self {
+            ChunkSource::FigureCaption => format!("Figure Caption - {}", figure_id.unwrap_or("Unknown")),
+            ChunkSource::FigureRegionText => format!("Figure OCR - {}", figure_id.unwrap_or("Unknown")),
+            ChunkSource::Table => "Table".to_string(),
+            ChunkSource::BodyText => "Document Text".to_string(),
+            ChunkSource::SectionHeader => "Content".to_string(),
+        }
+    }
+}
+
 /// Type de source pour l'extraction - Phase 1 OCR
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SourceType {
@@ -275,6 +436,9 @@ pub struct ChunkMetadata {
     pub ocr_metadata: Option<crate::rag::ocr::OcrMetadata>,
     pub source_type: SourceType,
     pub extraction_method: ExtractionMethod,
+    /// Octets de la fin de page précédente en tête de `content` (0 sans overlap, cf. `apply_page_overlap`)
+    #[serde(default)]
+    pub page_overlap_len: usize,
 }
 
 /// Métadonnées document enrichies
@@ -306,10 +470,143 @@ pub enum RagError {
     
     #[error("Document not found: {0}")]
     DocumentNotFound(String),
+
+    /// OCR indisponible (Tesseract absent, langue manquante...)
+    #[error("OCR unavailable: {0}")]
+    Ocr(#[from] ocr::OcrError),
+
+    /// Extraction sans texte exploitable (PDF scanné, image vide...)
+    #[error("No readable text extracted from {path} ({meaningful_chars} meaningful characters); the document may be scanned, try OCR")]
+    EmptyExtraction { path: String, meaningful_chars: usize },
+
+    /// Fichier binaire présenté comme texte (exécutable, archive ou image renommés)
+    #[error("{path} is not a text file: {reason}")]
+    BinaryContent { path: String, reason: String },
 }
 
 pub type RagResult<T> = Result<T, RagError>;
 
+/// Erreur structurée renvoyée par les commandes Tauri, sérialisée `{ "kind": ..., "message": ... }`
+/// pour que le frontend distingue un fichier absent d'un service indisponible
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CommandError {
+    #[error("{message}")]
+    NotFound { message: String },
+
+    #[error("{message}")]
+    PermissionDenied { message: String },
+
+    #[error("{message}")]
+    ServiceUnavailable { message: String },
+
+    #[error("{message}")]
+    InvalidInput { message: String },
+
+    /// Ressource déjà présente (ex: document identique déjà importé)
+    #[error("{message}")]
+    AlreadyExists { message: String },
+
+    #[error("{message}")]
+    Internal { message: String },
+}
+
+impl CommandError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound { message: message.into() }
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::PermissionDenied { message: message.into() }
+    }
+
+    pub fn service_unavailable(message: impl Into<String>) -> Self {
+        Self::ServiceUnavailable { message: message.into() }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::InvalidInput { message: message.into() }
+    }
+
+    pub fn already_exists(message: impl Into<String>) -> Self {
+        Self::AlreadyExists { message: message.into() }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal { message: message.into() }
+    }
+
+    /// Message à afficher
+    pub fn message(&self) -> &str {
+        match self {
+            Self::NotFound { message }
+            | Self::PermissionDenied { message }
+            | Self::ServiceUnavailable { message }
+            | Self::InvalidInput { message }
+            | Self::AlreadyExists { message }
+            | Self::Internal { message } => message,
+        }
+    }
+
+    /// Préfixer le message en conservant la catégorie
+    pub fn with_context(self, context: &str) -> Self {
+        let message = format!("{}: {}", context, self.message());
+        match self {
+            Self::NotFound { .. } => Self::NotFound { message },
+            Self::PermissionDenied { .. } => Self::PermissionDenied { message },
+            Self::ServiceUnavailable { .. } => Self::ServiceUnavailable { message },
+            Self::InvalidInput { .. } => Self::InvalidInput { message },
+            Self::AlreadyExists { .. } => Self::AlreadyExists { message },
+            Self::Internal { .. } => Self::Internal { message },
+        }
+    }
+
+    fn from_io(error: &std::io::Error, message: String) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound { message },
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied { message },
+            _ => Self::Internal { message },
+        }
+    }
+}
+
+impl From<RagError> for CommandError {
+    fn from(error: RagError) -> Self {
+        let message = error.to_string();
+        match error {
+            RagError::Io(ref e) => Self::from_io(e, message),
+            RagError::GroupNotFound(_) | RagError::DocumentNotFound(_) => Self::NotFound { message },
+            RagError::EmptyExtraction { .. } | RagError::BinaryContent { .. } => Self::InvalidInput { message },
+            RagError::Serialization(_) | RagError::InvalidConfig(_) => Self::Internal { message },
+            RagError::Ocr(error) => Self::from(error).with_context("OCR unavailable"),
+        }
+    }
+}
+
+impl From<ocr::OcrError> for CommandError {
+    fn from(error: ocr::OcrError) -> Self {
+        use ocr::OcrError;
+
+        let message = error.to_string();
+        match error {
+            OcrError::Io(ref e) => Self::from_io(e, message),
+            OcrError::FileNotFound(_) => Self::NotFound { message },
+            OcrError::UnsupportedFormat(_) | OcrError::LanguageNotAvailable(_) => Self::InvalidInput { message },
+            OcrError::TesseractCommand(_) | OcrError::NotInstalled(_) | OcrError::TransformerUnavailable(_) | OcrError::Timeout => {
+                Self::ServiceUnavailable { message }
+            }
+            OcrError::ImageProcessing(_) | OcrError::Parsing(_) | OcrError::Cache(_) => Self::Internal { message },
+        }
+    }
+}
+
+/// Erreurs historiques en `String` (`format!`) : catégorie `Internal`
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::Internal { message }
+    }
+}
+
 // === Utils ===
 
 impl DocumentGroup {
@@ -326,6 +623,7 @@ impl DocumentGroup {
             metadata_config: MetadataConfig::default(),
             documents: Vec::new(),
             qdrant_collection: format!("collection_{}", id),
+            collection_config: CollectionConfig::default(),
             created_at: now,
             updated_at: now,
         }
@@ -343,10 +641,17 @@ impl DocumentGroup {
             metadata_config: MetadataConfig::default(),
             documents: Vec::new(),
             qdrant_collection: format!("collection_{}", id),
+            collection_config: CollectionConfig::default(),
             created_at: now,
             updated_at: now,
         }
     }
+
+    /// Aligner la collection sur l'embedder utilisé pour ce groupe
+    pub fn with_collection_config(mut self, collection_config: CollectionConfig) -> Self {
+        self.collection_config = collection_config;
+        self
+    }
     
     /// Vérifier si le groupe peut être utilisé pour les requêtes
     pub fn is_ready(&self) -> bool {
@@ -382,6 +687,38 @@ mod tests {
         let config = ChunkConfig::default();
         assert_eq!(config.chunk_size, 384);
         assert_eq!(config.overlap, 48);
+        assert_eq!(config.target_chars(), 1536);
+        assert_eq!(config.overlap_chars(), 192);
+        assert_eq!(estimate_tokens("abcdefghi"), 3);
+    }
+
+    #[test]
+    fn test_command_error_mapping_and_serialization() {
+        let not_found = CommandError::from(RagError::GroupNotFound("g1".to_string()));
+        assert_eq!(not_found, CommandError::not_found("Group not found: g1"));
+        assert_eq!(
+            serde_json::to_value(&not_found).unwrap(),
+            serde_json::json!({ "kind": "not_found", "message": "Group not found: g1" })
+        );
+
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert!(matches!(CommandError::from(RagError::Io(io)), CommandError::PermissionDenied { .. }));
+        assert!(matches!(
+            CommandError::from(ocr::OcrError::TesseractCommand("missing".to_string())),
+            CommandError::ServiceUnavailable { .. }
+        ));
+        let not_installed = CommandError::from(RagError::Ocr(ocr::OcrError::NotInstalled("brew install tesseract".to_string())));
+        assert_eq!(
+            not_installed,
+            CommandError::service_unavailable("OCR unavailable: Tesseract is not installed. brew install tesseract")
+        );
+
+        let contextual = CommandError::invalid_input("bad").with_context("Upload failed");
+        assert_eq!(contextual, CommandError::invalid_input("Upload failed: bad"));
+        assert_eq!(
+            serde_json::to_value(CommandError::already_exists("Duplicate of document d1")).unwrap()["kind"],
+            "already_exists"
+        );
     }
 
     #[test]
@@ -404,6 +741,7 @@ mod tests {
                 ocr_metadata: None,
                 source_type: SourceType::NativeText,
                 extraction_method: ExtractionMethod::DirectRead,
+                page_overlap_len: 0,
             },
             group_id: "group1".to_string(),
             source_spans: None,
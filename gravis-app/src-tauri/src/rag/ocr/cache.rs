@@ -15,9 +15,11 @@ use tracing::{debug, info};
 
 /// Configuration du cache OCR
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredCacheConfig")]
 pub struct CacheConfig {
     pub enabled: bool,
-    pub max_size_mb: usize,           // Cache LRU 256MB
+    pub max_entries: usize,          // Nombre max d'entrées (éviction LRU au-delà)
+    pub max_bytes: u64,              // Taille estimée max (éviction LRU au-delà)
     pub ttl_hours: u64,              // TTL en heures
     pub persistent: bool,            // Sauvegarder sur disque
     pub cache_directory: Option<String>,
@@ -27,7 +29,8 @@ impl Default for CacheConfig {
     fn default() -> Self {
         Self {
             enabled: true,
-            max_size_mb: 256,
+            max_entries: 5000,
+            max_bytes: 256 * 1_048_576,
             ttl_hours: 24,
             persistent: false, // En mémoire par défaut
             cache_directory: None,
@@ -35,6 +38,38 @@ impl Default for CacheConfig {
     }
 }
 
+/// Configuration telle qu'enregistrée : plafonds absents = valeurs par défaut,
+/// ancien plafond `max_size_mb` converti en `max_bytes`
+#[derive(Deserialize)]
+struct StoredCacheConfig {
+    enabled: bool,
+    #[serde(default)]
+    max_entries: Option<usize>,
+    #[serde(default)]
+    max_bytes: Option<u64>,
+    #[serde(default)]
+    max_size_mb: Option<u64>,
+    ttl_hours: u64,
+    persistent: bool,
+    cache_directory: Option<String>,
+}
+
+impl From<StoredCacheConfig> for CacheConfig {
+    fn from(stored: StoredCacheConfig) -> Self {
+        let defaults = CacheConfig::default();
+        Self {
+            enabled: stored.enabled,
+            max_entries: stored.max_entries.unwrap_or(defaults.max_entries),
+            max_bytes: stored.max_bytes
+                .or(stored.max_size_mb.map(|mb| mb * 1_048_576))
+                .unwrap_or(defaults.max_bytes),
+            ttl_hours: stored.ttl_hours,
+            persistent: stored.persistent,
+            cache_directory: stored.cache_directory,
+        }
+    }
+}
+
 /// Entrée de cache avec timestamp et métadonnées
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CacheEntry {
@@ -74,6 +109,15 @@ impl CacheEntry {
     }
 }
 
+/// Résultat d'une éviction LRU (plafonds `max_entries` / `max_bytes`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheEvictionResult {
+    pub evicted_entries: usize,
+    pub freed_bytes: u64,
+    pub remaining_entries: usize,
+    pub remaining_bytes: u64,
+}
+
 /// Cache principal OCR
 pub struct OcrCache {
     config: CacheConfig,
@@ -86,7 +130,10 @@ pub struct OcrCache {
 pub struct CacheStats {
     pub hits: u64,
     pub misses: u64,
+    /// Entrées évincées pour respecter les plafonds
     pub evictions: u64,
+    /// Entrées retirées car expirées ou fichier modifié
+    pub expired_removals: u64,
     pub memory_usage_bytes: u64,
     pub entries_count: usize,
 }
@@ -117,13 +164,11 @@ impl OcrCache {
             });
         }
         
-        // Calculer la capacité du cache basée sur la taille mémoire
-        let estimated_entry_size = 50_000; // ~50KB par entrée en moyenne
-        let max_entries = (config.max_size_mb * 1_048_576) / estimated_entry_size;
-        let capacity = NonZeroUsize::new(max_entries.max(10)).unwrap();
+        // Plafonds appliqués par enforce_limits (octets) et par la capacité LRU (entrées)
+        let capacity = NonZeroUsize::new(config.max_entries.max(1)).unwrap();
         
-        info!("✅ OCR cache initialized: {}MB, ~{} entries", 
-              config.max_size_mb, capacity);
+        info!("✅ OCR cache initialized: max {} entries, max {:.0}MB", 
+              capacity, config.max_bytes as f64 / 1_048_576.0);
         
         Ok(Self {
             config,
@@ -131,6 +176,33 @@ impl OcrCache {
             stats: Arc::new(Mutex::new(CacheStats::default())),
         })
     }
+
+    /// Évince les entrées les moins récemment utilisées tant qu'un plafond est dépassé
+    pub fn enforce_limits(&self) -> CacheEvictionResult {
+        let mut result = CacheEvictionResult::default();
+        
+        if let (Ok(mut cache), Ok(mut stats)) = (self.memory_cache.lock(), self.stats.lock()) {
+            while cache.len() > self.config.max_entries || stats.memory_usage_bytes > self.config.max_bytes {
+                let Some((_, entry)) = cache.pop_lru() else { break };
+                let size = Self::estimate_entry_size(&entry);
+                stats.memory_usage_bytes = stats.memory_usage_bytes.saturating_sub(size);
+                stats.evictions += 1;
+                result.evicted_entries += 1;
+                result.freed_bytes += size;
+            }
+            stats.entries_count = cache.len();
+            result.remaining_entries = cache.len();
+            result.remaining_bytes = stats.memory_usage_bytes;
+        }
+        
+        if result.evicted_entries > 0 {
+            info!("🧹 OCR cache eviction: {} entries ({}KB) removed, {} remaining ({}KB)",
+                  result.evicted_entries, result.freed_bytes / 1024,
+                  result.remaining_entries, result.remaining_bytes / 1024);
+        }
+        
+        result
+    }
     
     /// Générer une clé de cache pour un fichier image
     #[allow(dead_code)]
@@ -219,7 +291,14 @@ impl OcrCache {
                         return Ok(Some(entry.result.clone()));
                     } else {
                         // Entrée expirée ou fichier modifié
-                        cache.pop(&key);
+                        if let Some(expired) = cache.pop(&key) {
+                            if let Ok(mut stats) = self.stats.lock() {
+                                stats.memory_usage_bytes = stats.memory_usage_bytes
+                                    .saturating_sub(Self::estimate_entry_size(&expired));
+                                stats.expired_removals += 1;
+                                stats.entries_count = cache.len();
+                            }
+                        }
                         debug!("🗑️ Cache entry expired/invalid for: {:?}", image_path);
                     }
                 }
@@ -258,23 +337,28 @@ impl OcrCache {
         
         // Stocker dans le cache mémoire
         if let Ok(mut cache) = self.memory_cache.lock() {
-            if let Some(_evicted) = cache.put(cache_key.clone(), entry) {
-                // Une entrée a été évincée
-                if let Ok(mut stats) = self.stats.lock() {
-                    stats.evictions += 1;
-                }
-            }
+            let displaced = cache.push(cache_key.clone(), entry);
             
-            // Mettre à jour les stats
             if let Ok(mut stats) = self.stats.lock() {
-                stats.entries_count = cache.len();
                 stats.memory_usage_bytes += entry_size;
+                if let Some((displaced_key, displaced_entry)) = displaced {
+                    stats.memory_usage_bytes = stats.memory_usage_bytes
+                        .saturating_sub(Self::estimate_entry_size(&displaced_entry));
+                    // Même clé : remplacement ; sinon éviction LRU (plafond d'entrées)
+                    if displaced_key != cache_key {
+                        stats.evictions += 1;
+                    }
+                }
+                stats.entries_count = cache.len();
             }
         }
         
         debug!("💾 Cached result for image: {:?} ({}KB)", 
                image_path, entry_size / 1024);
         
+        // Plafond en octets
+        self.enforce_limits();
+        
         Ok(())
     }
     
@@ -328,15 +412,19 @@ impl OcrCache {
             }
             
             // Supprimer les entrées expirées
+            let mut freed_bytes = 0;
             for key in keys_to_remove {
-                cache.pop(&key);
-                removed_count += 1;
+                if let Some(entry) = cache.pop(&key) {
+                    freed_bytes += Self::estimate_entry_size(&entry);
+                    removed_count += 1;
+                }
             }
             
             // Mettre à jour les stats
             if let Ok(mut stats) = self.stats.lock() {
                 stats.entries_count = cache.len();
-                stats.evictions += removed_count as u64;
+                stats.memory_usage_bytes = stats.memory_usage_bytes.saturating_sub(freed_bytes);
+                stats.expired_removals += removed_count as u64;
             }
         }
         
@@ -353,7 +441,8 @@ impl OcrCache {
         
         if let Ok(stats) = self.stats.lock() {
             info.insert("enabled".to_string(), serde_json::Value::Bool(self.config.enabled));
-            info.insert("max_size_mb".to_string(), serde_json::Value::Number(self.config.max_size_mb.into()));
+            info.insert("max_entries".to_string(), serde_json::Value::Number(self.config.max_entries.into()));
+            info.insert("max_bytes".to_string(), serde_json::Value::Number(self.config.max_bytes.into()));
             info.insert("ttl_hours".to_string(), serde_json::Value::Number(self.config.ttl_hours.into()));
             info.insert("hits".to_string(), serde_json::Value::Number(stats.hits.into()));
             info.insert("misses".to_string(), serde_json::Value::Number(stats.misses.into()));
@@ -361,6 +450,9 @@ impl OcrCache {
                 serde_json::Number::from_f64(stats.hit_rate()).unwrap_or(serde_json::Number::from(0))
             ));
             info.insert("entries_count".to_string(), serde_json::Value::Number(stats.entries_count.into()));
+            info.insert("evictions".to_string(), serde_json::Value::Number(stats.evictions.into()));
+            info.insert("expired_removals".to_string(), serde_json::Value::Number(stats.expired_removals.into()));
+            info.insert("memory_usage_bytes".to_string(), serde_json::Value::Number(stats.memory_usage_bytes.into()));
             info.insert("memory_usage_mb".to_string(), serde_json::Value::Number(
                 serde_json::Number::from_f64(stats.memory_usage_mb()).unwrap_or(serde_json::Number::from(0))
             ));
@@ -373,6 +465,7 @@ impl OcrCache {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rag::ocr::{OcrMetadata, PageSegMode, OcrEngineMode};
     use tempfile::NamedTempFile;
     
     #[tokio::test]
@@ -422,6 +515,80 @@ mod tests {
         println!("✅ Cache stats test passed");
     }
     
+    fn sample_result(text: &str) -> OcrResult {
+        OcrResult {
+            text: text.to_string(),
+            confidence: 0.9,
+            language: "fra".to_string(),
+            bounding_boxes: Vec::new(),
+            processing_time: Duration::from_millis(5),
+            engine_used: "Tesseract".to_string(),
+            tesseract_version: "5".to_string(),
+            metadata: OcrMetadata {
+                source_file: "test.png".to_string(),
+                file_size_bytes: 0,
+                image_dimensions: (0, 0),
+                preprocessing_applied: Vec::new(),
+                psm_used: PageSegMode::Auto,
+                oem_used: OcrEngineMode::LstmOnly,
+                temp_files_created: Vec::new(),
+            },
+            ocr_blocks: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_eviction_caps() {
+        let files: Vec<NamedTempFile> = (0..3).map(|_| NamedTempFile::new().unwrap()).collect();
+        for (i, file) in files.iter().enumerate() {
+            std::fs::write(file.path(), format!("image {}", i)).unwrap();
+        }
+        let entry_bytes = 2000 + 1000; // overhead fixe + texte
+        
+        // Plafond d'entrées : la plus ancienne est évincée
+        let cache = OcrCache::new(CacheConfig { max_entries: 2, ..Default::default() }).await.unwrap();
+        for file in &files {
+            cache.store_image_result(file.path(), &sample_result(&"a".repeat(1000))).await.unwrap();
+        }
+        let stats = cache.get_stats();
+        assert_eq!((stats.entries_count, stats.evictions), (2, 1));
+        assert_eq!(stats.memory_usage_bytes, 2 * entry_bytes);
+        assert!(cache.get_image_result(files[0].path()).await.unwrap().is_none());
+        assert!(cache.get_image_result(files[2].path()).await.unwrap().is_some());
+        
+        // Plafond en octets : 2 entrées max tiennent dans 2.5 entrées
+        let cache = OcrCache::new(CacheConfig { max_bytes: entry_bytes * 5 / 2, ..Default::default() }).await.unwrap();
+        for file in &files {
+            cache.store_image_result(file.path(), &sample_result(&"a".repeat(1000))).await.unwrap();
+        }
+        let stats = cache.get_stats();
+        assert_eq!((stats.entries_count, stats.evictions), (2, 1));
+        assert!(stats.memory_usage_bytes <= entry_bytes * 5 / 2);
+        
+        assert_eq!(cache.enforce_limits(), CacheEvictionResult {
+            evicted_entries: 0,
+            freed_bytes: 0,
+            remaining_entries: 2,
+            remaining_bytes: 2 * entry_bytes,
+        });
+    }
+    
+    #[test]
+    fn test_cache_config_from_legacy_format() {
+        // Format antérieur aux plafonds en entrées/octets
+        let legacy: CacheConfig = serde_json::from_str(
+            r#"{"enabled":true,"max_size_mb":64,"ttl_hours":12,"persistent":false,"cache_directory":null}"#
+        ).unwrap();
+        assert_eq!(legacy.max_bytes, 64 * 1_048_576);
+        assert_eq!(legacy.max_entries, CacheConfig::default().max_entries);
+        assert_eq!(legacy.ttl_hours, 12);
+
+        let roundtrip: CacheConfig = serde_json::from_str(
+            &serde_json::to_string(&CacheConfig { max_entries: 10, max_bytes: 4096, ..Default::default() }).unwrap()
+        ).unwrap();
+        assert_eq!((roundtrip.max_entries, roundtrip.max_bytes), (10, 4096));
+    }
+
     #[tokio::test]
     async fn test_cache_disabled() {
         let config = CacheConfig {
@@ -0,0 +1,183 @@
+// GRAVIS OCR - Comparaison des moteurs d'extraction
+// Mêmes fichier et langues passés à chaque moteur disponible : volume de texte, confiance,
+// temps et accord entre sorties, pour recommander un moteur par type de document.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::rag::estimate_tokens;
+
+/// Résultat d'un moteur sur le fichier comparé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineExtraction {
+    pub engine: String,
+    /// `false` : moteur absent de ce build ou inadapté au type de fichier
+    pub available: bool,
+    pub text_length: usize,
+    pub token_count: usize,
+    pub confidence: Option<f64>,
+    pub processing_time_ms: u64,
+    pub pages: u32,
+    pub error: Option<String>,
+}
+
+impl EngineExtraction {
+    pub fn success(engine: &str, text: &str, confidence: f64, pages: u32, processing_time_ms: u64) -> Self {
+        Self {
+            engine: engine.to_string(),
+            available: true,
+            text_length: text.chars().count(),
+            token_count: estimate_tokens(text),
+            confidence: Some(confidence),
+            processing_time_ms,
+            pages,
+            error: None,
+        }
+    }
+
+    pub fn failure(engine: &str, error: impl Into<String>, processing_time_ms: u64) -> Self {
+        Self {
+            engine: engine.to_string(),
+            available: true,
+            text_length: 0,
+            token_count: 0,
+            confidence: None,
+            processing_time_ms,
+            pages: 0,
+            error: Some(error.into()),
+        }
+    }
+
+    pub fn unavailable(engine: &str, reason: impl Into<String>) -> Self {
+        Self {
+            available: false,
+            ..Self::failure(engine, reason, 0)
+        }
+    }
+
+    fn produced_text(&self) -> bool {
+        self.error.is_none() && self.text_length > 0
+    }
+}
+
+/// Accord entre les sorties de deux moteurs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineSimilarity {
+    pub left: String,
+    pub right: String,
+    /// Jaccard sur les mots normalisés (0.0 - 1.0)
+    pub similarity: f32,
+}
+
+/// Comparaison complète renvoyée au frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionComparison {
+    pub filename: String,
+    pub engines: Vec<EngineExtraction>,
+    pub similarities: Vec<EngineSimilarity>,
+    /// Moteur au meilleur score confiance × accord avec les autres (None si aucun texte)
+    pub recommended_engine: Option<String>,
+}
+
+impl ExtractionComparison {
+    /// `runs` : résultat de chaque moteur et son texte (None en cas d'échec)
+    pub fn from_runs(filename: &str, runs: Vec<(EngineExtraction, Option<String>)>) -> Self {
+        let word_sets: Vec<Option<HashSet<String>>> = runs.iter()
+            .map(|(result, text)| text.as_deref().filter(|_| result.produced_text()).map(normalized_words))
+            .collect();
+
+        let mut similarities = Vec::new();
+        for i in 0..runs.len() {
+            for j in (i + 1)..runs.len() {
+                if let (Some(left), Some(right)) = (&word_sets[i], &word_sets[j]) {
+                    similarities.push(EngineSimilarity {
+                        left: runs[i].0.engine.clone(),
+                        right: runs[j].0.engine.clone(),
+                        similarity: jaccard(left, right),
+                    });
+                }
+            }
+        }
+
+        // Un moteur qui produit du bruit s'écarte des autres : l'accord moyen pondère la confiance
+        let recommended_engine = runs.iter()
+            .filter(|(result, _)| result.produced_text())
+            .map(|(result, _)| {
+                let agreements: Vec<f32> = similarities.iter()
+                    .filter(|pair| pair.left == result.engine || pair.right == result.engine)
+                    .map(|pair| pair.similarity)
+                    .collect();
+                let agreement = if agreements.is_empty() {
+                    1.0
+                } else {
+                    agreements.iter().sum::<f32>() / agreements.len() as f32
+                };
+                let score = result.confidence.unwrap_or(0.0) * (0.5 + 0.5 * agreement as f64);
+                (result, score)
+            })
+            .max_by(|(a, score_a), (b, score_b)| {
+                score_a.partial_cmp(score_b)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then(a.token_count.cmp(&b.token_count))
+            })
+            .map(|(result, _)| result.engine.clone());
+
+        Self {
+            filename: filename.to_string(),
+            engines: runs.into_iter().map(|(result, _)| result).collect(),
+            similarities,
+            recommended_engine,
+        }
+    }
+}
+
+/// Mots en minuscules sans ponctuation (les moteurs diffèrent surtout sur les espaces et la ponctuation)
+fn normalized_words(text: &str) -> HashSet<String> {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn jaccard(left: &HashSet<String>, right: &HashSet<String>) -> f32 {
+    if left.is_empty() && right.is_empty() {
+        return 1.0;
+    }
+    left.intersection(right).count() as f32 / left.union(right).count() as f32
+}
+
+/// Similarité entre deux textes extraits (Jaccard sur mots normalisés)
+pub fn text_similarity(left: &str, right: &str) -> f32 {
+    jaccard(&normalized_words(left), &normalized_words(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison_recommends_agreeing_engine() {
+        let native = "Le contrat est conclu pour une durée de trois ans.";
+        let ocr = "Le contrat est conc1u pour une durée de trois ans";
+        let noise = "Lc c0ntrat ost cnnclu p0ur unc dnrée";
+
+        assert_eq!(text_similarity("Bonjour, monde !", "bonjour monde"), 1.0);
+
+        let comparison = ExtractionComparison::from_runs("contrat.pdf", vec![
+            (EngineExtraction::success("lopdf", native, 0.9, 1, 12), Some(native.to_string())),
+            (EngineExtraction::success("extractous", ocr, 0.85, 1, 340), Some(ocr.to_string())),
+            (EngineExtraction::success("tesseract", noise, 0.92, 1, 900), Some(noise.to_string())),
+            (EngineExtraction::unavailable("trocr", "feature disabled"), None),
+        ]);
+
+        // Trois moteurs ayant produit du texte : trois paires
+        assert_eq!(comparison.similarities.len(), 3);
+        assert_eq!(comparison.recommended_engine.as_deref(), Some("lopdf"));
+        assert!(!comparison.engines[3].available);
+
+        let empty = ExtractionComparison::from_runs("scan.pdf", vec![
+            (EngineExtraction::failure("lopdf", "no text", 5), None),
+        ]);
+        assert!(empty.recommended_engine.is_none());
+    }
+}
@@ -16,6 +16,8 @@ pub struct LayoutAnalyzerConfig {
     pub grid_cell_size: f64,
     pub header_aspect_ratio_max: f64,
     pub header_max_y_percent: f64,
+    /// Blocs d'une même ligne lus de droite à gauche (arabe, hébreu)
+    pub right_to_left: bool,
 }
 
 impl Default for LayoutAnalyzerConfig {
@@ -32,6 +34,7 @@ impl Default for LayoutAnalyzerConfig {
             grid_cell_size: 50.0,
             header_aspect_ratio_max: 0.3,
             header_max_y_percent: 0.15,
+            right_to_left: false,
         }
     }
 }
@@ -75,11 +78,13 @@ impl LayoutAnalyzer {
             }
         }
 
-        // 3. Trier les blocs par position (top-to-bottom, left-to-right)
+        // 3. Trier les blocs par position (top-to-bottom, puis sens de lecture de l'écriture)
+        let right_to_left = self.config.right_to_left;
         blocks.sort_by(|a, b| {
             let y_diff = (a.bounding_box.y - b.bounding_box.y).abs();
             if y_diff < 10.0 {
-                a.bounding_box.x.partial_cmp(&b.bounding_box.x).unwrap()
+                let order = a.bounding_box.x.partial_cmp(&b.bounding_box.x).unwrap();
+                if right_to_left { order.reverse() } else { order }
             } else {
                 a.bounding_box.y.partial_cmp(&b.bounding_box.y).unwrap()
             }
@@ -12,6 +12,9 @@ pub mod commands;
 pub mod text_normalizer;
 pub mod types;
 pub mod layout_analyzer;
+pub mod transformer;      // TrOCR fallback (feature `trocr`)
+pub mod script_profile;
+pub mod extraction_comparison;
 
 // === Alternatives PDF (pures Rust et sans dépendances externes) ===
 pub mod pdf_lopdf;          // Alternative #1: lopdf (Pure Rust, recommandé)
@@ -19,15 +22,20 @@ pub mod pdf_extract_simple; // Alternative #2: pdf-extract (Simple)
 
 // Phase 2 exports
 pub use tesseract::{TesseractProcessor, TesseractConfig};
-pub use cache::{OcrCache, CacheConfig};
+pub use cache::{OcrCache, CacheConfig, CacheEvictionResult};
 pub use commands::{OcrCommands, OcrState};
-pub use text_normalizer::{normalize_for_rag, normalize_and_log, normalize_fast, needs_normalization, NormalizationStats};
+pub use text_normalizer::{normalize_for_rag, normalize_and_log, normalize_fast, needs_normalization, NormalizationStats, strip_ocr_noise, OcrNoiseConfig};
 pub use layout_analyzer::{LayoutAnalyzer, LayoutAnalyzerConfig};
 pub use types::{BoundingBox, OCRBlock, BlockType, BoundingBoxExt};
+pub use transformer::{TrOCRProcessor, TrOCRConfig, TrOCRResult};
+pub use script_profile::{ScriptFamily, ScriptProfile};
+pub use extraction_comparison::{EngineExtraction, EngineSimilarity, ExtractionComparison};
 
 // === Exports des alternatives PDF ===
-pub use pdf_lopdf::{LopdFProcessor, LopdFPipelineConfig, LopdFPageResult};
-pub use pdf_extract_simple::{SimplePdfExtractor, PdfExtractConfig, SimpleExtractionResult, quick_extract_text};
+pub use pdf_lopdf::{
+    LopdFProcessor, LopdFPipelineConfig, LopdFPageResult, PageQuality, average_page_confidence, worst_page,
+};
+pub use pdf_extract_simple::{SimplePdfExtractor, PdfExtractConfig, SimpleExtractionResult, quick_extract_text, PAGE_BREAK};
 
 /// Configuration OCR simplifiée pour Command-based approach
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,7 +62,7 @@ impl Default for OcrConfig {
 }
 
 /// Page Segmentation Mode Tesseract
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PageSegMode {
     AutoOsd = 1,        // Auto détection orientation/script
     Auto = 3,           // Auto sans OSD
@@ -72,17 +80,22 @@ impl PageSegMode {
 }
 
 /// OCR Engine Mode Tesseract
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OcrEngineMode {
     LegacyOnly = 0,     // Legacy engine seulement
     NeuralOnly = 1,     // Neural network seulement  
     LstmOnly = 2,       // LSTM seulement (recommandé)
-    Default = 3,        // Legacy + LSTM
+    Default = 3,        // Selon les modèles installés
 }
 
 impl OcrEngineMode {
+    /// Valeur de `--oem` : le réseau neuronal de Tesseract est le moteur LSTM (OEM 1)
     pub fn as_string(&self) -> String {
-        (*self as u32).to_string()
+        match self {
+            OcrEngineMode::LegacyOnly => "0",
+            OcrEngineMode::NeuralOnly | OcrEngineMode::LstmOnly => "1",
+            OcrEngineMode::Default => "3",
+        }.to_string()
     }
 }
 
@@ -95,6 +108,15 @@ pub struct PreprocessConfig {
     pub min_width: u32,                // Largeur minimale
     pub min_height: u32,               // Hauteur minimale
     pub target_dpi: u32,               // DPI cible pour OCR
+    #[serde(default = "default_true")]
+    pub deskew: bool,                  // Redressement (détection d'inclinaison par profil de projection)
+    #[serde(default = "default_true")]
+    pub binarize: bool,                // Binarisation Otsu
+}
+
+/// Configurations enregistrées avant l'ajout d'une étape : activée, comme dans `Default`
+fn default_true() -> bool {
+    true
 }
 
 impl Default for PreprocessConfig {
@@ -106,10 +128,77 @@ impl Default for PreprocessConfig {
             min_width: 1200,
             min_height: 800,
             target_dpi: 300,
+            deskew: true,
+            binarize: true,
+        }
+    }
+}
+
+/// Moteur d'extraction PDF choisi à l'exécution
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PdfEngine {
+    /// Extraction lopdf (pure Rust)
+    Lopdf,
+    /// Extraction extractous (nécessite la feature `extractous`)
+    Extractous,
+    /// extractous si disponible, puis fallback lopdf
+    #[default]
+    Auto,
+}
+
+impl PdfEngine {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PdfEngine::Lopdf => "lopdf",
+            PdfEngine::Extractous => "extractous",
+            PdfEngine::Auto => "auto",
         }
     }
 }
 
+/// Événement frontend de progression OCR d'un document multi-pages
+pub const OCR_PROGRESS_EVENT: &str = "ocr-progress";
+
+/// Progression du traitement d'un document multi-pages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrProgress {
+    /// Pages terminées
+    pub page: usize,
+    pub total_pages: usize,
+    pub percent: f32,
+    /// Confiance de la dernière page terminée (None pour du texte natif)
+    pub confidence: Option<f32>,
+    pub elapsed_ms: u64,
+    /// Temps restant estimé d'après le temps moyen par page
+    pub eta_ms: u64,
+}
+
+impl OcrProgress {
+    pub fn new(page: usize, total_pages: usize, confidence: Option<f32>, elapsed: Duration) -> Self {
+        let percent = if total_pages == 0 { 100.0 } else { page as f32 / total_pages as f32 * 100.0 };
+        let eta_ms = if page == 0 {
+            0
+        } else {
+            let avg_page_ms = elapsed.as_millis() as f64 / page as f64;
+            (avg_page_ms * total_pages.saturating_sub(page) as f64).round() as u64
+        };
+
+        Self {
+            page,
+            total_pages,
+            percent,
+            confidence,
+            elapsed_ms: elapsed.as_millis() as u64,
+            eta_ms,
+        }
+    }
+}
+
+/// Callback de progression optionnel : l'usage bibliothèque ne dépend pas de Tauri,
+/// les commandes le branchent sur `OCR_PROGRESS_EVENT`
+pub type OcrProgressCallback = std::sync::Arc<dyn Fn(OcrProgress) + Send + Sync>;
+
 /// Configuration performance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceConfig {
@@ -144,6 +233,44 @@ pub struct OcrResult {
     pub ocr_blocks: Option<Vec<OCRBlock>>,  // Layout analysis blocks
 }
 
+impl OcrResult {
+    /// Page hOCR (mots Tesseract avec bbox et confiance), numérotée à partir de 1
+    pub fn to_hocr_page(&self, page_number: usize) -> String {
+        let (width, height) = self.metadata.image_dimensions;
+        let mut page = format!(
+            "<div class='ocr_page' id='page_{0}' title='bbox 0 0 {1} {2}; ppageno {3}'>\n<p class='ocr_par' id='par_{0}_1'>\n",
+            page_number, width, height, page_number.saturating_sub(1)
+        );
+        for (index, word) in self.bounding_boxes.iter().enumerate() {
+            page.push_str(&format!(
+                "<span class='ocrx_word' id='word_{}_{}' title='bbox {} {} {} {}; x_wconf {:.0}'>{}</span>\n",
+                page_number, index + 1,
+                word.x, word.y, word.x + word.width, word.y + word.height,
+                word.confidence * 100.0,
+                escape_html(&word.text)
+            ));
+        }
+        page.push_str("</p>\n</div>\n");
+        page
+    }
+}
+
+/// Document hOCR complet à partir de pages `to_hocr_page`
+pub fn hocr_document(pages: &[String]) -> String {
+    format!(
+        "<?xml version='1.0' encoding='UTF-8'?>\n<!DOCTYPE html>\n<html xmlns='http://www.w3.org/1999/xhtml'>\n<head>\n<meta http-equiv='Content-Type' content='text/html; charset=utf-8'/>\n<meta name='ocr-system' content='gravis-ocr'/>\n<meta name='ocr-capabilities' content='ocr_page ocr_par ocrx_word'/>\n</head>\n<body>\n{}</body>\n</html>\n",
+        pages.concat()
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&#39;")
+        .replace('"', "&quot;")
+}
+
 /// Résultat OCR par page (pour PDF)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OcrPageResult {
@@ -181,6 +308,10 @@ pub struct OcrMetadata {
 pub enum OcrError {
     #[error("Tesseract command failed: {0}")]
     TesseractCommand(String),
+
+    /// Binaire `tesseract` absent du PATH : l'OCR est désactivé, le texte natif reste traité
+    #[error("Tesseract is not installed. {0}")]
+    NotInstalled(String),
     
     #[error("Image processing failed: {0}")]
     ImageProcessing(String),
@@ -203,25 +334,147 @@ pub enum OcrError {
     #[error("Cache error: {0}")]
     Cache(String),
     
+    #[error("Transformer OCR unavailable: {0}")]
+    TransformerUnavailable(String),
+    
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
 pub type Result<T> = std::result::Result<T, OcrError>;
 
-/// Validation des langues Tesseract disponibles
+/// Instructions d'installation de Tesseract pour la plateforme courante
+pub fn tesseract_install_instructions() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Install it with `brew install tesseract tesseract-lang`, then restart GRAVIS."
+    } else if cfg!(target_os = "windows") {
+        "Install it from https://github.com/UB-Mannheim/tesseract/wiki and add its folder to PATH, then restart GRAVIS."
+    } else {
+        "Install it with your package manager (e.g. `apt install tesseract-ocr tesseract-ocr-fra`), then restart GRAVIS."
+    }
+}
+
+/// Erreur de lancement du binaire : `NotInstalled` s'il est introuvable
+pub fn tesseract_spawn_error(error: std::io::Error) -> OcrError {
+    if error.kind() == std::io::ErrorKind::NotFound {
+        OcrError::NotInstalled(tesseract_install_instructions().to_string())
+    } else {
+        OcrError::TesseractCommand(format!("Failed to execute tesseract: {}", error))
+    }
+}
+
+/// Validation des langues Tesseract disponibles (l'erreur liste tous les packs manquants)
 pub async fn validate_languages(languages: &[String]) -> Result<()> {
     let available = get_available_languages().await?;
     
-    for lang in languages {
-        if !available.contains(lang) {
-            return Err(OcrError::LanguageNotAvailable(lang.clone()));
-        }
+    let missing = missing_languages(languages, &available);
+    if !missing.is_empty() {
+        return Err(OcrError::LanguageNotAvailable(missing.join(", ")));
     }
     
     Ok(())
 }
 
+/// Événement frontend émis au démarrage quand des langues OCR par défaut manquent
+pub const OCR_LANGUAGES_MISSING_EVENT: &str = "ocr-languages-missing";
+
+/// Avertissement de démarrage : langues OCR demandées mais non installées
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrLanguageWarning {
+    /// `false` : binaire absent, OCR désactivé (documents à texte natif toujours traités)
+    pub tesseract_installed: bool,
+    pub requested: Vec<String>,
+    pub missing: Vec<String>,
+    pub tessdata_prefix: String,
+    pub message: String,
+}
+
+/// `TESSDATA_PREFIX` résolu, pour le diagnostic des packs de langues
+pub fn tessdata_prefix() -> String {
+    std::env::var("TESSDATA_PREFIX")
+        .unwrap_or_else(|_| "(unset, Tesseract default tessdata directory)".to_string())
+}
+
+/// Vérifie les langues par défaut de `TesseractConfig` via `validate_languages`.
+/// Ne fait jamais échouer le démarrage : renvoie un avertissement si l'OCR sera dégradé.
+pub async fn check_default_languages() -> Option<OcrLanguageWarning> {
+    let requested = TesseractConfig::default().languages;
+    let tessdata_prefix = tessdata_prefix();
+
+    let (missing, reason) = match validate_languages(&requested).await {
+        Ok(()) => return None,
+        Err(OcrError::NotInstalled(instructions)) => {
+            let message = format!(
+                "Tesseract is not installed: OCR is disabled for images and scanned PDFs, native-text documents are still processed. {}",
+                instructions
+            );
+            return Some(OcrLanguageWarning {
+                tesseract_installed: false,
+                missing: requested.clone(),
+                requested,
+                tessdata_prefix,
+                message,
+            });
+        }
+        Err(OcrError::LanguageNotAvailable(list)) => {
+            let missing: Vec<String> = list.split(", ").map(String::from).collect();
+            let reason = format!("Missing Tesseract language packs: {}", list);
+            (missing, reason)
+        }
+        // Tesseract absent ou illisible : aucune langue n'est utilisable
+        Err(e) => (requested.clone(), format!("Unable to list Tesseract languages ({})", e)),
+    };
+
+    let message = format!(
+        "{}. OCR quality will be degraded. Install them (e.g. `brew install tesseract-lang` or `apt install {}`) into the tessdata directory: {}",
+        reason,
+        missing.iter().map(|lang| format!("tesseract-ocr-{}", lang)).collect::<Vec<_>>().join(" "),
+        tessdata_prefix
+    );
+
+    Some(OcrLanguageWarning {
+        tesseract_installed: true,
+        requested,
+        missing,
+        tessdata_prefix,
+        message,
+    })
+}
+
+/// Langues demandées absentes des packs Tesseract installés (ordre conservé)
+pub fn missing_languages(requested: &[String], available: &[String]) -> Vec<String> {
+    requested
+        .iter()
+        .filter(|lang| !available.contains(lang))
+        .cloned()
+        .collect()
+}
+
+/// Résout les langues OCR d'une extraction : liste explicite validée contre
+/// `get_available_languages`, ou langues par défaut (eng+fra) si absente/vide.
+/// L'erreur liste tous les packs manquants.
+pub async fn resolve_ocr_languages(requested: Option<Vec<String>>) -> Result<Vec<String>> {
+    let mut languages: Vec<String> = Vec::new();
+    for lang in requested.unwrap_or_default() {
+        let lang = lang.trim().to_string();
+        if !lang.is_empty() && !languages.contains(&lang) {
+            languages.push(lang);
+        }
+    }
+
+    if languages.is_empty() {
+        return Ok(TesseractConfig::default().languages);
+    }
+
+    let available = get_available_languages().await?;
+    let missing = missing_languages(&languages, &available);
+    if !missing.is_empty() {
+        return Err(OcrError::LanguageNotAvailable(missing.join(", ")));
+    }
+
+    Ok(languages)
+}
+
 /// Obtenir les langues Tesseract disponibles
 pub async fn get_available_languages() -> Result<Vec<String>> {
     use std::process::Command;
@@ -231,7 +484,7 @@ pub async fn get_available_languages() -> Result<Vec<String>> {
             .arg("--list-langs")
             .output()
     }).await.map_err(|e| OcrError::TesseractCommand(format!("Failed to spawn task: {}", e)))?
-    .map_err(|e| OcrError::TesseractCommand(format!("Failed to execute tesseract: {}", e)))?;
+    .map_err(tesseract_spawn_error)?;
     
     if !output.status.success() {
         return Err(OcrError::TesseractCommand(
@@ -251,20 +504,51 @@ pub async fn get_available_languages() -> Result<Vec<String>> {
     Ok(languages)
 }
 
-/// Détection automatique du format de fichier
+/// Détection automatique du format de fichier.
+/// L'extension est recoupée avec le contenu (magic bytes) : le contenu l'emporte s'il est reconnu,
+/// ce qui couvre les fichiers sans extension (dépôts DirectChat) ou mal nommés.
 pub fn detect_file_format(path: &Path) -> Result<FileFormat> {
-    match path.extension().and_then(|ext| ext.to_str()) {
-        Some("pdf") => Ok(FileFormat::Pdf),
-        Some("png") => Ok(FileFormat::Png),
-        Some("jpg") | Some("jpeg") => Ok(FileFormat::Jpeg),
-        Some("tiff") | Some("tif") => Ok(FileFormat::Tiff),
-        Some("bmp") => Ok(FileFormat::Bmp),
-        _ => Err(OcrError::UnsupportedFormat(
+    match (format_from_extension(path), sniff_file_format(path)) {
+        (Some(by_extension), Some(by_content)) if by_extension != by_content => {
+            tracing::warn!("⚠️ {:?} has a {:?} extension but {:?} content, using {:?}",
+                           path, by_extension, by_content, by_content);
+            Ok(by_content)
+        }
+        (Some(by_extension), _) => Ok(by_extension),
+        (None, Some(by_content)) => {
+            tracing::debug!("🔍 {:?} detected as {:?} from content", path, by_content);
+            Ok(by_content)
+        }
+        (None, None) => Err(OcrError::UnsupportedFormat(
             path.to_string_lossy().to_string()
         )),
     }
 }
 
+fn format_from_extension(path: &Path) -> Option<FileFormat> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "pdf" => Some(FileFormat::Pdf),
+        "png" => Some(FileFormat::Png),
+        "jpg" | "jpeg" => Some(FileFormat::Jpeg),
+        "tiff" | "tif" => Some(FileFormat::Tiff),
+        "bmp" => Some(FileFormat::Bmp),
+        _ => None,
+    }
+}
+
+/// Format d'après les premiers octets (None si fichier illisible ou type non supporté)
+fn sniff_file_format(path: &Path) -> Option<FileFormat> {
+    let kind = infer::get_from_path(path).ok().flatten()?;
+    match kind.mime_type() {
+        "application/pdf" => Some(FileFormat::Pdf),
+        "image/png" => Some(FileFormat::Png),
+        "image/jpeg" => Some(FileFormat::Jpeg),
+        "image/tiff" => Some(FileFormat::Tiff),
+        "image/bmp" => Some(FileFormat::Bmp),
+        _ => None,
+    }
+}
+
 /// Formats de fichier supportés
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FileFormat {
@@ -284,7 +568,7 @@ pub async fn get_tesseract_version() -> Result<String> {
             .arg("--version")
             .output()
     }).await.map_err(|e| OcrError::TesseractCommand(format!("Failed to spawn task: {}", e)))?
-    .map_err(|e| OcrError::TesseractCommand(format!("Failed to execute tesseract: {}", e)))?;
+    .map_err(tesseract_spawn_error)?;
     
     if !output.status.success() {
         return Err(OcrError::TesseractCommand(
@@ -317,6 +601,100 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_detect_file_format_sniffs_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let png_bytes = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D];
+
+        // PNG renommé en .pdf : le contenu l'emporte
+        let misnamed = dir.path().join("scan.pdf");
+        std::fs::write(&misnamed, png_bytes).unwrap();
+        assert_eq!(detect_file_format(&misnamed).unwrap(), FileFormat::Png);
+
+        // Fichier temporaire sans extension
+        let extensionless = dir.path().join("gravis_temp_scan");
+        std::fs::write(&extensionless, b"%PDF-1.7\n%...").unwrap();
+        assert_eq!(detect_file_format(&extensionless).unwrap(), FileFormat::Pdf);
+
+        // Fichier absent : extension seule (insensible à la casse)
+        assert_eq!(detect_file_format(Path::new("absent/Photo.JPG")).unwrap(), FileFormat::Jpeg);
+        assert!(detect_file_format(&dir.path().join("notes.txt")).is_err());
+    }
+
+    #[test]
+    fn test_ocr_progress_eta() {
+        let progress = OcrProgress::new(2, 8, Some(0.9), Duration::from_secs(4));
+        assert_eq!(progress.percent, 25.0);
+        assert_eq!(progress.elapsed_ms, 4000);
+        assert_eq!(progress.eta_ms, 12000); // 2s/page × 6 pages restantes
+
+        let done = OcrProgress::new(8, 8, None, Duration::from_secs(16));
+        assert_eq!(done.percent, 100.0);
+        assert_eq!(done.eta_ms, 0);
+        assert_eq!(OcrProgress::new(0, 8, None, Duration::ZERO).eta_ms, 0);
+    }
+
+    #[test]
+    fn test_hocr_page_escapes_words() {
+        let result = OcrResult {
+            text: "R&D <2024>".to_string(),
+            confidence: 0.9,
+            language: "fra".to_string(),
+            bounding_boxes: vec![TesseractBoundingBox {
+                x: 10, y: 20, width: 30, height: 12,
+                text: "R&D".to_string(),
+                confidence: 0.91,
+                level: 5,
+            }],
+            processing_time: Duration::from_millis(5),
+            engine_used: "Tesseract".to_string(),
+            tesseract_version: String::new(),
+            metadata: OcrMetadata {
+                source_file: "scan.png".to_string(),
+                file_size_bytes: 0,
+                image_dimensions: (200, 100),
+                preprocessing_applied: Vec::new(),
+                psm_used: PageSegMode::Auto,
+                oem_used: OcrEngineMode::LstmOnly,
+                temp_files_created: Vec::new(),
+            },
+            ocr_blocks: None,
+        };
+
+        let page = result.to_hocr_page(1);
+        assert!(page.contains("title='bbox 0 0 200 100; ppageno 0'"));
+        assert!(page.contains("title='bbox 10 20 40 32; x_wconf 91'>R&amp;D</span>"));
+        assert!(hocr_document(&[page]).contains("<body>\n<div class='ocr_page'"));
+    }
+
+    #[test]
+    fn test_tesseract_spawn_error() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "No such file or directory");
+        let error = tesseract_spawn_error(missing);
+        assert!(matches!(error, OcrError::NotInstalled(_)));
+        assert!(error.to_string().contains(tesseract_install_instructions()));
+
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert!(matches!(tesseract_spawn_error(denied), OcrError::TesseractCommand(_)));
+    }
+
+    #[test]
+    fn test_preprocess_config_defaults_new_steps() {
+        // Configuration sérialisée avant l'ajout du redressement et de la binarisation
+        let legacy = r#"{"enabled":true,"enhance_contrast":true,"resize_for_ocr":true,"min_width":1200,"min_height":800,"target_dpi":300}"#;
+        let config: PreprocessConfig = serde_json::from_str(legacy).unwrap();
+        assert!(config.deskew && config.binarize);
+    }
+
+    #[test]
+    fn test_missing_languages() {
+        let available = vec!["eng".to_string(), "fra".to_string(), "osd".to_string()];
+        let requested = vec!["deu".to_string(), "eng".to_string(), "spa".to_string()];
+
+        assert_eq!(missing_languages(&requested, &available), vec!["deu".to_string(), "spa".to_string()]);
+        assert!(missing_languages(&available[..2], &available).is_empty());
+    }
+
     #[tokio::test]
     async fn test_validate_languages() {
         let languages = vec!["eng".to_string(), "fra".to_string()];
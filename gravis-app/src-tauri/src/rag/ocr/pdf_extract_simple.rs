@@ -1,12 +1,21 @@
 // GRAVIS OCR - Pipeline PDF Simple avec pdf-extract
 // Alternative la plus simple pour extraction de texte uniquement
 
-use super::{OcrError, Result, normalize_and_log, OCRBlock, BlockType, BoundingBox as SemanticBoundingBox};
-use pdf_extract::{extract_text, extract_text_from_mem};
+use super::{OcrError, Result, normalize_and_log, normalize_for_rag, OCRBlock, BlockType, BoundingBox as SemanticBoundingBox};
+use pdf_extract::{extract_text_by_pages, extract_text_from_mem};
 use std::path::Path;
 use std::time::{Duration, Instant};
 use tracing::{info, warn, debug};
 
+/// Séparateur des pages dans le texte extrait (form feed) : seule frontière de page
+/// fiable pour les traitements en aval (en-têtes/pieds de page, overlap entre pages)
+pub const PAGE_BREAK: char = '\x0C';
+
+/// Pages d'un texte extrait, `None` s'il ne porte aucune frontière de page réelle
+pub fn split_pages(text: &str) -> Option<Vec<&str>> {
+    text.contains(PAGE_BREAK).then(|| text.split(PAGE_BREAK).collect())
+}
+
 /// Configuration simple pour pdf-extract
 #[derive(Debug, Clone)]
 pub struct PdfExtractConfig {
@@ -68,12 +77,12 @@ impl SimplePdfExtractor {
             self.config.timeout,
             tokio::task::spawn_blocking({
                 let path = pdf_path.to_path_buf();
-                move || extract_text(&path)
+                move || extract_text_by_pages(&path)
             })
         ).await;
         
-        let raw_text = match result {
-            Ok(Ok(Ok(text))) => text,
+        let raw_pages = match result {
+            Ok(Ok(Ok(pages))) => pages,
             Ok(Ok(Err(e))) => {
                 return Err(OcrError::ImageProcessing(format!("pdf-extract failed: {:?}", e)));
             }
@@ -85,12 +94,14 @@ impl SimplePdfExtractor {
             }
         };
         
-        // Normalisation Unicode pour RAG
-        let text = if self.config.normalize_unicode {
-            normalize_and_log(&raw_text, "pdf-extract")
+        // Normalisation Unicode pour RAG, page par page : elle compacte les blancs
+        // et effacerait les frontières de page
+        let pages: Vec<String> = if self.config.normalize_unicode {
+            raw_pages.iter().map(|page| normalize_for_rag(page)).collect()
         } else {
-            raw_text
+            raw_pages
         };
+        let text = pages.join(&PAGE_BREAK.to_string());
         
         let processing_time = start_time.elapsed();
         let token_count = text.split_whitespace().count();
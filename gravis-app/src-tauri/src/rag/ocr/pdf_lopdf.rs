@@ -4,12 +4,17 @@
 use super::{
     OcrResult, OcrMetadata, TesseractProcessor, 
     TesseractConfig,
-    PageSegMode, OcrEngineMode, PreprocessConfig, OcrError, Result
+    PageSegMode, OcrEngineMode, PreprocessConfig, PerformanceConfig, OcrError, Result,
+    OcrProgress, OcrProgressCallback
 };
 // use image::{DynamicImage, ImageBuffer, Rgba};
 use lopdf::{Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 // use std::collections::HashMap;
 // use tokio::fs;
 use tracing::{info, debug, warn};
@@ -17,7 +22,8 @@ use tracing::{info, debug, warn};
 /// Configuration du pipeline PDF avec lopdf
 #[derive(Debug, Clone)]
 pub struct LopdFPipelineConfig {
-    /// Seuil minimum de tokens natifs pour éviter l'OCR
+    /// Seuil minimum de tokens natifs pour éviter l'OCR :
+    /// une page sous ce seuil est considérée comme scannée
     pub min_native_tokens: usize,
     
     /// Ratio minimum de surface de texte natif (0.0-1.0)
@@ -31,8 +37,17 @@ pub struct LopdFPipelineConfig {
     
     /// Activer le fallback vers image crate si pas de texte natif
     pub enable_image_fallback: bool,
+
+    /// Parallélisme du traitement des pages (`max_concurrent_jobs` pages à la fois)
+    pub performance: PerformanceConfig,
+
+    /// Nombre maximal de pages traitées (`None` : toutes les pages)
+    pub max_pages: Option<usize>,
 }
 
+/// Plafond historique du pipeline lopdf : au-delà, les pages sont ignorées (avec warning)
+const DEFAULT_MAX_PAGES: usize = 10;
+
 impl Default for LopdFPipelineConfig {
     fn default() -> Self {
         Self {
@@ -50,6 +65,8 @@ impl Default for LopdFPipelineConfig {
                     min_width: 100,
                     min_height: 30,
                     target_dpi: 300,
+                    deskew: true,
+                    binarize: true,
                 },
                 confidence_threshold: 0.6,
                 temp_dir: std::env::temp_dir().join("gravis_lopdf_pipeline"),
@@ -57,6 +74,8 @@ impl Default for LopdFPipelineConfig {
                 timeout: Duration::from_secs(30),
             },
             enable_image_fallback: true,
+            performance: PerformanceConfig::default(),
+            max_pages: Some(DEFAULT_MAX_PAGES),
         }
     }
 }
@@ -72,6 +91,66 @@ pub struct LopdFPageResult {
     pub decision: PageProcessingDecision,
 }
 
+/// Confiance attribuée au texte natif (pas de score d'extraction)
+const NATIVE_TEXT_CONFIDENCE: f32 = 0.95;
+
+/// Qualité d'extraction d'une page, pour repérer les pages à rescanner
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageQuality {
+    pub page_number: usize,
+    /// Part du texte de la page obtenue sans OCR (0.0-1.0)
+    pub native_text_ratio: f32,
+    /// Confiance Tesseract, `None` si la page n'a pas été OCRisée
+    pub ocr_confidence: Option<f32>,
+    /// Caractères du texte retenu (natif s'il existe, OCR sinon)
+    pub char_count: usize,
+    /// Confiance retenue : texte natif, confiance OCR, ou 0 pour une page vide
+    pub confidence: f32,
+}
+
+impl PageQuality {
+    pub fn from_page(page: &LopdFPageResult) -> Self {
+        let native_chars = page.native_text.trim().chars().count();
+        let ocr_chars = page.ocr_result.as_ref().map_or(0, |ocr| ocr.text.trim().chars().count());
+        let ocr_confidence = page.ocr_result.as_ref().map(|ocr| ocr.confidence);
+
+        // Même priorité que l'assemblage du texte : natif s'il n'est pas vide
+        let (char_count, confidence) = if native_chars > 0 {
+            (native_chars, NATIVE_TEXT_CONFIDENCE)
+        } else {
+            (ocr_chars, ocr_confidence.filter(|_| ocr_chars > 0).unwrap_or(0.0))
+        };
+
+        Self {
+            page_number: page.page_number,
+            native_text_ratio: native_chars as f32 / native_chars.max(ocr_chars).max(1) as f32,
+            ocr_confidence,
+            char_count,
+            confidence,
+        }
+    }
+}
+
+/// Confiance moyenne des pages ayant du texte (0.5 si aucune)
+pub fn average_page_confidence(pages: &[PageQuality]) -> f32 {
+    let with_text: Vec<f32> = pages.iter()
+        .filter(|page| page.char_count > 0)
+        .map(|page| page.confidence)
+        .collect();
+    if with_text.is_empty() {
+        0.5
+    } else {
+        with_text.iter().sum::<f32>() / with_text.len() as f32
+    }
+}
+
+/// Page la moins fiable (confiance la plus basse, puis la moins de texte)
+pub fn worst_page(pages: &[PageQuality]) -> Option<&PageQuality> {
+    pages.iter().min_by(|a, b| {
+        a.confidence.total_cmp(&b.confidence).then(a.char_count.cmp(&b.char_count))
+    })
+}
+
 /// Décision de traitement pour une page
 #[derive(Debug, Clone)]
 pub enum PageProcessingDecision {
@@ -103,6 +182,7 @@ pub struct LopdFProcessor {
     config: LopdFPipelineConfig,
     #[allow(dead_code)]
     tesseract: TesseractProcessor,
+    progress_callback: Option<OcrProgressCallback>,
 }
 
 impl LopdFProcessor {
@@ -116,8 +196,15 @@ impl LopdFProcessor {
         Ok(Self {
             config,
             tesseract,
+            progress_callback: None,
         })
     }
+
+    /// Recevoir la progression page par page (page X / N, confiance, temps restant)
+    pub fn with_progress_callback(mut self, callback: OcrProgressCallback) -> Self {
+        self.progress_callback = Some(callback);
+        self
+    }
     
     /// Traiter un document PDF complet
     pub async fn process_pdf(&self, pdf_path: &Path) -> Result<Vec<LopdFPageResult>> {
@@ -129,20 +216,50 @@ impl LopdFProcessor {
             .map_err(|e| OcrError::ImageProcessing(format!("Failed to open PDF with lopdf: {}", e)))?;
         
         let pages = document.get_pages();
-        let page_count = pages.len();
-        info!("📖 PDF has {} pages", page_count);
+        let total_pages = pages.len();
+        let page_ids: Vec<ObjectId> = pages.values()
+            .copied()
+            .take(self.config.max_pages.unwrap_or(usize::MAX))
+            .collect();
+        let page_count = page_ids.len();
+        if page_count < total_pages {
+            warn!("⚠️ PDF has {} pages, only the first {} are processed (max_pages)", total_pages, page_count);
+        }
+        let max_concurrent = self.config.performance.max_concurrent_jobs.max(1);
+        info!("📖 PDF has {} pages (up to {} processed concurrently)", total_pages, max_concurrent);
         
-        let mut results = Vec::new();
+        // Pages indépendantes (texte natif puis fallback OCR), traitées dans le pool bloquant
+        let document = Arc::new(document);
+        let config = Arc::new(self.config.clone());
+        let mut completed = 0;
+        let page_results = run_pages_blocking(
+            page_count,
+            max_concurrent,
+            move |page_index| Self::process_page(&config, &document, page_ids[page_index], page_index + 1),
+            |_, page_result| {
+                completed += 1;
+                if let Some(callback) = &self.progress_callback {
+                    let confidence = page_result.as_ref().ok()
+                        .and_then(|page| page.ocr_result.as_ref())
+                        .map(|ocr| ocr.confidence);
+                    callback(OcrProgress::new(completed, page_count, confidence, start_time.elapsed()));
+                }
+            },
+        ).await;
         
-        // Traiter chaque page
-        for (page_index, (page_id, _)) in pages.iter().enumerate().take(10) {
-            match self.process_page(&document, (*page_id, 0), page_index + 1).await {
-                Ok(page_result) => {
-                    info!("✅ Page {} processed: {:?}", page_index + 1, page_result.decision);
+        let mut results = Vec::with_capacity(page_count);
+        for (page_index, page_result) in page_results.into_iter().enumerate() {
+            let page_number = page_index + 1;
+            match page_result {
+                Some(Ok(page_result)) => {
+                    info!("✅ Page {} processed: {:?}", page_number, page_result.decision);
                     results.push(page_result);
                 }
-                Err(e) => {
-                    warn!("❌ Failed to process page {}: {}", page_index + 1, e);
+                Some(Err(e)) => {
+                    warn!("❌ Failed to process page {}: {}", page_number, e);
+                }
+                None => {
+                    warn!("❌ Page {} task aborted", page_number);
                 }
             }
         }
@@ -154,24 +271,24 @@ impl LopdFProcessor {
         Ok(results)
     }
     
-    /// Traiter une page individuelle
-    async fn process_page(&self, document: &Document, page_id: ObjectId, page_number: usize) -> Result<LopdFPageResult> {
+    /// Traiter une page individuelle (CPU-bound, exécuté dans le pool bloquant)
+    fn process_page(config: &LopdFPipelineConfig, document: &Document, page_id: ObjectId, page_number: usize) -> Result<LopdFPageResult> {
         let start_time = Instant::now();
         
         debug!("🔄 Processing page {} with lopdf", page_number);
         
         // Étape 1: Extraire le texte natif avec lopdf
-        let (native_text, text_objects) = self.extract_native_text(document, page_id).await?;
+        let (native_text, text_objects) = Self::extract_native_text(document, page_id)?;
         let token_count = native_text.split_whitespace().count();
         
         debug!("📝 Page {} native text: {} tokens, {} chars, {} objects", 
                page_number, token_count, native_text.len(), text_objects.len());
         
         // Étape 2: Décider du traitement nécessaire
-        if token_count >= self.config.min_native_tokens {
+        if token_count >= config.min_native_tokens {
             // Texte natif suffisant, pas besoin d'OCR
-            info!("📄 Page {}: Native text sufficient ({} tokens)", 
-                  page_number, token_count);
+            info!("📄 Page {}: Native text sufficient ({} tokens >= threshold {})",
+                  page_number, token_count, config.min_native_tokens);
             
             return Ok(LopdFPageResult {
                 page_number,
@@ -188,14 +305,14 @@ impl LopdFProcessor {
         
         // Étape 3: Fallback OCR si activé et pas assez de texte natif
         let mut ocr_result = None;
-        let decision = if self.config.enable_image_fallback {
-            info!("🔄 Page {}: Falling back to OCR (insufficient native text: {} tokens)", 
-                  page_number, token_count);
+        let decision = if config.enable_image_fallback {
+            info!("🔄 Page {}: Falling back to OCR (insufficient native text: {} tokens < threshold {})",
+                  page_number, token_count, config.min_native_tokens);
             
             // Note: Pour l'OCR, vous devrez convertir la page en image
             // lopdf ne fait que l'extraction de texte, pas le rendu
             // Vous pouvez utiliser des outils externes ou rester sur pdfium pour le rendu
-            match self.fallback_to_ocr(document, page_id, page_number).await {
+            match Self::fallback_to_ocr(document, page_id, page_number) {
                 Ok(result) => {
                     ocr_result = Some(result);
                     PageProcessingDecision::OcrFallback {
@@ -233,7 +350,7 @@ impl LopdFProcessor {
     }
     
     /// Extraire le texte natif d'une page PDF avec lopdf
-    async fn extract_native_text(&self, document: &Document, page_id: ObjectId) -> Result<(String, Vec<TextObject>)> {
+    fn extract_native_text(document: &Document, page_id: ObjectId) -> Result<(String, Vec<TextObject>)> {
         let mut all_text = String::new();
         let mut text_objects = Vec::new();
         
@@ -244,8 +361,8 @@ impl LopdFProcessor {
         if let Ok(page_dict) = page_obj.as_dict() {
             // Extraire le contenu de la page
             if let Ok(contents) = page_dict.get(b"Contents") {
-                let content_stream = self.extract_content_stream(document, contents)?;
-                let (text, objects) = self.parse_content_stream(&content_stream)?;
+                let content_stream = Self::extract_content_stream(document, contents)?;
+                let (text, objects) = Self::parse_content_stream(&content_stream)?;
                 all_text = text;
                 text_objects = objects;
             }
@@ -255,7 +372,7 @@ impl LopdFProcessor {
     }
     
     /// Extraire le flux de contenu de la page
-    fn extract_content_stream(&self, document: &Document, contents: &Object) -> Result<String> {
+    fn extract_content_stream(document: &Document, contents: &Object) -> Result<String> {
         match contents {
             Object::Reference(ref_id) => {
                 let stream_obj = document.get_object(*ref_id)
@@ -291,7 +408,7 @@ impl LopdFProcessor {
                 let mut combined_content = String::new();
                 for item in array {
                     if let Object::Reference(ref_id) = item {
-                        let content = self.extract_content_stream(document, &Object::Reference(*ref_id))?;
+                        let content = Self::extract_content_stream(document, &Object::Reference(*ref_id))?;
                         combined_content.push_str(&content);
                     }
                 }
@@ -302,7 +419,7 @@ impl LopdFProcessor {
     }
     
     /// Parser le flux de contenu pour extraire le texte et les objets
-    fn parse_content_stream(&self, content: &str) -> Result<(String, Vec<TextObject>)> {
+    fn parse_content_stream(content: &str) -> Result<(String, Vec<TextObject>)> {
         let mut text = String::new();
         let mut text_objects = Vec::new();
         
@@ -342,7 +459,7 @@ impl LopdFProcessor {
             
             // Détecter les opérations d'affichage de texte
             else if line.contains("Tj") || line.contains("TJ") {
-                let extracted_text = self.extract_text_from_show_operation(line);
+                let extracted_text = Self::extract_text_from_show_operation(line);
                 if !extracted_text.is_empty() {
                     text.push_str(&extracted_text);
                     text.push(' ');
@@ -362,7 +479,7 @@ impl LopdFProcessor {
     }
     
     /// Extraire le texte des opérations de show (Tj, TJ)
-    fn extract_text_from_show_operation(&self, line: &str) -> String {
+    fn extract_text_from_show_operation(line: &str) -> String {
         // Parser basique pour extraire le texte entre parenthèses ou crochets
         let mut text = String::new();
         
@@ -403,7 +520,7 @@ impl LopdFProcessor {
     }
     
     /// Fallback vers OCR pour les pages sans texte natif suffisant
-    async fn fallback_to_ocr(&self, _document: &Document, _page_id: ObjectId, page_number: usize) -> Result<OcrResult> {
+    fn fallback_to_ocr(_document: &Document, _page_id: ObjectId, page_number: usize) -> Result<OcrResult> {
         // Note: lopdf ne peut pas rendre les pages en images
         // Pour l'OCR, vous avez plusieurs options :
         // 1. Utiliser un autre outil pour convertir PDF -> image (pdf2image, poppler-utils)
@@ -436,9 +553,134 @@ impl LopdFProcessor {
     }
 }
 
+/// Exécuter `work` pour chaque page dans le pool bloquant (`spawn_blocking`), au plus
+/// `max_concurrent` pages à la fois. `on_done` est appelé dans l'ordre de complétion ;
+/// les résultats sont rendus dans l'ordre des pages (`None` si la tâche a paniqué).
+async fn run_pages_blocking<T, F>(
+    page_count: usize,
+    max_concurrent: usize,
+    work: F,
+    mut on_done: impl FnMut(usize, &T),
+) -> Vec<Option<T>>
+where
+    T: Send + 'static,
+    F: Fn(usize) -> T + Send + Sync + 'static,
+{
+    let work = Arc::new(work);
+    let semaphore = Arc::new(Semaphore::new(max_concurrent.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for page_index in 0..page_count {
+        let work = Arc::clone(&work);
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.unwrap();
+            (page_index, tokio::task::spawn_blocking(move || work(page_index)).await)
+        });
+    }
+
+    let mut results: Vec<Option<T>> = (0..page_count).map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((page_index, Ok(result))) => {
+                on_done(page_index, &result);
+                results[page_index] = Some(result);
+            }
+            Ok((page_index, Err(e))) => warn!("❌ Page {} task failed: {}", page_index + 1, e),
+            Err(e) => warn!("❌ Page task failed: {}", e),
+        }
+    }
+    results
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    fn page(page_number: usize, native_text: &str, ocr: Option<(&str, f32)>) -> LopdFPageResult {
+        LopdFPageResult {
+            page_number,
+            native_text: native_text.to_string(),
+            text_objects: Vec::new(),
+            ocr_result: ocr.map(|(text, confidence)| OcrResult {
+                text: text.to_string(),
+                confidence,
+                language: "fra".to_string(),
+                bounding_boxes: Vec::new(),
+                processing_time: Duration::ZERO,
+                engine_used: "Tesseract".to_string(),
+                tesseract_version: String::new(),
+                metadata: OcrMetadata {
+                    source_file: String::new(),
+                    file_size_bytes: 0,
+                    image_dimensions: (0, 0),
+                    preprocessing_applied: Vec::new(),
+                    psm_used: PageSegMode::Auto,
+                    oem_used: OcrEngineMode::LstmOnly,
+                    temp_files_created: Vec::new(),
+                },
+                ocr_blocks: None,
+            }),
+            processing_time: Duration::ZERO,
+            decision: PageProcessingDecision::NativeTextOnly { token_count: 0, text_objects_count: 0 },
+        }
+    }
+
+    #[test]
+    fn test_page_quality_reports_worst_page() {
+        let pages: Vec<PageQuality> = [
+            page(1, "Contrat de travail à durée indéterminée", None),
+            page(2, "", Some(("Rémunération brute annuelle : 42 000 euros", 0.88))),
+            page(3, "4", Some(("l1 ~~ ;: ra", 0.31))),
+            page(4, "", None),
+        ].iter().map(PageQuality::from_page).collect();
+
+        assert_eq!(pages[0].native_text_ratio, 1.0);
+        assert_eq!(pages[0].ocr_confidence, None);
+        assert_eq!(pages[1].native_text_ratio, 0.0);
+        assert_eq!(pages[1].confidence, 0.88);
+        // Texte natif quasi vide sur une page scannée : ratio faible
+        assert!(pages[2].native_text_ratio < 0.2);
+        assert_eq!(pages[3].char_count, 0);
+
+        assert_eq!(worst_page(&pages).map(|p| p.page_number), Some(4));
+        let average = average_page_confidence(&pages);
+        assert!((average - (0.95 + 0.88 + 0.95) / 3.0).abs() < 1e-6);
+        assert!(worst_page(&[]).is_none());
+    }
+    
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_pages_returned_in_page_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (running_in_work, peak_in_work) = (Arc::clone(&running), Arc::clone(&peak));
+
+        // Les premières pages sont les plus lentes : elles terminent après les suivantes
+        let mut completion_order = Vec::new();
+        let results = run_pages_blocking(
+            6,
+            3,
+            move |page_index| {
+                let now = running_in_work.fetch_add(1, Ordering::SeqCst) + 1;
+                peak_in_work.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(10 * (6 - page_index as u64)));
+                running_in_work.fetch_sub(1, Ordering::SeqCst);
+                page_index + 1
+            },
+            |page_index, _| completion_order.push(page_index),
+        ).await;
+
+        assert_eq!(results, (1..=6).map(Some).collect::<Vec<_>>());
+        assert_eq!(completion_order.len(), 6);
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn test_default_page_cap() {
+        assert_eq!(LopdFPipelineConfig::default().max_pages, Some(DEFAULT_MAX_PAGES));
+    }
     
     #[tokio::test]
     async fn test_lopdf_pipeline_creation() {
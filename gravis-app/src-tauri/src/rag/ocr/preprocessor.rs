@@ -2,9 +2,116 @@
 // Phase 2: Preprocessing intelligent sans leptess
 
 use super::{PreprocessConfig, Result};
-use image::{DynamicImage, ImageBuffer, Luma, GenericImageView};
+use image::{DynamicImage, GrayImage, ImageBuffer, Luma, GenericImageView};
+use imageproc::contrast::otsu_level;
+use imageproc::geometric_transformations::{rotate_about_center, Interpolation};
 use tracing::{debug, info};
 
+/// Inclinaison maximale recherchée (degrés)
+const MAX_SKEW_DEGREES: f32 = 15.0;
+/// En dessous de cette inclinaison, l'image est considérée droite (degrés)
+const MIN_SKEW_DEGREES: f32 = 0.3;
+/// Plus grand côté de l'image analysée pour la détection d'inclinaison
+const SKEW_ANALYSIS_MAX_SIDE: u32 = 1000;
+
+/// Estime l'inclinaison des lignes de texte (degrés, positif = descend vers la droite)
+/// par profil de projection : l'angle retenu maximise la netteté de l'histogramme des lignes.
+/// `None` si l'image est droite ou ne ressemble pas à du texte.
+pub fn estimate_skew_angle(gray: &GrayImage) -> Option<f32> {
+    let (width, height) = gray.dimensions();
+    if width < 16 || height < 16 {
+        return None;
+    }
+
+    let scale = (SKEW_ANALYSIS_MAX_SIDE as f32 / width.max(height) as f32).min(1.0);
+    let sample = if scale < 1.0 {
+        image::imageops::resize(
+            gray,
+            ((width as f32 * scale) as u32).max(1),
+            ((height as f32 * scale) as u32).max(1),
+            image::imageops::FilterType::Triangle,
+        )
+    } else {
+        gray.clone()
+    };
+
+    // Pixels "encre" (sous le seuil d'Otsu)
+    let threshold = otsu_level(&sample);
+    let ink: Vec<(f32, f32)> = sample
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| pixel.0[0] <= threshold)
+        .map(|(x, y, _)| (x as f32, y as f32))
+        .collect();
+
+    let total_pixels = (sample.width() * sample.height()) as usize;
+    if ink.len() < 50 || ink.len() > total_pixels / 2 {
+        return None;
+    }
+
+    let diagonal = ((sample.width() as f32).powi(2) + (sample.height() as f32).powi(2)).sqrt();
+    let score = |degrees: f32| projection_profile_score(&ink, degrees, diagonal);
+
+    // Recherche grossière (0.5°) puis affinage (0.1°) autour du meilleur angle
+    let best_in = |angles: Vec<f32>| {
+        angles
+            .into_iter()
+            .map(|angle| (angle, score(angle)))
+            .fold((0.0f32, f64::MIN), |best, candidate| if candidate.1 > best.1 { candidate } else { best })
+            .0
+    };
+    let steps = (MAX_SKEW_DEGREES / 0.5) as i32;
+    let coarse = best_in((-steps..=steps).map(|i| i as f32 * 0.5).collect());
+    let fine = best_in((-5..=5).map(|i| coarse + i as f32 * 0.1).collect());
+
+    if fine.abs() < MIN_SKEW_DEGREES {
+        None
+    } else {
+        Some(fine)
+    }
+}
+
+/// Netteté du profil horizontal après redressement de `degrees` :
+/// somme des carrés des écarts entre lignes voisines de l'histogramme
+fn projection_profile_score(ink: &[(f32, f32)], degrees: f32, diagonal: f32) -> f64 {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let bins = (2.0 * diagonal) as usize + 1;
+    let mut histogram = vec![0u32; bins];
+
+    for &(x, y) in ink {
+        let row = y * cos - x * sin + diagonal;
+        if row >= 0.0 && (row as usize) < bins {
+            histogram[row as usize] += 1;
+        }
+    }
+
+    histogram
+        .windows(2)
+        .map(|pair| {
+            let delta = pair[1] as f64 - pair[0] as f64;
+            delta * delta
+        })
+        .sum()
+}
+
+/// Redresse une image inclinée (résultat en niveaux de gris, fond blanc).
+/// Retourne l'image corrigée et l'angle détecté, `None` si aucune correction n'est nécessaire.
+pub fn deskew(image: &DynamicImage) -> Option<(DynamicImage, f32)> {
+    let gray = image.to_luma8();
+    let angle = estimate_skew_angle(&gray)?;
+    // rotate_about_center tourne dans le sens horaire : on applique l'angle opposé
+    let rotated = rotate_about_center(&gray, -angle.to_radians(), Interpolation::Bilinear, Luma([255u8]));
+    Some((DynamicImage::ImageLuma8(rotated), angle))
+}
+
+/// Binarisation Otsu (seuil global calculé sur l'histogramme)
+pub fn binarize_otsu(gray: &GrayImage) -> GrayImage {
+    let threshold = otsu_level(gray);
+    ImageBuffer::from_fn(gray.width(), gray.height(), |x, y| {
+        let pixel = gray.get_pixel(x, y).0[0];
+        Luma([if pixel > threshold { 255 } else { 0 }])
+    })
+}
+
 /// Preprocesseur d'images pour optimiser l'OCR
 pub struct ImagePreprocessor {
     config: PreprocessConfig,
@@ -29,20 +136,34 @@ impl ImagePreprocessor {
         // 1. Conversion en niveaux de gris pour OCR
         processed = DynamicImage::ImageLuma8(processed.to_luma8());
         debug!("📊 Converted to grayscale");
+
+        // 2. Redressement des documents inclinés
+        if self.config.deskew {
+            if let Some((deskewed, angle)) = deskew(&processed) {
+                processed = deskewed;
+                debug!("📊 Deskewed ({:.2}°)", angle);
+            }
+        }
         
-        // 2. Amélioration du contraste
+        // 3. Amélioration du contraste
         if self.config.enhance_contrast {
             processed = processed.adjust_contrast(20.0);
             debug!("📊 Enhanced contrast (+20)");
         }
         
-        // 3. Redimensionnement intelligent
+        // 4. Redimensionnement intelligent
         if self.config.resize_for_ocr {
             processed = self.smart_resize(processed)?;
         }
         
-        // 4. Filtrage de bruit basique
+        // 5. Filtrage de bruit basique
         processed = self.reduce_noise(processed)?;
+
+        // 6. Binarisation
+        if self.config.binarize {
+            processed = DynamicImage::ImageLuma8(binarize_otsu(&processed.to_luma8()));
+            debug!("📊 Applied Otsu binarization");
+        }
         
         let final_dims = processed.dimensions();
         info!("✅ Image preprocessed: {}x{} → {}x{}", 
@@ -368,6 +489,42 @@ mod tests {
         println!("✅ PSM recommendation test passed");
     }
     
+    fn create_lined_page(width: u32, height: u32) -> GrayImage {
+        // Lignes de "texte" horizontales de 3px toutes les 20px
+        ImageBuffer::from_fn(width, height, |x, y| {
+            if x > 20 && x < width - 20 && y % 20 < 3 {
+                Luma([0u8])
+            } else {
+                Luma([255u8])
+            }
+        })
+    }
+
+    #[test]
+    fn test_skew_detection_and_correction() {
+        let page = create_lined_page(400, 300);
+        assert_eq!(estimate_skew_angle(&page), None);
+
+        // Rotation horaire de 5° : les lignes descendent vers la droite
+        let tilted = rotate_about_center(&page, 5f32.to_radians(), Interpolation::Bilinear, Luma([255u8]));
+        let angle = estimate_skew_angle(&tilted).expect("skew should be detected");
+        assert!((angle - 5.0).abs() < 1.0, "detected angle {}", angle);
+
+        let (corrected, _) = deskew(&DynamicImage::ImageLuma8(tilted)).unwrap();
+        let residual = estimate_skew_angle(&corrected.to_luma8()).unwrap_or(0.0);
+        assert!(residual.abs() < 1.0, "residual angle {}", residual);
+    }
+
+    #[test]
+    fn test_binarize_otsu() {
+        let gradient = ImageBuffer::from_fn(64, 8, |x, _| Luma([(x * 4) as u8]));
+        let binary = binarize_otsu(&gradient);
+
+        assert!(binary.pixels().all(|pixel| pixel.0[0] == 0 || pixel.0[0] == 255));
+        assert_eq!(binary.get_pixel(0, 0).0[0], 0);
+        assert_eq!(binary.get_pixel(63, 0).0[0], 255);
+    }
+
     #[test]
     fn test_smart_resize() {
         let config = PreprocessConfig {
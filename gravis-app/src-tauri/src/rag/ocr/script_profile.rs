@@ -0,0 +1,159 @@
+// GRAVIS OCR - Profils Tesseract par écriture
+// Les réglages par défaut (PSM 6, bloc unique) conviennent aux écritures latines mais
+// produisent un texte haché sur le chinois/japonais (colonnes verticales, pas d'espaces)
+// et un ordre de lecture inversé sur l'arabe/hébreu.
+
+use serde::{Deserialize, Serialize};
+
+use super::{OcrEngineMode, PageSegMode};
+
+/// Packs Tesseract CJK (les variantes `_vert` couvrent l'écriture verticale)
+const CJK_LANGUAGES: &[&str] = &["chi_sim", "chi_tra", "jpn", "kor"];
+/// Packs Tesseract des écritures de droite à gauche
+const RTL_LANGUAGES: &[&str] = &["ara", "heb", "fas", "urd", "yid", "pus", "snd", "uig", "div", "syr"];
+
+/// Famille d'écriture dominante des langues demandées
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptFamily {
+    Latin,
+    Cjk,
+    Rtl,
+}
+
+impl ScriptFamily {
+    /// CJK prioritaire (segmentation la plus exigeante), puis RTL, sinon latin
+    pub fn detect(languages: &[String]) -> Self {
+        let base = |lang: &String| lang.trim_end_matches("_vert").to_string();
+        if languages.iter().map(base).any(|lang| CJK_LANGUAGES.contains(&lang.as_str())) {
+            ScriptFamily::Cjk
+        } else if languages.iter().map(base).any(|lang| RTL_LANGUAGES.contains(&lang.as_str())) {
+            ScriptFamily::Rtl
+        } else {
+            ScriptFamily::Latin
+        }
+    }
+}
+
+/// Réglages Tesseract adaptés à l'écriture
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptProfile {
+    pub family: ScriptFamily,
+    /// `None` : conserver le PSM configuré (écritures latines)
+    pub psm: Option<PageSegMode>,
+    pub oem: OcrEngineMode,
+    /// Packs verticaux installés ajoutés aux langues demandées
+    pub extra_languages: Vec<String>,
+    /// Variables passées à Tesseract (`-c clé=valeur`)
+    pub variables: Vec<(&'static str, &'static str)>,
+    /// Ordre de lecture des blocs de droite à gauche
+    pub right_to_left: bool,
+}
+
+impl ScriptProfile {
+    /// Profil des langues demandées ; `available` sert à n'ajouter que des packs verticaux installés
+    pub fn for_languages(languages: &[String], available: &[String]) -> Self {
+        let family = ScriptFamily::detect(languages);
+        match family {
+            ScriptFamily::Latin => Self {
+                family,
+                psm: None,
+                oem: OcrEngineMode::LstmOnly,
+                extra_languages: Vec::new(),
+                variables: Vec::new(),
+                right_to_left: false,
+            },
+            ScriptFamily::Cjk => Self {
+                family,
+                // Segmentation automatique : colonnes et lignes verticales détectées
+                psm: Some(PageSegMode::Auto),
+                oem: OcrEngineMode::LstmOnly,
+                extra_languages: languages.iter()
+                    .filter(|lang| CJK_LANGUAGES.contains(&lang.as_str()))
+                    .map(|lang| format!("{}_vert", lang))
+                    .filter(|vertical| available.contains(vertical) && !languages.contains(vertical))
+                    .collect(),
+                // Espaces parasites entre idéogrammes retirés après OCR (`join_cjk_characters`)
+                variables: Vec::new(),
+                right_to_left: false,
+            },
+            ScriptFamily::Rtl => Self {
+                family,
+                // Le moteur LSTM gère la bidirectionnalité ; la segmentation auto préserve l'ordre des colonnes
+                psm: Some(PageSegMode::Auto),
+                oem: OcrEngineMode::LstmOnly,
+                extra_languages: Vec::new(),
+                variables: vec![("preserve_interword_spaces", "1")],
+                right_to_left: true,
+            },
+        }
+    }
+}
+
+/// Idéogrammes, kana et ponctuation CJK ; le hangul est exclu (le coréen sépare ses mots par des espaces)
+fn is_unspaced_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{30FF}'     // Ponctuation CJK, hiragana, katakana
+        | '\u{3400}'..='\u{4DBF}'   // Extension A
+        | '\u{4E00}'..='\u{9FFF}'   // Idéogrammes unifiés
+        | '\u{F900}'..='\u{FAFF}'   // Idéogrammes de compatibilité
+        | '\u{FF00}'..='\u{FFEF}'   // Formes pleine chasse
+    )
+}
+
+/// Retire les espaces insérés par Tesseract entre deux caractères chinois/japonais
+/// (les espaces entre idéogrammes et texte latin sont conservés)
+pub fn join_cjk_characters(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut joined = String::with_capacity(text.len());
+    for (index, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let previous = joined.chars().next_back();
+            let next = chars[index + 1..].iter().find(|next| **next != ' ');
+            if previous.is_some_and(is_unspaced_cjk) && next.is_some_and(|next| is_unspaced_cjk(*next)) {
+                continue;
+            }
+        }
+        joined.push(c);
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn langs(codes: &[&str]) -> Vec<String> {
+        codes.iter().map(|code| code.to_string()).collect()
+    }
+
+    #[test]
+    fn test_script_profiles() {
+        let available = langs(&["eng", "fra", "jpn", "jpn_vert", "chi_sim", "ara"]);
+
+        let latin = ScriptProfile::for_languages(&langs(&["eng", "fra"]), &available);
+        assert_eq!(latin.family, ScriptFamily::Latin);
+        assert!(latin.psm.is_none() && latin.variables.is_empty());
+
+        // Pack vertical ajouté uniquement s'il est installé
+        let cjk = ScriptProfile::for_languages(&langs(&["eng", "jpn", "chi_sim"]), &available);
+        assert_eq!(cjk.family, ScriptFamily::Cjk);
+        assert!(matches!(cjk.psm, Some(PageSegMode::Auto)));
+        assert_eq!(cjk.extra_languages, langs(&["jpn_vert"]));
+
+        let rtl = ScriptProfile::for_languages(&langs(&["ara", "eng"]), &available);
+        assert_eq!(rtl.family, ScriptFamily::Rtl);
+        assert!(rtl.right_to_left);
+
+        assert_eq!(ScriptFamily::detect(&langs(&["jpn_vert"])), ScriptFamily::Cjk);
+    }
+
+    #[test]
+    fn test_join_cjk_characters() {
+        assert_eq!(join_cjk_characters("日本 語 の テキスト 。"), "日本語のテキスト。");
+        assert_eq!(join_cjk_characters("年度 2024 の 売上"), "年度 2024 の売上");
+        assert_eq!(join_cjk_characters("東京 Tokyo\n大阪  京都"), "東京 Tokyo\n大阪京都");
+        // Coréen : espaces entre mots conservés
+        assert_eq!(join_cjk_characters("한국어 텍스트"), "한국어 텍스트");
+    }
+}
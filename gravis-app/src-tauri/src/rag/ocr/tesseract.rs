@@ -4,8 +4,9 @@
 use super::{
     OcrResult, OcrMetadata, TesseractBoundingBox,
     PageSegMode, OcrEngineMode, PreprocessConfig, OcrError, Result,
-    LayoutAnalyzer, BoundingBox as SemanticBoundingBox
+    LayoutAnalyzer, LayoutAnalyzerConfig, ScriptFamily, ScriptProfile, BoundingBox as SemanticBoundingBox
 };
+use super::script_profile::join_cjk_characters;
 use image::GenericImageView;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -18,6 +19,8 @@ use uuid::Uuid;
 pub struct TesseractProcessor {
     config: TesseractConfig,
     cache: Option<super::OcrCache>,
+    /// Réglages propres à l'écriture des langues demandées
+    script: ScriptProfile,
 }
 
 /// Configuration Tesseract détaillée
@@ -50,12 +53,25 @@ impl Default for TesseractConfig {
 
 impl TesseractProcessor {
     /// Créer un nouveau processeur Tesseract
-    pub async fn new(config: TesseractConfig) -> Result<Self> {
+    pub async fn new(mut config: TesseractConfig) -> Result<Self> {
         // Vérifier que Tesseract est disponible
         Self::verify_tesseract_installation().await?;
         
-        // Valider les langues demandées
-        super::validate_languages(&config.languages).await?;
+        // Valider les langues demandées (l'erreur liste tous les packs manquants)
+        let available = super::get_available_languages().await?;
+        let missing = super::missing_languages(&config.languages, &available);
+        if !missing.is_empty() {
+            return Err(OcrError::LanguageNotAvailable(missing.join(", ")));
+        }
+
+        // PSM/OEM et packs verticaux adaptés à l'écriture (CJK, droite à gauche)
+        let script = ScriptProfile::for_languages(&config.languages, &available);
+        if let Some(psm) = script.psm {
+            info!("🈶 {:?} script detected, using PSM {:?} instead of {:?}", script.family, psm, config.psm);
+            config.psm = psm;
+        }
+        config.oem = script.oem;
+        config.languages.extend(script.extra_languages.iter().cloned());
         
         // Créer le répertoire temporaire
         if !config.temp_dir.exists() {
@@ -70,7 +86,7 @@ impl TesseractProcessor {
         
         info!("✅ TesseractProcessor initialized with languages: {:?}", config.languages);
         
-        Ok(Self { config, cache })
+        Ok(Self { config, cache, script })
     }
     
     /// Traiter une image unique via Command
@@ -88,14 +104,15 @@ impl TesseractProcessor {
         info!("🔄 Processing image with Tesseract: {:?}", image_path);
         
         // 1. Preprocessing si activé
-        let processed_path = if self.config.preprocessing.enabled {
+        let (processed_path, preprocessing_applied) = if self.config.preprocessing.enabled {
             self.preprocess_image(image_path).await?
         } else {
-            image_path.to_path_buf()
+            (image_path.to_path_buf(), Vec::new())
         };
         
         // 2. Traitement OCR via Command
-        let result = self.run_tesseract_command(&processed_path).await?;
+        let mut result = self.run_tesseract_command(&processed_path).await?;
+        result.metadata.preprocessing_applied = preprocessing_applied;
         
         // 3. Mettre en cache le résultat
         if let Some(cache) = &self.cache {
@@ -125,8 +142,11 @@ impl TesseractProcessor {
            .arg(&output_base)  // Tesseract ajoute automatiquement .txt
            .arg("-l").arg(self.config.languages.join("+"))
            .arg("--psm").arg(self.config.psm.as_string())
-           .arg("--oem").arg("1")  // Force LSTM only (most compatible)
-           .arg("txt")  // Format texte de base
+           .arg("--oem").arg(self.config.oem.as_string());
+        for (name, value) in &self.script.variables {
+            cmd.arg("-c").arg(format!("{}={}", name, value));
+        }
+        cmd.arg("txt")  // Format texte de base
            .arg("tsv"); // Format TSV pour bounding boxes
         
         debug!("🔧 Tesseract command: {:?}", cmd);
@@ -138,7 +158,7 @@ impl TesseractProcessor {
         ).await
         .map_err(|_| OcrError::Timeout)?
         .map_err(|e| OcrError::TesseractCommand(format!("Task spawn failed: {}", e)))?
-        .map_err(|e| OcrError::TesseractCommand(format!("Command failed: {}", e)))?;
+        .map_err(super::tesseract_spawn_error)?;
         
         // Vérifier le succès de la commande
         if !result.status.success() {
@@ -157,6 +177,12 @@ impl TesseractProcessor {
         } else {
             String::new()
         };
+        // Le moteur LSTM sépare les idéogrammes par des espaces
+        let text = if self.script.family == ScriptFamily::Cjk {
+            join_cjk_characters(&text)
+        } else {
+            text
+        };
         
         let bounding_boxes = if output_tsv.exists() {
             self.parse_tsv_output(&output_tsv).await?
@@ -217,7 +243,10 @@ impl TesseractProcessor {
                 .collect();
 
             // Run layout analysis
-            let analyzer = LayoutAnalyzer::with_default_config();
+            let analyzer = LayoutAnalyzer::new(LayoutAnalyzerConfig {
+                right_to_left: self.script.right_to_left,
+                ..LayoutAnalyzerConfig::default()
+            });
             let blocks = analyzer.analyze_layout_with_text(
                 &boxes_with_text,
                 (image_dims.0 as f64, image_dims.1 as f64),
@@ -285,8 +314,9 @@ impl TesseractProcessor {
         Ok(boxes)
     }
     
-    /// Preprocessing d'image via image crate
-    async fn preprocess_image(&self, image_path: &Path) -> Result<PathBuf> {
+    /// Preprocessing d'image via image crate.
+    /// Retourne l'image preprocessée et les étapes effectivement appliquées.
+    async fn preprocess_image(&self, image_path: &Path) -> Result<(PathBuf, Vec<String>)> {
         let start = Instant::now();
         
         // Générer path temporaire pour l'image preprocessée
@@ -298,14 +328,15 @@ impl TesseractProcessor {
         let config = self.config.preprocessing.clone();
         
         // Preprocessing avancé avec Otsu via spawn_blocking
-        tokio::task::spawn_blocking(move || {
-            use image::{DynamicImage, ImageBuffer, Luma};
-            use imageproc::contrast::otsu_level;
+        let steps = tokio::task::spawn_blocking(move || {
+            use image::DynamicImage;
+            use super::preprocessor::{binarize_otsu, deskew};
             
             let image = image::open(&source_path)
                 .map_err(|e| OcrError::ImageProcessing(format!("Failed to load image: {}", e)))?;
             
             let mut processed = image;
+            let mut steps = Vec::new();
             
             // Redimensionnement si nécessaire (avant binarisation)
             if config.resize_for_ocr {
@@ -318,38 +349,41 @@ impl TesseractProcessor {
                         new_height, 
                         image::imageops::FilterType::Lanczos3
                     );
+                    steps.push(format!("resize:{}x{}", new_width, new_height));
+                }
+            }
+
+            // Redressement (photos/scans inclinés), avant binarisation
+            if config.deskew {
+                if let Some((deskewed, angle)) = deskew(&processed) {
+                    processed = deskewed;
+                    steps.push(format!("deskew:{:.2}deg", angle));
                 }
             }
             
             // Binarisation Otsu pour meilleure reconnaissance OCR
-            if config.enhance_contrast {
-                let gray = processed.to_luma8();
-                let threshold = otsu_level(&gray);
-                
-                let binary = ImageBuffer::from_fn(gray.width(), gray.height(), |x, y| {
-                    let pixel = gray.get_pixel(x, y).0[0];
-                    Luma([if pixel > threshold { 255 } else { 0 }])
-                });
-                
-                processed = DynamicImage::ImageLuma8(binary);
-            } else {
+            if config.binarize {
+                processed = DynamicImage::ImageLuma8(binarize_otsu(&processed.to_luma8()));
+                steps.push("binarize:otsu".to_string());
+            } else if config.enhance_contrast {
                 // Amélioration contraste classique si Otsu désactivé
                 processed = processed.adjust_contrast(15.0);
+                steps.push("contrast:+15".to_string());
             }
             
             // Sauvegarder en PNG pour qualité optimale
             processed.save(&target_path)
                 .map_err(|e| OcrError::ImageProcessing(format!("Failed to save processed image: {}", e)))?;
             
-            Ok(target_path)
+            Ok(steps)
         }).await
         .map_err(|e| OcrError::ImageProcessing(format!("Preprocessing task failed: {}", e)))?
         .map_err(|e: OcrError| e)?;
         
-        debug!("🖼️ Image preprocessed with Otsu binarization in {:.2}s: {:?}", 
-               start.elapsed().as_secs_f32(), processed_path);
+        debug!("🖼️ Image preprocessed in {:.2}s ({}): {:?}", 
+               start.elapsed().as_secs_f32(), steps.join(", "), processed_path);
         
-        Ok(processed_path)
+        Ok((processed_path, steps))
     }
     
     /// Obtenir les dimensions d'une image
@@ -378,9 +412,7 @@ impl TesseractProcessor {
                         ))
                     }
                 }
-                Err(_) => Err(OcrError::TesseractCommand(
-                    "Tesseract not found in PATH".to_string()
-                )),
+                Err(e) => Err(super::tesseract_spawn_error(e)),
             }
         }).await
         .map_err(|e| OcrError::TesseractCommand(format!("Task spawn failed: {}", e)))?
@@ -388,9 +420,20 @@ impl TesseractProcessor {
     
     /// Traitement par lots
     pub async fn process_batch(&self, image_paths: Vec<PathBuf>) -> Result<Vec<OcrResult>> {
+        self.process_batch_with_progress(image_paths, None).await
+    }
+
+    /// Traitement par lots avec progression optionnelle (une notification par image, dans l'ordre)
+    pub async fn process_batch_with_progress(
+        &self,
+        image_paths: Vec<PathBuf>,
+        progress: Option<&super::OcrProgressCallback>,
+    ) -> Result<Vec<OcrResult>> {
         use tokio::sync::Semaphore;
         use std::sync::Arc;
         
+        let start_time = std::time::Instant::now();
+        let total = image_paths.len();
         let semaphore = Arc::new(Semaphore::new(self.config.max_concurrent));
         let mut handles = Vec::new();
         
@@ -408,17 +451,36 @@ impl TesseractProcessor {
         
         // Collecter les résultats
         let mut results = Vec::new();
-        for handle in handles {
-            match handle.await {
-                Ok(Ok(result)) => results.push(result),
-                Ok(Err(e)) => error!("OCR processing failed: {}", e),
-                Err(e) => error!("Task join failed: {}", e),
+        for (index, handle) in handles.into_iter().enumerate() {
+            let confidence = match handle.await {
+                Ok(Ok(result)) => {
+                    let confidence = result.confidence;
+                    results.push(result);
+                    Some(confidence)
+                }
+                Ok(Err(e)) => {
+                    error!("OCR processing failed: {}", e);
+                    None
+                }
+                Err(e) => {
+                    error!("Task join failed: {}", e);
+                    None
+                }
+            };
+
+            if let Some(callback) = progress {
+                callback(super::OcrProgress::new(index + 1, total, confidence, start_time.elapsed()));
             }
         }
         
         Ok(results)
     }
     
+    /// Configuration du processeur
+    pub fn config(&self) -> &TesseractConfig {
+        &self.config
+    }
+    
     /// Nettoyer les fichiers temporaires
     pub async fn cleanup(&self) -> Result<()> {
         if self.config.temp_dir.exists() {
@@ -442,6 +504,7 @@ impl Clone for TesseractProcessor {
         Self {
             config: self.config.clone(),
             cache: None, // Simplifié pour le clonage
+            script: self.script.clone(),
         }
     }
 }
@@ -460,6 +523,15 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_oem_cli_values() {
+        // Valeurs `--oem` de Tesseract : 0 legacy, 1 LSTM, 3 selon les modèles
+        assert_eq!(OcrEngineMode::LstmOnly.as_string(), "1");
+        assert_eq!(OcrEngineMode::NeuralOnly.as_string(), "1");
+        assert_eq!(OcrEngineMode::LegacyOnly.as_string(), "0");
+        assert_eq!(OcrEngineMode::Default.as_string(), "3");
+    }
+    
     #[tokio::test]
     async fn test_tesseract_verification() {
         match TesseractProcessor::verify_tesseract_installation().await {
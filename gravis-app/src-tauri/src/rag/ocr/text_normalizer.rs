@@ -360,6 +360,79 @@ pub fn needs_normalization(input: &str) -> bool {
     score >= threshold
 }
 
+/// Seuils du filtre de bruit OCR (lignes parasites : symboles isolés, fragments)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrNoiseConfig {
+    /// Longueur minimale (en caractères, hors espaces de bord) d'une ligne conservée
+    pub min_line_chars: usize,
+    /// Part maximale de caractères spéciaux (hors espaces) tolérée dans une ligne
+    pub max_special_char_ratio: f32,
+    /// Caractères considérés comme parasites par l'OCR
+    pub special_chars: String,
+}
+
+impl Default for OcrNoiseConfig {
+    fn default() -> Self {
+        Self {
+            min_line_chars: 3,
+            max_special_char_ratio: 0.6,
+            special_chars: "©€@&+>\\|<~^_*#=".to_string(),
+        }
+    }
+}
+
+/// Symboles monétaires : une ligne qui en porte est une valeur, pas un artefact
+const CURRENCY_SYMBOLS: &str = "€$£¥%";
+
+impl OcrNoiseConfig {
+    fn is_noise_line(&self, line: &str) -> bool {
+        // Montants et numéros isolés ("42", "€", "5 %") : courts mais porteurs de sens
+        let carries_value = line
+            .chars()
+            .any(|c| c.is_ascii_digit() || CURRENCY_SYMBOLS.contains(c));
+        if !carries_value && line.chars().count() < self.min_line_chars {
+            return true;
+        }
+
+        let visible: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+        let special = visible
+            .iter()
+            .filter(|c| self.special_chars.contains(**c))
+            .filter(|c| !(carries_value && CURRENCY_SYMBOLS.contains(**c)))
+            .count();
+        special as f32 / visible.len() as f32 > self.max_special_char_ratio
+    }
+}
+
+/// Retire les lignes parasites d'une sortie OCR (symboles isolés, lignes trop courtes).
+///
+/// Les lignes vides sont conservées (séparateurs de paragraphes pour le chunking).
+/// Retourne le texte nettoyé et la part de lignes non vides retirées (0.0-1.0).
+pub fn strip_ocr_noise(text: &str, config: &OcrNoiseConfig) -> (String, f32) {
+    let mut kept = Vec::new();
+    let mut content_lines = 0usize;
+    let mut noise_lines = 0usize;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            kept.push(line);
+            continue;
+        }
+
+        content_lines += 1;
+        if config.is_noise_line(trimmed) {
+            noise_lines += 1;
+            trace!(line = trimmed, "OCR noise line removed");
+        } else {
+            kept.push(line);
+        }
+    }
+
+    let noise_ratio = if content_lines == 0 { 0.0 } else { noise_lines as f32 / content_lines as f32 };
+    (kept.join("\n"), noise_ratio)
+}
+
 /// Compte les ligatures dans un texte brut
 pub fn count_ligatures(input: &str) -> usize {
     input.chars()
@@ -524,6 +597,33 @@ mod tests {
         assert!(report.applied, "Should have applied normalization");
     }
 
+    #[test]
+    fn test_strip_ocr_noise() {
+        let config = OcrNoiseConfig::default();
+        let input = "Rapport annuel 2024\n©€ @|\nok\n\nChiffre d'affaires : 120 000 €\n> + & >";
+        let (cleaned, noise_ratio) = strip_ocr_noise(input, &config);
+
+        assert_eq!(cleaned, "Rapport annuel 2024\n\nChiffre d'affaires : 120 000 €");
+        assert!((noise_ratio - 0.6).abs() < 1e-6, "3 lignes sur 5 sont du bruit: {}", noise_ratio);
+
+        // Seuils configurables : lignes courtes acceptées
+        let lenient = OcrNoiseConfig { min_line_chars: 1, ..OcrNoiseConfig::default() };
+        let (cleaned, _) = strip_ocr_noise(input, &lenient);
+        assert!(cleaned.contains("ok"));
+
+        assert_eq!(strip_ocr_noise("", &config), (String::new(), 0.0));
+    }
+
+    #[test]
+    fn test_strip_ocr_noise_keeps_short_amounts() {
+        let config = OcrNoiseConfig::default();
+        let input = "Total\n12\n€\n5 %\n@|\n~";
+        let (cleaned, noise_ratio) = strip_ocr_noise(input, &config);
+
+        assert_eq!(cleaned, "Total\n12\n€\n5 %");
+        assert!((noise_ratio - 2.0 / 6.0).abs() < 1e-6, "2 lignes sur 6 sont du bruit: {}", noise_ratio);
+    }
+
     #[test]
     fn test_text_cleaner_api() {
         // Test de l'API TextCleaner compacte
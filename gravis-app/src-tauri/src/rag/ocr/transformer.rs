@@ -0,0 +1,270 @@
+// GRAVIS OCR - Transformer OCR (TrOCR via ONNX Runtime)
+// Fallback basse confiance pour Tesseract, activé via la feature `trocr`
+
+use super::{OcrError, Result};
+use std::path::PathBuf;
+use tracing::{debug, info};
+
+/// Taille d'entrée attendue par l'encodeur ViT de TrOCR
+#[cfg_attr(not(feature = "trocr"), allow(dead_code))]
+const TROCR_IMAGE_SIZE: u32 = 384;
+
+/// Configuration du modèle TrOCR exporté en ONNX
+///
+/// Le répertoire doit contenir `encoder_model.onnx`, `decoder_model.onnx`
+/// et `tokenizer.json` (export `optimum` standard de `microsoft/trocr-small-printed`).
+#[derive(Debug, Clone)]
+pub struct TrOCRConfig {
+    pub model_dir: PathBuf,
+    pub max_new_tokens: usize,
+    pub decoder_start_token_id: i64,
+    pub eos_token_id: i64,
+}
+
+impl Default for TrOCRConfig {
+    fn default() -> Self {
+        let model_dir = std::env::var("GRAVIS_TROCR_MODEL_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                std::env::var("HOME")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| std::env::temp_dir())
+                    .join(".gravis/models/trocr-small-printed")
+            });
+
+        Self {
+            model_dir,
+            max_new_tokens: 128,
+            decoder_start_token_id: 2, // </s> sert de token de départ pour TrOCR
+            eos_token_id: 2,
+        }
+    }
+}
+
+/// Résultat brut du décodage TrOCR
+#[derive(Debug, Clone)]
+pub struct TrOCRResult {
+    pub text: String,
+    pub confidence: f64, // Moyenne des probabilités max par token généré
+    pub processing_time_ms: u64,
+}
+
+/// Processeur TrOCR (encodeur ViT + décodeur autorégressif)
+#[cfg_attr(not(feature = "trocr"), allow(dead_code))]
+pub struct TrOCRProcessor {
+    #[cfg(feature = "trocr")]
+    encoder: std::sync::Arc<std::sync::Mutex<ort::session::Session>>,
+    #[cfg(feature = "trocr")]
+    decoder: std::sync::Arc<std::sync::Mutex<ort::session::Session>>,
+    #[cfg(feature = "trocr")]
+    tokenizer: std::sync::Arc<tokenizers::Tokenizer>,
+    config: TrOCRConfig,
+}
+
+impl std::fmt::Debug for TrOCRProcessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrOCRProcessor").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+impl TrOCRProcessor {
+    /// Charger le modèle TrOCR depuis `config.model_dir`
+    #[cfg(feature = "trocr")]
+    pub async fn new(config: TrOCRConfig) -> Result<Self> {
+        use ort::session::Session;
+
+        let encoder_path = config.model_dir.join("encoder_model.onnx");
+        let decoder_path = config.model_dir.join("decoder_model.onnx");
+        let tokenizer_path = config.model_dir.join("tokenizer.json");
+
+        for path in [&encoder_path, &decoder_path, &tokenizer_path] {
+            if !path.exists() {
+                return Err(OcrError::TransformerUnavailable(
+                    format!("TrOCR model file missing: {}", path.display())
+                ));
+            }
+        }
+
+        let (encoder, decoder, tokenizer) = tokio::task::spawn_blocking(move || {
+            let encoder = Session::builder()
+                .and_then(|b| b.commit_from_file(&encoder_path))
+                .map_err(|e| OcrError::TransformerUnavailable(format!("Encoder load failed: {}", e)))?;
+            let decoder = Session::builder()
+                .and_then(|b| b.commit_from_file(&decoder_path))
+                .map_err(|e| OcrError::TransformerUnavailable(format!("Decoder load failed: {}", e)))?;
+            let tokenizer = tokenizers::Tokenizer::from_file(&tokenizer_path)
+                .map_err(|e| OcrError::TransformerUnavailable(format!("Tokenizer load failed: {}", e)))?;
+            Ok::<_, OcrError>((encoder, decoder, tokenizer))
+        }).await
+        .map_err(|e| OcrError::TransformerUnavailable(format!("Failed to spawn task: {}", e)))??;
+
+        info!("✅ TrOCRProcessor initialized from {:?}", config.model_dir);
+
+        Ok(Self {
+            encoder: std::sync::Arc::new(std::sync::Mutex::new(encoder)),
+            decoder: std::sync::Arc::new(std::sync::Mutex::new(decoder)),
+            tokenizer: std::sync::Arc::new(tokenizer),
+            config,
+        })
+    }
+
+    /// Sans la feature `trocr`, le processeur n'est jamais disponible
+    #[cfg(not(feature = "trocr"))]
+    pub async fn new(config: TrOCRConfig) -> Result<Self> {
+        debug!("TrOCR requested but feature disabled (model dir: {:?})", config.model_dir);
+        Err(OcrError::TransformerUnavailable(
+            "GRAVIS was built without the `trocr` feature".to_string()
+        ))
+    }
+
+    /// Traiter une image encodée (PNG/JPEG) en mémoire
+    #[cfg(feature = "trocr")]
+    pub async fn process_image(&self, image_data: &[u8]) -> Result<TrOCRResult> {
+        let start_time = std::time::Instant::now();
+
+        let pixel_values = Self::preprocess(image_data)?;
+        let encoder = self.encoder.clone();
+        let decoder = self.decoder.clone();
+        let tokenizer = self.tokenizer.clone();
+        let config = self.config.clone();
+
+        let (text, confidence) = tokio::task::spawn_blocking(move || {
+            Self::generate(&encoder, &decoder, &tokenizer, &config, pixel_values)
+        }).await
+        .map_err(|e| OcrError::TransformerUnavailable(format!("Failed to spawn task: {}", e)))??;
+
+        let processing_time_ms = start_time.elapsed().as_millis() as u64;
+        info!("✅ TrOCR completed: {} characters, {:.1}% confidence, {}ms",
+              text.len(), confidence * 100.0, processing_time_ms);
+
+        Ok(TrOCRResult { text, confidence, processing_time_ms })
+    }
+
+    #[cfg(not(feature = "trocr"))]
+    pub async fn process_image(&self, _image_data: &[u8]) -> Result<TrOCRResult> {
+        Err(OcrError::TransformerUnavailable(
+            "GRAVIS was built without the `trocr` feature".to_string()
+        ))
+    }
+
+    /// Redimensionner en 384x384 RGB et normaliser vers [-1, 1] (layout NCHW)
+    #[cfg(feature = "trocr")]
+    fn preprocess(image_data: &[u8]) -> Result<Vec<f32>> {
+        let img = image::load_from_memory(image_data)
+            .map_err(|e| OcrError::ImageProcessing(format!("Failed to decode image: {}", e)))?;
+        let rgb = img
+            .resize_exact(TROCR_IMAGE_SIZE, TROCR_IMAGE_SIZE, image::imageops::FilterType::Triangle)
+            .to_rgb8();
+
+        let plane = (TROCR_IMAGE_SIZE * TROCR_IMAGE_SIZE) as usize;
+        let mut pixel_values = vec![0f32; 3 * plane];
+        for (i, pixel) in rgb.pixels().enumerate() {
+            for c in 0..3 {
+                pixel_values[c * plane + i] = (pixel[c] as f32 / 255.0 - 0.5) / 0.5;
+            }
+        }
+
+        Ok(pixel_values)
+    }
+
+    /// Décodage glouton autorégressif
+    #[cfg(feature = "trocr")]
+    fn generate(
+        encoder: &std::sync::Mutex<ort::session::Session>,
+        decoder: &std::sync::Mutex<ort::session::Session>,
+        tokenizer: &tokenizers::Tokenizer,
+        config: &TrOCRConfig,
+        pixel_values: Vec<f32>,
+    ) -> Result<(String, f64)> {
+        use ort::value::Tensor;
+
+        let ort_err = |e: ort::Error| OcrError::TesseractCommand(format!("TrOCR inference failed: {}", e));
+        let size = TROCR_IMAGE_SIZE as usize;
+
+        // 1. Encodeur: pixel_values -> last_hidden_state
+        let (hidden_shape, hidden_states) = {
+            let mut encoder = encoder.lock()
+                .map_err(|_| OcrError::TransformerUnavailable("Encoder lock poisoned".to_string()))?;
+            let input = Tensor::from_array(([1usize, 3, size, size], pixel_values)).map_err(ort_err)?;
+            let outputs = encoder.run(ort::inputs!["pixel_values" => input]).map_err(ort_err)?;
+            let (shape, data) = outputs["last_hidden_state"].try_extract_tensor::<f32>().map_err(ort_err)?;
+            (shape.iter().map(|&d| d as usize).collect::<Vec<_>>(), data.to_vec())
+        };
+
+        // 2. Décodeur: génération token par token
+        let mut decoder = decoder.lock()
+            .map_err(|_| OcrError::TransformerUnavailable("Decoder lock poisoned".to_string()))?;
+        let mut token_ids: Vec<i64> = vec![config.decoder_start_token_id];
+        let mut token_probs: Vec<f64> = Vec::new();
+
+        for _ in 0..config.max_new_tokens {
+            let input_ids = Tensor::from_array(([1usize, token_ids.len()], token_ids.clone())).map_err(ort_err)?;
+            let encoder_states = Tensor::from_array((hidden_shape.clone(), hidden_states.clone())).map_err(ort_err)?;
+            let outputs = decoder.run(ort::inputs![
+                "input_ids" => input_ids,
+                "encoder_hidden_states" => encoder_states,
+            ]).map_err(ort_err)?;
+
+            let (shape, logits) = outputs["logits"].try_extract_tensor::<f32>().map_err(ort_err)?;
+            let vocab_size = shape[2] as usize;
+            let last = &logits[logits.len() - vocab_size..];
+
+            let (next_id, prob) = softmax_argmax(last);
+            if next_id as i64 == config.eos_token_id {
+                break;
+            }
+            token_ids.push(next_id as i64);
+            token_probs.push(prob);
+        }
+
+        let ids: Vec<u32> = token_ids.iter().skip(1).map(|&id| id as u32).collect();
+        let text = tokenizer.decode(&ids, true)
+            .map_err(|e| OcrError::Parsing(format!("TrOCR decode failed: {}", e)))?;
+
+        let confidence = if token_probs.is_empty() {
+            0.0
+        } else {
+            token_probs.iter().sum::<f64>() / token_probs.len() as f64
+        };
+
+        debug!("TrOCR generated {} tokens", token_probs.len());
+        Ok((text.trim().to_string(), confidence))
+    }
+}
+
+/// Argmax + probabilité softmax associée (stable numériquement)
+#[cfg_attr(not(feature = "trocr"), allow(dead_code))]
+fn softmax_argmax(logits: &[f32]) -> (usize, f64) {
+    let (best_idx, best) = logits.iter()
+        .enumerate()
+        .fold((0, f32::NEG_INFINITY), |acc, (i, &v)| if v > acc.1 { (i, v) } else { acc });
+    let denom: f64 = logits.iter().map(|&v| ((v - best) as f64).exp()).sum();
+    (best_idx, if denom > 0.0 { 1.0 / denom } else { 0.0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_softmax_argmax() {
+        let (idx, prob) = softmax_argmax(&[0.0, 5.0, 1.0]);
+        assert_eq!(idx, 1);
+        assert!(prob > 0.9 && prob <= 1.0);
+
+        let (_, uniform) = softmax_argmax(&[1.0, 1.0, 1.0, 1.0]);
+        assert!((uniform - 0.25).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_missing_model_is_graceful() {
+        let config = TrOCRConfig {
+            model_dir: std::env::temp_dir().join("gravis_trocr_missing"),
+            ..TrOCRConfig::default()
+        };
+        assert!(matches!(
+            TrOCRProcessor::new(config).await,
+            Err(OcrError::TransformerUnavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+// GRAVIS RAG - Découpage AST (tree-sitter) pour le code source
+// Fournit les frontières de déclarations top-level utilisées par SmartChunker (AstFirst/Hybrid)
+
+use std::path::Path;
+use tracing::{debug, warn};
+use tree_sitter::{Language, Node, Parser};
+
+use crate::rag::ChunkType;
+
+/// Langages supportés par le découpage AST
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+}
+
+impl CodeLanguage {
+    /// Détection par extension de fichier
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "tsx" | "mts" | "cts" => Some(Self::TypeScript),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+        }
+    }
+
+    fn grammar(&self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        }
+    }
+}
+
+/// Langage d'un fichier source par extension : grammaire tree-sitter si disponible,
+/// sinon nom du langage seul (chunking heuristique)
+pub fn source_language_from_path(path: &Path) -> Option<String> {
+    if let Some(language) = CodeLanguage::from_path(path) {
+        return Some(language.name().to_string());
+    }
+
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    let name = match extension.as_str() {
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "cpp",
+        "cs" => "csharp",
+        "rb" => "ruby",
+        "php" => "php",
+        "swift" => "swift",
+        "scala" => "scala",
+        "sh" | "bash" | "zsh" => "shell",
+        "sql" => "sql",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Déclaration top-level (avec ses commentaires/attributs de tête)
+#[derive(Debug, Clone)]
+pub struct AstSegment {
+    pub byte_range: (usize, usize),
+    pub line_range: (usize, usize),
+    pub chunk_type: ChunkType,
+    pub symbol: Option<String>,
+}
+
+/// Découpe `content` en déclarations top-level.
+/// Retourne `None` si le parsing échoue ou ne trouve aucune fonction/classe (→ fallback heuristique).
+pub fn parse_top_level_segments(content: &str, language: CodeLanguage) -> Option<Vec<AstSegment>> {
+    let mut parser = Parser::new();
+    if let Err(e) = parser.set_language(&language.grammar()) {
+        warn!("tree-sitter grammar unavailable for {}: {}", language.name(), e);
+        return None;
+    }
+
+    let tree = parser.parse(content, None)?;
+    let root = tree.root_node();
+    if root.has_error() {
+        debug!("tree-sitter reported syntax errors in {} source, keeping valid declarations", language.name());
+    }
+
+    let source = content.as_bytes();
+    let mut segments: Vec<AstSegment> = Vec::new();
+    let mut leading: Option<Node> = None; // Commentaires/attributs à rattacher à la déclaration suivante
+
+    let mut cursor = root.walk();
+    for node in root.children(&mut cursor) {
+        if is_leading_trivia(node.kind()) {
+            leading.get_or_insert(node);
+            continue;
+        }
+
+        let start_node = leading.take().unwrap_or(node);
+        let declaration = unwrap_declaration(node);
+        segments.push(AstSegment {
+            byte_range: (start_node.start_byte(), node.end_byte()),
+            line_range: (start_node.start_position().row, node.end_position().row + 1),
+            chunk_type: classify_node(declaration.kind()),
+            symbol: declaration_symbol(declaration, source),
+        });
+    }
+
+    // Commentaires en fin de fichier
+    if let Some(trailing) = leading {
+        segments.push(AstSegment {
+            byte_range: (trailing.start_byte(), root.end_byte()),
+            line_range: (trailing.start_position().row, root.end_position().row + 1),
+            chunk_type: ChunkType::Comment,
+            symbol: None,
+        });
+    }
+
+    let has_declarations = segments
+        .iter()
+        .any(|s| matches!(s.chunk_type, ChunkType::Function | ChunkType::Class));
+    if !has_declarations {
+        debug!("No top-level declarations found in {} source", language.name());
+        return None;
+    }
+
+    Some(segments)
+}
+
+fn is_leading_trivia(kind: &str) -> bool {
+    kind.contains("comment") || kind == "attribute_item" || kind == "decorator"
+}
+
+/// `export function f()`, `@decorator def f()` → déclaration interne
+fn unwrap_declaration(node: Node) -> Node {
+    node.child_by_field_name("declaration")
+        .or_else(|| node.child_by_field_name("definition"))
+        .unwrap_or(node)
+}
+
+fn classify_node(kind: &str) -> ChunkType {
+    if kind.contains("function") || kind.contains("method") {
+        ChunkType::Function
+    } else if kind.contains("class")
+        || kind.contains("interface")
+        || matches!(kind, "struct_item" | "enum_item" | "trait_item" | "impl_item" | "enum_declaration")
+    {
+        ChunkType::Class
+    } else if kind.contains("comment") {
+        ChunkType::Comment
+    } else {
+        ChunkType::Module
+    }
+}
+
+fn declaration_symbol(node: Node, source: &[u8]) -> Option<String> {
+    node.child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("type")) // impl Foo
+        .and_then(|name| name.utf8_text(source).ok())
+        .map(|name| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_top_level_segments() {
+        let source = r#"use std::fmt;
+
+/// Un point
+#[derive(Debug)]
+struct Point { x: i32 }
+
+impl Point {
+    fn norm(&self) -> i32 { self.x }
+}
+
+fn main() {}
+"#;
+        let segments = parse_top_level_segments(source, CodeLanguage::Rust).unwrap();
+
+        assert_eq!(segments.len(), 4);
+        assert!(matches!(segments[0].chunk_type, ChunkType::Module));
+        assert!(matches!(segments[1].chunk_type, ChunkType::Class));
+        assert_eq!(segments[1].symbol.as_deref(), Some("Point"));
+        assert!(source[segments[1].byte_range.0..].starts_with("/// Un point"));
+        assert_eq!(segments[2].symbol.as_deref(), Some("Point"));
+        assert!(matches!(segments[3].chunk_type, ChunkType::Function));
+    }
+
+    #[test]
+    fn test_prose_falls_back() {
+        assert!(CodeLanguage::from_path(Path::new("notes.md")).is_none());
+        assert!(parse_top_level_segments("x = 1\ny = 2\n", CodeLanguage::Python).is_none());
+    }
+
+    #[test]
+    fn test_source_language_detection() {
+        assert_eq!(source_language_from_path(Path::new("src/lib.rs")).as_deref(), Some("rust"));
+        assert_eq!(source_language_from_path(Path::new("app/View.tsx")).as_deref(), Some("typescript"));
+        // Sans grammaire tree-sitter : reconnu comme code, chunké par heuristique
+        assert_eq!(source_language_from_path(Path::new("cmd/main.go")).as_deref(), Some("go"));
+        assert!(CodeLanguage::from_path(Path::new("cmd/main.go")).is_none());
+        assert!(source_language_from_path(Path::new("README.md")).is_none());
+    }
+}
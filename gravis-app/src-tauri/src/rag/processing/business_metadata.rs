@@ -133,6 +133,12 @@ static COMPANY_NAME_PATTERNS: Lazy<Regex> = Lazy::new(|| {
         .expect("Invalid company name pattern")
 });
 
+static FISCAL_YEAR_PATTERNS: Lazy<Regex> = Lazy::new(|| {
+    // "FY 2023", "fiscal year 2023", "exercice 2023", "exercice clos le 31 décembre 2023", "année fiscale 2023"
+    Regex::new(r"(?i)\b(?:FY\s?|fiscal\s+year\s+|financial\s+year\s+|ann[ée]e\s+fiscale\s+|exercice\s+(?:fiscal\s+)?(?:clos\s+(?:le\s+|au\s+)?\d{1,2}(?:er)?(?:\s+\p{L}+\s+|/\d{1,2}/))?)((?:19|20)\d{2})\b")
+        .expect("Invalid fiscal year pattern")
+});
+
 impl BusinessMetadataEnricher {
     pub fn new() -> Self {
         Self {
@@ -149,6 +155,8 @@ impl BusinessMetadataEnricher {
         fiscal_year: Option<i32>,
         page_number: Option<u32>,
     ) -> Result<BusinessMetadata> {
+        let fiscal_year = fiscal_year.or_else(|| self.detect_fiscal_year(content));
+
         // Extraction des KPIs financiers
         let financial_kpis = self.kpi_extractor.extract_kpis(content, fiscal_year)?;
         
@@ -174,6 +182,22 @@ impl BusinessMetadataEnricher {
         })
     }
 
+    /// Exercice fiscal mentionné explicitement dans le contenu (le plus cité, puis le plus récent).
+    /// Une année isolée ne suffit pas : `None` si aucune mention d'exercice.
+    pub fn detect_fiscal_year(&self, content: &str) -> Option<i32> {
+        let mut counts: HashMap<i32, usize> = HashMap::new();
+        for caps in FISCAL_YEAR_PATTERNS.captures_iter(content) {
+            if let Ok(year) = caps[1].parse::<i32>() {
+                *counts.entry(year).or_insert(0) += 1;
+            }
+        }
+
+        counts
+            .into_iter()
+            .max_by_key(|&(year, count)| (count, year))
+            .map(|(year, _)| year)
+    }
+
     /// Calcul du score de confiance des métadonnées
     fn calculate_metadata_confidence(
         &self,
@@ -459,6 +483,19 @@ mod tests {
         assert!(metadata.confidence_score > 0.5);
     }
 
+    #[test]
+    fn test_fiscal_year_detection() {
+        let enricher = BusinessMetadataEnricher::new();
+
+        assert_eq!(enricher.detect_fiscal_year("Revenue of $2.1 billion in FY 2023."), Some(2023));
+        assert_eq!(enricher.detect_fiscal_year("Comptes de l'exercice clos le 31 décembre 2022"), Some(2022));
+        assert_eq!(enricher.detect_fiscal_year("Exercice 2021 (rappel FY2022, exercice 2021)"), Some(2021));
+        assert_eq!(enricher.detect_fiscal_year("Founded in 1998, 120 employees in 2023."), None);
+
+        let metadata = enricher.enrich_business_content("Année fiscale 2024 : chiffre d'affaires de 3 M€", None, None).unwrap();
+        assert_eq!(metadata.fiscal_year, Some(2024));
+    }
+
     #[test]
     fn test_financial_kpi_extraction() {
         let extractor = FinancialKPIExtractor::new();
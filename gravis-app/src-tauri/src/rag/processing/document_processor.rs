@@ -9,23 +9,158 @@ use tracing::{info, warn, debug};
 use crate::rag::{
     GroupDocument, DocumentType, PdfStrategy, EnrichedChunk, ChunkType, ChunkMetadata,
     SourceType, ExtractionMethod, Priority, ChunkConfig, RagResult, RagError,
-    ChunkSource, sanitize_pdf_text
+    ChunkSource, ChunkStrategy, OversizedChunkPolicy, sanitize_pdf_text, estimate_tokens, CHARS_PER_TOKEN
 };
 use crate::rag::processing::FigureChunkBuilder;
+use crate::rag::processing::header_footer::{HeaderFooterConfig, strip_repeated_headers_footers};
+use crate::rag::ocr::pdf_extract_simple::{split_pages, PAGE_BREAK};
+use crate::rag::processing::page_overlap::{PageOverlapConfig, apply_page_overlap};
+use crate::rag::processing::table_detector::{find_table_regions, structure_table_content};
+use crate::rag::processing::smart_chunker::{SmartChunker, SmartChunkConfig};
+use crate::rag::processing::ast_chunker::{CodeLanguage, source_language_from_path};
+use crate::rag::processing::line_index::{LineIndex, parts_with_offsets, trimmed_span};
 use crate::rag::ocr::{
-    TesseractProcessor, OcrMetadata, PreprocessConfig, 
-    detect_file_format, FileFormat,
+    TesseractProcessor, TesseractConfig, TesseractBoundingBox, OcrMetadata, PreprocessConfig, OcrNoiseConfig, strip_ocr_noise,
+    detect_file_format, FileFormat, OcrProgress, OcrProgressCallback,
     pdf_extract_simple::{SimplePdfExtractor, PdfExtractConfig}
 };
 use crate::rag::search::custom_e5::CustomE5Embedder;
+use crate::rag::text::encoding::{decode_text_bytes, DecodedText, TEXT_ENCODING_FIELD};
 use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Clé de `EnrichedMetadata.custom_fields` contenant les métriques d'extraction (JSON)
+pub const EXTRACTION_QUALITY_FIELD: &str = "extraction_quality";
+
+/// Métriques de qualité de l'extraction PDF native, exposées pour diagnostiquer
+/// pourquoi un PDF a été traité comme scanné
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ExtractionQualityMetrics {
+    /// Part de caractères ASCII imprimables ou d'espaces
+    pub printable_ratio: f32,
+    /// Part de caractères alphabétiques
+    pub alpha_ratio: f32,
+    pub tokens_per_page: f32,
+    /// Score utilisé pour choisir la stratégie (> 0.6 : texte natif, sinon hybride/OCR)
+    pub detected_native_ratio: f32,
+    pub char_count: usize,
+    pub page_count: usize,
+}
+
+impl ExtractionQualityMetrics {
+    /// Calcule les métriques d'un texte extrait nativement
+    pub fn compute(text: &str, token_count: usize, page_count: usize) -> Self {
+        let text_len = text.len().max(1) as f32;
+        let alpha_ratio = text.chars().filter(|c| c.is_alphabetic()).count() as f32 / text_len;
+        let printable_ratio = text.chars()
+            .filter(|c| c.is_ascii_graphic() || c.is_whitespace())
+            .count() as f32 / text_len;
+        let page_count = page_count.max(1);
+
+        // Estimer la qualité du texte extrait - amélioration pour détection native
+        let detected_native_ratio = if text.len() > 200 {
+            // Si ratio printable > 0.9 et beaucoup de texte → texte natif de qualité
+            if printable_ratio > 0.9 && text.len() > 1000 {
+                1.0  // Parfait pour texte natif
+            } else {
+                (alpha_ratio * 1.2).min(1.0)
+            }
+        } else {
+            0.3 // Texte court mais présent
+        };
+
+        Self {
+            printable_ratio,
+            alpha_ratio,
+            tokens_per_page: token_count as f32 / page_count as f32,
+            detected_native_ratio,
+            char_count: text.chars().count(),
+            page_count,
+        }
+    }
+}
+
+/// Diagnostic de l'extraction native d'un PDF, sans ingestion
+pub async fn diagnose_pdf_extraction(path: &Path) -> Result<ExtractionQualityMetrics> {
+    let extractor = SimplePdfExtractor::new(PdfExtractConfig::default());
+    let result = extractor.extract_pdf_text(path).await
+        .map_err(|e| anyhow::anyhow!("PDF extraction failed: {}", e))?;
+
+    Ok(ExtractionQualityMetrics::compute(&result.text, result.token_count, result.page_dimensions.len()))
+}
+
+/// Caractères alphanumériques minimum pour qu'une extraction soit exploitable
+pub const DEFAULT_MIN_MEANINGFUL_CHARS: usize = 10;
+
+/// Caractères significatifs d'un texte extrait (lettres et chiffres, hors espaces et ponctuation)
+pub fn meaningful_char_count(text: &str) -> usize {
+    text.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+/// Identifiant déterministe d'un chunk : même document, même groupe, même rang → même ID.
+/// Le point Qdrant (UUID dérivé de l'ID) reste ainsi stable d'une ingestion à l'autre.
+pub fn deterministic_chunk_id(document_id: &str, group_id: &str, index: usize) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(group_id.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(document_id.as_bytes());
+    format!("chunk_{}_{}", &hasher.finalize().to_hex()[..16], index)
+}
+
+/// Identifiant déterministe d'un document : même fichier source (empreinte des octets), même contenu,
+/// même groupe → même ID, ce qui permet de retrouver (et purger) les points d'une ingestion précédente.
+/// Deux fichiers distincts dont l'extraction donne le même texte gardent des IDs distincts.
+pub fn deterministic_document_id(file_checksum: &str, document_content: &str, group_id: &str) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(group_id.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(file_checksum.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(document_content.as_bytes());
+    format!("doc_{}", &hasher.finalize().to_hex()[..32])
+}
+
+/// Remplace les identifiants des chunks (aléatoires à la création) par `deterministic_chunk_id`
+pub fn assign_deterministic_chunk_ids(chunks: &mut [EnrichedChunk], document_id: &str, group_id: &str) {
+    for (index, chunk) in chunks.iter_mut().enumerate() {
+        chunk.id = deterministic_chunk_id(document_id, group_id, index);
+    }
+}
+
+/// Ratio natif au-delà duquel un PDF est ingéré en texte seul (OCR jamais initialisé)
+pub const TEXT_ONLY_NATIVE_RATIO: f32 = 0.8;
+/// Taille minimale du texte natif pour le chemin texte seul (caractères)
+pub const TEXT_ONLY_MIN_CHARS: usize = 1000;
+
+/// Vrai si l'extraction native suffit à elle seule (texte abondant et fiable)
+pub fn is_text_only_pdf(quality: &ExtractionQualityMetrics, content: &str) -> bool {
+    quality.detected_native_ratio > TEXT_ONLY_NATIVE_RATIO && content.len() > TEXT_ONLY_MIN_CHARS
+}
+
+/// Progression d'un OCR sur une seule page (page 1 / 1)
+fn report_single_page_progress(progress: Option<&OcrProgressCallback>, confidence: f32, started: std::time::Instant) {
+    if let Some(callback) = progress {
+        callback(OcrProgress::new(1, 1, Some(confidence), started.elapsed()));
+    }
+}
+
+/// Texte extrait, type de document, méthode d'extraction et mots OCR (vide hors OCR)
+type ExtractedContent = (String, DocumentType, ExtractionMethod, Vec<TesseractBoundingBox>);
 
 /// Processeur de documents unifié avec intelligence OCR
 #[derive(Clone)]
 pub struct DocumentProcessor {
-    ocr_processor: TesseractProcessor,
+    /// Processeur Tesseract, construit au premier document nécessitant l'OCR
+    ocr_processor: Arc<OnceCell<TesseractProcessor>>,
+    ocr_config: TesseractConfig,
     #[allow(dead_code)]
     embedder: Arc<CustomE5Embedder>,
+    /// Filtre des lignes parasites appliqué au texte OCR
+    ocr_noise_config: OcrNoiseConfig,
+    /// Report de la fin de page sur le premier chunk de la page suivante (PDF)
+    page_overlap_config: PageOverlapConfig,
+    /// Caractères alphanumériques sous lesquels l'extraction est considérée vide
+    min_meaningful_chars: usize,
 }
 
 impl DocumentProcessor {
@@ -34,10 +169,81 @@ impl DocumentProcessor {
         ocr_processor: TesseractProcessor,
         embedder: Arc<CustomE5Embedder>,
     ) -> Result<Self> {
-        Ok(Self {
-            ocr_processor,
+        let ocr_config = ocr_processor.config().clone();
+        let mut processor = Self::with_lazy_ocr(ocr_config, embedder);
+        processor.ocr_processor = Arc::new(OnceCell::new_with(Some(ocr_processor)));
+        Ok(processor)
+    }
+
+    /// Initialise le processeur sans Tesseract : il n'est construit qu'au premier
+    /// document qui nécessite l'OCR (image, PDF scanné ou de qualité native insuffisante)
+    pub fn with_lazy_ocr(ocr_config: TesseractConfig, embedder: Arc<CustomE5Embedder>) -> Self {
+        Self {
+            ocr_processor: Arc::new(OnceCell::new()),
+            ocr_config,
             embedder,
-        })
+            ocr_noise_config: OcrNoiseConfig::default(),
+            page_overlap_config: PageOverlapConfig::default(),
+            min_meaningful_chars: DEFAULT_MIN_MEANINGFUL_CHARS,
+        }
+    }
+
+    /// Vrai si Tesseract a déjà été initialisé
+    pub fn is_ocr_initialized(&self) -> bool {
+        self.ocr_processor.initialized()
+    }
+
+    /// Processeur Tesseract, initialisé à la première demande
+    async fn ocr(&self) -> RagResult<&TesseractProcessor> {
+        self.ocr_processor
+            .get_or_try_init(|| async {
+                info!("🔧 Initializing Tesseract for first OCR-requiring document");
+                TesseractProcessor::new(self.ocr_config.clone()).await
+            })
+            .await
+            .map_err(RagError::Ocr)
+    }
+
+    /// Initialise Tesseract (s'il ne l'est pas déjà) et traite une petite image blanche :
+    /// le premier document OCR ne paie plus le chargement du moteur et des langues
+    pub async fn warm_up_ocr(&self) -> RagResult<()> {
+        let processor = self.ocr().await?;
+
+        let image_path = std::env::temp_dir().join(format!("gravis_warm_up_{}.png", std::process::id()));
+        image::RgbImage::from_pixel(64, 32, image::Rgb([255, 255, 255]))
+            .save(&image_path)
+            .map_err(|e| RagError::InvalidConfig(format!("Failed to save warm-up image: {}", e)))?;
+
+        let result = processor.process_image(&image_path).await;
+        let _ = std::fs::remove_file(&image_path);
+        result.map(|_| ()).map_err(RagError::Ocr)
+    }
+
+    /// Seuils du filtre de bruit appliqué après OCR
+    pub fn with_ocr_noise_config(mut self, config: OcrNoiseConfig) -> Self {
+        self.ocr_noise_config = config;
+        self
+    }
+
+    /// Seuil de caractères significatifs sous lequel `process_document` renvoie `EmptyExtraction`
+    pub fn with_min_meaningful_chars(mut self, min_meaningful_chars: usize) -> Self {
+        self.min_meaningful_chars = min_meaningful_chars;
+        self
+    }
+
+    /// Configurer l'overlap entre pages PDF
+    pub fn with_page_overlap_config(mut self, config: PageOverlapConfig) -> Self {
+        self.page_overlap_config = config;
+        self
+    }
+
+    /// Retire les lignes parasites du texte OCR
+    fn strip_ocr_noise(&self, text: &str) -> String {
+        let (cleaned, noise_ratio) = strip_ocr_noise(text, &self.ocr_noise_config);
+        if noise_ratio > 0.0 {
+            debug!("OCR noise filter: {:.1}% of lines removed", noise_ratio * 100.0);
+        }
+        cleaned
     }
 
     /// Point d'entrée principal: traite n'importe quel document
@@ -46,22 +252,44 @@ impl DocumentProcessor {
         file_path: &Path,
         group_id: &str,
         chunk_config: &ChunkConfig,
+    ) -> RagResult<GroupDocument> {
+        self.process_document_with_progress(file_path, group_id, chunk_config, None).await
+    }
+
+    /// `process_document` avec progression OCR optionnelle (pages OCRisées, confiance, temps restant)
+    #[tracing::instrument(name = "chunk", skip_all, fields(group_id = %group_id, file = %file_path.display()))]
+    pub async fn process_document_with_progress(
+        &self,
+        file_path: &Path,
+        group_id: &str,
+        chunk_config: &ChunkConfig,
+        progress: Option<&OcrProgressCallback>,
     ) -> RagResult<GroupDocument> {
         info!("Processing document: {:?}", file_path);
 
         // 1. Détection automatique du format
-        let (content, document_type, extraction_method) = match detect_file_format(file_path) {
-            Ok(FileFormat::Pdf) => self.process_pdf(file_path).await?,
+        let mut text_encoding = None;
+        let (content, document_type, extraction_method, ocr_words) = match detect_file_format(file_path) {
+            Ok(FileFormat::Pdf) => self.process_pdf(file_path, chunk_config.min_native_tokens, progress).await?,
             Ok(FileFormat::Png | FileFormat::Jpeg | FileFormat::Tiff | FileFormat::Bmp) => {
-                self.process_image(file_path).await?
+                self.process_image(file_path, progress).await?
             }
             Err(_) => {
                 // Format non supporté par OCR, traiter comme texte
                 debug!("Format not supported by OCR, treating as text: {:?}", file_path);
-                self.process_text(file_path).await?
+                let (decoded, document_type) = self.process_text(file_path).await?;
+                text_encoding = Some(decoded.encoding);
+                (decoded.text, document_type, ExtractionMethod::DirectRead, Vec::new())
             }
         };
 
+        // 2. En-têtes/pieds de page répétés sur les pages PDF (bruit pour la recherche)
+        let content = if matches!(document_type, DocumentType::PDF { .. }) {
+            strip_page_boilerplate(content, &chunk_config.header_footer)
+        } else {
+            content
+        };
+
         // 3. Normalisation Unicode optimisée avec cache et debug conditionnel
         let (normalized_content, norm_stats) = sanitize_pdf_text(&content)
             .map_err(|e| RagError::InvalidConfig(format!("Unicode normalization failed: {}", e)))?;
@@ -79,15 +307,37 @@ impl DocumentProcessor {
             );
         }
         
-        // 4. Chunking adaptatif selon le type de source
+        // Extraction vide (PDF scanné sans OCR, image blanche...) : rien à indexer
+        let meaningful_chars = meaningful_char_count(&normalized_content);
+        if meaningful_chars < self.min_meaningful_chars {
+            warn!("Empty extraction for {:?}: {} meaningful chars (min {})",
+                  file_path, meaningful_chars, self.min_meaningful_chars);
+            return Err(RagError::EmptyExtraction {
+                path: file_path.display().to_string(),
+                meaningful_chars,
+            });
+        }
+
+        // 4. Chunking adaptatif selon le type de source (code → stratégie du groupe)
         let source_type = self.determine_source_type(&extraction_method, &document_type);
-        let mut chunks = self.chunk_by_content_type(
-            &normalized_content,
-            source_type.clone(),
-            extraction_method.clone(),
-            chunk_config,
-            group_id,
-        ).await?;
+        let mut chunks = if let DocumentType::SourceCode { .. } = document_type {
+            self.chunk_source_code(
+                &normalized_content,
+                file_path,
+                source_type.clone(),
+                &extraction_method,
+                chunk_config,
+                group_id,
+            )?
+        } else {
+            self.chunk_by_content_type(
+                &normalized_content,
+                source_type.clone(),
+                extraction_method.clone(),
+                chunk_config,
+                group_id,
+            ).await?
+        };
         
         // GARDE-FOU: Si aucun chunk créé, créer un chunk avec tout le contenu
         if chunks.is_empty() && !normalized_content.trim().is_empty() {
@@ -101,8 +351,8 @@ impl DocumentProcessor {
             let fallback_chunk = EnrichedChunk {
                 id: format!("chunk_{}_fallback", uuid::Uuid::new_v4().simple()),
                 content: normalized_content.clone(),
-                start_line: 0,
-                end_line: normalized_content.lines().count(),
+                start_line: 1,
+                end_line: normalized_content.lines().count().max(1),
                 chunk_type: ChunkType::TextBlock,
                 embedding: None,
                 hash: String::new(),
@@ -116,6 +366,7 @@ impl DocumentProcessor {
                     ocr_metadata: None,
                     source_type: source_type.clone(),
                     extraction_method: extraction_method.clone(),
+                    page_overlap_len: 0,
                 },
                 group_id: group_id.to_string(),
                 source_spans: None,
@@ -128,7 +379,7 @@ impl DocumentProcessor {
         }
 
         // FALLBACK SPLIT: Si seulement 1 chunk et trop long, essayer de split simple
-        if chunks.len() == 1 && normalized_content.len() > chunk_config.chunk_size * 2 {
+        if chunks.len() == 1 && normalized_content.len() > chunk_config.target_chars() * 2 {
             tracing::info!(
                 chunks_count = chunks.len(),
                 content_len = normalized_content.len(),
@@ -137,7 +388,7 @@ impl DocumentProcessor {
             );
             
             let original_chunk = chunks.pop().unwrap();
-            let split_chunks = simple_text_split(&original_chunk.content, chunk_config);
+            let split_chunks = simple_text_split(&original_chunk.content, chunk_config, original_chunk.start_line.max(1));
             
             if split_chunks.len() > 1 {
                 tracing::info!("Successfully split into {} chunks", split_chunks.len());
@@ -165,39 +416,45 @@ impl DocumentProcessor {
             }
         }
         
-        // GARDE-FOU ULTIME: Si vraiment aucun chunk après tous les fallbacks, créer un chunk d'erreur
+        // GARDE-FOU ULTIME: aucun chunk exploitable → erreur typée plutôt qu'un vecteur inutile
+        chunks.retain(|chunk| !chunk.content.trim().is_empty());
         if chunks.is_empty() {
             tracing::error!("E2E CRITICAL: expected >0 chunks after all fallbacks for {:?}", file_path);
-            tracing::warn!("Creating emergency fallback chunk to prevent crash");
-            
-            // Créer un chunk d'urgence pour éviter la panique
-            let emergency_chunk = EnrichedChunk {
-                id: format!("chunk_{}_emergency", uuid::Uuid::new_v4().simple()),
-                content: format!("EXTRACTION FAILED: No text could be extracted from {}", file_path.file_name().unwrap_or_default().to_string_lossy()),
-                start_line: 0,
-                end_line: 1,
-                chunk_type: ChunkType::TextBlock,
-                embedding: None,
-                hash: String::new(),
-                metadata: ChunkMetadata {
-                    tags: vec!["extraction_failed".to_string(), "emergency".to_string()],
-                    priority: Priority::Low,
-                    language: "unknown".to_string(),
-                    symbol: None,
-                    context: None,
-                    confidence: 0.0,
-                    ocr_metadata: None,
-                    source_type: SourceType::NativeText,
-                    extraction_method: ExtractionMethod::DirectRead,
-                },
-                group_id: group_id.to_string(),
-                source_spans: None,
-            chunk_source: ChunkSource::BodyText,
-            figure_id: None,
-            };
-            chunks.push(emergency_chunk);
+            return Err(RagError::EmptyExtraction {
+                path: file_path.display().to_string(),
+                meaningful_chars: meaningful_char_count(&normalized_content),
+            });
         }
 
+        // Confiance OCR propre à chaque chunk (mots Tesseract de ses lignes), pas celle du document
+        if !ocr_words.is_empty() {
+            assign_chunk_ocr_confidence(&mut chunks, &normalized_content, &ocr_words);
+        }
+
+        // Overlap entre pages PDF : une phrase coupée par le saut de page reste lisible dans un même chunk
+        if matches!(document_type, DocumentType::PDF { .. }) {
+            let extended = apply_page_overlap(&mut chunks, &normalized_content, &self.page_overlap_config);
+            if extended > 0 {
+                debug!("Page overlap: {} chunks prefixed with previous page tail", extended);
+            }
+        }
+
+        // Extract OCR blocks if this is a PDF (zones de figures localisées pour la liaison légende/zone)
+        let (ocr_blocks, page_dimensions, extraction_quality) = if matches!(document_type, DocumentType::PDF { .. }) {
+            // Re-extract to get the image blocks
+            if let Ok(FileFormat::Pdf) = detect_file_format(file_path) {
+                if let Ok((_, quality, blocks, dims)) = self.extract_pdf_native(file_path).await {
+                    (blocks, dims, Some(quality))
+                } else {
+                    (Vec::new(), std::collections::HashMap::new(), None)
+                }
+            } else {
+                (Vec::new(), std::collections::HashMap::new(), None)
+            }
+        } else {
+            (Vec::new(), std::collections::HashMap::new(), None)
+        };
+
         // 🆕 PHASE 3: Vision-Aware RAG - Traiter les figures et graphiques
         if matches!(document_type, DocumentType::PDF { .. }) {
             debug!("Processing figures for PDF document");
@@ -208,7 +465,7 @@ impl DocumentProcessor {
                 Ok(pages) if !pages.is_empty() => {
                     // Créer le builder avec OCR activé
                     let figure_builder_opt = match FigureChunkBuilder::with_ocr().await {
-                        Ok(builder) => Some(builder),
+                        Ok(builder) => Some(builder.with_layout_blocks(ocr_blocks.clone())),
                         Err(err) => {
                             // Log and drop error immediately
                             warn!("Failed to initialize figure OCR: {}. Falling back to caption-only mode", err);
@@ -220,7 +477,7 @@ impl DocumentProcessor {
                     let figure_chunks_result = if let Some(builder) = figure_builder_opt {
                         builder.build_all_figure_chunks(pages.clone(), group_id).await
                     } else {
-                        let fallback_builder = FigureChunkBuilder::new();
+                        let fallback_builder = FigureChunkBuilder::new().with_layout_blocks(ocr_blocks.clone());
                         fallback_builder.build_all_figure_chunks(pages, group_id).await
                     };
 
@@ -260,28 +517,22 @@ impl DocumentProcessor {
         }
         info!("📊 Chunks générés par source: {:?}", by_source);
 
-        // 5. Construction du document enrichi
-        let document_id = format!("doc_{}", uuid::Uuid::new_v4().simple());
-        let now = SystemTime::now();
+        // Identifiants stables : une ré-ingestion des mêmes octets réécrit les mêmes points
+        let checksum = crate::rag::commands::file_checksum(file_path.to_path_buf()).await?;
+        let document_id = deterministic_document_id(&checksum, &normalized_content, group_id);
+        assign_deterministic_chunk_ids(&mut chunks, &document_id, group_id);
 
-        // Extract OCR blocks if this is a PDF
-        let (ocr_blocks, page_dimensions) = if matches!(document_type, DocumentType::PDF { .. }) {
-            // Re-extract to get the image blocks
-            if let Ok(FileFormat::Pdf) = detect_file_format(file_path) {
-                if let Ok((_, _, blocks, dims)) = self.extract_pdf_native(file_path).await {
-                    (blocks, dims)
-                } else {
-                    (Vec::new(), std::collections::HashMap::new())
-                }
-            } else {
-                (Vec::new(), std::collections::HashMap::new())
-            }
-        } else {
-            (Vec::new(), std::collections::HashMap::new())
-        };
+        // 5. Construction du document enrichi (ID stable comme ceux des chunks)
+        let now = SystemTime::now();
 
         // 🆕 Sérialiser les OCR blocks en JSON pour metadata.custom_fields
         let mut custom_fields = std::collections::HashMap::new();
+        if let Some(quality_json) = extraction_quality.and_then(|quality| serde_json::to_string(&quality).ok()) {
+            custom_fields.insert(EXTRACTION_QUALITY_FIELD.to_string(), quality_json);
+        }
+        if let Some(encoding) = text_encoding {
+            custom_fields.insert(TEXT_ENCODING_FIELD.to_string(), encoding.to_string());
+        }
         if !ocr_blocks.is_empty() {
             // Créer une structure sérialisable pour les blocs avec dimensions de page
             let native_blocks: Vec<crate::rag::direct_chat_commands::NativeOCRBlock> = ocr_blocks.iter().map(|block| {
@@ -333,31 +584,43 @@ impl DocumentProcessor {
             document_type,
             group_id: group_id.to_string(),
             ocr_blocks,
+            business_metadata: None,
         })
     }
 
     /// Traitement PDF avec stratégie intelligente
-    async fn process_pdf(&self, path: &Path) -> RagResult<(String, DocumentType, ExtractionMethod)> {
+    /// `min_native_tokens` : tokens natifs par page sous lesquels le PDF est traité comme scanné
+    async fn process_pdf(
+        &self,
+        path: &Path,
+        min_native_tokens: Option<usize>,
+        progress: Option<&OcrProgressCallback>,
+    ) -> RagResult<ExtractedContent> {
         debug!("Processing PDF: {:?}", path);
 
         // NOUVEAU: Stratégie hybride découplée affichage/embedding
         match self.extract_pdf_native(path).await {
-            Ok((content, native_ratio, _ocr_blocks, _page_dims)) => {
-                let text_quality_good = native_ratio > 0.8;
-                let substantial_text = content.len() > 1000;
-
-                if text_quality_good && substantial_text {
-                    // PDF avec texte extractible de qualité -> Retourner texte natif
-                    info!("High-quality extractable text detected (ratio={:.2}, {} chars). Using native extraction for display", 
-                          native_ratio, content.len());
-                    let doc_type = DocumentType::PDF {
-                        extraction_strategy: PdfStrategy::NativeOnly,
-                        native_text_ratio: native_ratio,
-                        ocr_pages: vec![],
-                        total_pages: 1,
-                    };
-                    Ok((content, doc_type, ExtractionMethod::PdfNative))
-                } else if native_ratio > 0.6 {
+            Ok((_, quality, _, _)) if is_below_native_threshold(&quality, min_native_tokens) => {
+                info!("Native text below threshold ({:.0} tokens/page < {}), treating PDF as scanned: {:?}",
+                      quality.tokens_per_page, min_native_tokens.unwrap_or_default(), path);
+                self.process_pdf_ocr_only(path, progress).await
+            }
+            Ok((content, quality, _, _)) if is_text_only_pdf(&quality, &content) => {
+                // Chemin rapide : texte natif de haute qualité, l'OCR n'est jamais sollicité
+                info!("Text-only PDF (ratio={:.2}, {} chars), skipping OCR: {:?}",
+                      quality.detected_native_ratio, content.len(), path);
+                let doc_type = DocumentType::PDF {
+                    extraction_strategy: PdfStrategy::NativeOnly,
+                    native_text_ratio: quality.detected_native_ratio,
+                    ocr_pages: vec![],
+                    total_pages: quality.page_count.max(1),
+                };
+                Ok((content, doc_type, ExtractionMethod::PdfNative, Vec::new()))
+            }
+            Ok((content, quality, _ocr_blocks, _page_dims)) => {
+                let native_ratio = quality.detected_native_ratio;
+
+                if native_ratio > 0.6 {
                     // Qualité correcte -> extraction native
                     let doc_type = DocumentType::PDF {
                         extraction_strategy: PdfStrategy::NativeOnly,
@@ -365,22 +628,22 @@ impl DocumentProcessor {
                         ocr_pages: vec![],
                         total_pages: 1,
                     };
-                    Ok((content, doc_type, ExtractionMethod::PdfNative))
+                    Ok((content, doc_type, ExtractionMethod::PdfNative, Vec::new()))
                 } else {
                     // Qualité médiocre -> hybride
-                    self.process_pdf_hybrid(path).await
+                    self.process_pdf_hybrid(path, progress).await
                 }
             }
             Err(_) => {
                 // Échec extraction native, utiliser OCR
                 warn!("Native PDF extraction failed for {:?}, using OCR", path);
-                self.process_pdf_ocr_only(path).await
+                self.process_pdf_ocr_only(path, progress).await
             }
         }
     }
 
     /// Extraction PDF native avec SimplePdfExtractor
-    async fn extract_pdf_native(&self, path: &Path) -> Result<(String, f32, Vec<crate::rag::core::direct_chat::OCRBlock>, std::collections::HashMap<u32, (f64, f64)>)> {
+    async fn extract_pdf_native(&self, path: &Path) -> Result<(String, ExtractionQualityMetrics, Vec<crate::rag::core::direct_chat::OCRBlock>, std::collections::HashMap<u32, (f64, f64)>)> {
         debug!("Attempting native PDF extraction for: {:?}", path);
 
         let config = PdfExtractConfig::default();
@@ -394,42 +657,35 @@ impl DocumentProcessor {
             return Err(anyhow::anyhow!("No native text extracted from PDF"));
         }
 
-        // Estimer la qualité du texte extrait - amélioration pour détection native
-        let quality_ratio = if result.text.len() > 200 {
-            let _word_count = result.text.split_whitespace().count();
-            let alpha_count = result.text.chars().filter(|c| c.is_alphabetic()).count();
-            let printable_count = result.text.chars().filter(|c| c.is_ascii_graphic() || c.is_whitespace()).count();
-
-            let alpha_ratio = alpha_count as f32 / result.text.len() as f32;
-            let printable_ratio = printable_count as f32 / result.text.len() as f32;
-
-            // Si ratio printable > 0.9 et beaucoup de texte → texte natif de qualité
-            if printable_ratio > 0.9 && result.text.len() > 1000 {
-                1.0  // Parfait pour texte natif
-            } else {
-                (alpha_ratio * 1.2).min(1.0)
-            }
-        } else {
-            0.3 // Texte court mais présent
-        };
+        let quality = ExtractionQualityMetrics::compute(&result.text, result.token_count, result.page_dimensions.len());
+        debug!(
+            file_path = ?path,
+            printable_ratio = quality.printable_ratio,
+            alpha_ratio = quality.alpha_ratio,
+            tokens_per_page = quality.tokens_per_page,
+            detected_native_ratio = quality.detected_native_ratio,
+            pages = quality.page_count,
+            "PDF native extraction quality metrics"
+        );
 
         // 🆕 Combiner image_blocks et layout_blocks
         let mut all_ocr_blocks = result.image_blocks.clone();
         all_ocr_blocks.extend(result.layout_blocks.clone());
 
         debug!("PDF native extraction: {} chars, quality={:.2}, {} layout blocks, {} image blocks, {} total blocks",
-               result.text.len(), quality_ratio, result.layout_blocks.len(), result.image_blocks.len(), all_ocr_blocks.len());
+               result.text.len(), quality.detected_native_ratio, result.layout_blocks.len(), result.image_blocks.len(), all_ocr_blocks.len());
 
-        Ok((result.text, quality_ratio, all_ocr_blocks, result.page_dimensions))
+        Ok((result.text, quality, all_ocr_blocks, result.page_dimensions))
     }
 
     /// Traitement PDF hybride intelligent
-    async fn process_pdf_hybrid(&self, path: &Path) -> RagResult<(String, DocumentType, ExtractionMethod)> {
+    async fn process_pdf_hybrid(&self, path: &Path, progress: Option<&OcrProgressCallback>) -> RagResult<ExtractedContent> {
         debug!("Processing PDF with hybrid intelligent strategy: {:?}", path);
         
         // 1. Tentative extraction native d'abord
         match self.extract_pdf_native(path).await {
-            Ok((content, quality, _ocr_blocks, _page_dims)) if quality > 0.7 => {
+            Ok((content, metrics, _ocr_blocks, _page_dims)) if metrics.detected_native_ratio > 0.7 => {
+                let quality = metrics.detected_native_ratio;
                 // Qualité suffisante, utiliser extraction native
                 info!("Using native PDF extraction (quality={:.2})", quality);
 
@@ -448,9 +704,10 @@ impl DocumentProcessor {
                     total_pages: 1, // TODO: compter pages réelles
                 };
 
-                Ok((sanitized_content, doc_type, ExtractionMethod::PdfNative))
+                Ok((sanitized_content, doc_type, ExtractionMethod::PdfNative, Vec::new()))
             }
-            Ok((content, quality, _ocr_blocks, _page_dims)) => {
+            Ok((content, metrics, _ocr_blocks, _page_dims)) => {
+                let quality = metrics.detected_native_ratio;
                 // Qualité insuffisante, mais on a du contenu
                 info!("Native PDF quality moderate ({:.2}), using as fallback", quality);
 
@@ -469,27 +726,30 @@ impl DocumentProcessor {
                     total_pages: 1,
                 };
 
-                Ok((sanitized_content, doc_type, ExtractionMethod::HybridIntelligent))
+                Ok((sanitized_content, doc_type, ExtractionMethod::HybridIntelligent, Vec::new()))
             }
             Err(_) => {
                 // Échec extraction native, utiliser OCR
                 warn!("Native PDF extraction failed, using OCR fallback");
-                self.process_pdf_ocr_only(path).await
+                self.process_pdf_ocr_only(path, progress).await
             }
         }
     }
 
     /// Traitement PDF par OCR uniquement
-    async fn process_pdf_ocr_only(&self, path: &Path) -> RagResult<(String, DocumentType, ExtractionMethod)> {
+    async fn process_pdf_ocr_only(&self, path: &Path, progress: Option<&OcrProgressCallback>) -> RagResult<ExtractedContent> {
         // TODO: Implémenter process_pdf pour TesseractProcessor
         // Pour l'instant, traiter comme image simple
         warn!("PDF OCR not fully implemented yet, treating as single page");
         
-        let ocr_result = self.ocr_processor.process_image(path).await
+        let started = std::time::Instant::now();
+        let ocr_result = self.ocr().await?.process_image(path).await
             .map_err(|e| RagError::InvalidConfig(format!("PDF OCR failed: {}", e)))?;
+        report_single_page_progress(progress, ocr_result.confidence, started);
 
         // Sanitization Unicode critique pour contenu OCR (plus de ligatures)
-        let (sanitized_content, normalization_stats) = sanitize_pdf_text(&ocr_result.text)
+        let denoised = self.strip_ocr_noise(&ocr_result.text);
+        let (sanitized_content, normalization_stats) = sanitize_pdf_text(&denoised)
             .map_err(|e| RagError::InvalidConfig(format!("Unicode sanitization failed: {}", e)))?;
         
         if normalization_stats.ligatures_replaced > 0 {
@@ -510,19 +770,22 @@ impl DocumentProcessor {
             language: if ocr_result.language.is_empty() { "fra".to_string() } else { ocr_result.language.clone() },
         };
 
-        Ok((sanitized_content, doc_type, extraction_method))
+        Ok((sanitized_content, doc_type, extraction_method, ocr_result.bounding_boxes))
     }
 
     /// Traitement d'image par OCR
-    async fn process_image(&self, path: &Path) -> RagResult<(String, DocumentType, ExtractionMethod)> {
+    async fn process_image(&self, path: &Path, progress: Option<&OcrProgressCallback>) -> RagResult<ExtractedContent> {
         debug!("Processing image: {:?}", path);
 
-        let ocr_result = self.ocr_processor.process_image(path).await
+        let started = std::time::Instant::now();
+        let ocr_result = self.ocr().await?.process_image(path).await
             .map_err(|e| RagError::InvalidConfig(format!("Image OCR failed: {}", e)))?;
+        report_single_page_progress(progress, ocr_result.confidence, started);
 
-        let content = ocr_result.text.clone();
+        let content = self.strip_ocr_noise(&ocr_result.text);
         let confidence = ocr_result.confidence;
 
+        let ocr_words = ocr_result.bounding_boxes.clone();
         let doc_type = DocumentType::Image {
             ocr_result: ocr_result.clone(),
             preprocessing_config: PreprocessConfig::default(), // TODO: récupérer config réelle
@@ -533,23 +796,34 @@ impl DocumentProcessor {
             language: if ocr_result.language.is_empty() { "fra".to_string() } else { ocr_result.language.clone() },
         };
 
-        Ok((content, doc_type, extraction_method))
+        Ok((content, doc_type, extraction_method, ocr_words))
     }
 
     /// Traitement de fichier texte simple
-    async fn process_text(&self, path: &Path) -> RagResult<(String, DocumentType, ExtractionMethod)> {
+    async fn process_text(&self, path: &Path) -> RagResult<(DecodedText, DocumentType)> {
         debug!("Processing text file: {:?}", path);
 
-        let content = tokio::fs::read_to_string(path).await
+        // Exports hérités non UTF-8 (Latin-1, Windows-1252, UTF-16) transcodés plutôt que rejetés
+        let bytes = tokio::fs::read(path).await
             .map_err(|e| RagError::Io(e))?;
+        let decoded = decode_text_bytes(&bytes)
+            .map_err(|e| RagError::BinaryContent { path: path.display().to_string(), reason: e.0 })?;
+        if decoded.encoding != "UTF-8" || decoded.had_bom {
+            info!("🔤 {:?} decoded from {} (BOM: {})", path, decoded.encoding, decoded.had_bom);
+        }
+        if decoded.had_errors {
+            warn!("⚠️ Invalid {} sequences replaced in {:?}", decoded.encoding, path);
+        }
 
-        let doc_type = if path.extension().and_then(|s| s.to_str()) == Some("md") {
+        let doc_type = if let Some(language) = source_language_from_path(path) {
+            DocumentType::SourceCode { language }
+        } else if path.extension().and_then(|s| s.to_str()) == Some("md") {
             DocumentType::Markdown
         } else {
             DocumentType::PlainText
         };
 
-        Ok((content, doc_type, ExtractionMethod::DirectRead))
+        Ok((decoded, doc_type))
     }
 
     /// Détermine le type de source selon la méthode d'extraction
@@ -627,6 +901,41 @@ impl DocumentProcessor {
         }
     }
 
+    /// Chunking du code source selon la stratégie du groupe (AST → fallback heuristique).
+    /// Les langages sans grammaire tree-sitter passent directement par l'heuristique.
+    fn chunk_source_code(
+        &self,
+        content: &str,
+        file_path: &Path,
+        source_type: SourceType,
+        extraction_method: &ExtractionMethod,
+        config: &ChunkConfig,
+        group_id: &str,
+    ) -> RagResult<Vec<EnrichedChunk>> {
+        let mut chunker = SmartChunker::new(SmartChunkConfig::from_chunk_config(config))
+            .map_err(|e| RagError::InvalidConfig(format!("Smart chunker init failed: {}", e)))?
+            .with_strategy(config.strategy)
+            .with_code_language(CodeLanguage::from_path(file_path));
+
+        let result = chunker
+            .chunk_document(content, source_type, extraction_method, group_id)
+            .map_err(|e| RagError::InvalidConfig(format!("Source code chunking failed: {}", e)))?;
+
+        let mut chunks = result.chunks;
+        if result.strategy_used == ChunkStrategy::Heuristic {
+            // Fallback heuristique : conserver le langage détecté pour le filtrage/affichage
+            if let Some(language) = source_language_from_path(file_path) {
+                for chunk in &mut chunks {
+                    chunk.metadata.language = language.clone();
+                    chunk.metadata.tags.push("strategy:heuristic".to_string());
+                }
+            }
+        }
+
+        info!("Source code chunked with {:?} strategy: {} chunks", result.strategy_used, chunks.len());
+        Ok(chunks)
+    }
+
     /// Chunking spécialisé pour contenu OCR - Phase 2
     async fn chunk_ocr_content(
         &self,
@@ -637,81 +946,18 @@ impl DocumentProcessor {
     ) -> RagResult<Vec<EnrichedChunk>> {
         debug!("OCR-specific chunking for {} chars", content.len());
 
-        // Chunking OCR: préservation structure + confiance par chunk
-        let chunk_size = config.chunk_size;
-        let overlap = config.overlap;
-        
-        // Découpage par paragraphes d'abord (respecte structure OCR)
-        let paragraphs: Vec<&str> = content.split("\n\n").collect();
+        // Chunking OCR: préservation structure + confiance par chunk (tableaux atomiques)
+        let line_index = LineIndex::new(content);
         let mut chunks = Vec::new();
-        let mut current_chunk = String::new();
-        let mut chunk_index = 0;
-
-        for paragraph in paragraphs {
-            let words: Vec<&str> = paragraph.split_whitespace().collect();
-            
-            // Si le paragraphe est trop grand, le diviser
-            if words.len() > chunk_size {
-                // Finaliser le chunk actuel s'il existe
-                if !current_chunk.trim().is_empty() {
-                    let chunk = self.create_ocr_chunk(
-                        &current_chunk, 
-                        chunk_index, 
-                        &extraction_method, 
-                        group_id
-                    )?;
-                    chunks.push(chunk);
-                    chunk_index += 1;
-                    current_chunk.clear();
-                }
-
-                // Diviser le gros paragraphe
-                for word_chunk in words.chunks(chunk_size) {
-                    let chunk_text = word_chunk.join(" ");
-                    let chunk = self.create_ocr_chunk(
-                        &chunk_text, 
-                        chunk_index, 
-                        &extraction_method, 
-                        group_id
-                    )?;
-                    chunks.push(chunk);
-                    chunk_index += 1;
-                }
-            } else {
-                // Ajouter au chunk actuel
-                if !current_chunk.is_empty() {
-                    current_chunk.push_str("\n\n");
-                }
-                current_chunk.push_str(paragraph);
-
-                // Vérifier si le chunk est assez grand
-                let current_words = current_chunk.split_whitespace().count();
-                if current_words >= chunk_size {
-                    let chunk = self.create_ocr_chunk(
-                        &current_chunk, 
-                        chunk_index, 
-                        &extraction_method, 
-                        group_id
-                    )?;
-                    chunks.push(chunk);
-                    chunk_index += 1;
-                    
-                    // Overlap: garder les derniers mots
-                    let words: Vec<&str> = current_chunk.split_whitespace().collect();
-                    let overlap_start = std::cmp::max(0, words.len().saturating_sub(overlap));
-                    current_chunk = words[overlap_start..].join(" ");
-                }
-            }
-        }
-
-        // Finaliser le dernier chunk
-        if !current_chunk.trim().is_empty() {
-            let chunk = self.create_ocr_chunk(
-                &current_chunk, 
+        for (chunk_index, (window, chunk_source)) in split_preserving_tables(content, config, split_ocr_paragraphs).iter().enumerate() {
+            let mut chunk = self.create_ocr_chunk(
+                &window.text, 
                 chunk_index, 
+                line_index.line_range(window.byte_range),
                 &extraction_method, 
                 group_id
             )?;
+            mark_table_chunk(&mut chunk, chunk_source);
             chunks.push(chunk);
         }
 
@@ -744,53 +990,18 @@ impl DocumentProcessor {
     ) -> RagResult<Vec<EnrichedChunk>> {
         debug!("Native text chunking for {} chars", content.len());
 
-        let chunk_size = config.chunk_size;
-        let overlap = config.overlap;
-        
-        // Chunking par phrases pour texte natif (meilleure qualité)
-        let sentences: Vec<&str> = content.split(|c| c == '.' || c == '!' || c == '?')
-            .filter(|s| !s.trim().is_empty())
-            .collect();
-
+        // Chunking par phrases pour texte natif (meilleure qualité), tableaux atomiques
+        let line_index = LineIndex::new(content);
         let mut chunks = Vec::new();
-        let mut current_chunk = String::new();
-        let mut chunk_index = 0;
-
-        for sentence in sentences {
-            let words_in_sentence = sentence.split_whitespace().count();
-            let current_words = current_chunk.split_whitespace().count();
-
-            if current_words + words_in_sentence > chunk_size && !current_chunk.is_empty() {
-                // Créer le chunk actuel
-                let chunk = self.create_native_chunk(
-                    &current_chunk, 
-                    chunk_index, 
-                    &extraction_method, 
-                    group_id
-                )?;
-                chunks.push(chunk);
-                chunk_index += 1;
-
-                // Overlap: garder les derniers mots
-                let words: Vec<&str> = current_chunk.split_whitespace().collect();
-                let overlap_start = std::cmp::max(0, words.len().saturating_sub(overlap));
-                current_chunk = words[overlap_start..].join(" ");
-            }
-
-            if !current_chunk.is_empty() {
-                current_chunk.push_str(". ");
-            }
-            current_chunk.push_str(sentence.trim());
-        }
-
-        // Finaliser le dernier chunk
-        if !current_chunk.trim().is_empty() {
-            let chunk = self.create_native_chunk(
-                &current_chunk, 
+        for (chunk_index, (window, chunk_source)) in split_preserving_tables(content, config, split_native_sentences).iter().enumerate() {
+            let mut chunk = self.create_native_chunk(
+                &window.text, 
                 chunk_index, 
+                line_index.line_range(window.byte_range),
                 &extraction_method, 
                 group_id
             )?;
+            mark_table_chunk(&mut chunk, chunk_source);
             chunks.push(chunk);
         }
 
@@ -803,6 +1014,7 @@ impl DocumentProcessor {
         &self,
         content: &str,
         index: usize,
+        line_range: (usize, usize),
         extraction_method: &ExtractionMethod,
         group_id: &str,
     ) -> RagResult<EnrichedChunk> {
@@ -814,8 +1026,8 @@ impl DocumentProcessor {
         let mut chunk = EnrichedChunk {
             id: format!("chunk_ocr_{}_{}", uuid::Uuid::new_v4().simple(), index),
             content: content.to_string(),
-            start_line: index,
-            end_line: index + 1,
+            start_line: line_range.0,
+            end_line: line_range.1,
             chunk_type: ChunkType::TextBlock,
             embedding: None,
             hash: String::new(),
@@ -829,6 +1041,7 @@ impl DocumentProcessor {
                 ocr_metadata: self.extract_ocr_metadata_for_chunk(extraction_method),
                 source_type: SourceType::OcrExtracted,
                 extraction_method: extraction_method.clone(),
+                page_overlap_len: 0,
             },
             group_id: group_id.to_string(),
             source_spans: None,
@@ -845,14 +1058,15 @@ impl DocumentProcessor {
         &self,
         content: &str,
         index: usize,
+        line_range: (usize, usize),
         extraction_method: &ExtractionMethod,
         group_id: &str,
     ) -> RagResult<EnrichedChunk> {
         let mut chunk = EnrichedChunk {
             id: format!("chunk_native_{}_{}", uuid::Uuid::new_v4().simple(), index),
             content: content.to_string(),
-            start_line: index,
-            end_line: index + 1,
+            start_line: line_range.0,
+            end_line: line_range.1,
             chunk_type: ChunkType::TextBlock,
             embedding: None,
             hash: String::new(),
@@ -866,6 +1080,7 @@ impl DocumentProcessor {
                 ocr_metadata: None, // Pas de métadonnées OCR pour texte natif
                 source_type: SourceType::NativeText,
                 extraction_method: extraction_method.clone(),
+                page_overlap_len: 0,
             },
             group_id: group_id.to_string(),
             source_spans: None,
@@ -896,51 +1111,418 @@ impl DocumentProcessor {
     }
 }
 
+/// Texte d'un chunk et sa plage d'octets dans le contenu source
+#[derive(Debug, Clone)]
+struct TextWindow {
+    text: String,
+    byte_range: (usize, usize),
+}
+
+/// Découpage OCR: paragraphes regroupés jusqu'à `chunk_size` tokens,
+/// paragraphes trop longs découpés par mots
+fn split_ocr_paragraphs(content: &str, config: &ChunkConfig) -> Vec<TextWindow> {
+    let (max_tokens, overlap_tokens) = window_bounds(config);
+
+    // Chaque unité doit tenir avec l'overlap et le séparateur qui la précèdent
+    let unit_budget = max_tokens.saturating_sub(overlap_tokens + 1).max(1);
+
+    // Découpage par paragraphes d'abord (respecte structure OCR)
+    let mut units = Vec::new();
+    for (offset, paragraph) in parts_with_offsets(content.split("\n\n"), 2) {
+        let Some((start, end, paragraph)) = trimmed_span(offset, paragraph) else {
+            continue;
+        };
+        if estimate_tokens(paragraph) > unit_budget {
+            units.extend(pack_words_by_tokens(paragraph, start, unit_budget));
+        } else {
+            units.push(TextWindow { text: paragraph.to_string(), byte_range: (start, end) });
+        }
+    }
+
+    pack_units_with_overlap(units, "\n\n", max_tokens, overlap_tokens)
+}
+
+/// Découpage natif: phrases regroupées jusqu'à `chunk_size` tokens avec overlap
+fn split_native_sentences(content: &str, config: &ChunkConfig) -> Vec<TextWindow> {
+    let (max_tokens, overlap_tokens) = window_bounds(config);
+
+    let sentences = parts_with_offsets(content.split(['.', '!', '?']), 1)
+        .filter_map(|(offset, sentence)| trimmed_span(offset, sentence))
+        .map(|(start, end, sentence)| TextWindow { text: sentence.to_string(), byte_range: (start, end) });
+
+    pack_units_with_overlap(sentences, ". ", max_tokens, overlap_tokens)
+}
+
+/// Découpage préservant les tableaux : chaque tableau détecté devient une fenêtre
+/// atomique (markdown, `ChunkSource::Table`) même au-delà de la taille cible ;
+/// le texte entre les tableaux est découpé par `split_text`
+fn split_preserving_tables(
+    content: &str,
+    config: &ChunkConfig,
+    split_text: fn(&str, &ChunkConfig) -> Vec<TextWindow>,
+) -> Vec<(TextWindow, ChunkSource)> {
+    let mut windows = Vec::new();
+    let mut cursor = 0;
+
+    let push_text = |windows: &mut Vec<(TextWindow, ChunkSource)>, start: usize, end: usize| {
+        for window in split_text(&content[start..end], config) {
+            let byte_range = (window.byte_range.0 + start, window.byte_range.1 + start);
+            windows.push((TextWindow { text: window.text, byte_range }, ChunkSource::BodyText));
+        }
+    };
+
+    for (start, end) in find_table_regions(content) {
+        if start > cursor {
+            push_text(&mut windows, cursor, start);
+        }
+        let lines: Vec<&str> = content[start..end].lines().collect();
+        windows.push((
+            TextWindow { text: structure_table_content(&lines), byte_range: (start, end) },
+            ChunkSource::Table,
+        ));
+        cursor = end;
+    }
+    if cursor < content.len() {
+        push_text(&mut windows, cursor, content.len());
+    }
+
+    windows
+}
+
+/// Marque un chunk issu d'un tableau détecté
+fn mark_table_chunk(chunk: &mut EnrichedChunk, chunk_source: &ChunkSource) {
+    if *chunk_source == ChunkSource::Table {
+        chunk.chunk_source = ChunkSource::Table;
+        chunk.metadata.tags.push("table".to_string());
+    }
+}
+
+/// Taille max et overlap en tokens (overlap borné à la moitié du chunk pour garantir la progression)
+fn window_bounds(config: &ChunkConfig) -> (usize, usize) {
+    let max_tokens = config.chunk_size.max(1);
+    (max_tokens, config.overlap.min(max_tokens / 2))
+}
+
+/// Fenêtre glissante: les unités sont regroupées jusqu'à `max_tokens`, et chaque chunk
+/// commence par `overlap_suffix` du chunk précédent
+fn pack_units_with_overlap(
+    units: impl IntoIterator<Item = TextWindow>,
+    separator: &str,
+    max_tokens: usize,
+    overlap_tokens: usize,
+) -> Vec<TextWindow> {
+    let mut pieces = Vec::new();
+    let mut current_chunk = String::new();
+    // (position dans le chunk, position dans la source) de chaque unité du chunk courant
+    let mut anchors: Vec<(usize, usize)> = Vec::new();
+    let mut range_end = 0;
+    let mut has_new_content = false; // Le chunk contient plus que l'overlap hérité
+
+    for unit in units {
+        let candidate_tokens = estimate_tokens(&current_chunk) + estimate_tokens(separator) + estimate_tokens(&unit.text);
+        if has_new_content && candidate_tokens > max_tokens {
+            let overlap_text = overlap_suffix(&current_chunk, overlap_tokens).to_string();
+            let overlap_source = source_position(&anchors, current_chunk.len() - overlap_text.len()).min(range_end);
+            let range_start = anchors[0].1;
+
+            pieces.push(TextWindow {
+                text: std::mem::replace(&mut current_chunk, overlap_text),
+                byte_range: (range_start, range_end),
+            });
+            anchors.clear();
+            if !current_chunk.is_empty() {
+                anchors.push((0, overlap_source));
+            }
+            has_new_content = false;
+        }
+
+        if !current_chunk.is_empty() {
+            current_chunk.push_str(separator);
+        }
+        anchors.push((current_chunk.len(), unit.byte_range.0));
+        current_chunk.push_str(&unit.text);
+        range_end = unit.byte_range.1;
+        has_new_content = true;
+    }
+
+    // Finaliser le dernier chunk (sauf s'il ne contient que l'overlap)
+    if has_new_content && !current_chunk.trim().is_empty() {
+        pieces.push(TextWindow {
+            text: current_chunk,
+            byte_range: (anchors[0].1, range_end),
+        });
+    }
+    pieces
+}
+
+/// Position source d'un octet du chunk, via l'unité qui le contient
+fn source_position(anchors: &[(usize, usize)], chunk_offset: usize) -> usize {
+    anchors
+        .iter()
+        .rev()
+        .find(|(position, _)| *position <= chunk_offset)
+        .map(|(position, source)| source + (chunk_offset - position))
+        .unwrap_or(anchors[0].1)
+}
+
+/// Regroupe les mots d'un texte en morceaux d'au plus `max_tokens` tokens
+/// (`base_offset`: position du texte dans la source)
+fn pack_words_by_tokens(text: &str, base_offset: usize, max_tokens: usize) -> Vec<TextWindow> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut range = (0, 0);
+
+    for (start, end) in word_spans(text) {
+        let word = &text[start..end];
+        let candidate_len = current.chars().count() + word.chars().count() + 1;
+        if !current.is_empty() && (candidate_len as f32 / CHARS_PER_TOKEN).ceil() as usize > max_tokens {
+            pieces.push(TextWindow {
+                text: std::mem::take(&mut current),
+                byte_range: (base_offset + range.0, base_offset + range.1),
+            });
+        }
+        if current.is_empty() {
+            range.0 = start;
+        } else {
+            current.push(' ');
+        }
+        current.push_str(word);
+        range.1 = end;
+    }
+
+    if !current.is_empty() {
+        pieces.push(TextWindow {
+            text: current,
+            byte_range: (base_offset + range.0, base_offset + range.1),
+        });
+    }
+    pieces
+}
+
+/// Regrouper les mots en morceaux d'au plus `max_tokens` comptés par `count_tokens`.
+/// Compte mot par mot : le pré-tokenizer BERT coupe aux espaces avant WordPiece.
+fn pack_words_by_counted_tokens(
+    text: &str,
+    max_tokens: usize,
+    count_tokens: &impl Fn(&str) -> usize,
+) -> Vec<(TextWindow, usize)> {
+    let mut pieces = Vec::new();
+    let mut range: Option<(usize, usize)> = None;
+    let mut tokens = 0;
+
+    for (start, end) in word_spans(text) {
+        let word_tokens = count_tokens(&text[start..end]);
+        if let Some((piece_start, piece_end)) = range {
+            if tokens + word_tokens > max_tokens {
+                let window = TextWindow { text: joined_words(&text[piece_start..piece_end]), byte_range: (piece_start, piece_end) };
+                pieces.push((window, tokens));
+                range = None;
+                tokens = 0;
+            }
+        }
+        range = Some((range.map_or(start, |(piece_start, _)| piece_start), end));
+        tokens += word_tokens;
+    }
+
+    if let Some((piece_start, piece_end)) = range {
+        let window = TextWindow { text: joined_words(&text[piece_start..piece_end]), byte_range: (piece_start, piece_end) };
+        pieces.push((window, tokens));
+    }
+    pieces
+}
+
+/// Mots d'une plage séparés par une espace simple (même rendu que `pack_words_by_tokens`)
+fn joined_words(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Garde-fou avant embedding : chunks qui dépassent `max_tokens` selon `count_tokens`
+/// (tokenizer de l'embedder, pour compter comme le modèle tronquera).
+/// `Split` les découpe par mots (identifiant `<id>_part<n>`, lignes recalculées, spans du parent
+/// retirés : ils couvrent tout le chunk) ; renvoie les chunks et les identifiants de ceux qui
+/// restent trop longs (tronqués par le modèle).
+pub fn enforce_chunk_token_limit(
+    chunks: Vec<EnrichedChunk>,
+    max_tokens: usize,
+    policy: OversizedChunkPolicy,
+    count_tokens: impl Fn(&str) -> usize,
+) -> (Vec<EnrichedChunk>, Vec<String>) {
+    let mut guarded = Vec::with_capacity(chunks.len());
+    let mut truncated = Vec::new();
+
+    for chunk in chunks {
+        let tokens = count_tokens(&chunk.content);
+        if tokens <= max_tokens || max_tokens == 0 {
+            guarded.push(chunk);
+            continue;
+        }
+
+        if policy == OversizedChunkPolicy::Flag {
+            warn!("Chunk {} exceeds embedding limit ({} tokens > {}), its tail will be truncated",
+                  chunk.id, tokens, max_tokens);
+            truncated.push(chunk.id.clone());
+            guarded.push(chunk);
+            continue;
+        }
+
+        let pieces = pack_words_by_counted_tokens(&chunk.content, max_tokens, &count_tokens);
+        debug!("Chunk {} split into {} parts ({} tokens > {})", chunk.id, pieces.len(), tokens, max_tokens);
+        for (index, (piece, piece_tokens)) in pieces.into_iter().enumerate() {
+            // Un mot isolé plus long que la limite reste trop long
+            if piece_tokens > max_tokens {
+                truncated.push(format!("{}_part{}", chunk.id, index));
+            }
+            let start_line = chunk.start_line + chunk.content[..piece.byte_range.0].matches('\n').count();
+            let end_line = chunk.start_line + chunk.content[..piece.byte_range.1].matches('\n').count();
+            guarded.push(EnrichedChunk {
+                id: format!("{}_part{}", chunk.id, index),
+                hash: blake3::hash(piece.text.as_bytes()).to_hex().to_string(),
+                content: piece.text,
+                start_line,
+                end_line,
+                embedding: None,
+                source_spans: None,
+                ..chunk.clone()
+            });
+        }
+    }
+
+    (guarded, truncated)
+}
+
+/// Tag des chunks conservés pour l'affichage mais exclus de l'index vectoriel
+pub const LOW_CONFIDENCE_TAG: &str = "low-confidence";
+
+/// Confiance OCR d'un chunk (`None` pour le texte natif, jamais filtré)
+pub fn chunk_ocr_confidence(chunk: &EnrichedChunk) -> Option<f32> {
+    match chunk.metadata.source_type {
+        SourceType::OcrExtracted | SourceType::HybridPdfOcr => Some(chunk.metadata.confidence),
+        _ => None,
+    }
+}
+
+/// Confiance OCR de chaque chunk OCR : moyenne des confiances Tesseract des mots de ses lignes.
+/// Les mots du texte absents des boîtes Tesseract (confiance < 0.3, écartés par le TSV) comptent pour 0.
+/// Les chunks sans mot reconnu gardent leur confiance.
+pub fn assign_chunk_ocr_confidence(chunks: &mut [EnrichedChunk], content: &str, words: &[TesseractBoundingBox]) {
+    let line_confidences = ocr_line_confidences(content, words);
+    for chunk in chunks.iter_mut().filter(|chunk| chunk_ocr_confidence(chunk).is_some()) {
+        let (sum, count) = line_confidences
+            .iter()
+            .skip(chunk.start_line.saturating_sub(1))
+            .take(chunk.end_line.saturating_sub(chunk.start_line.max(1)) + 1)
+            .fold((0.0, 0), |(sum, count), (line_sum, line_count)| (sum + line_sum, count + line_count));
+        if count > 0 {
+            chunk.metadata.confidence = sum / count as f32;
+        }
+    }
+}
+
+/// (somme des confiances, nombre de mots) par ligne du texte (index 0 = ligne 1).
+/// Les mots Tesseract sont alignés dans l'ordre de lecture, avec une courte fenêtre de recherche
+/// pour absorber les mots retirés par le nettoyage du bruit.
+fn ocr_line_confidences(content: &str, words: &[TesseractBoundingBox]) -> Vec<(f32, usize)> {
+    const LOOKAHEAD: usize = 8;
+    let mut cursor = 0;
+    content
+        .lines()
+        .map(|line| {
+            let mut sum = 0.0;
+            let mut count = 0;
+            for token in line.split_whitespace().filter(|token| token.chars().any(char::is_alphanumeric)) {
+                let found = words[cursor.min(words.len())..]
+                    .iter()
+                    .take(LOOKAHEAD)
+                    .position(|word| word.text == token);
+                if let Some(offset) = found {
+                    sum += words[cursor + offset].confidence;
+                    cursor += offset + 1;
+                }
+                count += 1;
+            }
+            (sum, count)
+        })
+        .collect()
+}
+
+/// Sépare les chunks OCR sous `min_confidence` : renvoie (chunks à indexer, chunks exclus tagués
+/// `LOW_CONFIDENCE_TAG`). Les chunks exclus restent dans le document pour l'affichage.
+pub fn split_low_confidence_chunks(
+    chunks: Vec<EnrichedChunk>,
+    min_confidence: f32,
+) -> (Vec<EnrichedChunk>, Vec<EnrichedChunk>) {
+    let (indexable, mut dropped): (Vec<_>, Vec<_>) = chunks.into_iter()
+        .partition(|chunk| !matches!(chunk_ocr_confidence(chunk), Some(confidence) if confidence < min_confidence));
+    for chunk in &mut dropped {
+        debug!("Chunk {} excluded from index (OCR confidence {:.2} < {:.2})",
+               chunk.id, chunk.metadata.confidence, min_confidence);
+        if !chunk.metadata.tags.iter().any(|tag| tag == LOW_CONFIDENCE_TAG) {
+            chunk.metadata.tags.push(LOW_CONFIDENCE_TAG.to_string());
+        }
+    }
+    (indexable, dropped)
+}
+
+/// Plages d'octets des mots d'un texte
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut word_start = None;
+    for (index, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if let Some(start) = word_start.take() {
+                spans.push((start, index));
+            }
+        } else if word_start.is_none() {
+            word_start = Some(index);
+        }
+    }
+    if let Some(start) = word_start {
+        spans.push((start, text.len()));
+    }
+    spans
+}
+
+/// Plus court suffixe du texte (aligné sur un début de mot) couvrant au moins `overlap_tokens` tokens
+fn overlap_suffix(text: &str, overlap_tokens: usize) -> &str {
+    if overlap_tokens == 0 {
+        return "";
+    }
+
+    word_spans(text)
+        .into_iter()
+        .rev()
+        .map(|(start, _)| &text[start..])
+        .find(|suffix| estimate_tokens(suffix) >= overlap_tokens)
+        .unwrap_or_else(|| text.trim_start())
+}
+
 /// Fallback simple text splitting when standard chunker fails
-fn simple_text_split(content: &str, chunk_config: &ChunkConfig) -> Vec<EnrichedChunk> {
+/// (`first_line`: ligne source où commence `content`)
+fn simple_text_split(content: &str, chunk_config: &ChunkConfig, first_line: usize) -> Vec<EnrichedChunk> {
     let mut chunks = Vec::new();
-    let target_size = chunk_config.chunk_size;
-    let overlap = chunk_config.overlap;
+    let target_size = chunk_config.target_chars();
+    let overlap = chunk_config.overlap_chars();
     
     // Si le contenu est trop petit, retourner un seul chunk
     if content.len() <= target_size {
         return chunks; // Retourner vide pour indiquer l'échec
     }
     
-    // Split par paragraphes d'abord
-    let paragraphs: Vec<&str> = content.split("\n\n").collect();
+    let line_index = LineIndex::new(content);
+    let to_source_lines = |(start, end): (usize, usize)| (start + first_line - 1, end + first_line - 1);
+
+    // Split par paragraphes d'abord; `current_chunk` reste une tranche contiguë de `content`
     let mut current_chunk = String::new();
+    let mut chunk_end = 0;
     let mut chunk_index = 0;
     
-    for paragraph in paragraphs {
+    for (offset, paragraph) in parts_with_offsets(content.split("\n\n"), 2) {
         // Si ajouter ce paragraphe dépasse la taille cible, finaliser le chunk actuel
         if !current_chunk.is_empty() && current_chunk.len() + paragraph.len() > target_size {
-            // Créer chunk avec le contenu actuel
-            let chunk = EnrichedChunk {
-                id: format!("chunk_{}_split_{}", uuid::Uuid::new_v4().simple(), chunk_index),
-                content: current_chunk.trim().to_string(),
-                start_line: 0,
-                end_line: 0,
-                chunk_type: ChunkType::TextBlock,
-                embedding: None,
-                hash: blake3::hash(current_chunk.trim().as_bytes()).to_hex().to_string(),
-                metadata: ChunkMetadata {
-                    tags: vec!["fallback-split".to_string()],
-                    priority: Priority::Normal,
-                    language: "auto".to_string(),
-                    symbol: None,
-                    context: None,
-                    confidence: 0.8, // Split confidence
-                    ocr_metadata: None,
-                    source_type: SourceType::NativeText,
-                    extraction_method: ExtractionMethod::DirectRead,
-                },
-                group_id: "split".to_string(),
-                source_spans: None,
-            chunk_source: ChunkSource::BodyText,
-            figure_id: None,
-            };
-            chunks.push(chunk);
+            if let Some((start, end, text)) = trimmed_span(chunk_end - current_chunk.len(), &current_chunk) {
+                let lines = to_source_lines(line_index.line_range((start, end)));
+                chunks.push(create_split_chunk(text, chunk_index, lines));
+            }
             
             // Préparer le chunk suivant avec overlap (respect UTF-8 boundaries)
             if overlap > 0 && current_chunk.len() > overlap {
@@ -961,100 +1543,116 @@ fn simple_text_split(content: &str, chunk_config: &ChunkConfig) -> Vec<EnrichedC
             current_chunk.push_str("\n\n");
         }
         current_chunk.push_str(paragraph);
+        chunk_end = offset + paragraph.len();
     }
     
     // Ajouter le dernier chunk s'il contient du contenu
-    if !current_chunk.trim().is_empty() {
-        let chunk = EnrichedChunk {
-            id: format!("chunk_{}_split_{}", uuid::Uuid::new_v4().simple(), chunk_index),
-            content: current_chunk.trim().to_string(),
-            start_line: 0,
-            end_line: 0,
-            chunk_type: ChunkType::TextBlock,
-            embedding: None,
-            hash: blake3::hash(current_chunk.trim().as_bytes()).to_hex().to_string(),
-            metadata: ChunkMetadata {
-                tags: vec!["fallback-split".to_string()],
-                priority: Priority::Normal,
-                language: "auto".to_string(),
-                symbol: None,
-                context: None,
-                confidence: 0.8, // Split confidence
-                ocr_metadata: None,
-                source_type: SourceType::NativeText,
-                extraction_method: ExtractionMethod::DirectRead,
-            },
-            group_id: "split".to_string(),
-            source_spans: None,
-        chunk_source: ChunkSource::BodyText,
-        figure_id: None,
-        };
-        chunks.push(chunk);
+    if let Some((start, end, text)) = trimmed_span(chunk_end - current_chunk.len(), &current_chunk) {
+        let lines = to_source_lines(line_index.line_range((start, end)));
+        chunks.push(create_split_chunk(text, chunk_index, lines));
     }
     
     chunks
 }
 
+/// Chunk produit par le split de secours
+fn create_split_chunk(content: &str, index: usize, line_range: (usize, usize)) -> EnrichedChunk {
+    EnrichedChunk {
+        id: format!("chunk_{}_split_{}", uuid::Uuid::new_v4().simple(), index),
+        content: content.to_string(),
+        start_line: line_range.0,
+        end_line: line_range.1,
+        chunk_type: ChunkType::TextBlock,
+        embedding: None,
+        hash: blake3::hash(content.as_bytes()).to_hex().to_string(),
+        metadata: ChunkMetadata {
+            tags: vec!["fallback-split".to_string()],
+            priority: Priority::Normal,
+            language: "auto".to_string(),
+            symbol: None,
+            context: None,
+            confidence: 0.8, // Split confidence
+            ocr_metadata: None,
+            source_type: SourceType::NativeText,
+            extraction_method: ExtractionMethod::DirectRead,
+            page_overlap_len: 0,
+        },
+        group_id: "split".to_string(),
+        source_spans: None,
+        chunk_source: ChunkSource::BodyText,
+        figure_id: None,
+    }
+}
+
 /// Fallback split agressif par pages/paragraphes pour documents longs
 fn fallback_split_by_pages_or_paragraphs(content: &str, target_size: usize) -> Vec<EnrichedChunk> {
     let mut chunks = Vec::new();
     let mut chunk_index = 0;
+    let line_index = LineIndex::new(content);
     
     // Essayer d'abord par pages (séparées par sauts de page multiples)
-    let page_splits: Vec<&str> = content.split("\n\n\n").collect();
+    let page_splits: Vec<(usize, &str)> = parts_with_offsets(content.split("\n\n\n"), 3).collect();
     if page_splits.len() > 1 {
         // Split par pages
-        for page in page_splits {
+        for (page_offset, page) in page_splits {
             if page.trim().len() > target_size {
                 // Page trop grande, subdiviser par paragraphes
-                let para_chunks = split_by_paragraphs(page, target_size, &mut chunk_index);
+                let para_chunks = split_by_paragraphs(page, page_offset, target_size, &mut chunk_index, &line_index);
                 chunks.extend(para_chunks);
-            } else if !page.trim().is_empty() {
+            } else if let Some((start, end, page_text)) = trimmed_span(page_offset, page) {
                 // Page de taille raisonnable
-                let chunk = create_fallback_chunk(page.trim(), chunk_index);
+                let chunk = create_fallback_chunk(page_text, chunk_index, line_index.line_range((start, end)));
                 chunks.push(chunk);
                 chunk_index += 1;
             }
         }
     } else {
         // Pas de pages distinctes, split par paragraphes
-        let para_chunks = split_by_paragraphs(content, target_size, &mut chunk_index);
+        let para_chunks = split_by_paragraphs(content, 0, target_size, &mut chunk_index, &line_index);
         chunks.extend(para_chunks);
     }
     
     chunks
 }
 
-/// Split par paragraphes avec taille cible
-fn split_by_paragraphs(content: &str, target_size: usize, chunk_index: &mut usize) -> Vec<EnrichedChunk> {
+/// Split par paragraphes avec taille cible (`base_offset`: position de `content` dans la source)
+fn split_by_paragraphs(
+    content: &str,
+    base_offset: usize,
+    target_size: usize,
+    chunk_index: &mut usize,
+    line_index: &LineIndex,
+) -> Vec<EnrichedChunk> {
     let mut chunks = Vec::new();
-    let paragraphs: Vec<&str> = content.split("\n\n").collect();
     let mut current_chunk = String::new();
+    let mut chunk_range = (0, 0);
     
-    for paragraph in paragraphs {
-        let para_trimmed = paragraph.trim();
-        if para_trimmed.is_empty() {
+    for (offset, paragraph) in parts_with_offsets(content.split("\n\n"), 2) {
+        let Some((start, end, para_trimmed)) = trimmed_span(base_offset + offset, paragraph) else {
             continue;
-        }
+        };
         
         // Si ajouter ce paragraphe dépasse la taille, finaliser le chunk actuel
         if !current_chunk.is_empty() && current_chunk.len() + para_trimmed.len() > target_size {
-            let chunk = create_fallback_chunk(&current_chunk, *chunk_index);
+            let chunk = create_fallback_chunk(&current_chunk, *chunk_index, line_index.line_range(chunk_range));
             chunks.push(chunk);
             *chunk_index += 1;
             current_chunk.clear();
         }
         
         // Ajouter le paragraphe
-        if !current_chunk.is_empty() {
+        if current_chunk.is_empty() {
+            chunk_range.0 = start;
+        } else {
             current_chunk.push_str("\n\n");
         }
         current_chunk.push_str(para_trimmed);
+        chunk_range.1 = end;
     }
     
     // Ajouter le dernier chunk
     if !current_chunk.trim().is_empty() {
-        let chunk = create_fallback_chunk(&current_chunk, *chunk_index);
+        let chunk = create_fallback_chunk(&current_chunk, *chunk_index, line_index.line_range(chunk_range));
         chunks.push(chunk);
     }
     
@@ -1062,12 +1660,12 @@ fn split_by_paragraphs(content: &str, target_size: usize, chunk_index: &mut usiz
 }
 
 /// Créer un chunk de fallback avec métadonnées appropriées
-fn create_fallback_chunk(content: &str, index: usize) -> EnrichedChunk {
+fn create_fallback_chunk(content: &str, index: usize, line_range: (usize, usize)) -> EnrichedChunk {
     EnrichedChunk {
         id: format!("chunk_{}_fallback_{}", uuid::Uuid::new_v4().simple(), index),
         content: content.to_string(),
-        start_line: 0,
-        end_line: 0,
+        start_line: line_range.0,
+        end_line: line_range.1,
         chunk_type: ChunkType::TextBlock,
         embedding: None,
         hash: blake3::hash(content.as_bytes()).to_hex().to_string(),
@@ -1081,6 +1679,7 @@ fn create_fallback_chunk(content: &str, index: usize) -> EnrichedChunk {
             ocr_metadata: None,
             source_type: SourceType::NativeText,
             extraction_method: ExtractionMethod::DirectRead,
+            page_overlap_len: 0,
         },
         group_id: "fallback".to_string(),
         source_spans: None,
@@ -1089,6 +1688,58 @@ fn create_fallback_chunk(content: &str, index: usize) -> EnrichedChunk {
     }
 }
 
+/// Vrai si le texte natif est sous `min_native_tokens` par page (seuil configuré)
+fn is_below_native_threshold(quality: &ExtractionQualityMetrics, min_native_tokens: Option<usize>) -> bool {
+    let Some(min_native_tokens) = min_native_tokens else {
+        return false;
+    };
+    debug!("PDF native text: {:.0} tokens/page (threshold {})", quality.tokens_per_page, min_native_tokens);
+    quality.tokens_per_page < min_native_tokens as f32
+}
+
+/// Retire les lignes d'en-tête/pied de page répétées sur la majorité des pages.
+/// Uniquement sur les frontières de page réelles (`PAGE_BREAK` de l'extraction PDF) :
+/// les pages devinées par `split_text_into_pages` feraient passer des paragraphes pour des bords de page.
+fn strip_page_boilerplate(content: String, config: &HeaderFooterConfig) -> String {
+    if !config.enabled {
+        return content;
+    }
+    let Some(pages) = split_pages(&content) else {
+        debug!("No page boundaries in extracted text, header/footer detection skipped");
+        return content;
+    };
+
+    let pages: Vec<String> = pages.into_iter().map(str::to_string).collect();
+    let result = strip_repeated_headers_footers(&pages, config);
+    if result.lines_stripped == 0 {
+        return content;
+    }
+
+    info!("🧹 Stripped {} header/footer lines across {} pages: {:?}",
+          result.lines_stripped, pages.len(), result.patterns);
+
+    result.pages.join(&PAGE_BREAK.to_string())
+}
+
+/// Chunks de figures (légendes + texte de zone) pour un texte déjà extrait, sans rendu d'image
+///
+/// Utilisé par l'ingestion de texte pré-extrait, qui ne passe pas par `process_document`.
+pub async fn build_figure_chunks_from_text(text: &str, group_id: &str) -> Vec<EnrichedChunk> {
+    let pages: Vec<(u32, String, Option<std::path::PathBuf>)> = split_text_into_pages(text)
+        .into_iter()
+        .enumerate()
+        .map(|(idx, page_text)| (idx as u32, page_text, None))
+        .collect();
+
+    match FigureChunkBuilder::new().build_all_figure_chunks(pages, group_id).await {
+        Ok(chunks) => chunks,
+        Err(err) => {
+            warn!("Failed to build figure chunks from text: {}", err);
+            Vec::new()
+        }
+    }
+}
+
 /// Helper: diviser le texte en pages basé sur des heuristiques
 ///
 /// Stratégie v1 simple:
@@ -1128,7 +1779,10 @@ fn split_text_into_pages(text: &str) -> Vec<String> {
         let mut current_pos = 0;
 
         while current_pos < text.len() {
-            let end_pos = (current_pos + target_page_size).min(text.len());
+            let mut end_pos = (current_pos + target_page_size).min(text.len());
+            while !text.is_char_boundary(end_pos) {
+                end_pos -= 1;
+            }
 
             // Essayer de couper à un saut de ligne proche
             let slice = &text[current_pos..end_pos];
@@ -1156,6 +1810,352 @@ mod tests {
     use super::*;
     use crate::rag::ocr::{TesseractConfig, OcrConfig};
 
+    /// ~4000 caractères: 10 paragraphes de 4 phrases de 100 caractères
+    fn sample_document() -> String {
+        let sentence = format!("{}abcd.", "abcd ".repeat(19));
+        let paragraph = vec![sentence; 4].join(" ");
+        vec![paragraph; 10].join("\n\n")
+    }
+
+    #[test]
+    fn test_meaningful_char_count() {
+        assert_eq!(meaningful_char_count(" \n\t \u{a0}\x0C"), 0);
+        assert_eq!(meaningful_char_count("- - - | . . ."), 0);
+        assert_eq!(meaningful_char_count("Facture n°12"), 10);
+        assert!(meaningful_char_count("\n\n  \n") < DEFAULT_MIN_MEANINGFUL_CHARS);
+    }
+
+    #[test]
+    fn test_native_threshold_from_chunk_config() {
+        let quality = ExtractionQualityMetrics::compute(&sample_document(), 800, 2);
+        let config: ChunkConfig = serde_json::from_value(serde_json::json!({
+            "chunk_size": 384, "overlap": 48, "strategy": "Heuristic", "min_native_tokens": 500
+        })).unwrap();
+
+        assert!(is_below_native_threshold(&quality, config.min_native_tokens));
+        assert!(!is_below_native_threshold(&quality, Some(100)));
+        // Groupes existants (champ absent) : comportement inchangé
+        assert!(!is_below_native_threshold(&quality, ChunkConfig::default().min_native_tokens));
+    }
+
+    #[test]
+    fn test_strip_page_boilerplate_on_real_pages_only() {
+        let page = |n: usize| format!("ACME Corp - Rapport annuel\nChiffre d'affaires du trimestre {}\nPage {} / 3", n, n);
+        let config = HeaderFooterConfig::default();
+
+        let pdf_text = (1..=3).map(page).collect::<Vec<_>>().join(&PAGE_BREAK.to_string());
+        let stripped = strip_page_boilerplate(pdf_text, &config);
+        assert_eq!(stripped.matches(PAGE_BREAK).count(), 2);
+        assert!(!stripped.contains("ACME Corp") && !stripped.contains("Page "));
+        assert!(stripped.contains("Chiffre d'affaires du trimestre 2"));
+
+        // Sans frontière de page réelle, des paragraphes ne sont pas des bords de page
+        let paragraphs = (1..=3).map(page).collect::<Vec<_>>().join("\n\n\n");
+        assert_eq!(strip_page_boilerplate(paragraphs.clone(), &config), paragraphs);
+    }
+
+    #[test]
+    fn test_extraction_quality_metrics() {
+        let native = ExtractionQualityMetrics::compute(&sample_document(), 800, 2);
+        assert_eq!(native.detected_native_ratio, 1.0);
+        assert!(native.printable_ratio > 0.99);
+        assert_eq!(native.tokens_per_page, 400.0);
+
+        // Texte court : considéré comme présent mais peu fiable
+        let short = ExtractionQualityMetrics::compute("Page 1", 2, 0);
+        assert_eq!(short.detected_native_ratio, 0.3);
+        assert_eq!(short.page_count, 1);
+
+        // Texte "garbled" majoritairement non imprimable
+        let garbled = "\u{fffd}\u{2}\u{3}ab ".repeat(100);
+        let metrics = ExtractionQualityMetrics::compute(&garbled, 100, 1);
+        assert!(metrics.detected_native_ratio < 0.6, "ratio {}", metrics.detected_native_ratio);
+    }
+
+    #[test]
+    fn test_text_only_pdf_predicate() {
+        // Chemin texte seul : qualité native élevée et texte abondant uniquement
+        let native = ExtractionQualityMetrics::compute(&sample_document(), 800, 2);
+        assert!(is_text_only_pdf(&native, &sample_document()));
+
+        let mut good = native;
+        good.detected_native_ratio = 0.85;
+        assert!(is_text_only_pdf(&good, &sample_document()));
+        good.detected_native_ratio = TEXT_ONLY_NATIVE_RATIO;
+        assert!(!is_text_only_pdf(&good, &sample_document()));
+
+        assert!(!is_text_only_pdf(&native, &"a".repeat(TEXT_ONLY_MIN_CHARS)));
+        let short = ExtractionQualityMetrics::compute("Page 1", 2, 0);
+        assert!(!is_text_only_pdf(&short, "Page 1"));
+        let garbled = "\u{fffd}\u{2}\u{3}ab ".repeat(100);
+        assert!(!is_text_only_pdf(&ExtractionQualityMetrics::compute(&garbled, 100, 1), &garbled));
+    }
+
+    #[test]
+    fn test_chunk_size_is_tokens_on_every_path() {
+        let content = sample_document();
+        assert_eq!(content.len(), 4048);
+
+        // 250 tokens ≈ 1000 caractères, sans overlap pour des comptes exacts
+        let config = ChunkConfig {
+            chunk_size: 250,
+            overlap: 0,
+            strategy: crate::rag::ChunkStrategy::Heuristic,
+            ..ChunkConfig::default()
+        };
+
+        // OCR: paragraphes (~101 tokens) regroupés par 2 sans dépasser 250 tokens
+        assert_eq!(split_ocr_paragraphs(&content, &config).len(), 5);
+        // Natif: phrases (~25 tokens) regroupées par 9
+        assert_eq!(split_native_sentences(&content, &config).len(), 5);
+        // Split de secours: 2 paragraphes par fenêtre de 1000 caractères
+        assert_eq!(simple_text_split(&content, &config, 1).len(), 5);
+
+        // SmartChunker: même unité via from_chunk_config
+        let mut chunker = SmartChunker::new(SmartChunkConfig::from_chunk_config(&config)).unwrap();
+        let result = chunker
+            .chunk_document(&content, SourceType::NativeText, &ExtractionMethod::DirectRead, "group")
+            .unwrap();
+        assert_eq!(result.chunks.len(), 5);
+    }
+
+    #[test]
+    fn test_overlap_prefixes_next_chunk() {
+        let content = sample_document();
+        let config = ChunkConfig {
+            chunk_size: 250,
+            overlap: 32,
+            strategy: crate::rag::ChunkStrategy::Heuristic,
+            ..ChunkConfig::default()
+        };
+
+        for chunks in [split_ocr_paragraphs(&content, &config), split_native_sentences(&content, &config)] {
+            assert!(chunks.len() > 1);
+            for pair in chunks.windows(2) {
+                let overlap = overlap_suffix(&pair[0].text, config.overlap);
+                assert!(estimate_tokens(overlap) >= config.overlap);
+                assert!(pair[1].text.starts_with(overlap), "chunk should start with previous tail: {:?}", overlap);
+                assert!(estimate_tokens(&pair[1].text) <= config.chunk_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tables_stay_atomic() {
+        let rows: Vec<String> = (1..=40).map(|i| format!("Produit {} | {} 000 € | {}%", i, i * 3, i)).collect();
+        let content = format!("{}\n\nProduit | Chiffre d'affaires | Marge\n{}\n\n{}", sample_document(), rows.join("\n"), sample_document());
+        let config = ChunkConfig {
+            chunk_size: 250,
+            overlap: 0,
+            strategy: crate::rag::ChunkStrategy::Heuristic,
+            ..ChunkConfig::default()
+        };
+
+        for split_text in [split_ocr_paragraphs as fn(&str, &ChunkConfig) -> Vec<TextWindow>, split_native_sentences] {
+            let windows = split_preserving_tables(&content, &config, split_text);
+            let tables: Vec<_> = windows.iter().filter(|(_, source)| *source == ChunkSource::Table).collect();
+
+            // Un seul chunk tableau, au-delà de la taille cible, avec toutes ses lignes
+            assert_eq!(tables.len(), 1);
+            let (table, _) = tables[0];
+            assert!(estimate_tokens(&table.text) > config.chunk_size);
+            assert!(table.text.contains("| Produit | Chiffre d'affaires | Marge |"));
+            assert!(table.text.contains("| Produit 40 | 120 000 € | 40% |"));
+            assert!(windows.iter().any(|(window, source)| *source == ChunkSource::BodyText && window.byte_range.0 > table.byte_range.1));
+        }
+    }
+
+    #[test]
+    fn test_single_page_ocr_progress_reported() {
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reported);
+        let callback: OcrProgressCallback = Arc::new(move |progress| sink.lock().unwrap().push(progress));
+
+        report_single_page_progress(None, 0.9, std::time::Instant::now());
+        report_single_page_progress(Some(&callback), 0.9, std::time::Instant::now());
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!((reported[0].page, reported[0].total_pages), (1, 1));
+        assert_eq!(reported[0].percent, 100.0);
+        assert_eq!(reported[0].confidence, Some(0.9));
+    }
+
+    #[tokio::test]
+    async fn test_document_and_chunk_ids_stable_across_ingests() {
+        let embedder = Arc::new(
+            CustomE5Embedder::new(crate::rag::CustomE5Config::default())
+                .await
+                .expect("Failed to create embedder")
+        );
+        let processor = DocumentProcessor::with_lazy_ocr(TesseractConfig::default(), embedder);
+        let config = ChunkConfig::default();
+
+        // Mêmes octets, deux fichiers distincts : deux ingestions complètes
+        let content = sample_document();
+        let paths = ["gravis_stable_ids_a.txt", "gravis_stable_ids_b.txt"].map(|name| std::env::temp_dir().join(name));
+        for path in &paths {
+            tokio::fs::write(path, &content).await.unwrap();
+        }
+
+        // Même texte une fois décodé, mais octets différents (BOM UTF-8) : un autre fichier source
+        let bom_path = std::env::temp_dir().join("gravis_stable_ids_bom.txt");
+        tokio::fs::write(&bom_path, [b"\xEF\xBB\xBF".as_slice(), content.as_bytes()].concat()).await.unwrap();
+
+        let first = processor.process_document(&paths[0], "g1", &config).await.unwrap();
+        let second = processor.process_document(&paths[1], "g1", &config).await.unwrap();
+        let other_group = processor.process_document(&paths[0], "g2", &config).await.unwrap();
+        let other_source = processor.process_document(&bom_path, "g1", &config).await.unwrap();
+        for path in paths.iter().chain([&bom_path]) {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+
+        let chunk_ids = |document: &GroupDocument| document.chunks.iter().map(|chunk| chunk.id.clone()).collect::<Vec<_>>();
+        assert!(first.chunks.len() > 1);
+        assert_eq!(first.id, second.id);
+        assert_eq!(chunk_ids(&first), chunk_ids(&second));
+        assert_ne!(first.id, other_group.id);
+        assert_ne!(chunk_ids(&first), chunk_ids(&other_group));
+        assert_eq!(first.content, other_source.content);
+        assert_ne!(first.id, other_source.id);
+        assert_ne!(chunk_ids(&first), chunk_ids(&other_source));
+
+        let unique: std::collections::HashSet<_> = chunk_ids(&first).into_iter().collect();
+        assert_eq!(unique.len(), first.chunks.len());
+    }
+
+    #[test]
+    fn test_oversized_chunks_split_or_flagged() {
+        // Compteur factice "3 tokens par mot" : la limite suit le compteur fourni, pas chars/4
+        let count_tokens = |text: &str| text.split_whitespace().count() * 3;
+        let content = vec![format!("{}abcd.", "abcd ".repeat(19)); 10].join("\n");
+        let mut chunk = create_split_chunk(&content, 0, (5, 14));
+        chunk.source_spans = Some(vec!["span_0".to_string(), "span_1".to_string()]);
+        assert!(estimate_tokens(&content) <= 300);
+
+        let (split, truncated) = enforce_chunk_token_limit(vec![chunk.clone()], 300, OversizedChunkPolicy::Split, count_tokens);
+        assert!(truncated.is_empty());
+        assert_eq!(split.len(), 2);
+        assert!(split.iter().all(|part| count_tokens(&part.content) <= 300 && part.hash != chunk.hash));
+        assert_eq!(split[0].id, format!("{}_part0", chunk.id));
+        assert_eq!((split[0].start_line, split[1].end_line), (5, 14));
+        assert!(split.iter().all(|part| part.source_spans.is_none()));
+
+        let (flagged, truncated) = enforce_chunk_token_limit(vec![chunk.clone()], 300, OversizedChunkPolicy::Flag, count_tokens);
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(truncated, vec![chunk.id.clone()]);
+
+        let (kept, truncated) = enforce_chunk_token_limit(vec![chunk], 600, OversizedChunkPolicy::Split, count_tokens);
+        assert_eq!(kept.len(), 1);
+        assert!(truncated.is_empty());
+    }
+
+    #[test]
+    fn test_chunk_ocr_confidence_on_mixed_quality_document() {
+        let word = |text: &str, confidence: f32| TesseractBoundingBox {
+            x: 0, y: 0, width: 10, height: 10, text: text.to_string(), confidence, level: 5,
+        };
+        // Page nette puis zone tachée : Tesseract n'a gardé que deux mots incertains ("m0ntant" < 0.3 écarté)
+        let content = "Facture numéro 42\nTotal dû 1200 €\n\nrèglement m0ntant illisible";
+        let words = vec![
+            word("Facture", 0.96), word("numéro", 0.94), word("42", 0.95),
+            word("Total", 0.93), word("dû", 0.91), word("1200", 0.97),
+            word("règlement", 0.42), word("illisible", 0.38),
+        ];
+        let ocr_chunk = |index: usize, lines: (usize, usize)| {
+            let mut chunk = create_split_chunk("", index, lines);
+            chunk.metadata.source_type = SourceType::OcrExtracted;
+            chunk.metadata.confidence = 0.8;
+            chunk
+        };
+        let mut chunks = vec![ocr_chunk(0, (1, 2)), ocr_chunk(1, (4, 4))];
+
+        assign_chunk_ocr_confidence(&mut chunks, content, &words);
+        assert!((chunks[0].metadata.confidence - 0.9433).abs() < 1e-3);
+        assert!((chunks[1].metadata.confidence - 0.2667).abs() < 1e-3);
+
+        // Seule la zone dégradée sort de l'index
+        let (indexable, dropped) = split_low_confidence_chunks(chunks, 0.5);
+        assert_eq!((indexable.len(), dropped.len()), (1, 1));
+        assert_eq!(dropped[0].start_line, 4);
+    }
+
+    #[test]
+    fn test_low_confidence_ocr_chunks_excluded() {
+        let chunk = |confidence: f32, source_type: SourceType| {
+            let mut chunk = create_split_chunk("Texte de la page", 0, (1, 1));
+            chunk.metadata.confidence = confidence;
+            chunk.metadata.source_type = source_type;
+            chunk
+        };
+        let chunks = vec![
+            chunk(0.9, SourceType::OcrExtracted),
+            chunk(0.3, SourceType::OcrExtracted),
+            chunk(0.4, SourceType::HybridPdfOcr),
+            // Texte natif : jamais filtré, quelle que soit sa confiance de chunking
+            chunk(0.2, SourceType::NativeText),
+        ];
+
+        let (indexable, dropped) = split_low_confidence_chunks(chunks, 0.5);
+        assert_eq!(indexable.len(), 2);
+        assert_eq!(dropped.len(), 2);
+        assert!(dropped.iter().all(|chunk| chunk.metadata.tags.iter().any(|tag| tag == LOW_CONFIDENCE_TAG)));
+        assert!(indexable.iter().any(|chunk| chunk_ocr_confidence(chunk).is_none()));
+    }
+
+    /// Chaque ligne non vide du document doit appartenir à au moins un chunk
+    fn assert_covers_document(content: &str, ranges: &[(usize, usize)]) {
+        let line_count = content.lines().count();
+        let mut covered = vec![false; line_count + 1];
+        for &(start, end) in ranges {
+            assert!(start >= 1 && start <= end && end <= line_count, "invalid range {}..={}", start, end);
+            for line in start..=end {
+                covered[line] = true;
+            }
+        }
+
+        for (index, line) in content.lines().enumerate() {
+            if !line.trim().is_empty() {
+                assert!(covered[index + 1], "line {} not covered", index + 1);
+            }
+        }
+        assert_eq!(ranges.first().map(|r| r.0), Some(1));
+        assert_eq!(ranges.last().map(|r| r.1), Some(line_count));
+    }
+
+    #[test]
+    fn test_chunks_carry_real_line_ranges() {
+        // Une phrase par ligne, paragraphes séparés par une ligne vide
+        let sentence = format!("{}abcd.", "abcd ".repeat(19));
+        let paragraph = vec![sentence; 4].join("\n");
+        let content = vec![paragraph; 10].join("\n\n");
+        let config = ChunkConfig {
+            chunk_size: 250,
+            overlap: 32,
+            strategy: crate::rag::ChunkStrategy::Heuristic,
+            ..ChunkConfig::default()
+        };
+        let line_index = LineIndex::new(&content);
+
+        for windows in [split_ocr_paragraphs(&content, &config), split_native_sentences(&content, &config)] {
+            let ranges: Vec<_> = windows.iter().map(|w| line_index.line_range(w.byte_range)).collect();
+            assert_covers_document(&content, &ranges);
+        }
+
+        let ranges: Vec<_> = simple_text_split(&content, &config, 1)
+            .iter()
+            .map(|chunk| (chunk.start_line, chunk.end_line))
+            .collect();
+        assert_covers_document(&content, &ranges);
+
+        let mut chunker = SmartChunker::new(SmartChunkConfig::from_chunk_config(&config)).unwrap();
+        let result = chunker
+            .chunk_document(&content, SourceType::NativeText, &ExtractionMethod::DirectRead, "group")
+            .unwrap();
+        let ranges: Vec<_> = result.chunks.iter().map(|chunk| (chunk.start_line, chunk.end_line)).collect();
+        assert_covers_document(&content, &ranges);
+    }
+
     #[tokio::test]
     async fn test_document_processor_text_file() {
         // Test basique de traitement fichier texte
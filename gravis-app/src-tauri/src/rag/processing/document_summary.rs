@@ -0,0 +1,125 @@
+// GRAVIS RAG - Résumé de document à l'ingestion
+// Extractif par défaut (phrases clés, sans service externe), abstractif via LLM sur demande
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::rag::core::llm_synthesis::{LlmSynthesisConfig, LlmSynthesizer};
+
+/// Clé de `EnrichedMetadata.custom_fields` et du payload Qdrant portant le résumé du document
+pub const DOCUMENT_SUMMARY_FIELD: &str = "document_summary";
+/// Nombre de phrases clés du résumé extractif
+pub const SUMMARY_KEY_SENTENCES: usize = 3;
+/// Longueur maximale d'un résumé (caractères)
+pub const SUMMARY_MAX_CHARS: usize = 600;
+/// Début du document transmis au LLM (caractères)
+const LLM_SUMMARY_INPUT_CHARS: usize = 8000;
+
+const LLM_SUMMARY_QUERY: &str = "Résume ce document en trois phrases maximum : sujet, points clés et chiffres importants.";
+
+/// Méthode ayant produit un résumé
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "method", content = "model")]
+pub enum SummaryMethod {
+    Extractive,
+    Llm(String),
+}
+
+/// Résumé d'un document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentSummary {
+    pub text: String,
+    pub method: SummaryMethod,
+}
+
+/// Extraire les phrases clés d'un texte (phrases complètes)
+pub fn extract_key_sentences(text: &str, max_sentences: usize) -> Vec<String> {
+    let sentences: Vec<String> = text
+        .split(|c| c == '.' || c == '!' || c == '?')
+        .filter(|s| !s.trim().is_empty() && s.len() > 30) // Filtrer phrases trop courtes
+        .take(max_sentences)
+        .map(|s| s.trim().to_string())
+        .collect();
+
+    sentences
+}
+
+/// Condenser un texte à une longueur maximale en préservant les phrases complètes
+pub fn condense_text(text: &str, max_chars: usize) -> String {
+    let trimmed = text.trim();
+
+    // Coupe sur une frontière de caractère (texte accentué)
+    let Some((cut, _)) = trimmed.char_indices().nth(max_chars) else {
+        return trimmed.to_string();
+    };
+
+    // Trouver la dernière phrase complète avant max_chars
+    let truncated = &trimmed[..cut];
+
+    // Chercher la dernière fin de phrase pour couper proprement
+    if let Some(last_end) = truncated.rfind(|c| matches!(c, '.' | '!' | '?')) {
+        if last_end > cut / 2 { // Garder au moins 50% du texte
+            return trimmed[..=last_end].to_string();
+        }
+    }
+
+    // Sinon, chercher le dernier espace
+    if let Some(last_space) = truncated.rfind(' ') {
+        return format!("{}...", &trimmed[..last_space]);
+    }
+
+    // Fallback: couper directement
+    format!("{}...", truncated)
+}
+
+/// Résumé extractif : phrases clés (lignes repliées), borné à `SUMMARY_MAX_CHARS`.
+/// `None` si le texte ne contient aucune phrase exploitable.
+pub fn extractive_summary(text: &str) -> Option<String> {
+    let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let sentences = extract_key_sentences(&flattened, SUMMARY_KEY_SENTENCES);
+    if sentences.is_empty() {
+        return None;
+    }
+    Some(condense_text(&format!("{}.", sentences.join(". ")), SUMMARY_MAX_CHARS))
+}
+
+/// Résumé du document : LLM si configuré (repli extractif en cas d'échec), extractif sinon
+pub async fn summarize_document(text: &str, llm: Option<&LlmSynthesisConfig>) -> Option<DocumentSummary> {
+    if let Some(config) = llm {
+        let input = condense_text(text, LLM_SUMMARY_INPUT_CHARS);
+        let result = match LlmSynthesizer::new(config.clone()) {
+            Ok(synthesizer) => synthesizer.synthesize(LLM_SUMMARY_QUERY, &input, &[], |_| {}).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(summary) => {
+                return Some(DocumentSummary {
+                    text: condense_text(&summary, SUMMARY_MAX_CHARS),
+                    method: SummaryMethod::Llm(config.model.clone()),
+                });
+            }
+            Err(e) => warn!("⚠️ LLM summary failed, using extractive summary: {}", e),
+        }
+    }
+
+    extractive_summary(text).map(|text| DocumentSummary { text, method: SummaryMethod::Extractive })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extractive_summary_keeps_key_sentences() {
+        let text = "Titre\nLe présent contrat de travail est conclu entre la société Alpha et M. Dupont.\n\
+                    Le salaire brut mensuel est fixé à 3 200 euros, versé le dernier jour du mois.\n\
+                    Ok.\nLa période d'essai est de trois mois renouvelable une fois.\nLe préavis est d'un mois.";
+        let summary = extractive_summary(text).unwrap();
+        assert!(summary.starts_with("Titre Le présent contrat de travail"));
+        assert!(summary.contains("3 200 euros"));
+        assert!(!summary.contains("préavis"));
+        assert!(summary.chars().count() <= SUMMARY_MAX_CHARS);
+
+        assert!(extractive_summary("Court. Trop court.").is_none());
+    }
+}
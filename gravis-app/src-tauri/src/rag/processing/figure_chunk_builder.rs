@@ -5,14 +5,32 @@ use crate::rag::{
     EnrichedChunk, ChunkType, ChunkMetadata, ChunkSource, Priority,
     SourceType, ExtractionMethod
 };
+use crate::rag::core::direct_chat::{BlockType, BoundingBox, OCRBlock};
 use super::{DetectedFigure, FigureOcrExtractor, FigureDetector};
 use std::path::Path;
 use tracing::{debug, info, warn};
 
+/// Voisinage textuel retenu de part et d'autre d'une légende (octets)
+const REGION_CONTEXT_CHARS: usize = 400;
+/// En dessous, le texte de la zone n'apporte rien de plus que la légende
+const MIN_REGION_TEXT_CHARS: usize = 80;
+
+/// Zone de figure localisée sur une page, associée à une légende par `link_captions_to_regions`
+#[derive(Debug, Clone)]
+pub struct FigureRegion {
+    /// Page de la zone (0-based, comme `DetectedFigure::page_index`)
+    pub page_index: u32,
+    pub bounding_box: BoundingBox,
+    /// Texte des blocs contenus dans la zone (vide si la zone n'est qu'une image)
+    pub text: String,
+}
+
 /// Builder pour créer des chunks à partir de figures
 pub struct FigureChunkBuilder {
     detector: FigureDetector,
     ocr_extractor: Option<FigureOcrExtractor>,
+    /// Blocs de l'extraction PDF (zones de figures + blocs de texte localisés)
+    layout_blocks: Vec<OCRBlock>,
 }
 
 impl FigureChunkBuilder {
@@ -21,6 +39,7 @@ impl FigureChunkBuilder {
         Self {
             detector: FigureDetector::new(),
             ocr_extractor: None,
+            layout_blocks: Vec::new(),
         }
     }
 
@@ -29,9 +48,17 @@ impl FigureChunkBuilder {
         Ok(Self {
             detector: FigureDetector::new(),
             ocr_extractor: Some(FigureOcrExtractor::new().await?),
+            layout_blocks: Vec::new(),
         })
     }
 
+    /// Fournir les blocs localisés de l'extraction PDF : chaque légende est reliée
+    /// à la zone de figure la plus proche de sa page (voir `link_captions_to_regions`)
+    pub fn with_layout_blocks(mut self, blocks: Vec<OCRBlock>) -> Self {
+        self.layout_blocks = blocks;
+        self
+    }
+
     /// Traiter une page de texte et générer des chunks de figures
     ///
     /// # Arguments
@@ -41,7 +68,7 @@ impl FigureChunkBuilder {
     /// * `group_id` - ID du groupe de documents
     ///
     /// # Returns
-    /// Vec de chunks générés (captions + optionnel OCR)
+    /// Vec de chunks générés (captions + texte de la zone de la figure, par OCR si disponible)
     pub async fn build_figure_chunks_for_page(
         &self,
         page_text: &str,
@@ -64,19 +91,21 @@ impl FigureChunkBuilder {
             page_index + 1
         );
 
-        for figure in detected_figures {
+        let linked_regions = link_captions_to_regions(&detected_figures, &self.layout_blocks);
+
+        for (figure, linked_region) in detected_figures.into_iter().zip(linked_regions) {
             // 2. Créer chunk pour la légende
             let caption_chunk = self.create_caption_chunk(&figure, group_id);
             chunks.push(caption_chunk);
 
             // 3. Si OCR activé et image disponible, créer chunk OCR
-            if let (Some(ocr), Some(image_path)) = (&self.ocr_extractor, page_image_path) {
+            let mut region_chunk = None;
+            if let (Some(_), Some(image_path)) = (&self.ocr_extractor, page_image_path) {
                 match self
                     .create_ocr_chunk(&figure, image_path, group_id)
                     .await
                 {
-                    Ok(Some(ocr_chunk)) => chunks.push(ocr_chunk),
-                    Ok(None) => {}
+                    Ok(chunk) => region_chunk = chunk,
                     Err(e) => {
                         warn!(
                             "Failed to create OCR chunk for {}: {}",
@@ -85,6 +114,25 @@ impl FigureChunkBuilder {
                     }
                 }
             }
+
+            // 4. Sinon, texte de la zone de figure reliée à la légende (même figure_id)
+            if region_chunk.is_none() {
+                region_chunk = linked_region
+                    .filter(|region| region.text.chars().count() >= MIN_REGION_TEXT_CHARS)
+                    .map(|region| self.create_region_chunk(
+                        &figure,
+                        &region.text,
+                        "linked_region",
+                        0.9, // Texte localisé dans la zone de la figure
+                        group_id,
+                    ));
+            }
+
+            // 5. En dernier recours, le texte entourant la légende tient lieu de zone de figure
+            if region_chunk.is_none() {
+                region_chunk = self.create_region_text_chunk(&figure, page_text, group_id);
+            }
+            chunks.extend(region_chunk);
         }
 
         Ok(chunks)
@@ -92,11 +140,7 @@ impl FigureChunkBuilder {
 
     /// Créer un chunk pour une légende de figure
     fn create_caption_chunk(&self, figure: &DetectedFigure, group_id: &str) -> EnrichedChunk {
-        let chunk_id = format!(
-            "fig_caption_{}_p{}",
-            figure.figure_id.replace(' ', "_"),
-            figure.page_index
-        );
+        let chunk_id = figure_chunk_id("fig_caption", figure);
 
         let content = format!(
             "[FIGURE CAPTION - Page {}]\n{}",
@@ -104,6 +148,9 @@ impl FigureChunkBuilder {
             figure.caption
         );
 
+        // Hash du contenu indexé : clé du cache d'embeddings
+        let hash = blake3::hash(content.as_bytes()).to_hex().to_string();
+
         EnrichedChunk {
             id: chunk_id,
             content,
@@ -111,7 +158,7 @@ impl FigureChunkBuilder {
             end_line: figure.page_index as usize,
             chunk_type: ChunkType::TextBlock,
             embedding: None, // Sera généré plus tard
-            hash: blake3::hash(figure.caption.as_bytes()).to_hex().to_string(),
+            hash,
             metadata: ChunkMetadata {
                 tags: vec![
                     "figure".to_string(),
@@ -126,6 +173,7 @@ impl FigureChunkBuilder {
                 ocr_metadata: None,
                 source_type: SourceType::NativeText,
                 extraction_method: ExtractionMethod::DirectRead,
+                page_overlap_len: 0,
             },
             group_id: group_id.to_string(),
             source_spans: None,
@@ -172,11 +220,7 @@ impl FigureChunkBuilder {
             String::new()
         };
 
-        let chunk_id = format!(
-            "fig_ocr_{}_p{}",
-            figure.figure_id.replace(' ', "_"),
-            figure.page_index
-        );
+        let chunk_id = figure_chunk_id("fig_ocr", figure);
 
         let content = format!(
             "[FIGURE OCR - {} - Page {}]\n{}{}\n\n⚠️ Note: Data extracted via OCR from graphic. Verify visually for exact values.",
@@ -186,6 +230,9 @@ impl FigureChunkBuilder {
             kv_summary
         );
 
+        // Hash du contenu indexé : clé du cache d'embeddings
+        let hash = blake3::hash(content.as_bytes()).to_hex().to_string();
+
         Ok(Some(EnrichedChunk {
             id: chunk_id,
             content,
@@ -193,7 +240,7 @@ impl FigureChunkBuilder {
             end_line: figure.page_index as usize,
             chunk_type: ChunkType::TextBlock,
             embedding: None,
-            hash: blake3::hash(filtered_text.as_bytes()).to_hex().to_string(),
+            hash,
             metadata: ChunkMetadata {
                 tags: vec![
                     "figure".to_string(),
@@ -212,6 +259,7 @@ impl FigureChunkBuilder {
                     confidence: 0.7,
                     language: "eng+fra".to_string(),
                 },
+                page_overlap_len: 0,
             },
             group_id: group_id.to_string(),
             source_spans: None,
@@ -220,6 +268,97 @@ impl FigureChunkBuilder {
         }))
     }
 
+    /// Créer un chunk à partir du texte natif entourant une figure (sans OCR)
+    ///
+    /// Retourne `None` si le voisinage de la légende est trop pauvre pour être utile.
+    fn create_region_text_chunk(
+        &self,
+        figure: &DetectedFigure,
+        page_text: &str,
+        group_id: &str,
+    ) -> Option<EnrichedChunk> {
+        let caption_line = page_text
+            .get(figure.text_position..)
+            .and_then(|rest| rest.lines().next())
+            .map(str::trim)
+            .unwrap_or_default();
+
+        let context = self
+            .detector
+            .extract_figure_context(page_text, figure, REGION_CONTEXT_CHARS);
+        let region_text = context
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && *line != caption_line)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if region_text.chars().count() < MIN_REGION_TEXT_CHARS {
+            debug!("Region text too short for {}, caption only", figure.figure_id);
+            return None;
+        }
+
+        Some(self.create_region_chunk(
+            figure,
+            &region_text,
+            "caption_context",
+            0.8, // Voisinage textuel = proche mais pas forcément dans la figure
+            group_id,
+        ))
+    }
+
+    /// Chunk `FigureRegionText` portant le même `figure_id` que la légende
+    fn create_region_chunk(
+        &self,
+        figure: &DetectedFigure,
+        region_text: &str,
+        origin_tag: &str,
+        confidence: f32,
+        group_id: &str,
+    ) -> EnrichedChunk {
+        let content = format!(
+            "[FIGURE REGION - {} - Page {}]\n{}\n\n{}",
+            figure.figure_id,
+            figure.page_index + 1,
+            figure.caption,
+            region_text
+        );
+
+        // Hash du contenu indexé : clé du cache d'embeddings
+        let hash = blake3::hash(content.as_bytes()).to_hex().to_string();
+
+        EnrichedChunk {
+            id: figure_chunk_id("fig_region", figure),
+            content,
+            start_line: figure.page_index as usize,
+            end_line: figure.page_index as usize,
+            chunk_type: ChunkType::TextBlock,
+            embedding: None,
+            hash,
+            metadata: ChunkMetadata {
+                tags: vec![
+                    "figure".to_string(),
+                    "region_text".to_string(),
+                    origin_tag.to_string(),
+                    figure.figure_type.as_str().to_lowercase(),
+                ],
+                priority: Priority::Normal,
+                language: "auto".to_string(),
+                symbol: None,
+                context: Some(format!("Page {} - figure region", figure.page_index + 1)),
+                confidence,
+                ocr_metadata: None,
+                source_type: SourceType::NativeText,
+                extraction_method: ExtractionMethod::DirectRead,
+                page_overlap_len: 0,
+            },
+            group_id: group_id.to_string(),
+            source_spans: None,
+            chunk_source: ChunkSource::FigureRegionText,
+            figure_id: Some(figure.figure_id.clone()),
+        }
+    }
+
     /// Traiter un document complet et générer tous les chunks de figures
     ///
     /// # Arguments
@@ -256,6 +395,106 @@ impl FigureChunkBuilder {
     }
 }
 
+/// Associer chaque légende à la zone de figure la plus proche de sa page (distance entre bbox).
+/// Association un-pour-un, par distance croissante : deux légendes ne partagent pas une zone.
+/// Le résultat est aligné sur `figures` (`None` : légende non localisée ou sans zone disponible).
+pub fn link_captions_to_regions(figures: &[DetectedFigure], blocks: &[OCRBlock]) -> Vec<Option<FigureRegion>> {
+    let regions: Vec<&OCRBlock> = blocks.iter()
+        .filter(|block| matches!(block.block_type, BlockType::Figure))
+        .collect();
+
+    // (distance, index légende, index zone) pour toutes les paires d'une même page
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (figure_index, figure) in figures.iter().enumerate() {
+        let Some(caption_box) = locate_caption(figure, blocks) else {
+            continue;
+        };
+        for (region_index, region) in regions.iter().enumerate() {
+            if region.page_number == figure.page_index + 1 {
+                candidates.push((bbox_distance(caption_box, &region.bounding_box), figure_index, region_index));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut linked: Vec<Option<FigureRegion>> = vec![None; figures.len()];
+    let mut taken = vec![false; regions.len()];
+    for (distance, figure_index, region_index) in candidates {
+        if linked[figure_index].is_some() || taken[region_index] {
+            continue;
+        }
+        taken[region_index] = true;
+
+        let region = regions[region_index];
+        debug!("Linked {} to figure region on page {} (distance {:.3})",
+               figures[figure_index].figure_id, region.page_number, distance);
+        linked[figure_index] = Some(FigureRegion {
+            page_index: figures[figure_index].page_index,
+            bounding_box: region.bounding_box.clone(),
+            text: region_text(region, blocks),
+        });
+    }
+
+    linked
+}
+
+/// Bbox du bloc de texte contenant la légende (comparaison sans casse ni espaces multiples)
+fn locate_caption<'a>(figure: &DetectedFigure, blocks: &'a [OCRBlock]) -> Option<&'a BoundingBox> {
+    let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let caption_text = figure.caption
+        .strip_prefix(&format!("{}: ", figure.figure_id))
+        .unwrap_or(&figure.caption);
+    let caption_text = normalize(caption_text);
+    if caption_text.is_empty() {
+        return None;
+    }
+
+    blocks.iter()
+        .filter(|block| block.page_number == figure.page_index + 1)
+        .filter(|block| !matches!(block.block_type, BlockType::Figure))
+        .find(|block| normalize(&block.content).contains(&caption_text))
+        .map(|block| &block.bounding_box)
+}
+
+/// Écart entre deux bbox (0 si elles se chevauchent), départagé par la distance des centres
+fn bbox_distance(a: &BoundingBox, b: &BoundingBox) -> f64 {
+    let gap_x = (b.x - (a.x + a.width)).max(a.x - (b.x + b.width)).max(0.0);
+    let gap_y = (b.y - (a.y + a.height)).max(a.y - (b.y + b.height)).max(0.0);
+    let center_x = (a.x + a.width / 2.0) - (b.x + b.width / 2.0);
+    let center_y = (a.y + a.height / 2.0) - (b.y + b.height / 2.0);
+    gap_x.hypot(gap_y) + 1e-3 * center_x.hypot(center_y)
+}
+
+/// Texte des blocs dont le centre tombe dans la zone de la figure
+fn region_text(region: &OCRBlock, blocks: &[OCRBlock]) -> String {
+    let area = &region.bounding_box;
+    blocks.iter()
+        .filter(|block| block.page_number == region.page_number)
+        .filter(|block| !matches!(block.block_type, BlockType::Figure))
+        .filter(|block| {
+            let center_x = block.bounding_box.x + block.bounding_box.width / 2.0;
+            let center_y = block.bounding_box.y + block.bounding_box.height / 2.0;
+            center_x >= area.x && center_x <= area.x + area.width
+                && center_y >= area.y && center_y <= area.y + area.height
+        })
+        .map(|block| block.content.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// ID unique par document : l'ID du point Qdrant est dérivé de l'ID du chunk,
+/// deux documents d'un même groupe peuvent avoir chacun une "Figure 1" en page 1
+fn figure_chunk_id(prefix: &str, figure: &DetectedFigure) -> String {
+    format!(
+        "{}_{}_p{}_{}",
+        prefix,
+        figure.figure_id.replace(' ', "_"),
+        figure.page_index,
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
 impl Default for FigureChunkBuilder {
     fn default() -> Self {
         Self::new()
@@ -322,4 +561,81 @@ More text after.
         assert_eq!(chunks[0].chunk_source, ChunkSource::FigureCaption);
         assert!(chunks[0].content.contains("Test caption"));
     }
+
+    #[tokio::test]
+    async fn test_build_figure_chunks_with_region_text() {
+        let builder = FigureChunkBuilder::new();
+
+        let page_text = r#"
+Revenue grew steadily across all regions during the fiscal year.
+Q1 revenue: 1.2M, Q2 revenue: 1.5M, Q3 revenue: 1.9M, Q4 revenue: 2.4M
+
+Figure 2: Quarterly revenue by region
+
+Europe contributed the largest share of the growth.
+        "#;
+
+        let chunks = builder
+            .build_figure_chunks_for_page(page_text, 1, None, "test_group")
+            .await
+            .unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        let region = &chunks[1];
+        assert_eq!(region.chunk_source, ChunkSource::FigureRegionText);
+        assert_eq!(region.figure_id.as_deref(), Some("Figure 2"));
+        assert!(region.content.starts_with("[FIGURE REGION - Figure 2 - Page 2]"));
+        assert!(region.content.contains("Q3 revenue: 1.9M"));
+        assert_ne!(chunks[0].id, region.id);
+    }
+
+    fn block(block_type: BlockType, content: &str, y: f64, height: f64) -> OCRBlock {
+        OCRBlock {
+            page_number: 1,
+            block_type,
+            content: content.to_string(),
+            bounding_box: BoundingBox { x: 0.1, y, width: 0.8, height },
+            confidence: 0.9,
+            spans: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_captions_linked_to_nearest_figure_regions() {
+        let page_text = "Figure 1: Revenue by quarter\nFigure 2: Headcount by site\n";
+
+        // Figure 1 en haut de page (légende sous la zone), Figure 2 en bas (légende au-dessus)
+        let blocks = vec![
+            block(BlockType::Figure, "[Figure/Image: Im1]", 0.05, 0.30),
+            block(BlockType::Text, "Q1 revenue 1.2M, Q2 revenue 1.5M, Q3 revenue 1.9M, Q4 revenue 2.4M across all regions", 0.15, 0.05),
+            block(BlockType::Text, "Figure 1: Revenue by quarter", 0.37, 0.03),
+            block(BlockType::Text, "Figure 2: Headcount   by site", 0.50, 0.03),
+            block(BlockType::Figure, "[Figure/Image: Im2]", 0.55, 0.35),
+            block(BlockType::Text, "Paris 120 employees, Lyon 85 employees, Nantes 40 employees, Lille 22 employees", 0.70, 0.05),
+        ];
+
+        let builder = FigureChunkBuilder::new().with_layout_blocks(blocks);
+        let chunks = builder
+            .build_figure_chunks_for_page(page_text, 0, None, "test_group")
+            .await
+            .unwrap();
+
+        assert_eq!(chunks.len(), 4);
+        let region = |figure_id: &str| chunks.iter()
+            .find(|c| c.chunk_source == ChunkSource::FigureRegionText && c.figure_id.as_deref() == Some(figure_id))
+            .unwrap();
+
+        let revenue = region("Figure 1");
+        assert!(revenue.content.contains("Q3 revenue 1.9M"));
+        assert!(!revenue.content.contains("Lyon"));
+        assert!(revenue.metadata.tags.contains(&"linked_region".to_string()));
+
+        let headcount = region("Figure 2");
+        assert!(headcount.content.contains("Lyon 85 employees"));
+        assert!(!headcount.content.contains("revenue"));
+
+        // Une légende hors de la page des zones n'est pas reliée
+        let other_page = FigureDetector::new().detect_figures_in_page(page_text, 3);
+        assert!(link_captions_to_regions(&other_page, &builder.layout_blocks).iter().all(Option::is_none));
+    }
 }
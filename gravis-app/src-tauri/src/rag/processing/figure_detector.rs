@@ -162,17 +162,25 @@ impl FigureDetector {
         all_figures
     }
 
-    /// Extraire le contexte autour d'une figure (pour analyse future)
+    /// Extraire le contexte autour d'une figure (texte de la zone de la figure)
     pub fn extract_figure_context(
         &self,
         page_text: &str,
         figure: &DetectedFigure,
         context_chars: usize,
     ) -> String {
-        let start = figure.text_position.saturating_sub(context_chars);
-        let end = (figure.text_position + figure.caption.len() + context_chars)
+        let mut start = figure.text_position.saturating_sub(context_chars).min(page_text.len());
+        let mut end = (figure.text_position + figure.caption.len() + context_chars)
             .min(page_text.len());
 
+        // Ne pas couper au milieu d'un caractère multi-octets (accents, symboles)
+        while !page_text.is_char_boundary(start) {
+            start -= 1;
+        }
+        while !page_text.is_char_boundary(end) {
+            end += 1;
+        }
+
         page_text[start..end].to_string()
     }
 }
@@ -266,4 +274,15 @@ Tableau 2: Métriques de performance
         assert!(context.contains("Figure 1"));
         assert!(context.contains("After"));
     }
+
+    #[test]
+    fn test_extract_context_multibyte() {
+        let detector = FigureDetector::new();
+        let text = "Évolution des coûts énergétiques à €/MWh.\nGraphique 2: Prix moyen\nDonnées été 2024 — hausse.";
+
+        let figures = detector.detect_figures_in_page(text, 0);
+        let context = detector.extract_figure_context(text, &figures[0], 7);
+
+        assert!(context.contains("Graphique 2"));
+    }
 }
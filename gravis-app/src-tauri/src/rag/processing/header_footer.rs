@@ -0,0 +1,205 @@
+// GRAVIS RAG - En-têtes / pieds de page répétés
+// Détection des lignes présentes en bord de page sur la majorité des pages (bruit pour le chunking)
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Configuration de la détection des en-têtes/pieds de page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeaderFooterConfig {
+    pub enabled: bool,
+    /// Part minimale des pages où la ligne doit apparaître (0.0-1.0)
+    pub min_page_ratio: f32,
+    /// Nombre de lignes non vides examinées en haut et en bas de chaque page
+    pub edge_lines: usize,
+    /// En dessous de ce nombre de pages, aucune détection
+    pub min_pages: usize,
+}
+
+impl Default for HeaderFooterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_page_ratio: 0.6,
+            edge_lines: 3,
+            min_pages: 3,
+        }
+    }
+}
+
+/// Résultat du nettoyage
+#[derive(Debug, Clone)]
+pub struct HeaderFooterStripResult {
+    pub pages: Vec<String>,
+    pub lines_stripped: usize,
+    /// Formes normalisées des lignes retirées
+    pub patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Edge {
+    Top,
+    Bottom,
+}
+
+/// Numéro de page dans une ligne (« page 3 / 12 », « p. 4 sur 20 », « page 5 of 9 »)
+static PAGE_NUMBER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:page|pg|p\.)\s*\d+(?:\s*(?:/|of|sur|de)\s*\d+)?").expect("Invalid page number pattern")
+});
+
+/// Ligne réduite à un numéro de page (« 3 », « - 3 - », « 3 / 12 »)
+static BARE_PAGE_NUMBER_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[-–—]?\s*\d+\s*(?:(?:/|of|sur|de)\s*\d+)?\s*[-–—]?$").expect("Invalid bare page number pattern")
+});
+
+/// Forme comparable d'une ligne : minuscules, espaces compactés.
+/// Seuls les numéros de page sont neutralisés, pour que « Page 3 / 12 » et « Page 4 / 12 »
+/// soient le même pied de page sans confondre des lignes de contenu qui ne diffèrent
+/// que par un nombre (« Total 2022 » / « Total 2023 »).
+fn normalize_line(line: &str) -> String {
+    let normalized = line.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    if BARE_PAGE_NUMBER_PATTERN.is_match(&normalized) {
+        return "#".to_string();
+    }
+    PAGE_NUMBER_PATTERN.replace_all(&normalized, "page #").into_owned()
+}
+
+/// Indices des lignes non vides en bord de page (haut puis bas, sans doublon)
+fn edge_line_indices(lines: &[&str], edge_lines: usize) -> Vec<(Edge, usize)> {
+    let non_empty: Vec<usize> = (0..lines.len()).filter(|&i| !lines[i].trim().is_empty()).collect();
+    let top: Vec<usize> = non_empty.iter().take(edge_lines).copied().collect();
+
+    let mut indices: Vec<(Edge, usize)> = top.iter().map(|&i| (Edge::Top, i)).collect();
+    indices.extend(
+        non_empty.iter().rev().take(edge_lines)
+            .filter(|i| !top.contains(i))
+            .map(|&i| (Edge::Bottom, i)),
+    );
+    indices
+}
+
+/// Retire des pages les lignes de bord répétées sur au moins `min_page_ratio` des pages.
+/// Les lignes qui varient d'une page à l'autre (contenu réel) sont conservées.
+pub fn strip_repeated_headers_footers(pages: &[String], config: &HeaderFooterConfig) -> HeaderFooterStripResult {
+    let unchanged = || HeaderFooterStripResult {
+        pages: pages.to_vec(),
+        lines_stripped: 0,
+        patterns: Vec::new(),
+    };
+
+    if !config.enabled || pages.len() < config.min_pages.max(2) {
+        return unchanged();
+    }
+
+    // Nombre de pages où chaque ligne normalisée apparaît sur le même bord
+    let mut page_counts: HashMap<(Edge, String), usize> = HashMap::new();
+    for page in pages {
+        let lines: Vec<&str> = page.lines().collect();
+        let seen: HashSet<(Edge, String)> = edge_line_indices(&lines, config.edge_lines)
+            .into_iter()
+            .map(|(edge, i)| (edge, normalize_line(lines[i])))
+            .filter(|(_, normalized)| !normalized.is_empty())
+            .collect();
+        for key in seen {
+            *page_counts.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    let min_pages = ((pages.len() as f32 * config.min_page_ratio).ceil() as usize).max(2);
+    let boilerplate: HashSet<(Edge, String)> = page_counts
+        .into_iter()
+        .filter(|(_, count)| *count >= min_pages)
+        .map(|(key, _)| key)
+        .collect();
+
+    if boilerplate.is_empty() {
+        return unchanged();
+    }
+
+    let mut lines_stripped = 0;
+    let cleaned: Vec<String> = pages
+        .iter()
+        .map(|page| {
+            let lines: Vec<&str> = page.lines().collect();
+            let to_remove: HashSet<usize> = edge_line_indices(&lines, config.edge_lines)
+                .into_iter()
+                .filter(|(edge, i)| boilerplate.contains(&(*edge, normalize_line(lines[*i]))))
+                .map(|(_, i)| i)
+                .collect();
+            lines_stripped += to_remove.len();
+
+            lines.iter()
+                .enumerate()
+                .filter(|(i, _)| !to_remove.contains(i))
+                .map(|(_, line)| *line)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect();
+
+    let mut patterns: Vec<String> = boilerplate.into_iter().map(|(_, pattern)| pattern).collect();
+    patterns.sort();
+    patterns.dedup();
+
+    HeaderFooterStripResult {
+        pages: cleaned,
+        lines_stripped,
+        patterns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report_page(number: usize, body: &str) -> String {
+        format!("ACME Corp - Rapport annuel 2023\n\n{}\nSuite du texte {}\n\nPage {} / 4", body, number, number)
+    }
+
+    #[test]
+    fn test_strip_repeated_headers_footers() {
+        let pages: Vec<String> = (1..=4)
+            .map(|n| report_page(n, &format!("Chiffre d'affaires du trimestre {}", n)))
+            .collect();
+
+        let result = strip_repeated_headers_footers(&pages, &HeaderFooterConfig::default());
+
+        assert_eq!(result.lines_stripped, 8);
+        assert!(result.pages.iter().all(|page| !page.contains("ACME Corp") && !page.contains("Page ")));
+        // Les lignes qui varient (contenu réel) sont conservées
+        assert!(result.pages[2].contains("Chiffre d'affaires du trimestre 3"));
+        assert!(result.pages[2].contains("Suite du texte 3"));
+    }
+
+    #[test]
+    fn test_normalize_line_masks_page_numbers_only() {
+        assert_eq!(normalize_line("Page 3 / 12"), normalize_line("page 4 / 12"));
+        assert_eq!(normalize_line("- 7 -"), normalize_line("- 8 -"));
+        assert_eq!(normalize_line("ACME Corp — p. 3 sur 20"), normalize_line("ACME Corp — p. 4 sur 20"));
+        // Lignes courtes de contenu qui ne diffèrent que par un nombre
+        assert_ne!(normalize_line("Total 2022"), normalize_line("Total 2023"));
+        assert_ne!(normalize_line("Tableau 3"), normalize_line("Tableau 4"));
+    }
+
+    #[test]
+    fn test_keep_lines_below_threshold() {
+        let pages = vec![
+            "Introduction\ncontenu a".to_string(),
+            "Méthodologie\ncontenu b".to_string(),
+            "Introduction\ncontenu c".to_string(),
+            "Résultats\ncontenu d".to_string(),
+        ];
+        let config = HeaderFooterConfig { min_page_ratio: 0.75, ..Default::default() };
+
+        let result = strip_repeated_headers_footers(&pages, &config);
+        assert_eq!(result.lines_stripped, 0);
+        assert_eq!(result.pages, pages);
+    }
+}
@@ -0,0 +1,74 @@
+// GRAVIS RAG - Index de lignes
+// Conversion positions (octets) → numéros de ligne pour start_line/end_line des chunks
+
+/// Débuts de ligne d'un texte. Les numéros de ligne sont 1-based, bornes incluses.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(index, _)| index + 1));
+        Self { line_starts }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Ligne contenant l'octet `offset`
+    pub fn line_at(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+
+    /// Lignes couvertes par une plage d'octets `[start, end)`
+    pub fn line_range(&self, byte_range: (usize, usize)) -> (usize, usize) {
+        let (start, end) = byte_range;
+        let last_byte = end.saturating_sub(1).max(start);
+        (self.line_at(start), self.line_at(last_byte))
+    }
+}
+
+/// Associe à chaque partie d'un `split` sa position dans le texte d'origine
+pub fn parts_with_offsets<'a>(
+    parts: impl Iterator<Item = &'a str>,
+    separator_len: usize,
+) -> impl Iterator<Item = (usize, &'a str)> {
+    parts.scan(0, move |offset, part| {
+        let start = *offset;
+        *offset += part.len() + separator_len;
+        Some((start, part))
+    })
+}
+
+/// Partie débarrassée de ses espaces de bord avec sa plage d'octets (`None` si vide)
+pub fn trimmed_span(offset: usize, part: &str) -> Option<(usize, usize, &str)> {
+    let trimmed = part.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let start = offset + (part.len() - part.trim_start().len());
+    Some((start, start + trimmed.len(), trimmed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_range_and_offsets() {
+        let text = "alpha\nbeta\n\ngamma";
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_range((0, 5)), (1, 1));
+        assert_eq!(index.line_range((0, 10)), (1, 2));
+
+        let parts: Vec<_> = parts_with_offsets(text.split("\n\n"), 2).collect();
+        assert_eq!(parts[1], (12, "gamma"));
+        assert_eq!(trimmed_span(0, "  beta \n"), Some((2, 6, "beta")));
+        assert_eq!(index.line_range((12, 17)), (4, 4));
+    }
+}
@@ -2,8 +2,15 @@
 pub mod document_processor;
 pub mod document_classifier;
 pub mod smart_chunker;
+pub mod ast_chunker;
+pub mod line_index;
+pub mod header_footer;
+pub mod page_overlap;
+pub mod table_detector;
 pub mod business_metadata;
 pub mod span_aware_chunker;
+pub mod document_summary;
+pub mod numeric_facts;
 // Phase 3: Vision-Aware RAG
 pub mod figure_detector;
 pub mod figure_ocr;
@@ -12,8 +19,15 @@ pub mod figure_chunk_builder;
 pub use document_processor::*;
 pub use document_classifier::*;
 pub use smart_chunker::*;
+pub use ast_chunker::*;
+pub use line_index::*;
+pub use header_footer::*;
+pub use page_overlap::*;
+pub use table_detector::*;
 pub use business_metadata::*;
 pub use span_aware_chunker::*;
+pub use document_summary::*;
+pub use numeric_facts::*;
 pub use figure_detector::*;
 pub use figure_ocr::*;
 pub use figure_chunk_builder::*;
\ No newline at end of file
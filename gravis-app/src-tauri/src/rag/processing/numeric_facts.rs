@@ -0,0 +1,228 @@
+// GRAVIS RAG - Faits numériques indexés à l'ingestion
+// Les embeddings denses confondent les nombres ("16x" ≈ "10x") : chaque chunk porte dans son
+// payload Qdrant ses valeurs (montants, pourcentages, ratios, années) avec unité et libellé,
+// filtrables et utilisées pour booster les résultats qui contiennent la valeur demandée.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Clé du payload Qdrant portant les faits numériques d'un chunk
+pub const NUMERIC_FACTS_FIELD: &str = "numeric_facts";
+/// Faits conservés par chunk (tableaux denses)
+const MAX_FACTS_PER_CHUNK: usize = 32;
+/// Mots du libellé conservés avant la valeur
+const LABEL_MAX_WORDS: usize = 6;
+
+/// Montant avec sa devise, avant ou après la valeur ("35 000 €", "1,000,000 €", "12,5 M€", "$1,000", "USD 3.5M").
+/// Milliers groupés par espace, virgule ou point ; multiplicateur optionnel (k, M, Md, millions, milliards).
+static AMOUNT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    let number = r"\d{1,3}(?:[ \u{a0},.]\d{3})+(?:[.,]\d+)?|\d+(?:[.,]\d+)?";
+    let multiplier = r"Md|[kKM]|milliards?|millions?";
+    Regex::new(&format!(
+        r"(?P<prefix>€|\$|\bEUR|\bUSD)\s?(?P<prefixed>{number})(?:\s?(?P<prefix_mult>{multiplier})\b)?|(?P<amount>{number})\s*(?P<mult>{multiplier})?\s*(?:d['’]\s*|de\s+)?(?P<currency>€|\$|EUR|USD|euros?)"
+    ))
+    .expect("Invalid amount pattern")
+});
+
+static PERCENT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d+(?:[.,]\d+)?)\s*%").expect("Invalid percent pattern")
+});
+
+/// Ratio collé à sa valeur ("16x", "10×") ; "2 Xerox" ou "3 x 4" ne sont pas des ratios
+static RATIO_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d+(?:[.,]\d+)?)(?:[xX]\b|\s?×)").expect("Invalid ratio pattern")
+});
+
+static YEAR_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b((?:19|20)\d{2})\b").expect("Invalid year pattern")
+});
+
+/// Valeur numérique d'un chunk avec son unité et le libellé qui la précède
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumericFact {
+    pub value: f64,
+    /// "€", "$", "%", "x" ou "year"
+    pub unit: String,
+    pub label: Option<String>,
+    pub raw_text: String,
+}
+
+impl NumericFact {
+    /// Même unité et même valeur (aux arrondis près)
+    pub fn matches(&self, other: &NumericFact) -> bool {
+        self.unit == other.unit
+            && (self.value - other.value).abs() <= 1e-6 * self.value.abs().max(other.value.abs()).max(1.0)
+    }
+}
+
+/// Extraire les faits numériques d'un texte (chunk ou requête), dans l'ordre d'apparition
+pub fn extract_numeric_facts(text: &str) -> Vec<NumericFact> {
+    let mut spans: Vec<(usize, usize, f64, String)> = Vec::new();
+
+    for caps in AMOUNT_PATTERN.captures_iter(text) {
+        let (amount, multiplier, currency) = match caps.name("prefixed") {
+            Some(amount) => (Some(amount), caps.name("prefix_mult"), caps.name("prefix")),
+            None => (caps.name("amount"), caps.name("mult"), caps.name("currency")),
+        };
+        let (Some(whole), Some(amount), Some(currency)) = (caps.get(0), amount, currency) else {
+            continue;
+        };
+        if let Some(value) = parse_number(amount.as_str()) {
+            let value = value * multiplier.map_or(1.0, |multiplier| multiplier_value(multiplier.as_str()));
+            spans.push((whole.start(), whole.end(), value, currency_unit(currency.as_str()).to_string()));
+        }
+    }
+    for (pattern, unit) in [(&*PERCENT_PATTERN, "%"), (&*RATIO_PATTERN, "x"), (&*YEAR_PATTERN, "year")] {
+        for caps in pattern.captures_iter(text) {
+            let (Some(whole), Some(number)) = (caps.get(0), caps.get(1)) else {
+                continue;
+            };
+            // "2024 €" est un montant, pas une année
+            if spans.iter().any(|(start, end, _, _)| whole.start() < *end && *start < whole.end()) {
+                continue;
+            }
+            if let Some(value) = parse_number(number.as_str()) {
+                spans.push((whole.start(), whole.end(), value, unit.to_string()));
+            }
+        }
+    }
+    spans.sort_by_key(|(start, ..)| *start);
+
+    let mut facts: Vec<NumericFact> = Vec::new();
+    for (start, end, value, unit) in spans {
+        let fact = NumericFact {
+            value,
+            unit,
+            label: label_around(text, start, end),
+            raw_text: text[start..end].trim().to_string(),
+        };
+        if !facts.iter().any(|existing| existing.matches(&fact)) {
+            facts.push(fact);
+        }
+        if facts.len() == MAX_FACTS_PER_CHUNK {
+            break;
+        }
+    }
+    facts
+}
+
+/// Au moins une valeur de la requête figure parmi les faits du chunk
+pub fn facts_match_query(facts: &[NumericFact], query_facts: &[NumericFact]) -> bool {
+    query_facts.iter().any(|wanted| facts.iter().any(|fact| fact.matches(wanted)))
+}
+
+/// "35 000", "1,000,000", "1.250.000,50", "12,5" → f64.
+/// Séparateur décimal : le dernier si virgule et point coexistent ; seul, il est décimal sauf s'il
+/// se répète ou précède exactement 3 chiffres ("1,000" = mille, "0,125" et "12,5" restent décimaux).
+fn parse_number(raw: &str) -> Option<f64> {
+    let digits: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+    let decimal_separator = match (digits.rfind(','), digits.rfind('.')) {
+        (Some(comma), Some(dot)) => Some(if comma > dot { ',' } else { '.' }),
+        (Some(index), None) | (None, Some(index)) => {
+            let separator = char::from(digits.as_bytes()[index]);
+            let repeated = digits.matches(separator).count() > 1;
+            let groups_thousands = digits.len() - index - 1 == 3 && &digits[..index] != "0";
+            (!repeated && !groups_thousands).then_some(separator)
+        }
+        (None, None) => None,
+    };
+
+    digits.chars()
+        .filter_map(|c| match c {
+            ',' | '.' if Some(c) == decimal_separator => Some('.'),
+            ',' | '.' => None,
+            c => Some(c),
+        })
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+/// "k" → 1e3, "M"/"millions" → 1e6, "Md"/"milliards" → 1e9
+fn multiplier_value(raw: &str) -> f64 {
+    match raw {
+        "k" | "K" => 1e3,
+        "Md" => 1e9,
+        raw if raw.starts_with("milliard") => 1e9,
+        _ => 1e6,
+    }
+}
+
+fn currency_unit(raw: &str) -> &'static str {
+    if raw == "$" || raw.eq_ignore_ascii_case("usd") { "$" } else { "€" }
+}
+
+/// Libellé de la valeur : fin de ligne qui la précède ("Chiffre d'affaires 2024 :"),
+/// sinon début de ligne qui la suit ("97.3% precision")
+fn label_around(text: &str, start: usize, end: usize) -> Option<String> {
+    let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = text[end..].find('\n').map_or(text.len(), |index| end + index);
+    let is_separator = |c: char| c.is_whitespace() || matches!(c, ':' | '=' | '-' | '–' | '|' | '(' | ')' | ',' | ';');
+
+    let before: Vec<&str> = text[line_start..start].trim_end_matches(is_separator).split_whitespace().collect();
+    let before = before[before.len().saturating_sub(LABEL_MAX_WORDS)..].join(" ");
+    let after = text[end..line_end].trim_start_matches(is_separator)
+        .split_whitespace()
+        .take(LABEL_MAX_WORDS / 2)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    [before, after].into_iter()
+        .map(|label| label.trim_matches(is_separator).to_string())
+        .find(|label| label.chars().any(char::is_alphabetic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_numeric_facts() {
+        let text = "Chiffre d'affaires 2024 : 1 250 000,50 €\nMarge brute = 38,5 %\nTable 2: 97.3% precision at 16x compression\nVu 2 Xerox, 3 x 4.";
+        let facts = extract_numeric_facts(text);
+        let summary: Vec<(f64, &str)> = facts.iter().map(|fact| (fact.value, fact.unit.as_str())).collect();
+        assert_eq!(summary, vec![(2024.0, "year"), (1_250_000.5, "€"), (38.5, "%"), (97.3, "%"), (16.0, "x")]);
+
+        assert_eq!(facts[1].label.as_deref(), Some("Chiffre d'affaires 2024"));
+        assert_eq!(facts[2].label.as_deref(), Some("Marge brute"));
+        assert_eq!(facts[4].label.as_deref(), Some("Table 2: 97.3% precision at"));
+
+        // Requête "exact match" : le ratio demandé doit figurer tel quel
+        assert!(facts_match_query(&facts, &extract_numeric_facts("quelle précision à 16x ?")));
+        assert!(!facts_match_query(&facts, &extract_numeric_facts("ratio 10x")));
+        assert!(extract_numeric_facts("aucun chiffre ici").is_empty());
+    }
+
+    #[test]
+    fn test_amount_separators_prefixes_and_multipliers() {
+        let amount = |text: &str| {
+            let facts = extract_numeric_facts(text);
+            assert_eq!(facts.len(), 1, "{}: {:?}", text, facts);
+            (facts[0].value, facts[0].unit.clone())
+        };
+
+        // Milliers à la virgule ou au point
+        assert_eq!(amount("Prix : 1,000 $"), (1_000.0, "$".to_string()));
+        assert_eq!(amount("Budget 1,000,000 €"), (1_000_000.0, "€".to_string()));
+        assert_eq!(amount("Budget 1.000.000 €"), (1_000_000.0, "€".to_string()));
+        assert_eq!(amount("Total 1.250.000,50 €"), (1_250_000.5, "€".to_string()));
+        assert_eq!(amount("Total 1,250.75 USD"), (1_250.75, "$".to_string()));
+        // Séparateur unique hors groupe de 3 chiffres : décimal
+        assert_eq!(amount("Frais 12,5 €"), (12.5, "€".to_string()));
+        assert_eq!(amount("Frais 0,125 €"), (0.125, "€".to_string()));
+
+        // Devise avant la valeur
+        assert_eq!(amount("Coût $1,000"), (1_000.0, "$".to_string()));
+        assert_eq!(amount("Levée USD 3.5M"), (3_500_000.0, "$".to_string()));
+
+        // Multiplicateurs
+        assert_eq!(amount("CA 12,5 M€"), (12_500_000.0, "€".to_string()));
+        assert_eq!(amount("Prime 500k€"), (500_000.0, "€".to_string()));
+        assert_eq!(amount("Dette 2 milliards d'euros"), (2_000_000_000.0, "€".to_string()));
+
+        // La même valeur écrite autrement correspond à la requête
+        let facts = extract_numeric_facts("Chiffre d'affaires : 12 500 000 €");
+        assert!(facts_match_query(&facts, &extract_numeric_facts("CA de 12,5 M€ ?")));
+    }
+}
@@ -0,0 +1,157 @@
+// GRAVIS RAG - Overlap entre pages PDF
+// La fin de la page N est reportée en tête du premier chunk de la page N+1 (phrases/tableaux coupés par un saut de page)
+
+use crate::rag::{ChunkSource, EnrichedChunk};
+use crate::rag::ocr::pdf_extract_simple::PAGE_BREAK;
+
+/// Tag des chunks préfixés par la fin de la page précédente
+pub const PAGE_OVERLAP_TAG: &str = "page_overlap";
+
+/// Configuration de l'overlap entre pages
+#[derive(Debug, Clone)]
+pub struct PageOverlapConfig {
+    pub enabled: bool,
+    /// Nombre de mots (≈ tokens) de la fin de page reportés sur la page suivante
+    pub overlap_tokens: usize,
+}
+
+impl Default for PageOverlapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            overlap_tokens: 50,
+        }
+    }
+}
+
+/// Sauts de page réels du texte (`PAGE_BREAK` de l'extraction PDF) : (début de la page précédente, position du saut).
+/// Aucun saut deviné : sans frontière de page connue, pas d'overlap.
+fn page_breaks(content: &str) -> Vec<(usize, usize)> {
+    let mut page_start = 0;
+    content.match_indices(PAGE_BREAK)
+        .map(|(idx, _)| {
+            let page_break = (page_start, idx);
+            page_start = idx + PAGE_BREAK.len_utf8();
+            page_break
+        })
+        .collect()
+}
+
+/// Début (en octets) des `tokens` derniers mots d'un texte, `None` s'il n'a aucun mot
+fn tail_start(text: &str, tokens: usize) -> Option<usize> {
+    let word_starts: Vec<usize> = text.char_indices()
+        .filter(|(idx, c)| !c.is_whitespace() && !text[..*idx].ends_with(|prev: char| !prev.is_whitespace()))
+        .map(|(idx, _)| idx)
+        .collect();
+    word_starts.get(word_starts.len().saturating_sub(tokens)).copied()
+}
+
+/// Préfixer le premier chunk de chaque page par la fin de la page précédente.
+/// La longueur du préfixe est conservée dans `metadata.page_overlap_len` et le chunk tagué
+/// `PAGE_OVERLAP_TAG` (voir `strip_page_overlap`) ; `start_line` et `hash` couvrent le préfixe.
+/// Retourne le nombre de chunks préfixés.
+pub fn apply_page_overlap(chunks: &mut [EnrichedChunk], content: &str, config: &PageOverlapConfig) -> usize {
+    if !config.enabled || config.overlap_tokens == 0 {
+        return 0;
+    }
+
+    let mut extended = 0;
+    for (page_start, break_start) in page_breaks(content) {
+        let page = &content[page_start..break_start];
+        let Some(tail_offset) = tail_start(page, config.overlap_tokens) else {
+            continue;
+        };
+        let tail = page[tail_offset..].trim_end();
+        let tail_line = content[..page_start + tail_offset].matches('\n').count() + 1;
+
+        // Première ligne non vide de la page suivante (1-based, comme `LineIndex`)
+        let break_end = break_start + PAGE_BREAK.len_utf8();
+        let Some(first_char) = content[break_end..].find(|c: char| !c.is_whitespace()) else {
+            continue;
+        };
+        let next_page_line = content[..break_end + first_char].matches('\n').count() + 1;
+
+        let head_chunk = chunks.iter_mut()
+            .filter(|chunk| matches!(chunk.chunk_source, ChunkSource::BodyText | ChunkSource::Table))
+            .filter(|chunk| chunk.metadata.page_overlap_len == 0)
+            .find(|chunk| chunk.start_line == next_page_line);
+
+        if let Some(chunk) = head_chunk {
+            chunk.content = format!("{}\n{}", tail, chunk.content);
+            chunk.metadata.page_overlap_len = tail.len();
+            chunk.metadata.tags.push(PAGE_OVERLAP_TAG.to_string());
+            chunk.start_line = tail_line.min(chunk.start_line);
+            chunk.generate_hash();
+            extended += 1;
+        }
+    }
+
+    extended
+}
+
+/// Contenu propre du chunk, sans la fin de page précédente (scoring lexical, payload : pas de double comptage)
+pub fn strip_page_overlap(chunk: &EnrichedChunk) -> &str {
+    without_page_overlap(&chunk.content, chunk.metadata.page_overlap_len)
+}
+
+/// `content` sans ses `page_overlap_len` premiers octets (préfixe de la page précédente)
+pub fn without_page_overlap(content: &str, page_overlap_len: usize) -> &str {
+    if page_overlap_len == 0 || !content.is_char_boundary(page_overlap_len) {
+        return content;
+    }
+    content[page_overlap_len..].trim_start_matches('\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rag::test_fixtures::test_chunk;
+    use crate::rag::{ExtractionMethod, SourceType};
+
+    fn chunk(id: &str, content: &str, start_line: usize) -> EnrichedChunk {
+        let mut chunk = test_chunk(id, content);
+        chunk.start_line = start_line;
+        chunk.end_line = start_line + content.matches('\n').count();
+        chunk.metadata.confidence = 0.9;
+        chunk.metadata.source_type = SourceType::OcrExtracted;
+        chunk.metadata.extraction_method = ExtractionMethod::PdfOcrFallback;
+        chunk
+    }
+
+    #[test]
+    fn test_sentence_split_across_pages() {
+        let page_1 = "Conditions générales\nLe montant total de l'indemnité versée au salarié\n";
+        let page_2 = "s'élève à 12 400 euros bruts.\nArticle 2 : préavis";
+        let content = format!("{}\x0C{}", page_1, page_2);
+
+        let mut chunks = vec![chunk("c1", page_1.trim_end(), 1), chunk("c2", page_2, 3)];
+        let hash_before = chunks[1].hash.clone();
+        let extended = apply_page_overlap(&mut chunks, &content, &PageOverlapConfig { enabled: true, overlap_tokens: 5 });
+
+        assert_eq!(extended, 1);
+        assert_eq!(chunks[0].content, page_1.trim_end());
+        assert!(chunks[1].content.starts_with("de l'indemnité versée au salarié\ns'élève à 12 400 euros"));
+        assert!(chunks[1].metadata.tags.contains(&PAGE_OVERLAP_TAG.to_string()));
+        // Le chunk couvre désormais la fin de la page 1 (ligne 2) ; hash recalculé sur le contenu préfixé
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (2, 4));
+        assert_ne!(chunks[1].hash, hash_before);
+        assert_eq!(chunks[1].metadata.context, None);
+
+        // Le préfixe est retiré pour le scoring lexical et le payload
+        assert_eq!(strip_page_overlap(&chunks[1]), page_2);
+        assert_eq!(strip_page_overlap(&chunks[0]), page_1.trim_end());
+
+        let disabled = PageOverlapConfig { enabled: false, ..PageOverlapConfig::default() };
+        let mut untouched = vec![chunk("c2", page_2, 3)];
+        assert_eq!(apply_page_overlap(&mut untouched, &content, &disabled), 0);
+    }
+
+    #[test]
+    fn test_no_overlap_without_real_page_break() {
+        // Paragraphes séparés par des lignes vides : pas des pages
+        let content = "Premier paragraphe du rapport.\n\n\nSecond paragraphe du rapport.";
+        let mut chunks = vec![chunk("c1", "Premier paragraphe du rapport.", 1), chunk("c2", "Second paragraphe du rapport.", 4)];
+        assert_eq!(apply_page_overlap(&mut chunks, content, &PageOverlapConfig::default()), 0);
+        assert_eq!(chunks[1].content, "Second paragraphe du rapport.");
+    }
+}
@@ -3,12 +3,16 @@
 
 use regex::Regex;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
-use crate::rag::{EnrichedChunk, ChunkType, ChunkMetadata, SourceType, ExtractionMethod, Priority, ChunkSource, LigatureCleaner};
+use crate::rag::{EnrichedChunk, ChunkType, ChunkMetadata, SourceType, ExtractionMethod, Priority, ChunkSource, LigatureCleaner, ChunkConfig, ChunkStrategy, CHARS_PER_TOKEN};
+use crate::rag::processing::ast_chunker::{parse_top_level_segments, AstSegment, CodeLanguage};
+use crate::rag::processing::line_index::{LineIndex, trimmed_span};
 
 /// Configuration pour le chunking intelligent
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SmartChunkConfig {
     /// Taille cible en tokens (600-900 recommandé)
     pub target_tokens: usize,
@@ -35,6 +39,41 @@ impl Default for SmartChunkConfig {
 }
 
 impl SmartChunkConfig {
+    /// Valider la cohérence des tailles (configuration fournie par l'utilisateur)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.target_tokens == 0 || self.chars_per_token <= 0.0 {
+            return Err("target_tokens and chars_per_token must be positive".to_string());
+        }
+        if self.min_tokens > self.max_tokens || self.target_tokens > self.max_tokens {
+            return Err(format!(
+                "Inconsistent sizes: min_tokens {} <= target_tokens {} <= max_tokens {} required",
+                self.min_tokens, self.target_tokens, self.max_tokens
+            ));
+        }
+        if !(0.0..1.0).contains(&self.overlap_percent) {
+            return Err(format!("overlap_percent must be in [0, 1), got {}", self.overlap_percent));
+        }
+        if !(0.0..=1.0).contains(&self.mmr_lambda) {
+            return Err(format!("mmr_lambda must be in [0, 1], got {}", self.mmr_lambda));
+        }
+        Ok(())
+    }
+
+    /// Configuration dérivée du `ChunkConfig` d'un groupe (chunk_size/overlap en tokens)
+    pub fn from_chunk_config(chunk_config: &ChunkConfig) -> Self {
+        let target_tokens = chunk_config.chunk_size;
+        Self {
+            target_tokens,
+            overlap_percent: chunk_config.overlap as f32 / chunk_config.chunk_size.max(1) as f32,
+            min_tokens: target_tokens / 2,
+            max_tokens: target_tokens + 100,
+            chars_per_token: CHARS_PER_TOKEN,
+            overlap_target_ratio: None,
+            mmr_lambda: 0.5,
+            max_context_docs: 10,
+        }
+    }
+
     /// Configuration optimisée pour documents académiques (Phase 2 validée)
     pub fn academic_optimized() -> Self {
         Self {
@@ -117,13 +156,87 @@ pub struct ChunkSection {
 }
 
 /// Résultat d'un chunking intelligent
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SmartChunkResult {
     pub chunks: Vec<EnrichedChunk>,
     pub sections_detected: Vec<String>,
     pub total_chars: usize,
     pub avg_chunk_size: f32,
     pub processing_time_ms: u64,
+    /// Stratégie effectivement appliquée (AstFirst/Hybrid retombent sur Heuristic hors code)
+    pub strategy_used: ChunkStrategy,
+    /// Recouvrement effectif entre chunks consécutifs
+    pub overlap: ChunkOverlapStats,
+}
+
+/// Recouvrement entre chunks consécutifs (fin du chunk N répétée en tête du chunk N+1)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkOverlapStats {
+    /// Paires de chunks consécutifs examinées
+    pub pairs: usize,
+    pub overlapping_pairs: usize,
+    /// Moyenne sur les paires avec recouvrement
+    pub avg_overlap_chars: f32,
+    pub max_overlap_chars: usize,
+    /// Part moyenne du chunk suivant occupée par le recouvrement
+    pub avg_overlap_ratio: f32,
+}
+
+impl ChunkOverlapStats {
+    pub fn compute(chunks: &[EnrichedChunk]) -> Self {
+        let mut stats = Self { pairs: chunks.len().saturating_sub(1), ..Self::default() };
+        let mut total_chars = 0;
+        let mut total_ratio = 0.0;
+
+        for pair in chunks.windows(2) {
+            let overlap = suffix_prefix_overlap(&pair[0].content, &pair[1].content);
+            if overlap == 0 {
+                continue;
+            }
+            stats.overlapping_pairs += 1;
+            stats.max_overlap_chars = stats.max_overlap_chars.max(overlap);
+            total_chars += overlap;
+            total_ratio += overlap as f32 / pair[1].content.chars().count().max(1) as f32;
+        }
+
+        if stats.overlapping_pairs > 0 {
+            stats.avg_overlap_chars = total_chars as f32 / stats.overlapping_pairs as f32;
+            stats.avg_overlap_ratio = total_ratio / stats.overlapping_pairs as f32;
+        }
+        stats
+    }
+}
+
+/// Longueur (en caractères) du plus long suffixe de `previous` qui commence `next` (fonction préfixe KMP)
+fn suffix_prefix_overlap(previous: &str, next: &str) -> usize {
+    let pattern: Vec<char> = next.chars().collect();
+    if pattern.is_empty() {
+        return 0;
+    }
+
+    let mut failure = vec![0usize; pattern.len()];
+    let mut k = 0;
+    for i in 1..pattern.len() {
+        while k > 0 && pattern[i] != pattern[k] {
+            k = failure[k - 1];
+        }
+        if pattern[i] == pattern[k] {
+            k += 1;
+        }
+        failure[i] = k;
+    }
+
+    // Parcours de `previous` : longueur du préfixe de `next` reconnue en fin de texte
+    let mut matched = 0;
+    for c in previous.chars() {
+        while matched > 0 && (matched == pattern.len() || c != pattern[matched]) {
+            matched = failure[matched - 1];
+        }
+        if c == pattern[matched] {
+            matched += 1;
+        }
+    }
+    matched
 }
 
 /// Chunker intelligent avec détection de sections
@@ -132,6 +245,8 @@ pub struct SmartChunker {
     section_regex: Regex,
     sentence_regex: Regex,
     ligature_cleaner: LigatureCleaner,
+    strategy: ChunkStrategy,
+    code_language: Option<CodeLanguage>,
 }
 
 impl SmartChunker {
@@ -155,6 +270,8 @@ impl SmartChunker {
             section_regex,
             sentence_regex,
             ligature_cleaner: LigatureCleaner::default(),
+            strategy: ChunkStrategy::Heuristic,
+            code_language: None,
         })
     }
 
@@ -173,6 +290,8 @@ impl SmartChunker {
             section_regex,
             sentence_regex,
             ligature_cleaner: LigatureCleaner::default(),
+            strategy: ChunkStrategy::Heuristic,
+            code_language: None,
         })
     }
 
@@ -190,6 +309,8 @@ impl SmartChunker {
             section_regex,
             sentence_regex,
             ligature_cleaner: LigatureCleaner::default(),
+            strategy: ChunkStrategy::Heuristic,
+            code_language: None,
         })
     }
 
@@ -207,9 +328,23 @@ impl SmartChunker {
             section_regex,
             sentence_regex,
             ligature_cleaner: LigatureCleaner::default(),
+            strategy: ChunkStrategy::Heuristic,
+            code_language: None,
         })
     }
 
+    /// Stratégie demandée par le groupe (Heuristic par défaut)
+    pub fn with_strategy(mut self, strategy: ChunkStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Langage du code source, requis pour le découpage AST
+    pub fn with_code_language(mut self, language: Option<CodeLanguage>) -> Self {
+        self.code_language = language;
+        self
+    }
+
     /// Calcul dynamique de l'overlap basé sur P50 tokens observé
     fn calculate_dynamic_overlap(&self, p50_tokens: usize) -> f32 {
         // Formule: overlap = clamp(round(0.15 * p50_tokens), 20, 64) / p50_tokens
@@ -233,29 +368,35 @@ impl SmartChunker {
         // 0. Nettoyage des ligatures avec logging
         let cleaned_content = self.ligature_cleaner.clean_and_log(content, "document_chunking");
         
-        // 1. Détection des sections
-        let sections = self.detect_sections(&cleaned_content);
-        info!("Detected {} sections", sections.len());
-        
-        // 2. Split en segments par section
-        let section_segments = self.split_by_sections(&cleaned_content, &sections);
-        
-        // 3. Chunking intelligent de chaque segment
-        let mut all_chunks = Vec::new();
-        let mut chunk_index = 0;
-        
-        for (section_info, section_content) in section_segments {
-            let section_chunks = self.chunk_section(
-                &section_content,
-                &section_info,
-                &mut chunk_index,
-                source_type.clone(),
-                extraction_method,
-                group_id,
-            )?;
-            
-            all_chunks.extend(section_chunks);
-        }
+        // 1. Découpage AST pour le code source (AstFirst/Hybrid), sinon heuristique
+        let ast_result = match (self.strategy, self.code_language) {
+            (ChunkStrategy::Heuristic, _) | (_, None) => None,
+            (strategy, Some(language)) => parse_top_level_segments(&cleaned_content, language)
+                .map(|segments| (strategy, language, segments)),
+        };
+
+        let (all_chunks, sections, strategy_used) = match ast_result {
+            Some((strategy, language, segments)) => {
+                info!("🌳 AST chunking ({:?}): {} top-level {} declarations", strategy, segments.len(), language.name());
+                let chunks = self.chunk_ast_segments(
+                    &cleaned_content,
+                    &segments,
+                    strategy == ChunkStrategy::Hybrid,
+                    language,
+                    source_type,
+                    extraction_method,
+                    group_id,
+                )?;
+                (chunks, Vec::new(), strategy)
+            }
+            None => {
+                if self.strategy != ChunkStrategy::Heuristic {
+                    debug!("{:?} requested but no AST available, falling back to heuristic chunking", self.strategy);
+                }
+                let (chunks, sections) = self.chunk_heuristic(&cleaned_content, source_type, extraction_method, group_id)?;
+                (chunks, sections, ChunkStrategy::Heuristic)
+            }
+        };
         
         let processing_time = start_time.elapsed();
         let avg_chunk_size = if !all_chunks.is_empty() {
@@ -264,6 +405,8 @@ impl SmartChunker {
             0.0
         };
         
+        let overlap = ChunkOverlapStats::compute(&all_chunks);
+
         // Générer le résumé des ligatures traitées
         self.ligature_cleaner.log_summary();
         
@@ -273,9 +416,140 @@ impl SmartChunker {
             total_chars: content.len(),
             avg_chunk_size,
             processing_time_ms: processing_time.as_millis() as u64,
+            strategy_used,
+            overlap,
         })
     }
 
+    /// Fenêtres glissantes par phrases à l'intérieur des sections détectées
+    fn chunk_heuristic(
+        &self,
+        content: &str,
+        source_type: SourceType,
+        extraction_method: &ExtractionMethod,
+        group_id: &str,
+    ) -> Result<(Vec<EnrichedChunk>, Vec<ChunkSection>)> {
+        // Détection des sections
+        let sections = self.detect_sections(content);
+        info!("Detected {} sections", sections.len());
+        
+        // Split en segments par section
+        let section_segments = self.split_by_sections(content, &sections);
+        
+        // Chunking intelligent de chaque segment
+        let line_index = LineIndex::new(content);
+        let mut all_chunks = Vec::new();
+        let mut chunk_index = 0;
+        
+        for (section_info, section_content) in section_segments {
+            let section_start = section_info.char_range.0;
+            let section_chunks = self.chunk_section(
+                &section_content,
+                &section_info,
+                &mut chunk_index,
+                source_type.clone(),
+                extraction_method,
+                group_id,
+            )?;
+            
+            // Plages relatives à la section → lignes du document
+            for (mut chunk, (start, end)) in section_chunks {
+                (chunk.start_line, chunk.end_line) = line_index.line_range((section_start + start, section_start + end));
+                all_chunks.push(chunk);
+            }
+        }
+
+        Ok((all_chunks, sections))
+    }
+
+    /// Chunking du code aux frontières AST.
+    /// AstFirst: une déclaration par chunk (imports consécutifs regroupés).
+    /// Hybrid: déclarations consécutives regroupées jusqu'à la taille cible.
+    /// Dans les deux cas, une déclaration trop longue est découpée par fenêtres de lignes.
+    #[allow(clippy::too_many_arguments)]
+    fn chunk_ast_segments(
+        &self,
+        content: &str,
+        segments: &[AstSegment],
+        pack_to_target: bool,
+        language: CodeLanguage,
+        source_type: SourceType,
+        extraction_method: &ExtractionMethod,
+        group_id: &str,
+    ) -> Result<Vec<EnrichedChunk>> {
+        let target_chars = (self.config.target_tokens as f32 * self.config.chars_per_token) as usize;
+        let max_chars = (self.config.max_tokens as f32 * self.config.chars_per_token) as usize;
+
+        // Regroupement des segments adjacents
+        let mut groups: Vec<Vec<&AstSegment>> = Vec::new();
+        for segment in segments {
+            let segment_len = segment.byte_range.1 - segment.byte_range.0;
+            let merge = groups.last().is_some_and(|group| {
+                let last = group[group.len() - 1];
+                let group_len = segment.byte_range.1 - group[0].byte_range.0;
+                if pack_to_target {
+                    group_len <= target_chars
+                } else {
+                    matches!(last.chunk_type, ChunkType::Module) && matches!(segment.chunk_type, ChunkType::Module)
+                        && segment_len < target_chars
+                }
+            });
+
+            match groups.last_mut() {
+                Some(group) if merge => group.push(segment),
+                _ => groups.push(vec![segment]),
+            }
+        }
+
+        let mut chunks = Vec::new();
+        for group in groups {
+            let first = group[0];
+            let last = group[group.len() - 1];
+            let text = &content[first.byte_range.0..last.byte_range.1];
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            // Le type et le symbole d'un groupe sont ceux de sa première déclaration significative
+            let main = group
+                .iter()
+                .find(|s| matches!(s.chunk_type, ChunkType::Function | ChunkType::Class))
+                .copied()
+                .unwrap_or(first);
+
+            for (window, line_offset) in split_lines_by_size(text, max_chars) {
+                // line_range AST: lignes 0-based → start_line/end_line 1-based inclusifs
+                let start_line = first.line_range.0 + line_offset + 1;
+                let end_line = start_line + window.trim_end().lines().count().max(1) - 1;
+                let mut chunk = self.create_enriched_chunk(
+                    window.trim_end(),
+                    chunks.len(),
+                    &ChunkSection {
+                        section_title: main.symbol.clone(),
+                        section_level: 1,
+                        section_number: None,
+                        page_number: None,
+                        char_range: (first.byte_range.0, last.byte_range.1),
+                    },
+                    source_type.clone(),
+                    extraction_method,
+                    group_id,
+                )?;
+
+                chunk.start_line = start_line;
+                chunk.end_line = end_line;
+                chunk.chunk_type = main.chunk_type;
+                chunk.metadata.language = language.name().to_string();
+                chunk.metadata.symbol = main.symbol.clone();
+                chunk.metadata.tags.push(format!("strategy:{}", if pack_to_target { "hybrid" } else { "ast" }));
+                chunk.generate_hash();
+                chunks.push(chunk);
+            }
+        }
+
+        Ok(chunks)
+    }
+
     /// Détecte les sections dans le contenu
     fn detect_sections(&self, content: &str) -> Vec<ChunkSection> {
         let mut sections = Vec::new();
@@ -403,7 +677,8 @@ impl SmartChunker {
         segments
     }
 
-    /// Chunk une section spécifique avec taille optimisée
+    /// Chunk une section spécifique avec taille optimisée.
+    /// Chaque chunk est accompagné de sa plage d'octets relative à `content`.
     fn chunk_section(
         &self,
         content: &str,
@@ -412,7 +687,7 @@ impl SmartChunker {
         source_type: SourceType,
         extraction_method: &ExtractionMethod,
         group_id: &str,
-    ) -> Result<Vec<EnrichedChunk>> {
+    ) -> Result<Vec<(EnrichedChunk, (usize, usize))>> {
         let target_chars = (self.config.target_tokens as f32 * self.config.chars_per_token) as usize;
         
         // Calcul overlap dynamique si configuré
@@ -430,31 +705,40 @@ impl SmartChunker {
         
         // Si la section est petite, la traiter comme un seul chunk
         if content.len() <= target_chars {
-            if content.trim().len() >= (self.config.min_tokens as f32 * self.config.chars_per_token) as usize {
-                let chunk = self.create_enriched_chunk(
-                    content.trim(),
-                    *chunk_index,
-                    section_info,
-                    source_type.clone(),
-                    extraction_method,
-                    group_id,
-                )?;
-                chunks.push(chunk);
-                *chunk_index += 1;
+            if let Some((start, end, text)) = trimmed_span(0, content) {
+                if text.len() >= (self.config.min_tokens as f32 * self.config.chars_per_token) as usize {
+                    let chunk = self.create_enriched_chunk(
+                        text,
+                        *chunk_index,
+                        section_info,
+                        source_type.clone(),
+                        extraction_method,
+                        group_id,
+                    )?;
+                    chunks.push((chunk, (start, end)));
+                    *chunk_index += 1;
+                }
             }
             return Ok(chunks);
         }
         
-        // Split par phrases pour préserver la cohérence
-        let sentences: Vec<&str> = self.sentence_regex.split(content).collect();
+        // Split par phrases pour préserver la cohérence (avec position de chaque phrase)
+        let mut sentences = Vec::new();
+        let mut last_end = 0;
+        for separator in self.sentence_regex.find_iter(content) {
+            sentences.push((last_end, &content[last_end..separator.start()]));
+            last_end = separator.end();
+        }
+        sentences.push((last_end, &content[last_end..]));
+
         let mut current_chunk = String::new();
         let mut current_size = 0;
+        let mut chunk_range = (0, 0);
         
-        for sentence in sentences {
-            let sentence = sentence.trim();
-            if sentence.is_empty() {
+        for (offset, sentence) in sentences {
+            let Some((sentence_start, sentence_end, sentence)) = trimmed_span(offset, sentence) else {
                 continue;
-            }
+            };
             
             let sentence_size = sentence.len();
             
@@ -469,7 +753,7 @@ impl SmartChunker {
                     extraction_method,
                     group_id,
                 )?;
-                chunks.push(chunk);
+                chunks.push((chunk, chunk_range));
                 *chunk_index += 1;
                 
                 // Commencer nouveau chunk avec overlap - safe Unicode slicing
@@ -480,16 +764,21 @@ impl SmartChunker {
                     current_chunk.clone()
                 };
                 
+                // L'overlap reprend (approximativement) la fin du chunk précédent
+                chunk_range.0 = chunk_range.1.saturating_sub(overlap_content.len()).max(chunk_range.0);
                 current_chunk = format!("{} {}", overlap_content, sentence);
                 current_size = current_chunk.len();
             } else {
                 // Ajouter à ce chunk
-                if !current_chunk.is_empty() {
+                if current_chunk.is_empty() {
+                    chunk_range.0 = sentence_start;
+                } else {
                     current_chunk.push(' ');
                 }
                 current_chunk.push_str(sentence);
                 current_size += sentence_size + 1;
             }
+            chunk_range.1 = sentence_end;
             
             // Split forcé si trop grand
             if current_size > max_chars {
@@ -501,7 +790,7 @@ impl SmartChunker {
                     extraction_method,
                     group_id,
                 )?;
-                chunks.push(chunk);
+                chunks.push((chunk, chunk_range));
                 *chunk_index += 1;
                 current_chunk.clear();
                 current_size = 0;
@@ -518,7 +807,7 @@ impl SmartChunker {
                 extraction_method,
                 group_id,
             )?;
-            chunks.push(chunk);
+            chunks.push((chunk, chunk_range));
             *chunk_index += 1;
         }
         
@@ -548,8 +837,8 @@ impl SmartChunker {
         let mut chunk = EnrichedChunk {
             id: format!("chunk_smart_{}_{}", uuid::Uuid::new_v4().simple(), index),
             content: content.to_string(),
-            start_line: index,
-            end_line: index + 1,
+            start_line: 0, // Lignes renseignées par l'appelant
+            end_line: 0,
             chunk_type: ChunkType::TextBlock,
             embedding: None,
             hash: String::new(),
@@ -570,6 +859,7 @@ impl SmartChunker {
                 ocr_metadata: None,
                 source_type,
                 extraction_method: extraction_method.clone(),
+                page_overlap_len: 0,
             },
             group_id: group_id.to_string(),
             source_spans: None,
@@ -582,11 +872,66 @@ impl SmartChunker {
     }
 }
 
+/// Découpe un bloc de code en fenêtres de lignes ne dépassant pas `max_chars`
+/// (retourne chaque fenêtre avec son décalage de ligne)
+fn split_lines_by_size(text: &str, max_chars: usize) -> Vec<(String, usize)> {
+    if text.len() <= max_chars {
+        return vec![(text.to_string(), 0)];
+    }
+
+    let mut windows = Vec::new();
+    let mut current = String::new();
+    let mut window_start = 0;
+
+    for (line_index, line) in text.lines().enumerate() {
+        if !current.is_empty() && current.len() + line.len() + 1 > max_chars {
+            windows.push((std::mem::take(&mut current), window_start));
+            window_start = line_index;
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if !current.trim().is_empty() {
+        windows.push((current, window_start));
+    }
+
+    windows
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::rag::ExtractionMethod;
 
+    #[test]
+    fn test_overlap_stats_and_config_validation() {
+        assert_eq!(suffix_prefix_overlap("alpha beta gamma", "beta gamma delta"), "beta gamma".len());
+        assert_eq!(suffix_prefix_overlap("abc", "xyz"), 0);
+
+        let config = SmartChunkConfig { target_tokens: 40, min_tokens: 5, max_tokens: 80, ..SmartChunkConfig::default() };
+        assert!(config.validate().is_ok());
+        let text = (1..=40)
+            .map(|i| format!("Sentence number {} describes the quarterly results in detail.", i))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut chunker = SmartChunker::new(config).unwrap();
+        let result = chunker.chunk_document(&text, SourceType::NativeText, &ExtractionMethod::DirectRead, "preview").unwrap();
+
+        assert!(result.chunks.len() > 2);
+        assert_eq!(result.overlap.pairs, result.chunks.len() - 1);
+        assert!(result.overlap.overlapping_pairs > 0);
+        assert!(result.overlap.avg_overlap_ratio > 0.0 && result.overlap.avg_overlap_ratio < 1.0);
+
+        let inverted = SmartChunkConfig { min_tokens: 900, ..SmartChunkConfig::default() };
+        assert!(inverted.validate().is_err());
+
+        // Configuration partielle depuis le frontend : valeurs par défaut pour le reste
+        let partial: SmartChunkConfig = serde_json::from_str(r#"{"target_tokens": 300}"#).unwrap();
+        assert_eq!(partial.target_tokens, 300);
+        assert_eq!(partial.max_tokens, SmartChunkConfig::default().max_tokens);
+    }
+
     #[test]
     fn test_section_detection() {
         let chunker = SmartChunker::new(SmartChunkConfig::default()).unwrap();
@@ -616,4 +961,39 @@ Our experimental results.
         assert_eq!(sections[2].section_title, Some("Data Processing".to_string()));
         assert_eq!(sections[3].section_title, Some("Results".to_string()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_strategy_used_for_code_and_prose() {
+        let code = "use std::fmt;\n\nfn alpha() -> u32 {\n    1\n}\n\nfn beta() -> u32 {\n    2\n}\n";
+
+        let mut ast_chunker = SmartChunker::new(SmartChunkConfig::default())
+            .unwrap()
+            .with_strategy(ChunkStrategy::AstFirst)
+            .with_code_language(Some(CodeLanguage::Rust));
+        let result = ast_chunker
+            .chunk_document(code, SourceType::NativeText, &ExtractionMethod::DirectRead, "group")
+            .unwrap();
+        assert_eq!(result.strategy_used, ChunkStrategy::AstFirst);
+        assert_eq!(result.chunks.len(), 3);
+        assert_eq!(result.chunks[1].metadata.symbol.as_deref(), Some("alpha"));
+
+        let mut hybrid_chunker = SmartChunker::new(SmartChunkConfig::default())
+            .unwrap()
+            .with_strategy(ChunkStrategy::Hybrid)
+            .with_code_language(Some(CodeLanguage::Rust));
+        let result = hybrid_chunker
+            .chunk_document(code, SourceType::NativeText, &ExtractionMethod::DirectRead, "group")
+            .unwrap();
+        assert_eq!(result.strategy_used, ChunkStrategy::Hybrid);
+        assert_eq!(result.chunks.len(), 1);
+
+        // Sans langage détecté, AstFirst retombe sur l'heuristique
+        let mut prose_chunker = SmartChunker::new(SmartChunkConfig::default())
+            .unwrap()
+            .with_strategy(ChunkStrategy::AstFirst);
+        let result = prose_chunker
+            .chunk_document("Introduction\n\nDu texte.", SourceType::NativeText, &ExtractionMethod::DirectRead, "group")
+            .unwrap();
+        assert_eq!(result.strategy_used, ChunkStrategy::Heuristic);
+    }
+}
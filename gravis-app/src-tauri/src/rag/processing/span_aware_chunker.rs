@@ -297,6 +297,7 @@ impl SpanAwareChunker {
                     ExtractionMethod::HybridIntelligent => SourceType::HybridPdfNative,
                 },
                 extraction_method: extraction_method.clone(),
+                page_overlap_len: 0,
             },
             group_id: group_id.to_string(),
             source_spans: Some(span_ids),
@@ -0,0 +1,195 @@
+// GRAVIS RAG - Détection de tableaux
+// Colonnes alignées → tableau markdown, conservé comme chunk atomique
+
+use crate::rag::processing::line_index::parts_with_offsets;
+
+/// Séparateurs de colonnes reconnus. Pas de double espace : la prose en contient
+/// (après un point, justification), seul un alignement d'au moins trois espaces compte.
+const TABLE_SEPARATORS: [&str; 4] = ["\t", "   ", " | ", "|"];
+
+/// Colonnes minimales d'une ligne de tableau : deux "colonnes" ressemblent trop à de la prose
+const MIN_TABLE_COLUMNS: usize = 3;
+
+/// Nombre maximal de colonnes d'une ligne selon les séparateurs reconnus
+fn table_column_count(line: &str) -> usize {
+    TABLE_SEPARATORS
+        .iter()
+        .map(|sep| line.split(sep).filter(|s| !s.trim().is_empty()).count())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Ligne ayant au moins `MIN_TABLE_COLUMNS` colonnes
+fn is_table_row(line: &str) -> bool {
+    table_column_count(line.trim()) >= MIN_TABLE_COLUMNS
+}
+
+/// Détecter contenu tabulaire : au moins deux lignes avec le même nombre de colonnes (≥ `MIN_TABLE_COLUMNS`)
+pub fn detect_table_content(lines: &[&str], start_idx: usize) -> Option<usize> {
+    let mut lines_consumed = 0;
+    let mut consistent_columns = 0;
+    let mut total_potential_rows = 0;
+
+    for i in start_idx..std::cmp::min(start_idx + 8, lines.len()) {
+        let line = lines[i].trim();
+        
+        if line.is_empty() {
+            lines_consumed += 1;
+            continue;
+        }
+
+        // Détecter séparateurs tabulaires
+        let max_columns = table_column_count(line);
+
+        if max_columns >= MIN_TABLE_COLUMNS {
+            if consistent_columns == 0 {
+                consistent_columns = max_columns;
+            }
+            
+            // Colonnes alignées : même nombre que la première ligne du tableau
+            if max_columns == consistent_columns {
+                total_potential_rows += 1;
+            }
+        }
+
+        lines_consumed += 1;
+
+        // Stop si trop de lignes sans structure tabulaire
+        if total_potential_rows == 0 && lines_consumed > 3 {
+            break;
+        }
+    }
+
+    // Au moins 2 lignes avec structure cohérente = table
+    if total_potential_rows >= 2 {
+        Some(lines_consumed)
+    } else {
+        None
+    }
+}
+
+/// Structurer le contenu tabulaire
+pub fn structure_table_content(lines: &[&str]) -> String {
+    let mut structured = String::new();
+    structured.push_str("📋 **Table Data**\n\n");
+
+    let mut is_first_row = true;
+
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() { continue; }
+
+        // Essayer différents séparateurs
+        let separators = TABLE_SEPARATORS;
+        let mut best_columns = Vec::new();
+        let mut max_columns = 0;
+
+        for sep in &separators {
+            let columns: Vec<&str> = line.split(sep)
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .collect();
+            
+            if columns.len() > max_columns {
+                max_columns = columns.len();
+                best_columns = columns;
+            }
+        }
+
+        if best_columns.len() >= 2 {
+            // Format en table markdown
+            if is_first_row {
+                structured.push_str("| ");
+                structured.push_str(&best_columns.join(" | "));
+                structured.push_str(" |\n");
+                
+                // Ligne de séparation
+                structured.push_str("|");
+                for _ in 0..best_columns.len() {
+                    structured.push_str("---|");
+                }
+                structured.push('\n');
+                is_first_row = false;
+            } else {
+                structured.push_str("| ");
+                structured.push_str(&best_columns.join(" | "));
+                structured.push_str(" |\n");
+            }
+        } else {
+            // Ligne simple si pas assez de colonnes
+            structured.push_str(&format!("• {}\n", line));
+        }
+    }
+
+    structured
+}
+
+/// Plages d'octets des tableaux d'un texte (via `detect_table_content`).
+/// Un tableau commence sur une ligne à colonnes et s'étend tant que les lignes suivantes
+/// ont le même nombre de colonnes ; il faut au moins deux lignes de tableau.
+pub fn find_table_regions(content: &str) -> Vec<(usize, usize)> {
+    let lines_with_offsets: Vec<(usize, &str)> = parts_with_offsets(content.split('\n'), 1).collect();
+    let lines: Vec<&str> = lines_with_offsets.iter().map(|(_, line)| *line).collect();
+
+    let mut regions = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        if !is_table_row(lines[index]) || detect_table_content(&lines, index).is_none() {
+            index += 1;
+            continue;
+        }
+
+        let columns = table_column_count(lines[index].trim());
+        let mut end = index;
+        while end < lines.len() && table_column_count(lines[end].trim()) == columns {
+            end += 1;
+        }
+
+        if end - index >= 2 {
+            let (last_offset, last_line) = lines_with_offsets[end - 1];
+            let last_line = last_line.trim_end();
+            regions.push((lines_with_offsets[index].0, last_offset + last_line.len()));
+        }
+        index = end.max(index + 1);
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_table_regions() {
+        let content = "Résultats du semestre.\n\nProduit | T1 | T2\nAlpha | 120 | 140\nBeta | 80 | 95\n\nLe chiffre d'affaires progresse.";
+        let regions = find_table_regions(content);
+
+        assert_eq!(regions.len(), 1);
+        let (start, end) = regions[0];
+        assert_eq!(&content[start..end], "Produit | T1 | T2\nAlpha | 120 | 140\nBeta | 80 | 95");
+
+        let lines: Vec<&str> = content[start..end].lines().collect();
+        let markdown = structure_table_content(&lines);
+        assert!(markdown.contains("| Produit | T1 | T2 |\n|---|---|---|"));
+        assert!(markdown.contains("| Beta | 80 | 95 |"));
+
+        assert!(find_table_regions("Une phrase simple.\nUne autre phrase.").is_empty());
+    }
+
+    #[test]
+    fn test_prose_with_double_spaces_is_not_a_table() {
+        let prose = "Le contrat est signé.  Il prend effet en mars.  Le préavis est de trois mois.\n\
+                     Les congés sont fixés.  La période de référence court du 1er juin.\n\
+                     Le salaire est versé.  Les primes suivent.  Le reste est inchangé.";
+        assert!(find_table_regions(prose).is_empty());
+        assert!(detect_table_content(&prose.lines().collect::<Vec<_>>(), 0).is_none());
+
+        // Deux colonnes seulement : liste clé/valeur, pas un tableau
+        assert!(find_table_regions("Nom | Dupont\nVille | Lyon").is_empty());
+
+        // Colonnes alignées par trois espaces ou plus
+        let aligned = "Produit   T1    T2\nAlpha     120   140\nBeta      80    95";
+        assert_eq!(find_table_regions(aligned), vec![(0, aligned.len())]);
+    }
+}
@@ -9,7 +9,7 @@ use hf_hub::api::tokio::Api;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokenizers::Tokenizer;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Configuration pour l'embedder E5 personnalisé
 #[derive(Debug, Clone)]
@@ -19,6 +19,10 @@ pub struct CustomE5Config {
     pub cache_dir: Option<PathBuf>,
     pub max_sequence_length: usize,
     pub device: Device,
+    /// Dimension des embeddings produits (hidden size du modèle)
+    pub embedding_dimension: usize,
+    /// Nombre maximal de textes tokenisés/encodés ensemble par `encode_documents_batch`
+    pub max_batch_size: usize,
 }
 
 impl Default for CustomE5Config {
@@ -29,19 +33,70 @@ impl Default for CustomE5Config {
             cache_dir: None,
             max_sequence_length: 512,
             device: Device::Cpu,
+            embedding_dimension: 384,
+            max_batch_size: 32,
         }
     }
 }
 
+/// Tokens réservés par séquence : [CLS], [SEP] et préfixe "passage: " / "query: "
+const E5_RESERVED_TOKENS: usize = 8;
+
+/// Modèle d'embedding sélectionnable à l'exécution
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EmbeddingModelInfo {
+    pub name: &'static str,
+    pub model_id: &'static str,
+    pub dimension: usize,
+    pub description: &'static str,
+}
+
+/// Modèles E5 compatibles (vocabulaire BERT uncased de `VOCAB_SIZE` tokens)
+pub const EMBEDDING_MODELS: &[EmbeddingModelInfo] = &[
+    EmbeddingModelInfo {
+        name: "e5-small-v2",
+        model_id: "intfloat/e5-small-v2",
+        dimension: 384,
+        description: "E5 small (défaut) : rapide, faible empreinte mémoire",
+    },
+    EmbeddingModelInfo {
+        name: "e5-base-v2",
+        model_id: "intfloat/e5-base-v2",
+        dimension: 768,
+        description: "E5 base : meilleure qualité, ~3x plus lent",
+    },
+    EmbeddingModelInfo {
+        name: "e5-large-v2",
+        model_id: "intfloat/e5-large-v2",
+        dimension: 1024,
+        description: "E5 large : qualité maximale, lent et gourmand en mémoire",
+    },
+];
+
+impl CustomE5Config {
+    /// Configuration d'un modèle de `EMBEDDING_MODELS` (par nom court ou model_id)
+    pub fn for_model(name: &str) -> Option<Self> {
+        EMBEDDING_MODELS.iter()
+            .find(|model| model.name == name || model.model_id == name)
+            .map(|model| Self {
+                model_id: model.model_id.to_string(),
+                embedding_dimension: model.dimension,
+                ..Self::default()
+            })
+    }
+}
+
+/// Taille du vocabulaire BERT (lignes de la matrice word_embeddings)
+const VOCAB_SIZE: usize = 30522;
+
 /// Cache des embeddings
 type EmbeddingCache = DashMap<String, Vec<f32>>;
 
 /// E5 Embedder personnalisé - charge directement les tensors sans BertModel
 pub struct CustomE5Embedder {
     tokenizer: Tokenizer,
-    embeddings: Tensor,         // word embeddings weights [vocab_size, 384]
+    embeddings: Tensor,         // word embeddings weights [vocab_size, embedding_dimension]
     cache: Arc<EmbeddingCache>,
-    #[allow(dead_code)]
     config: CustomE5Config,
 }
 
@@ -84,10 +139,10 @@ impl CustomE5Embedder {
         
         // Extraire directement les embeddings word weights
         info!("📊 Extracting word embeddings from model...");
-        let embeddings = vs.get((30522, 384), "embeddings.word_embeddings.weight")
+        let embeddings = vs.get((VOCAB_SIZE, config.embedding_dimension), "embeddings.word_embeddings.weight")
             .context("Failed to load word embeddings tensor")?;
         
-        info!("✅ Custom E5 embedder initialized with 384D embeddings");
+        info!("✅ Custom E5 embedder initialized with {}D embeddings", config.embedding_dimension);
         
         Ok(Self {
             tokenizer,
@@ -97,21 +152,67 @@ impl CustomE5Embedder {
         })
     }
     
-    /// Encode un texte en embedding 384D (pour les requêtes utilisateur)
+    /// Tokens de contenu encodables sans troncature par le modèle
+    /// (`max_sequence_length` moins les tokens spéciaux et le préfixe "passage:")
+    pub fn max_input_tokens(&self) -> usize {
+        self.config.max_sequence_length.saturating_sub(E5_RESERVED_TOKENS)
+    }
+
+    /// Nombre de tokens WordPiece du texte tel que le modèle le verra (hors tokens réservés)
+    pub fn count_tokens(&self, text: &str) -> usize {
+        self.tokenizer
+            .encode(text, false)
+            .map(|encoding| encoding.len())
+            .unwrap_or_else(|_| crate::rag::estimate_tokens(text))
+    }
+
+    /// Dimension des embeddings produits
+    pub fn dimension(&self) -> usize {
+        self.config.embedding_dimension
+    }
+
+    /// Identifiant du modèle pour les clés de cache (change si modèle/révision/dimension changent)
+    pub fn model_key(&self) -> String {
+        format!("{}@{}/{}d", self.config.model_id, self.config.revision, self.config.embedding_dimension)
+    }
+
+    /// Encode un texte en embedding (pour les requêtes utilisateur)
     pub async fn encode(&self, text: &str) -> Result<Vec<f32>> {
         self.encode_with_prefix(text, "query").await
     }
 
-    /// Encode un document en embedding 384D (pour l'indexation)
+    /// Encode un document en embedding (pour l'indexation)
     pub async fn encode_document(&self, text: &str) -> Result<Vec<f32>> {
         self.encode_with_prefix(text, "passage").await
     }
 
+    /// Encode un lot de documents (préfixe "passage") par paquets de `max_batch_size`.
+    /// Un résultat par texte, dans l'ordre : l'échec d'un texte n'affecte pas les autres.
+    pub async fn encode_documents_batch(&self, texts: &[String]) -> Vec<Result<Vec<f32>>> {
+        let mut results = Vec::with_capacity(texts.len());
+
+        for batch in texts.chunks(self.config.max_batch_size.max(1)) {
+            results.extend(self.encode_batch_with_prefix(batch, "passage"));
+            // Rendre la main au runtime entre deux paquets
+            tokio::task::yield_now().await;
+        }
+
+        results
+    }
+
+    /// Clé de cache incluant le préfixe
+    fn cache_key(prefix: &str, text: &str) -> String {
+        let cache_input = format!("{}:{}", prefix, text);
+        blake3::hash(cache_input.as_bytes()).to_hex().to_string()
+    }
+
     /// Encode un texte avec un préfixe spécifique (query ou passage)
     async fn encode_with_prefix(&self, text: &str, prefix: &str) -> Result<Vec<f32>> {
-        // Cache key incluant le préfixe
-        let cache_input = format!("{}:{}", prefix, text);
-        let cache_key = blake3::hash(cache_input.as_bytes()).to_hex().to_string();
+        self.encode_single(text, prefix)
+    }
+
+    fn encode_single(&self, text: &str, prefix: &str) -> Result<Vec<f32>> {
+        let cache_key = Self::cache_key(prefix, text);
 
         if let Some(cached) = self.cache.get(&cache_key) {
             return Ok(cached.clone());
@@ -124,56 +225,94 @@ impl CustomE5Embedder {
         let encoding = self.tokenizer
             .encode(prefixed_text, true)
             .map_err(|e| anyhow::anyhow!("Failed to tokenize text: {}", e))?;
-        
-        let tokens = encoding.get_ids();
-        
-        // Pour une implémentation simple, faisons juste la moyenne des embeddings des tokens
-        let mut embedding_sum = vec![0.0f32; 384];
-        let mut token_count = 0;
-        
-        for &token_id in tokens {
-            if token_id < 30522 {
-                // Extraire l'embedding pour ce token
-                let token_embedding = self.embeddings.get(token_id as usize)
-                    .context("Failed to get token embedding")?
-                    .to_vec1::<f32>()?;
-                
-                // Additionner
-                for (i, &val) in token_embedding.iter().enumerate() {
-                    embedding_sum[i] += val;
+
+        let embedding = self.pool_tokens(encoding.get_ids(), text)?;
+
+        // Mise en cache
+        self.cache.insert(cache_key, embedding.clone());
+
+        Ok(embedding)
+    }
+
+    /// Encode un paquet : une seule tokenisation pour tous les textes non cachés,
+    /// repli texte par texte si la tokenisation du paquet échoue
+    fn encode_batch_with_prefix(&self, texts: &[String], prefix: &str) -> Vec<Result<Vec<f32>>> {
+        let keys: Vec<String> = texts.iter().map(|text| Self::cache_key(prefix, text)).collect();
+        let mut results: Vec<Option<Result<Vec<f32>>>> = keys
+            .iter()
+            .map(|key| self.cache.get(key).map(|cached| Ok(cached.clone())))
+            .collect();
+
+        let pending: Vec<usize> = (0..texts.len()).filter(|&i| results[i].is_none()).collect();
+        if !pending.is_empty() {
+            let inputs: Vec<String> = pending
+                .iter()
+                .map(|&i| format!("{}: {}", prefix, texts[i]))
+                .collect();
+
+            match self.tokenizer.encode_batch(inputs, true) {
+                Ok(encodings) => {
+                    for (&i, encoding) in pending.iter().zip(encodings.iter()) {
+                        let result = self.pool_tokens(encoding.get_ids(), &texts[i]);
+                        if let Ok(embedding) = &result {
+                            self.cache.insert(keys[i].clone(), embedding.clone());
+                        }
+                        results[i] = Some(result);
+                    }
+                }
+                Err(e) => {
+                    warn!("⚠️ Batch tokenization failed ({} texts), falling back to per-text encoding: {}", pending.len(), e);
+                    for &i in &pending {
+                        results[i] = Some(self.encode_single(&texts[i], prefix));
+                    }
                 }
-                token_count += 1;
-            }
-        }
-        
-        // Moyenne
-        if token_count > 0 {
-            for val in &mut embedding_sum {
-                *val /= token_count as f32;
             }
         }
 
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err(anyhow::anyhow!("Missing embedding result"))))
+            .collect()
+    }
+
+    /// Moyenne des embeddings des tokens puis normalisation L2
+    fn pool_tokens(&self, tokens: &[u32], text: &str) -> Result<Vec<f32>> {
+        let token_ids: Vec<u32> = tokens
+            .iter()
+            .copied()
+            .filter(|&token_id| (token_id as usize) < VOCAB_SIZE)
+            .collect();
+
+        // Pour une implémentation simple, moyenne des embeddings des tokens (un seul index_select)
+        let mut embedding = if token_ids.is_empty() {
+            vec![0.0f32; self.config.embedding_dimension]
+        } else {
+            let ids = Tensor::new(token_ids.as_slice(), self.embeddings.device())?;
+            self.embeddings
+                .index_select(&ids, 0)
+                .context("Failed to get token embeddings")?
+                .mean(0)?
+                .to_vec1::<f32>()?
+        };
+
         // Normalisation L2 (critque pour la similarité cosinus)
-        let norm: f32 = embedding_sum.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
         if norm > 1e-6 {  // Seuil de stabilité numérique
-            for val in &mut embedding_sum {
+            for val in &mut embedding {
                 *val /= norm;
             }
         } else {
             // Si norme trop faible, retourner vecteur nul normalisé
-            tracing::warn!("Embedding norm too small ({}) for text: {}", norm, text);
+            warn!("Embedding norm too small ({}) for text: {}", norm, text);
         }
 
-        // Mise en cache
-        self.cache.insert(cache_key, embedding_sum.clone());
-
-        Ok(embedding_sum)
+        Ok(embedding)
     }
-    
+
     /// Stats du cache
     pub fn cache_stats(&self) -> (usize, usize) {
         let cache_size = self.cache.len();
-        let memory_mb = (cache_size * 384 * 4) / (1024 * 1024);
+        let memory_mb = (cache_size * self.config.embedding_dimension * 4) / (1024 * 1024);
         (cache_size, memory_mb)
     }
     
@@ -0,0 +1,200 @@
+// Embedding Stats - Diagnostic de la distribution des vecteurs d'un groupe
+// Des embeddings trop semblables expliquent une faible diversité des résultats ;
+// les points isolés signalent souvent des chunks parasites (OCR bruité, en-têtes, tables vides).
+//
+// Principe:
+// - Matrice de similarité cosinus complète sur un échantillon borné de points
+// - Clusters serrés : composantes connexes au-dessus de `TIGHT_CLUSTER_THRESHOLD`
+// - Centralité d'un chunk : similarité moyenne avec les autres points de l'échantillon
+
+use serde::{Deserialize, Serialize};
+
+use super::vector_ops::{dot, l2_normalize};
+
+/// Taille d'échantillon par défaut (matrice n² : 500 points ≈ 125k paires)
+pub const DEFAULT_EMBEDDING_SAMPLE_SIZE: usize = 500;
+/// Similarité à partir de laquelle deux chunks appartiennent au même cluster serré
+pub const TIGHT_CLUSTER_THRESHOLD: f32 = 0.92;
+/// Nombre de chunks centraux / isolés rapportés
+pub const CENTRALITY_REPORT_SIZE: usize = 5;
+/// Longueur de l'aperçu de contenu d'un chunk rapporté
+const PREVIEW_CHARS: usize = 160;
+
+/// Point échantillonné : identifiants, aperçu du contenu et vecteur
+#[derive(Debug, Clone)]
+pub struct SampledEmbedding {
+    pub chunk_id: String,
+    pub document_id: Option<String>,
+    pub content: String,
+    pub vector: Vec<f32>,
+}
+
+/// Chunk rapporté comme central ou isolé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkCentrality {
+    pub chunk_id: String,
+    pub document_id: Option<String>,
+    /// Similarité cosinus moyenne avec les autres points de l'échantillon
+    pub mean_similarity: f32,
+    pub preview: String,
+}
+
+/// Statistiques de l'espace d'embedding d'un échantillon
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbeddingSpaceStats {
+    pub sampled_points: usize,
+    pub dimension: usize,
+    pub mean_pairwise_similarity: f32,
+    pub pairwise_similarity_std: f32,
+    /// Clusters serrés (au moins deux points au-dessus de `TIGHT_CLUSTER_THRESHOLD`)
+    pub tight_clusters: usize,
+    pub largest_cluster_size: usize,
+    /// Points appartenant à un cluster serré
+    pub clustered_points: usize,
+    pub most_central: Vec<ChunkCentrality>,
+    pub outliers: Vec<ChunkCentrality>,
+}
+
+impl EmbeddingSpaceStats {
+    /// Points de dimension différente du premier ignorés ; moins de deux points = statistiques vides
+    pub fn compute(samples: &[SampledEmbedding]) -> Self {
+        let dimension = samples.first().map_or(0, |sample| sample.vector.len());
+        let samples: Vec<&SampledEmbedding> = samples.iter()
+            .filter(|sample| sample.vector.len() == dimension && dimension > 0)
+            .collect();
+        let n = samples.len();
+        if n < 2 {
+            return Self { sampled_points: n, dimension, ..Self::default() };
+        }
+
+        let normalized: Vec<Vec<f32>> = samples.iter()
+            .map(|sample| {
+                let mut vector = sample.vector.clone();
+                l2_normalize(&mut vector);
+                vector
+            })
+            .collect();
+
+        let mut similarity_sums = vec![0.0f64; n];
+        let mut sum = 0.0f64;
+        let mut sum_squares = 0.0f64;
+        let mut clusters = DisjointSet::new(n);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let similarity = dot(&normalized[i], &normalized[j]);
+                similarity_sums[i] += similarity as f64;
+                similarity_sums[j] += similarity as f64;
+                sum += similarity as f64;
+                sum_squares += (similarity as f64).powi(2);
+                if similarity >= TIGHT_CLUSTER_THRESHOLD {
+                    clusters.union(i, j);
+                }
+            }
+        }
+
+        let pairs = (n * (n - 1) / 2) as f64;
+        let mean = sum / pairs;
+        let variance = (sum_squares / pairs - mean * mean).max(0.0);
+
+        let cluster_sizes: Vec<usize> = clusters.component_sizes().into_iter().filter(|size| *size >= 2).collect();
+
+        let mut centrality: Vec<ChunkCentrality> = samples.iter()
+            .zip(&similarity_sums)
+            .map(|(sample, total)| ChunkCentrality {
+                chunk_id: sample.chunk_id.clone(),
+                document_id: sample.document_id.clone(),
+                mean_similarity: (*total / (n - 1) as f64) as f32,
+                preview: sample.content.chars().take(PREVIEW_CHARS).collect(),
+            })
+            .collect();
+        centrality.sort_by(|a, b| b.mean_similarity.total_cmp(&a.mean_similarity));
+        let report_size = CENTRALITY_REPORT_SIZE.min(n / 2).max(1);
+        let most_central = centrality[..report_size].to_vec();
+        let outliers = centrality.iter().rev().take(report_size).cloned().collect();
+
+        Self {
+            sampled_points: n,
+            dimension,
+            mean_pairwise_similarity: mean as f32,
+            pairwise_similarity_std: variance.sqrt() as f32,
+            tight_clusters: cluster_sizes.len(),
+            largest_cluster_size: cluster_sizes.iter().copied().max().unwrap_or(0),
+            clustered_points: cluster_sizes.iter().sum(),
+            most_central,
+            outliers,
+        }
+    }
+}
+
+/// Union-find avec compression de chemin
+struct DisjointSet {
+    parents: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        Self { parents: (0..size).collect() }
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parents[node] != node {
+            self.parents[node] = self.parents[self.parents[node]];
+            node = self.parents[node];
+        }
+        node
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a != root_b {
+            self.parents[root_b] = root_a;
+        }
+    }
+
+    fn component_sizes(&mut self) -> Vec<usize> {
+        let mut sizes = vec![0; self.parents.len()];
+        for node in 0..self.parents.len() {
+            let root = self.find(node);
+            sizes[root] += 1;
+        }
+        sizes.into_iter().filter(|size| *size > 0).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(chunk_id: &str, vector: Vec<f32>) -> SampledEmbedding {
+        SampledEmbedding {
+            chunk_id: chunk_id.to_string(),
+            document_id: Some("doc".to_string()),
+            content: format!("contenu {}", chunk_id),
+            vector,
+        }
+    }
+
+    #[test]
+    fn test_clusters_and_outliers() {
+        let samples = vec![
+            sample("a1", vec![1.0, 0.0, 0.0]),
+            sample("a2", vec![0.99, 0.05, 0.0]),
+            sample("b1", vec![0.0, 1.0, 0.0]),
+            sample("b2", vec![0.05, 0.99, 0.0]),
+            sample("junk", vec![0.0, 0.0, 1.0]),
+            sample("bad_dimension", vec![1.0, 0.0]),
+        ];
+
+        let stats = EmbeddingSpaceStats::compute(&samples);
+        assert_eq!(stats.sampled_points, 5);
+        assert_eq!(stats.dimension, 3);
+        assert_eq!(stats.tight_clusters, 2);
+        assert_eq!(stats.largest_cluster_size, 2);
+        assert_eq!(stats.clustered_points, 4);
+        assert_eq!(stats.outliers[0].chunk_id, "junk");
+        assert_ne!(stats.most_central[0].chunk_id, "junk");
+        assert!(stats.mean_pairwise_similarity > 0.0 && stats.mean_pairwise_similarity < 0.5);
+
+        assert_eq!(EmbeddingSpaceStats::compute(&samples[..1]).tight_clusters, 0);
+    }
+}
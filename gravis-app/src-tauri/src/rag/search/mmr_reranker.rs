@@ -3,6 +3,8 @@ use std::collections::HashSet;
 use anyhow::Result;
 use tracing::debug;
 
+use crate::rag::search::vector_ops::cosine_similarity;
+
 /// Structure pour les résultats de recherche avec scores
 #[derive(Debug, Clone)]
 pub struct SearchResult {
@@ -99,23 +101,6 @@ impl MMRReranker {
     }
 }
 
-/// Calcule la similarité cosine entre deux vecteurs
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
-    }
-    
-    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-    
-    if norm_a == 0.0 || norm_b == 0.0 {
-        return 0.0;
-    }
-    
-    dot_product / (norm_a * norm_b)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,6 +7,10 @@ pub mod scoring_engine;
 pub mod numerical_reranker;
 pub mod query_aware_reranker;  // Sprint 1 Niveau 1.5: Query-aware reranking
 pub mod section_prior;  // AUDIT 22 NOV: Section prior simple et générique
+pub mod query_translation;
+pub mod query_cache;
+pub mod embedding_stats;
+pub mod vector_ops;
 
 pub use search_optimizer::*;
 pub use mmr_reranker::*;
@@ -15,4 +19,8 @@ pub use enhanced_bm25::*;
 pub use scoring_engine::*;
 pub use numerical_reranker::*;
 pub use query_aware_reranker::*;
-pub use section_prior::*;
\ No newline at end of file
+pub use section_prior::*;
+pub use query_translation::*;
+pub use query_cache::*;
+pub use embedding_stats::*;
+pub use vector_ops::*;
\ No newline at end of file
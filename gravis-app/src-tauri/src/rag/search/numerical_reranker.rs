@@ -44,6 +44,7 @@ pub struct QueryKindDetector {
     digit_regex: Regex,
     percentage_regex: Regex,
     compression_regex: Regex,
+    currency_regex: Regex,
     constraint_regex: Regex,
 }
 
@@ -56,6 +57,8 @@ impl QueryKindDetector {
             percentage_regex: Regex::new(r"(\d+(?:\.\d+)?)\s*%").expect("Invalid percentage regex"),
             // Détecte ratios de compression: "10x", "10×", "10 x"
             compression_regex: Regex::new(r"(\d+(?:\.\d+)?)\s*[x×X]").expect("Invalid compression regex"),
+            // Détecte montants: "35000€", "35000 €", "120 $"
+            currency_regex: Regex::new(CURRENCY_PATTERN).expect("Invalid currency regex"),
             // Détecte opérateurs de contrainte
             constraint_regex: Regex::new(
                 r"(?i)(inférieur|supérieur|moins|plus|greater|less|between|entre|<|>|≤|≥)"
@@ -68,9 +71,10 @@ impl QueryKindDetector {
         let has_digits = self.digit_regex.is_match(query);
         let has_percentage = self.percentage_regex.is_match(query);
         let has_compression = self.compression_regex.is_match(query);
+        let has_currency = self.currency_regex.is_match(query);
         let has_constraint = self.constraint_regex.is_match(query);
 
-        let has_numeric = has_percentage || has_compression;
+        let has_numeric = has_percentage || has_compression || has_currency;
 
         // Mots-clés conceptuels
         let conceptual_keywords = [
@@ -180,7 +184,7 @@ impl QueryKindDetector {
         None
     }
 
-    /// Extraire valeur numérique simple (percentage ou compression)
+    /// Extraire valeur numérique simple (percentage, montant ou compression)
     fn extract_numeric_value(&self, text: &str) -> Option<(f32, String)> {
         // Try percentage first
         if let Some(caps) = self.percentage_regex.captures(text) {
@@ -188,6 +192,12 @@ impl QueryKindDetector {
             return Some((value, "%".to_string()));
         }
 
+        // Try currency amount
+        if let Some(caps) = self.currency_regex.captures(text) {
+            let value = parse_amount(caps.get(1)?.as_str())?;
+            return Some((value, currency_unit(caps.get(2)?.as_str())));
+        }
+
         // Try compression ratio
         if let Some(caps) = self.compression_regex.captures(text) {
             let value: f32 = caps.get(1)?.as_str().parse().ok()?;
@@ -204,10 +214,29 @@ impl Default for QueryKindDetector {
     }
 }
 
+/// Montant suivi d'une devise (espaces de milliers tolérés: "35 000 €")
+const CURRENCY_PATTERN: &str = r"(\d{1,3}(?:[ \u{a0}]\d{3})+|\d+(?:[.,]\d+)?)\s*(€|\$|EUR|USD|euros?)";
+
+/// Normaliser un montant brut ("35 000", "12,5") en f32
+fn parse_amount(raw: &str) -> Option<f32> {
+    raw.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == ',' { '.' } else { c })
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+/// Unité canonique pour une devise
+fn currency_unit(raw: &str) -> String {
+    if raw == "$" || raw.eq_ignore_ascii_case("usd") { "$".to_string() } else { "€".to_string() }
+}
+
 /// Extracteur de valeurs numériques dans les chunks
 pub struct ChunkValueExtractor {
     percentage_regex: Regex,
     compression_regex: Regex,
+    currency_regex: Regex,
 }
 
 impl ChunkValueExtractor {
@@ -215,6 +244,7 @@ impl ChunkValueExtractor {
         Self {
             percentage_regex: Regex::new(r"(\d+(?:\.\d+)?)\s*%").expect("Invalid percentage regex"),
             compression_regex: Regex::new(r"(\d+(?:\.\d+)?)\s*[x×X]").expect("Invalid compression regex"),
+            currency_regex: Regex::new(CURRENCY_PATTERN).expect("Invalid currency regex"),
         }
     }
 
@@ -236,6 +266,20 @@ impl ChunkValueExtractor {
             }
         }
 
+        // Extract currency amounts
+        for caps in self.currency_regex.captures_iter(content) {
+            if let (Some(m), Some(amount), Some(currency)) = (caps.get(0), caps.get(1), caps.get(2)) {
+                if let Some(value) = parse_amount(amount.as_str()) {
+                    values.push(ExtractedValue {
+                        value,
+                        unit: currency_unit(currency.as_str()),
+                        raw_text: m.as_str().to_string(),
+                        position: m.start(),
+                    });
+                }
+            }
+        }
+
         // Extract compression ratios
         for caps in self.compression_regex.captures_iter(content) {
             if let Some(m) = caps.get(0) {
@@ -512,6 +556,17 @@ mod tests {
         assert!(values.iter().any(|v| v.value == 6.7 && v.unit == "x"));
     }
 
+    #[test]
+    fn test_currency_query_and_values() {
+        let detector = QueryKindDetector::new();
+        assert_eq!(detector.detect_query_kind("35000€"), QueryKind::DigitAtomic);
+
+        let extractor = ChunkValueExtractor::new();
+        let constraint = NumericalConstraint::Exact { value: 35000.0, unit: "€".to_string() };
+        assert!(extractor.matches_constraint("Budget total: 35 000 € HT", &constraint));
+        assert!(!extractor.matches_constraint("Budget total: 35 000 $", &constraint));
+    }
+
     #[test]
     fn test_matches_constraint() {
         let extractor = ChunkValueExtractor::new();
@@ -0,0 +1,169 @@
+// Query Cache - Réponses de recherche récentes par groupe
+// Une requête identique (après normalisation) ou très proche sémantiquement réutilise la
+// réponse précédente au lieu de relancer embedding + recherche Qdrant + reranking.
+//
+// Principe:
+// - Clé exacte : (groupe, requête normalisée, empreinte des autres paramètres)
+// - Couche sémantique optionnelle : cosinus entre embeddings de requête >= seuil
+// - TTL court ; invalidation du groupe à chaque ajout/suppression de documents
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::vector_ops::cosine_similarity;
+
+/// Durée de validité d'une réponse en cache
+pub const QUERY_CACHE_TTL: Duration = Duration::from_secs(120);
+/// Similarité cosinus minimale pour réutiliser la réponse d'une requête voisine
+pub const SEMANTIC_CACHE_THRESHOLD: f32 = 0.97;
+/// Nombre maximal de réponses conservées par groupe (les plus anciennes sont évincées)
+pub const QUERY_CACHE_MAX_ENTRIES_PER_GROUP: usize = 64;
+
+/// Nature d'un hit de cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryCacheHitKind {
+    Exact,
+    Semantic,
+}
+
+/// Hit de cache rapporté dans la réponse de recherche
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryCacheHit {
+    pub kind: QueryCacheHitKind,
+    /// Requête (normalisée) dont la réponse est réutilisée
+    pub cached_query: String,
+    /// 1.0 pour un hit exact
+    pub similarity: f32,
+    pub age_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+struct CachedQuery<T> {
+    normalized_query: String,
+    params_fingerprint: String,
+    embedding: Vec<f32>,
+    response: T,
+    cached_at: Instant,
+}
+
+/// Cache des réponses de recherche, par groupe
+#[derive(Debug)]
+pub struct QueryCache<T> {
+    groups: HashMap<String, Vec<CachedQuery<T>>>,
+    ttl: Duration,
+}
+
+/// Requête normalisée : minuscules, espaces compactés, ponctuation finale retirée
+pub fn normalize_query(query: &str) -> String {
+    query.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .trim_end_matches(|c: char| matches!(c, '?' | '!' | '.' | ' '))
+        .to_string()
+}
+
+impl<T: Clone> QueryCache<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self { groups: HashMap::new(), ttl }
+    }
+
+    /// Réponse d'une requête identique (même normalisation, mêmes paramètres)
+    pub fn get_exact(&mut self, group_id: &str, query: &str, params_fingerprint: &str) -> Option<(T, QueryCacheHit)> {
+        let normalized = normalize_query(query);
+        self.evict_expired(group_id);
+        self.groups.get(group_id)?
+            .iter()
+            .find(|entry| entry.normalized_query == normalized && entry.params_fingerprint == params_fingerprint)
+            .map(|entry| (entry.response.clone(), QueryCacheHit {
+                kind: QueryCacheHitKind::Exact,
+                cached_query: entry.normalized_query.clone(),
+                similarity: 1.0,
+                age_ms: entry.cached_at.elapsed().as_millis() as u64,
+            }))
+    }
+
+    /// Réponse de la requête la plus proche dont l'embedding atteint `threshold`
+    pub fn get_semantic(&mut self, group_id: &str, embedding: &[f32], params_fingerprint: &str, threshold: f32) -> Option<(T, QueryCacheHit)> {
+        self.evict_expired(group_id);
+        self.groups.get(group_id)?
+            .iter()
+            .filter(|entry| entry.params_fingerprint == params_fingerprint)
+            .map(|entry| (entry, cosine_similarity(&entry.embedding, embedding)))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entry, similarity)| (entry.response.clone(), QueryCacheHit {
+                kind: QueryCacheHitKind::Semantic,
+                cached_query: entry.normalized_query.clone(),
+                similarity,
+                age_ms: entry.cached_at.elapsed().as_millis() as u64,
+            }))
+    }
+
+    /// Mémoriser une réponse (remplace l'entrée de même requête et mêmes paramètres)
+    pub fn insert(&mut self, group_id: &str, query: &str, params_fingerprint: &str, embedding: Vec<f32>, response: T) {
+        let normalized_query = normalize_query(query);
+        let entries = self.groups.entry(group_id.to_string()).or_default();
+        entries.retain(|entry| entry.normalized_query != normalized_query || entry.params_fingerprint != params_fingerprint);
+        if entries.len() >= QUERY_CACHE_MAX_ENTRIES_PER_GROUP {
+            entries.remove(0);
+        }
+        entries.push(CachedQuery {
+            normalized_query,
+            params_fingerprint: params_fingerprint.to_string(),
+            embedding,
+            response,
+            cached_at: Instant::now(),
+        });
+    }
+
+    /// Oublier les réponses d'un groupe (documents ajoutés ou supprimés)
+    pub fn invalidate_group(&mut self, group_id: &str) {
+        self.groups.remove(group_id);
+    }
+
+    /// Oublier toutes les réponses (modèle d'embedding ou pondérations changés)
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+
+    fn evict_expired(&mut self, group_id: &str) {
+        let ttl = self.ttl;
+        if let Some(entries) = self.groups.get_mut(group_id) {
+            entries.retain(|entry| entry.cached_at.elapsed() < ttl);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_and_semantic_hits_with_invalidation() {
+        let mut cache: QueryCache<&str> = QueryCache::new(QUERY_CACHE_TTL);
+        cache.insert("g1", "Quel est le  salaire net ?", "limit=5", vec![1.0, 0.0, 0.0], "réponse");
+
+        let (response, hit) = cache.get_exact("g1", "quel est le salaire net", "limit=5").unwrap();
+        assert_eq!(response, "réponse");
+        assert_eq!(hit.kind, QueryCacheHitKind::Exact);
+
+        // Autres paramètres ou autre groupe : pas de hit
+        assert!(cache.get_exact("g1", "quel est le salaire net", "limit=10").is_none());
+        assert!(cache.get_exact("g2", "quel est le salaire net", "limit=5").is_none());
+
+        let (_, hit) = cache.get_semantic("g1", &[0.99, 0.05, 0.0], "limit=5", SEMANTIC_CACHE_THRESHOLD).unwrap();
+        assert_eq!(hit.kind, QueryCacheHitKind::Semantic);
+        assert!(hit.similarity >= SEMANTIC_CACHE_THRESHOLD);
+        assert!(cache.get_semantic("g1", &[0.0, 1.0, 0.0], "limit=5", SEMANTIC_CACHE_THRESHOLD).is_none());
+
+        cache.invalidate_group("g1");
+        assert!(cache.get_exact("g1", "quel est le salaire net", "limit=5").is_none());
+
+        let mut expired: QueryCache<&str> = QueryCache::new(Duration::ZERO);
+        expired.insert("g1", "q", "", vec![1.0], "réponse");
+        assert!(expired.get_exact("g1", "q", "").is_none());
+    }
+}
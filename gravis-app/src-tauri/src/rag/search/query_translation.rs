@@ -0,0 +1,240 @@
+// Query Translation - Réécriture lexicale des requêtes cross-lingues
+// E5 est multilingue mais le recall chute quand la langue de la requête diffère du corpus :
+// une requête anglaise sur une base française est complétée par sa traduction lexicale
+//
+// Principe:
+// - Détection de langue par mots outils (FR/EN), sans modèle
+// - Traduction terme à terme via un glossaire métier (paie, facturation, contrats, finance)
+// - Aucun terme reconnu = pas de requête additionnelle
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use once_cell::sync::Lazy;
+
+/// Langue détectée d'une requête ou d'un corpus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryLanguage {
+    French,
+    English,
+}
+
+/// Nombre de caractères échantillonnés par document pour la langue dominante du corpus
+pub const CORPUS_LANGUAGE_SAMPLE_CHARS: usize = 2000;
+
+const FRENCH_STOPWORDS: &[&str] = &[
+    "le", "la", "les", "un", "une", "des", "du", "de", "et", "est", "sont", "dans", "pour",
+    "par", "sur", "avec", "au", "aux", "ce", "cette", "ces", "qui", "que", "quel", "quelle",
+    "quels", "quelles", "combien", "comment", "pourquoi", "il", "elle", "nous", "vous", "leur",
+    "pas", "ou", "où", "mon", "ma", "mes", "son", "sa", "ses",
+];
+
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "the", "a", "an", "of", "and", "is", "are", "was", "were", "in", "for", "on", "with",
+    "to", "at", "by", "this", "that", "these", "those", "what", "which", "who", "how", "why",
+    "when", "where", "much", "many", "does", "do", "did", "my", "our", "their", "it", "from",
+];
+
+/// Glossaire (anglais, français) ; les expressions de plusieurs mots priment sur les mots isolés
+const GLOSSARY: &[(&str, &str)] = &[
+    ("net salary", "salaire net"),
+    ("gross salary", "salaire brut"),
+    ("net pay", "net à payer"),
+    ("pay slip", "bulletin de paie"),
+    ("payslip", "bulletin de paie"),
+    ("salary", "salaire"),
+    ("wage", "salaire"),
+    ("employee", "salarié"),
+    ("employer", "employeur"),
+    ("social security", "sécurité sociale"),
+    ("contributions", "cotisations"),
+    ("contribution", "cotisation"),
+    ("paid leave", "congés payés"),
+    ("leave", "congés"),
+    ("notice period", "préavis"),
+    ("notice", "préavis"),
+    ("severance pay", "indemnité de licenciement"),
+    ("severance", "indemnité de licenciement"),
+    ("dismissal", "licenciement"),
+    ("contract", "contrat"),
+    ("clause", "clause"),
+    ("termination", "résiliation"),
+    ("invoice", "facture"),
+    ("amount due", "montant dû"),
+    ("amount", "montant"),
+    ("total", "total"),
+    ("due date", "date d'échéance"),
+    ("payment", "paiement"),
+    ("vat", "tva"),
+    ("tax", "impôt"),
+    ("excluding tax", "hors taxes"),
+    ("including tax", "toutes taxes comprises"),
+    ("supplier", "fournisseur"),
+    ("customer", "client"),
+    ("bank statement", "relevé bancaire"),
+    ("balance", "solde"),
+    ("account", "compte"),
+    ("revenue", "chiffre d'affaires"),
+    ("turnover", "chiffre d'affaires"),
+    ("net income", "résultat net"),
+    ("operating income", "résultat d'exploitation"),
+    ("profit", "bénéfice"),
+    ("loss", "perte"),
+    ("fiscal year", "exercice"),
+    ("financial year", "exercice"),
+    ("balance sheet", "bilan"),
+    ("growth", "croissance"),
+    ("report", "rapport"),
+    ("annual", "annuel"),
+    ("quarter", "trimestre"),
+    ("year", "année"),
+    ("month", "mois"),
+    ("date", "date"),
+    ("price", "prix"),
+    ("cost", "coût"),
+    ("rate", "taux"),
+    ("address", "adresse"),
+    ("company", "société"),
+    ("director", "directeur"),
+    ("start date", "date de début"),
+    ("end date", "date de fin"),
+    ("duration", "durée"),
+    ("working hours", "heures travaillées"),
+    ("overtime", "heures supplémentaires"),
+    ("bonus", "prime"),
+    ("figure", "figure"),
+    ("table", "tableau"),
+    ("summary", "résumé"),
+    ("conclusion", "conclusion"),
+    ("results", "résultats"),
+    ("result", "résultat"),
+];
+
+/// Glossaire indexé par langue source : expression normalisée -> traduction
+static DICTIONARIES: Lazy<HashMap<QueryLanguage, HashMap<String, &'static str>>> = Lazy::new(|| {
+    let mut english_to_french = HashMap::new();
+    let mut french_to_english = HashMap::new();
+    for (english, french) in GLOSSARY {
+        english_to_french.entry(english.to_string()).or_insert(*french);
+        french_to_english.entry(french.to_string()).or_insert(*english);
+    }
+    HashMap::from([
+        (QueryLanguage::English, english_to_french),
+        (QueryLanguage::French, french_to_english),
+    ])
+});
+
+/// Plus longue expression du glossaire (en mots)
+const MAX_PHRASE_WORDS: usize = 4;
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’'))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.replace('’', "'").to_lowercase())
+        .collect()
+}
+
+fn stopwords(language: QueryLanguage) -> &'static [&'static str] {
+    match language {
+        QueryLanguage::French => FRENCH_STOPWORDS,
+        QueryLanguage::English => ENGLISH_STOPWORDS,
+    }
+}
+
+/// Langue d'un texte par comptage des mots outils ; `None` si aucun indice ou égalité
+pub fn detect_language(text: &str) -> Option<QueryLanguage> {
+    let words = words(text);
+    let count = |language| words.iter().filter(|word| stopwords(language).contains(&word.as_str())).count();
+    let accents = text.chars().filter(|c| "éèêàâùûôîçœ".contains(*c)).count().min(2);
+
+    let french = count(QueryLanguage::French) + accents;
+    let english = count(QueryLanguage::English);
+    match french.cmp(&english) {
+        std::cmp::Ordering::Greater => Some(QueryLanguage::French),
+        std::cmp::Ordering::Less => Some(QueryLanguage::English),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Langue majoritaire d'un ensemble de documents (début de chaque document échantillonné)
+pub fn dominant_language<'a>(texts: impl IntoIterator<Item = &'a str>) -> Option<QueryLanguage> {
+    let mut votes: HashMap<QueryLanguage, usize> = HashMap::new();
+    for text in texts {
+        let end = text.char_indices().nth(CORPUS_LANGUAGE_SAMPLE_CHARS).map_or(text.len(), |(idx, _)| idx);
+        if let Some(language) = detect_language(&text[..end]) {
+            *votes.entry(language).or_insert(0) += 1;
+        }
+    }
+
+    let french = votes.get(&QueryLanguage::French).copied().unwrap_or(0);
+    let english = votes.get(&QueryLanguage::English).copied().unwrap_or(0);
+    match french.cmp(&english) {
+        std::cmp::Ordering::Greater => Some(QueryLanguage::French),
+        std::cmp::Ordering::Less => Some(QueryLanguage::English),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Traduire lexicalement une requête vers la langue du corpus.
+/// Les mots outils de la langue source sont retirés, les termes inconnus (noms, chiffres) conservés.
+/// Retourne `None` si aucun terme du glossaire n'a été reconnu.
+pub fn translate_query(query: &str, from: QueryLanguage, to: QueryLanguage) -> Option<String> {
+    if from == to {
+        return None;
+    }
+
+    let dictionary = &DICTIONARIES[&from];
+    let words = words(query);
+    let mut output: Vec<String> = Vec::new();
+    let mut translated = 0;
+    let mut index = 0;
+
+    while index < words.len() {
+        // Plus longue expression connue à partir de ce mot
+        let matched = (1..=MAX_PHRASE_WORDS.min(words.len() - index)).rev().find_map(|len| {
+            let phrase = words[index..index + len].join(" ");
+            dictionary.get(&phrase).map(|translation| (len, *translation))
+        });
+
+        match matched {
+            Some((len, translation)) => {
+                output.push(translation.to_string());
+                translated += 1;
+                index += len;
+            }
+            None => {
+                if !stopwords(from).contains(&words[index].as_str()) {
+                    output.push(words[index].clone());
+                }
+                index += 1;
+            }
+        }
+    }
+
+    (translated > 0).then(|| output.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_query_translated_for_french_corpus() {
+        let corpus = [
+            "Le salaire brut du salarié est versé à la fin du mois. Les cotisations sont prélevées.",
+            "La facture doit être réglée avant la date d'échéance indiquée sur le document.",
+        ];
+        assert_eq!(dominant_language(corpus), Some(QueryLanguage::French));
+
+        let query = "What is the gross salary of the employee in March 2024?";
+        assert_eq!(detect_language(query), Some(QueryLanguage::English));
+
+        let translated = translate_query(query, QueryLanguage::English, QueryLanguage::French).unwrap();
+        assert_eq!(translated, "salaire brut salarié march 2024");
+
+        // Même langue ou aucun terme connu : pas de requête additionnelle
+        assert!(translate_query(query, QueryLanguage::French, QueryLanguage::French).is_none());
+        assert!(translate_query("Who is John Smith?", QueryLanguage::English, QueryLanguage::French).is_none());
+        assert_eq!(detect_language("Quel est le montant de la facture ?"), Some(QueryLanguage::French));
+    }
+}
@@ -2,16 +2,8 @@
 // Normalisation embeddings + Hybrid BM25 + Query routing
 
 use crate::rag::DocumentCategory;
-
-/// Normalisation L2 des embeddings pour améliorer la similarité cosinus
-pub fn l2_normalize(embedding: &mut [f32]) {
-    let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-    if norm > 0.0 {
-        for value in embedding.iter_mut() {
-            *value /= norm;
-        }
-    }
-}
+use crate::rag::search::vector_ops::dot;
+use serde::{Deserialize, Serialize};
 
 /// Calcul BM25 simplifié pour recherche textuelle
 pub fn compute_bm25_score(query_terms: &[&str], document_text: &str) -> f32 {
@@ -133,6 +125,72 @@ pub fn detect_query_intent(query: &str) -> QueryIntent {
     }
 }
 
+/// Forme de réponse attendue par la requête (indépendante du domaine, cf. `QueryIntent`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnswerIntent {
+    /// Fait ponctuel : peu de chunks, haute précision
+    Factual,
+    /// Synthèse : large couverture du document
+    Summary,
+    /// Comparaison entre plusieurs éléments
+    Comparison,
+    /// Valeur chiffrée (montant, taux, quantité)
+    Numerical,
+}
+
+/// Paramètres de retrieval associés à un `AnswerIntent`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetrievalProfile {
+    pub limit: usize,
+    pub min_score: f32,
+    /// Poids BM25 dans `compute_hybrid_score` (0 = dense seul)
+    pub bm25_weight: f32,
+}
+
+impl AnswerIntent {
+    pub fn retrieval_profile(&self) -> RetrievalProfile {
+        match self {
+            AnswerIntent::Factual => RetrievalProfile { limit: 5, min_score: 0.6, bm25_weight: 0.3 },
+            AnswerIntent::Summary => RetrievalProfile { limit: 15, min_score: 0.45, bm25_weight: 0.1 },
+            AnswerIntent::Comparison => RetrievalProfile { limit: 12, min_score: 0.5, bm25_weight: 0.2 },
+            // Les chiffres exacts se retrouvent mieux lexicalement
+            AnswerIntent::Numerical => RetrievalProfile { limit: 8, min_score: 0.5, bm25_weight: 0.5 },
+        }
+    }
+}
+
+/// Détection de la forme de réponse attendue (comparaison > synthèse > chiffre > fait)
+pub fn detect_answer_intent(query: &str) -> AnswerIntent {
+    let query_lower = query.to_lowercase();
+    let contains_any = |terms: &[&str]| terms.iter().any(|term| query_lower.contains(term));
+
+    let comparison_terms = [
+        "compare", "comparer", "comparaison", " vs ", " vs.", "versus",
+        "différence", "difference", "par rapport", "evolution", "évolution",
+    ];
+    let summary_terms = [
+        "résume", "résumé", "summary", "summarize", "synthèse", "synthétise",
+        "overview", "vue d'ensemble", "de quoi parle", "what is this document",
+        "points clés", "key points", "main points", "principaux",
+    ];
+    let numerical_terms = [
+        "combien", "how much", "how many", "montant", "total", "pourcentage",
+        "percentage", "taux", "rate", "chiffre", "nombre de", "prix", "coût", "cost",
+    ];
+    let has_numeric_symbol = query.chars().any(|c| matches!(c, '%' | '€' | '$' | '£'));
+
+    if contains_any(&comparison_terms) {
+        AnswerIntent::Comparison
+    } else if contains_any(&summary_terms) {
+        AnswerIntent::Summary
+    } else if has_numeric_symbol || contains_any(&numerical_terms) {
+        AnswerIntent::Numerical
+    } else {
+        AnswerIntent::Factual
+    }
+}
+
 /// Lexique légal strict pour boost conditionnel
 const LEGAL_STRONG_PATTERNS: &[&str] = &[
     r"(?i)\barrêtés?\b",
@@ -365,7 +423,7 @@ impl SearchEngine {
             let bm25_score = compute_weighted_bm25(&query_terms, content);
             
             // Calcul cosine (embeddings déjà normalisés)
-            let cosine_score = cosine_similarity(query_embedding, embedding);
+            let cosine_score = dot(query_embedding, embedding);
             
             bm25_scores.push(bm25_score);
             cosine_scores.push(cosine_score);
@@ -413,16 +471,6 @@ impl SearchEngine {
     }
 }
 
-/// Similarité cosinus pour embeddings normalisés
-pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    if a.len() != b.len() {
-        return 0.0;
-    }
-    
-    // Si embeddings normalisés, le produit scalaire = cosine similarity
-    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
-}
-
 /// Normalisation MinMax pour stabiliser les scores hybrides
 pub fn normalize_minmax(scores: &mut [f32]) {
     if scores.is_empty() {
@@ -496,14 +544,18 @@ mod tests {
     }
     
     #[test]
-    fn test_l2_normalization() {
-        let mut vec = vec![3.0, 4.0, 0.0];
-        l2_normalize(&mut vec);
-        
-        let norm: f32 = vec.iter().map(|x| x * x).sum::<f32>().sqrt();
-        assert!((norm - 1.0).abs() < 1e-6);
+    fn test_answer_intent_detection() {
+        assert_eq!(detect_answer_intent("Résume le rapport annuel"), AnswerIntent::Summary);
+        assert_eq!(detect_answer_intent("Compare 2022 vs 2023 revenue"), AnswerIntent::Comparison);
+        assert_eq!(detect_answer_intent("Quel est le montant de l'impôt ?"), AnswerIntent::Numerical);
+        assert_eq!(detect_answer_intent("Qui a signé le contrat ?"), AnswerIntent::Factual);
+
+        let summary = AnswerIntent::Summary.retrieval_profile();
+        let factual = AnswerIntent::Factual.retrieval_profile();
+        assert!(summary.limit > factual.limit);
+        assert!(factual.min_score > summary.min_score);
     }
-    
+
     #[test]
     fn test_intent_boost() {
         let base_score = 0.5;
@@ -0,0 +1,127 @@
+// Opérations vectorielles partagées (embeddings 384D+ dans les boucles chaudes : dédup, MMR, scoring)
+// Accumulation sur LANES voies indépendantes : le compilateur peut vectoriser (SIMD)
+// malgré la non-associativité de l'addition flottante.
+
+/// Nombre de voies d'accumulation (256 bits de f32)
+const LANES: usize = 8;
+
+/// Produit scalaire ; 0.0 si les dimensions diffèrent
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let a_chunks = a.chunks_exact(LANES);
+    let b_chunks = b.chunks_exact(LANES);
+    let tail: f32 = a_chunks.remainder().iter()
+        .zip(b_chunks.remainder())
+        .map(|(x, y)| x * y)
+        .sum();
+
+    let mut lanes = [0.0f32; LANES];
+    for (chunk_a, chunk_b) in a_chunks.zip(b_chunks) {
+        for ((lane, x), y) in lanes.iter_mut().zip(chunk_a).zip(chunk_b) {
+            *lane += x * y;
+        }
+    }
+
+    lanes.iter().sum::<f32>() + tail
+}
+
+/// Similarité cosinus ; 0.0 si les dimensions diffèrent ou si un vecteur est nul
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let norm_a = dot(a, a).sqrt();
+    let norm_b = dot(b, b).sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    dot(a, b) / (norm_a * norm_b)
+}
+
+/// Normalisation L2 en place (le produit scalaire devient alors la similarité cosinus)
+pub fn l2_normalize(embedding: &mut [f32]) {
+    let norm = dot(embedding, embedding).sqrt();
+    if norm > 0.0 {
+        for value in embedding.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Implémentation scalaire de référence
+    fn naive_cosine(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (norm_a * norm_b)
+    }
+
+    fn embedding(seed: usize, dimension: usize) -> Vec<f32> {
+        (0..dimension).map(|i| (((i * 31 + seed * 17) % 97) as f32 / 97.0) - 0.5).collect()
+    }
+
+    #[test]
+    fn test_vector_ops_against_reference() {
+        // Référence : cos([1,2,3], [4,5,6]) = 32 / sqrt(14 * 77)
+        assert!((dot(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]) - 32.0).abs() < 1e-6);
+        assert!((cosine_similarity(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]) - 0.974_631_85).abs() < 1e-6);
+
+        // Voies + reste (384 = 48 * 8, 389 laisse un reste de 5)
+        for dimension in [384, 389] {
+            let (a, b) = (embedding(1, dimension), embedding(2, dimension));
+            assert!((cosine_similarity(&a, &b) - naive_cosine(&a, &b)).abs() < 1e-5);
+        }
+
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+
+        let mut vec = vec![3.0, 4.0, 0.0];
+        l2_normalize(&mut vec);
+        assert!((dot(&vec, &vec) - 1.0).abs() < 1e-6);
+        assert!((vec[0] - 0.6).abs() < 1e-6);
+    }
+
+    /// Micro-benchmark : `cargo test --release bench_cosine_similarity -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_cosine_similarity() {
+        let candidates: Vec<Vec<f32>> = (0..1000).map(|seed| embedding(seed, 384)).collect();
+        let query = embedding(4242, 384);
+        let iterations = 100;
+
+        let start = std::time::Instant::now();
+        let mut checksum = 0.0f32;
+        for _ in 0..iterations {
+            for candidate in &candidates {
+                checksum += cosine_similarity(&query, std::hint::black_box(candidate));
+            }
+        }
+        let optimized = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let mut naive_checksum = 0.0f32;
+        for _ in 0..iterations {
+            for candidate in &candidates {
+                naive_checksum += naive_cosine(&query, std::hint::black_box(candidate));
+            }
+        }
+        let naive = start.elapsed();
+
+        let comparisons = (iterations * candidates.len()) as f64;
+        println!("cosine_similarity 384D: {:.1} ns/op (naive: {:.1} ns/op, checksums {:.3} / {:.3})",
+                 optimized.as_nanos() as f64 / comparisons,
+                 naive.as_nanos() as f64 / comparisons,
+                 checksum, naive_checksum);
+    }
+}
@@ -0,0 +1,68 @@
+// GRAVIS RAG - Fixtures de test partagées
+// Chunk et résultat de recherche minimaux : les tests ne modifient que les champs qu'ils vérifient.
+
+use crate::rag::commands::{ChunkMetadataSlim, SearchResultWithMetadata};
+use crate::rag::{
+    ChunkMetadata, ChunkSource, ChunkType, DocumentCategory, EnrichedChunk, ExtractionMethod, Priority, SourceType,
+};
+
+/// Chunk texte natif d'un seul bloc, lignes couvertes déduites du contenu
+pub(crate) fn test_chunk(id: &str, content: &str) -> EnrichedChunk {
+    EnrichedChunk {
+        id: id.to_string(),
+        content: content.to_string(),
+        start_line: 1,
+        end_line: 1 + content.matches('\n').count(),
+        chunk_type: ChunkType::TextBlock,
+        embedding: None,
+        hash: String::new(),
+        metadata: ChunkMetadata {
+            tags: vec![],
+            priority: Priority::Normal,
+            language: "fr".to_string(),
+            symbol: None,
+            context: None,
+            confidence: 1.0,
+            ocr_metadata: None,
+            source_type: SourceType::NativeText,
+            extraction_method: ExtractionMethod::DirectRead,
+            page_overlap_len: 0,
+        },
+        group_id: "test_group".to_string(),
+        source_spans: None,
+        chunk_source: ChunkSource::BodyText,
+        figure_id: None,
+    }
+}
+
+/// Résultat de recherche issu de `test_chunk` (document "doc")
+pub(crate) fn test_search_result(chunk_id: &str, content: &str, score: f32) -> SearchResultWithMetadata {
+    SearchResultWithMetadata {
+        chunk_id: chunk_id.to_string(),
+        content: content.to_string(),
+        score,
+        cosine_score: score,
+        document_id: "doc".to_string(),
+        document_category: DocumentCategory::Mixed,
+        source_type: SourceType::NativeText,
+        extraction_method: ExtractionMethod::DirectRead,
+        char_range: None,
+        business_metadata: None,
+        ocr_confidence: None,
+        chunk_metadata: ChunkMetadataSlim {
+            tags: Vec::new(),
+            language: "fr".to_string(),
+            confidence: 1.0,
+            start_line: 1,
+            end_line: 1 + content.matches('\n').count(),
+        },
+        source_file: None,
+        chunk_source: ChunkSource::BodyText,
+        figure_id: None,
+        fiscal_year: None,
+        bbox: None,
+        merged_chunk_ids: Vec::new(),
+        document_priority: Priority::Normal,
+        numeric_facts: Vec::new(),
+    }
+}
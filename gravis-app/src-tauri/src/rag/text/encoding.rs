@@ -0,0 +1,110 @@
+// GRAVIS Text - Détection d'encodage des fichiers texte
+// Les exports hérités (Latin-1, Windows-1252, UTF-16 avec BOM) ne sont pas de l'UTF-8 :
+// ils sont transcodés avant chunking au lieu de faire échouer l'ingestion.
+
+use encoding_rs::{Encoding, UTF_8};
+
+/// Clé de `EnrichedMetadata.custom_fields` portant l'encodage détecté d'un fichier texte
+pub const TEXT_ENCODING_FIELD: &str = "text_encoding";
+
+/// Texte transcodé en UTF-8
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedText {
+    pub text: String,
+    /// Nom WHATWG de l'encodage source ("UTF-8", "windows-1252", "UTF-16LE"...)
+    pub encoding: &'static str,
+    /// BOM présent (retiré du texte)
+    pub had_bom: bool,
+    /// Séquences invalides remplacées par U+FFFD
+    pub had_errors: bool,
+}
+
+/// Contenu refusé par `decode_text_bytes` : chardetng devinerait un encodage pour n'importe quels octets
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("binary content ({0})")]
+pub struct BinaryContentError(pub String);
+
+/// Décoder un fichier texte : BOM prioritaire, UTF-8 si valide, sinon encodage deviné (chardetng).
+/// Refuse les contenus binaires : signature non textuelle reconnue par `infer`, ou octet NUL
+/// hors UTF-16 (seul encodage texte supporté où NUL est légitime, et alors annoncé par son BOM).
+pub fn decode_text_bytes(bytes: &[u8]) -> Result<DecodedText, BinaryContentError> {
+    let bom = Encoding::for_bom(bytes);
+    if !matches!(bom, Some((encoding, _)) if encoding != UTF_8) {
+        if let Some(kind) = infer::get(bytes).filter(|kind| kind.matcher_type() != infer::MatcherType::Text) {
+            return Err(BinaryContentError(kind.mime_type().to_string()));
+        }
+        if bytes.contains(&0) {
+            return Err(BinaryContentError("NUL byte without UTF-16 BOM".to_string()));
+        }
+    }
+
+    if let Some((encoding, bom_length)) = bom {
+        return Ok(decode_with(encoding, &bytes[bom_length..], true));
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(DecodedText {
+            text: text.to_string(),
+            encoding: UTF_8.name(),
+            had_bom: false,
+            had_errors: false,
+        });
+    }
+
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    Ok(decode_with(detector.guess(None, true), bytes, false))
+}
+
+fn decode_with(encoding: &'static Encoding, bytes: &[u8], had_bom: bool) -> DecodedText {
+    let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+    DecodedText {
+        text: text.into_owned(),
+        encoding: encoding.name(),
+        had_bom,
+        had_errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_text_bytes() {
+        let utf8 = decode_text_bytes("Résumé du contrat".as_bytes()).unwrap();
+        assert_eq!((utf8.text.as_str(), utf8.encoding, utf8.had_bom), ("Résumé du contrat", "UTF-8", false));
+
+        // BOM UTF-8 retiré
+        let with_bom = decode_text_bytes(b"\xEF\xBB\xBFBonjour").unwrap();
+        assert_eq!((with_bom.text.as_str(), with_bom.had_bom), ("Bonjour", true));
+
+        // UTF-16LE avec BOM
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("Élève".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        let decoded = decode_text_bytes(&utf16).unwrap();
+        assert_eq!((decoded.text.as_str(), decoded.encoding), ("Élève", "UTF-16LE"));
+
+        // Export Windows-1252 : "Le café coûte 3 € à l'été"
+        let legacy = b"Le caf\xE9 co\xFBte 3 \x80 \xE0 l'\xE9t\xE9, la cr\xE8me br\xFBl\xE9e aussi.";
+        let decoded = decode_text_bytes(legacy).unwrap();
+        assert_eq!(decoded.encoding, "windows-1252");
+        assert_eq!(decoded.text, "Le café coûte 3 € à l'été, la crème brûlée aussi.");
+        assert!(!decoded.had_errors);
+    }
+
+    #[test]
+    fn test_binary_content_rejected() {
+        // Image PNG renommée en .txt : signature reconnue par infer
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x00\x00\x00\x01";
+        assert_eq!(decode_text_bytes(png), Err(BinaryContentError("image/png".to_string())));
+
+        // Octets NUL sans BOM UTF-16 : binaire inconnu, pas du texte Windows-1252
+        let blob = b"\x00\x01\x02garbage\x00\xFF\xFE\x7F";
+        assert!(decode_text_bytes(blob).is_err());
+
+        // UTF-16 avec BOM : les NUL sont légitimes
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("Bonjour".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        assert!(decode_text_bytes(&utf16).is_ok());
+    }
+}
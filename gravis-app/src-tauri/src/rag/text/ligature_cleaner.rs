@@ -32,6 +32,7 @@ impl LigatureCleaner {
             ("ﬀ", "ff"),
             ("ﬃ", "ffi"),
             ("ﬄ", "ffl"),
+            ("ﬅ", "st"),
             ("ﬆ", "st"),
             ("Ａ", "A"), // Fullwidth A
             ("ａ", "a"), // Fullwidth a
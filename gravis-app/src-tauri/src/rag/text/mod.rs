@@ -2,7 +2,9 @@
 pub mod unicode_utils;
 pub mod ligature_cleaner;
 pub mod ligature_aggregator;
+pub mod encoding;
 
 pub use unicode_utils::*;
 pub use ligature_cleaner::*;
-pub use ligature_aggregator::*;
\ No newline at end of file
+pub use ligature_aggregator::*;
+pub use encoding::*;
\ No newline at end of file
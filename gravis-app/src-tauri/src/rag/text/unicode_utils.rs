@@ -17,7 +17,7 @@ static LIGATURE_MAPPING: Lazy<HashMap<char, &'static str>> = Lazy::new(|| {
     mapping.insert('\u{FB02}', "fl");  // ﬂ → fl
     mapping.insert('\u{FB03}', "ffi"); // ﬃ → ffi
     mapping.insert('\u{FB04}', "ffl"); // ﬄ → ffl
-    mapping.insert('\u{FB05}', "ft");  // ﬅ → ft
+    mapping.insert('\u{FB05}', "st");  // ﬅ (s long + t) → st
     mapping.insert('\u{FB06}', "st");  // ﬆ → st
     
     // Ligatures étendues (rares mais présentes dans certains PDFs)
@@ -42,7 +42,15 @@ pub struct NormalizationStats {
     pub lig_ffi: usize,
     pub lig_ffl: usize,
     pub lig_ff: usize,
+    /// ﬅ (U+FB05, s long + t)
+    pub lig_long_st: usize,
+    /// ﬆ (U+FB06)
+    pub lig_st: usize,
     pub lig_other: usize,
+    /// Guillemets typographiques ‘ ’ ‚ ‛ “ ” „ ‟ ramenés à ' et "
+    pub quotes_normalized: usize,
+    /// Tirets typographiques (‐ ‑ ‒ – — ― −) ramenés à -
+    pub dashes_normalized: usize,
     pub ligatures_replaced: usize,
     pub unicode_normalized: bool,
     pub nbsp_replaced: usize,
@@ -50,6 +58,41 @@ pub struct NormalizationStats {
     pub total_chars_after: usize,
 }
 
+/// Remplacement d'un caractère typographique (ligatures U+FB00-FB06, guillemets,
+/// tirets, espace insécable) avec mise à jour des compteurs.
+/// Le ß allemand n'est pas une ligature et reste inchangé.
+fn normalize_typographic_char(ch: char, stats: &mut NormalizationStats) -> Option<&'static str> {
+    let (replacement, counter) = match ch {
+        '\u{FB00}' => ("ff", &mut stats.lig_ff),
+        '\u{FB01}' => ("fi", &mut stats.lig_fi),
+        '\u{FB02}' => ("fl", &mut stats.lig_fl),
+        '\u{FB03}' => ("ffi", &mut stats.lig_ffi),
+        '\u{FB04}' => ("ffl", &mut stats.lig_ffl),
+        '\u{FB05}' => ("st", &mut stats.lig_long_st),
+        '\u{FB06}' => ("st", &mut stats.lig_st),
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => {
+            stats.quotes_normalized += 1;
+            return Some("'");
+        }
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => {
+            stats.quotes_normalized += 1;
+            return Some("\"");
+        }
+        '\u{2010}'..='\u{2015}' | '\u{2212}' => {
+            stats.dashes_normalized += 1;
+            return Some("-");
+        }
+        '\u{00A0}' => {
+            stats.nbsp_replaced += 1;
+            return Some(" ");
+        }
+        _ => return None,
+    };
+    *counter += 1;
+    stats.ligatures_replaced += 1;
+    Some(replacement)
+}
+
 /// Normalisation complète du texte PDF avec ligatures (optimisée, sans spam logs)
 pub fn sanitize_pdf_text(input: &str) -> Result<(String, NormalizationStats)> {
     use unicode_normalization::UnicodeNormalization;
@@ -63,37 +106,9 @@ pub fn sanitize_pdf_text(input: &str) -> Result<(String, NormalizationStats)> {
     let mut result = String::with_capacity(input.len());
     
     for ch in input.chars() {
-        match ch {
-            'ﬁ' => { 
-                result.push_str("fi"); 
-                stats.lig_fi += 1; 
-                stats.ligatures_replaced += 1;
-            }
-            'ﬂ' => { 
-                result.push_str("fl"); 
-                stats.lig_fl += 1; 
-                stats.ligatures_replaced += 1;
-            }
-            'ﬃ' => { 
-                result.push_str("ffi"); 
-                stats.lig_ffi += 1; 
-                stats.ligatures_replaced += 1;
-            }
-            'ﬄ' => { 
-                result.push_str("ffl"); 
-                stats.lig_ffl += 1; 
-                stats.ligatures_replaced += 1;
-            }
-            'ﬀ' => { 
-                result.push_str("ff"); 
-                stats.lig_ff += 1; 
-                stats.ligatures_replaced += 1;
-            }
-            '\u{00A0}' => { 
-                result.push(' '); 
-                stats.nbsp_replaced += 1;
-            }
-            _ => result.push(ch),
+        match normalize_typographic_char(ch, &mut stats) {
+            Some(replacement) => result.push_str(replacement),
+            None => result.push(ch),
         }
     }
     
@@ -110,6 +125,9 @@ pub fn sanitize_pdf_text(input: &str) -> Result<(String, NormalizationStats)> {
             ffi = stats.lig_ffi,
             ffl = stats.lig_ffl,
             ff = stats.lig_ff,
+            st = stats.lig_st + stats.lig_long_st,
+            quotes = stats.quotes_normalized,
+            dashes = stats.dashes_normalized,
             nbsp = stats.nbsp_replaced,
             total = stats.ligatures_replaced,
             "Unicode ligatures normalized"
@@ -128,25 +146,16 @@ pub fn sanitize_pdf_text_all(input: &str) -> (String, NormalizationStats) {
     
     // Étape 1: Remplacement ligatures (toutes les variantes importantes)
     for ch in input.chars() {
-        match ch {
-            'ﬁ' => { result.push_str("fi"); stats.lig_fi += 1; }
-            'ﬂ' => { result.push_str("fl"); stats.lig_fl += 1; }
-            'ﬃ' => { result.push_str("ffi"); stats.lig_ffi += 1; }
-            'ﬄ' => { result.push_str("ffl"); stats.lig_ffl += 1; }
-            'ﬀ' => { result.push_str("ff"); stats.lig_ff += 1; }
-            'ﬅ' => result.push_str("ft"),
-            'ﬆ' => result.push_str("st"),
-            '\u{00A0}' => { result.push(' '); stats.nbsp_replaced += 1; }
-            _ => result.push(ch),
+        match normalize_typographic_char(ch, &mut stats) {
+            Some(replacement) => result.push_str(replacement),
+            None => result.push(ch),
         }
     }
     
     // Log agrégé unique (plus de spam)
-    let total_ligatures = stats.lig_fi + stats.lig_fl + stats.lig_ffi + stats.lig_ffl + stats.lig_ff;
-    if total_ligatures > 0 {
-        tracing::debug!(fi = stats.lig_fi, fl = stats.lig_fl, total = total_ligatures, "Unicode ligatures normalized");
+    if stats.ligatures_replaced > 0 {
+        tracing::debug!(fi = stats.lig_fi, fl = stats.lig_fl, total = stats.ligatures_replaced, "Unicode ligatures normalized");
     }
-    stats.ligatures_replaced = total_ligatures;
     stats.total_chars_before = input.chars().count();
     
     // Étape 2: NFKC normalization
@@ -330,6 +339,26 @@ mod tests {
         assert_eq!(stats.ligatures_replaced, 4);
     }
 
+    #[test]
+    fn test_full_ligature_range_and_punctuation() {
+        let input = "ﬀ ﬁ ﬂ ﬃ ﬄ ﬅ ﬆ “Straße” ‘a’ – b — c";
+        let (result, stats) = sanitize_pdf_text(input).unwrap();
+
+        assert_eq!(result, "ff fi fl ffi ffl st st \"Straße\" 'a' - b - c");
+        assert_eq!(
+            (stats.lig_ff, stats.lig_fi, stats.lig_fl, stats.lig_ffi, stats.lig_ffl, stats.lig_long_st, stats.lig_st),
+            (1, 1, 1, 1, 1, 1, 1)
+        );
+        assert_eq!(stats.ligatures_replaced, 7);
+        assert_eq!(stats.quotes_normalized, 4);
+        assert_eq!(stats.dashes_normalized, 2);
+
+        // Même comportement pour le point d'entrée unique
+        let (all, all_stats) = sanitize_pdf_text_all(input);
+        assert_eq!(all, result);
+        assert_eq!(all_stats.ligatures_replaced, 7);
+    }
+
     #[test]
     fn test_french_ligatures() {
         let input = "Œuvre complète avec ﬁnitions";